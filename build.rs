@@ -0,0 +1,46 @@
+// 빌드 메타데이터 수집 (git 커밋, 빌드 프로필)
+// git이 없거나 저장소 밖에서 빌드하면 커밋 값 없이 빌드합니다.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=UDSS_GIT_COMMIT");
+
+    // 커밋이 바뀌면 다시 실행 (.git이 없으면 무시됨)
+    for path in [".git/HEAD", ".git/index"] {
+        if std::path::Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+
+    // 패키징 환경처럼 git을 쓸 수 없는 경우 UDSS_GIT_COMMIT으로 직접 지정 가능
+    let commit = std::env::var("UDSS_GIT_COMMIT").ok().filter(|commit| !commit.trim().is_empty()).or_else(git_commit);
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=UDSS_GIT_COMMIT={}", commit.trim());
+    }
+
+    if let Ok(profile) = std::env::var("PROFILE") {
+        println!("cargo:rustc-env=UDSS_BUILD_PROFILE={}", profile);
+    }
+}
+
+/// 현재 커밋 (작업 트리에 변경이 있으면 `-dirty`)
+fn git_commit() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "--short=12", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let mut commit = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    if commit.is_empty() {
+        return None;
+    }
+
+    let dirty = Command::new("git").args(["status", "--porcelain", "--untracked-files=no"]).output()
+        .map(|output| output.status.success() && !output.stdout.is_empty())
+        .unwrap_or(false);
+    if dirty {
+        commit.push_str("-dirty");
+    }
+    Some(commit)
+}
@@ -1,13 +1,17 @@
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use log::{debug, error, info};
-use chrono;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::tls::{generate_fake_cert, accept_tls_with_cert};
 use crate::logging::Logger;
+use crate::proxy::error_page::{negotiate, ErrorFormat, ErrorReason, ErrorResponse};
 use crate::error::{ProxyError, Result, http_err, tls_err};
 
+/// TLS 연결 후 첫 요청(Accept 헤더)을 기다리는 시간
+const BLOCKED_REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// 차단 페이지 생성 및 전송을 담당하는 구조체
 pub struct BlockPage {
     logger: Option<Arc<Logger>>,
@@ -27,96 +31,9 @@ impl BlockPage {
         self
     }
     
-    /// HTTP 차단 페이지 생성
-    pub fn create_http_block_page(&self, host: &str) -> String {
-        let html = format!(
-            "<!DOCTYPE html>\
-            <html>\
-            <head>\
-                <title>사이트 접근 차단됨</title>\
-                <meta charset=\"UTF-8\">\
-                <style>\
-                    body {{ font-family: Arial, sans-serif; margin: 0; padding: 20px; background-color: #f5f5f5; }}\
-                    .container {{ max-width: 800px; margin: 40px auto; background: white; padding: 30px; border-radius: 8px; box-shadow: 0 2px 10px rgba(0,0,0,0.1); }}\
-                    h1 {{ color: #e74c3c; margin-top: 0; }}\
-                    .info {{ background-color: #f8f9fa; padding: 15px; border-left: 4px solid #e74c3c; margin: 20px 0; }}\
-                    .button {{ display: inline-block; background-color: #3498db; color: white; padding: 10px 20px; text-decoration: none; border-radius: 4px; margin-top: 20px; }}\
-                </style>\
-            </head>\
-            <body>\
-                <div class=\"container\">\
-                    <h1>접속이 차단되었습니다</h1>\
-                    <p>관리자 정책에 따라 요청하신 사이트에 대한 접속이 차단되었습니다.</p>\
-                    <div class=\"info\">\
-                        <p><strong>차단된 도메인:</strong> {}</p>\
-                        <p><strong>차단 시간:</strong> {}</p>\
-                    </div>\
-                    <p>문의사항이 있으시면 네트워크 관리자에게 연락하세요.</p>\
-                </div>\
-            </body>\
-            </html>", 
-            host, 
-            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
-        );
-        
-        format!(
-            "HTTP/1.1 200 OK\r\n\
-            Connection: close\r\n\
-            Content-Type: text/html; charset=UTF-8\r\n\
-            Content-Length: {}\r\n\
-            \r\n\
-            {}", 
-            html.len(), 
-            html
-        )
-    }
-    
-    /// HTTPS 차단 페이지 생성
-    pub fn create_https_block_page(&self, host: &str) -> String {
-        let html = format!(
-            "<!DOCTYPE html>\
-            <html>\
-            <head>\
-                <title>사이트 접근 차단됨 (HTTPS)</title>\
-                <meta charset=\"UTF-8\">\
-                <style>\
-                    body {{ font-family: Arial, sans-serif; margin: 0; padding: 20px; background-color: #f5f5f5; }}\
-                    .container {{ max-width: 800px; margin: 40px auto; background: white; padding: 30px; border-radius: 8px; box-shadow: 0 2px 10px rgba(0,0,0,0.1); }}\
-                    h1 {{ color: #e74c3c; margin-top: 0; }}\
-                    .info {{ background-color: #f8f9fa; padding: 15px; border-left: 4px solid #e74c3c; margin: 20px 0; }}\
-                    .warning {{ background-color: #fff3cd; padding: 15px; border-left: 4px solid #ffc107; margin: 20px 0; }}\
-                    .button {{ display: inline-block; background-color: #3498db; color: white; padding: 10px 20px; text-decoration: none; border-radius: 4px; margin-top: 20px; }}\
-                </style>\
-            </head>\
-            <body>\
-                <div class=\"container\">\
-                    <h1>보안 연결이 차단되었습니다</h1>\
-                    <p>관리자 정책에 따라 요청하신 보안 사이트(HTTPS)에 대한 접속이 차단되었습니다.</p>\
-                    <div class=\"warning\">\
-                        <p><strong>참고:</strong> 이 페이지는 TLS 연결이 성공적으로 수립된 후 표시됩니다. 프록시 서버에서 제공하는 인증서를 사용하여 암호화된 연결이 설정되었습니다.</p>\
-                    </div>\
-                    <div class=\"info\">\
-                        <p><strong>차단된 도메인:</strong> {}</p>\
-                        <p><strong>차단 시간:</strong> {}</p>\
-                    </div>\
-                    <p>문의사항이 있으시면 네트워크 관리자에게 연락하세요.</p>\
-                </div>\
-            </body>\
-            </html>", 
-            host, 
-            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
-        );
-        
-        format!(
-            "HTTP/1.1 403 Forbidden\r\n\
-            Connection: close\r\n\
-            Content-Type: text/html; charset=UTF-8\r\n\
-            Content-Length: {}\r\n\
-            \r\n\
-            {}", 
-            html.len(), 
-            html
-        )
+    /// 차단 응답 생성 (403, Accept 헤더에 따라 HTML 또는 JSON 본문)
+    pub fn create_block_page(&self, host: &str, session_id: &str, format: ErrorFormat) -> String {
+        ErrorResponse::new(ErrorReason::Blocked, session_id, host).render(format)
     }
     
     /// 차단 요청 로깅
@@ -124,7 +41,7 @@ impl BlockPage {
         // Logger 인스턴스 사용 (이제 직접 사용 가능)
         if let Some(logger) = &self.logger {
             // 로그 저장 - 비동기 로깅 사용
-            match logger.log_rejected_request(request_data, host, ip, session_id, is_tls).await {
+            match logger.log_rejected_request(request_data, host, ip, session_id, is_tls, None).await {
                 Ok(_) => debug!("[Session:{}] 차단된 요청 로깅 성공", session_id),
                 Err(e) => debug!("[Session:{}] 차단된 요청 로깅 실패: {}", session_id, e)
             }
@@ -140,7 +57,8 @@ impl BlockPage {
             self.log_blocked_request(req, host, ip, session_id, false).await; // HTTP는 TLS 아님
         }
         
-        let blocked_message = self.create_http_block_page(host);
+        let format = request.map(|req| negotiate(req.as_bytes())).unwrap_or(ErrorFormat::Html);
+        let blocked_message = self.create_block_page(host, session_id, format);
         
         // 차단 메시지 전송 시도
         match client_stream.write_all(blocked_message.as_bytes()).await {
@@ -201,11 +119,18 @@ impl BlockPage {
         
         info!("[Session:{}] Established TLS with client for blocked domain {}", session_id, host);
         
-        // 3. TLS 연결 후 HTML 차단 페이지 전송
-        let blocked_html = self.create_https_block_page(host);
+        // 3. 터널 안의 첫 요청으로 응답 형식 결정 (요청이 오지 않으면 브라우저로 보고 HTML)
+        let mut request_head = vec![0u8; 8192];
+        let format = match tokio::time::timeout(BLOCKED_REQUEST_READ_TIMEOUT, tls_stream.read(&mut request_head)).await {
+            Ok(Ok(n)) if n > 0 => negotiate(&request_head[..n]),
+            _ => ErrorFormat::Html,
+        };
+        
+        // 4. TLS 연결 후 차단 응답 전송
+        let blocked_message = self.create_block_page(host, session_id, format);
         
         // TLS 스트림으로 차단 페이지 전송
-        match tls_stream.write_all(blocked_html.as_bytes()).await {
+        match tls_stream.write_all(blocked_message.as_bytes()).await {
             Ok(_) => {
                 info!("[Session:{}] Successfully sent TLS block page to client for {}", session_id, host);
                 
@@ -0,0 +1,199 @@
+// CONNECT 접근 제어 (connect_acl)
+// (클라이언트 주소, 대상 호스트, 대상 포트)를 설정 순서대로 규칙과 비교해 처음 일치한 규칙의 동작을 적용하고,
+// 일치하는 규칙이 없으면 기본 동작을 적용합니다. 도메인 차단(DomainBlocker)보다 먼저, CONNECT 요청(SOCKS4 포함)에만 적용됩니다.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use log::info;
+use once_cell::sync::OnceCell;
+
+use crate::config::{Config, ConnectAclAction, ConnectAclConfig};
+use crate::server::tenant::{cidr_contains, parse_cidr};
+
+// 전역 CONNECT 접근 제어 (connect_acl 설정 시에만 초기화)
+static CONNECT_ACL: OnceCell<Arc<ConnectAcl>> = OnceCell::new();
+
+/// 대상 호스트 조건
+#[derive(Debug)]
+enum HostMatch {
+    /// 모든 호스트 (`*`)
+    Any,
+    /// 하위 도메인 (`*.example.com`, example.com 자체는 제외)
+    Suffix(String),
+    /// 정확한 호스트
+    Exact(String),
+}
+
+impl HostMatch {
+    fn parse(pattern: &str) -> Self {
+        let pattern = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            _ if pattern == "*" => HostMatch::Any,
+            Some(domain) => HostMatch::Suffix(format!(".{}", domain)),
+            None => HostMatch::Exact(pattern),
+        }
+    }
+
+    fn matches(&self, host: &str) -> bool {
+        match self {
+            HostMatch::Any => true,
+            HostMatch::Suffix(suffix) => host.len() > suffix.len() && host.ends_with(suffix.as_str()),
+            HostMatch::Exact(exact) => host == exact,
+        }
+    }
+}
+
+/// 해석한 규칙 하나 (조건 목록이 비어 있으면 모두 일치)
+#[derive(Debug)]
+struct ConnectRule {
+    action: ConnectAclAction,
+    clients: Vec<(IpAddr, u8)>,
+    hosts: Vec<HostMatch>,
+    ports: Vec<(u16, u16)>,
+}
+
+impl ConnectRule {
+    fn matches(&self, client: IpAddr, host: &str, port: u16) -> bool {
+        (self.clients.is_empty() || self.clients.iter().any(|range| cidr_contains(*range, client)))
+            && (self.hosts.is_empty() || self.hosts.iter().any(|pattern| pattern.matches(host)))
+            && (self.ports.is_empty() || self.ports.iter().any(|(low, high)| (*low..=*high).contains(&port)))
+    }
+}
+
+/// CONNECT 접근 제어 판정 결과
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectAclVerdict {
+    pub action: ConnectAclAction,
+    /// 일치한 규칙 번호 (connect_acl.rules의 0부터 시작하는 순서, 기본 동작이면 None)
+    pub rule_index: Option<usize>,
+}
+
+impl ConnectAclVerdict {
+    pub fn is_allowed(&self) -> bool {
+        self.action == ConnectAclAction::Allow
+    }
+}
+
+impl fmt::Display for ConnectAclVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = if self.is_allowed() { "allow" } else { "deny" };
+        match self.rule_index {
+            Some(index) => write!(f, "{} (connect_acl rule #{})", action, index),
+            None => write!(f, "{} (connect_acl default)", action),
+        }
+    }
+}
+
+/// 순서가 있는 CONNECT 접근 제어 규칙 목록
+#[derive(Debug)]
+pub struct ConnectAcl {
+    rules: Vec<ConnectRule>,
+    default: ConnectAclAction,
+}
+
+/// `포트` 또는 `시작-끝` 포트 범위 해석
+pub fn parse_port_range(ports: &str) -> Result<(u16, u16), String> {
+    let parse = |port: &str| port.trim().parse::<u16>().ok().filter(|port| *port > 0)
+        .ok_or_else(|| format!("포트는 1 ~ 65535여야 합니다: {:?}", port.trim()));
+    match ports.split_once('-') {
+        Some((low, high)) => {
+            let (low, high) = (parse(low)?, parse(high)?);
+            if low > high {
+                return Err(format!("범위의 시작 포트가 끝 포트보다 큽니다: {}", ports.trim()));
+            }
+            Ok((low, high))
+        },
+        None => parse(ports).map(|port| (port, port)),
+    }
+}
+
+impl ConnectAcl {
+    /// 설정에서 규칙 생성 (형식은 설정 검증에서 확인됨)
+    pub fn from_config(config: &ConnectAclConfig) -> Self {
+        let rules = config.rules.iter().map(|rule| ConnectRule {
+            action: rule.action,
+            clients: rule.clients.iter().filter_map(|client| parse_cidr(client).ok()).collect(),
+            hosts: rule.hosts.iter().map(|host| HostMatch::parse(host)).collect(),
+            ports: rule.ports.iter().filter_map(|ports| parse_port_range(ports).ok()).collect(),
+        }).collect();
+        Self { rules, default: config.default }
+    }
+
+    /// (클라이언트, 대상 호스트, 대상 포트)에 처음 일치하는 규칙의 동작 (호스트는 정규 형식)
+    pub fn evaluate(&self, client: IpAddr, host: &str, port: u16) -> ConnectAclVerdict {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        match self.rules.iter().position(|rule| rule.matches(client, &host, port)) {
+            Some(index) => ConnectAclVerdict { action: self.rules[index].action, rule_index: Some(index) },
+            None => ConnectAclVerdict { action: self.default, rule_index: None },
+        }
+    }
+}
+
+/// 설정에 따라 전역 CONNECT 접근 제어 초기화
+pub fn init_connect_acl(config: &Config) {
+    let settings = &config.connect_acl;
+    if !settings.is_enabled() {
+        return;
+    }
+
+    let acl = ConnectAcl::from_config(settings);
+    info!("CONNECT 접근 제어 규칙 {}개 (기본 동작 {})", acl.rules.len(), settings.default.name());
+    let _ = CONNECT_ACL.set(Arc::new(acl));
+}
+
+/// 전역 CONNECT 접근 제어 (설정하지 않았으면 None)
+pub fn connect_acl() -> Option<&'static Arc<ConnectAcl>> {
+    CONNECT_ACL.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConnectAclRule;
+
+    fn rule(action: ConnectAclAction, clients: &[&str], hosts: &[&str], ports: &[&str]) -> ConnectAclRule {
+        ConnectAclRule {
+            action,
+            clients: clients.iter().map(|s| s.to_string()).collect(),
+            hosts: hosts.iter().map(|s| s.to_string()).collect(),
+            ports: ports.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_default_applies_otherwise() {
+        let acl = ConnectAcl::from_config(&ConnectAclConfig {
+            default: ConnectAclAction::Deny,
+            rules: vec![
+                rule(ConnectAclAction::Deny, &["10.0.0.0/8"], &["*.blocked.example"], &[]),
+                rule(ConnectAclAction::Allow, &["10.1.0.0/16"], &[], &["443", "8000-8999"]),
+                rule(ConnectAclAction::Deny, &["10.1.0.0/16"], &[], &[]),
+                rule(ConnectAclAction::Allow, &["10.2.0.0/16"], &["*"], &[]),
+            ],
+        });
+        let cases: [(&str, &str, u16, ConnectAclAction, Option<usize>); 7] = [
+            ("10.1.2.3", "example.com", 443, ConnectAclAction::Allow, Some(1)),
+            ("10.1.2.3", "example.com", 8080, ConnectAclAction::Allow, Some(1)),
+            ("10.1.2.3", "example.com", 22, ConnectAclAction::Deny, Some(2)),
+            // 앞선 거부 규칙이 이후 허용 규칙보다 우선
+            ("10.1.2.3", "A.Blocked.Example", 443, ConnectAclAction::Deny, Some(0)),
+            ("10.2.0.1", "example.com", 22, ConnectAclAction::Allow, Some(3)),
+            // 하위 도메인 패턴은 도메인 자체와 일치하지 않음
+            ("10.2.0.1", "blocked.example", 443, ConnectAclAction::Allow, Some(3)),
+            ("192.168.1.1", "example.com", 443, ConnectAclAction::Deny, None),
+        ];
+        for (client, host, port, action, rule_index) in cases {
+            let verdict = acl.evaluate(client.parse().unwrap(), host, port);
+            assert_eq!((verdict.action, verdict.rule_index), (action, rule_index), "{} -> {}:{}", client, host, port);
+        }
+    }
+
+    #[test]
+    fn default_config_allows_everything() {
+        let verdict = ConnectAcl::from_config(&ConnectAclConfig::default()).evaluate("192.168.1.1".parse().unwrap(), "example.com", 22);
+        assert!(verdict.is_allowed());
+        assert_eq!(verdict.rule_index, None);
+    }
+}
@@ -4,20 +4,62 @@ use std::num::NonZeroUsize;
 use std::sync::RwLock;
 use lru::LruCache;
 use tokio::time::Duration;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use chrono::{DateTime, Utc};
 use regex::Regex;
 
-use crate::config::Config;
+use super::expiring::{merge_expiry, ExpiringPattern, ExpiringRules};
+use super::filter_list::{load_filter_lists, FilterRules};
+use crate::config::{AclAction, AclActionsConfig, AclConflictPrecedence, AclFailurePolicy, AclMode, Config};
 use crate::constants::{domain_blocks, domain_pattern_blocks, ACL_CACHE_SIZE};
 use crate::db;
+use crate::metrics::{AclRuleKind, Metrics};
+use crate::proxy::target::canonical_host;
+
+/// 로드 시 하나씩 경고로 남길 규칙 충돌 수 (나머지는 debug)
+const CONFLICT_LOG_SAMPLES: usize = 20;
 
 /// 도메인 매칭 결과를 나타내는 열거형
 #[derive(Debug, Clone)]
 enum MatchResult {
-    Blocked,
+    Blocked(RuleMatch),
     NotBlocked,
 }
 
+/// 일치한 차단 규칙
+#[derive(Debug, Clone)]
+struct RuleMatch {
+    /// 로그용 표기 (domain:<도메인>, pattern:<정규표현식> 또는 filter:||<도메인>^)
+    label: String,
+    /// 차단 목록 항목 그대로의 도메인/패턴 (acl_actions.rules 조회용)
+    rule: String,
+    kind: AclRuleKind,
+    /// 예외 규칙과 겹칠 때 비교하는 구체성 (acl_conflict_precedence: most_specific)
+    specificity: Specificity,
+    /// 규칙 만료 시각 (지나면 캐시된 결과도 사용하지 않음)
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// 규칙이 호스트에 일치한 방식의 구체성 (정확한 도메인 > 와일드카드 > 정규표현식, 같은 유형이면 도메인 라벨 수)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Specificity {
+    Regex,
+    Wildcard(usize),
+    Exact(usize),
+}
+
+/// 차단 규칙 평가 결과 (적용할 동작)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AclDecision {
+    /// 일치한 규칙이 없거나 감사 모드, 또는 allow 동작
+    Allow,
+    Block,
+    /// 302로 이동할 주소
+    Redirect(String),
+    /// 조각 전송 간격과 최대 유지 시간
+    Tarpit { interval: Duration, max_hold: Duration },
+}
+
 /// 도메인 차단을 처리하는 구조체
 pub struct DomainBlocker {
     config: Arc<Config>,
@@ -25,10 +67,24 @@ pub struct DomainBlocker {
     domain_block_cache: RwLock<LruCache<String, MatchResult>>,
     // 차단된 도메인 목록
     blocked_domains: RwLock<HashSet<String>>,
-    // 정규표현식 패턴
-    regex_patterns: RwLock<Vec<Regex>>,
+    // 정규표현식 패턴과 원래 패턴 문자열 (와일드카드 패턴도 정규표현식으로 변환해 보관)
+    regex_patterns: RwLock<Vec<(Regex, AclRuleKind, String)>>,
+    // 만료 시각이 있는 정확한 도메인/패턴 규칙 (영구 규칙과 따로 보관)
+    expiring_rules: RwLock<ExpiringRules>,
+    // Adblock Plus 필터 목록의 도메인 규칙 (설정 파일에서만 로드)
+    filter_rules: RwLock<FilterRules>,
     // 초기화 완료 여부
     initialized: RwLock<bool>,
+    // 차단 정책 적용 방식 (재로드로 변경 가능)
+    acl_mode: RwLock<AclMode>,
+    // 규칙 일치 시 동작 (재로드로 변경 가능)
+    acl_actions: RwLock<AclActionsConfig>,
+    // 판단할 수 없을 때의 처리 (재로드로 변경 가능)
+    failure_policy: RwLock<AclFailurePolicy>,
+    // 허용/차단 규칙이 함께 일치할 때의 우선순위 (재로드로 변경 가능)
+    conflict_precedence: RwLock<AclConflictPrecedence>,
+    // 차단/감사 일치 및 규칙 통계 카운터
+    metrics: Arc<Metrics>,
 }
 
 impl DomainBlocker {
@@ -37,11 +93,18 @@ impl DomainBlocker {
         debug!("DomainBlocker 초기화: 캐시 크기 {}", ACL_CACHE_SIZE);
         
         Self {
+            acl_mode: RwLock::new(config.acl_mode),
+            acl_actions: RwLock::new(config.acl_actions.clone()),
+            failure_policy: RwLock::new(config.acl_failure_policy),
+            conflict_precedence: RwLock::new(config.acl_conflict_precedence),
             config,
             domain_block_cache: RwLock::new(LruCache::new(NonZeroUsize::new(ACL_CACHE_SIZE).unwrap())),
             blocked_domains: RwLock::new(HashSet::new()),
             regex_patterns: RwLock::new(Vec::new()),
+            expiring_rules: RwLock::new(ExpiringRules::default()),
+            filter_rules: RwLock::new(FilterRules::default()),
             initialized: RwLock::new(false),
+            metrics: Metrics::new(),
         }
     }
     
@@ -53,6 +116,17 @@ impl DomainBlocker {
             return Ok(());
         }
         
+        // 필터 목록은 DB 사용 여부와 관계없이 설정 파일에서 로드
+        self.load_filter_lists(&self.config);
+        
+        // DB 비활성화 모드: 설정 파일의 차단 목록만 사용
+        if !db::config::DbConfig::is_enabled() {
+            self.load_blocked_domains_from_config(&self.config);
+            *self.initialized.write().unwrap() = true;
+            info!("DB 비활성화 모드: 설정 파일의 도메인 차단 목록을 사용합니다");
+            return Ok(());
+        }
+        
         // 도메인 차단 테이블 초기화
         self.ensure_domain_block_tables().await?;
         info!("도메인 차단 테이블 초기화 완료");
@@ -84,54 +158,303 @@ impl DomainBlocker {
         Ok(())
     }
     
-    /// 주어진 도메인이 차단 목록에 있는지 확인
-    pub fn is_blocked(&self, host: &str) -> bool {
+    /// 주어진 도메인에 적용할 동작 결정
+    ///
+    /// 감사 모드(acl_mode: audit)에서는 일치한 규칙을 기록하고 카운트만 한 뒤 허용합니다.
+    /// 302로 응답할 수 없는 요청(`redirectable`이 false)에는 redirect 대신 차단을 적용합니다.
+    /// 규칙으로 판단할 수 없으면 acl_failure_policy에 따라 허용하거나 차단합니다.
+    #[cfg(test)]
+    pub fn decide(&self, host: &str, redirectable: bool) -> AclDecision {
+        self.decide_with_category(host, redirectable).0
+    }
+    
+    /// 적용할 동작과 일치한 차단 규칙 유형 (차단 로그의 분류, 판단할 수 없어 차단하면 None)
+    pub fn decide_with_category(&self, host: &str, redirectable: bool) -> (AclDecision, Option<AclRuleKind>) {
+        // 유니코드/punycode, 대소문자, 끝의 점이 달라도 같은 규칙에 일치하도록 정규 형식으로 평가
+        let host = canonical_host(host);
+        let host = host.as_str();
+        let matched = match self.find_matching_rule(host) {
+            Ok(Some(matched)) => matched,
+            Ok(None) => return (AclDecision::Allow, None),
+            Err(reason) => return (self.undecided(host, reason), None),
+        };
+        
+        let acl_mode = *self.acl_mode.read().unwrap();
+        if acl_mode == AclMode::Audit {
+            info!("[ACL AUDIT] 차단 규칙 일치, 감사 모드로 허용: {} (규칙: {})", host, matched.label);
+            self.metrics.acl_audit_matched();
+            return (AclDecision::Allow, None);
+        }
+        
+        let (action, redirect_url) = self.action_for(&matched);
+        let decision = match (action, redirect_url) {
+            (AclAction::Block, _) => AclDecision::Block,
+            (AclAction::Redirect, Some(url)) if redirectable => AclDecision::Redirect(url),
+            (AclAction::Redirect, _) => AclDecision::Block,
+            (AclAction::Tarpit, _) => {
+                let actions = self.acl_actions.read().unwrap();
+                AclDecision::Tarpit {
+                    interval: Duration::from_millis(actions.tarpit_interval_ms),
+                    max_hold: Duration::from_secs(actions.tarpit_max_hold_seconds),
+                }
+            },
+            (AclAction::Allow, _) => AclDecision::Allow,
+        };
+        
+        match &decision {
+            AclDecision::Block => {
+                info!("[ACL] 차단: {} (규칙: {})", host, matched.label);
+                self.metrics.acl_blocked();
+            },
+            AclDecision::Redirect(url) => {
+                info!("[ACL] 이동: {} -> {} (규칙: {})", host, url, matched.label);
+                self.metrics.acl_redirected();
+            },
+            AclDecision::Tarpit { .. } => {
+                info!("[ACL] tarpit: {} (규칙: {})", host, matched.label);
+                self.metrics.acl_tarpitted();
+            },
+            AclDecision::Allow => {
+                info!("[ACL] 허용 동작으로 통과: {} (규칙: {})", host, matched.label);
+                self.metrics.acl_allowed();
+            },
+        }
+        (decision, Some(matched.kind))
+    }
+    
+    /// 판단할 수 없는 요청에 acl_failure_policy 적용 (감사 모드에서는 fail-closed여도 허용)
+    fn undecided(&self, host: &str, reason: &str) -> AclDecision {
+        let policy = *self.failure_policy.read().unwrap_or_else(|e| e.into_inner());
+        let acl_mode = *self.acl_mode.read().unwrap_or_else(|e| e.into_inner());
+        match policy {
+            AclFailurePolicy::Closed if acl_mode == AclMode::Enforce => {
+                warn!("[ACL-UNDECIDED] 판단 불가, {}로 차단: {} ({})", policy.name(), host, reason);
+                self.metrics.acl_undecided_blocked();
+                AclDecision::Block
+            },
+            _ => {
+                warn!("[ACL-UNDECIDED] 판단 불가, {}로 허용: {} ({}, acl_mode: {:?})", policy.name(), host, reason, acl_mode);
+                self.metrics.acl_undecided_allowed();
+                AclDecision::Allow
+            },
+        }
+    }
+    
+    /// 일치한 규칙의 동작과 redirect 주소 (규칙, 규칙 유형, 기본값 순으로 조회)
+    fn action_for(&self, matched: &RuleMatch) -> (AclAction, Option<String>) {
+        let actions = self.acl_actions.read().unwrap();
+        let kind = format!("kind:{}", matched.kind.name());
+        let rule = actions.rules.iter()
+            .find(|rule| rule.rule.eq_ignore_ascii_case(&matched.rule)
+                || (matched.kind == AclRuleKind::Exact && canonical_host(&rule.rule) == matched.rule))
+            .or_else(|| actions.rules.iter().find(|rule| rule.rule == kind));
+        
+        match rule {
+            Some(rule) => (rule.action, rule.redirect_url.clone().or_else(|| actions.redirect_url.clone())),
+            None => (actions.default, actions.redirect_url.clone()),
+        }
+    }
+    
+    /// 설정 재로드: 차단 정책과 차단 목록을 다시 적용 (DB 활성화 시 DB에서 다시 로드)
+    pub async fn reload(&self, config: &Config) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        *self.acl_mode.write().unwrap() = config.acl_mode;
+        *self.acl_actions.write().unwrap() = config.acl_actions.clone();
+        *self.failure_policy.write().unwrap() = config.acl_failure_policy;
+        *self.conflict_precedence.write().unwrap() = config.acl_conflict_precedence;
+        self.load_filter_lists(config);
+        
+        if db::config::DbConfig::is_enabled() {
+            self.load_blocked_domains_from_db().await
+        } else {
+            self.load_blocked_domains_from_config(config);
+            Ok(())
+        }
+    }
+    
+    /// 현재 차단 정책 적용 방식
+    pub fn acl_mode(&self) -> AclMode {
+        *self.acl_mode.read().unwrap()
+    }
+    
+    /// 도메인과 일치하는 차단 규칙 찾기 (판단할 수 없으면 원인 반환)
+    ///
+    /// 차단 목록을 한 번도 불러오지 못했거나(초기 DB 로드 실패 포함) 규칙 잠금이 손상된 경우 판단할 수 없습니다.
+    /// 주기적 갱신이나 재로드가 실패하면 마지막으로 불러온 목록으로 계속 판단합니다.
+    fn find_matching_rule(&self, host: &str) -> Result<Option<RuleMatch>, &'static str> {
         // 초기화 여부 확인
-        if !*self.initialized.read().unwrap() {
-            warn!("초기화되지 않은 DomainBlocker에 접근 시도: {}", host);
-            return false; // 초기화되지 않은 경우 차단하지 않음
+        if !*self.initialized.read().map_err(|_| "initialization state lock poisoned")? {
+            return Err("block list not loaded");
         }
         
+        self.metrics.acl_evaluated();
+        
         // 캐시 확인
         if let Some(result) = self.check_cache(host) {
             match result {
-                MatchResult::Blocked => {
+                MatchResult::Blocked(matched) if matched.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) => {
+                    debug!("캐시의 차단 규칙이 만료되어 다시 평가: {} ({})", host, matched.label);
+                },
+                MatchResult::Blocked(matched) => {
                     debug!("캐시에서 차단된 도메인 확인: {}", host);
-                    return true;
+                    self.metrics.acl_rule_matched(matched.kind);
+                    return Ok(Some(matched));
                 },
                 MatchResult::NotBlocked => {
                     debug!("캐시에서 허용된 도메인 확인: {}", host);
-                    return false;
+                    return Ok(None);
                 }
             }
         }
         
-        // 정확한 도메인 일치 확인
-        let blocked_domains = self.blocked_domains.read().unwrap();
-        if blocked_domains.contains(host) {
+        // 일치하는 차단 규칙을 평가 순서대로 (정확한 도메인, 패턴, 필터 목록) 필요한 만큼만 찾음
+        // 만료 시각이 있는 규칙은 있을 때만 현재 시각과 비교 (만료된 규칙은 다음 로드 때 제거될 때까지 건너뜀)
+        let blocked_domains = self.blocked_domains.read().map_err(|_| "domain list lock poisoned")?;
+        let expiring_rules = self.expiring_rules.read().map_err(|_| "expiring rule lock poisoned")?;
+        let regex_patterns = self.regex_patterns.read().map_err(|_| "pattern list lock poisoned")?;
+        let filter_rules = self.filter_rules.read().map_err(|_| "filter list lock poisoned")?;
+        let now = (!expiring_rules.is_empty()).then(Utc::now);
+        
+        let exact = blocked_domains.contains(host).then(|| {
             debug!("정확히 차단된 도메인: {}", host);
-            self.update_cache(host, MatchResult::Blocked);
-            return true;
-        }
+            RuleMatch {
+                label: format!("domain:{}", host),
+                rule: host.to_string(),
+                kind: AclRuleKind::Exact,
+                specificity: Specificity::Exact(label_depth(host)),
+                expires_at: None,
+            }
+        });
+        let expiring_exact = now.and_then(|now| expiring_rules.find_domain(host, now)).map(|expires_at| {
+            debug!("만료 예정 규칙으로 차단된 도메인: {} (만료 {})", host, expires_at.to_rfc3339());
+            RuleMatch {
+                label: format!("domain:{} (만료 {})", host, expires_at.to_rfc3339()),
+                rule: host.to_string(),
+                kind: AclRuleKind::Exact,
+                specificity: Specificity::Exact(label_depth(host)),
+                expires_at: Some(expires_at),
+            }
+        });
+        let patterns = regex_patterns.iter().filter(|(pattern, _, _)| pattern.is_match(host)).map(|(pattern, kind, source)| {
+            debug!("패턴으로 차단된 도메인: {} ({})", host, pattern.as_str());
+            RuleMatch {
+                label: format!("pattern:{}", pattern.as_str()),
+                rule: source.clone(),
+                kind: *kind,
+                specificity: pattern_specificity(*kind, source),
+                expires_at: None,
+            }
+        });
+        let expiring_patterns = now.into_iter().flat_map(|now| expiring_rules.matching_patterns(host, now)).map(|pattern| {
+            debug!("만료 예정 패턴으로 차단된 도메인: {} ({}, 만료 {})", host, pattern.regex.as_str(), pattern.expires_at.to_rfc3339());
+            RuleMatch {
+                label: format!("pattern:{} (만료 {})", pattern.regex.as_str(), pattern.expires_at.to_rfc3339()),
+                rule: pattern.source.clone(),
+                kind: pattern.kind,
+                specificity: pattern_specificity(pattern.kind, &pattern.source),
+                expires_at: Some(pattern.expires_at),
+            }
+        });
+        let filter = filter_rules.find_block(host).map(|(domain, _)| {
+            debug!("필터 목록으로 차단된 도메인: {} (||{}^)", host, domain);
+            filter_match(host, domain)
+        });
+        let mut blocks = exact.into_iter().chain(expiring_exact).chain(patterns).chain(expiring_patterns).chain(filter);
+        
+        // 예외(@@) 규칙도 일치하면 acl_conflict_precedence에 따라 판단 ($important 필터 규칙은 항상 차단)
+        let precedence = *self.conflict_precedence.read().map_err(|_| "conflict precedence lock poisoned")?;
+        let matched = match filter_rules.find_exception(host) {
+            None => blocks.next(),
+            Some(_) if let Some(domain) = filter_rules.find_important(host) => Some(filter_match(host, domain)),
+            Some(_) if precedence == AclConflictPrecedence::BlockWins => blocks.next(),
+            Some(exception) if precedence == AclConflictPrecedence::FilterListOnly => {
+                // 필터 목록 규칙은 예외 규칙으로 통과, 운영자가 지정한 규칙은 그대로 차단
+                let block = blocks.find(|block| block.kind != AclRuleKind::Filter);
+                if block.is_none() {
+                    debug!("필터 목록 예외 규칙으로 통과: {} (@@||{}^)", host, exception);
+                }
+                block
+            },
+            Some(exception) if precedence == AclConflictPrecedence::AllowWins => {
+                if let Some(block) = blocks.next() {
+                    debug!("예외 규칙 우선으로 통과: {} (@@||{}^, 차단 규칙 {})", host, exception, block.label);
+                }
+                None
+            },
+            Some(exception) => {
+                // 가장 구체적인 차단 규칙과 비교해 예외 규칙이 더 구체적일 때만 통과 (같으면 차단)
+                let allowed = domain_specificity(host, exception);
+                let block = blocks.reduce(|best, next| if next.specificity > best.specificity { next } else { best });
+                match block {
+                    Some(block) if block.specificity >= allowed => Some(block),
+                    Some(block) => {
+                        debug!("더 구체적인 예외 규칙으로 통과: {} (@@||{}^, 차단 규칙 {})", host, exception, block.label);
+                        None
+                    },
+                    None => None,
+                }
+            },
+        };
         
-        // 정규표현식 패턴 매칭 확인
+        match matched {
+            Some(matched) => {
+                self.update_cache(host, MatchResult::Blocked(matched.clone()));
+                self.metrics.acl_rule_matched(matched.kind);
+                Ok(Some(matched))
+            },
+            None => {
+                self.update_cache(host, MatchResult::NotBlocked);
+                Ok(None)
+            },
+        }
+    }
+    
+    /// 예외(@@) 규칙과 겹치는 차단 규칙을 로드 시 기록 (목록 정리용)
+    ///
+    /// 정확한 도메인은 예외 규칙 도메인과 같거나 그 하위 도메인일 때, 패턴은 예외 규칙 도메인 자체에 일치할 때,
+    /// 필터 목록 규칙은 두 도메인이 같거나 한쪽이 하위 도메인일 때 겹치는 것으로 봅니다.
+    fn log_rule_conflicts(&self) {
+        let filter_rules = self.filter_rules.read().unwrap();
+        if !filter_rules.has_exceptions() {
+            return;
+        }
+        let blocked_domains = self.blocked_domains.read().unwrap();
+        let expiring_rules = self.expiring_rules.read().unwrap();
         let regex_patterns = self.regex_patterns.read().unwrap();
-        for pattern in regex_patterns.iter() {
-            if pattern.is_match(host) {
-                debug!("패턴으로 차단된 도메인: {} ({})", host, pattern.as_str());
-                self.update_cache(host, MatchResult::Blocked);
-                return true;
+        
+        let mut conflicts = filter_rules.overlapping_blocks();
+        for domain in blocked_domains.iter().chain(expiring_rules.domains.keys()) {
+            if let Some(exception) = filter_rules.find_exception(domain) {
+                conflicts.insert((exception.to_string(), format!("domain:{}", domain)));
+            }
+        }
+        for exception in filter_rules.exceptions() {
+            let sources = regex_patterns.iter().map(|(regex, _, source)| (regex, source))
+                .chain(expiring_rules.patterns.iter().map(|pattern| (&pattern.regex, &pattern.source)));
+            for (regex, source) in sources {
+                if regex.is_match(exception) {
+                    conflicts.insert((exception.to_string(), format!("pattern:{}", source)));
+                }
             }
         }
+        if conflicts.is_empty() {
+            return;
+        }
         
-        // 차단되지 않은 도메인
-        self.update_cache(host, MatchResult::NotBlocked);
-        false
+        let precedence = *self.conflict_precedence.read().unwrap();
+        warn!("[ACL] 허용(@@)과 차단 규칙 충돌 {} 건, acl_conflict_precedence: {} 적용", conflicts.len(), precedence.name());
+        for (index, (exception, block)) in conflicts.iter().enumerate() {
+            if index < CONFLICT_LOG_SAMPLES {
+                warn!("[ACL] 규칙 충돌: @@||{}^ <-> {}", exception, block);
+            } else {
+                debug!("[ACL] 규칙 충돌: @@||{}^ <-> {}", exception, block);
+            }
+        }
     }
     
     /// 캐시에서 도메인 차단 결과 확인
     fn check_cache(&self, host: &str) -> Option<MatchResult> {
-        let cache = self.domain_block_cache.read().unwrap();
+        // 캐시 잠금이 손상되면 캐시 없이 규칙으로 판단
+        let cache = self.domain_block_cache.read().ok()?;
         // LruCache의 get 메서드는 &self를 받지만, 직접 사용하면 오류가 발생하므로 다른 방법 사용
         let result = if let Some(value) = cache.peek(host) {
             Some(value.clone())
@@ -143,8 +466,9 @@ impl DomainBlocker {
     
     /// 캐시에 도메인 차단 결과 저장
     fn update_cache(&self, host: &str, result: MatchResult) {
-        let mut cache = self.domain_block_cache.write().unwrap();
-        cache.put(host.to_string(), result);
+        if let Ok(mut cache) = self.domain_block_cache.write() {
+            cache.put(host.to_string(), result);
+        }
     }
     
     /// 도메인 차단 테이블들 생성 확인
@@ -222,6 +546,14 @@ impl DomainBlocker {
             debug!("domain_pattern_blocks 테이블이 이미 존재합니다.");
         }
         
+        // 이전 버전에서 만든 테이블에 만료 시각 컬럼 추가
+        for (table, query) in [("domain_blocks", domain_blocks::ADD_EXPIRES_AT_COLUMN), ("domain_pattern_blocks", domain_pattern_blocks::ADD_EXPIRES_AT_COLUMN)] {
+            if let Err(e) = executor.execute_query(query, &[]).await {
+                error!("{} 만료 시각 컬럼 추가 실패: {}", table, e);
+                return Err(e);
+            }
+        }
+        
         Ok(())
     }
     
@@ -262,34 +594,126 @@ impl DomainBlocker {
             }
         };
         
-        // 결과 처리를 위한 임시 콜렉션
-        let mut exact_domains = HashSet::new();
-        let mut regex_patterns_vec = Vec::new();
-        
-        // 정확한 도메인 처리
-        for row in exact_rows {
-            let domain: String = row.get(0);
-            exact_domains.insert(domain.to_lowercase());
+        // 정확한 도메인 처리 (expires_at이 NULL이면 만료 없음)
+        let mut exact_domains: HashMap<String, Option<DateTime<Utc>>> = HashMap::new();
+        for row in &exact_rows {
+            let expires_at = row.get::<_, Option<DateTime<Utc>>>(1);
+            exact_domains.entry(canonical_host(&row.get::<_, String>(0)))
+                .and_modify(|current| *current = merge_expiry(*current, expires_at))
+                .or_insert(expires_at);
         }
         
         // 패턴 도메인 처리
-        for row in pattern_rows {
-            let pattern: String = row.get(0);
+        let mut patterns: BTreeMap<String, Option<DateTime<Utc>>> = BTreeMap::new();
+        for row in &pattern_rows {
+            let expires_at = row.get::<_, Option<DateTime<Utc>>>(1);
+            patterns.entry(row.get::<_, String>(0))
+                .and_modify(|current| *current = merge_expiry(*current, expires_at))
+                .or_insert(expires_at);
+        }
+        
+        let (exact_count, pattern_count) = self.apply_block_lists(exact_domains, patterns, "DB");
+        info!("DB에서 {} 개의 차단 도메인 로드 완료 (정확한 도메인: {}, 패턴: {})",
+            exact_count + pattern_count, exact_count, pattern_count);
+        
+        Ok(())
+    }
+    
+    /// 설정 파일에서 도메인 차단 목록 로드 (DB 비활성화 모드)
+    fn load_blocked_domains_from_config(&self, config: &Config) {
+        let expiry = |rule: &String| config.blocked_rule_expiry.get(rule).copied();
+        let mut exact_domains: HashMap<String, Option<DateTime<Utc>>> = HashMap::new();
+        for domain in &config.blocked_domains {
+            exact_domains.entry(canonical_host(domain))
+                .and_modify(|current| *current = merge_expiry(*current, expiry(domain)))
+                .or_insert(expiry(domain));
+        }
+        let patterns: BTreeMap<String, Option<DateTime<Utc>>> = config.blocked_patterns.iter()
+            .map(|pattern| (pattern.clone(), expiry(pattern)))
+            .collect();
+        
+        let (exact_count, pattern_count) = self.apply_block_lists(exact_domains, patterns, "설정 파일");
+        info!("설정 파일에서 {} 개의 차단 도메인 로드 완료 (정확한 도메인: {}, 패턴: {})",
+            exact_count + pattern_count, exact_count, pattern_count);
+    }
+    
+    /// 필터 목록 파일을 다시 읽어 필터 규칙 교체 후 캐시 초기화
+    fn load_filter_lists(&self, config: &Config) {
+        let rules = load_filter_lists(&config.blocked_filter_lists);
+        let count = rules.block_count();
+        self.metrics.set_acl_filter_rule_count(count as u64);
+        *self.filter_rules.write().unwrap() = rules;
+        if !config.blocked_filter_lists.is_empty() {
+            info!("필터 목록 {} 개에서 차단 규칙 {} 개 로드 완료", config.blocked_filter_lists.len(), count);
+        }
+        
+        if let Ok(mut cache) = self.domain_block_cache.write() {
+            cache.clear();
+        }
+    }
+    
+    /// 차단 목록 교체 후 캐시 초기화, (정확한 도메인 수, 패턴 수) 반환
+    ///
+    /// 만료 시각이 있는 규칙은 영구 규칙과 따로 보관하고, 이미 만료된 규칙은 제외하며 기록합니다
+    /// (직전 목록에서 유효했던 규칙은 info, 그 전부터 만료되어 있던 규칙은 debug).
+    fn apply_block_lists(
+        &self,
+        exact_domains: HashMap<String, Option<DateTime<Utc>>>,
+        patterns: BTreeMap<String, Option<DateTime<Utc>>>,
+        source: &str,
+    ) -> (usize, usize) {
+        let now = Utc::now();
+        let previously_live = self.expiring_rules.read().unwrap().labels();
+        let mut pruned = 0;
+        let mut prune = |label: String, expires_at: DateTime<Utc>| {
+            pruned += 1;
+            if previously_live.contains(&label) {
+                info!("[ACL] 만료된 차단 규칙 제거: {} (만료 {}, {})", label, expires_at.to_rfc3339(), source);
+            } else {
+                debug!("[ACL] 만료된 차단 규칙 제외: {} (만료 {}, {})", label, expires_at.to_rfc3339(), source);
+            }
+        };
+        
+        let mut permanent_domains = HashSet::new();
+        let mut expiring = ExpiringRules::default();
+        for (domain, expires_at) in exact_domains {
+            match expires_at {
+                None => {
+                    permanent_domains.insert(domain);
+                },
+                Some(expires_at) if expires_at <= now => prune(format!("domain:{}", domain), expires_at),
+                Some(expires_at) => {
+                    expiring.domains.insert(domain, expires_at);
+                },
+            }
+        }
+        
+        let mut regex_patterns_vec = Vec::new();
+        for (pattern, expires_at) in patterns {
+            if let Some(expires_at) = expires_at
+                && expires_at <= now {
+                prune(format!("pattern:{}", pattern), expires_at);
+                continue;
+            }
             
             // 정규표현식 패턴인 경우 (regex: 접두사 제거)
-            let regex_pattern = if pattern.starts_with("regex:") {
-                pattern[6..].to_string() // "regex:" 접두사 제거
+            let (regex_pattern, kind) = if let Some(stripped) = pattern.strip_prefix("regex:") {
+                (stripped.to_string(), AclRuleKind::Regex)
             } else {
-                // 그 외 모든 패턴은 와일드카드로 처리
-                pattern
+                // 그 외 모든 패턴은 와일드카드로 처리 (`*`가 없는 라벨은 정규 형식으로 변환)
+                let wildcard = canonical_wildcard(&pattern)
                     .replace(".", "\\.")
-                    .replace("*", ".*")
+                    .replace("*", ".*");
+                (wildcard, AclRuleKind::Wildcard)
             };
             
             match Regex::new(&regex_pattern) {
                 Ok(regex) => {
-                    regex_patterns_vec.push(regex);
                     debug!("패턴 컴파일 성공: {}", regex_pattern);
+                    match expires_at {
+                        Some(expires_at) => expiring.patterns.push(ExpiringPattern { regex, kind, source: pattern, expires_at }),
+                        None => regex_patterns_vec.push((regex, kind, pattern)),
+                    }
                 },
                 Err(e) => {
                     error!("패턴 컴파일 실패: {} - {}", regex_pattern, e);
@@ -297,20 +721,36 @@ impl DomainBlocker {
             }
         }
         
+        let exact_count = permanent_domains.len() + expiring.domains.len();
+        let pattern_count = regex_patterns_vec.len() + expiring.patterns.len();
+        let regex_count = regex_patterns_vec.iter().filter(|(_, kind, _)| *kind == AclRuleKind::Regex).count()
+            + expiring.patterns.iter().filter(|pattern| pattern.kind == AclRuleKind::Regex).count();
+        self.metrics.set_acl_rule_counts(exact_count as u64, (pattern_count - regex_count) as u64, regex_count as u64);
+        
+        if let Some(next_expiry) = expiring.next_expiry() {
+            info!("만료 시각이 있는 차단 규칙: 도메인 {} 개, 패턴 {} 개 (가장 이른 만료 {}), 만료로 제외 {} 개",
+                expiring.domains.len(), expiring.patterns.len(), next_expiry.to_rfc3339(), pruned);
+        } else if pruned > 0 {
+            info!("만료로 제외한 차단 규칙: {} 개", pruned);
+        }
+        
         // 정확한 도메인 목록 저장
         {
             let mut blocked_domains = self.blocked_domains.write().unwrap();
-            *blocked_domains = exact_domains.clone();
+            *blocked_domains = permanent_domains;
             info!("차단 도메인 목록 업데이트 완료: {} 개", blocked_domains.len());
         }
         
         // 정규표현식 패턴 저장
         {
             let mut regex_patterns = self.regex_patterns.write().unwrap();
-            *regex_patterns = regex_patterns_vec.clone();
+            *regex_patterns = regex_patterns_vec;
             info!("정규표현식 패턴 목록 업데이트 완료: {} 개", regex_patterns.len());
         }
         
+        // 만료 시각이 있는 규칙 저장
+        *self.expiring_rules.write().unwrap() = expiring;
+        
         // 캐시 초기화
         {
             let mut cache = self.domain_block_cache.write().unwrap();
@@ -318,10 +758,149 @@ impl DomainBlocker {
             debug!("도메인 차단 캐시 초기화 완료");
         }
         
-        let total_count = exact_domains.len() + regex_patterns_vec.len();
-        info!("DB에서 {} 개의 차단 도메인 로드 완료 (정확한 도메인: {}, 패턴: {})",
-            total_count, exact_domains.len(), regex_patterns_vec.len());
+        // 필터 목록은 차단 목록보다 먼저 로드되므로 여기서 전체 규칙의 충돌을 확인
+        self.log_rule_conflicts();
         
-        Ok(())
+        (exact_count, pattern_count)
+    }
+}
+
+/// 와일드카드 패턴의 정규 형식 (`*.München.de` → `*.xn--mnchen-3ya.de`)
+fn canonical_wildcard(pattern: &str) -> String {
+    pattern.trim().trim_end_matches('.')
+        .split('.')
+        .map(|label| if label.contains('*') { label.to_lowercase() } else { canonical_host(label) })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// 도메인 규칙(정확한 도메인 또는 하위 도메인 포함)이 호스트에 일치한 구체성
+fn domain_specificity(host: &str, domain: &str) -> Specificity {
+    if host == domain {
+        Specificity::Exact(label_depth(domain))
+    } else {
+        Specificity::Wildcard(label_depth(domain))
+    }
+}
+
+/// 패턴 규칙의 구체성 (와일드카드는 `*`가 없는 라벨 수)
+fn pattern_specificity(kind: AclRuleKind, source: &str) -> Specificity {
+    match kind {
+        AclRuleKind::Regex => Specificity::Regex,
+        _ => Specificity::Wildcard(label_depth(source)),
+    }
+}
+
+/// `*`가 없는 도메인 라벨 수
+fn label_depth(domain: &str) -> usize {
+    domain.split('.').filter(|label| !label.is_empty() && !label.contains('*')).count()
+}
+
+/// 필터 목록 차단 규칙 일치
+fn filter_match(host: &str, domain: &str) -> RuleMatch {
+    RuleMatch {
+        label: format!("filter:||{}^", domain),
+        rule: format!("||{}^", domain),
+        kind: AclRuleKind::Filter,
+        specificity: domain_specificity(host, domain),
+        expires_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocker(domains: &[&str], patterns: &[&str]) -> DomainBlocker {
+        db::config::DbConfig::disable();
+        let mut config = Config::new();
+        config.blocked_domains = domains.iter().map(|domain| domain.to_string()).collect();
+        config.blocked_patterns = patterns.iter().map(|pattern| pattern.to_string()).collect();
+        let blocker = DomainBlocker::new(Arc::new(config.clone()));
+        blocker.load_blocked_domains_from_config(&config);
+        *blocker.initialized.write().unwrap() = true;
+        blocker
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn canonical_wildcard_keeps_stars_and_folds_labels() {
+        assert_eq!(canonical_wildcard("*.München.DE."), "*.xn--mnchen-3ya.de");
+        assert_eq!(canonical_wildcard("*.XN--MNCHEN-3YA.de"), "*.xn--mnchen-3ya.de");
+        assert_eq!(canonical_wildcard("Ads*.Example.COM"), "ads*.example.com");
+    }
+
+    #[tokio::test]
+    async fn exact_rule_matches_every_host_spelling() {
+        for rule in ["münchen.de", "MÜNCHEN.DE.", "xn--mnchen-3ya.de", "XN--MNCHEN-3YA.de."] {
+            let blocker = blocker(&[rule], &[]);
+            for host in ["münchen.de", "München.De.", "xn--mnchen-3ya.de", "XN--MNCHEN-3YA.DE."] {
+                assert_eq!(blocker.decide(host, false), AclDecision::Block, "rule {} host {}", rule, host);
+            }
+            assert_eq!(blocker.decide("muenchen.de", false), AclDecision::Allow, "rule {}", rule);
+        }
+    }
+
+    #[tokio::test]
+    async fn wildcard_rule_matches_every_host_spelling() {
+        for rule in ["*.münchen.de", "*.MÜNCHEN.DE.", "*.xn--mnchen-3ya.de", "*.XN--MNCHEN-3YA.de"] {
+            let blocker = blocker(&[], &[rule]);
+            for host in ["www.münchen.de", "WWW.München.De.", "www.xn--mnchen-3ya.de", "Www.XN--MNCHEN-3YA.DE."] {
+                assert_eq!(blocker.decide(host, false), AclDecision::Block, "rule {} host {}", rule, host);
+            }
+            assert_eq!(blocker.decide("www.muenchen.de", false), AclDecision::Allow, "rule {}", rule);
+        }
+    }
+
+    #[tokio::test]
+    async fn conflict_precedence_decides_between_allow_and_block_rules() {
+        use AclConflictPrecedence::{AllowWins, BlockWins, FilterListOnly, MostSpecific};
+        let dir = std::env::temp_dir().join(format!("udss-unit-acl-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let list = dir.join("merged.txt");
+        std::fs::write(&list, "||cdn.example.net^\n||bad.example.org^$important\n\
+            @@||ads.example.com^\n@@||safe.tracker.example^\n@@||shop.example^\n@@||static.cdn.example.net^\n\
+            @@||cdn2.example^\n@@||bad.example.org^\n").unwrap();
+
+        // (호스트, filter_list_only, block_wins, allow_wins, most_specific에서 차단 여부)
+        let cases: [(&str, bool, bool, bool, bool); 8] = [
+            // 정확한 도메인 차단과 같은 도메인 예외: 구체성이 같으면 차단
+            ("ads.example.com", true, true, false, true),
+            // 와일드카드 차단보다 정확한 예외, 더 긴 하위 도메인 예외가 구체적
+            ("safe.tracker.example", true, true, false, false),
+            ("x.safe.tracker.example", true, true, false, false),
+            ("other.tracker.example", true, true, true, true),
+            // 정규표현식 차단보다 와일드카드 예외가 구체적
+            ("promo1.shop.example", true, true, false, false),
+            // 필터 목록 차단보다 정확한 예외가 구체적
+            ("static.cdn.example.net", false, true, false, false),
+            // 정확한 도메인 차단이 상위 도메인 예외보다 구체적
+            ("pixel.cdn2.example", true, true, false, true),
+            // $important는 항상 차단
+            ("bad.example.org", true, true, true, true),
+        ];
+        for precedence in [FilterListOnly, BlockWins, AllowWins, MostSpecific] {
+            let mut config = Config::new();
+            config.blocked_domains = ["ads.example.com", "pixel.cdn2.example"].iter().map(|s| s.to_string()).collect();
+            config.blocked_patterns = ["*.tracker.example", r"regex:^promo[0-9]+\.shop\.example$"].iter().map(|s| s.to_string()).collect();
+            config.blocked_filter_lists = vec![list.to_string_lossy().to_string()];
+            config.acl_conflict_precedence = precedence;
+            db::config::DbConfig::disable();
+            let blocker = Arc::new(DomainBlocker::new(Arc::new(config)));
+            blocker.initialize().await.unwrap();
+
+            for (host, filter_list_only, block_wins, allow_wins, most_specific) in cases {
+                let expected = match precedence {
+                    FilterListOnly => filter_list_only,
+                    BlockWins => block_wins,
+                    AllowWins => allow_wins,
+                    MostSpecific => most_specific,
+                };
+                // 캐시된 결과도 같은 판단이어야 함
+                for _ in 0..2 {
+                    assert_eq!(blocker.decide(host, false) == AclDecision::Block, expected, "{} {}", precedence.name(), host);
+                }
+            }
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
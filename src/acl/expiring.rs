@@ -0,0 +1,64 @@
+// 만료 시각이 있는 차단 규칙 (blocked_rule_expiry, DB의 expires_at)
+// 영구 규칙과 따로 보관해 만료 확인은 이 규칙을 평가할 때만 하고,
+// 만료된 규칙은 평가에서 건너뛰었다가 다음 목록 로드 때 제거합니다.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use crate::metrics::AclRuleKind;
+
+/// 만료 시각이 있는 패턴 규칙
+pub struct ExpiringPattern {
+    pub regex: Regex,
+    pub kind: AclRuleKind,
+    /// 차단 목록 항목 그대로의 패턴
+    pub source: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 만료 시각이 있는 정확한 도메인/패턴 규칙
+#[derive(Default)]
+pub struct ExpiringRules {
+    pub domains: HashMap<String, DateTime<Utc>>,
+    pub patterns: Vec<ExpiringPattern>,
+}
+
+impl ExpiringRules {
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty() && self.patterns.is_empty()
+    }
+
+    /// 호스트와 일치하고 아직 만료되지 않은 정확한 도메인 규칙의 만료 시각
+    pub fn find_domain(&self, host: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.domains.get(host).copied().filter(|expires_at| *expires_at > now)
+    }
+
+    /// 호스트와 일치하고 아직 만료되지 않은 패턴 규칙 (목록 순서)
+    pub fn matching_patterns<'a>(&'a self, host: &'a str, now: DateTime<Utc>) -> impl Iterator<Item = &'a ExpiringPattern> {
+        self.patterns.iter().filter(move |pattern| pattern.expires_at > now && pattern.regex.is_match(host))
+    }
+
+    /// 가장 이른 만료 시각
+    pub fn next_expiry(&self) -> Option<DateTime<Utc>> {
+        self.domains.values().copied()
+            .chain(self.patterns.iter().map(|pattern| pattern.expires_at))
+            .min()
+    }
+
+    /// 규칙 표기 목록 (`domain:<도메인>`, `pattern:<패턴>`)
+    pub fn labels(&self) -> HashSet<String> {
+        self.domains.keys().map(|domain| format!("domain:{}", domain))
+            .chain(self.patterns.iter().map(|pattern| format!("pattern:{}", pattern.source)))
+            .collect()
+    }
+}
+
+/// 같은 규칙이 여러 번 있을 때의 만료 시각 (만료 없는 항목이 있으면 만료 없음, 아니면 가장 늦은 시각)
+pub fn merge_expiry(current: Option<DateTime<Utc>>, next: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    match (current, next) {
+        (Some(current), Some(next)) => Some(current.max(next)),
+        _ => None,
+    }
+}
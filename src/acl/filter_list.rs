@@ -0,0 +1,211 @@
+use std::collections::{BTreeSet, HashSet};
+
+use log::{debug, info, warn};
+
+use crate::proxy::target::canonical_host;
+
+/// 로그에 예시로 남길 지원하지 않는 규칙 수 (목록 하나당)
+const UNSUPPORTED_LOG_SAMPLES: usize = 5;
+
+/// 호스트 단위로 그대로 적용할 수 있는 옵션
+///
+/// 그 외 옵션(요청 유형, third-party, `domain=` 등)은 요청 페이지나 리소스 유형을 알아야 하므로
+/// 호스트 전체를 차단하면 과차단이 되어 규칙을 적용하지 않습니다.
+const HOST_LEVEL_OPTIONS: [&str; 5] = ["important", "all", "document", "doc", "match-case"];
+
+/// 해석한 Adblock Plus 규칙 한 줄
+#[derive(Debug, PartialEq, Eq)]
+enum FilterLine {
+    /// `||도메인^` 차단 (`$important`이면 예외 규칙보다 우선)
+    Block { domain: String, important: bool },
+    /// `@@||도메인^` 예외
+    Exception { domain: String },
+    /// 요소 숨김 규칙 (`##`, `#@#`, `#?#`, `#$#`), 프록시에서 적용할 수 없어 무시
+    Cosmetic,
+    /// 주석, 헤더, 빈 줄
+    Ignored,
+    /// 도메인 단위로 적용할 수 없는 규칙
+    Unsupported(&'static str),
+}
+
+/// 목록별 해석 통계
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FilterListStats {
+    pub blocks: usize,
+    pub exceptions: usize,
+    pub cosmetic: usize,
+    pub unsupported: usize,
+}
+
+/// 필터 목록에서 불러온 도메인 규칙 (도메인과 모든 하위 도메인에 적용)
+#[derive(Debug, Default)]
+pub struct FilterRules {
+    blocks: HashSet<String>,
+    important: HashSet<String>,
+    exceptions: HashSet<String>,
+}
+
+impl FilterRules {
+    /// 차단 규칙 수 (예외 규칙 제외)
+    pub fn block_count(&self) -> usize {
+        self.blocks.len() + self.important.len()
+    }
+
+    /// 호스트에 적용되는 차단 규칙의 도메인과 `$important` 여부 (`$important` 규칙 먼저, 예외 규칙은 보지 않음)
+    ///
+    /// 예외 규칙과 겹칠 때의 처리는 `acl_conflict_precedence`에 따라 DomainBlocker가 정합니다.
+    pub fn find_block(&self, host: &str) -> Option<(&str, bool)> {
+        if let Some(domain) = self.find_important(host) {
+            return Some((domain, true));
+        }
+        find_suffix(&self.blocks, host).map(|domain| (domain, false))
+    }
+
+    /// 호스트에 적용되는 `$important` 차단 규칙의 도메인 (모든 우선순위에서 예외 규칙보다 우선)
+    pub fn find_important(&self, host: &str) -> Option<&str> {
+        find_suffix(&self.important, host)
+    }
+
+    /// 호스트에 적용되는 예외(`@@`) 규칙의 도메인
+    pub fn find_exception(&self, host: &str) -> Option<&str> {
+        find_suffix(&self.exceptions, host)
+    }
+
+    pub fn has_exceptions(&self) -> bool {
+        !self.exceptions.is_empty()
+    }
+
+    /// 예외 규칙 도메인 목록
+    pub fn exceptions(&self) -> impl Iterator<Item = &str> {
+        self.exceptions.iter().map(String::as_str)
+    }
+
+    /// 같은 호스트에 함께 일치할 수 있는 (예외 규칙 도메인, 차단 규칙 표기) 쌍
+    ///
+    /// 두 도메인이 같거나 한쪽이 다른 쪽의 하위 도메인이면 겹칩니다.
+    pub fn overlapping_blocks(&self) -> BTreeSet<(String, String)> {
+        let mut overlaps = BTreeSet::new();
+        for (domains, important) in [(&self.blocks, false), (&self.important, true)] {
+            let label = |domain: &str| format!("filter:||{}^{}", domain, if important { "$important" } else { "" });
+            for domain in domains {
+                if let Some(exception) = find_suffix(&self.exceptions, domain) {
+                    overlaps.insert((exception.to_string(), label(domain)));
+                }
+            }
+            for exception in &self.exceptions {
+                if let Some(domain) = find_suffix(domains, exception) {
+                    overlaps.insert((exception.clone(), label(domain)));
+                }
+            }
+        }
+        overlaps
+    }
+
+    /// 목록 하나를 해석해 규칙 추가
+    pub fn add_list(&mut self, source: &str, content: &str) -> FilterListStats {
+        let mut stats = FilterListStats::default();
+        for line in content.lines() {
+            match parse_line(line) {
+                FilterLine::Block { domain, important: true } => {
+                    self.important.insert(domain);
+                    stats.blocks += 1;
+                },
+                FilterLine::Block { domain, important: false } => {
+                    self.blocks.insert(domain);
+                    stats.blocks += 1;
+                },
+                FilterLine::Exception { domain } => {
+                    self.exceptions.insert(domain);
+                    stats.exceptions += 1;
+                },
+                FilterLine::Cosmetic => stats.cosmetic += 1,
+                FilterLine::Ignored => {},
+                FilterLine::Unsupported(reason) => {
+                    stats.unsupported += 1;
+                    if stats.unsupported <= UNSUPPORTED_LOG_SAMPLES {
+                        debug!("필터 목록 {}: 지원하지 않는 규칙 무시 ({}): {}", source, reason, line.trim());
+                    }
+                },
+            }
+        }
+        stats
+    }
+}
+
+/// 필터 목록 파일들을 읽어 규칙 생성 (읽을 수 없는 파일은 경고 후 건너뜀)
+pub fn load_filter_lists(paths: &[String]) -> FilterRules {
+    let mut rules = FilterRules::default();
+    for path in paths {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("필터 목록을 읽을 수 없어 건너뜁니다: {} ({})", path, e);
+                continue;
+            }
+        };
+        let stats = rules.add_list(path, &content);
+        info!("필터 목록 {} 로드: 차단 {}, 예외 {}, 요소 숨김 무시 {}, 지원하지 않는 규칙 무시 {}",
+            path, stats.blocks, stats.exceptions, stats.cosmetic, stats.unsupported);
+    }
+    rules
+}
+
+/// 호스트 자신 또는 상위 도메인 중 집합에 있는 도메인
+fn find_suffix<'a>(domains: &'a HashSet<String>, host: &str) -> Option<&'a str> {
+    let mut suffix = host;
+    loop {
+        if let Some(domain) = domains.get(suffix) {
+            return Some(domain);
+        }
+        suffix = suffix.split_once('.')?.1;
+    }
+}
+
+/// Adblock Plus 규칙 한 줄 해석 (도메인 고정 `||도메인^` 형식만 적용)
+fn parse_line(line: &str) -> FilterLine {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('!') || line.starts_with('[') {
+        return FilterLine::Ignored;
+    }
+    if ["##", "#@#", "#?#", "#$#"].iter().any(|marker| line.contains(marker)) {
+        return FilterLine::Cosmetic;
+    }
+
+    let (exception, rule) = match line.strip_prefix("@@") {
+        Some(rule) => (true, rule),
+        None => (false, line),
+    };
+    let (pattern, options) = match rule.rsplit_once('$') {
+        Some((pattern, options)) => (pattern, Some(options)),
+        None => (rule, None),
+    };
+
+    let mut important = false;
+    for option in options.into_iter().flat_map(|options| options.split(',')) {
+        let option = option.trim().to_ascii_lowercase();
+        if option.starts_with("domain=") {
+            return FilterLine::Unsupported("$domain= needs the requesting page");
+        }
+        if !HOST_LEVEL_OPTIONS.contains(&option.as_str()) {
+            return FilterLine::Unsupported("option not applicable to a whole host");
+        }
+        important |= option == "important";
+    }
+
+    let Some(domain) = pattern.strip_prefix("||") else {
+        return FilterLine::Unsupported("not anchored with ||");
+    };
+    let Some(domain) = domain.strip_suffix("^|").or_else(|| domain.strip_suffix('^')) else {
+        return FilterLine::Unsupported("path or missing ^ separator");
+    };
+    if domain.is_empty() || !domain.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '.') {
+        return FilterLine::Unsupported("wildcard or invalid domain");
+    }
+
+    let domain = canonical_host(domain);
+    if exception {
+        FilterLine::Exception { domain }
+    } else {
+        FilterLine::Block { domain, important }
+    }
+}
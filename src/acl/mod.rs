@@ -2,4 +2,7 @@
 // 접근 제어 관련 기능을 구현합니다.
 
 pub mod domain_blocker;
-pub mod block_page; 
\ No newline at end of file
+pub mod expiring;
+pub mod filter_list;
+pub mod block_page;
+pub mod connect_acl; 
\ No newline at end of file
@@ -0,0 +1,28 @@
+use std::net::IpAddr;
+
+use crate::server::tenant::{cidr_contains, parse_cidr};
+
+/// 관리 엔드포인트에 접속할 수 있는 출발지 주소 대역
+///
+/// 대역이 없으면 모든 주소를 허용합니다.
+pub struct SourceAllowlist {
+    ranges: Vec<(IpAddr, u8)>,
+}
+
+impl SourceAllowlist {
+    /// 설정의 `admin_allowed_sources`로 생성 (형식은 설정 검증에서 확인됨)
+    pub fn new(sources: &[String]) -> Self {
+        Self {
+            ranges: sources.iter().filter_map(|source| parse_cidr(source).ok()).collect(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ranges.is_empty()
+    }
+
+    /// 출발지 주소가 허용 대역에 속하는지 확인
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        !self.is_enabled() || self.ranges.iter().any(|range| cidr_contains(*range, ip))
+    }
+}
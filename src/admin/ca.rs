@@ -0,0 +1,109 @@
+// 루트 CA 인증서 배포 (GET /ca.crt)
+// 클라이언트가 신뢰 저장소에 설치할 수 있도록 현재 리프 서명 CA의 인증서만 PEM으로 응답합니다.
+// 개인 키는 다루지 않으며, public_bind 리스너는 이 경로 외의 요청에 모두 404로 응답합니다.
+
+use std::net::SocketAddr;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use log::{debug, info, warn};
+use rustls::pki_types::CertificateDer;
+use serde_json::json;
+use tokio::net::{TcpListener, TcpStream};
+
+use super::tls::certificate_fingerprint;
+use super::{ADMIN_REQUEST_TIMEOUT, AdminResponse, json_response, read_request_head, write_response};
+use crate::config::Config;
+use crate::error::Result;
+use crate::tls::root_ca_certificate;
+
+/// CA 인증서 응답 Content-Type
+pub const CA_CERT_CONTENT_TYPE: &str = "application/x-pem-file";
+
+/// CA 인증서 SHA-256 지문을 담는 응답 헤더 (설치 전 대조용)
+pub const CA_FINGERPRINT_HEADER: &str = "X-CA-Fingerprint-SHA256";
+
+/// PEM 본문 한 줄 길이
+const PEM_LINE_LEN: usize = 64;
+
+/// 인증서 DER을 PEM으로 인코딩
+pub fn encode_certificate_pem(cert: &CertificateDer<'_>) -> String {
+    let encoded = STANDARD.encode(cert.as_ref());
+    let mut pem = String::with_capacity(encoded.len() + encoded.len() / PEM_LINE_LEN + 64);
+    pem.push_str("-----BEGIN CERTIFICATE-----\n");
+    for line in encoded.as_bytes().chunks(PEM_LINE_LEN) {
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+    pem.push_str("-----END CERTIFICATE-----\n");
+    pem
+}
+
+/// `GET /ca.crt` 응답 (루트 CA가 아직 초기화되지 않았으면 503)
+pub(super) fn certificate_response() -> AdminResponse {
+    match root_ca_certificate() {
+        Some(cert) => AdminResponse {
+            status: "200 OK",
+            content_type: CA_CERT_CONTENT_TYPE,
+            body: encode_certificate_pem(&cert),
+            extra_header: Some(format!("{}: {}", CA_FINGERPRINT_HEADER, certificate_fingerprint(&cert))),
+        },
+        None => json_response("503 Service Unavailable", &json!({"status": "error", "error": "root ca not initialized"}), None),
+    }
+}
+
+/// `ca_download.public_bind`가 지정된 경우 인증 없이 `GET /ca.crt`만 응답하는 리스너 시작
+///
+/// 관리 엔드포인트의 `admin_allowed_sources`와 `admin_tls`는 적용되지 않습니다.
+/// 요청 하나를 처리하면 연결을 닫습니다.
+pub async fn start_ca_download_server(config: &Config) -> Result<()> {
+    let Some(public_bind) = &config.ca_download.public_bind else {
+        return Ok(());
+    };
+
+    let listener = TcpListener::bind(public_bind).await?;
+    info!("CA 인증서 배포 엔드포인트 시작: http://{}/ca.crt (인증 없음)", public_bind);
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_download_connection(stream, peer).await {
+                            debug!("CA 인증서 요청 처리 실패 ({}): {}", peer, e);
+                        }
+                    });
+                },
+                Err(e) => warn!("CA 인증서 배포 연결 수락 실패: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+async fn handle_download_connection(mut stream: TcpStream, peer: SocketAddr) -> std::io::Result<()> {
+    let mut buffer = Vec::with_capacity(1024);
+    if !tokio::time::timeout(ADMIN_REQUEST_TIMEOUT, read_request_head(&mut stream, &mut buffer)).await?? {
+        return write_response(&mut stream, &json_response("400 Bad Request", &json!({"status": "error", "error": "malformed request"}), None), None).await;
+    }
+
+    let mut headers = [httparse::EMPTY_HEADER; 32];
+    let mut request = httparse::Request::new(&mut headers);
+    let response = match request.parse(&buffer) {
+        Ok(httparse::Status::Complete(_)) => {
+            let path = request.path.unwrap_or_default().split('?').next().unwrap_or_default();
+            match (request.method.unwrap_or_default(), path) {
+                ("GET", "/ca.crt") => {
+                    debug!("CA 인증서 배포: {}", peer);
+                    certificate_response()
+                },
+                (_, "/ca.crt") => {
+                    json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET"))
+                },
+                _ => json_response("404 Not Found", &json!({"status": "error", "error": "not found"}), None),
+            }
+        },
+        _ => json_response("400 Bad Request", &json!({"status": "error", "error": "malformed request"}), None),
+    };
+    write_response(&mut stream, &response, None).await
+}
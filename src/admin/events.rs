@@ -0,0 +1,70 @@
+// 관리 엔드포인트 실시간 이벤트 스트림 (GET /events, Server-Sent Events)
+// 연결을 응답 하나로 끝내지 않고 구독이 끝날 때까지 이벤트 프레임을 이어서 씁니다.
+// 이벤트가 없어도 주기적으로 주석 줄을 보내 끊긴 시청자를 찾아내고, 시청자가 연결을 닫으면 바로 구독을 해제합니다.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use serde_json::json;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::{json_response, write_response};
+use crate::logging::events::{subscribe, subscriber_count};
+
+/// 이벤트가 없을 때 연결 확인용 주석을 보내는 주기
+const EVENT_STREAM_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// `GET /events` 처리: 구독을 등록하고 시청자가 연결을 닫을 때까지 이벤트를 전달
+///
+/// 시청자 수 한도에 도달했거나 비활성화(event_stream.max_clients: 0)되어 있으면 503으로 응답하고 닫습니다.
+pub(super) async fn stream_events<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, peer: SocketAddr) -> std::io::Result<()> {
+    let Some(mut subscription) = subscribe() else {
+        warn!("관리 엔드포인트: 이벤트 스트림 시청자 한도 도달로 거부 ({})", peer);
+        let response = json_response("503 Service Unavailable", &json!({"status": "error", "error": "event stream unavailable"}), None);
+        return write_response(&mut stream, &response, None).await;
+    };
+
+    let (mut reader, mut writer) = tokio::io::split(stream);
+    writer.write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n: connected\n\n").await?;
+    writer.flush().await?;
+    info!("이벤트 스트림 시청자 연결: {} (시청자 {}명)", peer, subscriber_count());
+
+    let mut keepalive = tokio::time::interval_at(tokio::time::Instant::now() + EVENT_STREAM_KEEPALIVE, EVENT_STREAM_KEEPALIVE);
+    let mut discard = [0u8; 256];
+    let result = loop {
+        tokio::select! {
+            frame = subscription.recv() => match frame {
+                Some(frame) => {
+                    if let Err(e) = writer.write_all(frame.as_bytes()).await {
+                        break Err(e);
+                    }
+                    if let Err(e) = writer.flush().await {
+                        break Err(e);
+                    }
+                },
+                None => break Ok(()),
+            },
+            _ = keepalive.tick() => {
+                if let Err(e) = writer.write_all(b": keepalive\n\n").await {
+                    break Err(e);
+                }
+                if let Err(e) = writer.flush().await {
+                    break Err(e);
+                }
+            },
+            // 시청자가 보내는 데이터는 버리고 연결 종료만 확인
+            read = reader.read(&mut discard) => match read {
+                Ok(0) | Err(_) => break Ok(()),
+                Ok(_) => {},
+            },
+        }
+    };
+
+    drop(subscription);
+    match &result {
+        Ok(()) => info!("이벤트 스트림 시청자 연결 종료: {} (시청자 {}명)", peer, subscriber_count()),
+        Err(e) => debug!("이벤트 스트림 전송 실패로 종료 ({}): {}", peer, e),
+    }
+    result
+}
@@ -0,0 +1,83 @@
+// 호스트별 TLS 가로채기 즉시 전환 (관리 엔드포인트 /intercept/{host})
+// 장애 대응 중 설정 파일 수정과 재로드 없이 특정 호스트의 가로채기를 끄거나 켤 수 있도록
+// 메모리의 가로채기 제외 목록에 호스트별 지정 값을 두고, 요청하면 DB 목록에도 반영합니다.
+
+use std::net::SocketAddr;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::db::config::DbConfig;
+use crate::tls::exemption::{InterceptState, clear_intercept_override, is_valid_intercept_host, persist_intercept, set_intercept_override};
+
+/// `POST /intercept/{host}` 요청 본문
+#[derive(Debug, Deserialize)]
+pub struct InterceptRequest {
+    /// 가로채기 여부 (false면 복호화 없이 전달)
+    pub intercept: bool,
+    /// DB `tls_exemptions` 목록에도 반영 (재시작/다른 인스턴스에도 적용)
+    #[serde(default)]
+    pub persist: bool,
+    /// 변경 사유 (감사 로그와 DB description에 기록)
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// 변경 후 상태
+#[derive(Debug, Serialize)]
+pub struct InterceptResponse {
+    pub status: &'static str,
+    #[serde(flatten)]
+    pub state: InterceptState,
+    /// DB 목록에 반영했는지
+    pub persisted: bool,
+}
+
+/// 요청 오류
+#[derive(Debug)]
+pub enum InterceptError {
+    /// 호스트 또는 본문 형식 오류
+    BadRequest(String),
+    /// DB 반영 실패 (메모리 지정 값은 적용됨)
+    PersistFailed(String),
+}
+
+/// 경로의 호스트 해석 (`*`는 `%2A`로 보낼 수 있음)
+pub fn parse_host(raw: &str) -> Result<String, InterceptError> {
+    let host = raw.replace("%2A", "*").replace("%2a", "*");
+    if !is_valid_intercept_host(&host) {
+        return Err(InterceptError::BadRequest(format!("invalid host: {}", raw)));
+    }
+    Ok(host)
+}
+
+/// 가로채기 여부 지정 (감사 로그 기록, persist이면 DB에도 반영)
+pub async fn apply(host: &str, body: &[u8], peer: SocketAddr) -> Result<InterceptResponse, InterceptError> {
+    let request: InterceptRequest = serde_json::from_slice(body)
+        .map_err(|e| InterceptError::BadRequest(format!("invalid body (expected {{\"intercept\": bool}}): {}", e)))?;
+    if request.persist && !DbConfig::is_enabled() {
+        return Err(InterceptError::BadRequest("persist requires the database".to_string()));
+    }
+
+    let state = set_intercept_override(host, request.intercept);
+    info!("[INTERCEPT-AUDIT] 가로채기 {} 지정: {} (요청 {}, persist {}, 사유: {}) → 현재 intercept={} ({})",
+          if request.intercept { "켜기" } else { "끄기" }, state.host, peer, request.persist,
+          request.reason.as_deref().unwrap_or("-"), state.intercept, state.source);
+
+    if request.persist {
+        let created_by = format!("admin:{}", peer.ip());
+        if let Err(e) = persist_intercept(host, request.intercept, &created_by, request.reason.as_deref()).await {
+            warn!("[INTERCEPT-AUDIT] 가로채기 지정 DB 반영 실패, 메모리에만 적용됨: {} ({})", state.host, e);
+            return Err(InterceptError::PersistFailed(e.to_string()));
+        }
+    }
+    Ok(InterceptResponse { status: "ok", state, persisted: request.persist })
+}
+
+/// 지정 값 해제 (목록 기준으로 되돌림, 감사 로그 기록)
+pub fn clear(host: &str, peer: SocketAddr) -> InterceptResponse {
+    let state = clear_intercept_override(host);
+    info!("[INTERCEPT-AUDIT] 가로채기 지정 해제: {} (요청 {}) → 현재 intercept={} ({})",
+          state.host, peer, state.intercept, state.source);
+    InterceptResponse { status: "ok", state, persisted: false }
+}
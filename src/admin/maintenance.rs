@@ -0,0 +1,28 @@
+// 점검 모드 전환 (관리 엔드포인트 /maintenance)
+
+use std::net::SocketAddr;
+
+use serde::Deserialize;
+
+use crate::proxy::maintenance::{MaintenanceState, set_maintenance};
+
+/// `POST /maintenance` 요청 본문
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceRequest {
+    /// 점검 모드 켜기/끄기
+    pub enabled: bool,
+    /// 켤 때 진행 중인 세션도 닫을지 (없으면 maintenance.close_existing 설정 값)
+    #[serde(default)]
+    pub close_existing: Option<bool>,
+    /// 전환 사유 (로그와 상태 응답에 기록)
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// 점검 모드 전환 (변경 후 상태 반환, 본문 형식 오류는 Err)
+pub fn apply(body: &[u8], peer: SocketAddr) -> Result<MaintenanceState, String> {
+    let request: MaintenanceRequest = serde_json::from_slice(body)
+        .map_err(|e| format!("invalid body (expected {{\"enabled\": bool}}): {}", e))?;
+    let changed_by = format!("admin:{}", peer.ip());
+    Ok(set_maintenance(request.enabled, request.close_existing, request.reason.as_deref(), &changed_by))
+}
@@ -0,0 +1,430 @@
+// 운영 자동화를 위한 관리 엔드포인트와 설정 재로드
+
+pub mod access;
+pub mod ca;
+pub mod events;
+pub mod intercept;
+pub mod maintenance;
+pub mod pac;
+pub mod reload;
+pub mod tls;
+pub mod version;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, error, info, warn};
+use serde_json::{Value, json};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::config::{AdminKeepAliveConfig, Config};
+use crate::db::quarantine::{quarantined_partitions, release_partition};
+use crate::error::Result;
+use crate::logging::events::set_event_stream;
+use crate::logging::recent::recent_logs;
+use crate::metrics::Metrics;
+use crate::proxy::passthrough::is_persistent;
+use crate::proxy::maintenance::maintenance_state;
+use crate::tls::exemption::intercept_state;
+use access::SourceAllowlist;
+use intercept::InterceptError;
+use pac::{PAC_CONTENT_TYPE, PacSource};
+use reload::{ReloadError, Reloader};
+
+/// 관리 요청 헤더 최대 길이
+const ADMIN_REQUEST_MAX_LEN: usize = 8192;
+
+/// 관리 요청 헤더 수신 제한 시간
+pub(super) const ADMIN_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `admin_bind`가 지정된 경우 관리 엔드포인트 시작
+///
+/// `POST /reload`는 설정 파일을 다시 읽어 적용하고 변경 요약을 JSON으로 응답합니다.
+/// 다른 재로드가 진행 중이면 409를 응답합니다.
+/// `GET /version`은 빌드 버전, git 커밋, 빌드 프로필과 적용된 설정 해시를 응답합니다.
+/// pac.enabled이면 `GET /proxy.pac`으로 자동 프록시 설정 파일을 응답합니다.
+/// ca_download.enabled이면 `GET /ca.crt`로 루트 CA 인증서(PEM)와 SHA-256 지문 헤더를 응답합니다.
+/// `GET /events`는 세션 시작/종료, 차단, 업스트림 실패 이벤트를 Server-Sent Events(JSON)로 연결이 끊길 때까지 스트리밍합니다.
+/// `GET /debug/log`는 최근 로그 링 버퍼의 기록을 오래된 순으로 응답합니다 (debug_log_buffer_size가 0이면 404).
+/// `/intercept/{host}`는 호스트의 가로채기 상태를 조회(GET), 지정(POST), 해제(DELETE)합니다.
+/// `/maintenance`는 점검 모드 상태를 조회(GET)하거나 전환(POST)합니다.
+/// `GET /circuits`는 닫히지 않은(열림/반열림) 업스트림 회로의 호스트와 상태를 응답합니다.
+/// `GET /db/quarantine`은 격리 중인 로그 파티션 목록을, `DELETE /db/quarantine/{partition}`은 복구한 파티션의 격리를 해제합니다.
+/// `admin_allowed_sources`가 있으면 그 대역 밖에서 온 연결은 요청을 읽기 전에 응답 없이 닫습니다.
+/// `admin_keep_alive`에 따라 한 연결에서 여러 요청을 처리합니다 (스크레이퍼의 재연결 감소).
+/// `admin_tls`가 활성화되어 있으면 TLS로만 응답하며, 지문이 고정된 클라이언트 인증서가 아니면 핸드셰이크에서 거부합니다.
+pub async fn start_admin_server(config: &Config, reloader: Arc<Reloader>) -> Result<()> {
+    let Some(admin_bind) = &config.admin_bind else {
+        return Ok(());
+    };
+
+    let listener = TcpListener::bind(admin_bind).await?;
+    let pac = PacSource::from_config(config).map(Arc::new);
+    let ca_download = config.ca_download.enabled;
+    set_event_stream(&config.event_stream);
+    let allowlist = SourceAllowlist::new(&config.admin_allowed_sources);
+    let keep_alive = config.admin_keep_alive;
+    let acceptor = if config.admin_tls.enabled {
+        let acceptor = tls::build_admin_acceptor(&config.admin_tls)?;
+        info!("관리 엔드포인트 TLS 활성화: 고정된 클라이언트 인증서 {}개", config.admin_tls.client_cert_fingerprints.len());
+        Some(acceptor)
+    } else {
+        None
+    };
+    let scheme = if acceptor.is_some() { "https" } else { "http" };
+    if allowlist.is_enabled() {
+        info!("관리 엔드포인트 접속 허용 대역: {}", config.admin_allowed_sources.join(", "));
+    }
+    let mut routes = "POST /reload, GET /version, GET /debug/log, GET /events, /intercept/{host}, /maintenance, GET /circuits, /db/quarantine".to_string();
+    if pac.is_some() {
+        routes.push_str(", GET /proxy.pac");
+    }
+    if ca_download {
+        routes.push_str(", GET /ca.crt");
+    }
+    info!("관리 엔드포인트 시작: {}://{} ({})", scheme, admin_bind, routes);
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    // 허용 대역 밖의 연결은 어떤 경로로도 라우팅하지 않고 바로 닫음
+                    if !allowlist.allows(peer.ip()) {
+                        warn!("관리 연결 거부 (허용 대역 밖): {}", peer);
+                        drop(stream);
+                        continue;
+                    }
+                    let reloader = Arc::clone(&reloader);
+                    let pac = pac.clone();
+                    let acceptor = acceptor.clone();
+                    tokio::spawn(async move {
+                        let result = match acceptor {
+                            Some(acceptor) => match tokio::time::timeout(ADMIN_REQUEST_TIMEOUT, acceptor.accept(stream)).await {
+                                Ok(Ok(stream)) => handle_admin_connection(stream, peer, &reloader, pac.as_deref(), ca_download, keep_alive).await,
+                                Ok(Err(e)) => {
+                                    warn!("관리 TLS 핸드셰이크 실패 ({}): {}", peer, e);
+                                    return;
+                                },
+                                Err(_) => {
+                                    debug!("관리 TLS 핸드셰이크 시간 초과 ({})", peer);
+                                    return;
+                                },
+                            },
+                            None => handle_admin_connection(stream, peer, &reloader, pac.as_deref(), ca_download, keep_alive).await,
+                        };
+                        if let Err(e) = result {
+                            debug!("관리 요청 처리 실패 ({}): {}", peer, e);
+                        }
+                    });
+                },
+                Err(e) => warn!("관리 연결 수락 실패: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+/// SIGHUP을 받으면 관리 엔드포인트와 같은 경로로 설정 재로드
+pub fn spawn_sighup_reload(reloader: Arc<Reloader>) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(signal) => signal,
+            Err(e) => {
+                warn!("can't install SIGHUP handler: {}", e);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, 설정 재로드 시작");
+            if let Err(e) = reloader.reload().await {
+                error!("설정 재로드 실패: {}", e);
+            }
+        }
+    });
+
+    #[cfg(not(unix))]
+    let _ = reloader;
+}
+
+/// 관리 응답 하나
+pub(super) struct AdminResponse {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+    extra_header: Option<String>,
+}
+
+/// 관리 연결 처리: 연결 유지가 켜져 있으면 같은 연결에서 `admin_keep_alive.max_requests`까지 요청을 차례로 처리
+///
+/// 응답 후 `idle_timeout_seconds` 안에 다음 요청이 시작되지 않으면 연결을 닫습니다.
+/// 클라이언트가 `Connection: close`를 보냈거나(HTTP/1.0은 `Connection: keep-alive`가 없으면) 요청 형식 오류가 있으면
+/// 그 응답을 끝으로 연결을 닫습니다.
+async fn handle_admin_connection<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, peer: SocketAddr, reloader: &Reloader, pac: Option<&PacSource>, ca_download: bool, keep_alive: AdminKeepAliveConfig) -> std::io::Result<()> {
+    let idle_timeout = Duration::from_secs(keep_alive.idle_timeout_seconds);
+    let mut buffer = Vec::with_capacity(1024);
+    let mut served: u32 = 0;
+    loop {
+        // 두 번째 요청부터는 유휴 시간 안에 요청이 시작되어야 함 (응답 없는 스크레이퍼 연결을 붙잡지 않음)
+        if served > 0 && buffer.is_empty() {
+            let mut chunk = [0u8; 1024];
+            match tokio::time::timeout(idle_timeout, stream.read(&mut chunk)).await {
+                Ok(Ok(0)) => return Ok(()),
+                Ok(Ok(n)) => buffer.extend_from_slice(&chunk[..n]),
+                Ok(Err(e)) => return Err(e),
+                Err(_) => {
+                    debug!("관리 연결 유휴 시간 초과로 종료 ({}, 요청 {}건 처리)", peer, served);
+                    return Ok(());
+                },
+            }
+        }
+
+        if !tokio::time::timeout(ADMIN_REQUEST_TIMEOUT, read_request_head(&mut stream, &mut buffer)).await?? {
+            return write_response(&mut stream, &json_response("400 Bad Request", &json!({"status": "error", "error": "malformed request"}), None), None).await;
+        }
+
+        let mut headers = [httparse::EMPTY_HEADER; 32];
+        let mut request = httparse::Request::new(&mut headers);
+        let (method, path, head_len, content_length) = match request.parse(&buffer) {
+            Ok(httparse::Status::Complete(head_len)) => (
+                request.method.unwrap_or_default().to_string(),
+                request.path.unwrap_or_default().split('?').next().unwrap_or_default().to_string(),
+                head_len,
+                request.headers.iter()
+                    .find(|header| header.name.eq_ignore_ascii_case("content-length"))
+                    .and_then(|header| std::str::from_utf8(header.value).ok()?.trim().parse::<usize>().ok())
+                    .unwrap_or(0),
+            ),
+            _ => return write_response(&mut stream, &json_response("400 Bad Request", &json!({"status": "error", "error": "malformed request"}), None), None).await,
+        };
+        if content_length > ADMIN_REQUEST_MAX_LEN {
+            return write_response(&mut stream, &json_response("413 Payload Too Large", &json!({"status": "error", "error": "request body too large"}), None), None).await;
+        }
+        if !tokio::time::timeout(ADMIN_REQUEST_TIMEOUT, read_request_body(&mut stream, &mut buffer, head_len + content_length)).await?? {
+            return write_response(&mut stream, &json_response("400 Bad Request", &json!({"status": "error", "error": "incomplete request body"}), None), None).await;
+        }
+
+        // 이벤트 스트림은 연결을 넘겨받아 시청자가 닫을 때까지 응답을 이어서 씀
+        if method == "GET" && path == "/events" {
+            return events::stream_events(stream, peer).await;
+        }
+
+        served += 1;
+        let persistent = keep_alive.is_enabled() && served < keep_alive.max_requests && is_persistent(&buffer[..head_len]);
+        let response = route_admin_request(&method, &path, &buffer[head_len..head_len + content_length], peer, reloader, pac, ca_download).await;
+        if !persistent {
+            return write_response(&mut stream, &response, None).await;
+        }
+        write_response(&mut stream, &response, Some((keep_alive.idle_timeout_seconds, keep_alive.max_requests - served))).await?;
+        // 이어서 보낸(파이프라인) 요청은 남겨 두고 처리한 요청만 버림
+        buffer.drain(..head_len + content_length);
+    }
+}
+
+async fn route_admin_request(method: &str, path: &str, body: &[u8], peer: SocketAddr, reloader: &Reloader, pac: Option<&PacSource>, ca_download: bool) -> AdminResponse {
+    match (method, path) {
+        ("POST", "/reload") => match reloader.reload().await {
+            Ok(summary) => {
+                let mut body = serde_json::to_value(&summary).unwrap_or_else(|_| json!({}));
+                if let Value::Object(map) = &mut body {
+                    map.insert("status".to_string(), json!("ok"));
+                }
+                json_response("200 OK", &body, None)
+            },
+            Err(ReloadError::InProgress) => {
+                warn!("관리 엔드포인트: 재로드 진행 중이라 요청 거부");
+                json_response("409 Conflict", &json!({"status": "error", "error": ReloadError::InProgress.to_string()}), None)
+            },
+            Err(e) => {
+                error!("관리 엔드포인트: 설정 재로드 실패: {}", e);
+                json_response("500 Internal Server Error", &json!({"status": "error", "error": e.to_string()}), None)
+            },
+        },
+        (_, "/reload") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: POST"))
+        },
+        ("GET", "/version") => {
+            let body = serde_json::to_value(reloader.version_info().await).unwrap_or_else(|_| json!({}));
+            json_response("200 OK", &body, None)
+        },
+        (_, "/version") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET"))
+        },
+        (_, "/events") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET"))
+        },
+        ("GET", "/debug/log") => match recent_logs() {
+            Some(recent) => {
+                let body = serde_json::to_value(recent.snapshot()).unwrap_or_else(|_| json!({}));
+                json_response("200 OK", &body, None)
+            },
+            None => json_response("404 Not Found", &json!({"status": "error", "error": "debug log buffer disabled"}), None),
+        },
+        (_, "/debug/log") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET"))
+        },
+        ("GET", path) if let Some(host) = path.strip_prefix("/intercept/") => match intercept::parse_host(host) {
+            Ok(host) => {
+                let body = serde_json::to_value(intercept_state(&host)).unwrap_or_else(|_| json!({}));
+                json_response("200 OK", &body, None)
+            },
+            Err(e) => intercept_error_response(e),
+        },
+        ("POST", path) if let Some(host) = path.strip_prefix("/intercept/") => {
+            let result = match intercept::parse_host(host) {
+                Ok(host) => intercept::apply(&host, body, peer).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(response) => {
+                    let body = serde_json::to_value(&response).unwrap_or_else(|_| json!({}));
+                    json_response("200 OK", &body, None)
+                },
+                Err(e) => intercept_error_response(e),
+            }
+        },
+        ("DELETE", path) if let Some(host) = path.strip_prefix("/intercept/") => match intercept::parse_host(host) {
+            Ok(host) => {
+                let body = serde_json::to_value(intercept::clear(&host, peer)).unwrap_or_else(|_| json!({}));
+                json_response("200 OK", &body, None)
+            },
+            Err(e) => intercept_error_response(e),
+        },
+        (_, path) if path.starts_with("/intercept/") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET, POST, DELETE"))
+        },
+        ("GET", "/maintenance") => {
+            let body = serde_json::to_value(maintenance_state()).unwrap_or_else(|_| json!({}));
+            json_response("200 OK", &body, None)
+        },
+        ("POST", "/maintenance") => match maintenance::apply(body, peer) {
+            Ok(state) => {
+                let body = serde_json::to_value(&state).unwrap_or_else(|_| json!({}));
+                json_response("200 OK", &body, None)
+            },
+            Err(e) => json_response("400 Bad Request", &json!({"status": "error", "error": e}), None),
+        },
+        (_, "/maintenance") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET, POST"))
+        },
+        ("GET", "/circuits") => {
+            let circuits: Vec<Value> = Metrics::new().open_circuits().into_iter()
+                .map(|(host, state)| json!({"host": host, "state": state}))
+                .collect();
+            json_response("200 OK", &json!({"status": "ok", "circuits": circuits}), None)
+        },
+        (_, "/circuits") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET"))
+        },
+        ("GET", "/db/quarantine") => {
+            let partitions: Vec<Value> = quarantined_partitions().into_iter()
+                .map(|(name, entry)| json!({
+                    "partition": name,
+                    "table": entry.table,
+                    "reason": entry.reason,
+                    "since": entry.since.to_rfc3339(),
+                    "routed": entry.routed,
+                }))
+                .collect();
+            json_response("200 OK", &json!({"status": "ok", "partitions": partitions}), None)
+        },
+        (_, "/db/quarantine") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET"))
+        },
+        ("DELETE", path) if let Some(partition) = path.strip_prefix("/db/quarantine/") => {
+            if release_partition(partition) {
+                info!("관리 엔드포인트: 파티션 {} 격리 해제 (요청 {})", partition, peer);
+                json_response("200 OK", &json!({"status": "ok", "partition": partition}), None)
+            } else {
+                json_response("404 Not Found", &json!({"status": "error", "error": "partition not quarantined"}), None)
+            }
+        },
+        (_, path) if path.starts_with("/db/quarantine/") => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: DELETE"))
+        },
+        ("GET", "/proxy.pac") if let Some(pac) = pac => match pac.render() {
+            Ok(script) => AdminResponse { status: "200 OK", content_type: PAC_CONTENT_TYPE, body: script, extra_header: None },
+            Err(e) => {
+                error!("관리 엔드포인트: PAC 파일 읽기 실패: {}", e);
+                json_response("500 Internal Server Error", &json!({"status": "error", "error": "pac file unavailable"}), None)
+            },
+        },
+        (_, "/proxy.pac") if pac.is_some() => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET"))
+        },
+        ("GET", "/ca.crt") if ca_download => ca::certificate_response(),
+        (_, "/ca.crt") if ca_download => {
+            json_response("405 Method Not Allowed", &json!({"status": "error", "error": "method not allowed"}), Some("Allow: GET"))
+        },
+        _ => json_response("404 Not Found", &json!({"status": "error", "error": "not found"}), None),
+    }
+}
+
+/// 요청 헤더 끝(빈 줄)까지 읽기 (이미 받은 바이트부터 확인, 연결 종료 또는 최대 길이 초과 시 false)
+pub(super) async fn read_request_head<S: AsyncRead + Unpin>(stream: &mut S, buffer: &mut Vec<u8>) -> std::io::Result<bool> {
+    let mut chunk = [0u8; 1024];
+    loop {
+        if buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+            return Ok(true);
+        }
+        if buffer.len() > ADMIN_REQUEST_MAX_LEN {
+            return Ok(false);
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(false);
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+}
+
+/// 본문 끝(`total_len`)까지 읽기 (그 전에 연결이 끊기면 false)
+async fn read_request_body<S: AsyncRead + Unpin>(stream: &mut S, buffer: &mut Vec<u8>, total_len: usize) -> std::io::Result<bool> {
+    let mut chunk = [0u8; 1024];
+    while buffer.len() < total_len {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(false);
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+    Ok(true)
+}
+
+fn intercept_error_response(error: InterceptError) -> AdminResponse {
+    match error {
+        InterceptError::BadRequest(e) => {
+            json_response("400 Bad Request", &json!({"status": "error", "error": e}), None)
+        },
+        InterceptError::PersistFailed(e) => {
+            error!("관리 엔드포인트: 가로채기 지정 DB 반영 실패: {}", e);
+            json_response("500 Internal Server Error", &json!({"status": "error", "error": format!("applied in memory but not persisted: {}", e)}), None)
+        },
+    }
+}
+
+pub(super) fn json_response(status: &'static str, body: &Value, extra_header: Option<&'static str>) -> AdminResponse {
+    AdminResponse { status, content_type: "application/json", body: body.to_string(), extra_header: extra_header.map(str::to_string) }
+}
+
+/// 응답 쓰기: `keep_alive`(유휴 시간 초, 남은 요청 수)가 있으면 연결을 유지하고, 없으면 `Connection: close` 후 연결 종료
+pub(super) async fn write_response<S: AsyncWrite + Unpin>(stream: &mut S, response: &AdminResponse, keep_alive: Option<(u64, u32)>) -> std::io::Result<()> {
+    let extra_header = response.extra_header.as_ref().map(|header| format!("{}\r\n", header)).unwrap_or_default();
+    let connection = match keep_alive {
+        Some((timeout, remaining)) => format!("Connection: keep-alive\r\nKeep-Alive: timeout={}, max={}\r\n", timeout, remaining),
+        None => "Connection: close\r\n".to_string(),
+    };
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}{}\r\n",
+        response.status, response.content_type, response.body.len(), extra_header, connection
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    if keep_alive.is_some() {
+        stream.flush().await
+    } else {
+        stream.shutdown().await
+    }
+}
@@ -0,0 +1,122 @@
+// 브라우저 자동 프록시 설정(PAC) 파일 생성
+// 관리 엔드포인트와 프록시 리스너(프록시 대상이 아닌 `GET /proxy.pac` 요청) 양쪽에서 같은 내용을 응답합니다.
+
+use std::collections::BTreeSet;
+
+use log::error;
+
+use crate::config::{Config, PacConfig};
+use crate::tls::exemption::tls_exempt_hosts;
+
+/// PAC 응답 Content-Type
+pub const PAC_CONTENT_TYPE: &str = "application/x-ns-proxy-autoconfig";
+
+/// `GET /proxy.pac` 응답 내용 출처
+#[derive(Debug, Clone)]
+pub struct PacSource {
+    settings: PacConfig,
+    /// PAC에 넣을 프록시 주소 (host:port)
+    proxy: String,
+}
+
+impl PacSource {
+    /// pac.enabled인 경우에만 생성
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.pac.enabled {
+            return None;
+        }
+        let proxy = config.pac.proxy.clone()
+            .unwrap_or_else(|| crate::proxy::target::format_authority(&config.bind_host, config.bind_port));
+        Some(Self { settings: config.pac.clone(), proxy })
+    }
+
+    /// PAC 파일 내용 (파일 지정 시 그대로 읽고, 아니면 현재 DIRECT 목록으로 생성)
+    pub fn render(&self) -> std::io::Result<String> {
+        if let Some(path) = &self.settings.file {
+            return std::fs::read_to_string(path);
+        }
+
+        let mut direct: BTreeSet<String> = self.settings.direct_hosts.iter().map(|host| normalize(host)).collect();
+        if self.settings.direct_tls_exempt {
+            direct.extend(tls_exempt_hosts());
+        }
+        Ok(generate_pac(&self.proxy, &direct))
+    }
+}
+
+/// 프록시 리스너로 들어온 `GET /proxy.pac`(origin-form) 요청이면 보낼 HTTP 응답 전체 (pac.enabled가 아니면 None)
+///
+/// `http://host/proxy.pac`처럼 대상 URL을 담은 프록시 요청은 해당 없으므로 평소대로 업스트림에 전달됩니다.
+pub fn proxy_listener_response(config: &Config, request: &str) -> Option<String> {
+    let mut parts = request.lines().next()?.split_whitespace();
+    if parts.next()? != "GET" || parts.next()?.split('?').next()? != "/proxy.pac" {
+        return None;
+    }
+    let pac = PacSource::from_config(config)?;
+    Some(match pac.render() {
+        Ok(script) => format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n{}",
+            PAC_CONTENT_TYPE, script.len(), script,
+        ),
+        Err(e) => {
+            error!("프록시 리스너: PAC 파일 읽기 실패: {}", e);
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+        },
+    })
+}
+
+/// DIRECT 호스트 목록과 프록시 주소로 PAC 스크립트 생성
+///
+/// `*.example.com` 항목은 TLS 가로채기 제외 목록과 같이 하위 도메인에만 일치합니다.
+fn generate_pac(proxy: &str, direct: &BTreeSet<String>) -> String {
+    let (suffixes, exact): (Vec<&String>, Vec<&String>) = direct.iter().partition(|host| host.starts_with("*."));
+    let quote = |value: &str| serde_json::Value::from(value).to_string();
+    let exact: Vec<String> = exact.iter().map(|host| quote(host)).collect();
+    let suffixes: Vec<String> = suffixes.iter().map(|host| quote(&host[1..])).collect();
+
+    format!(
+        "// udss-proxy에서 생성한 PAC 파일\n\
+         var directHosts = [{}];\n\
+         var directSuffixes = [{}];\n\
+         \n\
+         function FindProxyForURL(url, host) {{\n\
+         \x20   host = host.toLowerCase();\n\
+         \x20   for (var i = 0; i < directHosts.length; i++) {{\n\
+         \x20       if (host === directHosts[i]) return \"DIRECT\";\n\
+         \x20   }}\n\
+         \x20   for (var j = 0; j < directSuffixes.length; j++) {{\n\
+         \x20       if (dnsDomainIs(host, directSuffixes[j])) return \"DIRECT\";\n\
+         \x20   }}\n\
+         \x20   return {};\n\
+         }}\n",
+        exact.join(", "),
+        suffixes.join(", "),
+        quote(&format!("PROXY {}", proxy)),
+    )
+}
+
+fn normalize(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxy_listener_answers_only_origin_form_pac_requests() {
+        let mut config = Config::new();
+        config.pac.enabled = true;
+        config.pac.proxy = Some("proxy.example.com:50000".to_string());
+
+        let response = proxy_listener_response(&config, "GET /proxy.pac HTTP/1.1\r\nHost: proxy.example.com\r\n\r\n").unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(PAC_CONTENT_TYPE));
+        assert!(response.contains("PROXY proxy.example.com:50000"));
+
+        assert!(proxy_listener_response(&config, "GET http://example.com/proxy.pac HTTP/1.1\r\n\r\n").is_none());
+        assert!(proxy_listener_response(&config, "POST /proxy.pac HTTP/1.1\r\n\r\n").is_none());
+        config.pac.enabled = false;
+        assert!(proxy_listener_response(&config, "GET /proxy.pac HTTP/1.1\r\n\r\n").is_none());
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt;
+use std::sync::Arc;
+
+use log::info;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::acl::domain_blocker::DomainBlocker;
+use crate::config::{AclMode, Config};
+use crate::error::Result;
+use crate::metrics::{AclRuleKind, Metrics};
+use crate::server::source_filter::set_source_filter;
+use crate::tls::{load_trusted_certificates, set_trusted_certificates};
+
+use super::version::VersionInfo;
+
+/// 실행 중에 다시 적용할 수 있는 설정 항목 (그 외 항목은 재시작해야 반영됨)
+const RELOADABLE_KEYS: [&str; 10] = [
+    "blocked_domains", "blocked_patterns", "blocked_filter_lists", "blocked_rule_expiry", "acl_mode", "acl_actions", "acl_failure_policy",
+    "acl_conflict_precedence", "trusted_certificates", "source_filter",
+];
+
+/// 설정 파일 로드 함수
+pub type ConfigLoader = fn() -> Result<Config>;
+
+/// 재로드 전후 값
+#[derive(Debug, Serialize)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// 종류별 차단 규칙 수
+#[derive(Debug, Serialize)]
+pub struct RuleCounts {
+    pub exact: u64,
+    pub wildcard: u64,
+    pub regex: u64,
+    pub filter: u64,
+}
+
+/// 재로드 결과 요약
+#[derive(Debug, Serialize)]
+pub struct ReloadSummary {
+    pub block_rules: Change<RuleCounts>,
+    pub acl_mode: Change<AclMode>,
+    pub trusted_certificates: Change<usize>,
+    /// 값이 바뀌어 적용한 항목
+    pub applied: Vec<String>,
+    /// 값이 바뀌었지만 실행 중에는 바꿀 수 없어 건너뛴 항목
+    pub skipped: Vec<String>,
+}
+
+/// 재로드 실패 원인
+#[derive(Debug)]
+pub enum ReloadError {
+    /// 다른 재로드가 진행 중
+    InProgress,
+    /// 설정 로드/검증 또는 적용 실패
+    Failed(String),
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReloadError::InProgress => write!(f, "reload already in progress"),
+            ReloadError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// 설정 재로드 (SIGHUP과 관리 엔드포인트가 같은 경로를 사용)
+///
+/// 차단 목록, 차단 정책과 일치 시 동작, 판단 불가 시 처리, 신뢰할 인증서, 출발지 IP 필터만 다시 적용하고, 바인드 주소나 워커 수처럼
+/// 실행 중에 바꿀 수 없는 항목은 변경되었더라도 건너뛴 항목으로 보고합니다.
+pub struct Reloader {
+    load: ConfigLoader,
+    domain_blocker: Arc<DomainBlocker>,
+    metrics: Arc<Metrics>,
+    // 마지막으로 적용한 설정 (잠금으로 재로드를 한 번에 하나만 허용)
+    applied: Mutex<Config>,
+}
+
+impl Reloader {
+    pub fn new(config: &Config, load: ConfigLoader, domain_blocker: Arc<DomainBlocker>) -> Self {
+        Self {
+            load,
+            domain_blocker,
+            metrics: Metrics::new(),
+            applied: Mutex::new(config.clone()),
+        }
+    }
+
+    /// 설정 파일을 다시 읽어 재로드 가능한 항목 적용 (진행 중인 재로드가 있으면 즉시 실패)
+    pub async fn reload(&self) -> std::result::Result<ReloadSummary, ReloadError> {
+        let mut applied = self.applied.try_lock().map_err(|_| ReloadError::InProgress)?;
+
+        let mut config = (self.load)()
+            .map_err(|e| ReloadError::Failed(format!("config load failed: {}", e)))?;
+        config.validate()
+            .map_err(|e| ReloadError::Failed(format!("config validation failed: {}", e)))?;
+        load_trusted_certificates(&mut config)
+            .map_err(|e| ReloadError::Failed(format!("trusted certificate load failed: {}", e)))?;
+
+        let (changed, skipped) = changed_keys(&applied, &config);
+        let rules_before = self.rule_counts();
+        let acl_mode_before = self.domain_blocker.acl_mode();
+        let certs_before = applied.trusted_certificates.len();
+
+        self.domain_blocker.reload(&config).await
+            .map_err(|e| ReloadError::Failed(format!("block list reload failed: {}", e)))?;
+        set_trusted_certificates(config.trusted_certificates.clone());
+        set_source_filter(&config.source_filter);
+
+        // 건너뛴 항목은 다음 재로드에서도 계속 보고되도록 적용한 항목만 반영
+        applied.blocked_domains = config.blocked_domains.clone();
+        applied.blocked_patterns = config.blocked_patterns.clone();
+        applied.blocked_filter_lists = config.blocked_filter_lists.clone();
+        applied.blocked_rule_expiry = config.blocked_rule_expiry.clone();
+        applied.acl_mode = config.acl_mode;
+        applied.acl_actions = config.acl_actions.clone();
+        applied.acl_failure_policy = config.acl_failure_policy;
+        applied.acl_conflict_precedence = config.acl_conflict_precedence;
+        applied.trusted_certificates = config.trusted_certificates.clone();
+        applied.source_filter = config.source_filter.clone();
+
+        let summary = ReloadSummary {
+            block_rules: Change { before: rules_before, after: self.rule_counts() },
+            acl_mode: Change { before: acl_mode_before, after: config.acl_mode },
+            trusted_certificates: Change { before: certs_before, after: config.trusted_certificates.len() },
+            applied: changed,
+            skipped,
+        };
+        info!("설정 재로드 완료: 적용 {:?}, 건너뜀(재시작 필요) {:?}, 설정 sha256 {}",
+              summary.applied, summary.skipped, applied.content_hash());
+        Ok(summary)
+    }
+
+    /// 실행 중인 빌드와 마지막으로 적용한 설정의 버전 정보 (재로드 중이면 끝날 때까지 대기)
+    pub async fn version_info(&self) -> VersionInfo {
+        VersionInfo::new(&*self.applied.lock().await)
+    }
+
+    fn rule_counts(&self) -> RuleCounts {
+        let rules = self.metrics.acl_stats().rules;
+        RuleCounts {
+            exact: rules[AclRuleKind::Exact as usize],
+            wildcard: rules[AclRuleKind::Wildcard as usize],
+            regex: rules[AclRuleKind::Regex as usize],
+            filter: rules[AclRuleKind::Filter as usize],
+        }
+    }
+}
+
+/// 값이 바뀐 최상위 설정 항목을 (재로드 가능, 재로드 불가)로 나눠 반환
+fn changed_keys(old: &Config, new: &Config) -> (Vec<String>, Vec<String>) {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return (Vec::new(), Vec::new());
+    };
+
+    let mut changed: Vec<&String> = new.iter()
+        .filter(|(key, value)| old.get(key.as_str()).map(canonical) != Some(canonical(value)))
+        .map(|(key, _)| key)
+        .collect();
+    changed.sort();
+
+    let (reloadable, skipped): (Vec<&String>, Vec<&String>) = changed.into_iter()
+        .partition(|key| RELOADABLE_KEYS.contains(&key.as_str()));
+    (reloadable.into_iter().cloned().collect(), skipped.into_iter().cloned().collect())
+}
+
+/// HashSet 항목은 직렬화 순서가 매번 달라지므로 배열을 정렬해 비교
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Array(items) => {
+            let mut items: Vec<Value> = items.iter().map(canonical).collect();
+            items.sort_by_key(|item| item.to_string());
+            Value::Array(items)
+        },
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), canonical(v))).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_change_is_reported() {
+        let old = Config::new();
+        let mut new = old.clone();
+        new.pkcs12.passphrase = Some("hunter2".to_string());
+        assert_eq!(changed_keys(&old, &new), (Vec::new(), vec!["pkcs12".to_string()]));
+    }
+}
@@ -0,0 +1,127 @@
+// 관리 엔드포인트 TLS와 클라이언트 인증서 고정 (admin_tls)
+// 서버 인증서로 TLS를 종료하고, 클라이언트 인증서의 SHA-256 지문이 설정 목록에 있을 때만 핸드셰이크를 마칩니다.
+// 발급 CA나 유효 기간은 보지 않고 지문만 비교하므로 목록에 없는 인증서는 같은 CA가 발급했어도 거부됩니다.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use log::warn;
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::crypto::{WebPkiSupportedAlgorithms, verify_tls12_signature, verify_tls13_signature};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::{CertificateError, DigitallySignedStruct, DistinguishedName, ServerConfig, SignatureScheme};
+use sha2::{Digest, Sha256};
+use tokio_rustls::TlsAcceptor;
+
+use crate::config::AdminTlsConfig;
+use crate::error::{Result, tls_err};
+use crate::tls::pkcs12::load_pkcs12;
+use crate::tls::read_certificate_file;
+
+/// SHA-256 지문 (32바이트)
+type Fingerprint = [u8; 32];
+
+/// 16진수 SHA-256 지문 해석 (`:` 구분 허용, 대소문자 무시)
+pub fn parse_fingerprint(value: &str) -> std::result::Result<Fingerprint, String> {
+    let hex: String = value.trim().chars().filter(|c| *c != ':').collect();
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(format!("SHA-256 지문은 16진수 64자여야 합니다 (현재 {}자)", hex.len()));
+    }
+    let mut fingerprint = [0u8; 32];
+    for (index, byte) in fingerprint.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16)
+            .map_err(|_| format!("16진수가 아닌 문자가 있습니다: {:?}", &hex[index * 2..index * 2 + 2]))?;
+    }
+    Ok(fingerprint)
+}
+
+/// 인증서 DER의 SHA-256 지문 (`:` 구분 대문자, `openssl x509 -fingerprint -sha256` 형식)
+pub fn certificate_fingerprint(cert: &CertificateDer<'_>) -> String {
+    Sha256::digest(cert.as_ref()).iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(":")
+}
+
+/// 지문 목록에 있는 클라이언트 인증서만 받는 검증기 (서명 검증은 rustls 기본 알고리즘 사용)
+#[derive(Debug)]
+struct PinnedClientVerifier {
+    fingerprints: HashSet<Fingerprint>,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ClientCertVerifier for PinnedClientVerifier {
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _now: UnixTime,
+    ) -> std::result::Result<ClientCertVerified, rustls::Error> {
+        let fingerprint: Fingerprint = Sha256::digest(end_entity.as_ref()).into();
+        if self.fingerprints.contains(&fingerprint) {
+            return Ok(ClientCertVerified::assertion());
+        }
+        warn!("관리 연결 거부 (고정되지 않은 클라이언트 인증서): {}", certificate_fingerprint(end_entity));
+        Err(rustls::Error::InvalidCertificate(CertificateError::ApplicationVerificationFailure))
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+/// 서버 인증서 체인과 개인 키 로드 (PEM 인증서 + PEM 키 또는 PKCS#12 번들)
+fn load_server_identity(config: &AdminTlsConfig) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)> {
+    if config.is_pkcs12() {
+        let bundle = load_pkcs12(&config.cert_file)
+            .map_err(|e| tls_err(format!("admin_tls 번들 로드 실패 ({}): {}", config.cert_file, e)))?;
+        let key = bundle.key.ok_or_else(|| tls_err(format!("admin_tls 번들에 개인 키가 없습니다: {}", config.cert_file)))?;
+        return Ok((bundle.certs, PrivateKeyDer::Pkcs8(key)));
+    }
+
+    let certs = read_certificate_file(&config.cert_file)
+        .map_err(|e| tls_err(format!("admin_tls.cert_file 로드 실패 ({}): {}", config.cert_file, e)))?;
+    let key_pem = std::fs::read(&config.key_file)
+        .map_err(|e| tls_err(format!("admin_tls.key_file을 읽을 수 없습니다 ({}): {}", config.key_file, e)))?;
+    let key = rustls_pemfile::private_key(&mut key_pem.as_slice())
+        .map_err(|e| tls_err(format!("admin_tls.key_file 형식 오류 ({}): {}", config.key_file, e)))?
+        .ok_or_else(|| tls_err(format!("admin_tls.key_file에 개인 키가 없습니다: {}", config.key_file)))?;
+    Ok((certs, key))
+}
+
+/// 관리 엔드포인트용 TLS 수락기 생성 (클라이언트 인증서 필수)
+pub fn build_admin_acceptor(config: &AdminTlsConfig) -> Result<TlsAcceptor> {
+    let fingerprints = config.client_cert_fingerprints.iter()
+        .map(|fingerprint| parse_fingerprint(fingerprint).map_err(tls_err))
+        .collect::<Result<HashSet<_>>>()?;
+    let verifier = PinnedClientVerifier {
+        fingerprints,
+        algorithms: rustls::crypto::aws_lc_rs::default_provider().signature_verification_algorithms,
+    };
+    let (certs, key) = load_server_identity(config)?;
+    let server_config = ServerConfig::builder()
+        .with_client_cert_verifier(Arc::new(verifier))
+        .with_single_cert(certs, key)
+        .map_err(|e| tls_err(format!("admin_tls 서버 인증서와 키가 맞지 않습니다: {}", e)))?;
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
@@ -0,0 +1,45 @@
+// 빌드/설정 버전 정보 (관리 엔드포인트 GET /version과 시작 로그)
+
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// 패키지 버전
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 빌드한 git 커밋 (git 없이 빌드했으면 None)
+pub const GIT_COMMIT: Option<&str> = option_env!("UDSS_GIT_COMMIT");
+
+/// 빌드 프로필 (debug, release)
+pub const BUILD_PROFILE: &str = match option_env!("UDSS_BUILD_PROFILE") {
+    Some(profile) => profile,
+    None => "unknown",
+};
+
+/// 실행 중인 빌드와 적용된 설정 식별 정보
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    pub git_commit: Option<&'static str>,
+    pub build_profile: &'static str,
+    /// 적용된 설정의 SHA-256 해시
+    pub config_hash: String,
+}
+
+impl VersionInfo {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            version: VERSION,
+            git_commit: GIT_COMMIT,
+            build_profile: BUILD_PROFILE,
+            config_hash: config.content_hash(),
+        }
+    }
+}
+
+impl std::fmt::Display for VersionInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "udss-proxy {} (commit {}, {} build), config sha256 {}",
+               self.version, self.git_commit.unwrap_or("unknown"), self.build_profile, self.config_hash)
+    }
+}
@@ -2,7 +2,8 @@ use std::sync::RwLock;
 use std::time::{Instant, Duration};
 use std::sync::Arc;
 
-use bytes::{BytesMut};
+use bytes::{BufMut, BytesMut};
+use bytes::buf::Limit;
 use log::{info, debug, warn};
 use tokio::sync::{mpsc, oneshot};
 use tokio::task;
@@ -38,6 +39,15 @@ impl BufferSize {
             BufferSize::Large => BUFFER_SIZE_LARGE,
         }
     }
+    
+    /// 승격 시 다음 버퍼 크기 (대형은 승격 불가)
+    fn next(&self) -> Option<Self> {
+        match self {
+            BufferSize::Small => Some(BufferSize::Medium),
+            BufferSize::Medium => Some(BufferSize::Large),
+            BufferSize::Large => None,
+        }
+    }
 }
 
 /// 버퍼 풀 통계 정보
@@ -46,6 +56,7 @@ struct BufferStats {
     allocations: usize,
     reuses: usize,
     returns: usize,
+    promotions: usize,
     last_metrics_time: Instant,
     last_adjustment_time: Instant,
     small_pool_size: usize,
@@ -63,6 +74,7 @@ impl BufferStats {
             allocations: 0,
             reuses: 0,
             returns: 0,
+            promotions: 0,
             last_metrics_time: Instant::now(),
             last_adjustment_time: Instant::now(),
             small_pool_size,
@@ -89,6 +101,11 @@ impl BufferStats {
         self.returns += 1;
     }
     
+    /// 승격 카운트 증가
+    fn increment_promotions(&mut self) {
+        self.promotions += 1;
+    }
+    
     /// 통계 출력이 필요한지 확인
     fn should_print_metrics(&self) -> bool {
         Instant::now().duration_since(self.last_metrics_time).as_secs() > BUFFER_STATS_INTERVAL_SECS
@@ -119,10 +136,11 @@ impl BufferStats {
     /// 통계 출력
     fn print_metrics(&self) {
         info!(
-            "Buffer stats - Allocations: {}, Reuses: {}, Returns: {}",
+            "Buffer stats - Allocations: {}, Reuses: {}, Returns: {}, Promotions: {}",
             self.allocations,
             self.reuses,
-            self.returns
+            self.returns,
+            self.promotions
         );
         
         info!(
@@ -418,6 +436,12 @@ impl BufferPool {
         buffer
     }
     
+    /// 버퍼 재사용 통계 (풀에서 재사용한 수, 새로 할당한 수)
+    pub fn reuse_stats(&self) -> (usize, usize) {
+        let stats = self.stats.read().unwrap();
+        (stats.reuses, stats.allocations)
+    }
+    
     /// 메트릭스 업데이트 및 출력
     fn update_metrics(&self) {
         let mut stats = self.stats.write().unwrap();
@@ -466,6 +490,101 @@ impl BufferPool {
     }
 }
 
+/// 한 번의 읽기로 채울 버퍼 영역 (read_chunk_size가 없으면 버퍼 용량 전체)
+///
+/// 버퍼 크기 클래스와 별개로 읽기 단위를 줄여 시스템 콜 횟수와 지연 시간을 조절합니다.
+pub fn read_chunk(buffer: &mut BytesMut, read_chunk_size: Option<usize>) -> Limit<&mut BytesMut> {
+    buffer.limit(read_chunk_size.unwrap_or(usize::MAX))
+}
+
+/// 세션 펌프용 버퍼
+///
+/// 소형 버퍼로 시작하여 일정 시간 동안의 처리량이 현재 버퍼 용량 대비 충분히 클 때만
+/// 중형/대형으로 승격합니다. 승격 시 기존 버퍼는 풀로 반환되고, 세션 종료 시(drop)
+/// 사용 중인 버퍼도 풀로 반환되어 메모리 사용량이 실제 트래픽에 비례하도록 유지됩니다.
+pub struct SessionBuffer {
+    pool: Option<Arc<BufferPool>>,
+    buffer: BytesMut,
+    size: BufferSize,
+    window_start: Instant,
+    window_bytes: usize,
+}
+
+impl SessionBuffer {
+    /// 소형 버퍼로 시작하는 세션 버퍼 생성
+    pub fn new(pool: Option<Arc<BufferPool>>) -> Self {
+        let size = BufferSize::Small;
+        let buffer = Self::acquire(&pool, size);
+        
+        Self {
+            pool,
+            buffer,
+            size,
+            window_start: Instant::now(),
+            window_bytes: 0,
+        }
+    }
+    
+    /// 풀(없으면 새 할당)에서 지정 크기의 버퍼 가져오기
+    fn acquire(pool: &Option<Arc<BufferPool>>, size: BufferSize) -> BytesMut {
+        match pool {
+            Some(pool) => pool.get_buffer_by_size(size),
+            None => BytesMut::with_capacity(size.capacity()),
+        }
+    }
+    
+    /// 읽기에 사용할 버퍼 참조
+    pub fn buffer_mut(&mut self) -> &mut BytesMut {
+        &mut self.buffer
+    }
+    
+    /// 읽은 바이트 수를 기록하고 처리량이 지속되면 상위 클래스로 승격
+    ///
+    /// 버퍼 내용이 교체될 수 있으므로 읽은 데이터를 모두 사용한 뒤에 호출해야 합니다.
+    pub fn record_read(&mut self, n: usize) {
+        self.window_bytes += n;
+        
+        if self.window_start.elapsed() < Duration::from_millis(BUFFER_PROMOTION_WINDOW_MS) {
+            return;
+        }
+        
+        let sustained = self.window_bytes >= self.size.capacity() * BUFFER_PROMOTION_FACTOR;
+        self.window_start = Instant::now();
+        self.window_bytes = 0;
+        
+        if sustained {
+            self.promote();
+        }
+    }
+    
+    /// 다음 크기 클래스로 승격 (기존 버퍼는 풀로 반환)
+    fn promote(&mut self) {
+        let next_size = match self.size.next() {
+            Some(size) => size,
+            None => return,
+        };
+        
+        let new_buffer = Self::acquire(&self.pool, next_size);
+        let old_buffer = std::mem::replace(&mut self.buffer, new_buffer);
+        
+        if let Some(pool) = &self.pool {
+            pool.return_buffer(old_buffer);
+            pool.stats.write().unwrap().increment_promotions();
+        }
+        
+        debug!("세션 버퍼 승격: {:?} -> {:?}", self.size, next_size);
+        self.size = next_size;
+    }
+}
+
+impl Drop for SessionBuffer {
+    fn drop(&mut self) {
+        if let Some(pool) = &self.pool {
+            pool.return_buffer(std::mem::take(&mut self.buffer));
+        }
+    }
+}
+
 impl Clone for BufferPool {
     fn clone(&self) -> Self {
         let stats = self.stats.read().unwrap().clone();
@@ -0,0 +1,153 @@
+// 환경 변수로 설정 필드 덮어쓰기
+// 변수 이름은 Config 구조체를 직렬화한 키 경로에서 자동으로 만들어지므로 (UDSS_ + 대문자 경로,
+// 중첩 필드는 `__`로 연결) 필드를 추가하거나 이름을 바꾸면 매핑도 함께 바뀝니다.
+// 현재 매핑은 `--list-env`로 확인할 수 있습니다.
+
+use serde_yaml::{Mapping, Value};
+
+use super::Config;
+
+/// 덮어쓰기 환경 변수 접두사
+pub const ENV_OVERRIDE_PREFIX: &str = "UDSS_";
+
+/// 중첩 필드 경로 구분자 (예: UDSS_GRACEFUL_SHUTDOWN__GRACE_SECONDS)
+const ENV_PATH_SEPARATOR: &str = "__";
+
+/// 덮어쓸 수 있는 스칼라 필드
+#[derive(Debug, Clone)]
+pub struct EnvOverrideField {
+    /// 환경 변수 이름
+    pub var: String,
+    /// 설정 키 경로
+    pub path: Vec<String>,
+    /// 기본값으로 판단한 값 형식
+    pub kind: EnvValueKind,
+}
+
+/// 덮어쓰기 값 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvValueKind {
+    Bool,
+    Integer,
+    Float,
+    String,
+    /// 기본값이 없는(Option) 필드
+    Optional,
+}
+
+impl EnvValueKind {
+    /// 형식 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnvValueKind::Bool => "bool",
+            EnvValueKind::Integer => "integer",
+            EnvValueKind::Float => "float",
+            EnvValueKind::String => "string",
+            EnvValueKind::Optional => "optional",
+        }
+    }
+}
+
+/// 기본 설정을 직렬화해 덮어쓸 수 있는 스칼라 필드 목록 생성 (목록/맵 필드는 제외)
+pub fn env_override_fields() -> Vec<EnvOverrideField> {
+    let mut fields = Vec::new();
+    if let Ok(schema) = serde_yaml::to_value(Config::new()) {
+        collect_fields(&schema, &mut Vec::new(), &mut fields);
+    }
+    fields
+}
+
+fn collect_fields(value: &Value, path: &mut Vec<String>, fields: &mut Vec<EnvOverrideField>) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, child) in mapping {
+                if let Some(key) = key.as_str() {
+                    path.push(key.to_string());
+                    collect_fields(child, path, fields);
+                    path.pop();
+                }
+            }
+        }
+        Value::Sequence(_) | Value::Tagged(_) => {}
+        scalar => {
+            if path.is_empty() {
+                return;
+            }
+            let var = format!(
+                "{}{}",
+                ENV_OVERRIDE_PREFIX,
+                path.iter().map(|key| key.to_ascii_uppercase()).collect::<Vec<_>>().join(ENV_PATH_SEPARATOR)
+            );
+            fields.push(EnvOverrideField { var, path: path.clone(), kind: scalar_kind(scalar) });
+        }
+    }
+}
+
+/// 기본값으로 판단한 필드 형식
+fn scalar_kind(value: &Value) -> EnvValueKind {
+    match value {
+        Value::Bool(_) => EnvValueKind::Bool,
+        Value::Number(number) if number.is_f64() => EnvValueKind::Float,
+        Value::Number(_) => EnvValueKind::Integer,
+        Value::String(_) => EnvValueKind::String,
+        _ => EnvValueKind::Optional,
+    }
+}
+
+/// 환경 변수 값을 필드 형식에 맞게 해석
+fn parse_env_value(field: &EnvOverrideField, raw: &str) -> Result<Value, String> {
+    let invalid = |expected: &str| format!("{}={:?}: {} 값이어야 합니다", field.var, raw, expected);
+    let trimmed = raw.trim();
+    match field.kind {
+        EnvValueKind::Bool => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "1" => Ok(Value::Bool(true)),
+            "false" | "0" => Ok(Value::Bool(false)),
+            _ => Err(invalid("true/false")),
+        },
+        EnvValueKind::Integer => trimmed.parse::<i64>().map(Value::from)
+            .or_else(|_| trimmed.parse::<u64>().map(Value::from))
+            .map_err(|_| invalid("정수")),
+        EnvValueKind::Float => trimmed.parse::<f64>().map(Value::from).map_err(|_| invalid("실수")),
+        EnvValueKind::String => Ok(Value::String(raw.to_string())),
+        // 기본값이 없는(Option) 필드는 YAML 스칼라로 해석하고 형식은 설정 해석 단계에서 확인
+        EnvValueKind::Optional => match serde_yaml::from_str::<Value>(raw) {
+            Ok(value @ (Value::Bool(_) | Value::Number(_) | Value::String(_) | Value::Null)) => Ok(value),
+            _ => Err(invalid("스칼라")),
+        },
+    }
+}
+
+/// 읽어 들인 설정 값에 환경 변수 덮어쓰기를 적용하고 적용한 변수 이름 목록 반환
+pub(crate) fn apply_env_overrides(raw: &mut Value) -> Result<Vec<String>, String> {
+    let mut applied = Vec::new();
+    for field in env_override_fields() {
+        let Ok(value) = std::env::var(&field.var) else {
+            continue;
+        };
+        let value = parse_env_value(&field, &value)?;
+        set_path(raw, &field.path, value)
+            .map_err(|e| format!("{}: {}", field.var, e))?;
+        applied.push(field.var);
+    }
+    Ok(applied)
+}
+
+/// 키 경로에 값 설정 (중간 맵이 없으면 만듦)
+fn set_path(root: &mut Value, path: &[String], value: Value) -> Result<(), String> {
+    let mut current = root;
+    for (index, key) in path.iter().enumerate() {
+        if current.is_null() {
+            *current = Value::Mapping(Mapping::new());
+        }
+        let Value::Mapping(mapping) = current else {
+            return Err(format!("{}가 맵이 아닙니다", path[..index].join(".")));
+        };
+        let key = Value::String(key.clone());
+        if index + 1 == path.len() {
+            mapping.insert(key, value);
+            return Ok(());
+        }
+        current = mapping.entry(key).or_insert(Value::Null);
+    }
+    Ok(())
+}
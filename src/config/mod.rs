@@ -1,13 +1,46 @@
 use std::error::Error;
 use std::fs::File;
 use std::io::Read;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
 use regex::Regex;
 use lazy_static::lazy_static;
-use std::sync::RwLock;
-use log::{debug, error};
+use std::sync::{OnceLock, RwLock};
+use log::{debug, error, info};
+
+use crate::logging::webhook::WebhookEventKind;
+use crate::constants::{BUFFER_SIZE_LARGE, BUFFER_SIZE_MEDIUM, BUFFER_SIZE_SMALL};
+
+pub mod env;
+
+/// 설정 출력 시 민감한 값을 대체하는 문자열
+pub const REDACTED: &str = "***";
+
+/// 설정 파일 경로 대신 쓰면 표준 입력에서 설정 전체를 읽는 값
+pub const STDIN_CONFIG_PATH: &str = "-";
+
+// 표준 입력에서 읽은 설정 원문 (한 번만 읽을 수 있으므로 재로드 시 이 내용을 다시 해석)
+static STDIN_CONFIG: OnceLock<String> = OnceLock::new();
+
+/// 설정 원문 형식 (`--config-format`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConfigFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// 명령줄/로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Json => "json",
+        }
+    }
+}
 
 // 정규표현식 캐시
 lazy_static! {
@@ -20,22 +53,1982 @@ pub struct Config {
     pub bind_host: String,
     pub bind_port: u16,
     pub buffer_size: usize,
+    /// 세션 펌프의 1회 읽기 최대 바이트 (None이면 버퍼 용량 전체, 최대 `MAX_READ_CHUNK_SIZE`)
+    #[serde(default)]
+    pub read_chunk_size: Option<usize>,
     pub timeout_ms: usize,
     pub ssl_dir: String,
     pub worker_threads: Option<usize>,
     pub tls_verify_certificate: bool,
     #[serde(default = "default_disable_verify_internal_ip")]
     pub disable_verify_internal_ip: bool,
+    #[serde(default)]
+    pub upstream_cert_tiers: Vec<UpstreamCertTierRule>,
     pub blocked_domains: HashSet<String>,
     pub blocked_patterns: HashSet<String>,
     #[serde(default)]
-    pub trusted_certificates: Vec<String>,
-    #[serde(default = "default_cache_enabled")]
-    pub cache_enabled: bool,
-    #[serde(default = "default_cache_size")]
-    pub cache_size: usize,
-    #[serde(default = "default_cache_ttl_seconds")]
-    pub cache_ttl_seconds: u64,
+    pub blocked_filter_lists: Vec<String>,
+    /// blocked_domains/blocked_patterns 항목별 만료 시각 (지나면 규칙을 무시하고 다음 로드 때 제거)
+    #[serde(default)]
+    pub blocked_rule_expiry: BTreeMap<String, DateTime<Utc>>,
+    #[serde(default)]
+    pub trusted_certificates: Vec<String>,
+    #[serde(default = "default_cache_enabled")]
+    pub cache_enabled: bool,
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+    #[serde(default = "default_cache_ttl_seconds")]
+    pub cache_ttl_seconds: u64,
+    #[serde(default = "default_listen_backlog")]
+    pub listen_backlog: u32,
+    #[serde(default = "default_accept_batch_size")]
+    pub accept_batch_size: usize,
+    /// 같은 주소에 SO_REUSEPORT 리스너를 여러 개 열어 커널이 연결 수락을 분산 (Linux 전용)
+    #[serde(default)]
+    pub reuseport_enabled: bool,
+    /// SO_REUSEPORT 리스너 수 (0 - 워커 수와 같게)
+    #[serde(default)]
+    pub reuseport_listeners: usize,
+    /// bind_host:bind_port 외에 추가로 열 리스너 (리스너마다 가로채기/CONNECT 접근 제어 정책 지정)
+    #[serde(default)]
+    pub listeners: Vec<ListenerConfig>,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default)]
+    pub sni_overrides: HashMap<String, String>,
+    /// 업스트림 이름 해석 전에 확인하는 호스트 이름별 고정 주소 (DNS 대신 사용)
+    #[serde(default)]
+    pub static_hosts: HashMap<String, Vec<std::net::IpAddr>>,
+    #[serde(default)]
+    pub dns_prewarm: DnsPrewarmConfig,
+    #[serde(default)]
+    pub upstream_pool_enabled: bool,
+    #[serde(default = "default_upstream_pool_max_idle_per_host")]
+    pub upstream_pool_max_idle_per_host: usize,
+    #[serde(default = "default_upstream_pool_idle_timeout_seconds")]
+    pub upstream_pool_idle_timeout_seconds: u64,
+    #[serde(default)]
+    pub leaf_cert: LeafCertTemplate,
+    #[serde(default)]
+    pub root_ca: RootCaConfig,
+    #[serde(default)]
+    pub pkcs12: Pkcs12Config,
+    #[serde(default)]
+    pub tls_resumption: TlsResumptionConfig,
+    #[serde(default)]
+    pub wildcard_certs: WildcardCertConfig,
+    #[serde(default)]
+    pub cert_cache_alert: CertCacheAlertConfig,
+    #[serde(default)]
+    pub warm_pool: WarmPoolConfig,
+    #[serde(default)]
+    pub no_sni_action: NoSniAction,
+    #[serde(default)]
+    pub client_hello_peek: ClientHelloPeekConfig,
+    /// 가로챈 클라이언트의 ClientHello 암호군/키 교환 그룹 순서와 TLS 버전을 업스트림 핸드셰이크에 따라 함
+    #[serde(default)]
+    pub client_hello_mirroring: bool,
+    #[serde(default)]
+    pub connect_pipelining: ConnectPipelinePolicy,
+    #[serde(default)]
+    pub tls_exempt_hosts: HashSet<String>,
+    #[serde(default = "default_tls_exemption_refresh_seconds")]
+    pub tls_exemption_refresh_seconds: u64,
+    #[serde(default)]
+    pub socks_enabled: bool,
+    #[serde(default)]
+    pub egress_header: Option<String>,
+    #[serde(default)]
+    pub egress_pools: HashMap<String, EgressPool>,
+    #[serde(default)]
+    pub parent_proxies: ParentProxiesConfig,
+    #[serde(default)]
+    pub proxy_headers: ProxyHeadersConfig,
+    #[serde(default)]
+    pub security_headers: SecurityHeadersConfig,
+    #[serde(default)]
+    pub acl_mode: AclMode,
+    #[serde(default)]
+    pub acl_actions: AclActionsConfig,
+    #[serde(default)]
+    pub acl_failure_policy: AclFailurePolicy,
+    #[serde(default)]
+    pub acl_conflict_precedence: AclConflictPrecedence,
+    #[serde(default)]
+    pub connect_acl: ConnectAclConfig,
+    /// 리스너가 이름으로 참조하는 CONNECT 접근 제어 정책
+    #[serde(default)]
+    pub connect_acl_policies: HashMap<String, ConnectAclConfig>,
+    #[serde(default)]
+    pub max_session_duration_seconds: Option<u64>,
+    #[serde(default = "default_dns_timeout_ms")]
+    pub dns_timeout_ms: u64,
+    #[serde(default = "default_tunnel_detect_timeout_ms")]
+    pub tunnel_detect_timeout_ms: u64,
+    #[serde(default)]
+    pub tunnel_detect_timeout_action: TunnelDetectTimeoutAction,
+    #[serde(default)]
+    pub connect_fallback: ConnectFallbackConfig,
+    #[serde(default)]
+    pub upstream_ip_family: IpFamilyPolicy,
+    #[serde(default)]
+    pub passthrough_content_types: Vec<String>,
+    #[serde(default)]
+    pub inspection_limit: InspectionLimitConfig,
+    #[serde(default = "default_max_inspection_bytes")]
+    pub max_inspection_bytes: u64,
+    #[serde(default)]
+    pub error_pages: ErrorPagesConfig,
+    #[serde(default)]
+    pub write_coalescing: WriteCoalescingConfig,
+    #[serde(default)]
+    pub flow_control: FlowControlConfig,
+    #[serde(default)]
+    pub circuit_breaker_enabled: bool,
+    #[serde(default = "default_circuit_breaker_failure_threshold")]
+    pub circuit_breaker_failure_threshold: u32,
+    #[serde(default = "default_circuit_breaker_window_seconds")]
+    pub circuit_breaker_window_seconds: u64,
+    #[serde(default = "default_circuit_breaker_cooldown_seconds")]
+    pub circuit_breaker_cooldown_seconds: u64,
+    #[serde(default)]
+    pub upstream_host_limit: UpstreamHostLimitConfig,
+    #[serde(default)]
+    pub upstream_dscp: UpstreamDscpConfig,
+    #[serde(default)]
+    pub client_socket_buffers: SocketBufferConfig,
+    #[serde(default)]
+    pub upstream_socket_buffers: SocketBufferConfig,
+    #[serde(default)]
+    pub category_throttle: CategoryThrottleConfig,
+    #[serde(default)]
+    pub overload: OverloadConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub worker_metrics_enabled: bool,
+    #[serde(default = "default_worker_metrics_interval_seconds")]
+    pub worker_metrics_interval_seconds: u64,
+    #[serde(default)]
+    pub upstream_timing_enabled: bool,
+    #[serde(default)]
+    pub tls_params_logging: bool,
+    #[serde(default)]
+    pub upstream_cert_logging: UpstreamCertLoggingConfig,
+    #[serde(default)]
+    pub influx: InfluxExportConfig,
+    #[serde(default)]
+    pub graceful_shutdown: GracefulShutdownConfig,
+    #[serde(default)]
+    pub access_log: AccessLogConfig,
+    #[serde(default)]
+    pub admin_bind: Option<String>,
+    #[serde(default)]
+    pub admin_allowed_sources: Vec<String>,
+    #[serde(default)]
+    pub admin_keep_alive: AdminKeepAliveConfig,
+    #[serde(default)]
+    pub admin_tls: AdminTlsConfig,
+    #[serde(default)]
+    pub debug_log_buffer_size: usize,
+    #[serde(default)]
+    pub pac: PacConfig,
+    #[serde(default)]
+    pub ca_download: CaDownloadConfig,
+    #[serde(default)]
+    pub event_stream: EventStreamConfig,
+    #[serde(default)]
+    pub tenants: TenantConfig,
+    #[serde(default)]
+    pub source_filter: SourceFilterConfig,
+}
+
+/// 설정 해시에서 순서를 무시하는 집합 필드
+const UNORDERED_CONFIG_KEYS: [&str; 4] = ["blocked_domains", "blocked_patterns", "tls_exempt_hosts", "trusted_certificates"];
+
+/// 도메인 차단 정책 적용 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AclMode {
+    /// 일치하는 요청을 차단
+    #[default]
+    Enforce,
+    /// 일치 여부만 기록하고 요청은 허용 (차단 목록 변경 사전 검증용)
+    Audit,
+}
+
+/// 차단 규칙으로 판단할 수 없을 때의 처리 (차단 목록을 아직 불러오지 못했거나 규칙 잠금이 손상된 경우)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AclFailurePolicy {
+    /// 허용 (가용성 우선, 기존 동작)
+    #[default]
+    Open,
+    /// 차단 (보안 우선)
+    Closed,
+}
+
+impl AclFailurePolicy {
+    /// 로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            AclFailurePolicy::Open => "fail-open",
+            AclFailurePolicy::Closed => "fail-closed",
+        }
+    }
+}
+
+/// 같은 호스트에 허용(필터 목록의 `@@` 예외) 규칙과 차단 규칙이 함께 일치할 때의 우선순위
+///
+/// `$important` 필터 규칙은 어느 우선순위에서도 예외 규칙보다 우선합니다.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AclConflictPrecedence {
+    /// 예외 규칙은 필터 목록 규칙에만 적용 (운영자가 지정한 blocked_domains/blocked_patterns/DB 규칙은 항상 차단)
+    #[default]
+    FilterListOnly,
+    /// 예외 규칙이 모든 차단 규칙보다 우선 (Adblock Plus 동작)
+    AllowWins,
+    /// 차단 규칙 우선 (예외 규칙 무시)
+    BlockWins,
+    /// 더 구체적인 규칙 우선 (정확한 도메인 > 와일드카드 > 정규표현식, 같은 유형이면 더 긴 도메인, 같으면 차단)
+    MostSpecific,
+}
+
+impl AclConflictPrecedence {
+    /// 로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            AclConflictPrecedence::FilterListOnly => "filter_list_only",
+            AclConflictPrecedence::AllowWins => "allow_wins",
+            AclConflictPrecedence::BlockWins => "block_wins",
+            AclConflictPrecedence::MostSpecific => "most_specific",
+        }
+    }
+}
+
+/// CONNECT 접근 제어 규칙 (클라이언트 주소, 대상 호스트, 대상 포트 기준, 처음 일치한 규칙 적용)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ConnectAclConfig {
+    /// 일치하는 규칙이 없을 때의 동작
+    #[serde(default)]
+    pub default: ConnectAclAction,
+    /// 순서대로 평가할 규칙 목록
+    #[serde(default)]
+    pub rules: Vec<ConnectAclRule>,
+}
+
+impl ConnectAclConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.rules.is_empty() || self.default == ConnectAclAction::Deny
+    }
+}
+
+/// CONNECT 접근 제어 규칙 형식 확인 (`prefix`는 오류 메시지에 쓰는 설정 경로)
+fn validate_connect_acl(prefix: &str, acl: &ConnectAclConfig) -> std::result::Result<(), String> {
+    for (index, rule) in acl.rules.iter().enumerate() {
+        for client in &rule.clients {
+            crate::server::tenant::parse_cidr(client)
+                .map_err(|e| format!("{}.rules[{}] clients 형식 오류: {:?} ({})", prefix, index, client, e))?;
+        }
+        if let Some(host) = rule.hosts.iter().find(|h| {
+            let h = h.trim();
+            h.is_empty() || (h != "*" && h.trim_start_matches("*.").contains('*'))
+        }) {
+            return Err(format!("{}.rules[{}] hosts 형식 오류 (호스트, *.도메인 또는 *): {:?}", prefix, index, host));
+        }
+        for ports in &rule.ports {
+            crate::acl::connect_acl::parse_port_range(ports)
+                .map_err(|e| format!("{}.rules[{}] ports 형식 오류: {}", prefix, index, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 리스너별 TLS 가로채기 정책
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterceptionPolicy {
+    /// 가로채기 제외 호스트(tls_exemptions)와 리스너의 bypass_hosts만 빼고 가로채기
+    #[default]
+    BypassList,
+    /// 가로채기 제외 호스트와 관계없이 TLS는 모두 가로채기
+    Always,
+    /// 가로채지 않고 모든 터널을 그대로 전달
+    Never,
+}
+
+impl InterceptionPolicy {
+    /// 설정/로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            InterceptionPolicy::BypassList => "bypass_list",
+            InterceptionPolicy::Always => "always",
+            InterceptionPolicy::Never => "never",
+        }
+    }
+}
+
+/// 추가 리스너 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ListenerConfig {
+    /// 로그에 표시할 리스너 이름
+    pub name: String,
+    /// 바인딩 주소 (없으면 bind_host)
+    #[serde(default)]
+    pub bind_host: Option<String>,
+    pub bind_port: u16,
+    #[serde(default)]
+    pub interception: InterceptionPolicy,
+    /// interception이 bypass_list일 때 추가로 가로채지 않을 호스트 (호스트 또는 *.도메인)
+    #[serde(default)]
+    pub bypass_hosts: Vec<String>,
+    /// 적용할 connect_acl_policies 이름 (없으면 전역 connect_acl)
+    #[serde(default)]
+    pub connect_acl: Option<String>,
+}
+
+/// CONNECT 접근 제어 규칙 하나 (비어 있는 조건은 모두 일치)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConnectAclRule {
+    pub action: ConnectAclAction,
+    /// 클라이언트 IP 주소 또는 CIDR (예: 10.1.0.0/16)
+    #[serde(default)]
+    pub clients: Vec<String>,
+    /// 대상 호스트 (정확한 호스트, *.도메인, 또는 *)
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    /// 대상 포트 또는 포트 범위 (예: "443", "8000-8999")
+    #[serde(default)]
+    pub ports: Vec<String>,
+}
+
+/// CONNECT 접근 제어 동작
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectAclAction {
+    #[default]
+    Allow,
+    Deny,
+}
+
+impl ConnectAclAction {
+    /// 설정/로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConnectAclAction::Allow => "allow",
+            ConnectAclAction::Deny => "deny",
+        }
+    }
+}
+
+/// 차단 규칙에 일치한 요청 처리 동작 (acl_mode: enforce에서만 적용)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AclAction {
+    /// 차단 페이지 응답
+    #[default]
+    Block,
+    /// 일반 HTTP 요청에 302로 안내 페이지 이동 (HTTPS/SOCKS는 차단으로 처리)
+    Redirect,
+    /// 응답을 조금씩 보내며 연결을 붙잡아 두다가 최대 유지 시간 후 종료
+    Tarpit,
+    /// 예외로 허용
+    Allow,
+}
+
+/// 차단 규칙 일치 시 동작 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AclActionsConfig {
+    /// 규칙별 지정이 없을 때 동작
+    #[serde(default)]
+    pub default: AclAction,
+    /// redirect 동작의 이동 주소 (규칙에 redirect_url이 없을 때 사용)
+    #[serde(default)]
+    pub redirect_url: Option<String>,
+    /// tarpit 연결 최대 유지 시간
+    #[serde(default = "default_tarpit_max_hold_seconds")]
+    pub tarpit_max_hold_seconds: u64,
+    /// tarpit 응답 조각 전송 간격
+    #[serde(default = "default_tarpit_interval_ms")]
+    pub tarpit_interval_ms: u64,
+    /// 규칙별 동작 (위에서부터 처음 일치한 항목 사용)
+    #[serde(default)]
+    pub rules: Vec<AclActionRule>,
+}
+
+impl Default for AclActionsConfig {
+    fn default() -> Self {
+        Self {
+            default: AclAction::default(),
+            redirect_url: None,
+            tarpit_max_hold_seconds: default_tarpit_max_hold_seconds(),
+            tarpit_interval_ms: default_tarpit_interval_ms(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// 규칙(또는 규칙 유형)별 동작
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AclActionRule {
+    /// 차단 목록 항목 그대로의 도메인/패턴 (DB 규칙 포함), 또는 규칙 유형 `kind:exact` | `kind:wildcard` | `kind:regex` | `kind:filter`
+    pub rule: String,
+    pub action: AclAction,
+    /// redirect 동작의 이동 주소 (없으면 acl_actions.redirect_url)
+    #[serde(default)]
+    pub redirect_url: Option<String>,
+}
+
+fn default_tarpit_max_hold_seconds() -> u64 {
+    30
+}
+
+fn default_tarpit_interval_ms() -> u64 {
+    1000
+}
+
+/// 업스트림 연결 시 주소 체계(IPv4/IPv6) 시도 순서
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressFamilyOrder {
+    /// 이름 해석 결과의 첫 주소 체계부터 시도
+    #[default]
+    Resolver,
+    /// IPv6 주소를 먼저 시도하고 실패 시 IPv4
+    Ipv6First,
+    /// IPv4 주소를 먼저 시도하고 실패 시 IPv6
+    Ipv4First,
+}
+
+/// 업스트림 이름 해석 결과에서 사용할 주소 체계
+///
+/// `*_only`는 다른 주소 체계의 주소를 버리고, `prefer_*`는 해당 주소 체계를 해석 결과 앞으로 옮깁니다
+/// (`connect_fallback.family_order: resolver`일 때 먼저 시도).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpFamilyPolicy {
+    /// 해석 결과 그대로 사용 (시스템 설정 순서)
+    #[default]
+    DualStack,
+    /// IPv4(A) 주소만 사용
+    Ipv4Only,
+    /// IPv6(AAAA) 주소만 사용
+    Ipv6Only,
+    /// IPv4 주소를 앞으로
+    PreferIpv4,
+    /// IPv6 주소를 앞으로
+    PreferIpv6,
+}
+
+impl IpFamilyPolicy {
+    /// 로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            IpFamilyPolicy::DualStack => "dual_stack",
+            IpFamilyPolicy::Ipv4Only => "ipv4_only",
+            IpFamilyPolicy::Ipv6Only => "ipv6_only",
+            IpFamilyPolicy::PreferIpv4 => "prefer_ipv4",
+            IpFamilyPolicy::PreferIpv6 => "prefer_ipv6",
+        }
+    }
+}
+
+/// 업스트림 연결 실패 시 다른 주소 체계로 대체 연결 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConnectFallbackConfig {
+    #[serde(default)]
+    pub family_order: AddressFamilyOrder,
+    /// 주소 체계마다 시도할 최대 주소 수
+    #[serde(default = "default_connect_attempts_per_family")]
+    pub attempts_per_family: usize,
+    /// 주소 하나에 대한 연결 제한 시간 (응답 없는 주소 체계에서 오래 대기하지 않도록)
+    #[serde(default = "default_connect_attempt_timeout_ms")]
+    pub attempt_timeout_ms: u64,
+}
+
+impl Default for ConnectFallbackConfig {
+    fn default() -> Self {
+        Self {
+            family_order: AddressFamilyOrder::default(),
+            attempts_per_family: default_connect_attempts_per_family(),
+            attempt_timeout_ms: default_connect_attempt_timeout_ms(),
+        }
+    }
+}
+
+fn default_connect_attempts_per_family() -> usize {
+    2
+}
+
+fn default_connect_attempt_timeout_ms() -> u64 {
+    3000
+}
+
+/// SNI 없는 ClientHello 처리 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoSniAction {
+    /// CONNECT 대상 호스트로 인증서를 만들어 가로채기
+    #[default]
+    ConnectHost,
+    /// 복호화 없이 업스트림으로 그대로 전달
+    Passthrough,
+}
+
+/// CONNECT 터널의 첫 바이트가 `tunnel_detect_timeout_ms` 안에 오지 않았을 때 처리 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TunnelDetectTimeoutAction {
+    /// 요청 대기 시간(timeout_ms)까지 첫 바이트를 더 기다려 판별 (그래도 없으면 연결 종료)
+    #[default]
+    Wait,
+    /// 클라이언트 연결 종료
+    Deny,
+    /// 복호화 없이 평문 터널로 전달 (SMTP처럼 서버가 먼저 말하는 프로토콜용)
+    Passthrough,
+}
+
+impl TunnelDetectTimeoutAction {
+    /// 설정/로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            TunnelDetectTimeoutAction::Wait => "wait",
+            TunnelDetectTimeoutAction::Deny => "deny",
+            TunnelDetectTimeoutAction::Passthrough => "passthrough",
+        }
+    }
+}
+
+/// ClientHello를 끝까지 받지 못했을 때(시간 초과 또는 크기 한도 초과) 처리 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientHelloIncompleteAction {
+    /// SNI 없이 CONNECT 대상 호스트로 가로채기
+    #[default]
+    Intercept,
+    /// 복호화 없이 업스트림으로 그대로 전달
+    Opaque,
+    /// 클라이언트 연결 종료
+    Reject,
+}
+
+impl ClientHelloIncompleteAction {
+    /// 설정/로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClientHelloIncompleteAction::Intercept => "intercept",
+            ClientHelloIncompleteAction::Opaque => "opaque",
+            ClientHelloIncompleteAction::Reject => "reject",
+        }
+    }
+}
+
+/// ClientHello 크기 한도 최소/최대값
+pub const MIN_CLIENT_HELLO_PEEK_BYTES: usize = 1024;
+pub const MAX_CLIENT_HELLO_PEEK_BYTES: usize = 256 * 1024;
+
+/// 가로채기 전 ClientHello 확인 설정 (여러 조각/TLS 레코드로 나뉘어 와도 모두 받을 때까지 기다림)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClientHelloPeekConfig {
+    /// ClientHello를 모두 받을 때까지 기다리는 시간 (없으면 timeout_ms)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// 기다릴 ClientHello 최대 크기 (TLS 레코드 헤더 포함)
+    #[serde(default = "default_client_hello_peek_max_bytes")]
+    pub max_bytes: usize,
+    #[serde(default)]
+    pub on_incomplete: ClientHelloIncompleteAction,
+}
+
+impl Default for ClientHelloPeekConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: None,
+            max_bytes: default_client_hello_peek_max_bytes(),
+            on_incomplete: ClientHelloIncompleteAction::default(),
+        }
+    }
+}
+
+fn default_client_hello_peek_max_bytes() -> usize {
+    32 * 1024
+}
+
+/// CONNECT 요청과 함께(200 응답 전에) 받은 바이트 처리 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectPipelinePolicy {
+    /// 모두 터널 데이터의 시작으로 업스트림에 전달
+    Forward,
+    /// HTTP 요청처럼 보이면(요청 밀반입 의심) 400으로 거부하고 나머지는 전달
+    #[default]
+    RejectRequests,
+    /// 모두 400으로 거부
+    Reject,
+}
+
+impl ConnectPipelinePolicy {
+    /// 설정/로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConnectPipelinePolicy::Forward => "forward",
+            ConnectPipelinePolicy::RejectRequests => "reject_requests",
+            ConnectPipelinePolicy::Reject => "reject",
+        }
+    }
+}
+
+/// 검사 중인 응답이 크기 한도를 넘었을 때 처리 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InspectionOverflowAction {
+    /// 검사를 멈추고 남은 본문은 버퍼에 모으지 않고 그대로 전달
+    #[default]
+    Passthrough,
+    /// 연결을 끊어 응답 전달 중단
+    Abort,
+}
+
+impl InspectionOverflowAction {
+    /// 설정/로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            InspectionOverflowAction::Passthrough => "passthrough",
+            InspectionOverflowAction::Abort => "abort",
+        }
+    }
+}
+
+/// 검사 응답 크기 한도 최소값 (응답 헤더를 모두 받을 수 있어야 함)
+pub const MIN_INSPECTION_RESPONSE_BYTES: u64 = 16 * 1024;
+
+/// 검사용 버퍼링 전체 상한 기본값 (10MiB)
+pub const DEFAULT_MAX_INSPECTION_BYTES: u64 = 10 * 1024 * 1024;
+
+fn default_max_inspection_bytes() -> u64 {
+    DEFAULT_MAX_INSPECTION_BYTES
+}
+
+/// 검사(버퍼링) 중인 업스트림 응답 크기 한도 (평문 HTTP, 가로챈 HTTPS)
+///
+/// 가로채지 않는 터널의 전송량과는 별개이며, 터널 전송량은 제한하지 않습니다.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InspectionLimitConfig {
+    /// 버퍼에 모아 검사하는 응답 하나의 최대 크기 (헤더 포함, 없으면 제한 없음)
+    #[serde(default)]
+    pub max_response_bytes: Option<u64>,
+    /// 한도를 넘었을 때 처리 방식
+    #[serde(default)]
+    pub on_exceed: InspectionOverflowAction,
+}
+
+impl InspectionLimitConfig {
+    /// 지금까지 모은 응답 크기가 한도를 넘었는지
+    pub fn exceeded(&self, buffered: usize) -> bool {
+        self.max_response_bytes.is_some_and(|limit| buffered as u64 > limit)
+    }
+}
+
+impl Config {
+    /// 지금까지 검사용으로 모은 요청/응답 크기가 전체 상한(max_inspection_bytes)을 넘었는지
+    ///
+    /// Content-Type 기준 패스스루나 inspection_limit과 관계없이 항상 적용되며, 넘으면 나머지 본문은 검사 없이 전달합니다.
+    pub fn inspection_cap_exceeded(&self, buffered: usize) -> bool {
+        buffered as u64 > self.max_inspection_bytes
+    }
+}
+
+/// 쓰기 합치기 대기 시간 상한 (밀리초)
+pub const MAX_WRITE_COALESCING_DELAY_MS: u64 = 50;
+
+/// 업스트림 쓰기 합치기 (가로채지 않는 터널의 클라이언트→업스트림 방향)
+///
+/// 클라이언트가 작은 조각으로 보낸 데이터를 `max_bytes`가 찰 때까지, 첫 조각을 받은 뒤 최대 `max_delay_ms` 동안
+/// 모아 한 번에 업스트림 소켓에 씁니다. 쓰기 시스템 콜은 줄지만 조각마다 최대 `max_delay_ms`만큼 지연이 늘어납니다.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WriteCoalescingConfig {
+    /// 한 번에 쓸 최대 바이트 수 (0이면 사용 안 함, 이보다 큰 읽기는 기다리지 않고 바로 씀)
+    #[serde(default)]
+    pub max_bytes: usize,
+    /// 첫 조각을 받은 뒤 더 기다리는 최대 시간 (밀리초)
+    #[serde(default = "default_write_coalescing_max_delay_ms")]
+    pub max_delay_ms: u64,
+}
+
+impl Default for WriteCoalescingConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 0,
+            max_delay_ms: default_write_coalescing_max_delay_ms(),
+        }
+    }
+}
+
+impl WriteCoalescingConfig {
+    /// 쓰기 합치기를 사용하는지
+    pub fn is_enabled(&self) -> bool {
+        self.max_bytes > 0
+    }
+}
+
+fn default_write_coalescing_max_delay_ms() -> u64 {
+    1
+}
+
+/// 흐름 제어 상한 워터마크 최대값
+pub const MAX_FLOW_CONTROL_HIGH_WATERMARK: usize = 64 * BUFFER_SIZE_LARGE;
+
+/// 세션 전달 흐름 제어 (방향별 미전송 버퍼)
+///
+/// 가로채지 않는 터널은 읽는 쪽이 빠르고 쓰는 쪽이 느리면 아직 쓰지 못한 데이터가 `high_watermark`에 닿을 때 읽기를 멈추고,
+/// `low_watermark` 이하로 줄면 다시 읽습니다. 가로챈 TLS 세션은 한 번에 `high_watermark`까지만 읽어 다 쓴 뒤 다음을 읽습니다.
+/// 사용하지 않으면(기본) 방향마다 한 번 읽은 만큼(`read_chunk_size`)만 쌓습니다.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FlowControlConfig {
+    /// 읽기를 멈추는 미전송 바이트 수 (0이면 사용 안 함)
+    #[serde(default)]
+    pub high_watermark: usize,
+    /// 읽기를 다시 시작하는 미전송 바이트 수 (0이면 모두 쓴 뒤 재개)
+    #[serde(default)]
+    pub low_watermark: usize,
+}
+
+impl FlowControlConfig {
+    /// 흐름 제어를 사용하는지
+    pub fn is_enabled(&self) -> bool {
+        self.high_watermark > 0
+    }
+
+    /// 한 번에 읽을 크기 (사용하면 `high_watermark`를 넘지 않음)
+    pub fn read_limit(&self, read_chunk_size: Option<usize>) -> Option<usize> {
+        if !self.is_enabled() {
+            return read_chunk_size;
+        }
+        Some(read_chunk_size.map_or(self.high_watermark, |size| size.min(self.high_watermark)))
+    }
+}
+
+/// 프록시가 직접 보내는 오류 응답(차단, 과부하, 업스트림 연결 실패 등) 본문 템플릿
+///
+/// 템플릿의 `{{status}}`, `{{reason}}`, `{{title}}`, `{{message}}`, `{{detail}}`, `{{host}}`, `{{request_id}}`,
+/// `{{timestamp}}` 자리에 형식에 맞게 이스케이프한 값이 들어갑니다.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ErrorPagesConfig {
+    /// 브라우저(Accept: text/html)용 HTML 템플릿 파일 (없으면 내장 템플릿)
+    #[serde(default)]
+    pub html_template: Option<String>,
+    /// API 클라이언트용 JSON 템플릿 파일 (없으면 내장 템플릿)
+    #[serde(default)]
+    pub json_template: Option<String>,
+}
+
+/// 생성되는 리프 인증서의 subject/SAN 템플릿
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LeafCertTemplate {
+    #[serde(default = "default_leaf_cert_organization")]
+    pub organization: String,
+    #[serde(default)]
+    pub organizational_unit: Option<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub locality: Option<String>,
+    /// 방문 호스트 외에 추가할 SAN 목록 (`{host}`는 방문 호스트로 치환, IP 형식이면 IP SAN)
+    #[serde(default)]
+    pub extra_sans: Vec<String>,
+}
+
+impl Default for LeafCertTemplate {
+    fn default() -> Self {
+        Self {
+            organization: default_leaf_cert_organization(),
+            organizational_unit: None,
+            country: None,
+            state: None,
+            locality: None,
+            extra_sans: Vec::new(),
+        }
+    }
+}
+
+fn default_leaf_cert_organization() -> String {
+    "UDSS Proxy Generated".to_string()
+}
+
+/// 업스트림 인증서 검증 실패 시 처리 등급
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamCertTier {
+    /// 연결 거부
+    Strict,
+    /// 허용하되 경고 로그를 남기고 세션에 표시
+    Warn,
+    /// 검증 없이 허용
+    Insecure,
+}
+
+/// 호스트 패턴별 업스트림 인증서 검증 등급 (`example.com` 또는 `*.example.com`)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpstreamCertTierRule {
+    pub host: String,
+    pub tier: UpstreamCertTier,
+}
+
+/// 루트 CA 한 개 (인증서와 키 PEM 파일, 파일이 없으면 새로 생성해 저장)
+///
+/// `cert_file`이 PKCS#12 번들(.p12/.pfx)이면 인증서와 키를 번들에서 읽으며 `key_file`은 비워 둡니다.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RootCaEntry {
+    pub name: String,
+    pub cert_file: String,
+    #[serde(default)]
+    pub key_file: String,
+}
+
+impl RootCaEntry {
+    /// 인증서와 키를 PKCS#12 번들에서 읽는지
+    pub fn is_pkcs12(&self) -> bool {
+        crate::tls::pkcs12::is_pkcs12_path(&self.cert_file)
+    }
+}
+
+/// 루트 CA 목록과 서명 CA 선택 (CA 교체 시 전환 기간 동안 여러 CA를 함께 로드)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RootCaConfig {
+    /// 로드할 CA 목록 (비어 있으면 ssl/ca_cert.pem, ssl/ca_key.pem 하나만 사용)
+    #[serde(default)]
+    pub cas: Vec<RootCaEntry>,
+    /// 새 리프 인증서를 서명할 CA 이름 (null이면 첫 항목)
+    #[serde(default)]
+    pub active: Option<String>,
+    /// 이 CA로 서명 CA를 교차 서명한 인증서를 체인에 넣음 (이전 CA만 신뢰하는 클라이언트용)
+    #[serde(default)]
+    pub cross_sign_with: Option<String>,
+}
+
+/// PKCS#12(.p12/.pfx) 번들 암호 (루트 CA 번들과 신뢰할 인증서 번들에 공통 적용)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Pkcs12Config {
+    /// 번들 암호 (설정 덤프에서는 가려짐)
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    /// 암호를 읽을 환경 변수 이름 (지정하면 passphrase 대신 사용)
+    #[serde(default)]
+    pub passphrase_env: Option<String>,
+}
+
+impl Pkcs12Config {
+    /// 사용할 번들 암호 (둘 다 없으면 빈 암호)
+    pub fn resolve_passphrase(&self) -> Result<String, String> {
+        match &self.passphrase_env {
+            Some(var) => std::env::var(var)
+                .map_err(|e| format!("pkcs12.passphrase_env 환경 변수를 읽을 수 없습니다 ({}): {}", var, e)),
+            None => Ok(self.passphrase.clone().unwrap_or_default()),
+        }
+    }
+}
+
+/// 클라이언트 측(MITM) TLS 세션 재개 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TlsResumptionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 세션 ID 재개용 서버 세션 캐시 크기
+    #[serde(default = "default_tls_session_cache_size")]
+    pub session_cache_size: usize,
+    /// 세션 티켓 발급 여부 (false이면 세션 ID 재개만 사용)
+    #[serde(default = "default_tls_tickets")]
+    pub tickets: bool,
+    /// 티켓 암호화 키 교체 주기 (이전 키로 발급된 티켓은 한 주기 더 허용)
+    #[serde(default = "default_tls_ticket_rotation_seconds")]
+    pub ticket_rotation_seconds: u64,
+}
+
+impl Default for TlsResumptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            session_cache_size: default_tls_session_cache_size(),
+            tickets: default_tls_tickets(),
+            ticket_rotation_seconds: default_tls_ticket_rotation_seconds(),
+        }
+    }
+}
+
+fn default_tls_session_cache_size() -> usize {
+    10000
+}
+
+fn default_tls_tickets() -> bool {
+    true
+}
+
+fn default_tls_ticket_rotation_seconds() -> u64 {
+    3600
+}
+
+/// 같은 상위 도메인의 하위 도메인들이 공유하는 와일드카드 리프 인증서 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WildcardCertConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 상위 도메인 아래에서 서로 다른 하위 도메인 인증서가 이 수보다 많이 생성되면 와일드카드로 전환
+    #[serde(default = "default_wildcard_min_subdomains")]
+    pub min_subdomains: u32,
+}
+
+impl Default for WildcardCertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_subdomains: default_wildcard_min_subdomains(),
+        }
+    }
+}
+
+fn default_wildcard_min_subdomains() -> u32 {
+    3
+}
+
+/// 리프 인증서 캐시 미스율 경고 설정
+///
+/// 미스율(새 인증서 생성 비율)은 항상 메트릭으로 집계하고, 임계값을 지정하면 넘어설 때 경고 로그를 남깁니다.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct CertCacheAlertConfig {
+    /// 미스율을 계산할 최근 구간 길이
+    #[serde(default = "default_cert_cache_alert_window_seconds")]
+    pub window_seconds: u64,
+    /// 경고할 미스율 (퍼센트, 0이면 경고하지 않음)
+    #[serde(default)]
+    pub miss_rate_threshold_percent: u8,
+    /// 구간 안의 조회 수가 이보다 적으면 경고하지 않음 (시작 직후나 한가할 때의 오경보 방지)
+    #[serde(default = "default_cert_cache_alert_min_lookups")]
+    pub min_lookups: u64,
+}
+
+impl Default for CertCacheAlertConfig {
+    fn default() -> Self {
+        Self {
+            window_seconds: default_cert_cache_alert_window_seconds(),
+            miss_rate_threshold_percent: 0,
+            min_lookups: default_cert_cache_alert_min_lookups(),
+        }
+    }
+}
+
+/// 미스율 구간 길이 최댓값
+pub const MAX_CERT_CACHE_ALERT_WINDOW_SECONDS: u64 = 3600;
+
+fn default_cert_cache_alert_window_seconds() -> u64 {
+    60
+}
+
+fn default_cert_cache_alert_min_lookups() -> u64 {
+    20
+}
+
+/// 종료 대기 시간이 지났을 때 남은 세션 처리 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownExpiryAction {
+    /// 남은 세션을 강제로 닫고 종료
+    #[default]
+    ForceClose,
+    /// 모든 세션이 끝날 때까지 계속 대기 (점검 작업용)
+    Wait,
+}
+
+/// 종료 시그널 후 진행 중인 세션 정리 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GracefulShutdownConfig {
+    /// 진행 중인 세션이 끝나기를 기다리는 시간
+    #[serde(default = "default_shutdown_grace_seconds")]
+    pub grace_seconds: u64,
+    /// 대기 시간이 지난 뒤 처리 방식
+    #[serde(default)]
+    pub on_expiry: ShutdownExpiryAction,
+}
+
+impl Default for GracefulShutdownConfig {
+    fn default() -> Self {
+        Self {
+            grace_seconds: default_shutdown_grace_seconds(),
+            on_expiry: ShutdownExpiryAction::default(),
+        }
+    }
+}
+
+fn default_shutdown_grace_seconds() -> u64 {
+    30
+}
+
+/// 가로챈 세션의 업스트림 인증서 체인 기록 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpstreamCertLoggingConfig {
+    /// 업스트림 인증서 체인 기록 여부
+    #[serde(default)]
+    pub enabled: bool,
+    /// 기록 위치
+    #[serde(default)]
+    pub target: UpstreamCertLogTarget,
+    /// 같은 호스트의 같은 리프 인증서를 다시 기록하지 않는 시간 (0이면 세션마다 기록)
+    #[serde(default = "default_upstream_cert_dedupe_seconds")]
+    pub dedupe_seconds: u64,
+}
+
+impl Default for UpstreamCertLoggingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: UpstreamCertLogTarget::default(),
+            dedupe_seconds: default_upstream_cert_dedupe_seconds(),
+        }
+    }
+}
+
+fn default_upstream_cert_dedupe_seconds() -> u64 {
+    3600
+}
+
+/// 업스트림 인증서 체인 기록 위치
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamCertLogTarget {
+    /// DB upstream_certificates 테이블 (DB를 쓰지 않으면 로그로 기록)
+    #[default]
+    Table,
+    /// upstream_cert 대상 로그에 한 줄 JSON
+    Log,
+}
+
+/// 접근 로그 출력 형식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLogFormat {
+    /// 한 줄 텍스트 (필드를 고르면 `이름=값` 나열)
+    #[default]
+    Text,
+    /// 한 줄에 JSON 객체 하나
+    Json,
+}
+
+/// 접근 로그 필드 (요청 줄과 응답 줄 중 해당하는 줄에만 출력)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLogField {
+    Timestamp,
+    SessionId,
+    ClientIp,
+    Method,
+    Host,
+    Path,
+    TargetIp,
+    Tls,
+    Rejected,
+    Status,
+    ResponseTimeMs,
+    ResponseSize,
+    /// 업스트림 구간 시간 (upstream_timing_enabled인 경우)
+    UpstreamTiming,
+    /// warn 등급으로 업스트림 인증서 검증 실패를 허용한 세션 여부
+    UpstreamCertWarning,
+    /// 클라이언트/업스트림 TLS 협상 값 (tls_params_logging인 경우)
+    TlsParams,
+    /// 클라이언트 주소 대역의 테넌트 태그 (tenants 설정 시, 요청 줄과 응답 줄 모두)
+    Tenant,
+}
+
+impl AccessLogField {
+    /// 로그에 쓰는 필드 이름 (설정 이름과 같음)
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessLogField::Timestamp => "timestamp",
+            AccessLogField::SessionId => "session_id",
+            AccessLogField::ClientIp => "client_ip",
+            AccessLogField::Method => "method",
+            AccessLogField::Host => "host",
+            AccessLogField::Path => "path",
+            AccessLogField::TargetIp => "target_ip",
+            AccessLogField::Tls => "tls",
+            AccessLogField::Rejected => "rejected",
+            AccessLogField::Status => "status",
+            AccessLogField::ResponseTimeMs => "response_time_ms",
+            AccessLogField::ResponseSize => "response_size",
+            AccessLogField::UpstreamTiming => "upstream_timing",
+            AccessLogField::UpstreamCertWarning => "upstream_cert_warning",
+            AccessLogField::TlsParams => "tls_params",
+            AccessLogField::Tenant => "tenant",
+        }
+    }
+}
+
+/// 접근 로그 설정 (DB 비활성화 시 표준 출력으로 기록하는 접근 로그)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AccessLogConfig {
+    #[serde(default)]
+    pub format: AccessLogFormat,
+    /// 출력할 필드 (비어 있으면 모든 필드, 알 수 없는 이름은 설정 로드 실패)
+    #[serde(default)]
+    pub fields: Vec<AccessLogField>,
+    /// 접근 로그 파일 (path 지정 시 표준 출력 대신 파일에 기록, DB 활성화 시에도 기록)
+    #[serde(default)]
+    pub file: AccessLogFileConfig,
+}
+
+/// 접근 로그 파일 압축 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLogCompression {
+    /// 압축하지 않음
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl AccessLogCompression {
+    /// 설정/로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessLogCompression::None => "none",
+            AccessLogCompression::Gzip => "gzip",
+            AccessLogCompression::Zstd => "zstd",
+        }
+    }
+
+    /// 압축 수준 허용 범위와 기본값 (최소, 최대, 기본)
+    pub fn level_range(&self) -> (i32, i32, i32) {
+        match self {
+            AccessLogCompression::None => (0, 0, 0),
+            AccessLogCompression::Gzip => (0, 9, 6),
+            AccessLogCompression::Zstd => (1, 22, 3),
+        }
+    }
+}
+
+/// 접근 로그 파일 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccessLogFileConfig {
+    /// 파일 경로 (null이면 파일에 기록하지 않음, 이미 있으면 이어서 기록)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 압축 방식
+    #[serde(default)]
+    pub compression: AccessLogCompression,
+    /// 압축 수준 (null이면 방식별 기본값: gzip 6, zstd 3)
+    #[serde(default)]
+    pub level: Option<i32>,
+    /// 파일로 내보내는 주기 (짧을수록 비정상 종료 시 유실이 적고 압축률은 낮아짐)
+    #[serde(default = "default_access_log_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+    /// 기록 스레드로 넘기기 전에 쌓아 둘 수 있는 줄 수 (가득 차면 새 줄을 버리고 개수를 셈)
+    #[serde(default = "default_access_log_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+impl Default for AccessLogFileConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            compression: AccessLogCompression::default(),
+            level: None,
+            flush_interval_ms: default_access_log_flush_interval_ms(),
+            queue_capacity: default_access_log_queue_capacity(),
+        }
+    }
+}
+
+impl AccessLogFileConfig {
+    /// 적용할 압축 수준 (지정하지 않으면 방식별 기본값)
+    pub fn effective_level(&self) -> i32 {
+        self.level.unwrap_or(self.compression.level_range().2)
+    }
+}
+
+fn default_access_log_flush_interval_ms() -> u64 {
+    1000
+}
+
+fn default_access_log_queue_capacity() -> usize {
+    10000
+}
+
+/// InfluxDB 라인 프로토콜 UDP 메트릭 전송 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InfluxExportConfig {
+    /// 수집기 주소 (host:port, null이면 비활성화)
+    #[serde(default)]
+    pub collector: Option<String>,
+    /// 전송 주기
+    #[serde(default = "default_influx_interval_seconds")]
+    pub interval_seconds: u64,
+    /// measurement 이름
+    #[serde(default = "default_influx_measurement")]
+    pub measurement: String,
+    /// 모든 포인트에 붙일 태그
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+impl Default for InfluxExportConfig {
+    fn default() -> Self {
+        Self {
+            collector: None,
+            interval_seconds: default_influx_interval_seconds(),
+            measurement: default_influx_measurement(),
+            tags: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_influx_interval_seconds() -> u64 {
+    10
+}
+
+fn default_influx_measurement() -> String {
+    "udss_proxy".to_string()
+}
+
+/// 업스트림 호스트별 동시 연결 수 제한 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpstreamHostLimitConfig {
+    /// 호스트당 최대 동시 연결 수 (0이면 overrides에 지정한 호스트만 제한)
+    #[serde(default)]
+    pub max_connections: usize,
+    /// 호스트별 최대 동시 연결 수 (0이면 해당 호스트는 제한 없음)
+    #[serde(default)]
+    pub overrides: HashMap<String, usize>,
+    /// 한도에 도달했을 때 빈 자리를 기다리는 시간 (0이면 즉시 거부)
+    #[serde(default)]
+    pub queue_timeout_ms: u64,
+    /// 연결이 없는 호스트 항목을 제거하기까지의 유휴 시간
+    #[serde(default = "default_upstream_host_limit_idle_seconds")]
+    pub idle_expiry_seconds: u64,
+}
+
+impl Default for UpstreamHostLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 0,
+            overrides: HashMap::new(),
+            queue_timeout_ms: 0,
+            idle_expiry_seconds: default_upstream_host_limit_idle_seconds(),
+        }
+    }
+}
+
+impl UpstreamHostLimitConfig {
+    /// 제한이 하나라도 설정되어 있는지
+    pub fn is_enabled(&self) -> bool {
+        self.max_connections > 0 || self.overrides.values().any(|limit| *limit > 0)
+    }
+}
+
+fn default_upstream_host_limit_idle_seconds() -> u64 {
+    300
+}
+
+/// DSCP 최대값 (6비트)
+pub const MAX_DSCP: u8 = 63;
+
+/// 시작 시 미리 이름 해석할 업스트림 호스트 (지연에 민감한 대상의 첫 요청에서 DNS 조회 생략)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DnsPrewarmConfig {
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    /// 미리 해석한 주소를 다시 해석하는 주기
+    #[serde(default = "default_dns_prewarm_refresh_seconds")]
+    pub refresh_seconds: u64,
+    /// 갱신에 계속 실패할 때 이전 주소를 쓰는 최대 시간 (지나면 요청 시 평소처럼 해석)
+    #[serde(default = "default_dns_prewarm_max_age_seconds")]
+    pub max_age_seconds: u64,
+}
+
+impl DnsPrewarmConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.hosts.is_empty()
+    }
+}
+
+impl Default for DnsPrewarmConfig {
+    fn default() -> Self {
+        Self {
+            hosts: Vec::new(),
+            refresh_seconds: default_dns_prewarm_refresh_seconds(),
+            max_age_seconds: default_dns_prewarm_max_age_seconds(),
+        }
+    }
+}
+
+/// 미리 해석한 주소 갱신 주기 범위
+pub const MIN_DNS_PREWARM_REFRESH_SECONDS: u64 = 10;
+pub const MAX_DNS_PREWARM_REFRESH_SECONDS: u64 = 86400;
+
+fn default_dns_prewarm_refresh_seconds() -> u64 {
+    300
+}
+
+fn default_dns_prewarm_max_age_seconds() -> u64 {
+    900
+}
+
+/// TCP 소켓 송수신 버퍼 크기 (SO_RCVBUF/SO_SNDBUF, null이면 클라이언트 소켓은 BUFFER_SIZE_MEDIUM, 업스트림 소켓은 OS 기본값)
+///
+/// 범위 밖의 값은 시작 시 경고하고 `MIN_SOCKET_BUFFER_BYTES` ~ `MAX_SOCKET_BUFFER_BYTES`로 조정해 적용합니다.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct SocketBufferConfig {
+    #[serde(default)]
+    pub recv_bytes: Option<usize>,
+    #[serde(default)]
+    pub send_bytes: Option<usize>,
+}
+
+impl SocketBufferConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.recv_bytes.is_some() || self.send_bytes.is_some()
+    }
+
+    /// 적용할 크기 (허용 범위로 조정)
+    pub fn clamp(bytes: usize) -> usize {
+        bytes.clamp(MIN_SOCKET_BUFFER_BYTES, MAX_SOCKET_BUFFER_BYTES)
+    }
+}
+
+/// 소켓 버퍼 크기 최솟값 (4KiB)
+pub const MIN_SOCKET_BUFFER_BYTES: usize = 4 * 1024;
+
+/// 소켓 버퍼 크기 최댓값 (64MiB)
+pub const MAX_SOCKET_BUFFER_BYTES: usize = 64 * 1024 * 1024;
+
+/// 업스트림 소켓 DSCP 표시 설정 (IPv4 TOS, IPv6 traffic class의 상위 6비트)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UpstreamDscpConfig {
+    /// 모든 업스트림 연결에 표시할 DSCP 값 (0 ~ 63, 없으면 표시하지 않음)
+    #[serde(default)]
+    pub default: Option<u8>,
+    /// 호스트(정확한 호스트 또는 `*.상위도메인`)별 DSCP 값 (default보다 우선)
+    #[serde(default)]
+    pub overrides: HashMap<String, u8>,
+}
+
+impl UpstreamDscpConfig {
+    /// 표시할 값이 하나라도 설정되어 있는지
+    pub fn is_enabled(&self) -> bool {
+        self.default.is_some() || !self.overrides.is_empty()
+    }
+}
+
+/// 목적지 분류별 세션 대역폭 제한 (업스트림→클라이언트 방향, 세션 시작 시 분류 결정)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CategoryThrottleConfig {
+    /// 어느 분류에도 속하지 않는 세션의 초당 바이트 한도 (0이면 제한 없음)
+    #[serde(default)]
+    pub default_bytes_per_second: u64,
+    /// 분류 목록 (호스트가 여러 분류에 속하면 먼저 적은 분류)
+    #[serde(default)]
+    pub categories: Vec<ThrottleCategory>,
+}
+
+impl CategoryThrottleConfig {
+    /// 제한이 하나라도 설정되어 있는지
+    pub fn is_enabled(&self) -> bool {
+        self.default_bytes_per_second > 0 || !self.categories.is_empty()
+    }
+}
+
+/// 대역폭 분류 하나
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThrottleCategory {
+    /// 로그에 쓰는 분류 이름 (예: streaming)
+    pub name: String,
+    /// 분류에 속하는 호스트 (정확한 호스트 또는 `*.상위도메인`)
+    pub hosts: Vec<String>,
+    /// 세션당 초당 바이트 한도 (0이면 제한 없음)
+    pub bytes_per_second: u64,
+}
+
+/// 과부하 시 요청 거부 설정 (전체 동시 세션 수, 초당 요청 수)
+///
+/// 한도를 넘은 요청과 호스트별 연결 한도(upstream_host_limit)로 거부한 요청에는
+/// `503 Service Unavailable`과 `Retry-After`로 응답합니다.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OverloadConfig {
+    /// 전체 동시 세션 수 한도 (0이면 제한 없음)
+    #[serde(default)]
+    pub max_connections: usize,
+    /// 초당 새 요청 수 한도 (0이면 제한 없음, 순간 허용량도 같은 값)
+    #[serde(default)]
+    pub max_requests_per_second: u32,
+    /// 503 응답의 Retry-After 값 (초)
+    #[serde(default = "default_overload_retry_after_seconds")]
+    pub retry_after_seconds: u64,
+    /// 클라이언트 IP별 동시 세션 수 한도 (0이면 제한 없음, 넘으면 요청을 읽기 전에 연결을 닫음)
+    #[serde(default)]
+    pub max_sessions_per_client_ip: usize,
+    /// 유지할 남은 파일 디스크립터 수 (0이면 확인하지 않음, 남은 수가 이보다 적으면 새 연결을 수락 직후 닫음)
+    #[serde(default)]
+    pub min_free_fds: u64,
+}
+
+impl Default for OverloadConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 0,
+            max_requests_per_second: 0,
+            retry_after_seconds: default_overload_retry_after_seconds(),
+            max_sessions_per_client_ip: 0,
+            min_free_fds: 0,
+        }
+    }
+}
+
+impl OverloadConfig {
+    /// 전체 한도가 하나라도 설정되어 있는지
+    pub fn is_enabled(&self) -> bool {
+        self.max_connections > 0 || self.max_requests_per_second > 0
+    }
+}
+
+fn default_overload_retry_after_seconds() -> u64 {
+    1
+}
+
+/// 점검 모드 (관리 엔드포인트 `/maintenance`로 실행 중에 전환)
+///
+/// 점검 모드에서는 새 HTTP 요청에 503 점검 페이지를, CONNECT에는 터널을 열지 않고 503을 응답합니다.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MaintenanceConfig {
+    /// 시작할 때부터 점검 모드
+    #[serde(default)]
+    pub enabled: bool,
+    /// 점검 응답 HTML 템플릿 파일 (None이면 오류 응답 HTML 템플릿 사용, 자리 표시자는 오류 응답과 같음)
+    #[serde(default)]
+    pub page_file: Option<String>,
+    /// 점검 응답의 Retry-After 값 (초)
+    #[serde(default = "default_maintenance_retry_after_seconds")]
+    pub retry_after_seconds: u64,
+    /// 점검 모드로 전환할 때 진행 중인 세션도 닫음 (false면 끝날 때까지 유지)
+    #[serde(default)]
+    pub close_existing: bool,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            page_file: None,
+            retry_after_seconds: default_maintenance_retry_after_seconds(),
+            close_existing: false,
+        }
+    }
+}
+
+fn default_maintenance_retry_after_seconds() -> u64 {
+    300
+}
+
+/// 프록시 리스너와 관리 엔드포인트의 `GET /proxy.pac` 자동 프록시 설정 파일
+///
+/// `file`을 지정하면 그 파일을 그대로 응답하고, 없으면 `direct_hosts`와 TLS 가로채기 제외 목록을
+/// DIRECT로, 나머지를 `proxy` 주소로 보내는 PAC를 요청마다 만듭니다.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PacConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 그대로 응답할 PAC 파일 경로 (지정 시 생성하지 않음)
+    #[serde(default)]
+    pub file: Option<String>,
+    /// PAC에 넣을 프록시 주소 (host:port, 생략 시 bind_host:bind_port)
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// 프록시를 거치지 않을 호스트 (정확한 호스트 또는 `*.도메인`)
+    #[serde(default)]
+    pub direct_hosts: Vec<String>,
+    /// TLS 가로채기 제외 호스트도 DIRECT로 보낼지 여부
+    #[serde(default)]
+    pub direct_tls_exempt: bool,
+}
+
+/// 루트 CA 인증서 배포 (`GET /ca.crt`) 설정
+///
+/// 현재 리프 서명 CA의 인증서만 PEM으로 응답하며 개인 키는 어떤 경로로도 내보내지 않습니다.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CaDownloadConfig {
+    /// 관리 엔드포인트에서 `GET /ca.crt` 응답 여부
+    #[serde(default = "default_ca_download_enabled")]
+    pub enabled: bool,
+    /// 인증 없이 `GET /ca.crt`만 응답하는 평문 HTTP 리스너 주소 (None이면 열지 않음)
+    #[serde(default)]
+    pub public_bind: Option<String>,
+}
+
+impl Default for CaDownloadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_ca_download_enabled(),
+            public_bind: None,
+        }
+    }
+}
+
+fn default_ca_download_enabled() -> bool {
+    true
+}
+
+/// 관리 엔드포인트 실시간 이벤트 스트림 (`GET /events`, Server-Sent Events) 설정
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EventStreamConfig {
+    /// 동시에 연결할 수 있는 시청자 수 (0이면 비활성화)
+    #[serde(default = "default_event_stream_max_clients")]
+    pub max_clients: usize,
+    /// 시청자마다 보내지 못한 이벤트를 쌓아 둘 최대 수 (넘으면 버리고 dropped 이벤트로 알림)
+    #[serde(default = "default_event_stream_buffer_size")]
+    pub buffer_size: usize,
+}
+
+impl Default for EventStreamConfig {
+    fn default() -> Self {
+        Self {
+            max_clients: default_event_stream_max_clients(),
+            buffer_size: default_event_stream_buffer_size(),
+        }
+    }
+}
+
+/// 이벤트 스트림 동시 시청자 수 최댓값
+pub const MAX_EVENT_STREAM_CLIENTS: usize = 64;
+
+/// 이벤트 스트림 시청자별 버퍼 범위
+pub const MIN_EVENT_STREAM_BUFFER_SIZE: usize = 16;
+pub const MAX_EVENT_STREAM_BUFFER_SIZE: usize = 65_536;
+
+fn default_event_stream_max_clients() -> usize {
+    4
+}
+
+fn default_event_stream_buffer_size() -> usize {
+    256
+}
+
+/// 관리 엔드포인트 연결 유지 (HTTP keep-alive) 설정
+///
+/// 프록시 경로에는 적용되지 않습니다. 유휴 시간 안에 다음 요청이 오지 않으면 연결을 닫습니다.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct AdminKeepAliveConfig {
+    /// 응답 후 다음 요청을 기다리는 시간 (0이면 요청마다 연결 종료)
+    #[serde(default = "default_admin_keep_alive_idle_timeout_seconds")]
+    pub idle_timeout_seconds: u64,
+    /// 연결 하나에서 처리할 최대 요청 수
+    #[serde(default = "default_admin_keep_alive_max_requests")]
+    pub max_requests: u32,
+}
+
+impl AdminKeepAliveConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.idle_timeout_seconds > 0 && self.max_requests > 1
+    }
+}
+
+impl Default for AdminKeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout_seconds: default_admin_keep_alive_idle_timeout_seconds(),
+            max_requests: default_admin_keep_alive_max_requests(),
+        }
+    }
+}
+
+/// 관리 연결 유휴 시간 최댓값
+pub const MAX_ADMIN_KEEP_ALIVE_IDLE_TIMEOUT_SECONDS: u64 = 300;
+
+fn default_admin_keep_alive_idle_timeout_seconds() -> u64 {
+    5
+}
+
+fn default_admin_keep_alive_max_requests() -> u32 {
+    100
+}
+
+/// 관리 엔드포인트 TLS (상호 인증) 설정
+///
+/// 활성화하면 관리 엔드포인트는 TLS로만 응답하고, 클라이언트 인증서의 SHA-256 지문이 목록에 있어야 핸드셰이크를 마칩니다.
+/// CA 서명 여부는 보지 않으므로 같은 CA가 발급한 다른 인증서로는 접속할 수 없습니다.
+/// `cert_file`이 PKCS#12 번들(.p12/.pfx)이면 인증서와 키를 번들에서 읽으며 `key_file`은 비워 둡니다.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AdminTlsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 관리 엔드포인트 서버 인증서 (체인 포함 PEM 또는 PKCS#12 번들)
+    #[serde(default)]
+    pub cert_file: String,
+    /// 서버 인증서 개인 키 (PEM)
+    #[serde(default)]
+    pub key_file: String,
+    /// 허용할 클라이언트 인증서 SHA-256 지문 (16진수, `:` 구분 허용, 대소문자 무시)
+    #[serde(default)]
+    pub client_cert_fingerprints: Vec<String>,
+}
+
+impl AdminTlsConfig {
+    /// 서버 인증서와 키를 PKCS#12 번들에서 읽는지
+    pub fn is_pkcs12(&self) -> bool {
+        crate::tls::pkcs12::is_pkcs12_path(&self.cert_file)
+    }
+}
+
+/// 출발지 IP 필터 동작 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceFilterMode {
+    /// 필터 사용 안 함
+    #[default]
+    Off,
+    /// 목록 대역에서 온 연결만 닫음
+    Deny,
+    /// 목록 대역에서 온 연결만 받음
+    Allow,
+}
+
+impl SourceFilterMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceFilterMode::Off => "off",
+            SourceFilterMode::Deny => "deny",
+            SourceFilterMode::Allow => "allow",
+        }
+    }
+}
+
+/// 프록시 리스너의 출발지 IP 필터 (연결 수락 직후, TLS/요청 파싱 전에 적용)
+///
+/// 목적지를 보는 ACL과 별개이며 모든 프록시 리스너(listeners 포함)에 적용됩니다. 재로드로 바꿀 수 있습니다.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SourceFilterConfig {
+    #[serde(default)]
+    pub mode: SourceFilterMode,
+    /// IP 주소 또는 CIDR (예: 203.0.113.0/24, 2001:db8::/32)
+    #[serde(default)]
+    pub cidrs: Vec<String>,
+}
+
+/// 클라이언트 주소 대역별 테넌트 태그 (세션 시작 시 결정해 로그, DB 기록, 메트릭스에 붙임)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TenantConfig {
+    /// 어느 대역에도 속하지 않는 클라이언트의 태그
+    #[serde(default = "default_tenant_tag")]
+    pub default_tag: String,
+    /// 주소 대역과 태그 (여러 대역에 속하면 접두사가 가장 긴 대역), 비어 있으면 비활성화
+    #[serde(default)]
+    pub ranges: Vec<TenantRange>,
+}
+
+impl Default for TenantConfig {
+    fn default() -> Self {
+        Self {
+            default_tag: default_tenant_tag(),
+            ranges: Vec::new(),
+        }
+    }
+}
+
+impl TenantConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.ranges.is_empty()
+    }
+}
+
+/// 테넌트 주소 대역 하나
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TenantRange {
+    /// IP 주소 또는 CIDR (예: 10.1.0.0/16, 2001:db8::/32)
+    pub cidr: String,
+    pub tag: String,
+}
+
+fn default_tenant_tag() -> String {
+    "default".to_string()
+}
+
+/// 미리 연결해 둘 업스트림 TLS 연결 풀 설정 (트래픽이 많은 고정 업스트림 전용)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WarmPoolConfig {
+    /// 미리 연결할 업스트림 (host:port, 포트 생략 시 443), 비어 있으면 비활성화
+    #[serde(default)]
+    pub upstreams: Vec<String>,
+    /// 업스트림당 유지할 연결 수
+    #[serde(default = "default_warm_pool_size")]
+    pub size: usize,
+    /// 이 시간보다 오래된 연결은 서버 유휴 타임아웃 전에 새 연결로 교체
+    #[serde(default = "default_warm_pool_refresh_seconds")]
+    pub refresh_seconds: u64,
+    /// 연결 상태 확인 및 보충 주기
+    #[serde(default = "default_warm_pool_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+}
+
+impl Default for WarmPoolConfig {
+    fn default() -> Self {
+        Self {
+            upstreams: Vec::new(),
+            size: default_warm_pool_size(),
+            refresh_seconds: default_warm_pool_refresh_seconds(),
+            check_interval_seconds: default_warm_pool_check_interval_seconds(),
+        }
+    }
+}
+
+fn default_warm_pool_size() -> usize {
+    2
+}
+
+fn default_warm_pool_refresh_seconds() -> u64 {
+    50
+}
+
+fn default_warm_pool_check_interval_seconds() -> u64 {
+    5
+}
+
+/// 클라이언트가 요청 헤더로 고를 수 있는 업스트림 출구 설정 (source_address, parent_proxy 중 하나)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EgressPool {
+    /// 업스트림 연결에 사용할 로컬 주소
+    #[serde(default)]
+    pub source_address: Option<std::net::IpAddr>,
+    /// 업스트림 연결을 CONNECT로 중계할 상위 프록시 (`host:port`)
+    #[serde(default)]
+    pub parent_proxy: Option<String>,
+}
+
+/// 업스트림 연결을 여러 상위 프록시에 나눠 보내는 설정 (proxies가 비어 있으면 직접 연결)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ParentProxiesConfig {
+    /// 상위 프록시 목록
+    #[serde(default)]
+    pub proxies: Vec<ParentProxy>,
+    /// 연결마다 상위 프록시를 고르는 방식
+    #[serde(default)]
+    pub strategy: ParentSelection,
+    /// 구간 내 연속 실패가 이 횟수에 도달하면 상위 프록시를 잠시 제외
+    #[serde(default = "default_parent_failure_threshold")]
+    pub failure_threshold: u32,
+    /// 연속 실패를 세는 구간
+    #[serde(default = "default_circuit_breaker_window_seconds")]
+    pub window_seconds: u64,
+    /// 제외한 상위 프록시를 다시 시험하기까지 기다리는 시간
+    #[serde(default = "default_circuit_breaker_cooldown_seconds")]
+    pub cooldown_seconds: u64,
+}
+
+impl Default for ParentProxiesConfig {
+    fn default() -> Self {
+        Self {
+            proxies: Vec::new(),
+            strategy: ParentSelection::default(),
+            failure_threshold: default_parent_failure_threshold(),
+            window_seconds: default_circuit_breaker_window_seconds(),
+            cooldown_seconds: default_circuit_breaker_cooldown_seconds(),
+        }
+    }
+}
+
+fn default_parent_failure_threshold() -> u32 {
+    3
+}
+
+/// 상위 프록시 한 개
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ParentProxy {
+    /// 상위 프록시 주소 (`host:port`)
+    pub address: String,
+    /// 분배 비중 (0이면 새 연결을 보내지 않음)
+    #[serde(default = "default_parent_weight")]
+    pub weight: u32,
+}
+
+fn default_parent_weight() -> u32 {
+    1
+}
+
+/// 상위 프록시 선택 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParentSelection {
+    /// 비중에 비례한 무작위 선택
+    #[default]
+    WeightedRandom,
+    /// 비중을 반영한 순환 선택 (비중 2:1이면 a, b, a 순서로 고르게 섞음)
+    RoundRobin,
+}
+
+impl ParentSelection {
+    /// 로그용 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParentSelection::WeightedRandom => "weighted_random",
+            ParentSelection::RoundRobin => "round_robin",
+        }
+    }
+}
+
+/// 가로챈 HTTP 요청/응답에 추가하는 프록시 식별 헤더 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProxyHeadersConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Via 헤더에 실제 호스트명 대신 넣을 이름
+    #[serde(default = "default_via_pseudonym")]
+    pub via_pseudonym: String,
+    /// X-Proxy 헤더 값 (없으면 X-Proxy 헤더 생략)
+    #[serde(default)]
+    pub x_proxy: Option<String>,
+}
+
+impl Default for ProxyHeadersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            via_pseudonym: default_via_pseudonym(),
+            x_proxy: None,
+        }
+    }
+}
+
+fn default_via_pseudonym() -> String {
+    "udss-proxy".to_string()
+}
+
+/// 보안 헤더를 응답에 넣는 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityHeaderMode {
+    /// 응답에 같은 이름의 헤더가 없을 때만 추가
+    #[default]
+    IfAbsent,
+    /// 응답의 같은 이름 헤더를 모두 지우고 설정 값으로 교체
+    Overwrite,
+}
+
+/// 미리 정해 둔 보안 헤더 묶음
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityHeaderPreset {
+    /// 묶음 없이 `headers`만 사용
+    #[default]
+    None,
+    /// 페이지 동작을 거의 바꾸지 않는 헤더 (MIME 스니핑, 다른 출처 프레임, Referer 축소)
+    Basic,
+    /// basic보다 엄격한 값에 HSTS와 프레임 금지 CSP 추가
+    Strict,
+}
+
+impl SecurityHeaderPreset {
+    /// 묶음에 포함된 (헤더 이름, 값) 목록
+    pub fn headers(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            SecurityHeaderPreset::None => &[],
+            SecurityHeaderPreset::Basic => &[
+                ("X-Content-Type-Options", "nosniff"),
+                ("X-Frame-Options", "SAMEORIGIN"),
+                ("Referrer-Policy", "strict-origin-when-cross-origin"),
+            ],
+            SecurityHeaderPreset::Strict => &[
+                ("X-Content-Type-Options", "nosniff"),
+                ("X-Frame-Options", "DENY"),
+                ("Referrer-Policy", "no-referrer"),
+                ("Strict-Transport-Security", "max-age=31536000; includeSubDomains"),
+                ("Content-Security-Policy", "frame-ancestors 'none'"),
+            ],
+        }
+    }
+}
+
+/// 직접 지정하는 보안 헤더 (mode가 없으면 security_headers.mode 사용)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SecurityHeaderRule {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub mode: Option<SecurityHeaderMode>,
+}
+
+/// 삽입기가 바꾸면 메시지 경계가 깨지는 헤더 (security_headers에 지정할 수 없음)
+const SECURITY_HEADER_FORBIDDEN: [&str; 5] = ["content-length", "transfer-encoding", "connection", "upgrade", "trailer"];
+
+/// 가로챈 HTTPS 응답에 넣는 보안 헤더 (CSP, HSTS 등) 설정
+///
+/// 가로채지 않는 터널은 내용을 볼 수 없으므로 적용하지 않습니다.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SecurityHeadersConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 기본 헤더 묶음 (`headers`에 같은 이름이 있으면 그 값이 우선)
+    #[serde(default)]
+    pub preset: SecurityHeaderPreset,
+    /// 헤더별 mode가 없을 때의 삽입 방식
+    #[serde(default)]
+    pub mode: SecurityHeaderMode,
+    #[serde(default)]
+    pub headers: Vec<SecurityHeaderRule>,
+}
+
+impl SecurityHeadersConfig {
+    /// 묶음과 직접 지정한 헤더를 합친 최종 목록 (이름은 대소문자 무시로 중복 제거, 나중 값 우선)
+    pub fn resolved(&self) -> Vec<SecurityHeaderRule> {
+        let mut rules: Vec<SecurityHeaderRule> = self.preset.headers().iter()
+            .map(|(name, value)| SecurityHeaderRule { name: name.to_string(), value: value.to_string(), mode: Some(self.mode) })
+            .collect();
+        for rule in &self.headers {
+            rules.retain(|existing| !existing.name.eq_ignore_ascii_case(&rule.name));
+            rules.push(SecurityHeaderRule { name: rule.name.trim().to_string(), value: rule.value.trim().to_string(),
+                                            mode: Some(rule.mode.unwrap_or(self.mode)) });
+        }
+        rules
+    }
+}
+
+/// 이벤트 웹훅 엔드포인트 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// POST 대상 URL (http:// 또는 https://)
+    pub url: String,
+    /// 전송할 이벤트 목록 (비어 있으면 모든 이벤트)
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// 설정 덤프에서 값을 가리는 비밀 값 키 이름 (키 전체 또는 `_`/`-` 뒤 끝 단어, 설정과 DB 설정 공통)
+const SECRET_KEY_WORDS: [&str; 6] = ["token", "secret", "key", "password", "passphrase", "dsn"];
+
+/// 비밀 값 키인지 (`api_token`, `client-secret`, `private_key`, `db_password`처럼 끝 단어가 비밀 값 이름, 대소문자 무시)
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_WORDS.iter().any(|word| {
+        key.strip_suffix(word).is_some_and(|rest| rest.is_empty() || rest.ends_with('_') || rest.ends_with('-'))
+    })
+}
+
+/// 직렬화한 설정에서 비밀 값 키의 값을 가립니다 (설정하지 않은 null 값은 그대로 둠).
+///
+/// 직렬화 자체는 실제 값을 유지하므로 설정 비교나 재직렬화에는 영향이 없습니다.
+pub fn redact_secrets(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, value) in map.iter_mut() {
+                let secret = key.as_str().is_some_and(is_secret_key);
+                if secret && !value.is_null() {
+                    *value = serde_yaml::Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        },
+        serde_yaml::Value::Sequence(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {},
+    }
 }
 
 fn default_disable_verify_internal_ip() -> bool {
@@ -54,6 +2047,59 @@ fn default_cache_ttl_seconds() -> u64 {
     300
 }
 
+fn default_listen_backlog() -> u32 {
+    1024
+}
+
+fn default_accept_batch_size() -> usize {
+    1
+}
+
+/// SO_REUSEPORT 리스너 최대 수
+pub const MAX_REUSEPORT_LISTENERS: usize = 256;
+
+fn default_upstream_pool_max_idle_per_host() -> usize {
+    8
+}
+
+fn default_upstream_pool_idle_timeout_seconds() -> u64 {
+    60
+}
+
+fn default_tls_exemption_refresh_seconds() -> u64 {
+    300
+}
+
+fn default_tunnel_detect_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_dns_timeout_ms() -> u64 {
+    3000
+}
+
+fn default_circuit_breaker_failure_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_breaker_window_seconds() -> u64 {
+    30
+}
+
+fn default_circuit_breaker_cooldown_seconds() -> u64 {
+    30
+}
+
+fn default_worker_metrics_interval_seconds() -> u64 {
+    60
+}
+
+/// 세션 펌프 1회 읽기 최대 크기 (방향마다 이만큼의 버퍼를 잡으므로 메모리 사용량 제한)
+pub const MAX_READ_CHUNK_SIZE: usize = 16 * BUFFER_SIZE_LARGE;
+
+/// 최근 로그 링 버퍼 최대 크기 (기록 하나가 최대 수 KB이므로 메모리 사용량 제한)
+pub const MAX_DEBUG_LOG_BUFFER_SIZE: usize = 100_000;
+
 impl Config {
     /// 기본 설정으로 Config 인스턴스 생성
     pub fn new() -> Self {
@@ -61,6 +2107,7 @@ impl Config {
             bind_host: "0.0.0.0".to_string(),
             bind_port: 50000,
             buffer_size: 32768,
+            read_chunk_size: None,
             timeout_ms: 60000,
             ssl_dir: "ssl".to_string(),
             worker_threads: None,
@@ -68,10 +2115,91 @@ impl Config {
             disable_verify_internal_ip: default_disable_verify_internal_ip(),
             blocked_domains: HashSet::new(),
             blocked_patterns: HashSet::new(),
+            blocked_filter_lists: Vec::new(),
+            blocked_rule_expiry: BTreeMap::new(),
             trusted_certificates: Vec::new(),
             cache_enabled: default_cache_enabled(),
             cache_size: default_cache_size(),
             cache_ttl_seconds: default_cache_ttl_seconds(),
+            listen_backlog: default_listen_backlog(),
+            accept_batch_size: default_accept_batch_size(),
+            reuseport_enabled: false,
+            reuseport_listeners: 0,
+            listeners: Vec::new(),
+            webhooks: Vec::new(),
+            sni_overrides: HashMap::new(),
+            static_hosts: HashMap::new(),
+            dns_prewarm: DnsPrewarmConfig::default(),
+            upstream_pool_enabled: false,
+            upstream_pool_max_idle_per_host: default_upstream_pool_max_idle_per_host(),
+            upstream_pool_idle_timeout_seconds: default_upstream_pool_idle_timeout_seconds(),
+            leaf_cert: LeafCertTemplate::default(),
+            upstream_cert_tiers: Vec::new(),
+            root_ca: RootCaConfig::default(),
+            pkcs12: Pkcs12Config::default(),
+            tls_resumption: TlsResumptionConfig::default(),
+            wildcard_certs: WildcardCertConfig::default(),
+            cert_cache_alert: CertCacheAlertConfig::default(),
+            warm_pool: WarmPoolConfig::default(),
+            no_sni_action: NoSniAction::default(),
+            client_hello_peek: ClientHelloPeekConfig::default(),
+            client_hello_mirroring: false,
+            connect_pipelining: ConnectPipelinePolicy::default(),
+            tls_exempt_hosts: HashSet::new(),
+            tls_exemption_refresh_seconds: default_tls_exemption_refresh_seconds(),
+            socks_enabled: false,
+            egress_header: None,
+            egress_pools: HashMap::new(),
+            parent_proxies: ParentProxiesConfig::default(),
+            proxy_headers: ProxyHeadersConfig::default(),
+            security_headers: SecurityHeadersConfig::default(),
+            acl_mode: AclMode::default(),
+            acl_actions: AclActionsConfig::default(),
+            acl_failure_policy: AclFailurePolicy::default(),
+            acl_conflict_precedence: AclConflictPrecedence::default(),
+            connect_acl: ConnectAclConfig::default(),
+            connect_acl_policies: HashMap::new(),
+            max_session_duration_seconds: None,
+            dns_timeout_ms: default_dns_timeout_ms(),
+            tunnel_detect_timeout_ms: default_tunnel_detect_timeout_ms(),
+            tunnel_detect_timeout_action: TunnelDetectTimeoutAction::default(),
+            connect_fallback: ConnectFallbackConfig::default(),
+            upstream_ip_family: IpFamilyPolicy::default(),
+            passthrough_content_types: Vec::new(),
+            inspection_limit: InspectionLimitConfig::default(),
+            max_inspection_bytes: default_max_inspection_bytes(),
+            error_pages: ErrorPagesConfig::default(),
+            write_coalescing: WriteCoalescingConfig::default(),
+            flow_control: FlowControlConfig::default(),
+            circuit_breaker_enabled: false,
+            circuit_breaker_failure_threshold: default_circuit_breaker_failure_threshold(),
+            circuit_breaker_window_seconds: default_circuit_breaker_window_seconds(),
+            circuit_breaker_cooldown_seconds: default_circuit_breaker_cooldown_seconds(),
+            upstream_host_limit: UpstreamHostLimitConfig::default(),
+            upstream_dscp: UpstreamDscpConfig::default(),
+            client_socket_buffers: SocketBufferConfig::default(),
+            upstream_socket_buffers: SocketBufferConfig::default(),
+            category_throttle: CategoryThrottleConfig::default(),
+            overload: OverloadConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            worker_metrics_enabled: false,
+            worker_metrics_interval_seconds: default_worker_metrics_interval_seconds(),
+            upstream_timing_enabled: false,
+            tls_params_logging: false,
+            upstream_cert_logging: UpstreamCertLoggingConfig::default(),
+            admin_bind: None,
+            admin_allowed_sources: Vec::new(),
+            admin_keep_alive: AdminKeepAliveConfig::default(),
+            admin_tls: AdminTlsConfig::default(),
+            debug_log_buffer_size: 0,
+            pac: PacConfig::default(),
+            ca_download: CaDownloadConfig::default(),
+            event_stream: EventStreamConfig::default(),
+            tenants: TenantConfig::default(),
+            source_filter: SourceFilterConfig::default(),
+            influx: InfluxExportConfig::default(),
+            graceful_shutdown: GracefulShutdownConfig::default(),
+            access_log: AccessLogConfig::default(),
         }
     }
 
@@ -85,13 +2213,47 @@ impl Config {
         HashSet::new()
     }
 
-    /// 설정 파일에서 Config 인스턴스 로드
-    pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
-        let mut file = File::open(path)?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
+    /// 설정 파일에서 Config 인스턴스 로드 (경로가 `-`이면 표준 입력 전체를 읽고, 오류는 표준 입력에서 온 것으로 보고)
+    pub fn from_file(path: &str, format: ConfigFormat) -> Result<Self, Box<dyn Error>> {
+        if path != STDIN_CONFIG_PATH {
+            let mut file = File::open(path)?;
+            let mut contents = String::new();
+            file.read_to_string(&mut contents)?;
+            return Self::from_str_with_format(&contents, format);
+        }
+
+        let contents = match STDIN_CONFIG.get() {
+            Some(contents) => contents,
+            None => {
+                let mut contents = String::new();
+                std::io::stdin().read_to_string(&mut contents)
+                    .map_err(|e| format!("표준 입력에서 설정 읽기 실패: {}", e))?;
+                STDIN_CONFIG.get_or_init(|| contents)
+            },
+        };
+        if contents.trim().is_empty() {
+            return Err("표준 입력에서 읽은 설정이 비어 있습니다".into());
+        }
+        Self::from_str_with_format(contents, format)
+            .map_err(|e| format!("표준 입력 설정({}) 해석 실패: {}", format.name(), e).into())
+    }
 
-        let mut config: Self = serde_yaml::from_str(&contents)?;
+    /// 설정 원문 해석 (환경 변수 덮어쓰기와 차단 패턴 컴파일 포함)
+    pub fn from_str_with_format(contents: &str, format: ConfigFormat) -> Result<Self, Box<dyn Error>> {
+        // 환경 변수 덮어쓰기가 없으면 위치 정보가 있는 오류를 위해 원문에서 바로 해석
+        let mut raw: serde_yaml::Value = match format {
+            ConfigFormat::Yaml => serde_yaml::from_str(contents)?,
+            ConfigFormat::Json => serde_json::from_str(contents)?,
+        };
+        let overrides = env::apply_env_overrides(&mut raw)?;
+        let mut config: Self = if overrides.is_empty() {
+            match format {
+                ConfigFormat::Yaml => serde_yaml::from_str(contents)?,
+                ConfigFormat::Json => serde_json::from_str(contents)?,
+            }
+        } else {
+            Self::from_overridden_value(raw, &overrides)?
+        };
         
         // 파일에서 로드한 설정에 기본 차단 도메인이 없으면 추가
         if config.blocked_domains.is_empty() {
@@ -123,6 +2285,617 @@ impl Config {
         Ok(config)
     }
     
+    /// 기본 설정에 환경 변수 덮어쓰기 적용 (설정 파일이 없을 때)
+    pub fn with_env_overrides(self) -> Result<Self, Box<dyn Error>> {
+        let mut raw = serde_yaml::to_value(&self)?;
+        let overrides = env::apply_env_overrides(&mut raw)?;
+        if overrides.is_empty() {
+            return Ok(self);
+        }
+        Self::from_overridden_value(raw, &overrides)
+    }
+
+    /// 환경 변수를 덮어쓴 설정 값 해석 (실패하면 적용한 변수 이름을 함께 보고)
+    fn from_overridden_value(raw: serde_yaml::Value, overrides: &[String]) -> Result<Self, Box<dyn Error>> {
+        let config = serde_yaml::from_value(raw)
+            .map_err(|e| format!("환경 변수 덮어쓰기({}) 적용 후 설정 해석 실패: {}", overrides.join(", "), e))?;
+        info!("환경 변수로 덮어쓴 설정: {}", overrides.join(", "));
+        Ok(config)
+    }
+    
+    /// 차단 규칙 일치 시 동작 설정 검증
+    fn validate_acl_actions(&self) -> Result<(), Box<dyn Error>> {
+        let actions = &self.acl_actions;
+        if actions.tarpit_max_hold_seconds == 0 || actions.tarpit_interval_ms == 0 {
+            return Err("acl_actions.tarpit_max_hold_seconds와 acl_actions.tarpit_interval_ms는 0보다 커야 합니다".into());
+        }
+        if actions.tarpit_interval_ms > actions.tarpit_max_hold_seconds * 1000 {
+            return Err("acl_actions.tarpit_interval_ms는 tarpit_max_hold_seconds보다 길 수 없습니다".into());
+        }
+
+        let check_url = |url: &str| -> Result<(), Box<dyn Error>> {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(format!("acl_actions redirect_url은 http:// 또는 https://로 시작해야 합니다: {}", url).into());
+            }
+            if url.chars().any(|c| c.is_ascii_control() || c == ' ') {
+                return Err(format!("acl_actions redirect_url에 공백/제어 문자가 있습니다: {:?}", url).into());
+            }
+            Ok(())
+        };
+        if let Some(url) = &actions.redirect_url {
+            check_url(url)?;
+        }
+        if actions.default == AclAction::Redirect && actions.redirect_url.is_none() {
+            return Err("acl_actions.default가 redirect인 경우 acl_actions.redirect_url이 필요합니다".into());
+        }
+
+        for rule in &actions.rules {
+            if rule.rule.trim().is_empty() {
+                return Err("acl_actions.rules에 빈 rule이 있습니다".into());
+            }
+            if let Some(kind) = rule.rule.strip_prefix("kind:")
+                && !["exact", "wildcard", "regex", "filter"].contains(&kind) {
+                return Err(format!("acl_actions.rules 규칙 유형 오류: {} (kind:exact | kind:wildcard | kind:regex | kind:filter)", rule.rule).into());
+            }
+            if let Some(url) = &rule.redirect_url {
+                check_url(url)?;
+            }
+            if rule.action == AclAction::Redirect && rule.redirect_url.is_none() && actions.redirect_url.is_none() {
+                return Err(format!("acl_actions.rules의 redirect 동작에 이동 주소가 없습니다: {}", rule.rule).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// 루트 CA 목록 검증 (이름 중복, 서명/교차 서명 CA 이름 확인)
+    fn validate_root_ca(&self) -> Result<(), Box<dyn Error>> {
+        let root_ca = &self.root_ca;
+        let mut names = HashSet::new();
+        for ca in &root_ca.cas {
+            if ca.is_pkcs12() {
+                if ca.name.trim().is_empty() || !ca.key_file.trim().is_empty() {
+                    return Err(format!("root_ca.cas의 PKCS#12 번들 항목에는 name, cert_file만 지정합니다 (키는 번들에서 읽음): {}", ca.cert_file).into());
+                }
+                if !std::path::Path::new(&ca.cert_file).exists() {
+                    return Err(format!("root_ca.cas의 PKCS#12 번들 파일이 없습니다: {}", ca.cert_file).into());
+                }
+            } else if ca.name.trim().is_empty() || ca.cert_file.trim().is_empty() || ca.key_file.trim().is_empty() {
+                return Err("root_ca.cas 항목에는 name, cert_file, key_file이 모두 필요합니다".into());
+            }
+            if !names.insert(ca.name.as_str()) {
+                return Err(format!("root_ca.cas에 같은 이름이 여러 번 있습니다: {}", ca.name).into());
+            }
+        }
+        for (key, name) in [("active", &root_ca.active), ("cross_sign_with", &root_ca.cross_sign_with)] {
+            if let Some(name) = name {
+                if root_ca.cas.is_empty() {
+                    return Err(format!("root_ca.{}를 쓰려면 root_ca.cas가 필요합니다", key).into());
+                }
+                if !names.contains(name.as_str()) {
+                    return Err(format!("root_ca.{}에 지정한 CA가 root_ca.cas에 없습니다: {}", key, name).into());
+                }
+            }
+        }
+        let active = root_ca.active.as_deref().or(root_ca.cas.first().map(|ca| ca.name.as_str()));
+        if root_ca.cross_sign_with.is_some() && root_ca.cross_sign_with.as_deref() == active {
+            return Err("root_ca.cross_sign_with는 서명 CA(active)와 달라야 합니다".into());
+        }
+        Ok(())
+    }
+    
+    /// 설정 값 검증
+    pub fn validate(&self) -> Result<(), Box<dyn Error>> {
+        if self.bind_host.trim().is_empty() {
+            return Err("bind_host가 비어 있습니다".into());
+        }
+        if self.buffer_size == 0 {
+            return Err("buffer_size는 0보다 커야 합니다".into());
+        }
+        if let Some(size) = self.read_chunk_size
+            && !(1..=MAX_READ_CHUNK_SIZE).contains(&size) {
+            return Err(format!("read_chunk_size는 1 ~ {} 범위여야 합니다: {}", MAX_READ_CHUNK_SIZE, size).into());
+        }
+        if self.timeout_ms == 0 {
+            return Err("timeout_ms는 0보다 커야 합니다".into());
+        }
+        if self.worker_threads == Some(0) {
+            return Err("worker_threads는 0보다 커야 합니다".into());
+        }
+        if self.listen_backlog == 0 || self.listen_backlog > i32::MAX as u32 {
+            return Err(format!("listen_backlog 범위 오류: {} (1 ~ {})", self.listen_backlog, i32::MAX).into());
+        }
+        if self.dns_timeout_ms == 0 {
+            return Err("dns_timeout_ms는 0보다 커야 합니다".into());
+        }
+        if self.tunnel_detect_timeout_ms == 0 {
+            return Err("tunnel_detect_timeout_ms는 0보다 커야 합니다".into());
+        }
+        if self.connect_fallback.attempts_per_family == 0 || self.connect_fallback.attempt_timeout_ms == 0 {
+            return Err("connect_fallback.attempts_per_family와 connect_fallback.attempt_timeout_ms는 0보다 커야 합니다".into());
+        }
+        if self.accept_batch_size == 0 {
+            return Err("accept_batch_size는 0보다 커야 합니다".into());
+        }
+        if self.reuseport_listeners > MAX_REUSEPORT_LISTENERS {
+            return Err(format!("reuseport_listeners는 {} 이하여야 합니다: {}", MAX_REUSEPORT_LISTENERS, self.reuseport_listeners).into());
+        }
+        if self.cache_enabled && self.cache_size == 0 {
+            return Err("cache_enabled가 true인 경우 cache_size는 0보다 커야 합니다".into());
+        }
+        if let Some(rule) = self.blocked_rule_expiry.keys()
+            .find(|rule| !self.blocked_domains.contains(*rule) && !self.blocked_patterns.contains(*rule)) {
+            return Err(format!("blocked_rule_expiry의 규칙이 blocked_domains, blocked_patterns에 없습니다: {}", rule).into());
+        }
+        if let Some(path) = self.blocked_filter_lists.iter().find(|path| !std::path::Path::new(path).is_file()) {
+            return Err(format!("blocked_filter_lists 파일을 찾을 수 없습니다: {}", path).into());
+        }
+        for pattern in &self.blocked_patterns {
+            if let Some(regex_pattern) = pattern.strip_prefix("regex:") {
+                Regex::new(regex_pattern)
+                    .map_err(|e| format!("잘못된 정규표현식 패턴: {} - {}", regex_pattern, e))?;
+            }
+        }
+        self.validate_acl_actions()?;
+        if self.max_session_duration_seconds == Some(0) {
+            return Err("max_session_duration_seconds는 0보다 커야 합니다 (제한 없음은 null)".into());
+        }
+        if let Some(limit) = self.inspection_limit.max_response_bytes
+            && limit < MIN_INSPECTION_RESPONSE_BYTES {
+            return Err(format!("inspection_limit.max_response_bytes는 {} 이상이어야 합니다 (제한 없음은 null): {}",
+                               MIN_INSPECTION_RESPONSE_BYTES, limit).into());
+        }
+        if self.max_inspection_bytes < MIN_INSPECTION_RESPONSE_BYTES {
+            return Err(format!("max_inspection_bytes는 {} 이상이어야 합니다: {}",
+                               MIN_INSPECTION_RESPONSE_BYTES, self.max_inspection_bytes).into());
+        }
+        if self.write_coalescing.max_bytes > BUFFER_SIZE_MEDIUM {
+            return Err(format!("write_coalescing.max_bytes는 {} 이하여야 합니다: {}",
+                               BUFFER_SIZE_MEDIUM, self.write_coalescing.max_bytes).into());
+        }
+        if self.write_coalescing.is_enabled()
+            && (self.write_coalescing.max_delay_ms == 0 || self.write_coalescing.max_delay_ms > MAX_WRITE_COALESCING_DELAY_MS) {
+            return Err(format!("write_coalescing.max_delay_ms는 1~{} 사이여야 합니다: {}",
+                               MAX_WRITE_COALESCING_DELAY_MS, self.write_coalescing.max_delay_ms).into());
+        }
+        if self.flow_control.is_enabled()
+            && !(BUFFER_SIZE_SMALL..=MAX_FLOW_CONTROL_HIGH_WATERMARK).contains(&self.flow_control.high_watermark) {
+            return Err(format!("flow_control.high_watermark는 0 또는 {}~{} 사이여야 합니다: {}",
+                               BUFFER_SIZE_SMALL, MAX_FLOW_CONTROL_HIGH_WATERMARK, self.flow_control.high_watermark).into());
+        }
+        if self.flow_control.is_enabled() && self.flow_control.low_watermark >= self.flow_control.high_watermark {
+            return Err(format!("flow_control.low_watermark는 high_watermark보다 작아야 합니다: {} >= {}",
+                               self.flow_control.low_watermark, self.flow_control.high_watermark).into());
+        }
+        for (name, path) in [("html_template", &self.error_pages.html_template), ("json_template", &self.error_pages.json_template)] {
+            if let Some(path) = path
+                && !std::path::Path::new(path).is_file() {
+                return Err(format!("error_pages.{} 파일이 없습니다: {}", name, path).into());
+            }
+        }
+        for content_type in &self.passthrough_content_types {
+            if !content_type.contains('/') {
+                return Err(format!("passthrough_content_types 형식 오류: {} (예: video/*, application/octet-stream)", content_type).into());
+            }
+        }
+        if self.circuit_breaker_enabled {
+            if self.circuit_breaker_failure_threshold == 0 {
+                return Err("circuit_breaker_failure_threshold는 0보다 커야 합니다".into());
+            }
+            if self.circuit_breaker_window_seconds == 0 || self.circuit_breaker_cooldown_seconds == 0 {
+                return Err("circuit_breaker_window_seconds와 circuit_breaker_cooldown_seconds는 0보다 커야 합니다".into());
+            }
+        }
+        if self.upstream_host_limit.is_enabled() && self.upstream_host_limit.idle_expiry_seconds == 0 {
+            return Err("upstream_host_limit.idle_expiry_seconds는 0보다 커야 합니다".into());
+        }
+        if self.upstream_host_limit.overrides.keys().any(|host| host.trim().is_empty()) {
+            return Err("upstream_host_limit.overrides에 빈 호스트가 있습니다".into());
+        }
+        if let Some(dscp) = self.upstream_dscp.default
+            && dscp > MAX_DSCP {
+            return Err(format!("upstream_dscp.default는 0 ~ {}이어야 합니다: {}", MAX_DSCP, dscp).into());
+        }
+        for (host, dscp) in &self.upstream_dscp.overrides {
+            if host.trim().is_empty() || host.trim_start_matches("*.").contains('*') {
+                return Err(format!("upstream_dscp.overrides 호스트 형식 오류 (호스트 또는 *.도메인): {:?}", host).into());
+            }
+            if *dscp > MAX_DSCP {
+                return Err(format!("upstream_dscp.overrides[{}]는 0 ~ {}이어야 합니다: {}", host, MAX_DSCP, dscp).into());
+            }
+        }
+        for category in &self.category_throttle.categories {
+            if category.name.trim().is_empty() {
+                return Err("category_throttle.categories에 빈 name이 있습니다".into());
+            }
+            if category.hosts.is_empty() {
+                return Err(format!("category_throttle.categories[{}]의 hosts가 비어 있습니다", category.name).into());
+            }
+            if let Some(host) = category.hosts.iter().find(|h| h.trim().is_empty() || h.trim_start_matches("*.").contains('*')) {
+                return Err(format!("category_throttle.categories[{}] 호스트 형식 오류 (호스트 또는 *.도메인): {:?}", category.name, host).into());
+            }
+            if category.bytes_per_second > u32::MAX as u64 {
+                return Err(format!("category_throttle.categories[{}].bytes_per_second는 {} 이하여야 합니다", category.name, u32::MAX).into());
+            }
+        }
+        if self.category_throttle.default_bytes_per_second > u32::MAX as u64 {
+            return Err(format!("category_throttle.default_bytes_per_second는 {} 이하여야 합니다", u32::MAX).into());
+        }
+        if self.overload.retry_after_seconds == 0 {
+            return Err("overload.retry_after_seconds는 0보다 커야 합니다".into());
+        }
+        if self.maintenance.retry_after_seconds == 0 {
+            return Err("maintenance.retry_after_seconds는 0보다 커야 합니다".into());
+        }
+        if let Some(page_file) = &self.maintenance.page_file
+            && !std::path::Path::new(page_file).is_file() {
+            return Err(format!("maintenance.page_file 파일이 없습니다: {}", page_file).into());
+        }
+        if self.worker_metrics_enabled && self.worker_metrics_interval_seconds == 0 {
+            return Err("worker_metrics_enabled가 true인 경우 worker_metrics_interval_seconds는 0보다 커야 합니다".into());
+        }
+        if let Some(collector) = &self.influx.collector {
+            let port = collector.rsplit_once(':').and_then(|(_, port)| port.parse::<u16>().ok());
+            if !matches!(port, Some(port) if port > 0) {
+                return Err(format!("influx.collector 형식 오류 (예: 127.0.0.1:8089): {}", collector).into());
+            }
+            if self.influx.interval_seconds == 0 {
+                return Err("influx.interval_seconds는 0보다 커야 합니다".into());
+            }
+            if self.influx.measurement.trim().is_empty() {
+                return Err("influx.measurement가 비어 있습니다".into());
+            }
+            if self.influx.tags.iter().any(|(key, value)| key.is_empty() || value.is_empty()) {
+                return Err("influx.tags의 키와 값은 비어 있을 수 없습니다".into());
+            }
+        }
+        if let Some(admin_bind) = &self.admin_bind
+            && admin_bind.parse::<std::net::SocketAddr>().is_err() {
+            return Err(format!("admin_bind 형식 오류 (예: 127.0.0.1:50080): {}", admin_bind).into());
+        }
+        for source in &self.admin_allowed_sources {
+            crate::server::tenant::parse_cidr(source)
+                .map_err(|e| format!("admin_allowed_sources 형식 오류: {:?} ({})", source, e))?;
+        }
+        if self.admin_keep_alive.idle_timeout_seconds > MAX_ADMIN_KEEP_ALIVE_IDLE_TIMEOUT_SECONDS {
+            return Err(format!("admin_keep_alive.idle_timeout_seconds는 {} 이하여야 합니다: {}",
+                               MAX_ADMIN_KEEP_ALIVE_IDLE_TIMEOUT_SECONDS, self.admin_keep_alive.idle_timeout_seconds).into());
+        }
+        if self.admin_keep_alive.max_requests == 0 {
+            return Err("admin_keep_alive.max_requests는 1 이상이어야 합니다".into());
+        }
+        if self.admin_tls.enabled {
+            if self.admin_bind.is_none() {
+                return Err("admin_tls.enabled에는 admin_bind가 필요합니다".into());
+            }
+            if self.admin_tls.cert_file.trim().is_empty() || (!self.admin_tls.is_pkcs12() && self.admin_tls.key_file.trim().is_empty()) {
+                return Err("admin_tls에는 cert_file과 key_file이 필요합니다 (PKCS#12 번들이면 cert_file만)".into());
+            }
+            if self.admin_tls.client_cert_fingerprints.is_empty() {
+                return Err("admin_tls.client_cert_fingerprints에 허용할 클라이언트 인증서 지문이 하나 이상 필요합니다".into());
+            }
+            for fingerprint in &self.admin_tls.client_cert_fingerprints {
+                crate::admin::tls::parse_fingerprint(fingerprint)
+                    .map_err(|e| format!("admin_tls.client_cert_fingerprints 형식 오류: {:?} ({})", fingerprint, e))?;
+            }
+        }
+        if self.event_stream.max_clients > MAX_EVENT_STREAM_CLIENTS {
+            return Err(format!("event_stream.max_clients는 {} 이하여야 합니다: {}", MAX_EVENT_STREAM_CLIENTS, self.event_stream.max_clients).into());
+        }
+        if !(MIN_EVENT_STREAM_BUFFER_SIZE..=MAX_EVENT_STREAM_BUFFER_SIZE).contains(&self.event_stream.buffer_size) {
+            return Err(format!("event_stream.buffer_size는 {} ~ {} 사이여야 합니다: {}",
+                               MIN_EVENT_STREAM_BUFFER_SIZE, MAX_EVENT_STREAM_BUFFER_SIZE, self.event_stream.buffer_size).into());
+        }
+        if self.debug_log_buffer_size > MAX_DEBUG_LOG_BUFFER_SIZE {
+            return Err(format!("debug_log_buffer_size는 {} 이하여야 합니다: {}", MAX_DEBUG_LOG_BUFFER_SIZE, self.debug_log_buffer_size).into());
+        }
+        if self.pac.enabled {
+            if self.pac.file.is_none() && self.pac.proxy.is_none()
+                && self.bind_host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_unspecified()) {
+                return Err(format!("bind_host가 {}이면 pac.proxy에 클라이언트가 접속할 프록시 주소를 지정해야 합니다", self.bind_host).into());
+            }
+            if let Some(host) = self.pac.direct_hosts.iter().find(|h| h.trim().is_empty() || h.trim_start_matches("*.").contains('*')) {
+                return Err(format!("pac.direct_hosts 형식 오류 (호스트 또는 *.도메인): {:?}", host).into());
+            }
+        }
+        if let Some(public_bind) = &self.ca_download.public_bind {
+            if public_bind.parse::<std::net::SocketAddr>().is_err() {
+                return Err(format!("ca_download.public_bind 형식 오류 (예: 0.0.0.0:50081): {}", public_bind).into());
+            }
+            if self.admin_bind.as_deref() == Some(public_bind.as_str()) {
+                return Err("ca_download.public_bind는 admin_bind와 달라야 합니다".into());
+            }
+        }
+        validate_connect_acl("connect_acl", &self.connect_acl)?;
+        for (name, policy) in &self.connect_acl_policies {
+            validate_connect_acl(&format!("connect_acl_policies.{}", name), policy)?;
+        }
+        let mut listener_names = HashSet::new();
+        for listener in &self.listeners {
+            let name = listener.name.trim();
+            if name.is_empty() || !listener_names.insert(name) {
+                return Err(format!("listeners 이름이 비어 있거나 중복됩니다: {:?}", listener.name).into());
+            }
+            if listener.bind_port == 0 && self.bind_port != 0 {
+                return Err(format!("listeners.{}.bind_port는 0보다 커야 합니다", name).into());
+            }
+            if let Some(policy) = &listener.connect_acl
+                && !self.connect_acl_policies.contains_key(policy) {
+                return Err(format!("listeners.{}.connect_acl이 참조하는 정책이 connect_acl_policies에 없습니다: {}", name, policy).into());
+            }
+            if let Some(host) = listener.bypass_hosts.iter().find(|host| !crate::tls::exemption::is_valid_intercept_host(host)) {
+                return Err(format!("listeners.{}.bypass_hosts 형식 오류 (호스트 또는 *.도메인): {:?}", name, host).into());
+            }
+        }
+        if self.tenants.is_enabled() {
+            let valid_tag = |tag: &str| !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            if !valid_tag(&self.tenants.default_tag) {
+                return Err(format!("tenants.default_tag 형식 오류 (영문, 숫자, -_. 만 사용): {:?}", self.tenants.default_tag).into());
+            }
+            for range in &self.tenants.ranges {
+                crate::server::tenant::parse_cidr(&range.cidr)
+                    .map_err(|e| format!("tenants.ranges cidr 형식 오류: {:?} ({})", range.cidr, e))?;
+                if !valid_tag(&range.tag) {
+                    return Err(format!("tenants.ranges tag 형식 오류 (영문, 숫자, -_. 만 사용): {:?}", range.tag).into());
+                }
+            }
+        }
+        for cidr in &self.source_filter.cidrs {
+            crate::server::tenant::parse_cidr(cidr)
+                .map_err(|e| format!("source_filter.cidrs 형식 오류: {:?} ({})", cidr, e))?;
+        }
+        if self.source_filter.mode == SourceFilterMode::Allow && self.source_filter.cidrs.is_empty() {
+            return Err("source_filter.mode가 allow이면 source_filter.cidrs에 허용할 대역이 하나 이상 필요합니다".into());
+        }
+        if let Some(path) = &self.access_log.file.path {
+            let file = &self.access_log.file;
+            if path.trim().is_empty() {
+                return Err("access_log.file.path가 비어 있습니다".into());
+            }
+            if file.flush_interval_ms == 0 {
+                return Err("access_log.file.flush_interval_ms는 0보다 커야 합니다".into());
+            }
+            if file.queue_capacity == 0 {
+                return Err("access_log.file.queue_capacity는 0보다 커야 합니다".into());
+            }
+            let (min, max, _) = file.compression.level_range();
+            if let Some(level) = file.level
+                && (level < min || level > max) {
+                return Err(format!("access_log.file.level 범위 오류: {} ({} ~ {})", level, min, max).into());
+            }
+        }
+        if self.upstream_pool_enabled && self.upstream_pool_max_idle_per_host == 0 {
+            return Err("upstream_pool_enabled가 true인 경우 upstream_pool_max_idle_per_host는 0보다 커야 합니다".into());
+        }
+        if self.upstream_pool_enabled && self.upstream_pool_idle_timeout_seconds == 0 {
+            return Err("upstream_pool_enabled가 true인 경우 upstream_pool_idle_timeout_seconds는 0보다 커야 합니다".into());
+        }
+        if let Some(country) = &self.leaf_cert.country
+            && country.len() != 2 {
+            return Err(format!("leaf_cert.country는 2자리 국가 코드여야 합니다: {}", country).into());
+        }
+        if self.leaf_cert.extra_sans.iter().any(|san| san.trim().is_empty()) {
+            return Err("leaf_cert.extra_sans에 빈 값이 있습니다".into());
+        }
+        self.validate_root_ca()?;
+        if self.pkcs12.passphrase_env.as_ref().is_some_and(|var| var.trim().is_empty()) {
+            return Err("pkcs12.passphrase_env가 비어 있습니다".into());
+        }
+        if let Some(rule) = self.upstream_cert_tiers.iter().find(|rule| {
+            let host = rule.host.trim();
+            host.is_empty() || host.strip_prefix("*.").unwrap_or(host).contains('*')
+        }) {
+            return Err(format!("upstream_cert_tiers host는 호스트 또는 *.도메인 형식이어야 합니다: {:?}", rule.host).into());
+        }
+        if self.tls_resumption.enabled {
+            if self.tls_resumption.session_cache_size == 0 {
+                return Err("tls_resumption.session_cache_size는 0보다 커야 합니다".into());
+            }
+            // 티켓 키 생성기 자체가 6시간마다 키를 교체하므로 그보다 긴 주기는 의미가 없음
+            if self.tls_resumption.ticket_rotation_seconds == 0 || self.tls_resumption.ticket_rotation_seconds > 21600 {
+                return Err(format!("tls_resumption.ticket_rotation_seconds 범위 오류: {} (1 ~ 21600)",
+                                   self.tls_resumption.ticket_rotation_seconds).into());
+            }
+        }
+        if !(MIN_CLIENT_HELLO_PEEK_BYTES..=MAX_CLIENT_HELLO_PEEK_BYTES).contains(&self.client_hello_peek.max_bytes) {
+            return Err(format!("client_hello_peek.max_bytes 범위 오류: {} ({} ~ {})", self.client_hello_peek.max_bytes,
+                               MIN_CLIENT_HELLO_PEEK_BYTES, MAX_CLIENT_HELLO_PEEK_BYTES).into());
+        }
+        if self.client_hello_peek.timeout_ms == Some(0) {
+            return Err("client_hello_peek.timeout_ms는 0보다 커야 합니다".into());
+        }
+        let cert_cache_alert = &self.cert_cache_alert;
+        if cert_cache_alert.window_seconds == 0 || cert_cache_alert.window_seconds > MAX_CERT_CACHE_ALERT_WINDOW_SECONDS {
+            return Err(format!("cert_cache_alert.window_seconds 범위 오류: {} (1 ~ {})",
+                               cert_cache_alert.window_seconds, MAX_CERT_CACHE_ALERT_WINDOW_SECONDS).into());
+        }
+        if cert_cache_alert.miss_rate_threshold_percent > 100 {
+            return Err(format!("cert_cache_alert.miss_rate_threshold_percent는 0 ~ 100이어야 합니다: {}",
+                               cert_cache_alert.miss_rate_threshold_percent).into());
+        }
+        if self.proxy_headers.enabled {
+            let pseudonym = &self.proxy_headers.via_pseudonym;
+            // Via의 received-by는 공백/쉼표 없는 토큰이어야 함
+            if pseudonym.is_empty() || pseudonym.chars().any(|c| c.is_whitespace() || c.is_control() || c == ',') {
+                return Err(format!("proxy_headers.via_pseudonym 형식 오류: {:?}", pseudonym).into());
+            }
+            if let Some(x_proxy) = &self.proxy_headers.x_proxy
+                && (x_proxy.trim().is_empty() || x_proxy.chars().any(|c| c.is_control())) {
+                return Err(format!("proxy_headers.x_proxy 형식 오류: {:?}", x_proxy).into());
+            }
+        }
+        if self.security_headers.enabled {
+            let rules = self.security_headers.resolved();
+            if rules.is_empty() {
+                return Err("security_headers.enabled인 경우 preset 또는 headers를 하나 이상 지정해야 합니다".into());
+            }
+            for rule in &rules {
+                // 헤더 이름은 RFC 7230 토큰 문자만 허용
+                if rule.name.is_empty() || !rule.name.chars().all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)) {
+                    return Err(format!("security_headers.headers 헤더 이름 형식 오류: {:?}", rule.name).into());
+                }
+                if SECURITY_HEADER_FORBIDDEN.iter().any(|name| rule.name.eq_ignore_ascii_case(name)) {
+                    return Err(format!("security_headers.headers에 지정할 수 없는 헤더입니다: {}", rule.name).into());
+                }
+                if rule.value.is_empty() || rule.value.chars().any(|c| c.is_control()) {
+                    return Err(format!("security_headers.headers 헤더 값 형식 오류 ({}): {:?}", rule.name, rule.value).into());
+                }
+            }
+        }
+        if !self.warm_pool.upstreams.is_empty() {
+            if self.warm_pool.size == 0 {
+                return Err("warm_pool.upstreams를 지정한 경우 warm_pool.size는 0보다 커야 합니다".into());
+            }
+            if self.warm_pool.refresh_seconds == 0 || self.warm_pool.check_interval_seconds == 0 {
+                return Err("warm_pool.refresh_seconds와 warm_pool.check_interval_seconds는 0보다 커야 합니다".into());
+            }
+            if self.warm_pool.check_interval_seconds >= self.warm_pool.refresh_seconds {
+                return Err("warm_pool.check_interval_seconds는 warm_pool.refresh_seconds보다 작아야 합니다".into());
+            }
+            if let Some(upstream) = self.warm_pool.upstreams.iter().find(|u| crate::proxy::target::parse_authority(u, 443).is_none()) {
+                return Err(format!("warm_pool.upstreams 형식 오류: {}", upstream).into());
+            }
+        }
+        if self.tls_exemption_refresh_seconds == 0 {
+            return Err("tls_exemption_refresh_seconds는 0보다 커야 합니다".into());
+        }
+        if let Some(host) = self.tls_exempt_hosts.iter().find(|h| h.trim().is_empty() || h.trim_start_matches("*.").contains('*')) {
+            return Err(format!("tls_exempt_hosts 형식 오류 (호스트 또는 *.도메인): {:?}", host).into());
+        }
+        if let Some(header) = &self.egress_header {
+            if header.trim().is_empty() || header.contains(':') {
+                return Err(format!("egress_header 형식 오류: {:?}", header).into());
+            }
+            if self.egress_pools.is_empty() {
+                return Err("egress_header를 사용하려면 egress_pools에 허용할 출구를 하나 이상 지정해야 합니다".into());
+            }
+        }
+        for (name, pool) in &self.egress_pools {
+            match (&pool.source_address, &pool.parent_proxy) {
+                (Some(_), None) => {},
+                (None, Some(parent)) if crate::proxy::target::parse_authority(parent, 0).is_some_and(|(_, port)| port != 0) => {},
+                (None, Some(parent)) => {
+                    return Err(format!("egress_pools.{}.parent_proxy는 host:port 형식이어야 합니다: {}", name, parent).into());
+                },
+                _ => {
+                    return Err(format!("egress_pools.{}에는 source_address와 parent_proxy 중 하나만 지정해야 합니다", name).into());
+                },
+            }
+        }
+        if !self.parent_proxies.proxies.is_empty() {
+            let parents = &self.parent_proxies;
+            for parent in &parents.proxies {
+                if crate::proxy::target::parse_authority(&parent.address, 0).is_none_or(|(_, port)| port == 0) {
+                    return Err(format!("parent_proxies.proxies의 address는 host:port 형식이어야 합니다: {}", parent.address).into());
+                }
+            }
+            if parents.proxies.iter().all(|parent| parent.weight == 0) {
+                return Err("parent_proxies.proxies에 weight가 0보다 큰 상위 프록시가 하나 이상 있어야 합니다".into());
+            }
+            if parents.failure_threshold == 0 || parents.window_seconds == 0 || parents.cooldown_seconds == 0 {
+                return Err("parent_proxies의 failure_threshold, window_seconds, cooldown_seconds는 0보다 커야 합니다".into());
+            }
+        }
+        for (host, sni) in &self.sni_overrides {
+            if sni.trim().is_empty() {
+                return Err(format!("sni_overrides의 SNI 값이 비어 있습니다: {}", host).into());
+            }
+        }
+        for (host, addrs) in &self.static_hosts {
+            let name = host.trim_end_matches('.');
+            if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ':' || c == '*' || c == '/') {
+                return Err(format!("static_hosts 호스트 이름 형식 오류: {:?}", host).into());
+            }
+            if addrs.is_empty() {
+                return Err(format!("static_hosts.{}에 주소가 없습니다", host).into());
+            }
+        }
+        for host in &self.dns_prewarm.hosts {
+            let name = host.trim_end_matches('.');
+            if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ':' || c == '*' || c == '/') {
+                return Err(format!("dns_prewarm.hosts 호스트 이름 형식 오류: {:?}", host).into());
+            }
+        }
+        if !(MIN_DNS_PREWARM_REFRESH_SECONDS..=MAX_DNS_PREWARM_REFRESH_SECONDS).contains(&self.dns_prewarm.refresh_seconds) {
+            return Err(format!("dns_prewarm.refresh_seconds 범위 오류: {} ({} ~ {})", self.dns_prewarm.refresh_seconds,
+                               MIN_DNS_PREWARM_REFRESH_SECONDS, MAX_DNS_PREWARM_REFRESH_SECONDS).into());
+        }
+        if self.dns_prewarm.max_age_seconds < self.dns_prewarm.refresh_seconds {
+            return Err(format!("dns_prewarm.max_age_seconds({})는 refresh_seconds({}) 이상이어야 합니다",
+                               self.dns_prewarm.max_age_seconds, self.dns_prewarm.refresh_seconds).into());
+        }
+        for webhook in &self.webhooks {
+            if !webhook.url.starts_with("http://") && !webhook.url.starts_with("https://") {
+                return Err(format!("웹훅 URL은 http:// 또는 https://로 시작해야 합니다: {}", webhook.url).into());
+            }
+            if let Some(event) = webhook.events.iter().find(|e| WebhookEventKind::from_name(e).is_none()) {
+                return Err(format!("알 수 없는 웹훅 이벤트: {} (url: {})", event, webhook.url).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 업스트림 TLS 연결에 사용할 SNI (호스트별 대체 SNI가 없으면 요청된 호스트명)
+    pub fn sni_for<'a>(&'a self, host: &'a str) -> &'a str {
+        self.sni_overrides
+            .get(host)
+            .or_else(|| self.sni_overrides.get(&host.to_lowercase()))
+            .map(String::as_str)
+            .unwrap_or(host)
+    }
+
+    /// `static_hosts`에 지정한 호스트의 고정 주소 (대소문자와 끝의 점 무시, 없으면 None)
+    pub fn static_host_addresses(&self, host: &str) -> Option<&[std::net::IpAddr]> {
+        self.static_hosts
+            .get(host)
+            .or_else(|| {
+                let host = host.trim_end_matches('.');
+                self.static_hosts.iter()
+                    .find(|(name, _)| name.trim_end_matches('.').eq_ignore_ascii_case(host))
+                    .map(|(_, addrs)| addrs)
+            })
+            .map(Vec::as_slice)
+    }
+
+    /// 호스트에 적용할 업스트림 인증서 검증 등급 (처음 일치한 규칙, 없으면 None)
+    pub fn upstream_cert_tier(&self, host: &str) -> Option<UpstreamCertTier> {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.upstream_cert_tiers.iter()
+            .find(|rule| {
+                let pattern = rule.host.trim_end_matches('.').to_ascii_lowercase();
+                match pattern.strip_prefix("*.") {
+                    Some(parent) => host.strip_suffix(parent).is_some_and(|rest| rest.ends_with('.')),
+                    None => host == pattern,
+                }
+            })
+            .map(|rule| rule.tier)
+    }
+    
+    /// 민감한 값을 가린 YAML 문자열로 변환
+    pub fn to_redacted_yaml(&self) -> Result<String, serde_yaml::Error> {
+        let mut value = serde_yaml::to_value(self)?;
+        redact_secrets(&mut value);
+        serde_yaml::to_string(&value)
+    }
+    
+    /// 적용된 설정의 SHA-256 해시 (인스턴스 간 같은 설정을 읽었는지 비교용)
+    ///
+    /// 키는 정렬된 JSON으로 직렬화하고, 순서가 의미 없는 집합 필드(차단 목록, 가로채기 제외 목록,
+    /// 신뢰할 인증서)는 값도 정렬한 뒤 해시하므로 파일 안의 순서와 무관하게 같은 값이 나옵니다.
+    pub fn content_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+        
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let serde_json::Value::Object(fields) = &mut value {
+            for key in UNORDERED_CONFIG_KEYS {
+                if let Some(serde_json::Value::Array(items)) = fields.get_mut(key) {
+                    items.sort_by_key(|item| item.to_string());
+                }
+            }
+        }
+        Sha256::digest(value.to_string().as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+    
     /// 도메인이 차단 목록에 있는지 확인
     pub fn is_domain_blocked(&self, domain: &str) -> bool {
         // 1. 정확한 도메인 매칭
@@ -153,3 +2926,77 @@ impl Config {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacted_yaml_hides_only_set_secrets() {
+        let mut config = Config::new();
+        let yaml = config.to_redacted_yaml().unwrap();
+        assert!(yaml.contains("passphrase: null"), "{}", yaml);
+
+        config.pkcs12.passphrase = Some("hunter2".to_string());
+        let yaml = config.to_redacted_yaml().unwrap();
+        assert!(yaml.contains("passphrase: '***'"), "{}", yaml);
+        assert!(!yaml.contains("hunter2"));
+    }
+
+    #[test]
+    fn redacts_secret_key_suffixes() {
+        for key in ["token", "api_token", "secret", "client-secret", "key", "private_key", "password", "db_password",
+                    "passphrase", "pkcs12_passphrase", "dsn", "sentry_dsn", "API_TOKEN"] {
+            let mut value: serde_yaml::Value = serde_yaml::from_str(&format!("nested:\n  - {}: hunter2\n", key)).unwrap();
+            redact_secrets(&mut value);
+            assert_eq!(value["nested"][0][key], serde_yaml::Value::String(REDACTED.to_string()), "{}", key);
+        }
+        for key in ["key_file", "passphrase_env", "tokens", "monkey", "keyword"] {
+            let mut value: serde_yaml::Value = serde_yaml::from_str(&format!("{}: kept\n", key)).unwrap();
+            redact_secrets(&mut value);
+            assert_eq!(value[key], serde_yaml::Value::String("kept".to_string()), "{}", key);
+        }
+    }
+
+    #[test]
+    fn serialization_keeps_secrets() {
+        let mut config = Config::new();
+        config.pkcs12.passphrase = Some("hunter2".to_string());
+        let parsed: Config = serde_yaml::from_str(&serde_yaml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.pkcs12.passphrase.as_deref(), Some("hunter2"));
+    }
+
+    #[test]
+    fn read_chunk_size_is_bounded() {
+        let mut config = Config::new();
+        for (size, valid) in [(None, true), (Some(1), true), (Some(MAX_READ_CHUNK_SIZE), true), (Some(0), false), (Some(MAX_READ_CHUNK_SIZE + 1), false)] {
+            config.read_chunk_size = size;
+            assert_eq!(config.validate().is_ok(), valid, "{:?}", size);
+        }
+    }
+
+    #[test]
+    fn dns_prewarm_max_age_covers_refresh() {
+        let mut config = Config::new();
+        config.dns_prewarm.refresh_seconds = 300;
+        for (max_age, valid) in [(300, true), (900, true), (299, false)] {
+            config.dns_prewarm.max_age_seconds = max_age;
+            assert_eq!(config.validate().is_ok(), valid, "{}", max_age);
+        }
+    }
+
+    #[test]
+    fn yaml_and_json_parse_to_the_same_config() {
+        let mut config = Config::new();
+        config.bind_port = 18443;
+        config.max_inspection_bytes = MIN_INSPECTION_RESPONSE_BYTES * 2;
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let json = serde_json::to_string(&config).unwrap();
+
+        let from_yaml = Config::from_str_with_format(&yaml, ConfigFormat::Yaml).unwrap();
+        let from_json = Config::from_str_with_format(&json, ConfigFormat::Json).unwrap();
+        assert_eq!(from_json.bind_port, 18443);
+        assert_eq!(from_yaml.to_redacted_yaml().unwrap(), from_json.to_redacted_yaml().unwrap());
+        assert!(Config::from_str_with_format("bind_port: [", ConfigFormat::Json).is_err());
+    }
+}
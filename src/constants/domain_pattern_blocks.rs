@@ -17,19 +17,26 @@ pub const CREATE_TABLE: &str = "
         created_by VARCHAR(100) NOT NULL,
         created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
         description TEXT,
-        active BOOLEAN NOT NULL DEFAULT TRUE
+        active BOOLEAN NOT NULL DEFAULT TRUE,
+        expires_at TIMESTAMPTZ
     )
 ";
 
+/// 만료 시각 컬럼 추가 (이전 버전에서 만든 테이블)
+pub const ADD_EXPIRES_AT_COLUMN: &str = "
+    ALTER TABLE domain_pattern_blocks
+        ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ
+";
+
 /// 인덱스 생성 쿼리
 pub const CREATE_INDICES: [&str; 2] = [
     "CREATE INDEX IF NOT EXISTS domain_pattern_blocks_pattern_idx ON domain_pattern_blocks(pattern)",
     "CREATE INDEX IF NOT EXISTS domain_pattern_blocks_active_idx ON domain_pattern_blocks(active)"
 ];
 
-/// 패턴 목록 조회 쿼리
+/// 패턴 목록 조회 쿼리 (만료 시각 포함, NULL이면 만료 없음)
 pub const SELECT_ACTIVE_PATTERNS: &str = "
-    SELECT pattern
+    SELECT pattern, expires_at
     FROM domain_pattern_blocks
     WHERE active = TRUE
     ORDER BY pattern
@@ -6,6 +6,9 @@ pub mod partition;
 pub mod domain_blocks;
 pub mod domain_pattern_blocks;
 pub mod response_logs;
+pub mod shutdown_events;
+pub mod upstream_certificates;
+pub mod tls_exemptions;
 
 // 버퍼 크기
 pub const BUFFER_SIZE_SMALL: usize = 64 * 1024;   // 64KB
@@ -25,18 +28,30 @@ pub const BUFFER_POOL_ADJUSTMENT_RATE: f64 = 0.2;     // 버퍼 풀 조정 비
 
 pub const BUFFER_STATS_INTERVAL_SECS: u64 = 30;     // 버퍼 통계 출력
 
+// 세션 버퍼 승격 설정 (측정 구간 동안 현재 용량 x 배수 이상 처리 시 승격)
+pub const BUFFER_PROMOTION_WINDOW_MS: u64 = 1000;   // 처리량 측정 구간
+pub const BUFFER_PROMOTION_FACTOR: usize = 8;       // 승격 기준 배수
+
 pub const TCP_NODELAY: bool = true;
 pub const TCP_QUICKACK: bool = true;  // TCP QUICKACK 활성화
 
+// 파일 디스크립터 부족(EMFILE/ENFILE) 시 accept 재시도 설정
+pub const ACCEPT_FD_BACKOFF_MS: u64 = 100;          // 다시 accept하기 전 대기 시간
+pub const ACCEPT_FD_WARN_INTERVAL_SECS: u64 = 10;   // 경고 로그 최소 간격
+pub const FD_RESERVE_SAMPLE_INTERVAL_MS: u64 = 1000; // fd 예비분(overload.min_free_fds) 사용량 확인 주기
+
 // LRU 캐시 크기
 pub const CERT_CACHE_SIZE: usize = 1000;         // 인증서 캐시 크기
 pub const TLS_SESSION_CACHE_SIZE: usize = 5000;  // TLS 세션 캐시 크기
 pub const ACL_CACHE_SIZE: usize = 10000;         // ACL 결과 캐시 크기
+pub const WILDCARD_APEX_CACHE_SIZE: usize = 4096; // 와일드카드 전환 판단용 상위 도메인 집계 수
+pub const UPSTREAM_CERT_LOG_CACHE_SIZE: usize = 4096; // 업스트림 인증서 중복 기록 방지용 호스트 수
 
 // 루트 CA 인증서 파일 경로
 pub const CA_CERT_FILE: &str = "ssl/ca_cert.pem";
 pub const CA_KEY_FILE: &str = "ssl/ca_key.pem";
 pub const CA_CERT_CRT_FILE: &str = "ssl/ca_cert.crt";
+pub const PEM_BEGIN_MARKER: &[u8] = b"-----BEGIN";  // PEM 블록 시작 표시 (DER와 구분)
 
 // 로그 배치 크기 및 플러시 간격
 pub const LOG_BATCH_SIZE: usize = 500;
@@ -52,6 +67,31 @@ pub const LOG_LOW_PRIORITY_QUEUE_SIZE: usize = 15000;  // 저우선순위 로그
 // 로그 처리 타임아웃 (ms)
 pub const LOG_PROCESSING_TIMEOUT_MS: u64 = 1000;  // 로그 처리 타임아웃
 pub const LOG_DB_OPERATION_TIMEOUT_MS: u64 = 2000;  // DB 작업 타임아웃
+pub const LOG_DB_RETRY_BASE_MS: u64 = 100;  // 배치 저장 재시도 대기 시간 (지수 증가)
+pub const LOG_DB_RETRY_MAX_MS: u64 = 5000;  // 배치 저장 재시도 대기 시간 상한
+
+// 로그 싱크 설정
+pub const LOG_SINK_QUEUE_SIZE: usize = 10000;       // 싱크별 기본 대기열 크기 (가득 차면 그 싱크에서만 버림)
+pub const LOG_SINK_WRITE_TIMEOUT_MS: u64 = 5000;    // 싱크 기록 하나의 최대 처리 시간
+pub const LOG_SINK_FLUSH_TIMEOUT_MS: u64 = 3000;    // 플러시 요청 시 전체 싱크 대기 시간
+
+// 이벤트 웹훅 전송 설정
+pub const WEBHOOK_QUEUE_SIZE: usize = 1024;        // 웹훅 이벤트 큐 크기 (가득 차면 버림)
+pub const WEBHOOK_ENDPOINT_QUEUE_SIZE: usize = 256; // 엔드포인트별 전송 대기 이벤트 수 (가득 차면 버림)
+pub const WEBHOOK_MAX_RETRIES: u32 = 3;            // 전송 실패 시 최대 재시도 횟수
+pub const WEBHOOK_RETRY_BASE_MS: u64 = 500;        // 재시도 대기 시간 (지수 증가)
+pub const WEBHOOK_TIMEOUT_MS: u64 = 5000;          // 1회 전송 타임아웃
+
+// 업스트림 회로 차단기
+pub const CIRCUIT_BREAKER_MAX_HOSTS: usize = 4096;      // 상태를 추적할 최대 업스트림 호스트 수 (LRU)
+
+// 업스트림 구간 시간 집계
+pub const UPSTREAM_LATENCY_MAX_HOSTS: usize = 4096;     // 구간 시간을 집계할 최대 업스트림 호스트 수 (LRU)
+pub const UPSTREAM_LATENCY_LOG_TOP: usize = 10;         // 주기 로그에 출력할 호스트 수 (평균 첫 바이트 시간 순)
 
 // 로그 파티션 관리
 pub const LOG_PARTITION_CHECK_INTERVAL_HOURS: u64 = 24;  // 파티션 체크 주기 (시간)
+
+// 파티션 내보내기 설정
+pub const EXPORT_FETCH_SIZE: i32 = 1000;                // 커서에서 한 번에 가져올 행 수
+pub const EXPORT_PROGRESS_INTERVAL: u64 = 100_000;      // 진행 상황 로깅 간격 (행)
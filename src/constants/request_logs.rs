@@ -15,6 +15,8 @@ pub const CREATE_TABLE: &str = "
         target_ip TEXT NOT NULL,
         is_rejected BOOLEAN NOT NULL DEFAULT FALSE,
         is_tls BOOLEAN NOT NULL DEFAULT FALSE,
+        tenant TEXT,
+        block_category TEXT,
         PRIMARY KEY (id, timestamp)
     ) PARTITION BY RANGE (timestamp)";
 
@@ -22,26 +24,40 @@ pub const CREATE_TABLE: &str = "
 pub const INSERT_LOG: &str = "
     INSERT INTO request_logs (
         host, method, path, header, body, timestamp, 
-        session_id, client_ip, target_ip, is_rejected, is_tls
-    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category
+    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
 ";
 
 /// 복사 모드를 위한 쿼리
 pub const COPY_LOGS: &str = "
     COPY request_logs (
         host, method, path, header, body, timestamp, 
-        session_id, client_ip, target_ip, is_rejected, is_tls
+        session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category
     ) FROM STDIN BINARY
 ";
 
+/// 테넌트 태그 컬럼 추가 (이전 버전에서 만든 테이블)
+pub const ADD_TENANT_COLUMN: &str = "
+    ALTER TABLE request_logs ADD COLUMN IF NOT EXISTS tenant TEXT
+";
+
+/// 차단 분류 컬럼 추가 (이전 버전에서 만든 테이블)
+pub const ADD_BLOCK_CATEGORY_COLUMN: &str = "
+    ALTER TABLE request_logs ADD COLUMN IF NOT EXISTS block_category TEXT
+";
+
 /// 기본 인덱스 생성 쿼리 - 부모 테이블에만 적용
-pub const CREATE_INDICES: [&str; 4] = [
+pub const CREATE_INDICES: [&str; 5] = [
     "CREATE INDEX IF NOT EXISTS request_logs_host_idx ON request_logs(host)",
     "CREATE INDEX IF NOT EXISTS request_logs_timestamp_idx ON request_logs(timestamp)",
     "CREATE INDEX IF NOT EXISTS request_logs_is_rejected_idx ON request_logs(is_rejected)",
-    "CREATE INDEX IF NOT EXISTS request_logs_is_tls_idx ON request_logs(is_tls)"
+    "CREATE INDEX IF NOT EXISTS request_logs_is_tls_idx ON request_logs(is_tls)",
+    TENANT_INDEX,
 ];
 
+/// 테넌트 태그 인덱스 (테넌트별 보고용)
+pub const TENANT_INDEX: &str = "CREATE INDEX IF NOT EXISTS request_logs_tenant_idx ON request_logs(tenant)";
+
 /// 파티션별 인덱스 생성 쿼리 - 각 파티션에 개별 적용
 pub fn create_partition_indices(partition_name: &str) -> Vec<String> {
     vec![
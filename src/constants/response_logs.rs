@@ -11,6 +11,17 @@ pub const CREATE_TABLE: &str = "
         timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
         headers TEXT NOT NULL,
         body_preview TEXT,
+        dns_ms BIGINT,
+        connect_ms BIGINT,
+        tls_handshake_ms BIGINT,
+        first_byte_ms BIGINT,
+        client_tls_version TEXT,
+        client_cipher TEXT,
+        client_alpn TEXT,
+        upstream_tls_version TEXT,
+        upstream_cipher TEXT,
+        upstream_alpn TEXT,
+        upstream_sni TEXT,
         PRIMARY KEY (id, timestamp)
     ) PARTITION BY RANGE (timestamp)";
 
@@ -18,18 +29,45 @@ pub const CREATE_TABLE: &str = "
 pub const INSERT_LOG: &str = "
     INSERT INTO response_logs (
         session_id, status_code, response_time, response_size, 
-        timestamp, headers, body_preview
-    ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+        timestamp, headers, body_preview,
+        dns_ms, connect_ms, tls_handshake_ms, first_byte_ms,
+        client_tls_version, client_cipher, client_alpn,
+        upstream_tls_version, upstream_cipher, upstream_alpn, upstream_sni
+    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
 ";
 
 /// 복사 모드를 위한 쿼리
 pub const COPY_LOGS: &str = "
     COPY response_logs (
         session_id, status_code, response_time, response_size, 
-        timestamp, headers, body_preview
+        timestamp, headers, body_preview,
+        dns_ms, connect_ms, tls_handshake_ms, first_byte_ms,
+        client_tls_version, client_cipher, client_alpn,
+        upstream_tls_version, upstream_cipher, upstream_alpn, upstream_sni
     ) FROM STDIN BINARY
 ";
 
+/// 업스트림 구간 시간 컬럼 추가 (이전 버전에서 만든 테이블)
+pub const ADD_TIMING_COLUMNS: &str = "
+    ALTER TABLE response_logs
+        ADD COLUMN IF NOT EXISTS dns_ms BIGINT,
+        ADD COLUMN IF NOT EXISTS connect_ms BIGINT,
+        ADD COLUMN IF NOT EXISTS tls_handshake_ms BIGINT,
+        ADD COLUMN IF NOT EXISTS first_byte_ms BIGINT
+";
+
+/// TLS 협상 값 컬럼 추가 (이전 버전에서 만든 테이블)
+pub const ADD_TLS_PARAMS_COLUMNS: &str = "
+    ALTER TABLE response_logs
+        ADD COLUMN IF NOT EXISTS client_tls_version TEXT,
+        ADD COLUMN IF NOT EXISTS client_cipher TEXT,
+        ADD COLUMN IF NOT EXISTS client_alpn TEXT,
+        ADD COLUMN IF NOT EXISTS upstream_tls_version TEXT,
+        ADD COLUMN IF NOT EXISTS upstream_cipher TEXT,
+        ADD COLUMN IF NOT EXISTS upstream_alpn TEXT,
+        ADD COLUMN IF NOT EXISTS upstream_sni TEXT
+";
+
 /// 응답 시간 업데이트 쿼리
 pub const UPDATE_RESPONSE_TIME: &str = "
     UPDATE response_logs SET response_time = $2 
@@ -0,0 +1,18 @@
+// shutdown_events 테이블 관련 SQL 쿼리
+
+/// 테이블 생성 쿼리
+pub const CREATE_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS shutdown_events (
+        id BIGSERIAL PRIMARY KEY,
+        timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        sessions_total BIGINT NOT NULL,
+        bytes_total BIGINT NOT NULL,
+        blocked_total BIGINT NOT NULL,
+        uptime_seconds BIGINT NOT NULL,
+        exit_reason TEXT NOT NULL
+    )";
+
+/// 종료 이벤트 삽입 쿼리
+pub const INSERT_EVENT: &str = "
+    INSERT INTO shutdown_events (sessions_total, bytes_total, blocked_total, uptime_seconds, exit_reason)
+    VALUES ($1, $2, $3, $4, $5)";
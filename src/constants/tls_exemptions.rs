@@ -0,0 +1,41 @@
+//! tls_exemptions 테이블 관련 SQL 쿼리
+
+/// 테이블 생성 쿼리
+pub const CREATE_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS tls_exemptions (
+        id BIGSERIAL PRIMARY KEY,
+        host VARCHAR(255) NOT NULL,
+        created_by VARCHAR(100) NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        description TEXT,
+        active BOOLEAN NOT NULL DEFAULT TRUE
+    )
+";
+
+/// 인덱스 생성 쿼리
+pub const CREATE_INDICES: [&str; 2] = [
+    "CREATE INDEX IF NOT EXISTS tls_exemptions_host_idx ON tls_exemptions(host)",
+    "CREATE INDEX IF NOT EXISTS tls_exemptions_active_idx ON tls_exemptions(active)"
+];
+
+/// 가로채기 제외 호스트 추가 쿼리 (이미 활성 항목이 있으면 추가하지 않음)
+pub const INSERT_HOST: &str = "
+    INSERT INTO tls_exemptions (host, created_by, description)
+    SELECT $1::VARCHAR, $2::VARCHAR, $3::TEXT
+    WHERE NOT EXISTS (SELECT 1 FROM tls_exemptions WHERE host = $1::VARCHAR AND active = TRUE)
+";
+
+/// 가로채기 제외 호스트 비활성화 쿼리
+pub const DEACTIVATE_HOST: &str = "
+    UPDATE tls_exemptions
+    SET active = FALSE
+    WHERE host = $1 AND active = TRUE
+";
+
+/// 가로채기 제외 호스트 목록 조회 쿼리
+pub const SELECT_ACTIVE_HOSTS: &str = "
+    SELECT host
+    FROM tls_exemptions
+    WHERE active = TRUE
+    ORDER BY host
+";
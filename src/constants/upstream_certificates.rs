@@ -0,0 +1,28 @@
+// upstream_certificates 테이블 관련 SQL 쿼리
+
+/// 테이블 생성 쿼리 (chain은 리프부터 순서대로 인증서 정보 배열)
+pub const CREATE_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS upstream_certificates (
+        id BIGSERIAL PRIMARY KEY,
+        timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        session_id TEXT NOT NULL,
+        host TEXT NOT NULL,
+        leaf_fingerprint TEXT NOT NULL,
+        leaf_subject TEXT NOT NULL,
+        leaf_issuer TEXT NOT NULL,
+        leaf_not_before TIMESTAMPTZ,
+        leaf_not_after TIMESTAMPTZ,
+        chain JSONB NOT NULL
+    )";
+
+/// 인덱스 생성 쿼리 (리프 지문 조회, 호스트별 시간순 조회)
+pub const CREATE_INDICES: [&str; 2] = [
+    "CREATE INDEX IF NOT EXISTS upstream_certificates_leaf_fingerprint_idx ON upstream_certificates(leaf_fingerprint)",
+    "CREATE INDEX IF NOT EXISTS upstream_certificates_host_timestamp_idx ON upstream_certificates(host, timestamp)"
+];
+
+/// 인증서 체인 삽입 쿼리
+pub const INSERT_CHAIN: &str = "
+    INSERT INTO upstream_certificates
+        (session_id, host, leaf_fingerprint, leaf_subject, leaf_issuer, leaf_not_before, leaf_not_after, chain)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8::text::jsonb)";
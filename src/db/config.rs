@@ -52,16 +52,94 @@ pub struct PartitionConfig {
     pub future_partitions: u32,
 }
 
+/// 로그 배치 저장 실패 시 재시도 및 데드레터 설정
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LogWriteConfig {
+    /// 배치 저장 실패 시 재시도 횟수
+    #[serde(default = "default_log_write_retries")]
+    pub retries: u32,
+    /// 재시도 후에도 저장하지 못한 로그를 NDJSON으로 덧붙일 파일
+    #[serde(default = "default_dead_letter_path")]
+    pub dead_letter_path: String,
+    /// 파티션 손상 등 구조적인 오류로 저장에 실패했을 때의 처리
+    #[serde(default)]
+    pub on_partition_failure: PartitionFailurePolicy,
+    /// 격리한 파티션으로 갈 로그를 보낼 곳 (on_partition_failure가 quarantine일 때)
+    #[serde(default)]
+    pub quarantine_target: QuarantineTarget,
+}
+
+impl Default for LogWriteConfig {
+    fn default() -> Self {
+        Self {
+            retries: default_log_write_retries(),
+            dead_letter_path: default_dead_letter_path(),
+            on_partition_failure: PartitionFailurePolicy::default(),
+            quarantine_target: QuarantineTarget::default(),
+        }
+    }
+}
+
+/// 구조적인 오류(파티션 손상, 파일 오류, 파티션 없음)로 저장에 실패한 배치 처리
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartitionFailurePolicy {
+    /// 다른 오류와 같이 재시도 후 배치 전체를 데드레터 파일에 기록
+    #[default]
+    Retry,
+    /// 실패한 파티션만 격리하고 나머지 파티션의 로그는 계속 저장
+    Quarantine,
+}
+
+/// 격리한 파티션으로 갈 로그를 보낼 곳
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuarantineTarget {
+    /// 데드레터 파일 (log_write.dead_letter_path)
+    #[default]
+    DeadLetter,
+    /// 새 기본(DEFAULT) 파티션 `<테이블>_quarantine`에 기록 (격리한 파티션이 아직 붙어 있으면 운영자가 떼어 낼 때까지 데드레터)
+    FallbackPartition,
+}
+
+fn default_log_write_retries() -> u32 {
+    3
+}
+
+fn default_dead_letter_path() -> String {
+    "logs/db_dead_letter.ndjson".to_string()
+}
+
+fn default_db_enabled() -> bool {
+    true
+}
+
+fn default_read_replica_max_lag_seconds() -> u64 {
+    60
+}
+
 /// 데이터베이스 설정
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DbConfig {
+    /// false이면 DB 없이 실행 (연결 풀, 스키마, DB 로깅 모두 생략)
+    #[serde(default = "default_db_enabled")]
+    pub enabled: bool,
     pub connection: ConnectionConfig,
     pub partitioning: PartitionConfig,
+    #[serde(default)]
+    pub log_write: LogWriteConfig,
+    /// 조회/내보내기용 읽기 전용 복제본 접속 정보 (없으면 주 DB에서 읽음)
+    #[serde(default)]
+    pub read_replica: Option<ConnectionConfig>,
+    /// 복제본 지연이 이 시간을 넘으면 복제본 대신 주 DB에서 읽음
+    #[serde(default = "default_read_replica_max_lag_seconds")]
+    pub read_replica_max_lag_seconds: u64,
 }
 
 impl Default for DbConfig {
     fn default() -> Self {
         Self {
+            enabled: default_db_enabled(),
             connection: ConnectionConfig {
                 host: "localhost".to_string(),
                 port: 5432,
@@ -77,6 +155,9 @@ impl Default for DbConfig {
                 retention_period: 365,
                 future_partitions: 1,
             },
+            log_write: LogWriteConfig::default(),
+            read_replica: None,
+            read_replica_max_lag_seconds: default_read_replica_max_lag_seconds(),
         }
     }
 }
@@ -92,6 +173,10 @@ impl DbConfig {
         file.read_to_string(&mut contents)?;
         
         let config: DbConfig = serde_yaml::from_str(&contents)?;
+        if !config.enabled {
+            info!("DB 설정 로드 완료: 데이터베이스 비활성화");
+            return Ok(config);
+        }
         info!("DB 설정 로드 완료: {}:{}/{}", config.connection.host, config.connection.port, config.connection.database);
         
         Ok(config)
@@ -127,6 +212,26 @@ impl DbConfig {
         }
     }
 
+    /// 민감한 값을 가린 YAML 문자열로 변환
+    pub fn to_redacted_yaml(&self) -> Result<String, serde_yaml::Error> {
+        let mut value = serde_yaml::to_value(self)?;
+        crate::config::redact_secrets(&mut value);
+        serde_yaml::to_string(&value)
+    }
+
+    /// DB 비활성화 (설정 파일 없이 DB를 사용하지 않아야 하는 경우)
+    #[cfg(any(test, feature = "self-test"))]
+    pub fn disable() {
+        if let Ok(mut config) = DB_CONFIG.write() {
+            config.enabled = false;
+        }
+    }
+
+    /// DB 사용 여부 확인
+    pub fn is_enabled() -> bool {
+        DB_CONFIG.read().map(|config| config.enabled).unwrap_or(true)
+    }
+
     /// 연결 풀 최대 크기 가져오기
     pub fn get_max_connections(&self) -> usize {
         self.connection.max_connections
@@ -0,0 +1,87 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use log::{info, warn};
+use regex::Regex;
+
+use crate::constants::{EXPORT_FETCH_SIZE, EXPORT_PROGRESS_INTERVAL};
+use crate::db::pool::{get_client, get_read_client};
+
+/// 파티션 존재 여부 확인 (파티션 테이블의 자식 테이블만 허용)
+const CHECK_IS_PARTITION: &str = "
+    SELECT EXISTS (
+        SELECT 1 FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = 'public' AND c.relname = $1
+    )";
+
+/// 파티션을 gzip 압축된 NDJSON(행마다 JSON 한 줄) 파일로 내보내기
+///
+/// 서버 측 커서로 `EXPORT_FETCH_SIZE` 행씩 읽어 바로 파일에 쓰므로 파티션 전체를 메모리에 올리지 않습니다.
+/// `drop_after`가 true이면 파일을 모두 기록한 뒤에만 파티션을 삭제합니다.
+/// 삭제하지 않는 내보내기는 읽기 복제본에서 읽고, 삭제하는 경우에는 복제 지연으로 아직 복제되지 않은 행이
+/// 파일에서 빠진 채 삭제되지 않도록 주 DB에서 읽습니다.
+pub async fn export_partition(
+    partition_name: &str,
+    path: &str,
+    drop_after: bool,
+) -> Result<u64, Box<dyn Error + Send + Sync>> {
+    // 식별자는 바인딩할 수 없으므로 이름 형식을 먼저 제한
+    let valid_name = Regex::new(r"^[a-z_][a-z0-9_]*$")?;
+    if !valid_name.is_match(partition_name) {
+        return Err(format!("잘못된 파티션 이름: {}", partition_name).into());
+    }
+
+    let mut client = if drop_after { get_client().await? } else { get_read_client().await? };
+    let is_partition: bool = client.query_one(CHECK_IS_PARTITION, &[&partition_name]).await?.get(0);
+    if !is_partition {
+        return Err(format!("파티션을 찾을 수 없습니다: {}", partition_name).into());
+    }
+
+    info!("파티션 내보내기 시작: {} -> {}", partition_name, path);
+
+    let file = File::create(path)?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    let mut exported: u64 = 0;
+
+    let transaction = client.transaction().await?;
+    let statement = transaction.prepare(&format!("SELECT row_to_json(t)::text FROM {} t", partition_name)).await?;
+    let portal = transaction.bind(&statement, &[]).await?;
+
+    loop {
+        let rows = transaction.query_portal(&portal, EXPORT_FETCH_SIZE).await?;
+        if rows.is_empty() {
+            break;
+        }
+
+        for row in &rows {
+            let line: String = row.get(0);
+            encoder.write_all(line.as_bytes())?;
+            encoder.write_all(b"\n")?;
+
+            exported += 1;
+            if exported.is_multiple_of(EXPORT_PROGRESS_INTERVAL) {
+                info!("파티션 내보내기 진행 중: {} ({} 행)", partition_name, exported);
+            }
+        }
+    }
+    transaction.commit().await?;
+
+    // 압축 스트림을 닫고 디스크에 기록된 것을 확인한 뒤에만 삭제
+    let writer = encoder.finish()?;
+    let file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()?;
+
+    info!("파티션 내보내기 완료: {} ({} 행, {})", partition_name, exported, path);
+
+    if drop_after {
+        client.execute(&format!("DROP TABLE {}", partition_name), &[]).await?;
+        warn!("내보낸 파티션 삭제: {}", partition_name);
+    }
+
+    Ok(exported)
+}
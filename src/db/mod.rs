@@ -4,6 +4,9 @@
 pub mod config;
 pub mod pool;
 pub mod partition;
+pub mod quarantine;
+pub mod export;
+pub mod report;
 pub mod query;
 
 // 외부로 노출할 항목들
@@ -1,11 +1,17 @@
 use std::error::Error;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use tokio_postgres::Client;
 use chrono::{Duration, Datelike, Timelike};
 
 use crate::constants::{request_logs, proxy_stats, proxy_stats_hourly};
 use crate::db::config::DbConfig;
 use crate::db::pool::get_client;
+use crate::db::quarantine::{quarantine_enabled, quarantine_partition, quarantined_partitions, DbFailure};
+
+/// 일 단위 파티션 이름 (예: request_logs_20261015)
+pub fn partition_name(table_name: &str, date: chrono::NaiveDate) -> String {
+    format!("{}_{:04}{:02}{:02}", table_name, date.year(), date.month(), date.day())
+}
 
 /// 테이블 유형 열거형
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -78,7 +84,12 @@ impl PartitionManager {
             },
             Ok(Err(e)) => {
                 error!("파티션 생성 스크립트 실행 실패: {}", e);
-                return Err(e.into());
+                let e: Box<dyn Error + Send + Sync> = e.into();
+                // 격리 정책이면 파티션 하나의 문제로 전체가 실패하지 않도록 하루씩 따로 생성
+                if quarantine_enabled() && DbFailure::classify(&*e) == DbFailure::Structural {
+                    return Ok(self.create_partitions_individually(client, table_type, start_date, num_days).await);
+                }
+                return Err(e);
             },
             Err(_) => {
                 error!("파티션 생성 스크립트 실행 타임아웃");
@@ -89,6 +100,34 @@ impl PartitionManager {
         Ok(created_partitions)
     }
     
+    /// 파티션을 하루씩 따로 생성 (구조적인 오류로 실패한 파티션은 격리하고 나머지는 계속 생성)
+    async fn create_partitions_individually(
+        &self,
+        client: &Client,
+        table_type: TableType,
+        start_date: chrono::NaiveDate,
+        num_days: i32
+    ) -> Vec<String> {
+        let table_name = table_type.get_name();
+        let mut created_partitions = Vec::new();
+        // 생성 스크립트와 같이 오늘부터 num_days일 뒤까지
+        for i in 0..=num_days {
+            let date = start_date + Duration::days(i as i64);
+            match self.create_partition(client, table_type, date).await {
+                Ok(partition_name) => created_partitions.push(partition_name),
+                Err(e) => {
+                    let partition_name = self.get_partition_name(table_name, date);
+                    if DbFailure::classify(&*e) == DbFailure::Structural {
+                        quarantine_partition(table_name, &partition_name, &e.to_string()).await;
+                    } else {
+                        error!("파티션 생성 실패: {} - {}", partition_name, e);
+                    }
+                }
+            }
+        }
+        created_partitions
+    }
+    
     /// 파티션별 인덱스 생성
     async fn create_partition_indices(
         &self,
@@ -132,7 +171,6 @@ impl PartitionManager {
     }
     
     /// 단일 파티션 생성
-    #[allow(dead_code)]
     async fn create_partition(
         &self,
         client: &Client,
@@ -187,16 +225,7 @@ impl PartitionManager {
     
     /// 파티션 이름 생성
     fn get_partition_name(&self, table_name: &str, date: chrono::NaiveDate) -> String {
-        let year = date.year();
-        let month = date.month();
-        let day = date.day();
-        
-        format!("{}_{:04}{:02}{:02}", 
-            table_name, 
-            year, 
-            month, 
-            day
-        )
+        partition_name(table_name, date)
     }
     
     /// 파티션이 존재하는지 확인
@@ -313,6 +342,12 @@ impl PartitionManager {
                     }
                 }
                 
+                // 격리 중인 파티션은 복구될 때까지 매일 알림
+                for (partition, entry) in quarantined_partitions() {
+                    warn!("[PARTITION-QUARANTINE] 격리 중인 파티션 (수동 복구 필요): {} ({}부터, 기본 파티션 우회 {}): {}",
+                          partition, entry.since.to_rfc3339(), entry.routed, entry.reason);
+                }
+                
                 info!("파티션 생성 작업 완료");
             }
             
@@ -520,6 +555,13 @@ async fn create_tables(client: &Client) -> Result<(), Box<dyn Error + Send + Syn
     match client.execute(request_logs::CREATE_TABLE, &[]).await {
         Ok(_) => {
             info!("request_logs 테이블 생성 확인 완료");
+            // 테넌트 태그/차단 분류 컬럼이 추가되기 전에 만든 테이블 (인덱스보다 먼저 추가)
+            if let Err(e) = client.execute(request_logs::ADD_TENANT_COLUMN, &[]).await {
+                error!("request_logs 테넌트 컬럼 추가 실패: {}", e);
+            }
+            if let Err(e) = client.execute(request_logs::ADD_BLOCK_CATEGORY_COLUMN, &[]).await {
+                error!("request_logs 차단 분류 컬럼 추가 실패: {}", e);
+            }
             // request_logs 인덱스 생성
             for index_query in request_logs::CREATE_INDICES {
                 if let Err(e) = client.execute(index_query, &[]).await {
@@ -1,5 +1,6 @@
 use std::error::Error;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use deadpool_postgres::{Config, Pool, Runtime, PoolError};
@@ -8,13 +9,26 @@ use once_cell::sync::Lazy;
 use tokio::sync::RwLock;
 use log::{debug, error, info, warn};
 
-use super::config::DbConfig;
+use super::config::{ConnectionConfig, DbConfig};
 
 // 데이터베이스 연결 풀을 전역적으로 관리하는 싱글톤
 pub static DB_POOL: Lazy<RwLock<Option<Arc<DatabasePool>>>> = Lazy::new(|| {
     RwLock::new(None)
 });
 
+// 조회/내보내기용 읽기 전용 복제본 연결 풀 (db.yml read_replica 설정 시)
+pub static DB_READ_POOL: Lazy<RwLock<Option<Arc<ReadReplica>>>> = Lazy::new(|| {
+    RwLock::new(None)
+});
+
+/// 복제 지연(초) 조회 (복제본이 아니거나 받은 WAL을 모두 재생했으면 0)
+const SELECT_REPLICA_LAG: &str = "
+    SELECT CASE
+        WHEN NOT pg_is_in_recovery() THEN 0
+        WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+        ELSE COALESCE(EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp()), 0)
+    END::float8";
+
 /// 데이터베이스 풀 관리자 구조체
 pub struct DatabasePool {
     pool: Pool,
@@ -57,6 +71,45 @@ impl DatabasePool {
     }
 }
 
+/// 읽기 전용 복제본 연결 풀과 복제 지연 상태
+pub struct ReadReplica {
+    pool: DatabasePool,
+    max_lag: Duration,
+    // 마지막 확인에서 지연이 허용 범위를 넘었거나 확인하지 못했는지 (그동안은 주 DB에서 읽음)
+    lagging: AtomicBool,
+}
+
+impl ReadReplica {
+    /// 복제 지연을 확인해 복제본 사용 여부 갱신
+    pub async fn check_lag(&self) {
+        let lag = match self.pool.get_client().await {
+            Ok(client) => client.query_one(SELECT_REPLICA_LAG, &[]).await
+                .map(|row| row.get::<_, f64>(0))
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        
+        let lagging = match lag {
+            Ok(lag) if lag > self.max_lag.as_secs_f64() => {
+                warn!("DB 복제본 지연 {:.1}초가 허용 범위({}초)를 넘어 주 DB에서 읽습니다", lag, self.max_lag.as_secs());
+                true
+            },
+            Ok(lag) => {
+                debug!("DB 복제본 지연: {:.1}초", lag);
+                false
+            },
+            Err(e) => {
+                warn!("DB 복제본 지연 확인 실패, 주 DB에서 읽습니다: {}", e);
+                true
+            }
+        };
+        
+        if self.lagging.swap(lagging, Ordering::Relaxed) && !lagging {
+            info!("DB 복제본 지연이 허용 범위로 돌아와 다시 복제본에서 읽습니다");
+        }
+    }
+}
+
 /// 풀 상태 구조체
 pub struct PoolStatus {
     pub max_size: usize,
@@ -68,8 +121,47 @@ pub struct PoolStatus {
 pub async fn create_db_pool() -> Result<Arc<DatabasePool>, Box<dyn Error + Send + Sync>> {
     // DB 설정 로드
     let db_config = DbConfig::get()?;
-    let conn_config = &db_config.connection;
+    if !db_config.enabled {
+        return Err("데이터베이스가 비활성화되어 있습니다 (db.yml enabled: false)".into());
+    }
+    let pool = build_pool(&db_config.connection).await?;
+    info!("DB 연결 풀 생성 완료 및 연결 테스트 성공");
+    
+    // 풀 관리자 생성
+    let pool_manager = Arc::new(DatabasePool::new(pool, db_config.clone()));
+    
+    // 전역 풀 설정
+    let mut global_pool = DB_POOL.write().await;
+    *global_pool = Some(Arc::clone(&pool_manager));
+    
+    Ok(pool_manager)
+}
+
+/// 읽기 전용 복제본 연결 풀 생성 (설정되지 않았으면 None)
+pub async fn create_read_pool() -> Result<Option<Arc<ReadReplica>>, Box<dyn Error + Send + Sync>> {
+    let db_config = DbConfig::get()?;
+    let Some(replica_config) = &db_config.read_replica else {
+        return Ok(None);
+    };
+    
+    let pool = build_pool(replica_config).await?;
+    info!("DB 복제본 연결 풀 생성 완료: {}:{}/{}", replica_config.host, replica_config.port, replica_config.database);
+    
+    let mut pool_config = db_config.clone();
+    pool_config.connection = replica_config.clone();
+    let replica = Arc::new(ReadReplica {
+        pool: DatabasePool::new(pool, pool_config),
+        max_lag: Duration::from_secs(db_config.read_replica_max_lag_seconds),
+        lagging: AtomicBool::new(false),
+    });
+    replica.check_lag().await;
     
+    *DB_READ_POOL.write().await = Some(Arc::clone(&replica));
+    Ok(Some(replica))
+}
+
+/// 접속 정보로 deadpool 연결 풀 생성 후 연결 테스트
+async fn build_pool(conn_config: &ConnectionConfig) -> Result<Pool, Box<dyn Error + Send + Sync>> {
     // deadpool-postgres 설정 생성
     let mut cfg = Config::new();
     cfg.host = Some(conn_config.host.clone());
@@ -92,16 +184,8 @@ pub async fn create_db_pool() -> Result<Arc<DatabasePool>, Box<dyn Error + Send
     // 연결 테스트
     let client = pool.get().await?;
     client.execute("SELECT 1", &[]).await?;
-    info!("DB 연결 풀 생성 완료 및 연결 테스트 성공");
-    
-    // 풀 관리자 생성
-    let pool_manager = Arc::new(DatabasePool::new(pool, db_config.clone()));
     
-    // 전역 풀 설정
-    let mut global_pool = DB_POOL.write().await;
-    *global_pool = Some(Arc::clone(&pool_manager));
-    
-    Ok(pool_manager)
+    Ok(pool)
 }
 
 /// 전역 데이터베이스 연결 풀 가져오기
@@ -126,6 +210,22 @@ pub async fn get_client() -> Result<deadpool::managed::Object<deadpool_postgres:
     }
 }
 
+/// 조회/내보내기용 클라이언트 가져오기
+///
+/// 복제본이 설정되지 않았거나, 지연이 허용 범위를 넘었거나, 연결할 수 없으면 주 DB 클라이언트를 반환합니다.
+/// 쓰기는 항상 `get_client`로 주 DB에 해야 합니다.
+pub async fn get_read_client() -> Result<deadpool::managed::Object<deadpool_postgres::Manager>, PoolError> {
+    let replica = DB_READ_POOL.read().await.clone();
+    if let Some(replica) = replica
+        && !replica.lagging.load(Ordering::Relaxed) {
+        match replica.pool.get_client().await {
+            Ok(client) => return Ok(client),
+            Err(e) => warn!("DB 복제본 연결 실패, 주 DB에서 읽습니다: {}", e),
+        }
+    }
+    get_client().await
+}
+
 /// 연결 풀 초기화 및 테스트
 pub async fn initialize_pool() -> Result<(), Box<dyn Error + Send + Sync>> {
     // 연결 풀 생성
@@ -137,7 +237,16 @@ pub async fn initialize_pool() -> Result<(), Box<dyn Error + Send + Sync>> {
     let version: String = result.get(0);
     info!("DB 연결 성공: {}", version);
     
-    // 주기적인 풀 관리 작업 시작
+    // 복제본 연결 실패는 치명적이지 않음 (읽기도 주 DB 사용)
+    let replica = match create_read_pool().await {
+        Ok(replica) => replica,
+        Err(e) => {
+            warn!("DB 복제본 연결 풀 생성 실패, 조회도 주 DB에서 합니다: {}", e);
+            None
+        }
+    };
+    
+    // 주기적인 풀 관리 작업 시작 (복제본 지연 확인 포함)
     let pool_clone = Arc::clone(&pool);
     tokio::spawn(async move {
         loop {
@@ -145,6 +254,9 @@ pub async fn initialize_pool() -> Result<(), Box<dyn Error + Send + Sync>> {
             if let Err(e) = pool_clone.maintenance().await {
                 warn!("DB 풀 관리 작업 실패: {}", e);
             }
+            if let Some(replica) = &replica {
+                replica.check_lag().await;
+            }
         }
     });
     
@@ -0,0 +1,219 @@
+// 손상된 파티션 격리 (db.yml log_write.on_partition_failure: quarantine)
+// 파티션 하나가 손상되거나 없어서 쓰기가 계속 실패하면 그 파티션만 격리하고, 그 파티션으로 갈 로그는
+// 데드레터 파일이나 기본(DEFAULT) 파티션으로 돌려 나머지 로그 저장이 멈추지 않게 합니다.
+// 일시적인 오류(연결 끊김, 교착 상태, 타임아웃 등)와 쿼리/스키마 오류는 격리하지 않고 기존처럼 재시도합니다.
+// 손상된 파티션은 자동으로 떼어 내지 않고 복구 명령만 로그로 남기며, 격리 목록은 메모리에만 두므로
+// 파티션을 복구한 뒤 재시작하거나 관리 엔드포인트(DELETE /db/quarantine/{partition})로 해제합니다.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::RwLock;
+
+use chrono::{DateTime, Local, Utc};
+use log::{error, warn};
+use once_cell::sync::Lazy;
+use tokio_postgres::error::SqlState;
+
+use crate::db::config::{DbConfig, PartitionFailurePolicy, QuarantineTarget};
+use crate::db::partition::partition_name;
+use crate::db::pool::get_client;
+use crate::metrics::Metrics;
+
+/// 격리한 파티션 정보
+#[derive(Clone, Debug)]
+pub struct QuarantinedPartition {
+    /// 부모 테이블 이름
+    pub table: String,
+    /// 격리 사유 (처음 실패한 오류)
+    pub reason: String,
+    /// 격리 시각
+    pub since: DateTime<Utc>,
+    /// 기본 파티션으로 우회했는지 (true면 DB에 계속 기록)
+    pub routed: bool,
+}
+
+// 격리한 파티션 (파티션 이름 -> 정보)
+static QUARANTINED: Lazy<RwLock<HashMap<String, QuarantinedPartition>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 저장 실패 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbFailure {
+    /// 다시 시도하면 성공할 수 있는 오류 (연결, 교착 상태, 자원 부족, 타임아웃 등)
+    Transient,
+    /// 파티션 자체의 문제로 다시 시도해도 실패하는 오류 (손상, 파일 오류, 파티션 없음)
+    Structural,
+}
+
+impl DbFailure {
+    /// 저장 오류 분류 (PostgreSQL 오류 코드가 없으면 일시적인 오류로 봄)
+    pub fn classify(e: &(dyn Error + Send + Sync + 'static)) -> Self {
+        e.downcast_ref::<tokio_postgres::Error>()
+            .and_then(|e| e.as_db_error())
+            .map_or(DbFailure::Transient, |db_error| Self::from_sqlstate(db_error.code()))
+    }
+
+    /// SQLSTATE 코드 분류
+    pub fn from_sqlstate(code: &SqlState) -> Self {
+        let structural = [
+            SqlState::DATA_CORRUPTED,  // XX001 데이터 페이지 손상
+            SqlState::INDEX_CORRUPTED, // XX002 인덱스 손상
+            SqlState::UNDEFINED_FILE,  // 58P01 파티션 파일 없음
+            SqlState::IO_ERROR,        // 58030 파티션 파일 읽기/쓰기 오류
+            SqlState::UNDEFINED_TABLE, // 42P01 파티션이 삭제되었거나 떼어짐
+            SqlState::CHECK_VIOLATION, // 23514 행이 들어갈 파티션 없음
+        ];
+        if structural.contains(code) {
+            DbFailure::Structural
+        } else {
+            DbFailure::Transient
+        }
+    }
+}
+
+/// 로그 시각이 들어갈 일 단위 파티션 이름 (파티션 스케줄러와 같은 로컬 날짜 기준)
+pub fn log_partition(table: &str, timestamp: &DateTime<Utc>) -> String {
+    partition_name(table, timestamp.with_timezone(&Local).date_naive())
+}
+
+/// 격리 정책을 쓰는지 (db.yml log_write.on_partition_failure)
+pub fn quarantine_enabled() -> bool {
+    DbConfig::get().is_ok_and(|config| config.log_write.on_partition_failure == PartitionFailurePolicy::Quarantine)
+}
+
+/// 파티션 격리 (처음 격리하면 오류 로그와 메트릭을 남기고, quarantine_target이 fallback_partition이면 기본 파티션으로 우회)
+///
+/// 우회해서 그 파티션의 로그를 계속 DB에 기록할 수 있으면 true, 데드레터로 보내야 하면 false
+pub async fn quarantine_partition(table: &str, partition: &str, reason: &str) -> bool {
+    let routed = {
+        let mut quarantined = QUARANTINED.write().unwrap();
+        match quarantined.get(partition) {
+            Some(entry) => entry.routed,
+            None => {
+                quarantined.insert(partition.to_string(), QuarantinedPartition {
+                    table: table.to_string(),
+                    reason: reason.to_string(),
+                    since: Utc::now(),
+                    routed: false,
+                });
+                let total = Metrics::new().db_partition_quarantined();
+                error!("[PARTITION-QUARANTINE] 파티션 {} 격리 (수동 복구 필요, 격리 중 {} 개): {}", partition, total, reason);
+                false
+            }
+        }
+    };
+    if routed {
+        return true;
+    }
+
+    let target = DbConfig::get().map(|config| config.log_write.quarantine_target).unwrap_or_default();
+    if target != QuarantineTarget::FallbackPartition {
+        return false;
+    }
+    match route_to_fallback(table, partition).await {
+        Ok(Some(fallback)) => {
+            if let Some(entry) = QUARANTINED.write().unwrap().get_mut(partition) {
+                entry.routed = true;
+            }
+            warn!("[PARTITION-QUARANTINE] 파티션 {}이 없어 {}의 로그를 기본 파티션 {}에 기록합니다", partition, table, fallback);
+            true
+        },
+        Ok(None) => {
+            error!("[PARTITION-QUARANTINE] 파티션 {}이 부모 테이블에 붙어 있어 로그를 데드레터 파일에 기록합니다. \
+                    데이터를 확인한 뒤 'ALTER TABLE {} DETACH PARTITION {};'로 떼어 내고 격리를 해제하면 기본 파티션에 기록합니다",
+                   partition, table, partition);
+            false
+        },
+        Err(e) => {
+            error!("[PARTITION-QUARANTINE] 기본 파티션으로 우회 실패, {} 로그는 데드레터 파일에 기록합니다: {}", partition, e);
+            false
+        }
+    }
+}
+
+/// 기본 파티션을 만들고, 격리한 파티션이 부모 테이블에 붙어 있지 않으면 기본 파티션 이름 반환
+///
+/// 붙어 있는 파티션은 떼어 내지 않으므로(DETACH는 부모 테이블을 잠그고 기존 로그를 조회에서 빼므로 운영자가 직접 실행)
+/// 그 날짜의 로그는 기본 파티션으로 가지 않고 None
+async fn route_to_fallback(table: &str, partition: &str) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+    let client = get_client().await?;
+    let fallback = format!("{}_quarantine", table);
+    client.batch_execute(&format!("CREATE TABLE IF NOT EXISTS {} PARTITION OF {} DEFAULT", fallback, table)).await?;
+    let attached: bool = client
+        .query_one("SELECT EXISTS (SELECT 1 FROM pg_class WHERE relname = $1 AND relispartition)", &[&partition])
+        .await?
+        .get(0);
+    Ok((!attached).then_some(fallback))
+}
+
+/// 로그를 DB에 기록할 것과 격리한(우회하지 않은) 파티션으로 갈 것으로 나눔
+pub fn split_quarantined<T>(logs: Vec<T>, table: &str, timestamp: impl Fn(&T) -> &DateTime<Utc>) -> (Vec<T>, Vec<T>) {
+    let quarantined = QUARANTINED.read().unwrap();
+    if quarantined.values().all(|entry| entry.routed || entry.table != table) {
+        return (logs, Vec::new());
+    }
+    logs.into_iter().partition(|log| {
+        quarantined.get(&log_partition(table, timestamp(log))).is_none_or(|entry| entry.routed)
+    })
+}
+
+/// 격리 중인 파티션 목록 (이름순)
+pub fn quarantined_partitions() -> Vec<(String, QuarantinedPartition)> {
+    let mut partitions: Vec<_> = QUARANTINED.read().unwrap().iter()
+        .map(|(name, entry)| (name.clone(), entry.clone()))
+        .collect();
+    partitions.sort_by(|a, b| a.0.cmp(&b.0));
+    partitions
+}
+
+/// 파티션 격리 해제 (복구 후, 관리 엔드포인트 DELETE /db/quarantine/{partition})
+pub fn release_partition(partition: &str) -> bool {
+    let released = QUARANTINED.write().unwrap().remove(partition).is_some();
+    if released {
+        Metrics::new().db_partition_released();
+        log::info!("[PARTITION-QUARANTINE] 파티션 {} 격리 해제", partition);
+    }
+    released
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_corruption_and_missing_partitions_are_structural() {
+        for code in [SqlState::DATA_CORRUPTED, SqlState::INDEX_CORRUPTED, SqlState::CHECK_VIOLATION, SqlState::UNDEFINED_TABLE] {
+            assert_eq!(DbFailure::from_sqlstate(&code), DbFailure::Structural, "{}", code.code());
+        }
+        for code in [
+            SqlState::T_R_DEADLOCK_DETECTED, SqlState::T_R_SERIALIZATION_FAILURE, SqlState::CONNECTION_FAILURE, SqlState::QUERY_CANCELED,
+            SqlState::UNDEFINED_COLUMN, SqlState::DATATYPE_MISMATCH,
+        ] {
+            assert_eq!(DbFailure::from_sqlstate(&code), DbFailure::Transient, "{}", code.code());
+        }
+        let timeout_error: Box<dyn Error + Send + Sync> = "요청 로그 저장 타임아웃".into();
+        assert_eq!(DbFailure::classify(&*timeout_error), DbFailure::Transient);
+    }
+
+    #[tokio::test]
+    async fn quarantined_partition_logs_are_diverted_until_released() {
+        DbConfig::disable();
+        let metrics = Metrics::new();
+        let before = metrics.get("db_partitions_quarantined");
+        let table = "unit_test_logs";
+        let today = Utc::now();
+        let logs = vec![(today, 1), (today - chrono::Duration::days(3), 2), (today, 3)];
+        let partition = log_partition(table, &today);
+
+        // 데드레터 대상(기본값)이면 DB에 접속하지 않고 격리만 기록
+        assert!(!quarantine_partition(table, &partition, "XX001 invalid page in block").await);
+        let (kept, diverted) = split_quarantined(logs.clone(), table, |log| &log.0);
+        assert_eq!(kept.iter().map(|log| log.1).collect::<Vec<_>>(), [2]);
+        assert_eq!(diverted.iter().map(|log| log.1).collect::<Vec<_>>(), [1, 3]);
+
+        assert!(release_partition(&partition));
+        assert_eq!(metrics.get("db_partitions_quarantined"), before);
+        let (kept, diverted) = split_quarantined(logs, table, |log| &log.0);
+        assert_eq!(kept.len(), 3);
+        assert!(diverted.is_empty());
+    }
+}
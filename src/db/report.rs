@@ -0,0 +1,204 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use chrono::{DateTime, Utc};
+use log::info;
+use serde_json::json;
+use tokio_postgres::types::Type;
+
+use crate::constants::EXPORT_FETCH_SIZE;
+use crate::db::pool::get_read_client;
+
+/// 차단 요청 보고서 묶음 기준
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportGroup {
+    /// 차단 분류 (일치한 차단 규칙 유형, 기록되지 않았으면 빈 값)
+    Category,
+    /// 차단된 호스트
+    Host,
+    /// 클라이언트 주소 대역 (CIDR)
+    Client,
+}
+
+impl ReportGroup {
+    /// `--group-by` 값 해석 (`category`, `host`, `client`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "category" => Some(ReportGroup::Category),
+            "host" => Some(ReportGroup::Host),
+            "client" => Some(ReportGroup::Client),
+            _ => None,
+        }
+    }
+
+    /// 출력 열 이름
+    fn column(&self) -> &'static str {
+        match self {
+            ReportGroup::Category => "category",
+            ReportGroup::Host => "host",
+            ReportGroup::Client => "client_cidr",
+        }
+    }
+
+    /// 묶음 SQL 식 (클라이언트는 IPv4 $3, IPv6 $4 길이의 대역으로 묶음)
+    fn expression(&self) -> &'static str {
+        match self {
+            ReportGroup::Category => "COALESCE(block_category, '')",
+            ReportGroup::Host => "host",
+            ReportGroup::Client => "CASE WHEN family(client_ip::inet) = 4 \
+                THEN network(set_masklen(client_ip::inet, $3))::text \
+                ELSE network(set_masklen(client_ip::inet, $4))::text END",
+        }
+    }
+}
+
+/// 보고서 출력 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    /// `--format` 값 해석 (`csv`, `json`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "csv" => Some(ReportFormat::Csv),
+            "json" => Some(ReportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// 차단 요청 보고서 조건
+#[derive(Debug, Clone)]
+pub struct BlockedReport {
+    /// 시작 시각 (포함)
+    pub from: DateTime<Utc>,
+    /// 끝 시각 (제외)
+    pub to: DateTime<Utc>,
+    pub group_by: Vec<ReportGroup>,
+    /// 클라이언트 대역 접두사 길이 (IPv4, IPv6)
+    pub client_prefix_v4: i32,
+    pub client_prefix_v6: i32,
+    pub format: ReportFormat,
+    /// 출력 파일 경로 (None이면 표준 출력)
+    pub output: Option<String>,
+}
+
+/// 기간 안의 차단 요청(`request_logs.is_rejected`)을 묶음 기준별로 집계해 CSV 또는 JSON으로 출력
+///
+/// 조건은 인덱스가 있는 `timestamp`(파티션 범위)와 `is_rejected` 열만 사용하고, 집계 결과는
+/// 서버 측 커서로 `EXPORT_FETCH_SIZE` 행씩 읽어 바로 쓰므로 결과 전체를 메모리에 올리지 않습니다.
+/// 보고서는 읽기 복제본에서 읽습니다.
+pub async fn write_blocked_report(report: &BlockedReport) -> Result<u64, Box<dyn Error + Send + Sync>> {
+    if report.group_by.is_empty() {
+        return Err("묶음 기준이 하나 이상 필요합니다".into());
+    }
+    if report.from >= report.to {
+        return Err(format!("잘못된 기간: {} ~ {}", report.from, report.to).into());
+    }
+
+    let mut writer: Box<dyn Write + Send> = match &report.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout())),
+    };
+
+    info!("차단 요청 보고서 생성 시작: {} ~ {} (묶음 {:?})", report.from, report.to, report.group_by);
+
+    let mut client = get_read_client().await?;
+    let transaction = client.transaction().await?;
+    // 클라이언트 대역으로 묶지 않으면 $3/$4가 쓰이지 않으므로 매개변수 형식을 직접 지정
+    let statement = transaction.prepare_typed(
+        &report_query(&report.group_by),
+        &[Type::TIMESTAMPTZ, Type::TIMESTAMPTZ, Type::INT4, Type::INT4],
+    ).await?;
+    let portal = transaction.bind(
+        &statement,
+        &[&report.from, &report.to, &report.client_prefix_v4, &report.client_prefix_v6],
+    ).await?;
+
+    let keys = report.group_by.len();
+    if report.format == ReportFormat::Csv {
+        let mut header: Vec<&str> = report.group_by.iter().map(|group| group.column()).collect();
+        header.extend(["blocked", "first_seen", "last_seen"]);
+        writeln!(writer, "{}", header.join(","))?;
+    } else {
+        writer.write_all(b"[")?;
+    }
+
+    let mut written: u64 = 0;
+    loop {
+        let rows = transaction.query_portal(&portal, EXPORT_FETCH_SIZE).await?;
+        if rows.is_empty() {
+            break;
+        }
+
+        for row in &rows {
+            let values: Vec<String> = (0..keys).map(|index| row.get(index)).collect();
+            let blocked: i64 = row.get(keys);
+            let first_seen: DateTime<Utc> = row.get(keys + 1);
+            let last_seen: DateTime<Utc> = row.get(keys + 2);
+
+            match report.format {
+                ReportFormat::Csv => {
+                    for value in &values {
+                        write!(writer, "{},", csv_field(value))?;
+                    }
+                    writeln!(writer, "{},{},{}", blocked, first_seen.to_rfc3339(), last_seen.to_rfc3339())?;
+                }
+                ReportFormat::Json => {
+                    let mut object = serde_json::Map::new();
+                    for (group, value) in report.group_by.iter().zip(values) {
+                        object.insert(group.column().to_string(), json!(value));
+                    }
+                    object.insert("blocked".to_string(), json!(blocked));
+                    object.insert("first_seen".to_string(), json!(first_seen.to_rfc3339()));
+                    object.insert("last_seen".to_string(), json!(last_seen.to_rfc3339()));
+                    if written > 0 {
+                        writer.write_all(b",")?;
+                    }
+                    writer.write_all(b"\n  ")?;
+                    serde_json::to_writer(&mut writer, &object)?;
+                }
+            }
+            written += 1;
+        }
+    }
+    transaction.commit().await?;
+
+    if report.format == ReportFormat::Json {
+        writer.write_all(b"\n]\n")?;
+    }
+    writer.flush()?;
+
+    info!("차단 요청 보고서 생성 완료: {} 행", written);
+    Ok(written)
+}
+
+/// 묶음 기준에 맞는 집계 쿼리 ($1 시작, $2 끝, $3/$4 클라이언트 대역 길이)
+fn report_query(group_by: &[ReportGroup]) -> String {
+    let keys: Vec<String> = group_by.iter()
+        .map(|group| format!("{} AS {}", group.expression(), group.column()))
+        .collect();
+    let positions: Vec<String> = (1..=group_by.len()).map(|position| position.to_string()).collect();
+
+    format!(
+        "SELECT {}, COUNT(*)::bigint AS blocked, MIN(timestamp) AS first_seen, MAX(timestamp) AS last_seen
+         FROM request_logs
+         WHERE is_rejected = TRUE AND timestamp >= $1 AND timestamp < $2
+         GROUP BY {}
+         ORDER BY blocked DESC, {}",
+        keys.join(", "), positions.join(", "), positions.join(", ")
+    )
+}
+
+/// CSV 필드 (쉼표, 따옴표, 줄바꿈이 있으면 따옴표로 감쌈)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
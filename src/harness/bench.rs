@@ -0,0 +1,255 @@
+// 내장 부하 생성기 (--bench, `bench` 기능으로 빌드할 때만 포함)
+// 하네스 프록시와 로컬 TLS 에코 서버를 띄운 뒤 여러 연결에서 CONNECT 터널로 요청을 반복해
+// 처리량, 지연 시간 백분위수, 버퍼 풀 재사용률을 출력합니다.
+// 평문 터널(--plain)과 작은 조각 쓰기(--write-chunk)로 쓰기 합치기(write_coalescing) 효과도 비교할 수 있습니다.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{debug, info};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::buffer::BufferPool;
+use crate::error::{ProxyError, Result, internal_err};
+use crate::metrics::Metrics;
+
+use super::{HARNESS_IO_TIMEOUT, ProxyHarness, spawn_tcp_echo_server, spawn_tls_echo_server};
+
+/// 연결 실패 후 재시도 전 대기 시간
+const BENCH_RECONNECT_DELAY: Duration = Duration::from_millis(10);
+
+/// 부하 생성 조건
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// 동시 연결 수
+    pub concurrency: usize,
+    /// 요청 한 번에 보내는 바이트 수 (같은 크기의 에코 응답을 기다림)
+    pub request_size: usize,
+    /// 측정 시간
+    pub duration: Duration,
+    /// 연결 하나에서 보낼 요청 수 (넘으면 새 CONNECT 터널을 엶)
+    pub requests_per_connection: u64,
+    /// TLS 대신 평문 TCP 에코 서버로 가로채지 않는 터널을 측정
+    pub plain: bool,
+    /// 요청을 이 크기의 조각으로 나눠 쓰기 (없으면 한 번에 씀)
+    pub write_chunk: Option<usize>,
+    /// 프록시의 write_coalescing.max_bytes (0이면 사용 안 함)
+    pub coalesce_bytes: usize,
+    /// 프록시의 write_coalescing.max_delay_ms
+    pub coalesce_delay_ms: u64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 32,
+            request_size: 4096,
+            duration: Duration::from_secs(10),
+            requests_per_connection: 100,
+            plain: false,
+            write_chunk: None,
+            coalesce_bytes: 0,
+            coalesce_delay_ms: 1,
+        }
+    }
+}
+
+/// 측정에 쓰는 클라이언트 연결 (TLS 또는 평문 터널)
+trait BenchStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> BenchStream for T {}
+
+/// 연결 하나가 측정한 결과
+#[derive(Default)]
+struct WorkerResult {
+    latencies_us: Vec<u64>,
+    connections: u64,
+    errors: u64,
+}
+
+/// 하네스 프록시에 부하를 걸고 결과를 표준 출력에 출력
+pub async fn run(options: BenchOptions, buffer_pool: Arc<BufferPool>) -> Result<()> {
+    if options.concurrency == 0 || options.request_size == 0 || options.duration.is_zero() {
+        return Err(internal_err("concurrency, request size and duration must be greater than 0"));
+    }
+
+    let (echo_addr, echo_handle) = if options.plain {
+        spawn_tcp_echo_server().await?
+    } else {
+        spawn_tls_echo_server().await?
+    };
+    let mut config = ProxyHarness::default_config();
+    config.write_coalescing.max_bytes = options.coalesce_bytes;
+    config.write_coalescing.max_delay_ms = options.coalesce_delay_ms;
+    config.validate().map_err(|e| internal_err(format!("invalid bench config: {}", e)))?;
+    let harness = Arc::new(
+        ProxyHarness::start_with_buffer_pool(config, Some(Arc::clone(&buffer_pool))).await?
+    );
+    let metrics = Metrics::new();
+    let metrics_before = metrics.exported_fields();
+    let (reuses_before, allocations_before) = buffer_pool.reuse_stats();
+
+    info!(
+        "bench: {} connections, {} byte requests, {}s via {} to {}",
+        options.concurrency, options.request_size, options.duration.as_secs_f64(), harness.addr(), echo_addr
+    );
+
+    let started = Instant::now();
+    let deadline = started + options.duration;
+    let workers: Vec<_> = (0..options.concurrency)
+        .map(|_| tokio::spawn(bench_worker(Arc::clone(&harness), echo_addr, options.clone(), deadline)))
+        .collect();
+
+    let mut total = WorkerResult::default();
+    for worker in workers {
+        let result = worker.await
+            .map_err(|e| internal_err(format!("bench worker failed: {}", e)))?;
+        total.latencies_us.extend(result.latencies_us);
+        total.connections += result.connections;
+        total.errors += result.errors;
+    }
+    let elapsed = started.elapsed();
+
+    let metrics_after = metrics.exported_fields();
+    let (reuses_after, allocations_after) = buffer_pool.reuse_stats();
+
+    let harness = Arc::try_unwrap(harness)
+        .map_err(|_| internal_err("bench harness still in use"))?;
+    harness.shutdown().await?;
+    echo_handle.abort();
+
+    total.latencies_us.sort_unstable();
+    let requests = total.latencies_us.len() as u64;
+    let seconds = elapsed.as_secs_f64();
+    let bytes = requests * options.request_size as u64 * 2;
+    let metric_delta = |name: &str| {
+        let value = |fields: &[(&'static str, u64)]| fields.iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, value)| *value)
+            .unwrap_or(0);
+        value(&metrics_after).saturating_sub(value(&metrics_before))
+    };
+    let reuses = reuses_after.saturating_sub(reuses_before);
+    let allocations = allocations_after.saturating_sub(allocations_before);
+
+    println!("duration        {:.2}s", seconds);
+    println!("concurrency     {}", options.concurrency);
+    println!("request size    {} bytes", options.request_size);
+    println!("mode            {} tunnel, {} writes, coalescing {}",
+        if options.plain { "plain" } else { "tls" },
+        options.write_chunk.map(|chunk| format!("{} byte", chunk)).unwrap_or_else(|| "whole".to_string()),
+        if options.coalesce_bytes > 0 {
+            format!("{} bytes / {}ms", options.coalesce_bytes, options.coalesce_delay_ms)
+        } else {
+            "off".to_string()
+        });
+    println!("requests        {} ({} errors, {} connections)", requests, total.errors, total.connections);
+    println!("throughput      {:.1} req/s, {:.2} MiB/s (sent + received)",
+        requests as f64 / seconds, bytes as f64 / seconds / (1024.0 * 1024.0));
+    println!("latency         p50 {}  p90 {}  p99 {}  max {}",
+        format_latency(percentile(&total.latencies_us, 50.0)),
+        format_latency(percentile(&total.latencies_us, 90.0)),
+        format_latency(percentile(&total.latencies_us, 99.0)),
+        format_latency(total.latencies_us.last().copied()));
+    println!("buffer pool     {:.1}% reused ({} reused, {} allocated)",
+        ratio(reuses, reuses + allocations) * 100.0, reuses, allocations);
+    println!("proxy metrics   sessions {}, tls bytes in {}, tls bytes out {}, tls handshakes {}",
+        metric_delta("sessions_total"), metric_delta("tls_bytes_in"),
+        metric_delta("tls_bytes_out"), metric_delta("tls_handshakes"));
+
+    if requests == 0 {
+        return Err(internal_err(format!("bench completed no requests ({} errors)", total.errors)));
+    }
+    Ok(())
+}
+
+/// 기한까지 CONNECT 터널을 열고 요청/에코 왕복을 반복
+async fn bench_worker(
+    harness: Arc<ProxyHarness>,
+    echo_addr: SocketAddr,
+    options: BenchOptions,
+    deadline: Instant,
+) -> WorkerResult {
+    let mut result = WorkerResult::default();
+    let payload = vec![0x5a; options.request_size];
+    let mut echoed = vec![0u8; options.request_size];
+
+    let chunk_size = options.write_chunk.unwrap_or(options.request_size);
+
+    while Instant::now() < deadline {
+        let mut stream = match open_stream(&harness, echo_addr, options.plain).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                debug!("bench connect failed: {}", e);
+                result.errors += 1;
+                tokio::time::sleep(BENCH_RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        result.connections += 1;
+
+        for _ in 0..options.requests_per_connection {
+            if Instant::now() >= deadline {
+                break;
+            }
+            let started = Instant::now();
+            let round_trip = async {
+                for chunk in payload.chunks(chunk_size) {
+                    stream.write_all(chunk).await?;
+                    stream.flush().await?;
+                }
+                tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read_exact(&mut echoed)).await
+                    .map_err(|_| internal_err("echo response timed out"))??;
+                Ok::<_, ProxyError>(())
+            }.await;
+
+            match round_trip {
+                Ok(()) => result.latencies_us.push(started.elapsed().as_micros() as u64),
+                Err(e) => {
+                    debug!("bench request failed: {}", e);
+                    result.errors += 1;
+                    break;
+                }
+            }
+        }
+    }
+    result
+}
+
+/// 하네스 프록시로 CONNECT 터널 열기 (평문이면 TLS 없이 터널 그대로 사용)
+async fn open_stream(harness: &ProxyHarness, echo_addr: SocketAddr, plain: bool) -> Result<Box<dyn BenchStream>> {
+    if !plain {
+        return Ok(Box::new(harness.connect(echo_addr).await?));
+    }
+    let (status_line, stream) = harness.connect_pipelined(echo_addr, &[]).await?;
+    if !status_line.starts_with("HTTP/1.1 200") {
+        return Err(internal_err(format!("unexpected CONNECT response: {}", status_line)));
+    }
+    // 조각마다 따로 전송되도록 Nagle 비활성화
+    stream.set_nodelay(true)?;
+    Ok(Box::new(stream))
+}
+
+/// 정렬된 지연 시간에서 백분위수 값 (nearest-rank)
+fn percentile(sorted_us: &[u64], percent: f64) -> Option<u64> {
+    if sorted_us.is_empty() {
+        return None;
+    }
+    let rank = ((percent / 100.0) * sorted_us.len() as f64).ceil() as usize;
+    Some(sorted_us[rank.clamp(1, sorted_us.len()) - 1])
+}
+
+/// 지연 시간 표시 (마이크로초 단위 입력)
+fn format_latency(value_us: Option<u64>) -> String {
+    match value_us {
+        Some(us) if us >= 1000 => format!("{:.2}ms", us as f64 / 1000.0),
+        Some(us) => format!("{}us", us),
+        None => "-".to_string(),
+    }
+}
+
+/// 0으로 나누지 않는 비율
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
@@ -0,0 +1,1429 @@
+// 프록시 종단 간 점검용 하네스
+// 임시 포트에 ProxyServer를 띄우고 (메모리 설정, 로그 버림, DB 비활성화)
+// 실제 클라이언트로 CONNECT 터널을 통해 로컬 에코 서버까지 왕복을 확인합니다.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info};
+use rcgen::generate_simple_self_signed;
+use rustls::ServerConfig;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_rustls::{TlsAcceptor, TlsConnector, client::TlsStream as ClientTlsStream};
+
+use crate::admin::reload::Reloader;
+use crate::admin::ca::{start_ca_download_server, CA_CERT_CONTENT_TYPE, CA_FINGERPRINT_HEADER};
+use crate::admin::start_admin_server;
+use crate::admin::tls::certificate_fingerprint;
+use crate::acl::domain_blocker::DomainBlocker;
+use crate::buffer::BufferPool;
+use crate::config::{AdminKeepAliveConfig, AdminTlsConfig, Config, ConnectAclAction, ConnectAclConfig, EventStreamConfig, InspectionLimitConfig, InspectionOverflowAction, InterceptionPolicy, ListenerConfig, TunnelDetectTimeoutAction, MIN_EVENT_STREAM_BUFFER_SIZE, MIN_INSPECTION_RESPONSE_BYTES, SourceFilterConfig, SourceFilterMode};
+use crate::db::config::DbConfig;
+use crate::error::{ProxyError, Result, internal_err, tls_err};
+use crate::logging::Logger;
+use crate::logging::events::{publish, set_event_stream, subscribe, subscriber_count};
+use crate::logging::webhook::{WebhookEvent, WebhookEventKind};
+use crate::metrics::Metrics;
+use crate::proxy::dialer::connect_upstream;
+use crate::proxy::dns_prewarm::{prewarm_dns, prewarmed_addresses};
+use crate::proxy::maintenance::set_maintenance;
+use crate::proxy::pool::init_upstream_pool;
+use crate::server::{run_session_isolated, ProxyServer, ShutdownKind};
+use crate::server::fd_reserve::set_fd_reserve;
+use crate::server::source_filter::set_source_filter;
+use crate::session::close::ActiveConnections;
+use crate::tls::{create_unverified_client_config, init_root_ca, root_ca_certificate};
+
+#[cfg(feature = "bench")]
+pub mod bench;
+
+/// 하네스 I/O 타임아웃
+const HARNESS_IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// HTTP/1.0 점검 서버가 응답하는 본문
+const HTTP10_BODY: &[u8] = b"udss-proxy http/1.0 self-test body";
+
+/// 임시 포트에서 실행 중인 프록시 서버
+pub struct ProxyHarness {
+    addr: SocketAddr,
+    /// 추가 리스너 이름별 주소
+    named_addrs: Vec<(String, SocketAddr)>,
+    server: Arc<ProxyServer>,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: JoinHandle<Result<ShutdownKind>>,
+}
+
+impl ProxyHarness {
+    /// 하네스 기본 설정 (루프백 임시 포트, 내부 IP 인증서 검증 생략)
+    pub fn default_config() -> Config {
+        let mut config = Config::new();
+        config.bind_host = "127.0.0.1".to_string();
+        config.bind_port = 0;
+        config.disable_verify_internal_ip = true;
+        config
+    }
+
+    /// 주어진 설정으로 프록시 서버 시작 (bind_port는 0으로 두면 임시 포트 사용)
+    pub async fn start(config: Config) -> Result<Self> {
+        Self::start_with_buffer_pool(config, None).await
+    }
+
+    /// 버퍼 풀을 지정해 프록시 서버 시작 (None이면 세션마다 버퍼를 새로 할당)
+    pub async fn start_with_buffer_pool(config: Config, buffer_pool: Option<Arc<BufferPool>>) -> Result<Self> {
+        // DB 없이 실행하고 MITM용 루트 CA 준비
+        DbConfig::disable();
+        std::fs::create_dir_all(&config.ssl_dir)?;
+        init_root_ca(&config.root_ca)?;
+
+        let config = Arc::new(config);
+        let domain_blocker = Arc::new(DomainBlocker::new(Arc::clone(&config)));
+        domain_blocker.initialize().await
+            .map_err(|e| internal_err(format!("domain blocker init failed: {}", e)))?;
+
+        let server = ProxyServer::new(
+            config,
+            Metrics::new(),
+            buffer_pool,
+            Arc::new(Logger::noop()),
+            domain_blocker,
+        );
+
+        let listeners = server.bind().await?;
+        let addr = listeners[0].local_addr()?;
+        let mut named_addrs = Vec::new();
+        for listener in &listeners {
+            if let Some(name) = listener.name() {
+                named_addrs.push((name.to_string(), listener.local_addr()?));
+            }
+        }
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+        let server = Arc::new(server);
+        let serving = Arc::clone(&server);
+        let handle = tokio::spawn(async move {
+            serving.serve(listeners, async {
+                let _ = shutdown_rx.await;
+            }).await
+        });
+
+        info!("harness proxy listening on {}", addr);
+        Ok(Self {
+            addr,
+            named_addrs,
+            server,
+            shutdown: Some(shutdown_tx),
+            handle,
+        })
+    }
+
+    /// 프록시가 바인딩된 주소
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 이름으로 지정한 추가 리스너의 주소
+    pub fn listener_addr(&self, name: &str) -> Option<SocketAddr> {
+        self.named_addrs.iter().find(|(listener, _)| listener == name).map(|(_, addr)| *addr)
+    }
+
+    /// 리스너별 누적 수락 연결 수
+    pub fn listener_accepts(&self) -> Vec<u64> {
+        self.server.listener_accepts()
+    }
+
+    /// 연결 수락을 중단하고 서버 태스크 종료 대기
+    pub async fn shutdown(mut self) -> Result<()> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        self.handle.await
+            .map_err(|e| internal_err(format!("harness server task failed: {}", e)))?
+            .map(|_| ())
+    }
+
+    /// 프록시에 CONNECT 요청을 보내고 터널이 열리면 (MITM 인증서는 검증하지 않고) TLS 연결
+    pub async fn connect(&self, target: SocketAddr) -> Result<ClientTlsStream<TcpStream>> {
+        let (status_line, stream) = self.connect_pipelined(target, &[]).await?;
+        if !status_line.starts_with("HTTP/1.1 200") {
+            return Err(internal_err(format!("unexpected CONNECT response: {}", status_line)));
+        }
+
+        let connector = TlsConnector::from(Arc::new(create_unverified_client_config(None)?));
+        let server_name = ServerName::from(target.ip());
+        let tls_stream = connector.connect(server_name, stream).await?;
+        Ok(tls_stream)
+    }
+
+    /// CONNECT 요청 헤더와 `pipelined`를 한 번에 보내고 응답 상태 줄과 연결 반환
+    pub async fn connect_pipelined(&self, target: SocketAddr, pipelined: &[u8]) -> Result<(String, TcpStream)> {
+        self.connect_authority(&target.to_string(), pipelined).await
+    }
+
+    /// `host:port`로 CONNECT 요청 헤더와 `pipelined`를 한 번에 보내고 응답 상태 줄과 연결 반환
+    pub async fn connect_authority(&self, authority: &str, pipelined: &[u8]) -> Result<(String, TcpStream)> {
+        self.connect_authority_at(self.addr, authority, pipelined).await
+    }
+
+    /// 지정한 리스너 주소로 CONNECT 요청을 보내고 응답 상태 줄과 연결 반환
+    pub async fn connect_authority_at(&self, proxy: SocketAddr, authority: &str, pipelined: &[u8]) -> Result<(String, TcpStream)> {
+        let mut stream = TcpStream::connect(proxy).await?;
+        let mut request = format!("CONNECT {} HTTP/1.1\r\nHost: {}\r\n\r\n", authority, authority).into_bytes();
+        request.extend_from_slice(pipelined);
+        stream.write_all(&request).await?;
+
+        // CONNECT 응답 헤더 끝까지 읽기
+        let mut response = Vec::new();
+        let mut byte = [0u8; 1];
+        while !response.ends_with(b"\r\n\r\n") {
+            let n = tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read(&mut byte)).await
+                .map_err(|_| internal_err("CONNECT response timed out"))??;
+            if n == 0 {
+                return Err(internal_err("proxy closed connection during CONNECT"));
+            }
+            response.push(byte[0]);
+        }
+
+        let status_line = String::from_utf8_lossy(&response).lines().next().unwrap_or_default().to_string();
+        Ok((status_line, stream))
+    }
+}
+
+/// 로컬 서버용 자체 서명 인증서 TLS acceptor
+fn self_signed_acceptor() -> Result<TlsAcceptor> {
+    let cert = generate_simple_self_signed(vec!["localhost".to_string(), "127.0.0.1".to_string()])
+        .map_err(tls_err)?;
+    let cert_der = CertificateDer::from(cert.serialize_der().map_err(tls_err)?);
+    let key_der = PrivateKeyDer::Pkcs8(cert.serialize_private_key_der().into());
+
+    let server_config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert_der], key_der)?;
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+/// 로컬 TLS 에코 서버 시작 (받은 데이터를 그대로 돌려줌)
+pub async fn spawn_tls_echo_server() -> Result<(SocketAddr, JoinHandle<()>)> {
+    let acceptor = self_signed_acceptor()?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    let handle = tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let mut tls_stream = match acceptor.accept(stream).await {
+                    Ok(tls_stream) => tls_stream,
+                    Err(e) => {
+                        error!("echo server handshake failed: {}", e);
+                        return;
+                    }
+                };
+                let mut buf = [0u8; 4096];
+                loop {
+                    match tls_stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => {
+                            if tls_stream.write_all(&buf[..n]).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    Ok((addr, handle))
+}
+
+/// 로컬 평문 TCP 에코 서버 시작 (받은 데이터를 그대로 돌려줌)
+pub async fn spawn_tcp_echo_server() -> Result<(SocketAddr, JoinHandle<()>)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    let handle = tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let (mut reader, mut writer) = stream.split();
+                let _ = tokio::io::copy(&mut reader, &mut writer).await;
+            });
+        }
+    });
+
+    Ok((addr, handle))
+}
+
+/// 로컬 HTTP/1.0 서버 시작 (`tls`이면 TLS로 받음)
+///
+/// HTTP/1.0 요청에 `Connection: keep-alive`가 없으면 길이 정보 없이 응답하고 연결을 닫아 본문 끝을 알리며,
+/// 있으면 Content-Length로 응답하고 다음 요청을 기다립니다. HEAD 요청에는 본문 없이
+/// HTTP/1.1 `Content-Length`만 응답하고 연결을 유지합니다.
+pub async fn spawn_http10_server(tls: bool) -> Result<(SocketAddr, JoinHandle<()>)> {
+    let acceptor = if tls { Some(self_signed_acceptor()?) } else { None };
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+
+    let handle = tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                match acceptor {
+                    Some(acceptor) => match acceptor.accept(stream).await {
+                        Ok(tls_stream) => serve_http10(tls_stream).await,
+                        Err(e) => error!("http/1.0 server handshake failed: {}", e),
+                    },
+                    None => serve_http10(stream).await,
+                }
+            });
+        }
+    });
+
+    Ok((addr, handle))
+}
+
+/// HTTP/1.0 점검 서버의 연결 하나 처리
+async fn serve_http10<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S) {
+    loop {
+        let Ok(head) = read_head(&mut stream).await else {
+            return;
+        };
+        let head = String::from_utf8_lossy(&head).to_ascii_lowercase();
+        let request_line = head.lines().next().unwrap_or_default().to_string();
+        let keep_alive = head.contains("connection: keep-alive");
+
+        let response = if request_line.starts_with("head ") {
+            b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\n".to_vec()
+        } else if request_line.ends_with("http/1.0") && !keep_alive {
+            let mut response = b"HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\n".to_vec();
+            response.extend_from_slice(HTTP10_BODY);
+            let _ = stream.write_all(&response).await;
+            let _ = stream.shutdown().await;
+            return;
+        } else {
+            let mut response = format!("HTTP/1.0 200 OK\r\nConnection: keep-alive\r\nContent-Length: {}\r\n\r\n", HTTP10_BODY.len()).into_bytes();
+            response.extend_from_slice(HTTP10_BODY);
+            response
+        };
+        if stream.write_all(&response).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// 메시지 헤더 끝(빈 줄)까지 읽기
+async fn read_head<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Vec<u8>> {
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        let n = tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read(&mut byte)).await
+            .map_err(|_| internal_err("message head timed out"))??;
+        if n == 0 {
+            return Err(internal_err("connection closed before end of message head"));
+        }
+        head.push(byte[0]);
+    }
+    Ok(head)
+}
+
+/// 연결이 닫힐 때까지 읽기 (HARNESS_IO_TIMEOUT 안에 닫히지 않으면 실패)
+async fn read_until_close<S: AsyncRead + Unpin>(stream: &mut S, what: &str) -> Result<Vec<u8>> {
+    let mut received = Vec::new();
+    tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read_to_end(&mut received)).await
+        .map_err(|_| internal_err(format!("{}: connection was not closed after the response", what)))??;
+    Ok(received)
+}
+
+/// 하네스 프록시를 통해 TLS 에코 서버까지 왕복 확인
+async fn echo_round_trip(harness: &ProxyHarness, echo_addr: SocketAddr) -> Result<()> {
+    let mut stream = harness.connect(echo_addr).await?;
+    let payload = b"udss-proxy self-test";
+    stream.write_all(payload).await?;
+
+    let mut echoed = vec![0u8; payload.len()];
+    tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read_exact(&mut echoed)).await
+        .map_err(|_| internal_err("echo response timed out"))??;
+
+    if echoed != payload {
+        return Err(internal_err("echoed payload mismatch"));
+    }
+    Ok(())
+}
+
+/// 패닉을 일으키는 세션 핸들러 스텁 (세션처럼 활성 연결 게이지를 올린 채 패닉)
+async fn panicking_session() -> Result<()> {
+    let active = ActiveConnections::new(Metrics::new());
+    active.opened(true);
+    tokio::task::yield_now().await;
+    panic!("injected session panic (self-test)");
+}
+
+/// 세션 패닉 격리 확인: 패닉한 세션은 기록만 되고 활성 연결 게이지가 원래대로 돌아오며,
+/// 프록시는 이후에도 연결을 수락해야 함
+async fn panic_isolation(harness: &ProxyHarness, echo_addr: SocketAddr) -> Result<()> {
+    let metrics = Metrics::new();
+    let panics_before = metrics.session_panics();
+    let active_before = metrics.get("tls_active_connections");
+
+    // 서버 워커와 같은 방식으로 세션 태스크를 띄워 패닉이 태스크 밖으로 전파되지 않는지 확인
+    let session = tokio::spawn(async {
+        run_session_isolated("self-test-panic", &Metrics::new(), panicking_session()).await;
+    });
+    session.await
+        .map_err(|e| internal_err(format!("session panic escaped isolation: {}", e)))?;
+
+    if metrics.session_panics() != panics_before + 1 {
+        return Err(internal_err("session panic was not counted"));
+    }
+    let active_after = metrics.get("tls_active_connections");
+    if active_after != active_before {
+        return Err(internal_err(format!("active connection gauge leaked by a panicked session: {} -> {}", active_before, active_after)));
+    }
+    echo_round_trip(harness, echo_addr).await
+}
+
+/// CONNECT 뒤에 이어 붙은 데이터 처리 확인 (기본 정책 reject_requests)
+///
+/// 판단할 수 없는 데이터는 터널의 첫 데이터로 전달되어 에코되어야 하고,
+/// HTTP 요청을 이어 붙이면(요청 밀반입) 터널을 열지 않고 400으로 거부해야 합니다.
+async fn connect_pipelining(harness: &ProxyHarness, tcp_echo_addr: SocketAddr) -> Result<()> {
+    let metrics = Metrics::new();
+    let (forwarded_before, rejected_before, ambiguous_before) = metrics.connect_pipeline_stats();
+
+    let payload = b"udss-proxy pipelined payload";
+    let (status_line, mut stream) = harness.connect_pipelined(tcp_echo_addr, payload).await?;
+    if !status_line.starts_with("HTTP/1.1 200") {
+        return Err(internal_err(format!("pipelined payload was not tunneled: {}", status_line)));
+    }
+    let mut echoed = vec![0u8; payload.len()];
+    tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read_exact(&mut echoed)).await
+        .map_err(|_| internal_err("pipelined payload echo timed out"))??;
+    if echoed != payload {
+        return Err(internal_err("pipelined payload was not forwarded as the start of the tunnel"));
+    }
+    drop(stream);
+
+    let smuggled = b"GET /internal HTTP/1.1\r\nHost: smuggled.example\r\n\r\n";
+    let (status_line, _) = harness.connect_pipelined(tcp_echo_addr, smuggled).await?;
+    if !status_line.starts_with("HTTP/1.1 400") {
+        return Err(internal_err(format!("smuggled request after CONNECT was not rejected: {}", status_line)));
+    }
+
+    let (forwarded, rejected, ambiguous) = metrics.connect_pipeline_stats();
+    if forwarded != forwarded_before + 1 || rejected != rejected_before + 1 || ambiguous != ambiguous_before + 1 {
+        return Err(internal_err("CONNECT pipelining outcomes were not counted"));
+    }
+    Ok(())
+}
+
+/// CONNECT와 같은 쓰기로 보낸 ClientHello도 가로채기 확인: 프록시의 가짜 인증서(업스트림의 자체 서명 인증서가 아님)로
+/// 핸드셰이크가 끝나고, 가로챈 연결로 보낸 데이터가 에코되어야 함
+async fn pipelined_client_hello_intercepted(harness: &ProxyHarness, echo_addr: SocketAddr) -> Result<()> {
+    let proxy = harness.addr();
+    tokio::task::spawn_blocking(move || {
+        use std::io::{Read, Write};
+
+        let mut stream = std::net::TcpStream::connect(proxy)?;
+        stream.set_read_timeout(Some(HARNESS_IO_TIMEOUT))?;
+        let config = Arc::new(create_unverified_client_config(None)?);
+        let mut tls = rustls::ClientConnection::new(config, ServerName::from(echo_addr.ip()))?;
+
+        // CONNECT 요청 헤더 뒤에 ClientHello를 붙여 한 번에 보냄
+        let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n\r\n", echo_addr).into_bytes();
+        tls.write_tls(&mut request)?;
+        stream.write_all(&request)?;
+
+        let mut response = Vec::new();
+        let mut byte = [0u8; 1];
+        while !response.ends_with(b"\r\n\r\n") {
+            if stream.read(&mut byte)? == 0 {
+                return Err(internal_err("proxy closed connection during pipelined CONNECT"));
+            }
+            response.push(byte[0]);
+        }
+        if !response.starts_with(b"HTTP/1.1 200") {
+            return Err(internal_err(format!("pipelined ClientHello was not tunneled: {}", String::from_utf8_lossy(&response))));
+        }
+
+        while tls.is_handshaking() {
+            tls.complete_io(&mut stream)?;
+        }
+        let leaf = tls.peer_certificates().and_then(|certs| certs.first())
+            .ok_or_else(|| internal_err("no certificate after pipelined ClientHello"))?;
+        let (_, leaf) = x509_parser::parse_x509_certificate(leaf).map_err(internal_err)?;
+        if leaf.issuer() == leaf.subject() {
+            return Err(internal_err("pipelined ClientHello reached the upstream without interception"));
+        }
+
+        let payload = b"pipelined hello";
+        tls.writer().write_all(payload)?;
+        tls.complete_io(&mut stream)?;
+        let mut echoed = Vec::new();
+        while echoed.len() < payload.len() {
+            tls.complete_io(&mut stream)?;
+            let mut chunk = [0u8; 64];
+            match tls.reader().read(&mut chunk) {
+                Ok(n) => echoed.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        if echoed != payload {
+            return Err(internal_err("intercepted pipelined session did not echo"));
+        }
+        Ok(())
+    }).await.map_err(internal_err)?
+}
+
+/// 터널 프로토콜 판별 시간 초과 확인: 기본(wait)은 tunnel_detect_timeout_ms 뒤에 보낸 ClientHello도 가로채고,
+/// deny는 데이터를 보내지 않은 클라이언트 연결을 닫아야 함 (어느 쪽도 검사 없는 평문 터널로 넘기지 않음)
+async fn tunnel_detect_timeout(echo_addr: SocketAddr) -> Result<()> {
+    for action in [TunnelDetectTimeoutAction::Wait, TunnelDetectTimeoutAction::Deny] {
+        let mut config = ProxyHarness::default_config();
+        config.tunnel_detect_timeout_ms = 100;
+        config.tunnel_detect_timeout_action = action;
+        let harness = ProxyHarness::start(config).await?;
+
+        let result = async {
+            let (status_line, mut stream) = harness.connect_pipelined(echo_addr, b"").await?;
+            if !status_line.starts_with("HTTP/1.1 200") {
+                return Err(internal_err(format!("unexpected CONNECT response: {}", status_line)));
+            }
+            tokio::time::sleep(Duration::from_millis(300)).await;
+
+            if action == TunnelDetectTimeoutAction::Deny {
+                let mut byte = [0u8; 1];
+                let n = tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read(&mut byte)).await
+                    .map_err(|_| internal_err("tunnel_detect_timeout_action deny kept the tunnel open"))??;
+                return if n == 0 { Ok(()) } else { Err(internal_err("tunnel_detect_timeout_action deny relayed data")) };
+            }
+
+            let connector = TlsConnector::from(Arc::new(create_unverified_client_config(None)?));
+            let tls_stream = connector.connect(ServerName::from(echo_addr.ip()), stream).await?;
+            let leaf = tls_stream.get_ref().1.peer_certificates().and_then(|certs| certs.first().cloned())
+                .ok_or_else(|| internal_err("no certificate after late ClientHello"))?;
+            let (_, leaf) = x509_parser::parse_x509_certificate(&leaf).map_err(internal_err)?;
+            if leaf.issuer() == leaf.subject() {
+                return Err(internal_err("late ClientHello was tunneled without interception"));
+            }
+            Ok(())
+        }.await;
+        harness.shutdown().await?;
+        result?;
+    }
+    Ok(())
+}
+
+/// 가로챈 연결에서 HTTP/1.0 처리 확인
+///
+/// `Connection: keep-alive`가 없는 요청은 길이 정보 없는 응답을 연결 종료까지 받은 뒤 연결이 닫혀야 하고,
+/// keep-alive 요청은 Content-Length 응답 뒤에도 같은 연결로 다음 요청을 보낼 수 있어야 합니다.
+async fn http10_intercepted(harness: &ProxyHarness, http10_tls_addr: SocketAddr) -> Result<()> {
+    let mut stream = harness.connect(http10_tls_addr).await?;
+    stream.write_all(b"GET /close HTTP/1.0\r\nHost: 127.0.0.1\r\n\r\n").await?;
+    let response = read_until_close(&mut stream, "intercepted HTTP/1.0 read-until-close").await?;
+    if !response.starts_with(b"HTTP/1.0 200") || !response.ends_with(HTTP10_BODY) {
+        return Err(internal_err(format!("intercepted HTTP/1.0 response truncated: {:?}", String::from_utf8_lossy(&response))));
+    }
+
+    let mut stream = harness.connect(http10_tls_addr).await?;
+    for path in ["/first", "/second"] {
+        stream.write_all(format!("GET {} HTTP/1.0\r\nHost: 127.0.0.1\r\nConnection: keep-alive\r\n\r\n", path).as_bytes()).await?;
+        let head = read_head(&mut stream).await?;
+        if !head.starts_with(b"HTTP/1.0 200") {
+            return Err(internal_err(format!("intercepted HTTP/1.0 keep-alive request {} failed", path)));
+        }
+        let mut body = vec![0u8; HTTP10_BODY.len()];
+        tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read_exact(&mut body)).await
+            .map_err(|_| internal_err("intercepted HTTP/1.0 keep-alive body timed out"))??;
+    }
+    Ok(())
+}
+
+/// 평문 HTTP/1.0 요청 처리 확인: 본문 없는 HEAD 응답은 서버가 연결을 유지해도 바로 끝나야 함
+async fn http10_plain(harness: &ProxyHarness, http10_addr: SocketAddr) -> Result<()> {
+    let mut stream = TcpStream::connect(harness.addr()).await?;
+    stream.write_all(format!("HEAD http://{}/ HTTP/1.0\r\nHost: {}\r\n\r\n", http10_addr, http10_addr).as_bytes()).await?;
+    let response = read_until_close(&mut stream, "plain HEAD through proxy").await?;
+    if !response.starts_with(b"HTTP/1.1 200") || !response.ends_with(b"\r\n\r\n") {
+        return Err(internal_err(format!("unexpected HEAD response: {:?}", String::from_utf8_lossy(&response))));
+    }
+    Ok(())
+}
+
+/// 점검 모드 확인: 새 CONNECT와 HTTP 요청은 503으로 거절하고, 진행 중인 터널은 유지하다가
+/// `close_existing`으로 전환하면 닫고, 끄면 다시 터널을 열 수 있어야 함
+async fn maintenance_mode(harness: &ProxyHarness, tcp_echo_addr: SocketAddr) -> Result<()> {
+    let (status_line, mut tunnel) = harness.connect_pipelined(tcp_echo_addr, b"").await?;
+    if !status_line.starts_with("HTTP/1.1 200") {
+        return Err(internal_err(format!("tunnel before maintenance failed: {}", status_line)));
+    }
+
+    set_maintenance(true, Some(false), Some("self-test"), "self-test");
+    let result = async {
+        let (status_line, _) = harness.connect_pipelined(tcp_echo_addr, b"").await?;
+        if !status_line.starts_with("HTTP/1.1 503") {
+            return Err(internal_err(format!("CONNECT during maintenance was not refused: {}", status_line)));
+        }
+
+        let mut stream = TcpStream::connect(harness.addr()).await?;
+        stream.write_all(format!("GET http://{}/ HTTP/1.1\r\nHost: {}\r\nAccept: text/html\r\n\r\n", tcp_echo_addr, tcp_echo_addr).as_bytes()).await?;
+        let response = read_until_close(&mut stream, "maintenance response").await?;
+        let response = String::from_utf8_lossy(&response);
+        if !response.starts_with("HTTP/1.1 503") || !response.contains("X-Proxy-Error: maintenance") {
+            return Err(internal_err(format!("HTTP request during maintenance was not refused: {:?}", response.lines().next())));
+        }
+
+        // 진행 중인 터널은 점검 모드에서도 유지
+        let payload = b"udss-proxy maintenance payload";
+        tunnel.write_all(payload).await?;
+        let mut echoed = vec![0u8; payload.len()];
+        tokio::time::timeout(HARNESS_IO_TIMEOUT, tunnel.read_exact(&mut echoed)).await
+            .map_err(|_| internal_err("existing tunnel stalled during maintenance"))??;
+
+        set_maintenance(true, Some(true), None, "self-test");
+        read_until_close(&mut tunnel, "existing tunnel after close_existing").await?;
+        Ok(())
+    }.await;
+    set_maintenance(false, None, None, "self-test");
+    result?;
+
+    let (status_line, _) = harness.connect_pipelined(tcp_echo_addr, b"").await?;
+    if !status_line.starts_with("HTTP/1.1 200") {
+        return Err(internal_err(format!("tunnel after maintenance failed: {}", status_line)));
+    }
+    Ok(())
+}
+
+/// fd 예비분 확인: 남은 fd보다 큰 예비분이면 수락 직후 응답 없이 닫히고 집계되는지,
+/// 예비분을 끄면 다시 수락하는지 확인 (끝나면 예비분 해제)
+async fn fd_reserve_refusal(harness: &ProxyHarness, tcp_echo_addr: SocketAddr) -> Result<()> {
+    let metrics = Metrics::new();
+    let refused = || metrics.get("fd_reserve_refused");
+    let before = refused();
+
+    // 어떤 fd 한도보다도 큰 예비분
+    set_fd_reserve(u64::MAX / 2);
+    let result = async {
+        let mut stream = TcpStream::connect(harness.addr()).await?;
+        let mut received = [0u8; 64];
+        match tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read(&mut received)).await {
+            Ok(Ok(0)) | Ok(Err(_)) => Ok(()),
+            Ok(Ok(n)) => Err(internal_err(format!("connection below the fd reserve received data: {:?}", String::from_utf8_lossy(&received[..n])))),
+            Err(_) => Err(internal_err("connection below the fd reserve was not closed")),
+        }
+    }.await;
+    set_fd_reserve(0);
+    result?;
+    if refused() - before != 1 {
+        return Err(internal_err(format!("fd reserve counted {} refusals", refused() - before)));
+    }
+
+    let (status_line, _) = harness.connect_pipelined(tcp_echo_addr, b"").await?;
+    if !status_line.starts_with("HTTP/1.1 200") {
+        return Err(internal_err(format!("tunnel after disabling the fd reserve failed: {}", status_line)));
+    }
+    Ok(())
+}
+
+/// 출발지 IP 필터 확인: deny/allow 대역에 따라 수락 직후 응답 없이 닫히고 집계되는지,
+/// 재로드로 필터가 교체되는지 확인 (끝나면 필터 해제)
+async fn source_filter_modes(harness: &ProxyHarness, tcp_echo_addr: SocketAddr) -> Result<()> {
+    let result = check_source_filter_modes(harness, tcp_echo_addr).await;
+    set_source_filter(&SourceFilterConfig::default());
+    result
+}
+
+async fn check_source_filter_modes(harness: &ProxyHarness, tcp_echo_addr: SocketAddr) -> Result<()> {
+    let metrics = Metrics::new();
+    let filtered = || metrics.get("source_filtered");
+    let filter = |mode: SourceFilterMode, cidrs: &[&str]| SourceFilterConfig { mode, cidrs: cidrs.iter().map(|cidr| cidr.to_string()).collect() };
+    let closed_without_response = || async {
+        let mut stream = TcpStream::connect(harness.addr()).await?;
+        let mut received = [0u8; 64];
+        // 바로 닫히므로 빈 읽기나 RST만 허용
+        match tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read(&mut received)).await {
+            Ok(Ok(0)) | Ok(Err(_)) => Ok(()),
+            Ok(Ok(n)) => Err(internal_err(format!("filtered connection received data: {:?}", String::from_utf8_lossy(&received[..n])))),
+            Err(_) => Err(internal_err("filtered connection was not closed")),
+        }
+    };
+
+    let cases = [
+        (filter(SourceFilterMode::Deny, &["10.0.0.0/8", "2001:db8::/32"]), true),
+        (filter(SourceFilterMode::Allow, &["127.0.0.1"]), true),
+        (filter(SourceFilterMode::Allow, &["192.0.2.0/24"]), false),
+    ];
+    for (config, allowed) in cases {
+        set_source_filter(&config);
+        let before = filtered();
+        if allowed {
+            let (status_line, _) = harness.connect_pipelined(tcp_echo_addr, b"").await?;
+            if !status_line.starts_with("HTTP/1.1 200") {
+                return Err(internal_err(format!("source filter {:?} should accept loopback: {}", config, status_line)));
+            }
+        } else {
+            closed_without_response().await?;
+        }
+        let counted = filtered() - before;
+        if counted != u64::from(!allowed) {
+            return Err(internal_err(format!("source filter {:?} counted {} rejections", config, counted)));
+        }
+    }
+
+    // 재로드로 루프백 차단 목록 적용
+    let deny_loopback = || {
+        let mut config = Config::new();
+        config.source_filter = SourceFilterConfig { mode: SourceFilterMode::Deny, cidrs: vec!["127.0.0.0/8".to_string()] };
+        Ok(config)
+    };
+    let config = ProxyHarness::default_config();
+    let domain_blocker = Arc::new(DomainBlocker::new(Arc::new(config.clone())));
+    let reloader = Reloader::new(&config, deny_loopback, domain_blocker);
+    let summary = reloader.reload().await.map_err(|e| internal_err(format!("reload failed: {}", e)))?;
+    if !summary.applied.iter().any(|key| key == "source_filter") {
+        return Err(internal_err(format!("source_filter was not reloaded: {:?}", summary.applied)));
+    }
+    let before = filtered();
+    closed_without_response().await?;
+    if filtered() != before + 1 {
+        return Err(internal_err("reloaded source filter rejection was not counted"));
+    }
+    Ok(())
+}
+
+/// 리스너별 정책 확인: 가로채지 않는 리스너로 연 터널은 업스트림 인증서가 그대로 보이고 기본 리스너는 가로채는지,
+/// 리스너가 참조한 CONNECT 접근 제어 정책은 그 리스너에만 적용되는지 확인
+async fn listener_policies(echo_addr: SocketAddr) -> Result<()> {
+    let mut config = ProxyHarness::default_config();
+    let listener = |name: &str, interception: InterceptionPolicy, connect_acl: Option<&str>| ListenerConfig {
+        name: name.to_string(),
+        bind_host: None,
+        bind_port: 0,
+        interception,
+        bypass_hosts: Vec::new(),
+        connect_acl: connect_acl.map(str::to_string),
+    };
+    config.listeners = vec![
+        listener("tunnel", InterceptionPolicy::Never, None),
+        listener("restricted", InterceptionPolicy::Always, Some("deny_all")),
+    ];
+    config.connect_acl_policies.insert("deny_all".to_string(), ConnectAclConfig { default: ConnectAclAction::Deny, rules: Vec::new() });
+    config.validate().map_err(internal_err)?;
+    let harness = ProxyHarness::start(config).await?;
+
+    let result = async {
+        let connector = TlsConnector::from(Arc::new(create_unverified_client_config(None)?));
+        let peer_certificate = |stream: &ClientTlsStream<TcpStream>| {
+            stream.get_ref().1.peer_certificates().and_then(|certs| certs.first()).map(|cert| cert.to_vec())
+        };
+        let direct = connector.connect(ServerName::from(echo_addr.ip()), TcpStream::connect(echo_addr).await?).await?;
+        let origin = peer_certificate(&direct);
+
+        let intercepted = harness.connect(echo_addr).await?;
+        if peer_certificate(&intercepted) == origin {
+            return Err(internal_err("default listener should intercept TLS"));
+        }
+
+        let tunnel_addr = harness.listener_addr("tunnel").ok_or_else(|| internal_err("tunnel listener not bound"))?;
+        let (status_line, stream) = harness.connect_authority_at(tunnel_addr, &echo_addr.to_string(), &[]).await?;
+        if !status_line.starts_with("HTTP/1.1 200") {
+            return Err(internal_err(format!("tunnel listener CONNECT failed: {}", status_line)));
+        }
+        let tunneled = connector.connect(ServerName::from(echo_addr.ip()), stream).await?;
+        if peer_certificate(&tunneled) != origin {
+            return Err(internal_err("listener with interception never should not intercept TLS"));
+        }
+
+        let restricted_addr = harness.listener_addr("restricted").ok_or_else(|| internal_err("restricted listener not bound"))?;
+        let (status_line, _) = harness.connect_authority_at(restricted_addr, &echo_addr.to_string(), &[]).await?;
+        if !status_line.starts_with("HTTP/1.1 403") {
+            return Err(internal_err(format!("listener connect_acl policy should deny CONNECT: {}", status_line)));
+        }
+        Ok(())
+    }.await;
+
+    harness.shutdown().await?;
+    result
+}
+
+/// 세션 크기 분포 확인: 불투명 터널로 10KiB를 주고받고 닫으면 업로드/다운로드 분포의 `le_16k` 구간이 하나씩 늘어야 함
+async fn session_size_histogram(harness: &ProxyHarness, tcp_echo_addr: SocketAddr) -> Result<()> {
+    let metrics = Metrics::new();
+    let le_16k = |metrics: &Metrics| -> Vec<u64> {
+        metrics.session_bytes_histograms().iter()
+            .map(|(_, histogram)| histogram.exported_fields()[2].1)
+            .collect()
+    };
+    let before = le_16k(&metrics);
+
+    let (status_line, mut tunnel) = harness.connect_pipelined(tcp_echo_addr, b"").await?;
+    if !status_line.starts_with("HTTP/1.1 200") {
+        return Err(internal_err(format!("tunnel for size histogram failed: {}", status_line)));
+    }
+    let payload = vec![b'x'; 10 * 1024];
+    tunnel.write_all(&payload).await?;
+    let mut echoed = vec![0u8; payload.len()];
+    tokio::time::timeout(HARNESS_IO_TIMEOUT, tunnel.read_exact(&mut echoed)).await
+        .map_err(|_| internal_err("size histogram tunnel stalled"))??;
+    drop(tunnel);
+
+    let deadline = tokio::time::Instant::now() + HARNESS_IO_TIMEOUT;
+    loop {
+        let after = le_16k(&metrics);
+        if after.iter().zip(&before).all(|(after, before)| after > before) {
+            return Ok(());
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(internal_err(format!("session size histogram not updated: le_16k {:?} -> {:?}", before, after)));
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// SO_REUSEPORT 분산 확인: 리스너 여러 개로 띄운 프록시에 연결을 반복해서 열면
+/// 모든 연결이 수락되고 리스너마다 고른 몫의 절반 이상을 받아야 함 (Linux 외에는 생략)
+async fn reuseport_distribution() -> Result<()> {
+    const LISTENERS: usize = 4;
+    const CONNECTIONS: u64 = 400;
+    if !cfg!(target_os = "linux") {
+        return Ok(());
+    }
+
+    let mut config = ProxyHarness::default_config();
+    config.reuseport_enabled = true;
+    config.reuseport_listeners = LISTENERS;
+    let harness = ProxyHarness::start(config).await?;
+    let result = async {
+        // 연결마다 출발 포트가 달라 커널이 4-튜플 해시로 리스너를 고름
+        for _ in 0..CONNECTIONS {
+            drop(TcpStream::connect(harness.addr()).await?);
+        }
+        let deadline = tokio::time::Instant::now() + HARNESS_IO_TIMEOUT;
+        let accepts = loop {
+            let accepts = harness.listener_accepts();
+            if accepts.iter().sum::<u64>() >= CONNECTIONS {
+                break accepts;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(internal_err(format!("not all connections accepted: {:?}", accepts)));
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        };
+        if accepts.len() != LISTENERS {
+            return Err(internal_err(format!("expected {} listeners, got {:?}", LISTENERS, accepts)));
+        }
+        let min_share = CONNECTIONS / LISTENERS as u64 / 2;
+        if accepts.iter().any(|&accepted| accepted < min_share) {
+            return Err(internal_err(format!("uneven accept distribution across listeners (min {}): {:?}", min_share, accepts)));
+        }
+        info!("SO_REUSEPORT accept distribution: {:?}", accepts);
+        Ok(())
+    }.await;
+    harness.shutdown().await?;
+    result
+}
+
+/// static_hosts 확인: 지정한 이름은 DNS 대신 지정한 주소로 연결하고 (대소문자 무시),
+/// 앞 주소로 연결할 수 없으면 다음 주소로 넘어감
+async fn static_hosts_override(tcp_echo_addr: SocketAddr) -> Result<()> {
+    // .invalid는 실제 DNS에서 해석되지 않으므로 연결되면 static_hosts가 쓰인 것
+    const NAME: &str = "udss-self-test.invalid";
+    let mut config = ProxyHarness::default_config();
+    config.static_hosts.insert(NAME.to_string(), vec!["::1".parse().unwrap(), tcp_echo_addr.ip()]);
+    let harness = ProxyHarness::start(config).await?;
+    let result = async {
+        for authority in [format!("{}:{}", NAME, tcp_echo_addr.port()), format!("UDSS-Self-Test.Invalid.:{}", tcp_echo_addr.port())] {
+            let payload = b"udss-proxy static host";
+            let (status_line, mut stream) = harness.connect_authority(&authority, payload).await?;
+            if !status_line.starts_with("HTTP/1.1 200") {
+                return Err(internal_err(format!("static_hosts name {} was not connected: {}", authority, status_line)));
+            }
+            let mut echoed = vec![0u8; payload.len()];
+            tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read_exact(&mut echoed)).await
+                .map_err(|_| internal_err("static_hosts tunnel echo timed out"))??;
+            if echoed != payload {
+                return Err(internal_err("static_hosts tunnel did not reach the configured address"));
+            }
+        }
+        Ok(())
+    }.await;
+    harness.shutdown().await?;
+    result
+}
+
+/// DNS 미리 해석 확인: 해석되는 이름만 저장하고(대소문자, 끝의 점 무시) 해석되지 않는 이름은 건너뛰는지 확인
+async fn dns_prewarm() -> Result<()> {
+    let mut config = ProxyHarness::default_config();
+    config.dns_prewarm.hosts = vec!["LocalHost.".to_string(), "udss-self-test-prewarm.invalid".to_string()];
+    let resolved = prewarm_dns(&Arc::new(config)).await;
+    if resolved != 1 {
+        return Err(internal_err(format!("dns_prewarm should resolve only localhost, resolved {}", resolved)));
+    }
+    match prewarmed_addresses("localhost") {
+        Some(ips) if ips.iter().all(|ip| ip.is_loopback()) => {},
+        other => return Err(internal_err(format!("localhost was not prewarmed to loopback: {:?}", other))),
+    }
+    if let Some(ips) = prewarmed_addresses("udss-self-test-prewarm.invalid") {
+        return Err(internal_err(format!("unresolvable name should not be prewarmed: {:?}", ips)));
+    }
+    Ok(())
+}
+
+/// DNS 결과별 메트릭스 확인: 미리 해석한 이름은 캐시 적중으로, 해석되지 않는 이름은 실패 유형 하나로 집계하는지 확인
+/// (dns_prewarm 이후 실행, 오류 코드별 분류는 dialer 단위 테스트에서 확인)
+async fn dns_lookup_outcomes() -> Result<()> {
+    let metrics = Metrics::new();
+    let counts = || ["dns_lookup_cache_hit", "dns_lookup_nxdomain", "dns_lookup_servfail", "dns_lookup_success"].map(|name| metrics.get(name));
+    let config = ProxyHarness::default_config();
+    let before = counts();
+    // 연결 성공 여부와 관계없이 이름 해석 결과만 확인 (포트 1은 닫혀 있음)
+    let _ = connect_upstream("localhost", 1, &config, None, &mut Default::default()).await;
+    let _ = connect_upstream("udss-self-test-outcome.invalid", 1, &config, None, &mut Default::default()).await;
+    let after = counts();
+    let delta: Vec<u64> = after.iter().zip(&before).map(|(after, before)| after - before).collect();
+    // .invalid는 리졸버에 따라 NXDOMAIN 또는 (DNS 서버에 닿지 못하면) SERVFAIL
+    if delta[0] != 1 || delta[1] + delta[2] != 1 || delta[3] != 0 {
+        return Err(internal_err(format!("DNS outcome counters not updated as expected (cache_hit, nxdomain, servfail, success): {:?}", delta)));
+    }
+    Ok(())
+}
+
+/// 관리 엔드포인트 응답 하나 읽기 (헤더와 Content-Length만큼의 본문)
+async fn read_admin_response<S: AsyncRead + Unpin>(stream: &mut S) -> Result<String> {
+    let head = String::from_utf8_lossy(&read_head(stream).await?).to_string();
+    let content_length = head.lines()
+        .find_map(|line| line.strip_prefix("Content-Length:"))
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .ok_or_else(|| internal_err(format!("admin response without Content-Length: {:?}", head)))?;
+    let mut body = vec![0u8; content_length];
+    tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read_exact(&mut body)).await
+        .map_err(|_| internal_err("admin response body timed out"))??;
+    Ok(head)
+}
+
+/// 관리 엔드포인트 연결 유지 확인: 한 연결에서 max_requests까지 응답하고 마지막 응답에서 닫으며,
+/// 유휴 시간이 지나거나 HTTP/1.0 요청(keep-alive 없음)이면 연결을 닫음
+async fn admin_keep_alive() -> Result<()> {
+    let admin_addr = TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+    let mut config = ProxyHarness::default_config();
+    config.admin_bind = Some(admin_addr.to_string());
+    config.admin_keep_alive = AdminKeepAliveConfig { idle_timeout_seconds: 1, max_requests: 3 };
+    let domain_blocker = Arc::new(DomainBlocker::new(Arc::new(config.clone())));
+    let reloader = Arc::new(Reloader::new(&config, || Ok(Config::new()), domain_blocker));
+    start_admin_server(&config, reloader).await?;
+
+    let request = b"GET /version HTTP/1.1\r\nHost: admin\r\n\r\n";
+    let mut stream = TcpStream::connect(admin_addr).await?;
+    for remaining in [2, 1] {
+        stream.write_all(request).await?;
+        let head = read_admin_response(&mut stream).await?;
+        if !head.contains("Connection: keep-alive") || !head.contains(&format!("Keep-Alive: timeout=1, max={}", remaining)) {
+            return Err(internal_err(format!("admin response should keep the connection open: {:?}", head)));
+        }
+    }
+    stream.write_all(request).await?;
+    let head = read_admin_response(&mut stream).await?;
+    if !head.contains("Connection: close") || !read_until_close(&mut stream, "admin max_requests").await?.is_empty() {
+        return Err(internal_err(format!("admin connection should close after max_requests: {:?}", head)));
+    }
+
+    // 유휴 시간 안에 다음 요청이 없으면 닫힘
+    let mut stream = TcpStream::connect(admin_addr).await?;
+    stream.write_all(request).await?;
+    read_admin_response(&mut stream).await?;
+    let started = tokio::time::Instant::now();
+    read_until_close(&mut stream, "admin idle timeout").await?;
+    if started.elapsed() < Duration::from_millis(900) {
+        return Err(internal_err(format!("admin connection closed before the idle timeout: {:?}", started.elapsed())));
+    }
+
+    let mut stream = TcpStream::connect(admin_addr).await?;
+    stream.write_all(b"GET /version HTTP/1.0\r\n\r\n").await?;
+    let head = read_admin_response(&mut stream).await?;
+    if !head.contains("Connection: close") {
+        return Err(internal_err(format!("HTTP/1.0 admin request should close the connection: {:?}", head)));
+    }
+    read_until_close(&mut stream, "admin HTTP/1.0").await?;
+    Ok(())
+}
+
+/// 루트 CA 인증서 배포 확인: 관리 엔드포인트와 인증 없는 public_bind 리스너가 현재 CA 인증서만 PEM으로 응답하고
+/// 지문 헤더가 인증서와 일치하는지, public_bind 리스너는 다른 관리 경로에 응답하지 않는지 확인
+async fn ca_certificate_download() -> Result<()> {
+    let admin_addr = TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+    let public_addr = TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+    let mut config = ProxyHarness::default_config();
+    config.admin_bind = Some(admin_addr.to_string());
+    config.admin_allowed_sources = vec!["192.0.2.1".to_string()];
+    config.ca_download.public_bind = Some(public_addr.to_string());
+    let domain_blocker = Arc::new(DomainBlocker::new(Arc::new(config.clone())));
+    let reloader = Arc::new(Reloader::new(&config, || Ok(Config::new()), domain_blocker));
+    start_admin_server(&config, reloader).await?;
+    start_ca_download_server(&config).await?;
+
+    let expected = root_ca_certificate().ok_or_else(|| internal_err("root CA not initialized"))?;
+    let fingerprint = format!("{}: {}", CA_FINGERPRINT_HEADER, certificate_fingerprint(&expected));
+    let fetch = |addr: SocketAddr, path: &'static str| async move {
+        let mut stream = TcpStream::connect(addr).await?;
+        stream.write_all(format!("GET {} HTTP/1.1\r\nHost: proxy\r\nConnection: close\r\n\r\n", path).as_bytes()).await?;
+        let response = read_until_close(&mut stream, "CA download").await?;
+        Ok::<_, ProxyError>(String::from_utf8_lossy(&response).to_string())
+    };
+
+    // 허용 대역 밖(루프백)이므로 관리 엔드포인트는 응답 없이 닫고, public_bind는 인증 없이 응답
+    if !fetch(admin_addr, "/ca.crt").await?.is_empty() {
+        return Err(internal_err("admin endpoint should still enforce admin_allowed_sources for /ca.crt"));
+    }
+    let response = fetch(public_addr, "/ca.crt").await?;
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    let served = rustls_pemfile::certs(&mut body.as_bytes()).collect::<std::result::Result<Vec<_>, _>>()?;
+    if !head.starts_with("HTTP/1.1 200") || !head.contains(&format!("Content-Type: {}", CA_CERT_CONTENT_TYPE))
+        || !head.contains(&fingerprint) || served != [expected] || body.contains("PRIVATE KEY") {
+        return Err(internal_err(format!("unexpected CA certificate response: {:?}", response)));
+    }
+    if !fetch(public_addr, "/version").await?.starts_with("HTTP/1.1 404") {
+        return Err(internal_err("public CA download listener should only serve /ca.crt"));
+    }
+
+    // 허용 대역 안에서는 관리 엔드포인트도 같은 인증서를 응답하고, 비활성화하면 404
+    for enabled in [true, false] {
+        let admin_addr = TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+        let mut config = ProxyHarness::default_config();
+        config.admin_bind = Some(admin_addr.to_string());
+        config.ca_download.enabled = enabled;
+        let domain_blocker = Arc::new(DomainBlocker::new(Arc::new(config.clone())));
+        let reloader = Arc::new(Reloader::new(&config, || Ok(Config::new()), domain_blocker));
+        start_admin_server(&config, reloader).await?;
+        let response = fetch(admin_addr, "/ca.crt").await?;
+        let served = enabled && response.starts_with("HTTP/1.1 200") && response.contains(&fingerprint);
+        if served != enabled || (!enabled && !response.starts_with("HTTP/1.1 404")) {
+            return Err(internal_err(format!("admin /ca.crt with enabled={}: {:?}", enabled, response)));
+        }
+    }
+    Ok(())
+}
+
+/// 실시간 이벤트 스트림 확인: GET /events 시청자가 세션 시작/종료 이벤트를 받는지, 시청자 수 한도를 넘으면 503인지,
+/// 느린 시청자의 큐가 가득 차면 버린 수를 dropped 이벤트로 알리는지, 연결을 닫으면 구독이 해제되는지 확인
+async fn event_stream(harness: &ProxyHarness, tcp_echo_addr: SocketAddr) -> Result<()> {
+    let admin_addr = TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+    let mut config = ProxyHarness::default_config();
+    config.admin_bind = Some(admin_addr.to_string());
+    config.event_stream = EventStreamConfig { max_clients: 2, buffer_size: MIN_EVENT_STREAM_BUFFER_SIZE };
+    let domain_blocker = Arc::new(DomainBlocker::new(Arc::new(config.clone())));
+    let reloader = Arc::new(Reloader::new(&config, || Ok(Config::new()), domain_blocker));
+    start_admin_server(&config, reloader).await?;
+    let result = check_event_stream(harness, tcp_echo_addr, admin_addr).await;
+    set_event_stream(&EventStreamConfig::default());
+    result
+}
+
+async fn check_event_stream(harness: &ProxyHarness, tcp_echo_addr: SocketAddr, admin_addr: SocketAddr) -> Result<()> {
+    let open_viewer = || async {
+        let mut stream = TcpStream::connect(admin_addr).await?;
+        stream.write_all(b"GET /events HTTP/1.1\r\nHost: admin\r\n\r\n").await?;
+        let head = String::from_utf8_lossy(&read_head(&mut stream).await?).to_string();
+        Ok::<_, ProxyError>((head, stream))
+    };
+    let wait_for_subscribers = |expected: usize| async move {
+        let started = tokio::time::Instant::now();
+        while subscriber_count() != expected {
+            if started.elapsed() > HARNESS_IO_TIMEOUT {
+                return Err(internal_err(format!("event stream subscribers: {}, expected {}", subscriber_count(), expected)));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        Ok(())
+    };
+
+    let (head, mut viewer) = open_viewer().await?;
+    if !head.starts_with("HTTP/1.1 200") || !head.contains("Content-Type: text/event-stream") {
+        return Err(internal_err(format!("unexpected event stream response: {:?}", head)));
+    }
+    let (_, second) = open_viewer().await?;
+    wait_for_subscribers(2).await?;
+    let (head, _) = open_viewer().await?;
+    if !head.starts_with("HTTP/1.1 503") {
+        return Err(internal_err(format!("viewer over max_clients should be refused: {:?}", head)));
+    }
+
+    // 터널 세션 하나의 시작/종료 이벤트가 같은 세션 ID로 도착
+    let (status_line, tunnel) = harness.connect_pipelined(tcp_echo_addr, b"").await?;
+    if !status_line.starts_with("HTTP/1.1 200") {
+        return Err(internal_err(format!("tunnel for event stream failed: {}", status_line)));
+    }
+    drop(tunnel);
+    let mut received = String::new();
+    let mut chunk = [0u8; 4096];
+    while !received.contains("event: session_end") {
+        let n = tokio::time::timeout(HARNESS_IO_TIMEOUT, viewer.read(&mut chunk)).await
+            .map_err(|_| internal_err(format!("session events not streamed: {:?}", received)))??;
+        if n == 0 {
+            return Err(internal_err("event stream closed unexpectedly"));
+        }
+        received.push_str(&String::from_utf8_lossy(&chunk[..n]));
+    }
+    let session_ids: Vec<String> = received.lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .filter_map(|data| serde_json::from_str::<serde_json::Value>(data).ok())
+        .filter(|event| matches!(event["event"].as_str(), Some("session_start" | "session_end")))
+        .filter_map(|event| event["session_id"].as_str().map(str::to_string))
+        .collect();
+    if session_ids.len() != 2 || session_ids[0] != session_ids[1] {
+        return Err(internal_err(format!("session start/end events mismatch: {:?}", received)));
+    }
+
+    // 시청자가 연결을 닫으면 구독 해제
+    drop(viewer);
+    drop(second);
+    wait_for_subscribers(0).await?;
+
+    // 읽지 않는 구독자: 큐를 넘친 이벤트는 버리고 다음 이벤트 앞에 버린 수를 알림
+    let metrics = Metrics::new();
+    let dropped_metric = || metrics.get("event_stream_dropped");
+    let dropped_before = dropped_metric();
+    let mut subscription = subscribe().ok_or_else(|| internal_err("event stream subscription refused"))?;
+    let event = |n: usize| WebhookEvent::new(WebhookEventKind::BlockedRequest, format!("self-test-{}", n), "127.0.0.1", "blocked.example", "GET /");
+    for n in 0..MIN_EVENT_STREAM_BUFFER_SIZE + 5 {
+        publish(&event(n));
+    }
+    for _ in 0..MIN_EVENT_STREAM_BUFFER_SIZE {
+        subscription.recv().await.ok_or_else(|| internal_err("event stream subscription closed"))?;
+    }
+    publish(&event(MIN_EVENT_STREAM_BUFFER_SIZE + 5));
+    let marker = subscription.recv().await.unwrap_or_default();
+    let next = subscription.recv().await.unwrap_or_default();
+    if !marker.starts_with("event: dropped\ndata: {\"dropped\":5}") || !next.contains("self-test-21") {
+        return Err(internal_err(format!("dropped marker not delivered in order: {:?} then {:?}", marker, next)));
+    }
+    if dropped_metric() - dropped_before != 5 {
+        return Err(internal_err(format!("event_stream_dropped counted {}", dropped_metric() - dropped_before)));
+    }
+    drop(subscription);
+    wait_for_subscribers(0).await
+}
+
+/// 업스트림 연결 풀 확인: CONNECT 터널은 풀을 쓰지 않고, 평문 HTTP keep-alive 응답 뒤의 연결만 풀에 반환되며,
+/// 101 응답으로 프로토콜을 전환한 연결은 재사용 대상에서 빠지고 닫히며, 가로챈 HTTPS 세션의 업스트림 TLS 연결은
+/// 클라이언트가 요청 사이에서 닫으면 다음 세션이 재사용하는지 확인 (이후 점검을 위해 풀은 켜 둠)
+async fn upstream_pool_reuse(harness: &ProxyHarness, tcp_echo_addr: SocketAddr) -> Result<()> {
+    let mut config = ProxyHarness::default_config();
+    config.upstream_pool_enabled = true;
+    init_upstream_pool(&config);
+
+    // /keep은 길이가 정해진 응답, /upgrade는 101 응답 후 상대가 닫을 때까지 대기하고 닫히면 알림
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let server_addr = listener.local_addr()?;
+    let (upgrade_closed_tx, mut upgrade_closed) = tokio::sync::mpsc::unbounded_channel();
+    let server = tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let upgrade_closed = upgrade_closed_tx.clone();
+            tokio::spawn(async move {
+                while let Ok(head) = read_head(&mut stream).await {
+                    if !String::from_utf8_lossy(&head).contains("/upgrade") {
+                        let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok").await;
+                        continue;
+                    }
+                    let _ = stream.write_all(b"HTTP/1.1 101 Switching Protocols\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nContent-Length: 0\r\n\r\n").await;
+                    let mut rest = Vec::new();
+                    let _ = stream.read_to_end(&mut rest).await;
+                    let _ = upgrade_closed.send(());
+                    return;
+                }
+            });
+        }
+    });
+
+    // 길이가 정해진 응답으로 연결을 유지하는 TLS 서버 (맺은 연결 수를 셈)
+    let acceptor = self_signed_acceptor()?;
+    let tls_listener = TcpListener::bind("127.0.0.1:0").await?;
+    let tls_addr = tls_listener.local_addr()?;
+    let tls_accepted = Arc::new(AtomicUsize::new(0));
+    let accepted = Arc::clone(&tls_accepted);
+    let tls_server = tokio::spawn(async move {
+        while let Ok((stream, _)) = tls_listener.accept().await {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let Ok(mut stream) = acceptor.accept(stream).await else { return };
+                while let Ok(head) = read_head(&mut stream).await {
+                    if stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok").await.is_err()
+                        || String::from_utf8_lossy(&head).to_ascii_lowercase().contains("connection: close") {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    let result = async {
+        let metrics = Metrics::new();
+        let idle = || metrics.upstream_pool_stats().2;
+        let wait_for_idle = |expected: u64| async move {
+            let started = tokio::time::Instant::now();
+            while idle() != expected {
+                if started.elapsed() > HARNESS_IO_TIMEOUT {
+                    return Err(internal_err(format!("upstream pool idle connections: {}, expected {}", idle(), expected)));
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            Ok(())
+        };
+        let request = |path: &'static str| async move {
+            let mut stream = TcpStream::connect(harness.addr()).await?;
+            stream.write_all(format!("GET http://{}{} HTTP/1.1\r\nHost: {}\r\n\r\n", server_addr, path, server_addr).as_bytes()).await?;
+            let head = String::from_utf8_lossy(&read_head(&mut stream).await?).to_string();
+            Ok::<_, ProxyError>(head)
+        };
+        let idle_before = idle();
+
+        // 터널은 끝나도 풀에 들어가지 않음
+        let (status_line, mut tunnel) = harness.connect_pipelined(tcp_echo_addr, b"").await?;
+        if !status_line.starts_with("HTTP/1.1 200") {
+            return Err(internal_err(format!("tunnel for upstream pool check failed: {}", status_line)));
+        }
+        tunnel.write_all(b"tunnel bytes").await?;
+        let mut echoed = [0u8; 12];
+        tokio::time::timeout(HARNESS_IO_TIMEOUT, tunnel.read_exact(&mut echoed)).await
+            .map_err(|_| internal_err("tunnel echo timed out"))??;
+        drop(tunnel);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        wait_for_idle(idle_before).await?;
+
+        let head = request("/keep").await?;
+        if !head.starts_with("HTTP/1.1 200") {
+            return Err(internal_err(format!("pooled HTTP request failed: {:?}", head)));
+        }
+        wait_for_idle(idle_before + 1).await?;
+
+        // 풀에서 꺼낸 연결이 101로 전환되면 반환되지 않고 닫힘
+        let (hits_before, _, _) = metrics.upstream_pool_stats();
+        let head = request("/upgrade").await?;
+        if !head.starts_with("HTTP/1.1 101") || metrics.upstream_pool_stats().0 != hits_before + 1 {
+            return Err(internal_err(format!("upgrade request did not reuse the pooled connection: {:?}", head)));
+        }
+        tokio::time::timeout(HARNESS_IO_TIMEOUT, upgrade_closed.recv()).await
+            .map_err(|_| internal_err("switched-protocol upstream connection was kept open"))?;
+        wait_for_idle(idle_before).await?;
+
+        // 가로챈 세션도 클라이언트가 요청 사이에서 닫으면 업스트림 TLS 연결을 풀에 반환하고 다음 세션이 재사용
+        let (hits_before, _, _) = metrics.upstream_pool_stats();
+        for (path, connection) in [("/first", "keep-alive"), ("/second", "close")] {
+            let mut stream = harness.connect(tls_addr).await?;
+            stream.write_all(format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: {}\r\n\r\n", path, connection).as_bytes()).await?;
+            let head = read_head(&mut stream).await?;
+            let mut body = [0u8; 2];
+            tokio::time::timeout(HARNESS_IO_TIMEOUT, stream.read_exact(&mut body)).await
+                .map_err(|_| internal_err("intercepted pooled response body timed out"))??;
+            if !head.starts_with(b"HTTP/1.1 200") {
+                return Err(internal_err(format!("intercepted pooled request {} failed: {:?}", path, String::from_utf8_lossy(&head))));
+            }
+            stream.shutdown().await?;
+            drop(stream);
+            if path == "/first" {
+                wait_for_idle(idle_before + 1).await?;
+            }
+        }
+        wait_for_idle(idle_before).await?;
+        let accepted = tls_accepted.load(Ordering::SeqCst);
+        if accepted != 1 || metrics.upstream_pool_stats().0 != hits_before + 1 {
+            return Err(internal_err(format!("intercepted session did not reuse the pooled TLS connection ({} upstream connections)", accepted)));
+        }
+        Ok(())
+    }.await;
+    server.abort();
+    tls_server.abort();
+    result
+}
+
+/// 검사용 버퍼링 전체 상한 확인: 본문 중간에 max_inspection_bytes를 넘은 응답이 inspection_limit.on_exceed(abort)와
+/// 관계없이 패스스루로 끝까지 전달되고 집계되는지 확인
+async fn inspection_cap_passthrough() -> Result<()> {
+    const BODY_LEN: usize = 64 * 1024;
+
+    // 본문 앞 절반을 보낸 뒤 잠시 쉬었다가 나머지를 보내는 서버
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let server_addr = listener.local_addr()?;
+    let server = tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                if read_head(&mut stream).await.is_err() {
+                    return;
+                }
+                let body = vec![b'x'; BODY_LEN];
+                let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", BODY_LEN);
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(&body[..BODY_LEN / 2]).await;
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                let _ = stream.write_all(&body[BODY_LEN / 2..]).await;
+            });
+        }
+    });
+
+    let mut config = ProxyHarness::default_config();
+    config.max_inspection_bytes = MIN_INSPECTION_RESPONSE_BYTES;
+    config.inspection_limit = InspectionLimitConfig {
+        max_response_bytes: Some(BODY_LEN as u64 * 4),
+        on_exceed: InspectionOverflowAction::Abort,
+    };
+    let harness = ProxyHarness::start(config).await?;
+
+    let result = async {
+        let metrics = Metrics::new();
+        let (cap_before, limit_before) = (metrics.get("inspection_cap_exceeded"), metrics.get("inspection_limit_exceeded"));
+
+        let mut stream = TcpStream::connect(harness.addr()).await?;
+        stream.write_all(format!("GET http://{}/large HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", server_addr, server_addr).as_bytes()).await?;
+        let head = String::from_utf8_lossy(&read_head(&mut stream).await?).to_string();
+        if !head.starts_with("HTTP/1.1 200") {
+            return Err(internal_err(format!("inspection cap request failed: {:?}", head)));
+        }
+        let body = read_until_close(&mut stream, "inspection cap passthrough").await?;
+        if body.len() != BODY_LEN || body.iter().any(|byte| *byte != b'x') {
+            return Err(internal_err(format!("response over the inspection cap was not relayed intact: {} of {} bytes", body.len(), BODY_LEN)));
+        }
+        if metrics.get("inspection_cap_exceeded") != cap_before + 1 || metrics.get("inspection_limit_exceeded") != limit_before {
+            return Err(internal_err("inspection cap passthrough was not counted separately from inspection_limit"));
+        }
+        Ok(())
+    }.await;
+    harness.shutdown().await?;
+    server.abort();
+    result
+}
+
+/// 관리 엔드포인트 상호 TLS 확인: 지문이 고정된 클라이언트 인증서로만 응답을 받고,
+/// 다른 인증서나 인증서 없는 연결은 핸드셰이크에서 거부되는지 확인
+async fn admin_mutual_tls() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("udss-self-test-admin-tls-{}", std::process::id()));
+    let result = check_admin_mutual_tls(&dir).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+async fn check_admin_mutual_tls(dir: &std::path::Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let server = generate_simple_self_signed(vec!["localhost".to_string()]).map_err(tls_err)?;
+    let server_der = CertificateDer::from(server.serialize_der().map_err(tls_err)?);
+    let (cert_file, key_file) = (dir.join("admin.pem"), dir.join("admin.key"));
+    std::fs::write(&cert_file, server.serialize_pem().map_err(tls_err)?)?;
+    std::fs::write(&key_file, server.serialize_private_key_pem())?;
+    let client_identity = |name: &str| -> Result<(CertificateDer<'static>, PrivateKeyDer<'static>)> {
+        let cert = generate_simple_self_signed(vec![name.to_string()]).map_err(tls_err)?;
+        Ok((CertificateDer::from(cert.serialize_der().map_err(tls_err)?), PrivateKeyDer::Pkcs8(cert.serialize_private_key_der().into())))
+    };
+    let pinned = client_identity("pinned.admin-client")?;
+    let other = client_identity("other.admin-client")?;
+
+    let admin_addr = TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+    let mut config = ProxyHarness::default_config();
+    config.admin_bind = Some(admin_addr.to_string());
+    config.admin_tls = AdminTlsConfig {
+        enabled: true,
+        cert_file: cert_file.to_string_lossy().to_string(),
+        key_file: key_file.to_string_lossy().to_string(),
+        // 소문자, 구분자 없는 형식도 허용
+        client_cert_fingerprints: vec![certificate_fingerprint(&pinned.0).replace(':', "").to_lowercase()],
+    };
+    config.validate().map_err(internal_err)?;
+    let domain_blocker = Arc::new(DomainBlocker::new(Arc::new(config.clone())));
+    let reloader = Arc::new(Reloader::new(&config, || Ok(Config::new()), domain_blocker));
+    start_admin_server(&config, reloader).await?;
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(server_der).map_err(tls_err)?;
+    let builder = rustls::ClientConfig::builder().with_root_certificates(roots);
+    let request = |client_config: rustls::ClientConfig| async move {
+        let stream = TcpStream::connect(admin_addr).await?;
+        let server_name = ServerName::try_from("localhost").map_err(tls_err)?;
+        let mut stream = TlsConnector::from(Arc::new(client_config)).connect(server_name, stream).await?;
+        stream.write_all(b"GET /version HTTP/1.0\r\n\r\n").await?;
+        read_admin_response(&mut stream).await
+    };
+
+    let head = request(builder.clone().with_client_auth_cert(vec![pinned.0], pinned.1).map_err(tls_err)?).await?;
+    if !head.starts_with("HTTP/1.1 200") {
+        return Err(internal_err(format!("pinned admin client should be served: {:?}", head)));
+    }
+    // TLS 1.3에서는 클라이언트 핸드셰이크가 먼저 끝나므로 거부는 첫 읽기에서 드러남
+    if let Ok(head) = request(builder.clone().with_client_auth_cert(vec![other.0], other.1).map_err(tls_err)?).await {
+        return Err(internal_err(format!("unpinned admin client certificate should be rejected: {:?}", head)));
+    }
+    if let Ok(head) = request(builder.with_no_client_auth()).await {
+        return Err(internal_err(format!("admin client without a certificate should be rejected: {:?}", head)));
+    }
+    Ok(())
+}
+
+/// 점검 하나의 결과 기록 (통과하면 이름을 남기고, 실패하면 어느 점검인지 남김)
+fn check_result(name: &str, result: Result<()>) -> Result<()> {
+    match &result {
+        Ok(()) => info!("self-test: {} 통과", name),
+        Err(_) => error!("self-test: {} 실패", name),
+    }
+    result
+}
+
+/// 점검 함수를 실행하고 함수 이름으로 결과 기록
+macro_rules! check {
+    ($check:ident($($arg:expr),*).await) => {
+        check_result(stringify!($check), $check($($arg),*).await)
+    };
+    ($check:ident($($arg:expr),*)) => {
+        check_result(stringify!($check), $check($($arg),*))
+    };
+}
+
+/// 자체 점검: 하네스 프록시와 로컬 에코 서버로 기능별 점검을 차례로 실행 (점검 내용은 각 점검 함수 참고)
+pub async fn self_test() -> Result<()> {
+    let (echo_addr, echo_handle) = spawn_tls_echo_server().await?;
+    let (tcp_echo_addr, tcp_echo_handle) = spawn_tcp_echo_server().await?;
+    let (http10_tls_addr, http10_tls_handle) = spawn_http10_server(true).await?;
+    let (http10_addr, http10_handle) = spawn_http10_server(false).await?;
+    let harness = ProxyHarness::start(ProxyHarness::default_config()).await?;
+    let proxy_addr = harness.addr();
+
+    let result = async {
+        check!(echo_round_trip(&harness, echo_addr).await)?;
+        check!(panic_isolation(&harness, echo_addr).await)?;
+        check!(connect_pipelining(&harness, tcp_echo_addr).await)?;
+        check!(pipelined_client_hello_intercepted(&harness, echo_addr).await)?;
+        check!(tunnel_detect_timeout(echo_addr).await)?;
+        check!(http10_intercepted(&harness, http10_tls_addr).await)?;
+        check!(http10_plain(&harness, http10_addr).await)?;
+        check!(maintenance_mode(&harness, tcp_echo_addr).await)?;
+        check!(source_filter_modes(&harness, tcp_echo_addr).await)?;
+        check!(fd_reserve_refusal(&harness, tcp_echo_addr).await)?;
+        check!(session_size_histogram(&harness, tcp_echo_addr).await)?;
+        check!(listener_policies(echo_addr).await)?;
+        check!(admin_keep_alive().await)?;
+        check!(admin_mutual_tls().await)?;
+        check!(ca_certificate_download().await)?;
+        check!(event_stream(&harness, tcp_echo_addr).await)?;
+        check!(upstream_pool_reuse(&harness, tcp_echo_addr).await)?;
+        check!(inspection_cap_passthrough().await)?;
+        check!(static_hosts_override(tcp_echo_addr).await)?;
+        check!(dns_prewarm().await)?;
+        check!(dns_lookup_outcomes().await)?;
+        check!(reuseport_distribution().await)
+    }.await;
+
+    harness.shutdown().await?;
+    echo_handle.abort();
+    tcp_echo_handle.abort();
+    http10_tls_handle.abort();
+    http10_handle.abort();
+
+    match &result {
+        Ok(()) => info!("self-test passed: 프록시 {}, TLS 에코 서버 {}", proxy_addr, echo_addr),
+        Err(e) => error!("self-test failed: {}", e),
+    }
+    result
+}
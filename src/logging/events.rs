@@ -0,0 +1,156 @@
+// 실시간 이벤트 스트림 (관리 엔드포인트 GET /events)
+// 로거가 웹훅으로 보내는 이벤트(차단, 업스트림 실패)와 세션 시작/종료를 구독 중인 시청자에게 SSE 프레임으로 나눠 줍니다.
+// 시청자마다 크기가 정해진 큐를 두고, 큐가 가득 차면 이벤트를 버렸다가 다시 넣을 수 있을 때 버린 수를 알리는 dropped 프레임을 먼저 넣습니다.
+// 구독자가 없으면 원자 변수 하나만 확인하므로 세션 경로의 비용은 거의 없습니다.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use log::warn;
+use once_cell::sync::Lazy;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+use crate::config::EventStreamConfig;
+use crate::logging::webhook::WebhookEvent;
+use crate::metrics::Metrics;
+
+// 전역 이벤트 허브
+static EVENT_HUB: Lazy<EventHub> = Lazy::new(EventHub::new);
+
+/// SSE 프레임 하나 (`event:`/`data:` 줄과 빈 줄까지 포함)
+pub type EventFrame = Arc<str>;
+
+/// 구독자 목록과 설정
+struct EventHub {
+    subscribers: Mutex<Vec<Subscriber>>,
+    /// 구독자 수 (발행 시 잠금 없이 확인)
+    active: AtomicUsize,
+    next_id: AtomicU64,
+    max_clients: AtomicUsize,
+    buffer_size: AtomicUsize,
+}
+
+/// 시청자 하나의 큐
+struct Subscriber {
+    id: u64,
+    sender: mpsc::Sender<EventFrame>,
+    /// 큐가 가득 차 버렸지만 아직 알리지 못한 이벤트 수
+    dropped: u64,
+}
+
+/// 구독 하나 (해제 시 구독자 목록에서 제거)
+pub struct EventSubscription {
+    id: u64,
+    receiver: mpsc::Receiver<EventFrame>,
+}
+
+impl EventHub {
+    fn new() -> Self {
+        let defaults = EventStreamConfig::default();
+        Self {
+            subscribers: Mutex::new(Vec::new()),
+            active: AtomicUsize::new(0),
+            next_id: AtomicU64::new(1),
+            max_clients: AtomicUsize::new(defaults.max_clients),
+            buffer_size: AtomicUsize::new(defaults.buffer_size),
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| subscriber.id != id);
+        self.active.store(subscribers.len(), Ordering::Release);
+    }
+}
+
+impl EventSubscription {
+    /// 다음 프레임 (구독이 끊긴 경우 None)
+    pub async fn recv(&mut self) -> Option<EventFrame> {
+        self.receiver.recv().await
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        EVENT_HUB.remove(self.id);
+    }
+}
+
+/// 이벤트 스트림 설정 적용 (관리 엔드포인트 시작 시, 이미 연결된 시청자의 큐 크기는 바꾸지 않음)
+pub fn set_event_stream(config: &EventStreamConfig) {
+    EVENT_HUB.max_clients.store(config.max_clients, Ordering::Relaxed);
+    EVENT_HUB.buffer_size.store(config.buffer_size, Ordering::Relaxed);
+}
+
+/// 새 시청자 등록 (비활성화되었거나 동시 시청자 수 한도에 도달했으면 None)
+pub fn subscribe() -> Option<EventSubscription> {
+    let max_clients = EVENT_HUB.max_clients.load(Ordering::Relaxed);
+    let mut subscribers = EVENT_HUB.subscribers.lock().unwrap();
+    if subscribers.len() >= max_clients {
+        return None;
+    }
+    let id = EVENT_HUB.next_id.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = mpsc::channel(EVENT_HUB.buffer_size.load(Ordering::Relaxed));
+    subscribers.push(Subscriber { id, sender, dropped: 0 });
+    EVENT_HUB.active.store(subscribers.len(), Ordering::Release);
+    Some(EventSubscription { id, receiver })
+}
+
+/// 현재 시청자 수
+pub fn subscriber_count() -> usize {
+    EVENT_HUB.active.load(Ordering::Acquire)
+}
+
+/// 이벤트를 모든 시청자에게 발행 (구독자가 없으면 직렬화도 하지 않음)
+pub fn publish(event: &WebhookEvent) {
+    if subscriber_count() == 0 {
+        return;
+    }
+    let data = match serde_json::to_string(event) {
+        Ok(data) => data,
+        Err(e) => {
+            warn!("이벤트 스트림 직렬화 실패: {}", e);
+            return;
+        }
+    };
+    let frame: EventFrame = Arc::from(format!("event: {}\ndata: {}\n\n", event.event, data));
+
+    let mut dropped = 0;
+    let mut subscribers = EVENT_HUB.subscribers.lock().unwrap();
+    subscribers.retain_mut(|subscriber| {
+        // 앞서 버린 이벤트가 있으면 그 수를 먼저 알린 뒤에 새 이벤트를 넣음
+        if subscriber.dropped > 0 {
+            match subscriber.sender.try_send(dropped_frame(subscriber.dropped)) {
+                Ok(()) => subscriber.dropped = 0,
+                Err(TrySendError::Full(_)) => {
+                    subscriber.dropped += 1;
+                    dropped += 1;
+                    return true;
+                },
+                Err(TrySendError::Closed(_)) => return false,
+            }
+        }
+        match subscriber.sender.try_send(Arc::clone(&frame)) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                subscriber.dropped += 1;
+                dropped += 1;
+                true
+            },
+            Err(TrySendError::Closed(_)) => false,
+        }
+    });
+    EVENT_HUB.active.store(subscribers.len(), Ordering::Release);
+    drop(subscribers);
+
+    if dropped > 0 {
+        Metrics::new().event_stream_dropped(dropped);
+    }
+}
+
+/// 버린 이벤트 수를 알리는 프레임
+fn dropped_frame(count: u64) -> EventFrame {
+    Arc::from(format!("event: dropped\ndata: {}\n\n", json!({"dropped": count})))
+}
@@ -0,0 +1,177 @@
+// 접근 로그 파일 기록 (access_log.file.path 설정 시)
+// 세션 처리를 막지 않도록 전용 스레드가 줄을 받아 파일(필요하면 gzip/zstd 스트림 압축)에 씁니다.
+// 압축 스트림은 flush_interval_ms마다 블록을 마무리해 내보내므로 비정상 종료 시에도 그때까지의 로그는 풀 수 있습니다.
+// 대기열은 queue_capacity 줄로 제한하며, 디스크가 따라오지 못해 가득 차면 새 줄을 버리고 개수를 셉니다.
+
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender, TrySendError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use log::{error, info, warn};
+
+use crate::config::{AccessLogCompression, AccessLogFileConfig};
+
+/// 압축 방식별 파일 출력
+enum FileWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl FileWriter {
+    fn open(settings: &AccessLogFileConfig, path: &str) -> io::Result<Self> {
+        if let Some(dir) = Path::new(path).parent()
+            && !dir.as_os_str().is_empty() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // 이미 있는 파일에는 이어서 씀 (gzip 멤버, zstd 프레임을 이어 붙인 파일도 한 번에 풀림)
+        let file = BufWriter::new(OpenOptions::new().create(true).append(true).open(path)?);
+        let level = settings.effective_level();
+        Ok(match settings.compression {
+            AccessLogCompression::None => FileWriter::Plain(file),
+            AccessLogCompression::Gzip => FileWriter::Gzip(GzEncoder::new(file, Compression::new(level as u32))),
+            AccessLogCompression::Zstd => FileWriter::Zstd(zstd::Encoder::new(file, level)?),
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let writer: &mut dyn Write = match self {
+            FileWriter::Plain(writer) => writer,
+            FileWriter::Gzip(writer) => writer,
+            FileWriter::Zstd(writer) => writer,
+        };
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")
+    }
+
+    /// 지금까지 쓴 줄을 파일로 내보냄 (압축 스트림은 현재 블록을 마무리)
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            FileWriter::Plain(writer) => writer.flush(),
+            FileWriter::Gzip(writer) => writer.flush(),
+            FileWriter::Zstd(writer) => writer.flush(),
+        }
+    }
+
+    /// 압축 스트림을 끝맺고 파일로 내보냄
+    fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            FileWriter::Plain(writer) => writer,
+            FileWriter::Gzip(writer) => writer.finish()?,
+            FileWriter::Zstd(writer) => writer.finish()?,
+        };
+        file.flush()
+    }
+}
+
+/// 접근 로그 파일 기록기
+///
+/// 마지막 참조가 해제되면 남은 줄을 모두 쓰고 압축 스트림을 끝맺을 때까지 기다립니다.
+pub struct AccessLogFile {
+    sender: Option<SyncSender<String>>,
+    thread: Option<JoinHandle<()>>,
+    /// 대기열이 가득 차 버린 줄 수
+    dropped: AtomicU64,
+}
+
+impl AccessLogFile {
+    /// 설정에 따라 파일을 열고 기록 스레드 시작 (path가 없으면 None)
+    pub fn open(settings: &AccessLogFileConfig) -> Result<Option<Self>, Box<dyn Error + Send + Sync>> {
+        let Some(path) = settings.path.clone() else {
+            return Ok(None);
+        };
+        let writer = FileWriter::open(settings, &path)
+            .map_err(|e| format!("접근 로그 파일 열기 실패 ({}): {}", path, e))?;
+        let flush_interval = Duration::from_millis(settings.flush_interval_ms);
+        let (sender, receiver) = mpsc::sync_channel(settings.queue_capacity);
+        let thread = std::thread::Builder::new()
+            .name("access-log-file".to_string())
+            .spawn(move || run_writer(writer, receiver, flush_interval))?;
+
+        info!("접근 로그 파일 기록: {} (압축 {}, 수준 {}, 내보내기 주기 {}ms, 대기열 {}줄)",
+              path, settings.compression.name(), settings.effective_level(), settings.flush_interval_ms, settings.queue_capacity);
+        Ok(Some(Self { sender: Some(sender), thread: Some(thread), dropped: AtomicU64::new(0) }))
+    }
+
+    /// 한 줄 기록 요청 (대기열이 가득 차면 기다리지 않고 버림)
+    pub fn write_line(&self, line: String) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if let Err(TrySendError::Full(_)) = sender.try_send(line) {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped == 1 || dropped.is_multiple_of(1000) {
+                warn!("접근 로그 파일 대기열이 가득 차 줄을 버림 (누적 {}건)", dropped);
+            }
+        }
+    }
+
+    /// 대기열이 가득 차 버린 줄 수
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for AccessLogFile {
+    fn drop(&mut self) {
+        self.sender.take();
+        let dropped = self.dropped();
+        if dropped > 0 {
+            warn!("접근 로그 파일 대기열이 가득 차 버린 줄: 총 {}건", dropped);
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// 기록 스레드: 받은 줄을 쓰고 주기마다 내보내며, 채널이 닫히면 스트림을 끝맺음
+fn run_writer(mut writer: FileWriter, receiver: mpsc::Receiver<String>, flush_interval: Duration) {
+    let mut next_flush = Instant::now() + flush_interval;
+    let mut pending = false;
+    loop {
+        match receiver.recv_timeout(next_flush.saturating_duration_since(Instant::now())) {
+            Ok(line) => {
+                if let Err(e) = writer.write_line(&line) {
+                    error!("접근 로그 파일 기록 실패: {}", e);
+                }
+                pending = true;
+            },
+            Err(RecvTimeoutError::Timeout) => {},
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if Instant::now() >= next_flush {
+            if pending && let Err(e) = writer.flush() {
+                error!("접근 로그 파일 내보내기 실패: {}", e);
+            }
+            pending = false;
+            next_flush = Instant::now() + flush_interval;
+        }
+    }
+    if let Err(e) = writer.finish() {
+        error!("접근 로그 파일 마무리 실패: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_queue_drops_and_counts_lines() {
+        let (sender, receiver) = mpsc::sync_channel(2);
+        let file = AccessLogFile { sender: Some(sender), thread: None, dropped: AtomicU64::new(0) };
+        for i in 0..5 {
+            file.write_line(format!("line {}", i));
+        }
+        assert_eq!(file.dropped(), 3);
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["line 0", "line 1"]);
+    }
+}
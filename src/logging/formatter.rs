@@ -1,6 +1,12 @@
 use chrono::{DateTime, Utc, Datelike};
+use serde_json::{json, Value};
 use std::fmt::Write;
 
+use crate::config::{AccessLogConfig, AccessLogField, AccessLogFormat};
+use crate::logging::message::LogMessage;
+use crate::proxy::timing::UpstreamTiming;
+use crate::tls::params::TlsSessionParams;
+
 /// 로그 포맷터 - 로그 데이터 형식 변환 담당
 pub struct LogFormatter;
 
@@ -80,4 +86,99 @@ impl LogFormatter {
         
         result
     }
-} 
\ No newline at end of file
+    
+    /// 접근 로그 한 줄 (설정된 필드만, 고른 필드가 이 메시지에 하나도 없으면 None)
+    ///
+    /// 텍스트 형식은 `이름=값`을 공백으로 나열하고, JSON 형식은 필드 이름을 키로 하는 객체 하나를 씁니다.
+    pub fn format_access_log(settings: &AccessLogConfig, message: &LogMessage) -> Option<String> {
+        let values: Vec<(AccessLogField, Value)> = match message {
+            LogMessage::RequestLog { host, method, path, timestamp, session_id, client_ip, target_ip, is_rejected, is_tls, tenant, .. } => {
+                let mut values = vec![
+                    (AccessLogField::Timestamp, json!(timestamp.to_rfc3339())),
+                    (AccessLogField::SessionId, json!(session_id)),
+                    (AccessLogField::ClientIp, json!(client_ip)),
+                    (AccessLogField::Method, json!(method)),
+                    (AccessLogField::Host, json!(host)),
+                    (AccessLogField::Path, json!(path)),
+                    (AccessLogField::TargetIp, json!(target_ip)),
+                    (AccessLogField::Tls, json!(is_tls)),
+                    (AccessLogField::Rejected, json!(is_rejected)),
+                ];
+                if let Some(tenant) = tenant {
+                    values.push((AccessLogField::Tenant, json!(tenant)));
+                }
+                values
+            },
+            LogMessage::ResponseLog { session_id, status_code, response_time, response_size, timestamp, timing, upstream_cert_warning, tls_params, tenant, .. } => {
+                let mut values = vec![
+                    (AccessLogField::Timestamp, json!(timestamp.to_rfc3339())),
+                    (AccessLogField::SessionId, json!(session_id)),
+                    (AccessLogField::Status, json!(status_code)),
+                    (AccessLogField::ResponseTimeMs, json!(response_time)),
+                    (AccessLogField::ResponseSize, json!(response_size)),
+                ];
+                if let Some(timing) = timing {
+                    values.push((AccessLogField::UpstreamTiming, Self::timing_value(timing, settings.format)));
+                }
+                values.push((AccessLogField::UpstreamCertWarning, json!(upstream_cert_warning)));
+                if let Some(params) = tls_params {
+                    values.push((AccessLogField::TlsParams, Self::tls_params_value(params, settings.format)));
+                }
+                if let Some(tenant) = tenant {
+                    values.push((AccessLogField::Tenant, json!(tenant)));
+                }
+                values
+            },
+            _ => return None,
+        };
+        
+        let selected = values.into_iter()
+            .filter(|(field, _)| settings.fields.is_empty() || settings.fields.contains(field));
+        
+        match settings.format {
+            AccessLogFormat::Json => {
+                let object: serde_json::Map<String, Value> = selected
+                    .map(|(field, value)| (field.name().to_string(), value))
+                    .collect();
+                (!object.is_empty()).then(|| Value::Object(object).to_string())
+            },
+            AccessLogFormat::Text => {
+                let mut line = String::new();
+                for (field, value) in selected {
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    match value {
+                        Value::String(text) => { let _ = write!(line, "{}={}", field.name(), text); },
+                        other => { let _ = write!(line, "{}={}", field.name(), other); },
+                    }
+                }
+                (!line.is_empty()).then_some(line)
+            },
+        }
+    }
+    
+    /// TLS 협상 값 (텍스트는 `client_tls=TLSv1_3 ...` 형식, JSON은 값이 있는 항목만 담은 객체)
+    fn tls_params_value(params: &TlsSessionParams, format: AccessLogFormat) -> Value {
+        if format == AccessLogFormat::Text {
+            return json!(format!("\"{}\"", params));
+        }
+        let object: serde_json::Map<String, Value> = params.fields().into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name.to_string(), json!(value))))
+            .collect();
+        Value::Object(object)
+    }
+    
+    /// 업스트림 구간 시간 값 (텍스트는 `dns=3ms connect=12ms` 형식, JSON은 구간별 밀리초 객체)
+    fn timing_value(timing: &UpstreamTiming, format: AccessLogFormat) -> Value {
+        if format == AccessLogFormat::Text {
+            return json!(format!("\"{}\"", timing));
+        }
+        json!({
+            "dns_ms": UpstreamTiming::millis(timing.dns),
+            "connect_ms": UpstreamTiming::millis(timing.connect),
+            "tls_ms": UpstreamTiming::millis(timing.tls_handshake),
+            "ttfb_ms": UpstreamTiming::millis(timing.first_byte),
+        })
+    }
+}
@@ -1,35 +1,147 @@
 use std::error::Error;
+use std::net::IpAddr;
 use std::sync::Arc;
 use chrono::Utc;
-use log::{debug, error, info};
+use log::{debug, info};
 
+use crate::config::Config;
+use crate::db::config::DbConfig;
+use crate::logging::events;
 use crate::logging::message::{LogMessage, LogPriority};
-use crate::logging::worker::WorkerPool;
+use crate::logging::sink::{AccessLogSink, DbLogSink, LogPipeline, LogSink, SinkStats};
+use crate::logging::webhook::{WebhookEvent, WebhookEventKind, WebhookNotifier};
+use crate::proxy::timing::UpstreamTiming;
+use crate::tls::params::TlsSessionParams;
 
 /// 로거 인터페이스
 #[derive(Clone)]
 pub struct Logger {
-    /// 워커 풀 참조
-    worker_pool: Option<Arc<WorkerPool>>,
+    /// 요청/응답 로그를 싱크별로 나눠 보내는 파이프라인 (초기화 시 생성)
+    pipeline: Option<Arc<LogPipeline>>,
     /// 초기화 완료 여부
     initialized: bool,
+    /// 이벤트 웹훅 전송기 (설정된 경우)
+    webhook: Option<Arc<WebhookNotifier>>,
+    /// 모든 로그를 버림 (하네스 등에서 사용)
+    discard: bool,
+    /// 접근 로그 싱크 (with_access_log로 설정, 초기화 시 파이프라인에 등록)
+    access_log: Option<Arc<AccessLogSink>>,
+    /// 초기화 시 기본 싱크 뒤에 등록할 추가 싱크
+    extra_sinks: Vec<Arc<dyn LogSink>>,
+    /// 세션의 테넌트 태그 (tenants 설정 시 세션마다 지정)
+    tenant: Option<Arc<str>>,
 }
 
 impl Logger {
     /// 새 로거 인스턴스 생성
     pub fn new() -> Self {
         Self {
-            worker_pool: None,
+            pipeline: None,
             initialized: false,
+            webhook: None,
+            discard: false,
+            access_log: None,
+            extra_sinks: Vec::new(),
+            tenant: None,
         }
     }
     
+    /// 모든 로그를 버리는 초기화된 로거 생성
+    #[cfg(feature = "self-test")]
+    pub fn noop() -> Self {
+        Self {
+            initialized: true,
+            discard: true,
+            ..Self::new()
+        }
+    }
+    
+    /// 설정된 웹훅 엔드포인트로 이벤트 전송 활성화
+    pub fn with_webhooks(mut self, config: Arc<Config>) -> Self {
+        self.webhook = WebhookNotifier::new(config).map(Arc::new);
+        self
+    }
+    
+    /// 접근 로그의 형식과 필드, 기록 파일 설정
+    ///
+    /// 파일을 열지 못하면 오류를 남기고 표준 출력으로 기록합니다.
+    pub fn with_access_log(mut self, config: &Config) -> Self {
+        self.access_log = Some(Arc::new(AccessLogSink::new(&config.access_log)));
+        self
+    }
+    
+    /// 요청/응답 로그를 받을 싱크 추가 (초기화 전에 등록, 기본 싱크 뒤에 등록 순서대로 전달)
+    #[cfg(test)]
+    pub fn with_sink(mut self, sink: Arc<dyn LogSink>) -> Self {
+        self.extra_sinks.push(sink);
+        self
+    }
+    
+    /// 이 로거로 남기는 요청/응답 로그에 테넌트 태그 지정
+    pub fn with_tenant(mut self, tenant: Arc<str>) -> Self {
+        self.tenant = Some(tenant);
+        self
+    }
+    
+    /// 이벤트를 실시간 이벤트 스트림에 발행하고 웹훅으로 전송 (웹훅이 설정되지 않았으면 발행만)
+    fn emit_event(&self, event: WebhookEvent) {
+        events::publish(&event);
+        if let Some(webhook) = &self.webhook {
+            webhook.notify(event);
+        }
+    }
+    
+    /// 세션 시작 기록 (실시간 이벤트 스트림에만 발행, 웹훅 대상 아님)
+    pub fn log_session_start(&self, session_id: &str, client_ip: IpAddr) {
+        if events::subscriber_count() > 0 {
+            events::publish(&self.session_event("session_start", session_id, client_ip, String::new()));
+        }
+    }
+    
+    /// 세션 종료 기록 (종료 사유, 릴레이 종료 방식, 고른 상위 프록시, 실시간 이벤트 스트림에만 발행)
+    pub fn log_session_end(&self, session_id: &str, client_ip: IpAddr, reason: &str, close_kind: Option<&str>, parent_proxy: Option<&str>) {
+        if events::subscriber_count() > 0 {
+            let mut detail = reason.to_string();
+            if let Some(kind) = close_kind {
+                detail.push_str(&format!(" close={}", kind));
+            }
+            if let Some(parent) = parent_proxy {
+                detail.push_str(&format!(" parent={}", parent));
+            }
+            events::publish(&self.session_event("session_end", session_id, client_ip, detail));
+        }
+    }
+    
+    /// 세션 이벤트 (테넌트 태그가 있으면 detail 앞에 붙임)
+    fn session_event(&self, event: &'static str, session_id: &str, client_ip: IpAddr, detail: String) -> WebhookEvent {
+        let detail = match &self.tenant {
+            Some(tenant) if detail.is_empty() => format!("tenant={}", tenant),
+            Some(tenant) => format!("tenant={} {}", tenant, detail),
+            None => detail,
+        };
+        WebhookEvent {
+            event,
+            timestamp: Utc::now(),
+            session_id: session_id.to_string(),
+            client_ip: client_ip.to_string(),
+            host: String::new(),
+            detail,
+        }
+    }
+    
+    /// 업스트림 연결 실패 기록
+    pub fn log_upstream_failure(&self, session_id: &str, client_ip: &str, host: &str, error: &str) {
+        self.emit_event(WebhookEvent::new(WebhookEventKind::UpstreamFailure, session_id, client_ip, host, error));
+    }
+    
     /// 로거 초기화 상태 확인
     pub fn is_initialized(&self) -> bool {
         self.initialized
     }
     
-    /// 로거 초기화
+    /// 로거 초기화 (기본 싱크와 추가 싱크로 파이프라인 생성)
+    ///
+    /// DB 활성화 시에는 DB 싱크와 접근 로그 파일(설정된 경우)에, DB 비활성화 시에는 접근 로그 싱크(파일 또는 표준 출력)에 기록합니다.
     pub async fn init(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
         // 이미 초기화되었는지 확인
         if self.initialized {
@@ -39,15 +151,23 @@ impl Logger {
         
         debug!("Logger 초기화 시작...");
         
-        // 워커 풀 생성
-        let worker_pool = WorkerPool::new().await?;
-        let worker_pool = Arc::new(worker_pool);
+        let db_enabled = DbConfig::is_enabled();
+        let mut sinks: Vec<Arc<dyn LogSink>> = Vec::new();
+        if db_enabled {
+            sinks.push(Arc::new(DbLogSink::new().await?));
+        }
+        if let Some(access_log) = self.access_log.take()
+            && (!db_enabled || access_log.has_file()) {
+            sinks.push(access_log);
+        }
+        sinks.append(&mut self.extra_sinks);
         
-        // 로거 설정
-        self.worker_pool = Some(worker_pool.clone());
+        let pipeline = LogPipeline::new(sinks);
+        let names: Vec<String> = pipeline.stats().into_iter().map(|stats| stats.name).collect();
+        self.pipeline = Some(Arc::new(pipeline));
         self.initialized = true;
         
-        info!("Logger 초기화 완료");
+        info!("Logger 초기화 완료 ({}싱크: {})", if db_enabled { "" } else { "DB 비활성화, " }, names.join(", "));
         Ok(())
     }
     
@@ -70,40 +190,34 @@ impl Logger {
             debug!("초기화되지 않은 Logger에 로깅 시도");
             return Err("로거가 초기화되지 않았습니다");
         }
-        
-        if let Some(worker_pool) = &self.worker_pool {
-            let method_str = method.into();
-            
-            // 우선순위 결정
-            let priority = LogPriority::from_request_info(is_rejected, &method_str);
-            
-            let log_message = LogMessage::RequestLog {
-                host: host.into(),
-                method: method_str,
-                path: path.into(),
-                header: header.into(),
-                body,
-                timestamp: Utc::now(),
-                session_id: session_id.into(),
-                client_ip: client_ip.into(),
-                target_ip: target_ip.into(),
-                is_rejected,
-                is_tls,
-                priority,
-            };
-            
-            // 비동기로 메시지 전송
-            match worker_pool.send_log(log_message).await {
-                Ok(_) => Ok(()),
-                Err(e) => {
-                    error!("로그 메시지 전송 실패: {}", e);
-                    Err("로그 메시지 전송 실패")
-                }
-            }
-        } else {
-            error!("초기화는 되었지만 worker_pool이 없습니다");
-            Err("로거 내부 오류: worker_pool이 없습니다")
+        if self.discard {
+            return Ok(());
         }
+        
+        let method_str = method.into();
+        
+        // 우선순위 결정
+        let priority = LogPriority::from_request_info(is_rejected, &method_str);
+        
+        let log_message = LogMessage::RequestLog {
+            host: host.into(),
+            method: method_str,
+            path: path.into(),
+            header: header.into(),
+            body,
+            timestamp: Utc::now(),
+            session_id: session_id.into(),
+            client_ip: client_ip.into(),
+            target_ip: target_ip.into(),
+            is_rejected,
+            is_tls,
+            tenant: self.tenant.as_deref().map(str::to_string),
+            block_category: None,
+            priority,
+        };
+        
+        self.dispatch(log_message);
+        Ok(())
     }
     
     /// 비동기 응답 로깅
@@ -115,45 +229,51 @@ impl Logger {
         response_size: usize,
         headers: impl Into<String>,
         body_preview: Option<String>,
+        timing: Option<UpstreamTiming>,
+        upstream_cert_warning: bool,
+        tls_params: Option<TlsSessionParams>,
     ) -> Result<(), &'static str> {
         // 초기화 여부 확인
         if !self.initialized {
             debug!("초기화되지 않은 Logger에 응답 로깅 시도");
             return Err("로거가 초기화되지 않았습니다");
         }
+        if self.discard {
+            return Ok(());
+        }
         
-        if let Some(worker_pool) = &self.worker_pool {
-            // 우선순위 결정
-            let base_priority = LogPriority::from_status_code(status_code);
-            let priority = LogPriority::from_response_time(response_time, base_priority);
-            
-            let log_message = LogMessage::ResponseLog {
-                session_id: session_id.into(),
-                status_code,
-                response_time,
-                response_size,
-                timestamp: Utc::now(),
-                headers: headers.into(),
-                body_preview,
-                priority,
-            };
-            
-            // 비동기로 메시지 전송
-            match worker_pool.send_log(log_message).await {
-                Ok(_) => Ok(()),
-                Err(e) => {
-                    error!("응답 로그 메시지 전송 실패: {}", e);
-                    Err("응답 로그 메시지 전송 실패")
-                }
-            }
-        } else {
-            error!("초기화는 되었지만 worker_pool이 없습니다");
-            Err("로거 내부 오류: worker_pool이 없습니다")
+        // 우선순위 결정
+        let base_priority = LogPriority::from_status_code(status_code);
+        let priority = LogPriority::from_response_time(response_time, base_priority);
+        
+        let log_message = LogMessage::ResponseLog {
+            session_id: session_id.into(),
+            status_code,
+            response_time,
+            response_size,
+            timestamp: Utc::now(),
+            headers: headers.into(),
+            body_preview,
+            timing,
+            upstream_cert_warning,
+            tls_params,
+            tenant: self.tenant.as_deref().map(str::to_string),
+            priority,
+        };
+        
+        self.dispatch(log_message);
+        Ok(())
+    }
+    
+    /// 등록된 싱크로 기록 전달 (싱크별 대기열에 넣고 바로 반환)
+    fn dispatch(&self, message: LogMessage) {
+        if let Some(pipeline) = &self.pipeline {
+            pipeline.dispatch(message);
         }
     }
     
-    /// 차단된 요청 로깅
-    pub async fn log_rejected_request(&self, request: &str, host: &str, ip: &str, session_id: &str, is_tls: bool) -> Result<(), &'static str> {
+    /// 차단된 요청 로깅 (`category`는 일치한 차단 규칙 유형, 알 수 없으면 None으로 비워 둠)
+    pub async fn log_rejected_request(&self, request: &str, host: &str, ip: &str, session_id: &str, is_tls: bool, category: Option<&str>) -> Result<(), &'static str> {
         // 초기화 여부 확인
         if !self.initialized {
             debug!("초기화되지 않은 Logger에 차단된 요청 로깅 시도");
@@ -163,19 +283,37 @@ impl Logger {
         // 요청 파싱
         let (method, path, header, body) = self.parse_request_for_reject(request);
         
-        // log_request 메서드 호출하여 로깅
-        self.log_request(
+        self.emit_event(WebhookEvent::new(
+            WebhookEventKind::BlockedRequest,
+            session_id,
+            ip,
             host,
+            format!("{} {}", method, path),
+        ));
+        
+        if self.discard {
+            return Ok(());
+        }
+        
+        let log_message = LogMessage::RequestLog {
+            host: host.to_string(),
+            priority: LogPriority::from_request_info(true, &method),
             method,
             path,
             header,
             body,
-            session_id,
-            ip,
-            "Blocked", // 차단된 요청은 타겟 IP를 "Blocked"로 표시
-            true,      // 차단된 요청
-            is_tls     // TLS 여부
-        ).await
+            timestamp: Utc::now(),
+            session_id: session_id.to_string(),
+            client_ip: ip.to_string(),
+            target_ip: "Blocked".to_string(), // 차단된 요청은 타겟 IP를 "Blocked"로 표시
+            is_rejected: true,
+            is_tls,
+            tenant: self.tenant.as_deref().map(str::to_string),
+            block_category: category.map(str::to_string),
+        };
+        
+        self.dispatch(log_message);
+        Ok(())
     }
     
     /// 요청 문자열 파싱
@@ -208,7 +346,7 @@ impl Logger {
         (method, path, header, body)
     }
     
-    /// 로그 플러시 요청
+    /// 로그 플러시 요청 (모든 싱크가 버퍼링한 기록을 내보낼 때까지 제한 시간 동안 대기)
     pub async fn flush(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
         // 초기화 여부 확인
         if !self.initialized {
@@ -216,11 +354,108 @@ impl Logger {
             return Err("로거가 초기화되지 않았습니다".into());
         }
         
-        if let Some(worker_pool) = &self.worker_pool {
-            worker_pool.flush().await
-        } else {
-            error!("초기화는 되었지만 worker_pool이 없습니다");
-            Err("로거 내부 오류: worker_pool이 없습니다".into())
+        match &self.pipeline {
+            Some(pipeline) => pipeline.flush().await,
+            None => Ok(()),
+        }
+    }
+    
+    /// 싱크별 처리 통계 (초기화 전이면 빈 목록)
+    pub fn sink_stats(&self) -> Vec<SinkStats> {
+        self.pipeline.as_ref().map(|pipeline| pipeline.stats()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use async_trait::async_trait;
+    use tokio::sync::Semaphore;
+    use crate::logging::sink::SinkResult;
+
+    const RECORDS: u64 = 50;
+
+    // 기록이 열릴 때까지 멈춰 있는 싱크 (느린 외부 싱크 흉내)
+    struct StalledSink {
+        gate: Semaphore,
+    }
+
+    #[async_trait]
+    impl LogSink for StalledSink {
+        fn name(&self) -> &str {
+            "test-stalled"
         }
+
+        async fn write(&self, _record: &LogMessage) -> SinkResult {
+            self.gate.acquire().await?.forget();
+            Ok(())
+        }
+
+        fn queue_capacity(&self) -> usize {
+            4
+        }
+    }
+
+    // 항상 실패하는 싱크
+    struct FailingSink;
+
+    #[async_trait]
+    impl LogSink for FailingSink {
+        fn name(&self) -> &str {
+            "test-failing"
+        }
+
+        async fn write(&self, _record: &LogMessage) -> SinkResult {
+            Err("injected sink failure".into())
+        }
+    }
+
+    // 받은 기록을 세기만 하는 싱크
+    struct CountingSink;
+
+    #[async_trait]
+    impl LogSink for CountingSink {
+        fn name(&self) -> &str {
+            "test-counting"
+        }
+
+        async fn write(&self, _record: &LogMessage) -> SinkResult {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn stalled_and_failing_sinks_do_not_block_others() {
+        DbConfig::disable();
+        let stalled = Arc::new(StalledSink { gate: Semaphore::new(0) });
+        let mut logger = Logger::new()
+            .with_sink(stalled.clone())
+            .with_sink(Arc::new(FailingSink))
+            .with_sink(Arc::new(CountingSink));
+        logger.init().await.unwrap();
+
+        for i in 0..RECORDS {
+            logger.log_request("example.com", "GET", "/", "", None, format!("sink-{}", i), "127.0.0.1", "127.0.0.1", false, false).await.unwrap();
+        }
+
+        let stats = |name: &str| logger.sink_stats().into_iter().find(|stats| stats.name == name).unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+        while (stats("test-counting").written < RECORDS || stats("test-failing").failed < RECORDS)
+            && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(stats("test-counting").written, RECORDS);
+        assert_eq!(stats("test-failing").failed, RECORDS);
+        // 멈춘 싱크는 자기 대기열 한도를 넘는 기록만 버림
+        let blocked = stats("test-stalled");
+        assert_eq!(blocked.written, 0);
+        assert!(blocked.dropped > 0);
+
+        // 멈춘 싱크를 풀면 대기열에 남은 기록을 마저 씀
+        stalled.gate.add_permits(RECORDS as usize);
+        logger.flush().await.unwrap();
+        let blocked = stats("test-stalled");
+        assert_eq!(blocked.written + blocked.dropped, RECORDS);
     }
-} 
\ No newline at end of file
+}
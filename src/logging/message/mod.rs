@@ -1,5 +1,8 @@
 use chrono::{DateTime, Utc};
 
+use crate::proxy::timing::UpstreamTiming;
+use crate::tls::params::TlsSessionParams;
+
 /// 로그 메시지 타입
 #[derive(Debug, Clone)]
 pub enum LogMessage {
@@ -16,6 +19,10 @@ pub enum LogMessage {
         target_ip: String,
         is_rejected: bool,
         is_tls: bool,
+        /// 클라이언트 주소 대역의 테넌트 태그 (tenants 설정 시)
+        tenant: Option<String>,
+        /// 차단된 요청이 일치한 차단 규칙 유형 (exact, wildcard, regex, filter, 알 수 없으면 None)
+        block_category: Option<String>,
         priority: LogPriority,
     },
     
@@ -28,6 +35,14 @@ pub enum LogMessage {
         timestamp: DateTime<Utc>,
         headers: String,
         body_preview: Option<String>,
+        /// 업스트림 구간 시간 (upstream_timing_enabled인 경우)
+        timing: Option<UpstreamTiming>,
+        /// warn 등급으로 업스트림 인증서 검증 실패를 허용한 세션
+        upstream_cert_warning: bool,
+        /// 클라이언트/업스트림 TLS 협상 값 (tls_params_logging인 경우)
+        tls_params: Option<TlsSessionParams>,
+        /// 클라이언트 주소 대역의 테넌트 태그 (tenants 설정 시)
+        tenant: Option<String>,
         priority: LogPriority,
     },
     
@@ -1,8 +1,13 @@
+pub mod events;
 pub mod message;
 pub mod storage;
 pub mod worker;
 mod logger;
+pub mod file_sink;
 pub mod formatter;
+pub mod recent;
+pub mod sink;
+pub mod webhook;
 
 pub use logger::Logger;
 pub use formatter::LogFormatter; 
\ No newline at end of file
@@ -0,0 +1,151 @@
+// 최근 로그 기록 보관 (관리 엔드포인트 GET /debug/log)
+// 전역 로그 수준을 올리지 않고도 문제가 생긴 인스턴스의 최근 상황을 볼 수 있도록
+// 표준 출력 로거를 감싸 모든 수준의 기록을 고정 크기 링 버퍼에 남깁니다.
+// 링 버퍼 쓰기는 슬롯을 try_lock으로만 잡으므로 경합 시 해당 기록을 버릴 뿐 실제 로그 출력을 막지 않습니다.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::Utc;
+use log::{LevelFilter, Log, Metadata, Record};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+
+/// 기록 하나에 보관하는 메시지 최대 길이 (넘으면 잘라냄)
+const RECENT_LOG_MESSAGE_MAX_LEN: usize = 2048;
+
+// 전역 최근 로그 링 버퍼 (debug_log_buffer_size가 0이면 초기화하지 않음)
+static RECENT_LOGS: OnceCell<RecentLogs> = OnceCell::new();
+
+/// 보관된 로그 기록
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentLogEntry {
+    /// 기록 순번 (프로세스 시작 후 단조 증가)
+    pub seq: u64,
+    pub timestamp: String,
+    pub level: &'static str,
+    pub target: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub message: String,
+}
+
+/// 최근 로그 링 버퍼 스냅샷
+#[derive(Debug, Serialize)]
+pub struct RecentLogSnapshot {
+    pub capacity: usize,
+    /// 슬롯 경합으로 버린 기록 수
+    pub dropped: u64,
+    /// 오래된 순으로 정렬된 기록
+    pub records: Vec<RecentLogEntry>,
+}
+
+/// 고정 크기 로그 링 버퍼
+///
+/// 쓰기마다 원자적 순번으로 슬롯을 고르고, 슬롯 잠금을 바로 얻지 못하면 기다리지 않고 기록을 버립니다.
+pub struct RecentLogs {
+    slots: Box<[Mutex<Option<RecentLogEntry>>]>,
+    next_seq: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl RecentLogs {
+    fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity).map(|_| Mutex::new(None)).collect(),
+            next_seq: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    fn push(&self, record: &Record) {
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        let slot = &self.slots[(seq % self.slots.len() as u64) as usize];
+        let Ok(mut slot) = slot.try_lock() else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        };
+
+        let mut message = record.args().to_string();
+        if message.len() > RECENT_LOG_MESSAGE_MAX_LEN {
+            let mut end = RECENT_LOG_MESSAGE_MAX_LEN;
+            while !message.is_char_boundary(end) {
+                end -= 1;
+            }
+            message.truncate(end);
+        }
+        *slot = Some(RecentLogEntry {
+            seq,
+            timestamp: Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            level: record.level().as_str(),
+            target: record.target().to_string(),
+            file: record.file().map(str::to_string),
+            line: record.line(),
+            message,
+        });
+    }
+
+    /// 현재 보관 중인 기록 (오래된 순)
+    pub fn snapshot(&self) -> RecentLogSnapshot {
+        let mut records: Vec<RecentLogEntry> = self.slots.iter()
+            .filter_map(|slot| match slot.lock() {
+                Ok(slot) => slot.clone(),
+                Err(poisoned) => poisoned.into_inner().clone(),
+            })
+            .collect();
+        records.sort_by_key(|record| record.seq);
+        RecentLogSnapshot {
+            capacity: self.slots.len(),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            records,
+        }
+    }
+}
+
+/// 최근 로그 링 버퍼 (비활성화되었으면 None)
+pub fn recent_logs() -> Option<&'static RecentLogs> {
+    RECENT_LOGS.get()
+}
+
+/// 링 버퍼를 만들고 모든 수준의 기록을 받도록 전역 최대 수준을 올림 (0이면 비활성화)
+pub fn init_recent_logs(capacity: usize) {
+    if capacity == 0 || RECENT_LOGS.set(RecentLogs::new(capacity)).is_err() {
+        return;
+    }
+    log::set_max_level(LevelFilter::Trace);
+    log::info!("최근 로그 링 버퍼 활성화: {}개 (GET /debug/log)", capacity);
+}
+
+/// 표준 출력 로거를 감싸 링 버퍼에도 기록을 남기는 로거
+pub struct RecentLogCapture {
+    inner: env_logger::Logger,
+}
+
+impl RecentLogCapture {
+    /// 전역 로거로 설치 (최대 수준은 링 버퍼 초기화 전까지 표준 출력 로거의 필터를 따름)
+    pub fn install(inner: env_logger::Logger) -> Result<(), log::SetLoggerError> {
+        let filter = inner.filter();
+        log::set_boxed_logger(Box::new(Self { inner }))?;
+        log::set_max_level(filter);
+        Ok(())
+    }
+}
+
+impl Log for RecentLogCapture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        RECENT_LOGS.get().is_some() || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if let Some(recent) = RECENT_LOGS.get() {
+            recent.push(record);
+        }
+        if self.inner.matches(record) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
@@ -0,0 +1,284 @@
+// 로그 싱크 파이프라인
+// 요청/응답 로그 기록을 등록된 싱크(DB, 접근 로그 파일/표준 출력, 외부 연동 싱크)에 나눠 보냅니다.
+// 싱크마다 대기열과 처리 태스크를 따로 두므로 느리거나 실패하는 싱크는 자기 대기열에서만 기록을 버리고
+// 다른 싱크와 세션 처리를 막지 않습니다.
+
+use std::error::Error;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use log::{error, info, warn};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Instant, timeout, timeout_at};
+
+use crate::config::{AccessLogConfig, AccessLogFormat};
+use crate::constants::{LOG_SINK_FLUSH_TIMEOUT_MS, LOG_SINK_QUEUE_SIZE, LOG_SINK_WRITE_TIMEOUT_MS};
+use crate::logging::LogFormatter;
+use crate::logging::file_sink::AccessLogFile;
+use crate::logging::message::LogMessage;
+use crate::logging::worker::WorkerPool;
+
+pub type SinkResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+/// 로그 기록을 받아 저장하거나 전달하는 싱크
+///
+/// 기록은 싱크 전용 태스크에서 한 번에 하나씩 전달되며, 한 기록에 `LOG_SINK_WRITE_TIMEOUT_MS`를 넘기면
+/// 실패로 집계하고 다음 기록으로 넘어갑니다. 외부 연동 싱크는 `Logger::with_sink`로 등록합니다.
+#[async_trait]
+pub trait LogSink: Send + Sync {
+    /// 로그와 통계에 쓰는 이름
+    fn name(&self) -> &str;
+
+    /// 요청/응답 로그 기록 하나 쓰기
+    async fn write(&self, record: &LogMessage) -> SinkResult;
+
+    /// 버퍼링한 기록 내보내기
+    async fn flush(&self) -> SinkResult {
+        Ok(())
+    }
+
+    /// 이 싱크의 대기열 크기 (가득 차면 새 기록을 버림)
+    fn queue_capacity(&self) -> usize {
+        LOG_SINK_QUEUE_SIZE
+    }
+}
+
+/// 싱크 태스크로 보내는 명령
+enum SinkCommand {
+    Record(Arc<LogMessage>),
+    Flush(oneshot::Sender<()>),
+}
+
+/// 싱크별 누적 처리 수
+#[derive(Debug, Default)]
+struct SinkCounters {
+    written: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// 싱크별 처리 통계
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkStats {
+    pub name: String,
+    /// 기록 성공
+    pub written: u64,
+    /// 기록 실패 또는 처리 시간 초과
+    pub failed: u64,
+    /// 대기열이 가득 차 버림
+    pub dropped: u64,
+}
+
+/// 등록된 싱크 하나의 대기열
+struct SinkQueue {
+    name: String,
+    sender: mpsc::Sender<SinkCommand>,
+    counters: Arc<SinkCounters>,
+}
+
+/// 등록된 싱크 전체에 기록을 나눠 보내는 파이프라인
+pub struct LogPipeline {
+    queues: Vec<SinkQueue>,
+}
+
+impl LogPipeline {
+    /// 싱크마다 대기열과 처리 태스크 시작
+    pub fn new(sinks: Vec<Arc<dyn LogSink>>) -> Self {
+        let queues = sinks.into_iter().map(|sink| {
+            let name = sink.name().to_string();
+            let (sender, receiver) = mpsc::channel(sink.queue_capacity().max(1));
+            let counters = Arc::new(SinkCounters::default());
+            info!("로그 싱크 등록: {} (대기열 {})", name, sink.queue_capacity().max(1));
+            tokio::spawn(run_sink(sink, receiver, counters.clone()));
+            SinkQueue { name, sender, counters }
+        }).collect();
+        Self { queues }
+    }
+
+    /// 기록을 모든 싱크 대기열에 넣음 (대기열이 가득 찬 싱크는 이 기록을 버림)
+    pub fn dispatch(&self, record: LogMessage) {
+        let record = Arc::new(record);
+        for queue in &self.queues {
+            if queue.sender.try_send(SinkCommand::Record(record.clone())).is_err() {
+                let dropped = queue.counters.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                // 가득 찬 동안 매 기록마다 남기지 않도록 처음과 이후 1000건마다 기록
+                if dropped == 1 || dropped.is_multiple_of(1000) {
+                    warn!("로그 싱크 {} 대기열이 가득 차 기록을 버림 (누적 {}건)", queue.name, dropped);
+                }
+            }
+        }
+    }
+
+    /// 모든 싱크에 플러시를 요청하고 `LOG_SINK_FLUSH_TIMEOUT_MS`까지 완료를 기다림
+    pub async fn flush(&self) -> SinkResult {
+        let deadline = Instant::now() + Duration::from_millis(LOG_SINK_FLUSH_TIMEOUT_MS);
+        let mut pending = Vec::with_capacity(self.queues.len());
+        for queue in &self.queues {
+            let (ack_tx, ack_rx) = oneshot::channel();
+            if let Ok(Ok(())) = timeout_at(deadline, queue.sender.send(SinkCommand::Flush(ack_tx))).await {
+                pending.push((queue.name.as_str(), Some(ack_rx)));
+            } else {
+                pending.push((queue.name.as_str(), None));
+            }
+        }
+
+        let mut unfinished = Vec::new();
+        for (name, ack_rx) in pending {
+            let done = match ack_rx {
+                Some(ack_rx) => matches!(timeout_at(deadline, ack_rx).await, Ok(Ok(()))),
+                None => false,
+            };
+            if !done {
+                unfinished.push(name);
+            }
+        }
+        if unfinished.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("로그 싱크 플러시 미완료: {}", unfinished.join(", ")).into())
+        }
+    }
+
+    /// 싱크별 처리 통계 (등록 순서)
+    pub fn stats(&self) -> Vec<SinkStats> {
+        self.queues.iter().map(|queue| SinkStats {
+            name: queue.name.clone(),
+            written: queue.counters.written.load(Ordering::Relaxed),
+            failed: queue.counters.failed.load(Ordering::Relaxed),
+            dropped: queue.counters.dropped.load(Ordering::Relaxed),
+        }).collect()
+    }
+}
+
+/// 싱크 처리 태스크: 대기열의 기록을 순서대로 쓰고, 대기열이 닫히면 남은 기록을 내보낸 뒤 종료
+async fn run_sink(sink: Arc<dyn LogSink>, mut receiver: mpsc::Receiver<SinkCommand>, counters: Arc<SinkCounters>) {
+    let write_timeout = Duration::from_millis(LOG_SINK_WRITE_TIMEOUT_MS);
+    while let Some(command) = receiver.recv().await {
+        match command {
+            SinkCommand::Record(record) => match timeout(write_timeout, sink.write(&record)).await {
+                Ok(Ok(())) => {
+                    counters.written.fetch_add(1, Ordering::Relaxed);
+                },
+                Ok(Err(e)) => {
+                    let failed = counters.failed.fetch_add(1, Ordering::Relaxed) + 1;
+                    if failed == 1 || failed.is_multiple_of(1000) {
+                        error!("로그 싱크 {} 기록 실패 (누적 {}건): {}", sink.name(), failed, e);
+                    }
+                },
+                Err(_) => {
+                    let failed = counters.failed.fetch_add(1, Ordering::Relaxed) + 1;
+                    warn!("로그 싱크 {} 기록 시간 초과 ({}ms, 누적 실패 {}건)", sink.name(), LOG_SINK_WRITE_TIMEOUT_MS, failed);
+                },
+            },
+            SinkCommand::Flush(ack) => {
+                if let Err(e) = sink.flush().await {
+                    error!("로그 싱크 {} 플러시 실패: {}", sink.name(), e);
+                }
+                let _ = ack.send(());
+            },
+        }
+    }
+    if let Err(e) = sink.flush().await {
+        error!("로그 싱크 {} 플러시 실패: {}", sink.name(), e);
+    }
+}
+
+/// DB 싱크: 우선순위별 워커 풀을 거쳐 request_logs/response_logs에 배치로 저장
+pub struct DbLogSink {
+    worker_pool: WorkerPool,
+}
+
+impl DbLogSink {
+    /// 로그 테이블을 확인하고 워커 풀 시작
+    pub async fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Ok(Self { worker_pool: WorkerPool::new().await? })
+    }
+}
+
+#[async_trait]
+impl LogSink for DbLogSink {
+    fn name(&self) -> &str {
+        "db"
+    }
+
+    async fn write(&self, record: &LogMessage) -> SinkResult {
+        self.worker_pool.send_log(record.clone()).await
+    }
+
+    async fn flush(&self) -> SinkResult {
+        self.worker_pool.flush().await
+    }
+}
+
+/// 접근 로그 싱크: access_log 형식으로 한 줄씩 파일(access_log.file.path 설정 시) 또는 표준 출력에 기록
+pub struct AccessLogSink {
+    config: AccessLogConfig,
+    file: Option<AccessLogFile>,
+}
+
+impl AccessLogSink {
+    /// 접근 로그 형식과 기록 파일 설정
+    ///
+    /// 파일을 열지 못하면 오류를 남기고 표준 출력으로 기록합니다.
+    pub fn new(config: &AccessLogConfig) -> Self {
+        let file = match AccessLogFile::open(&config.file) {
+            Ok(file) => file,
+            Err(e) => {
+                error!("{} (표준 출력으로 기록)", e);
+                None
+            }
+        };
+        Self { config: config.clone(), file }
+    }
+
+    /// 파일에 기록하는지 (DB 활성화 시에는 파일 기록만 함께 사용)
+    pub fn has_file(&self) -> bool {
+        self.file.is_some()
+    }
+
+    /// 접근 로그 한 줄 생성
+    ///
+    /// 필드를 고르지 않은 텍스트 형식은 기존 한 줄 형식 앞에 기록 시각(RFC 3339)을 붙입니다.
+    fn format(&self, message: &LogMessage) -> Option<String> {
+        if self.config.format != AccessLogFormat::Text || !self.config.fields.is_empty() {
+            return LogFormatter::format_access_log(&self.config, message);
+        }
+
+        match message {
+            LogMessage::RequestLog { host, method, path, timestamp, session_id, client_ip, target_ip, is_rejected, is_tls, tenant, .. } => {
+                let tenant = tenant.as_ref().map(|tenant| format!(", tenant={}", tenant)).unwrap_or_default();
+                Some(format!("{} [Session:{}] {} -> {} {} {} (target={}, tls={}, rejected={}{})",
+                             timestamp.to_rfc3339(), session_id, client_ip, method, host, path, target_ip, is_tls, is_rejected, tenant))
+            },
+            LogMessage::ResponseLog { session_id, status_code, response_time, response_size, timestamp, timing, upstream_cert_warning, tls_params, tenant, .. } => {
+                let timing = timing.map(|timing| format!(" {}", timing)).unwrap_or_default();
+                let cert_warning = if *upstream_cert_warning { " upstream_cert_warning=true" } else { "" };
+                let tls_params = tls_params.as_ref().map(|params| format!(" {}", params)).unwrap_or_default();
+                let tenant = tenant.as_ref().map(|tenant| format!(" tenant={}", tenant)).unwrap_or_default();
+                Some(format!("{} [Session:{}] status={} time={}ms size={}{}{}{}{}",
+                             timestamp.to_rfc3339(), session_id, status_code, response_time, response_size, timing, cert_warning, tls_params, tenant))
+            },
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl LogSink for AccessLogSink {
+    fn name(&self) -> &str {
+        if self.file.is_some() { "access_log_file" } else { "access_log_stdout" }
+    }
+
+    async fn write(&self, record: &LogMessage) -> SinkResult {
+        let Some(line) = self.format(record) else {
+            return Ok(());
+        };
+        match &self.file {
+            Some(file) => file.write_line(line),
+            None => info!(target: "access", "{}", line),
+        }
+        Ok(())
+    }
+}
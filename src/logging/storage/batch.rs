@@ -2,10 +2,19 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use chrono::{DateTime, Utc};
 
+use crate::proxy::timing::UpstreamTiming;
+use crate::tls::params::TlsSessionParams;
+
+/// 요청 로그 항목 (host, method, path, header, body, timestamp, session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category)
+pub type RequestLogEntry = (String, String, String, String, Option<String>, DateTime<Utc>, String, String, String, bool, bool, Option<String>, Option<String>);
+
+/// 응답 로그 항목 (session_id, status_code, response_time, response_size, timestamp, headers, body_preview, timing)
+pub type ResponseLogEntry = (String, u16, u64, usize, DateTime<Utc>, String, Option<String>, Option<UpstreamTiming>, Option<TlsSessionParams>);
+
 /// 요청 로그 배치
 #[derive(Default)]
 pub struct RequestLogBatch {
-    pub logs: Vec<(String, String, String, String, Option<String>, DateTime<Utc>, String, String, String, bool, bool)>,
+    pub logs: Vec<RequestLogEntry>,
     pub size: usize,
 }
 
@@ -32,6 +41,8 @@ impl RequestLogBatch {
         target_ip: String,
         is_rejected: bool,
         is_tls: bool,
+        tenant: Option<String>,
+        block_category: Option<String>,
     ) -> usize {
         // 로그 항목 크기 계산
         let item_size = header.len() + body.as_ref().map_or(0, |b| b.len());
@@ -39,7 +50,7 @@ impl RequestLogBatch {
         // 로그 추가
         self.logs.push((
             host, method, path, header, body, timestamp,
-            session_id, client_ip, target_ip, is_rejected, is_tls
+            session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category
         ));
         
         // 배치 크기 업데이트
@@ -67,7 +78,7 @@ impl RequestLogBatch {
 /// 응답 로그 배치
 #[derive(Default)]
 pub struct ResponseLogBatch {
-    pub logs: Vec<(String, u16, u64, usize, DateTime<Utc>, String, Option<String>)>,
+    pub logs: Vec<ResponseLogEntry>,
     pub size: usize,
 }
 
@@ -90,6 +101,8 @@ impl ResponseLogBatch {
         timestamp: DateTime<Utc>,
         headers: String,
         body_preview: Option<String>,
+        timing: Option<UpstreamTiming>,
+        tls_params: Option<TlsSessionParams>,
     ) -> usize {
         // 로그 항목 크기 계산
         let item_size = headers.len() + body_preview.as_ref().map_or(0, |b| b.len());
@@ -97,7 +110,7 @@ impl ResponseLogBatch {
         // 로그 추가
         self.logs.push((
             session_id, status_code, response_time, response_size,
-            timestamp, headers, body_preview
+            timestamp, headers, body_preview, timing, tls_params
         ));
         
         // 배치 크기 업데이트
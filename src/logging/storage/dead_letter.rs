@@ -0,0 +1,87 @@
+use std::error::Error;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use serde_json::{Value, json};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use super::batch::{RequestLogEntry, ResponseLogEntry};
+use crate::proxy::timing::UpstreamTiming;
+
+// 여러 로그 워커가 같은 파일에 덧붙이므로 한 번에 한 배치씩 기록
+static DEAD_LETTER_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 요청 로그 데드레터 레코드
+pub fn request_log_record(log: &RequestLogEntry) -> Value {
+    let (host, method, path, header, body, timestamp, session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category) = log;
+    json!({
+        "table": "request_logs",
+        "host": host,
+        "method": method,
+        "path": path,
+        "header": header,
+        "body": body,
+        "timestamp": timestamp.to_rfc3339(),
+        "session_id": session_id,
+        "client_ip": client_ip,
+        "target_ip": target_ip,
+        "is_rejected": is_rejected,
+        "is_tls": is_tls,
+        "tenant": tenant,
+        "block_category": block_category,
+    })
+}
+
+/// 응답 로그 데드레터 레코드
+pub fn response_log_record(log: &ResponseLogEntry) -> Value {
+    let (session_id, status_code, response_time, response_size, timestamp, headers, body_preview, timing, tls_params) = log;
+    let timing = timing.unwrap_or_default();
+    let [client_tls, client_cipher, client_alpn, upstream_tls, upstream_cipher, upstream_alpn, upstream_sni] =
+        tls_params.as_ref().map(|params| params.fields().map(|(_, value)| value)).unwrap_or_default();
+    json!({
+        "table": "response_logs",
+        "session_id": session_id,
+        "status_code": status_code,
+        "response_time": response_time,
+        "response_size": response_size,
+        "timestamp": timestamp.to_rfc3339(),
+        "headers": headers,
+        "body_preview": body_preview,
+        "dns_ms": UpstreamTiming::millis(timing.dns),
+        "connect_ms": UpstreamTiming::millis(timing.connect),
+        "tls_handshake_ms": UpstreamTiming::millis(timing.tls_handshake),
+        "first_byte_ms": UpstreamTiming::millis(timing.first_byte),
+        "client_tls_version": client_tls,
+        "client_cipher": client_cipher,
+        "client_alpn": client_alpn,
+        "upstream_tls_version": upstream_tls,
+        "upstream_cipher": upstream_cipher,
+        "upstream_alpn": upstream_alpn,
+        "upstream_sni": upstream_sni,
+    })
+}
+
+/// 저장하지 못한 로그를 데드레터 파일에 NDJSON(레코드마다 JSON 한 줄)으로 덧붙임
+pub async fn append_dead_letters(path: &str, records: &[Value]) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&record.to_string());
+        lines.push('\n');
+    }
+
+    let _guard = DEAD_LETTER_LOCK.lock().await;
+    if let Some(dir) = Path::new(path).parent()
+        && !dir.as_os_str().is_empty() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(lines.as_bytes()).await?;
+    file.sync_data().await?;
+    Ok(())
+}
@@ -1,4 +1,6 @@
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::future::Future;
 use std::time::Duration;
 use chrono::{DateTime, Utc};
 use log::{debug, error, info, warn};
@@ -6,13 +8,20 @@ use tokio::time::timeout;
 use tokio_postgres::{Transaction, types::{Type, ToSql}};
 
 use crate::constants::{
-    request_logs, response_logs, LOG_BATCH_SIZE, LOG_DB_OPERATION_TIMEOUT_MS
+    request_logs, response_logs, LOG_BATCH_SIZE, LOG_DB_OPERATION_TIMEOUT_MS, LOG_DB_RETRY_BASE_MS, LOG_DB_RETRY_MAX_MS
 };
+use crate::db::config::{DbConfig, LogWriteConfig};
 use crate::db::query::QueryExecutor;
+use crate::db::quarantine::{log_partition, quarantine_enabled, quarantine_partition, split_quarantined, DbFailure};
+use crate::metrics::Metrics;
+use crate::proxy::timing::UpstreamTiming;
+use crate::tls::params::TlsSessionParams;
 use deadpool_postgres::GenericClient;
 
 mod batch;
+mod dead_letter;
 pub use batch::*;
+use dead_letter::{append_dead_letters, request_log_record, response_log_record};
 
 /// 로그 저장소 - DB 저장 담당
 pub struct LogStorage {
@@ -72,6 +81,13 @@ impl LogStorage {
             }
             
             info!("request_logs 테이블 생성 완료");
+        } else {
+            // 테넌트 태그/차단 분류 컬럼이 추가되기 전에 만든 테이블
+            executor.execute_query(request_logs::ADD_TENANT_COLUMN, &[]).await?;
+            executor.execute_query(request_logs::ADD_BLOCK_CATEGORY_COLUMN, &[]).await?;
+            if let Err(e) = executor.execute_query(request_logs::TENANT_INDEX, &[]).await {
+                warn!("request_logs 테넌트 인덱스 생성 실패: {}", e);
+            }
         }
         
         Ok(())
@@ -102,6 +118,10 @@ impl LogStorage {
             }
             
             info!("response_logs 테이블 생성 완료");
+        } else {
+            // 구간 시간/TLS 협상 값 컬럼이 추가되기 전에 만든 테이블
+            executor.execute_query(response_logs::ADD_TIMING_COLUMNS, &[]).await?;
+            executor.execute_query(response_logs::ADD_TLS_PARAMS_COLUMNS, &[]).await?;
         }
         
         Ok(())
@@ -120,7 +140,9 @@ impl LogStorage {
         client_ip: String,
         target_ip: String,
         is_rejected: bool,
-        is_tls: bool
+        is_tls: bool,
+        tenant: Option<String>,
+        block_category: Option<String>
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         // 값 미리 복사
         let host_clone = host.clone();
@@ -145,7 +167,7 @@ impl LogStorage {
             // 로그 추가 (이미 복사된 값 사용)
             batch_guard.add_log(
                 host_clone, method_clone, path_clone, header_clone, body_clone, timestamp_clone, 
-                session_id_clone, client_ip_clone, target_ip_clone, is_rejected_clone, is_tls_clone
+                session_id_clone, client_ip_clone, target_ip_clone, is_rejected_clone, is_tls_clone, tenant, block_category
             );
         });
         
@@ -162,6 +184,8 @@ impl LogStorage {
         timestamp: DateTime<Utc>,
         headers: String,
         body_preview: Option<String>,
+        timing: Option<UpstreamTiming>,
+        tls_params: Option<TlsSessionParams>,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         // 값 미리 복사
         let session_id_clone = session_id.clone();
@@ -182,7 +206,7 @@ impl LogStorage {
             // 로그 추가 (이미 복사된 값 사용)
             batch_guard.add_log(
                 session_id_clone, status_code_clone, response_time_clone, response_size_clone, 
-                timestamp_clone, headers_clone, body_preview_clone
+                timestamp_clone, headers_clone, body_preview_clone, timing, tls_params
             );
         });
         
@@ -201,30 +225,33 @@ impl LogStorage {
         
         // 배치 복사 및 비우기
         let logs = std::mem::take(&mut batch.logs);
-        let log_count = logs.len();
         batch.clear();
         
         // 락 해제
         drop(batch);
         
-        // 타임아웃과 함께 DB에 저장
-        let result = timeout(
-            Duration::from_millis(LOG_DB_OPERATION_TIMEOUT_MS),
-            self.save_request_logs(logs)
-        ).await;
+        // 격리한 파티션으로 갈 로그는 DB에 쓰지 않고 데드레터 파일에 보존
+        let (logs, quarantined) = split_quarantined(logs, "request_logs", |log| &log.5);
+        Self::dead_letter_quarantined("요청", quarantined.iter().map(request_log_record).collect()).await;
+        if logs.is_empty() {
+            return Ok(());
+        }
+        let log_count = logs.len();
         
-        match result {
-            Ok(Ok(_)) => {
+        // 재시도를 포함해 DB에 저장하고, 끝내 실패하면 데드레터 파일에 보존
+        // (격리 정책이면 구조적인 오류는 파티션별로 나눠 실패한 파티션만 격리)
+        match Self::save_with_retry("요청", || self.save_request_logs(&logs)).await {
+            Ok(()) => {
                 debug!("{} 개의 요청 로그 저장 완료", log_count);
                 Ok(())
             },
-            Ok(Err(e)) => {
-                error!("요청 로그 저장 실패: {}", e);
-                Err(e)
+            Err(e) if quarantine_enabled() && DbFailure::classify(&*e) == DbFailure::Structural => {
+                Self::save_by_partition("요청", "request_logs", logs, |log| &log.5, request_log_record,
+                                        |group| async move { self.save_request_logs(&group).await }).await
             },
-            Err(_) => {
-                error!("요청 로그 저장 타임아웃");
-                Err("요청 로그 저장 타임아웃".into())
+            Err(e) => {
+                Self::dead_letter("요청", logs.iter().map(request_log_record).collect()).await;
+                Err(e)
             }
         }
     }
@@ -241,38 +268,152 @@ impl LogStorage {
         
         // 배치 복사 및 비우기
         let logs = std::mem::take(&mut batch.logs);
-        let log_count = logs.len();
         batch.clear();
         
         // 락 해제
         drop(batch);
         
-        // 타임아웃과 함께 DB에 저장
-        let result = timeout(
-            Duration::from_millis(LOG_DB_OPERATION_TIMEOUT_MS),
-            self.save_response_logs(logs)
-        ).await;
+        // 격리한 파티션으로 갈 로그는 DB에 쓰지 않고 데드레터 파일에 보존
+        let (logs, quarantined) = split_quarantined(logs, "response_logs", |log| &log.4);
+        Self::dead_letter_quarantined("응답", quarantined.iter().map(response_log_record).collect()).await;
+        if logs.is_empty() {
+            return Ok(());
+        }
+        let log_count = logs.len();
         
-        match result {
-            Ok(Ok(_)) => {
+        // 재시도를 포함해 DB에 저장하고, 끝내 실패하면 데드레터 파일에 보존
+        // (격리 정책이면 구조적인 오류는 파티션별로 나눠 실패한 파티션만 격리)
+        match Self::save_with_retry("응답", || self.save_response_logs(&logs)).await {
+            Ok(()) => {
                 debug!("{} 개의 응답 로그 저장 완료", log_count);
                 Ok(())
             },
-            Ok(Err(e)) => {
-                error!("응답 로그 저장 실패: {}", e);
-                Err(e)
+            Err(e) if quarantine_enabled() && DbFailure::classify(&*e) == DbFailure::Structural => {
+                Self::save_by_partition("응답", "response_logs", logs, |log| &log.4, response_log_record,
+                                        |group| async move { self.save_response_logs(&group).await }).await
             },
-            Err(_) => {
-                error!("응답 로그 저장 타임아웃");
-                Err("응답 로그 저장 타임아웃".into())
+            Err(e) => {
+                Self::dead_letter("응답", logs.iter().map(response_log_record).collect()).await;
+                Err(e)
+            }
+        }
+    }
+    
+    /// 배치 저장 (실패/타임아웃 시 db.yml의 log_write.retries만큼 짧게 대기 후 재시도)
+    async fn save_with_retry<F, Fut>(kind: &str, mut save: F) -> Result<(), Box<dyn Error + Send + Sync>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<(), Box<dyn Error + Send + Sync>>>,
+    {
+        let retries = DbConfig::get().map(|config| config.log_write.retries).unwrap_or_default();
+        // 격리 정책이면 구조적인 오류는 재시도해도 실패하므로 바로 반환
+        let quarantine = quarantine_enabled();
+        let mut attempt = 0;
+        
+        loop {
+            match Self::save_once(kind, save()).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < retries && !(quarantine && DbFailure::classify(&*e) == DbFailure::Structural) => {
+                    attempt += 1;
+                    warn!("{} 로그 저장 실패, 재시도 {}/{}: {}", kind, attempt, retries, e);
+                    tokio::time::sleep(retry_delay(attempt)).await;
+                },
+                Err(e) => {
+                    error!("{} 로그 저장 실패: {}", kind, e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+    
+    /// 배치 한 번 저장 (타임아웃 포함)
+    async fn save_once(kind: &str, save: impl Future<Output = Result<(), Box<dyn Error + Send + Sync>>>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match timeout(Duration::from_millis(LOG_DB_OPERATION_TIMEOUT_MS), save).await {
+            Ok(result) => result,
+            Err(_) => Err(format!("{} 로그 저장 타임아웃", kind).into()),
+        }
+    }
+    
+    /// 구조적인 오류로 실패한 배치를 파티션별로 나눠 한 번씩 다시 저장
+    ///
+    /// 다시 구조적인 오류로 실패한 파티션은 격리하고 그 로그는 기본 파티션(우회한 경우)이나 데드레터 파일에 기록합니다.
+    /// 일시적인 오류로 실패한 파티션의 로그는 데드레터 파일에 기록하고 그 오류를 반환합니다.
+    async fn save_by_partition<T, F, Fut>(
+        kind: &str,
+        table: &str,
+        logs: Vec<T>,
+        timestamp: impl Fn(&T) -> &DateTime<Utc>,
+        record: impl Fn(&T) -> serde_json::Value,
+        mut save: F,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>
+    where
+        T: Clone,
+        F: FnMut(Vec<T>) -> Fut,
+        Fut: Future<Output = Result<(), Box<dyn Error + Send + Sync>>>,
+    {
+        let mut partitions: BTreeMap<String, Vec<T>> = BTreeMap::new();
+        for log in logs {
+            partitions.entry(log_partition(table, timestamp(&log))).or_default().push(log);
+        }
+        
+        let mut transient = None;
+        for (partition, group) in partitions {
+            let e = match Self::save_once(kind, save(group.clone())).await {
+                Ok(()) => {
+                    debug!("{} 로그 {}건 저장 완료 (파티션 {})", kind, group.len(), partition);
+                    continue;
+                },
+                Err(e) => e,
+            };
+            let records = group.iter().map(&record).collect();
+            if DbFailure::classify(&*e) == DbFailure::Transient {
+                error!("{} 로그 저장 실패 (파티션 {}): {}", kind, partition, e);
+                Self::dead_letter(kind, records).await;
+                transient = Some(e);
+                continue;
             }
+            
+            // 파티션 격리 후 기본 파티션으로 우회했으면 한 번 더 저장
+            if quarantine_partition(table, &partition, &e.to_string()).await {
+                match Self::save_once(kind, save(group)).await {
+                    Ok(()) => continue,
+                    Err(e) => error!("{} 로그 기본 파티션 저장 실패 (파티션 {}): {}", kind, partition, e),
+                }
+            }
+            Self::dead_letter_quarantined(kind, records).await;
+        }
+        
+        match transient {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+    
+    /// 격리한 파티션으로 갈 로그를 데드레터 파일에 기록
+    async fn dead_letter_quarantined(kind: &str, records: Vec<serde_json::Value>) {
+        if records.is_empty() {
+            return;
+        }
+        Metrics::new().db_quarantined_logs(records.len());
+        Self::dead_letter(kind, records).await;
+    }
+    
+    /// 저장하지 못한 로그를 데드레터 파일에 기록
+    async fn dead_letter(kind: &str, records: Vec<serde_json::Value>) {
+        let path = DbConfig::get()
+            .map(|config| config.log_write.dead_letter_path)
+            .unwrap_or_else(|_| LogWriteConfig::default().dead_letter_path);
+        
+        match append_dead_letters(&path, &records).await {
+            Ok(()) => warn!("{} 로그 {}건을 데드레터 파일에 기록했습니다: {}", kind, records.len(), path),
+            Err(e) => error!("{} 로그 {}건 데드레터 기록 실패 ({}): {}", kind, records.len(), path, e),
         }
     }
     
     /// 요청 로그 저장
     async fn save_request_logs(
         &self, 
-        logs: Vec<(String, String, String, String, Option<String>, DateTime<Utc>, String, String, String, bool, bool)>
+        logs: &[RequestLogEntry]
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         if logs.is_empty() {
             return Ok(());
@@ -288,10 +429,10 @@ impl LogStorage {
         // 배치 크기에 따라 다른 방식으로 저장
         if logs.len() >= LOG_BATCH_SIZE {
             // 대량 배치 - 복사 모드 사용
-            self.save_request_logs_batch(&tx, &logs).await?;
+            self.save_request_logs_batch(&tx, logs).await?;
         } else {
             // 소량 배치 - 개별 삽입
-            self.save_request_logs_individually(&tx, &logs).await?;
+            self.save_request_logs_individually(&tx, logs).await?;
         }
         
         // 트랜잭션 커밋
@@ -304,15 +445,15 @@ impl LogStorage {
     async fn save_request_logs_individually(
         &self,
         tx: &Transaction<'_>, // Changed from client: &Client
-        logs: &[(String, String, String, String, Option<String>, DateTime<Utc>, String, String, String, bool, bool)]
+        logs: &[RequestLogEntry]
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        for (host, method, path, header, body, timestamp, session_id, client_ip, target_ip, is_rejected, is_tls) in logs {
+        for (host, method, path, header, body, timestamp, session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category) in logs {
             // 개별 로그 저장
             tx.execute( // Changed from client.execute
                 request_logs::INSERT_LOG,
                 &[
                     &host, &method, &path, &header, &body, &timestamp, 
-                    &session_id, &client_ip, &target_ip, &is_rejected, &is_tls
+                    &session_id, &client_ip, &target_ip, &is_rejected, &is_tls, &tenant, &block_category
                 ]
             ).await?;
         }
@@ -324,7 +465,7 @@ impl LogStorage {
     async fn save_request_logs_batch(
         &self,
         tx: &Transaction<'_>,
-        logs: &[(String, String, String, String, Option<String>, DateTime<Utc>, String, String, String, bool, bool)]
+        logs: &[RequestLogEntry]
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         let copy_stmt = tx.prepare(request_logs::COPY_LOGS).await?;
         let sink = tx.copy_in(&copy_stmt).await?;
@@ -332,12 +473,12 @@ impl LogStorage {
         let types = &[
             Type::TEXT, Type::TEXT, Type::TEXT, Type::TEXT, Type::TEXT,
             Type::TIMESTAMPTZ, Type::TEXT, Type::TEXT, Type::TEXT,
-            Type::BOOL, Type::BOOL
+            Type::BOOL, Type::BOOL, Type::TEXT, Type::TEXT
         ];
         let writer = tokio_postgres::binary_copy::BinaryCopyInWriter::new(sink, types);
         let mut writer = std::pin::pin!(writer);
         
-        for (host, method, path, header, body, timestamp, session_id, client_ip, target_ip, is_rejected, is_tls) in logs {
+        for (host, method, path, header, body, timestamp, session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category) in logs {
             writer.as_mut().write(&[
                 host as &(dyn ToSql + Sync), 
                 method as &(dyn ToSql + Sync), 
@@ -349,7 +490,9 @@ impl LogStorage {
                 client_ip as &(dyn ToSql + Sync), 
                 target_ip as &(dyn ToSql + Sync), 
                 is_rejected as &(dyn ToSql + Sync), 
-                is_tls as &(dyn ToSql + Sync)
+                is_tls as &(dyn ToSql + Sync),
+                &tenant.as_deref() as &(dyn ToSql + Sync),
+                &block_category.as_deref() as &(dyn ToSql + Sync)
             ]).await?;
         }
         
@@ -361,7 +504,7 @@ impl LogStorage {
     /// 응답 로그 저장
     async fn save_response_logs(
         &self,
-        logs: Vec<(String, u16, u64, usize, DateTime<Utc>, String, Option<String>)> 
+        logs: &[ResponseLogEntry]
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         if logs.is_empty() {
             return Ok(());
@@ -377,10 +520,10 @@ impl LogStorage {
         // 배치 크기에 따라 다른 방식으로 저장
         if logs.len() >= LOG_BATCH_SIZE {
             // 대량 배치 - 복사 모드 사용
-            self.save_response_logs_batch(&tx, &logs).await?;
+            self.save_response_logs_batch(&tx, logs).await?;
         } else {
             // 소량 배치 - 개별 삽입
-            self.save_response_logs_individually(&tx, &logs).await?;
+            self.save_response_logs_individually(&tx, logs).await?;
         }
         
         // 트랜잭션 커밋
@@ -393,15 +536,22 @@ impl LogStorage {
     async fn save_response_logs_individually(
         &self,
         tx: &Transaction<'_>, // Changed from client: &Client
-        logs: &[(String, u16, u64, usize, DateTime<Utc>, String, Option<String>)]
+        logs: &[ResponseLogEntry]
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        for (session_id, status_code, response_time, response_size, timestamp, headers, body_preview) in logs {
+        for (session_id, status_code, response_time, response_size, timestamp, headers, body_preview, timing, tls_params) in logs {
+            let timing = timing.unwrap_or_default();
+            let [client_tls, client_cipher, client_alpn, upstream_tls, upstream_cipher, upstream_alpn, upstream_sni] =
+                tls_params.as_ref().map(|params| params.fields().map(|(_, value)| value)).unwrap_or_default();
             // 개별 로그 저장
             tx.execute( // Changed from client.execute
                 response_logs::INSERT_LOG,
                 &[
                     &session_id, &(*status_code as i32), &(*response_time as i64), &(*response_size as i64),
-                    &timestamp, &headers, &body_preview
+                    &timestamp, &headers, &body_preview,
+                    &UpstreamTiming::millis(timing.dns), &UpstreamTiming::millis(timing.connect),
+                    &UpstreamTiming::millis(timing.tls_handshake), &UpstreamTiming::millis(timing.first_byte),
+                    &client_tls, &client_cipher, &client_alpn,
+                    &upstream_tls, &upstream_cipher, &upstream_alpn, &upstream_sni
                 ]
             ).await?;
         }
@@ -413,7 +563,7 @@ impl LogStorage {
     async fn save_response_logs_batch(
         &self,
         tx: &Transaction<'_>, // Changed from client: &Client
-        logs: &[(String, u16, u64, usize, DateTime<Utc>, String, Option<String>)]
+        logs: &[ResponseLogEntry]
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         // 복사 모드 시작
         let copy_stmt = tx.prepare(response_logs::COPY_LOGS).await?; // Changed from client.prepare
@@ -426,17 +576,33 @@ impl LogStorage {
             Type::INT8,        // response_size (usize -> i64 for BIGINT)
             Type::TIMESTAMPTZ, // timestamp
             Type::TEXT,        // headers
-            Type::TEXT         // body_preview (Option<String>)
+            Type::TEXT,        // body_preview (Option<String>)
+            Type::INT8,        // dns_ms (Option<i64>)
+            Type::INT8,        // connect_ms
+            Type::INT8,        // tls_handshake_ms
+            Type::INT8,        // first_byte_ms
+            Type::TEXT,        // client_tls_version (Option<&str>)
+            Type::TEXT,        // client_cipher
+            Type::TEXT,        // client_alpn
+            Type::TEXT,        // upstream_tls_version
+            Type::TEXT,        // upstream_cipher
+            Type::TEXT,        // upstream_alpn
+            Type::TEXT         // upstream_sni
         ];
         let writer = tokio_postgres::binary_copy::BinaryCopyInWriter::new(sink, types);
         let mut writer = std::pin::pin!(writer);
         
         // 로그 데이터 쓰기
-        for (session_id, status_code, response_time, response_size, timestamp, headers, body_preview) in logs {
+        for (session_id, status_code, response_time, response_size, timestamp, headers, body_preview, timing, tls_params) in logs {
             // 각 필드 쓰기
             let status_code_i16 = *status_code as i16;
             let response_time_i64 = *response_time as i64;
             let response_size_i64 = *response_size as i64;
+            let timing = timing.unwrap_or_default();
+            let (dns_ms, connect_ms) = (UpstreamTiming::millis(timing.dns), UpstreamTiming::millis(timing.connect));
+            let (tls_handshake_ms, first_byte_ms) = (UpstreamTiming::millis(timing.tls_handshake), UpstreamTiming::millis(timing.first_byte));
+            let [client_tls, client_cipher, client_alpn, upstream_tls, upstream_cipher, upstream_alpn, upstream_sni] =
+                tls_params.as_ref().map(|params| params.fields().map(|(_, value)| value)).unwrap_or_default();
             
             writer.as_mut().write(&[
                 session_id as &(dyn ToSql + Sync),
@@ -445,7 +611,18 @@ impl LogStorage {
                 &response_size_i64 as &(dyn ToSql + Sync),
                 timestamp as &(dyn ToSql + Sync),
                 headers as &(dyn ToSql + Sync),
-                &body_preview.as_deref() as &(dyn ToSql + Sync)
+                &body_preview.as_deref() as &(dyn ToSql + Sync),
+                &dns_ms as &(dyn ToSql + Sync),
+                &connect_ms as &(dyn ToSql + Sync),
+                &tls_handshake_ms as &(dyn ToSql + Sync),
+                &first_byte_ms as &(dyn ToSql + Sync),
+                &client_tls as &(dyn ToSql + Sync),
+                &client_cipher as &(dyn ToSql + Sync),
+                &client_alpn as &(dyn ToSql + Sync),
+                &upstream_tls as &(dyn ToSql + Sync),
+                &upstream_cipher as &(dyn ToSql + Sync),
+                &upstream_alpn as &(dyn ToSql + Sync),
+                &upstream_sni as &(dyn ToSql + Sync)
             ]).await?;
         }
         
@@ -490,4 +667,23 @@ impl LogStorage {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// 재시도 대기 시간 (시도마다 두 배, LOG_DB_RETRY_MAX_MS 상한)
+fn retry_delay(attempt: u32) -> Duration {
+    let shift = attempt.saturating_sub(1).min(16);
+    Duration::from_millis((LOG_DB_RETRY_BASE_MS << shift).min(LOG_DB_RETRY_MAX_MS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_is_capped() {
+        assert_eq!(retry_delay(1), Duration::from_millis(LOG_DB_RETRY_BASE_MS));
+        assert_eq!(retry_delay(2), Duration::from_millis(LOG_DB_RETRY_BASE_MS * 2));
+        assert_eq!(retry_delay(64), Duration::from_millis(LOG_DB_RETRY_MAX_MS));
+        assert_eq!(retry_delay(u32::MAX), Duration::from_millis(LOG_DB_RETRY_MAX_MS));
+    }
+}
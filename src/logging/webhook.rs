@@ -0,0 +1,283 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::{debug, info, warn};
+use serde::Serialize;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+
+use crate::config::{Config, WebhookConfig};
+use crate::constants::*;
+use crate::metrics::Metrics;
+use crate::proxy::target::{dial_address, format_authority, parse_authority};
+use crate::tls::connect_tls;
+
+/// 웹훅으로 전송 가능한 이벤트 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEventKind {
+    /// 차단된 요청
+    BlockedRequest,
+    /// 업스트림 연결/핸드셰이크 실패
+    UpstreamFailure,
+}
+
+impl WebhookEventKind {
+    /// 설정 파일에서 사용하는 이벤트 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEventKind::BlockedRequest => "blocked_request",
+            WebhookEventKind::UpstreamFailure => "upstream_failure",
+        }
+    }
+
+    /// 이벤트 이름으로 종류 찾기
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "blocked_request" => Some(WebhookEventKind::BlockedRequest),
+            "upstream_failure" => Some(WebhookEventKind::UpstreamFailure),
+            _ => None,
+        }
+    }
+}
+
+/// 웹훅 JSON 페이로드
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookEvent {
+    pub event: &'static str,
+    pub timestamp: DateTime<Utc>,
+    pub session_id: String,
+    pub client_ip: String,
+    pub host: String,
+    pub detail: String,
+}
+
+impl WebhookEvent {
+    pub fn new(
+        kind: WebhookEventKind,
+        session_id: impl Into<String>,
+        client_ip: impl Into<String>,
+        host: impl Into<String>,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            event: kind.name(),
+            timestamp: Utc::now(),
+            session_id: session_id.into(),
+            client_ip: client_ip.into(),
+            host: host.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+// 전송 워커의 대기열과 대기열이 가득 차 버리는 중인지 (경고를 한 번만 남기기 위함)
+struct Endpoint {
+    webhook: WebhookConfig,
+    sender: mpsc::Sender<Arc<String>>,
+    dropping: bool,
+}
+
+/// 웹훅 이벤트 전송기
+///
+/// 세션 경로에서는 큐에 넣기만 하고(가득 차면 버림) 실제 전송은 백그라운드 태스크가 담당합니다.
+/// 엔드포인트마다 전송 워커 하나와 전용 큐를 두어, 느리거나 죽은 엔드포인트는 자기 큐만 채우고
+/// (가득 차면 그 엔드포인트로 갈 이벤트를 버림) 다른 엔드포인트 전송이나 메모리 사용량에 영향을 주지 않습니다.
+pub struct WebhookNotifier {
+    sender: mpsc::Sender<WebhookEvent>,
+    metrics: Arc<Metrics>,
+}
+
+impl WebhookNotifier {
+    /// 설정된 웹훅이 있으면 전송기를 생성하고 백그라운드 전송 태스크 시작
+    pub fn new(config: Arc<Config>) -> Option<Self> {
+        if config.webhooks.is_empty() {
+            return None;
+        }
+
+        let (sender, receiver) = mpsc::channel(WEBHOOK_QUEUE_SIZE);
+        let metrics = Metrics::new();
+
+        info!("이벤트 웹훅 활성화: {} 개 엔드포인트", config.webhooks.len());
+        tokio::spawn(Self::dispatch_loop(receiver, config, Arc::clone(&metrics)));
+
+        Some(Self { sender, metrics })
+    }
+
+    /// 이벤트 전송 요청 (블로킹 없음)
+    pub fn notify(&self, event: WebhookEvent) {
+        if let Err(e) = self.sender.try_send(event) {
+            warn!("웹훅 이벤트 큐 적재 실패, 이벤트를 버립니다: {}", e);
+            self.metrics.webhook_dropped();
+        }
+    }
+
+    /// 큐에서 이벤트를 꺼내 대상 엔드포인트의 전송 큐에 넣음 (엔드포인트 큐가 가득 차면 버림)
+    async fn dispatch_loop(mut receiver: mpsc::Receiver<WebhookEvent>, config: Arc<Config>, metrics: Arc<Metrics>) {
+        let mut endpoints: Vec<Endpoint> = config.webhooks.iter().map(|webhook| {
+            let (sender, queue) = mpsc::channel(WEBHOOK_ENDPOINT_QUEUE_SIZE);
+            tokio::spawn(Self::endpoint_worker(webhook.clone(), queue, Arc::clone(&config), Arc::clone(&metrics)));
+            Endpoint { webhook: webhook.clone(), sender, dropping: false }
+        }).collect();
+
+        while let Some(event) = receiver.recv().await {
+            let payload = match serde_json::to_string(&event) {
+                Ok(payload) => Arc::new(payload),
+                Err(e) => {
+                    warn!("웹훅 페이로드 직렬화 실패: {}", e);
+                    metrics.webhook_failed();
+                    continue;
+                }
+            };
+
+            for endpoint in endpoints.iter_mut().filter(|endpoint| Self::accepts(&endpoint.webhook, event.event)) {
+                match endpoint.sender.try_send(Arc::clone(&payload)) {
+                    Ok(()) => {
+                        if endpoint.dropping {
+                            info!("웹훅 전송 대기열 회복: {}", endpoint.webhook.url);
+                            endpoint.dropping = false;
+                        }
+                    },
+                    Err(_) => {
+                        let dropped = metrics.webhook_dropped();
+                        // 대기열이 찬 동안에는 처음 한 번만 경고
+                        if !endpoint.dropping {
+                            warn!("웹훅 전송 대기열이 가득 차 이벤트를 버립니다: {} (대기 {} 개, 누적 버림 {})",
+                                  endpoint.webhook.url, WEBHOOK_ENDPOINT_QUEUE_SIZE, dropped);
+                            endpoint.dropping = true;
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    /// 엔드포인트 하나의 전송 워커 (대기열의 이벤트를 순서대로 전송)
+    async fn endpoint_worker(webhook: WebhookConfig, mut queue: mpsc::Receiver<Arc<String>>, config: Arc<Config>, metrics: Arc<Metrics>) {
+        while let Some(payload) = queue.recv().await {
+            Self::deliver(&webhook, &payload, &config, &metrics).await;
+        }
+    }
+
+    /// 엔드포인트의 이벤트 필터 확인
+    fn accepts(webhook: &WebhookConfig, event: &str) -> bool {
+        webhook.events.is_empty() || webhook.events.iter().any(|e| e == event)
+    }
+
+    /// 재시도를 포함한 단일 엔드포인트 전송
+    async fn deliver(webhook: &WebhookConfig, payload: &str, config: &Config, metrics: &Metrics) {
+        for attempt in 0..=WEBHOOK_MAX_RETRIES {
+            if attempt > 0 {
+                let delay = WEBHOOK_RETRY_BASE_MS * (1 << (attempt - 1));
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+
+            let result = tokio::time::timeout(
+                Duration::from_millis(WEBHOOK_TIMEOUT_MS),
+                post_json(&webhook.url, payload, config),
+            ).await;
+
+            match result {
+                Ok(Ok(status)) if (200..300).contains(&status) => {
+                    debug!("웹훅 전송 성공: {} (상태 코드 {})", webhook.url, status);
+                    metrics.webhook_delivered();
+                    return;
+                },
+                Ok(Ok(status)) => warn!("웹훅 전송 실패: {} (상태 코드 {}, 시도 {})", webhook.url, status, attempt + 1),
+                Ok(Err(e)) => warn!("웹훅 전송 실패: {} ({}, 시도 {})", webhook.url, e, attempt + 1),
+                Err(_) => warn!("웹훅 전송 타임아웃: {} (시도 {})", webhook.url, attempt + 1),
+            }
+        }
+
+        metrics.webhook_failed();
+    }
+}
+
+/// URL에 JSON 본문을 POST하고 응답 상태 코드 반환
+async fn post_json(url: &str, payload: &str, config: &Config) -> Result<u16, Box<dyn Error + Send + Sync>> {
+    let (is_tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+        (true, rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (false, rest)
+    } else {
+        return Err(format!("지원하지 않는 웹훅 URL: {}", url).into());
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = parse_authority(authority, if is_tls { 443 } else { 80 })
+        .ok_or_else(|| format!("잘못된 웹훅 URL 호스트: {}", authority))?;
+
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path, authority, payload.len(), payload
+    );
+
+    if is_tls {
+        let stream = connect_tls(&format_authority(&host, port), config).await?;
+        send_request(stream, &request).await
+    } else {
+        let stream = TcpStream::connect(dial_address(&host, port)).await?;
+        send_request(stream, &request).await
+    }
+}
+
+/// 요청을 보내고 응답 상태 줄에서 상태 코드 파싱
+async fn send_request<S>(mut stream: S, request: &str) -> Result<u16, Box<dyn Error + Send + Sync>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut buffer = [0u8; 1024];
+    let n = stream.read(&mut buffer).await?;
+    let response = String::from_utf8_lossy(&buffer[..n]);
+
+    response
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| "웹훅 응답 상태 줄 파싱 실패".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn dropped() -> u64 {
+        Metrics::new().get("webhook_dropped")
+    }
+
+    #[tokio::test]
+    async fn stalled_endpoint_queue_is_bounded() {
+        crate::db::config::DbConfig::disable();
+        // 연결은 받지만 응답하지 않는 엔드포인트
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut config = Config::new();
+        config.webhooks = vec![WebhookConfig { url: format!("http://{}/hook", listener.local_addr().unwrap()), events: Vec::new() }];
+        let notifier = WebhookNotifier::new(Arc::new(config)).unwrap();
+
+        let before = dropped();
+        let extra = 50;
+        for n in 0..WEBHOOK_ENDPOINT_QUEUE_SIZE + extra {
+            notifier.notify(WebhookEvent::new(WebhookEventKind::BlockedRequest, format!("test-{}", n), "127.0.0.1", "blocked.test", "GET /"));
+        }
+
+        // 워커가 하나를 꺼내 전송 중이면 extra - 1, 아니면 extra 개를 버림
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while dropped() - before < extra as u64 - 1 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let count = dropped() - before;
+        assert!(count == extra as u64 - 1 || count == extra as u64, "버린 이벤트 {}", count);
+        drop(listener);
+    }
+}
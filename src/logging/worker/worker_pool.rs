@@ -169,12 +169,12 @@ impl WorkerPool {
         match message {
             LogMessage::RequestLog { 
                 host, method, path, header, body, timestamp, 
-                session_id, client_ip, target_ip, is_rejected, is_tls, ..
+                session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category, ..
             } => {
                 // 요청 로그 처리
                 storage.add_request_log(
                     host, method, path, header, body, timestamp, 
-                    session_id, client_ip, target_ip, is_rejected, is_tls
+                    session_id, client_ip, target_ip, is_rejected, is_tls, tenant, block_category
                 )?;
                 
                 // 배치 크기 확인 및 플러시
@@ -187,12 +187,12 @@ impl WorkerPool {
             
             LogMessage::ResponseLog { 
                 session_id, status_code, response_time, response_size, 
-                timestamp, headers, body_preview, ..
+                timestamp, headers, body_preview, timing, tls_params, ..
             } => {
                 // 응답 로그 처리
                 storage.add_response_log(
                     session_id.clone(), status_code, response_time, response_size, 
-                    timestamp, headers, body_preview
+                    timestamp, headers, body_preview, timing, tls_params
                 )?;
                 
                 // 배치 크기 확인 및 플러시
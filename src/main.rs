@@ -18,18 +18,41 @@ mod acl;
 mod db;
 mod logging;
 mod error;
+#[cfg(feature = "self-test")]
+mod harness;
+mod admin;
 
 use error::{ProxyError, Result, config_err, db_err, internal_err};
 
-use config::Config;
+use config::{Config, ConfigFormat, STDIN_CONFIG_PATH};
 use metrics::Metrics;
 use buffer::BufferPool;
 use constants::*;
-use server::ProxyServer;
-use tls::init_root_ca;
+use server::{ProxyServer, ShutdownKind};
+use server::fd_reserve::set_fd_reserve;
+use server::source_filter::set_source_filter;
+use tls::{init_root_ca, set_cert_cache_alert, set_leaf_cert_template, set_tls_resumption, set_wildcard_certs};
 use tls::load_trusted_certificates;
+use tls::pkcs12::set_pkcs12_passphrase;
 use logging::Logger;
+use logging::recent::{RecentLogCapture, init_recent_logs};
+use proxy::pool::init_upstream_pool;
+use proxy::warm::init_warm_pool;
+use proxy::error_page::init_error_pages;
+use tls::exemption::init_tls_exemptions;
+use proxy::breaker::init_circuit_breaker;
+use proxy::parents::init_parent_proxies;
+use proxy::host_limit::init_host_limiter;
+use proxy::sockbuf::check_socket_buffers;
+use proxy::overload::init_overload_guard;
+use proxy::maintenance::init_maintenance;
+use metrics::influx::start_influx_exporter;
 use acl::domain_blocker::DomainBlocker;
+use acl::connect_acl::init_connect_acl;
+use admin::{start_admin_server, spawn_sighup_reload};
+use admin::ca::start_ca_download_server;
+use admin::reload::Reloader;
+use admin::version::VersionInfo;
 use db::config::DbConfig;
 
 // 파일 디스크립터 제한 설정
@@ -47,13 +70,54 @@ async fn main() -> Result<()> {
     
     // 시스템 리소스 제한 설정
     setup_resource_limits();
+    
+    // 자체 점검 모드: 임시 포트의 프록시를 통해 CONNECT 왕복 확인 후 종료
+    if std::env::args().any(|arg| arg == "--self-test") {
+        return run_self_test().await;
+    }
+    
+    // 환경 변수 덮어쓰기 목록 출력: 설정 필드에서 만든 UDSS_* 변수 이름과 형식
+    if std::env::args().any(|arg| arg == "--list-env") {
+        print_env_overrides();
+        return Ok(());
+    }
+    
+    // 부하 측정 모드: --bench [--concurrency N] [--request-size BYTES] [--duration SECS] [--per-connection N] [--plain] ...
+    if std::env::args().any(|arg| arg == "--bench") {
+        return run_bench().await;
+    }
+    
+    // 파티션 내보내기 모드: --export-partition <name> <path> [--drop]
+    if let Some(export_args) = parse_export_args() {
+        return run_partition_export(export_args).await;
+    }
+    
+    // 차단 요청 보고서 모드: --blocked-report <from> <to> [--group-by category,client] [--format csv|json] ...
+    if std::env::args().any(|arg| arg == "--blocked-report") {
+        return run_blocked_report().await;
+    }
+
+    // 프록시 실행 (정상 종료든 오류든 종료 보고를 남기고, 강제 종료면 종료 코드 130)
+    let result = run_proxy().await;
+    report_shutdown(&result).await;
+    match result {
+        Ok(ShutdownKind::Forced) => std::process::exit(130),
+        result => result.map(|_| ()),
+    }
+}
 
+/// 설정을 로드하고 프록시 서버 실행 (종료 시그널을 받으면 종료 방식 반환)
+async fn run_proxy() -> Result<ShutdownKind> {
     info!("udss-proxy 서버 시작 중...");
     let num_cpus = num_cpus::get();
     info!("시스템 코어 수: {}", num_cpus);
 
     // 프록시 설정 로드
     let mut config = load_config()?;
+    config.validate().map_err(config_err)?;
+    
+    // 최근 로그 링 버퍼 (GET /debug/log)
+    init_recent_logs(config.debug_log_buffer_size);
     
     // 데이터베이스 설정 로드 및 초기화
     setup_database().await?;
@@ -61,8 +125,11 @@ async fn main() -> Result<()> {
     // SSL 디렉토리 확인 및 생성
     ensure_ssl_directories(&config)?;
     
+    // PKCS#12 번들 암호 (루트 CA와 신뢰할 인증서 번들에 사용)
+    set_pkcs12_passphrase(config.pkcs12.resolve_passphrase().map_err(config_err)?);
+    
     // TLS 루트 CA 인증서 초기화
-    if let Err(e) = init_root_ca() {
+    if let Err(e) = init_root_ca(&config.root_ca) {
         error!("루트 CA 초기화 실패: {}", e);
     } else {
         info!("루트 CA 초기화 성공");
@@ -73,6 +140,34 @@ async fn main() -> Result<()> {
         error!("신뢰할 인증서 로드 실패: {}", e);
     }
     
+    // 업스트림 이름 해석 주소 체계 (실제 적용되는 설정을 항상 기록)
+    info!("업스트림 이름 해석 주소 체계: {}", config.upstream_ip_family.name());
+    
+    // 오류 응답 본문 템플릿 (차단/과부하/업스트림 실패)
+    init_error_pages(&config.error_pages).map_err(config_err)?;
+    
+    // 리프 인증서 subject/SAN 템플릿 적용
+    set_leaf_cert_template(config.leaf_cert.clone());
+    
+    // 하위 도메인용 와일드카드 리프 인증서 설정
+    set_wildcard_certs(&config.wildcard_certs);
+    
+    // 인증서 캐시 미스율 구간과 경고 임계값 설정
+    set_cert_cache_alert(&config.cert_cache_alert);
+    
+    // 클라이언트 측 TLS 세션 재개 설정
+    set_tls_resumption(&config.tls_resumption)?;
+    
+    // 프록시 리스너 출발지 IP 필터 (재로드 시 교체)
+    set_source_filter(&config.source_filter);
+    set_fd_reserve(config.overload.min_free_fds);
+    
+    // 최종 적용된 설정 출력 (디버그 빌드 또는 --dump-config)
+    dump_effective_config(&config);
+    
+    // 빌드/설정 식별 정보 (인스턴스 간 같은 빌드와 설정인지 확인용)
+    info!("{}", VersionInfo::new(&config));
+    
     // config를 Arc로 감싸서 공유 가능하게 함
     let config = Arc::new(config);
     
@@ -84,7 +179,7 @@ async fn main() -> Result<()> {
     info!("버퍼 풀 초기화: 소형 {}, 중형 {}, 대형 {}", SMALL_POOL_SIZE, MEDIUM_POOL_SIZE, LARGE_POOL_SIZE);
 
     // Logger 인스턴스 생성
-    let mut logger = Logger::new();
+    let mut logger = Logger::new().with_webhooks(config.clone()).with_access_log(&config);
     // 비동기 초기화 수행
     match logger.init().await {
         Ok(_) => info!("로거 초기화 완료"),
@@ -93,31 +188,92 @@ async fn main() -> Result<()> {
     // Arc로 감싸서 공유 가능하게 함
     let logger = Arc::new(logger);
     
+    // 업스트림 연결 풀 초기화 (upstream_pool_enabled인 경우)
+    init_upstream_pool(&config);
+    
+    // 고정 업스트림 미리 연결 풀 초기화 (warm_pool.upstreams가 지정된 경우)
+    init_warm_pool(&config);
+    
+    // 업스트림 회로 차단기 초기화 (circuit_breaker_enabled인 경우)
+    init_circuit_breaker(&config);
+    
+    // 상위 프록시 분산과 상태 추적 초기화 (parent_proxies.proxies가 지정된 경우)
+    init_parent_proxies(&config);
+    
+    // 업스트림 호스트별 동시 연결 제한 초기화 (upstream_host_limit 설정 시)
+    init_host_limiter(&config);
+    
+    // 클라이언트/업스트림 소켓 버퍼 크기 확인 (범위 밖 값은 경고 후 조정해 적용)
+    check_socket_buffers(&config);
+    
+    // 전체 동시 세션/초당 요청 수 한도 초기화 (overload 설정 시)
+    init_overload_guard(&config);
+    
+    // CONNECT 접근 제어 규칙 초기화 (connect_acl 설정 시)
+    init_connect_acl(&config);
+    
+    // 점검 페이지 로드, maintenance.enabled이면 점검 모드로 시작
+    init_maintenance(&config.maintenance).map_err(config_err)?;
+    
+    // InfluxDB 라인 프로토콜 메트릭 전송 시작 (influx.collector 설정 시)
+    start_influx_exporter(&config);
+    
     // 워커 스레드 설정
     let worker_threads = config.worker_threads.unwrap_or_else(|| num_cpus);
     
     // DomainBlocker 인스턴스 생성 (config는 이미 Arc<Config> 타입)
     let domain_blocker = Arc::new(DomainBlocker::new(config.clone()));
     
+    // TLS 가로채기 제외 목록 초기화 (DB 활성화 시 주기적 갱신)
+    init_tls_exemptions(&config).await;
+    
     // DomainBlocker 초기화 (비동기 초기화 메서드 명시적 호출)
     match domain_blocker.initialize().await {
         Ok(_) => info!("도메인 차단기 초기화 완료"),
         Err(e) => error!("도메인 차단기 초기화 실패: {}", e)
     }
+    
+    // 설정 재로드 (SIGHUP, admin_bind 지정 시 POST /reload)
+    let reloader = Arc::new(Reloader::new(&config, load_config, domain_blocker.clone()));
+    spawn_sighup_reload(reloader.clone());
+    start_admin_server(&config, reloader).await?;
+    start_ca_download_server(&config).await?;
 
     info!("워커 스레드 수: {}", worker_threads);
 
     // 프록시 서버 시작
     let server = ProxyServer::new(config, metrics, Some(buffer_pool), logger.clone(), domain_blocker);
-    server.run().await?;
+    server.run().await
+}
 
-    Ok(())
+/// 종료 보고서를 로그와 DB(사용 가능한 경우)에 기록
+async fn report_shutdown(result: &Result<ShutdownKind>) {
+    let exit_reason = match result {
+        Ok(ShutdownKind::Graceful) => "shutdown signal".to_string(),
+        Ok(ShutdownKind::Forced) => "forced shutdown (second SIGINT)".to_string(),
+        Err(e) => format!("error: {}", e),
+    };
+    
+    let metrics = Metrics::new();
+    let report = metrics.shutdown_report(exit_reason);
+    report.log();
+    metrics.log_acl_stats();
+    metrics.log_session_close_stats();
+    metrics.log_session_bytes();
+    metrics.log_upstream_latency();
+    metrics.log_tenant_stats();
+    
+    match tokio::time::timeout(std::time::Duration::from_secs(3), report.save_to_db()).await {
+        Ok(Ok(())) => {},
+        Ok(Err(e)) => warn!("종료 이벤트 DB 기록 실패: {}", e),
+        Err(_) => warn!("종료 이벤트 DB 기록 타임아웃"),
+    }
 }
 
-/// 로거 설정
+/// 로거 설정 (표준 출력 로거를 최근 로그 링 버퍼 기록기로 감싸 설치)
 fn setup_logger() {
     #[cfg(debug_assertions)]
-    {
+    let logger = {
         Builder::new()
             .filter(None, LevelFilter::Trace)
             .format(|buf, record| {
@@ -131,15 +287,17 @@ fn setup_logger() {
                     record.args()
                 )
             })
-            .init();
-    }
+            .build()
+    };
 
     #[cfg(not(debug_assertions))]
-    {
+    let logger = {
         Builder::new()
             .filter(None, LevelFilter::Info)
-            .init();
-    }
+            .build()
+    };
+
+    RecentLogCapture::install(logger).expect("logger already installed");
 }
 
 /// 시스템 리소스 제한 설정
@@ -159,27 +317,71 @@ fn setup_resource_limits() {
     }
 }
 
-/// 프록시 설정 로드
+/// 프록시 설정 로드 (`--config-format`으로 형식 지정, CONFIG_FILE이 `-`이면 표준 입력)
 fn load_config() -> Result<Config> {
+    let format = parse_config_format().map_err(config_err)?;
+    
     // 먼저 현재 디렉토리의 config.yml 파일 확인
     if Path::new("config.yml").exists() {
         info!("설정 파일 로드: config.yml");
-        return Ok(Config::from_file("config.yml")?);
+        return Config::from_file("config.yml", format).map_err(config_err);
     }
     
     // 환경 변수에서 설정 파일 경로 확인
     match std::env::var("CONFIG_FILE") {
+        Ok(path) if path == STDIN_CONFIG_PATH => {
+            info!("표준 입력에서 설정 로드 ({})", format.name());
+            Config::from_file(&path, format).map_err(config_err)
+        },
         Ok(path) => {
             info!("환경 변수에서 설정 파일 로드: {}", path);
-            Ok(Config::from_file(&path).map_err(|e| config_err(e))?)
+            Config::from_file(&path, format).map_err(config_err)
         },
         Err(_) => {
             info!("설정 파일을 찾을 수 없어 기본 설정 사용");
-            Ok(Config::new())
+            Config::new().with_env_overrides().map_err(config_err)
         }
     }
 }
 
+/// `--config-format yaml|json` 인자 해석 (없으면 yaml)
+fn parse_config_format() -> std::result::Result<ConfigFormat, String> {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(idx) = args.iter().position(|arg| arg == "--config-format") else {
+        return Ok(ConfigFormat::Yaml);
+    };
+    match args.get(idx + 1).map(String::as_str) {
+        Some("yaml") => Ok(ConfigFormat::Yaml),
+        Some("json") => Ok(ConfigFormat::Json),
+        other => Err(format!("사용법: --config-format yaml|json (입력값: {})", other.unwrap_or(""))),
+    }
+}
+
+/// 덮어쓸 수 있는 설정 필드와 환경 변수 이름 출력
+fn print_env_overrides() {
+    for field in config::env::env_override_fields() {
+        println!("{:<56} {:<9} {}", field.var, field.kind.name(), field.path.join("."));
+    }
+}
+
+/// 최종 적용된 설정을 YAML로 출력 (민감한 값은 마스킹)
+fn dump_effective_config(config: &Config) {
+    if !cfg!(debug_assertions) && !std::env::args().any(|arg| arg == "--dump-config") {
+        return;
+    }
+    
+    match config.to_redacted_yaml() {
+        Ok(yaml) => info!("적용된 프록시 설정:\n{}", yaml),
+        Err(e) => warn!("프록시 설정 출력 실패: {}", e),
+    }
+    
+    match DbConfig::get().map(|db_config| db_config.to_redacted_yaml()) {
+        Ok(Ok(yaml)) => info!("적용된 데이터베이스 설정:\n{}", yaml),
+        Ok(Err(e)) => warn!("데이터베이스 설정 출력 실패: {}", e),
+        Err(e) => warn!("데이터베이스 설정 조회 실패: {}", e),
+    }
+}
+
 /// 데이터베이스 설정 및 초기화
 async fn setup_database() -> Result<()> {
     // DB 설정 로드
@@ -194,6 +396,12 @@ async fn setup_database() -> Result<()> {
         info!("기본 데이터베이스 설정 사용");
     }
     
+    // DB 비활성화 모드: 연결 풀과 스키마 초기화를 모두 건너뜀
+    if !DbConfig::is_enabled() {
+        info!("데이터베이스 비활성화 모드로 실행합니다. 접근 로그는 표준 출력으로만 기록됩니다.");
+        return Ok(());
+    }
+    
     // DB 연결 풀 초기화
     if let Err(e) = db::pool::initialize_pool().await {
         error!("데이터베이스 연결 풀 초기화 실패: {}", e);
@@ -208,6 +416,172 @@ async fn setup_database() -> Result<()> {
     Ok(())
 }
 
+/// 파티션 내보내기 인자 (파티션 이름, 출력 경로, 내보낸 뒤 삭제 여부)
+struct ExportArgs {
+    partition: String,
+    path: String,
+    drop_after: bool,
+}
+
+/// `--export-partition <name> <path> [--drop]` 인자 파싱
+fn parse_export_args() -> Option<ExportArgs> {
+    let args: Vec<String> = std::env::args().collect();
+    let idx = args.iter().position(|arg| arg == "--export-partition")?;
+    
+    Some(ExportArgs {
+        partition: args.get(idx + 1).cloned().unwrap_or_default(),
+        path: args.get(idx + 2).cloned().unwrap_or_default(),
+        drop_after: args.iter().any(|arg| arg == "--drop"),
+    })
+}
+
+/// DB 파티션을 gzip NDJSON 파일로 내보낸 뒤 종료
+async fn run_partition_export(args: ExportArgs) -> Result<()> {
+    if args.partition.is_empty() || args.path.is_empty() || args.path.starts_with("--") {
+        return Err(config_err("사용법: --export-partition <파티션 이름> <출력 경로> [--drop]"));
+    }
+    
+    setup_database().await?;
+    if !DbConfig::is_enabled() {
+        return Err(db_err("데이터베이스가 비활성화되어 있어 파티션을 내보낼 수 없습니다"));
+    }
+    
+    match db::export::export_partition(&args.partition, &args.path, args.drop_after).await {
+        Ok(rows) => {
+            info!("파티션 내보내기 성공: {} ({} 행)", args.partition, rows);
+            Ok(())
+        },
+        Err(e) => {
+            error!("파티션 내보내기 실패: {}", e);
+            Err(db_err(e))
+        }
+    }
+}
+
+const BLOCKED_REPORT_USAGE: &str = "사용법: --blocked-report <시작> <끝> [--group-by category,client] \
+    [--client-prefix <IPv4 길이>] [--client-prefix6 <IPv6 길이>] [--format csv|json] [--output <경로>] \
+    (시각은 RFC 3339 또는 YYYY-MM-DD, 끝 시각은 제외)";
+
+/// `--blocked-report` 인자 파싱 (기본: category와 client로 묶음, /24 및 /64 대역, CSV, 표준 출력)
+fn parse_report_args() -> std::result::Result<db::report::BlockedReport, String> {
+    let args: Vec<String> = std::env::args().collect();
+    let idx = args.iter().position(|arg| arg == "--blocked-report").ok_or(BLOCKED_REPORT_USAGE)?;
+    let option = |name: &str| args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1)).cloned();
+    
+    let from = args.get(idx + 1).and_then(|value| parse_report_time(value)).ok_or(BLOCKED_REPORT_USAGE)?;
+    let to = args.get(idx + 2).and_then(|value| parse_report_time(value)).ok_or(BLOCKED_REPORT_USAGE)?;
+    let group_by = match option("--group-by") {
+        Some(value) => value.split(',')
+            .map(|group| db::report::ReportGroup::parse(group).ok_or_else(|| format!("알 수 없는 묶음 기준: {}", group)))
+            .collect::<std::result::Result<Vec<_>, _>>()?,
+        None => vec![db::report::ReportGroup::Category, db::report::ReportGroup::Client],
+    };
+    let format = match option("--format") {
+        Some(value) => db::report::ReportFormat::parse(&value).ok_or_else(|| format!("알 수 없는 형식: {}", value))?,
+        None => db::report::ReportFormat::Csv,
+    };
+    let prefix = |name: &str, default: i32, max: i32| match option(name) {
+        Some(value) => value.parse().ok().filter(|len| (0..=max).contains(len))
+            .ok_or_else(|| format!("{}는 0~{} 사이여야 합니다: {}", name, max, value)),
+        None => Ok(default),
+    };
+    
+    Ok(db::report::BlockedReport {
+        from,
+        to,
+        group_by,
+        client_prefix_v4: prefix("--client-prefix", 24, 32)?,
+        client_prefix_v6: prefix("--client-prefix6", 64, 128)?,
+        format,
+        output: option("--output"),
+    })
+}
+
+/// 보고서 기간 시각 해석 (RFC 3339 또는 UTC 기준 날짜)
+fn parse_report_time(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|time| time.and_utc())
+}
+
+/// 기간 안의 차단 요청을 묶음 기준별로 집계해 출력한 뒤 종료
+async fn run_blocked_report() -> Result<()> {
+    let report = parse_report_args().map_err(config_err)?;
+    
+    setup_database().await?;
+    if !DbConfig::is_enabled() {
+        return Err(db_err("데이터베이스가 비활성화되어 있어 차단 요청 보고서를 만들 수 없습니다"));
+    }
+    
+    match db::report::write_blocked_report(&report).await {
+        Ok(rows) => {
+            info!("차단 요청 보고서 생성 성공 ({} 행)", rows);
+            Ok(())
+        },
+        Err(e) => {
+            error!("차단 요청 보고서 생성 실패: {}", e);
+            Err(db_err(e))
+        }
+    }
+}
+
+/// 자체 점검 실행
+#[cfg(feature = "self-test")]
+async fn run_self_test() -> Result<()> {
+    harness::self_test().await
+}
+
+/// self-test 기능 없이 빌드된 경우 안내만 하고 종료
+#[cfg(not(feature = "self-test"))]
+async fn run_self_test() -> Result<()> {
+    Err(config_err("--self-test는 self-test 기능으로 빌드해야 사용할 수 있습니다 (cargo build --features self-test)"))
+}
+
+/// --bench 사용법
+#[cfg(feature = "bench")]
+const BENCH_USAGE: &str = "사용법: --bench [--concurrency N] [--request-size BYTES] [--duration SECS] [--per-connection N] \
+[--plain] [--write-chunk BYTES] [--coalesce BYTES] [--coalesce-delay-ms MS]";
+
+/// --bench 인자 해석
+#[cfg(feature = "bench")]
+fn parse_bench_args() -> std::result::Result<harness::bench::BenchOptions, String> {
+    let args: Vec<String> = std::env::args().collect();
+    let option = |name: &str| args.iter().position(|arg| arg == name).and_then(|i| args.get(i + 1)).cloned();
+    let number = |name: &str, default: u64| match option(name) {
+        Some(value) => value.parse::<u64>().ok().filter(|n| *n > 0)
+            .ok_or_else(|| format!("{}는 0보다 큰 정수여야 합니다: {} ({})", name, value, BENCH_USAGE)),
+        None => Ok(default),
+    };
+    
+    let defaults = harness::bench::BenchOptions::default();
+    Ok(harness::bench::BenchOptions {
+        concurrency: number("--concurrency", defaults.concurrency as u64)? as usize,
+        request_size: number("--request-size", defaults.request_size as u64)? as usize,
+        duration: std::time::Duration::from_secs(number("--duration", defaults.duration.as_secs())?),
+        requests_per_connection: number("--per-connection", defaults.requests_per_connection)?,
+        plain: args.iter().any(|arg| arg == "--plain"),
+        write_chunk: option("--write-chunk").map(|_| number("--write-chunk", 0).map(|n| n as usize)).transpose()?,
+        coalesce_bytes: number("--coalesce", defaults.coalesce_bytes as u64)? as usize,
+        coalesce_delay_ms: number("--coalesce-delay-ms", defaults.coalesce_delay_ms)?,
+    })
+}
+
+/// 내장 부하 생성기로 하네스 프록시를 측정한 뒤 종료
+#[cfg(feature = "bench")]
+async fn run_bench() -> Result<()> {
+    let options = parse_bench_args().map_err(config_err)?;
+    harness::bench::run(options, Arc::new(create_buffer_pool())).await
+}
+
+/// bench 기능 없이 빌드된 경우 안내만 하고 종료
+#[cfg(not(feature = "bench"))]
+async fn run_bench() -> Result<()> {
+    Err(config_err("--bench는 bench 기능으로 빌드해야 사용할 수 있습니다 (cargo build --release --features bench)"))
+}
+
 /// 버퍼 풀 생성
 fn create_buffer_pool() -> BufferPool {
     BufferPool::new(
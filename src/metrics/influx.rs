@@ -0,0 +1,136 @@
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::{debug, info, warn};
+use tokio::net::UdpSocket;
+
+use crate::config::{Config, InfluxExportConfig};
+use crate::metrics::Metrics;
+
+/// `influx.collector`가 지정된 경우 메트릭 스냅샷을 주기적으로 InfluxDB 라인 프로토콜 UDP로 전송
+///
+/// 전송은 논블로킹 `try_send_to`로 한 번만 시도하며, 실패(이름 해석, 소켓 버퍼 가득 참 등)는
+/// 메트릭스의 전송 실패 수로만 집계하고 다음 주기에 다시 보냅니다.
+pub fn start_influx_exporter(config: &Config) {
+    let Some(collector) = config.influx.collector.clone() else {
+        return;
+    };
+    let settings = config.influx.clone();
+    let metrics = Metrics::new();
+    info!("InfluxDB 메트릭 UDP 전송 시작: {} ({}초 주기, measurement {})",
+          collector, settings.interval_seconds, settings.measurement);
+
+    tokio::spawn(async move {
+        // 포인트 앞부분(measurement와 태그)은 바뀌지 않으므로 한 번만 만들고, 버퍼는 매 주기 재사용
+        let prefix = line_prefix(&settings);
+        let mut line = String::with_capacity(prefix.len() + 1024);
+        let mut target: Option<(SocketAddr, UdpSocket)> = None;
+
+        let mut interval = tokio::time::interval(Duration::from_secs(settings.interval_seconds));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+
+            if target.is_none() {
+                target = match connect_collector(&collector).await {
+                    Ok(target) => Some(target),
+                    Err(e) => {
+                        debug!("InfluxDB 수집기 주소 확인 실패: {} ({})", collector, e);
+                        metrics.influx_push_failed();
+                        continue;
+                    }
+                };
+            }
+            let Some((addr, socket)) = &target else { continue };
+
+            line.clear();
+            line.push_str(&prefix);
+            write_fields(&mut line, &metrics);
+            write_tenant_points(&mut line, &prefix, &metrics);
+            write_histogram_points(&mut line, &prefix, &metrics);
+
+            if let Err(e) = socket.try_send_to(line.as_bytes(), *addr) {
+                debug!("InfluxDB 메트릭 전송 실패: {} ({})", addr, e);
+                metrics.influx_push_failed();
+            }
+        }
+    });
+}
+
+/// 수집기 주소 해석 후 같은 주소 체계의 UDP 소켓 생성
+async fn connect_collector(collector: &str) -> std::io::Result<(SocketAddr, UdpSocket)> {
+    let addr = tokio::net::lookup_host(collector).await?
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no address resolved"))?;
+    let bind: SocketAddr = if addr.is_ipv6() { "[::]:0".parse().unwrap() } else { "0.0.0.0:0".parse().unwrap() };
+    let socket = UdpSocket::bind(bind).await
+        .inspect_err(|e| warn!("InfluxDB 전송용 UDP 소켓 생성 실패: {}", e))?;
+    Ok((addr, socket))
+}
+
+/// `measurement,tag=value,...` 부분 (태그는 키 순서로 정렬됨)
+fn line_prefix(settings: &InfluxExportConfig) -> String {
+    let mut prefix = String::new();
+    push_escaped(&mut prefix, &settings.measurement, &[',', ' ']);
+    for (key, value) in &settings.tags {
+        prefix.push(',');
+        push_escaped(&mut prefix, key, &[',', '=', ' ']);
+        prefix.push('=');
+        push_escaped(&mut prefix, value, &[',', '=', ' ']);
+    }
+    prefix
+}
+
+/// ` field=1i,field=2i <나노초 타임스탬프>` 부분
+fn write_fields(line: &mut String, metrics: &Arc<Metrics>) {
+    for (index, (name, value)) in metrics.exported_fields().iter().enumerate() {
+        let separator = if index == 0 { ' ' } else { ',' };
+        let _ = write!(line, "{}{}={}i", separator, name, value);
+    }
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+    let _ = write!(line, " {}", timestamp);
+}
+
+/// 테넌트 태그별 포인트 (`tenant` 태그를 붙인 줄을 테넌트마다 하나씩 덧붙임)
+fn write_tenant_points(line: &mut String, prefix: &str, metrics: &Arc<Metrics>) {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+    for (tenant, stats) in metrics.tenant_stats() {
+        line.push('\n');
+        line.push_str(prefix);
+        line.push_str(",tenant=");
+        push_escaped(line, &tenant, &[',', '=', ' ']);
+        for (index, (name, value)) in stats.exported_fields().iter().enumerate() {
+            let separator = if index == 0 { ' ' } else { ',' };
+            let _ = write!(line, "{}tenant_{}={}i", separator, name, value);
+        }
+        let _ = write!(line, " {}", timestamp);
+    }
+}
+
+/// 세션 크기 분포 포인트 (`histogram` 태그를 붙인 줄을 분포마다 하나씩 덧붙이고, 필드는 구간별 세션 수)
+fn write_histogram_points(line: &mut String, prefix: &str, metrics: &Arc<Metrics>) {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+    for (name, histogram) in metrics.session_bytes_histograms() {
+        line.push('\n');
+        line.push_str(prefix);
+        line.push_str(",histogram=");
+        line.push_str(name);
+        for (index, (bucket, count)) in histogram.exported_fields().iter().enumerate() {
+            let separator = if index == 0 { ' ' } else { ',' };
+            let _ = write!(line, "{}{}={}i", separator, bucket, count);
+        }
+        let _ = write!(line, " {}", timestamp);
+    }
+}
+
+/// 라인 프로토콜 특수 문자 이스케이프
+fn push_escaped(out: &mut String, value: &str, special: &[char]) {
+    for c in value.chars() {
+        if c == '\\' || special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod influx;
+
 use std::sync::atomic::{ AtomicU64, Ordering };
 use std::time::{ Duration, Instant };
 use std::sync::{ Arc };
@@ -12,6 +14,9 @@ use chrono::Timelike;
 use crate::db;
 use crate::constants::proxy_stats;
 use crate::constants::proxy_stats_hourly;
+use crate::constants::shutdown_events;
+use crate::proxy::timing::UpstreamTiming;
+use crate::session::close::SessionCloseReason;
 
 // 전역 메트릭스 인스턴스를 위한 Lazy 정적 변수
 static METRICS_INSTANCE: Lazy<Arc<Metrics>> = Lazy::new(|| {
@@ -20,40 +25,45 @@ static METRICS_INSTANCE: Lazy<Arc<Metrics>> = Lazy::new(|| {
     // 주기적인 통계 로깅 설정 (비활성화)
     // 주기적인 통계 로깅 코드 제거
     
-    // DB에서 마지막 메트릭스 값 로드 시도
-    let metrics_clone = Arc::clone(&metrics);
-    tokio::spawn(async move {
-        // DB에서 마지막 메트릭스 값 로드
-        if let Err(e) = metrics_clone.load_last_metrics_from_db().await {
-            error!("DB에서 마지막 메트릭스 로드 실패: {}", e);
-        } else {
-            info!("DB에서 마지막 메트릭스 로드 완료");
-        }
-    });
+    // DB 비활성화 모드에서는 DB 관련 작업을 모두 건너뜀
+    let db_enabled = db::config::DbConfig::is_enabled();
     
-    // 시간별 통계 테이블 초기화
-    let _metrics_clone = Arc::clone(&metrics);
-    tokio::spawn(async move {
-        // 시간별 통계 테이블 생성 확인
-        match db::query::QueryExecutor::get_instance().await {
-            Ok(executor) => {
-                if let Err(e) = Metrics::ensure_hourly_stats_table(&executor).await {
-                    error!("시간별 통계 테이블 초기화 실패: {}", e);
-                } else {
-                    info!("시간별 통계 테이블 초기화 완료");
+    if db_enabled {
+        // DB에서 마지막 메트릭스 값 로드 시도
+        let metrics_clone = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            // DB에서 마지막 메트릭스 값 로드
+            if let Err(e) = metrics_clone.load_last_metrics_from_db().await {
+                error!("DB에서 마지막 메트릭스 로드 실패: {}", e);
+            } else {
+                info!("DB에서 마지막 메트릭스 로드 완료");
+            }
+        });
+    
+        // 시간별 통계 테이블 초기화
+        let _metrics_clone = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            // 시간별 통계 테이블 생성 확인
+            match db::query::QueryExecutor::get_instance().await {
+                Ok(executor) => {
+                    if let Err(e) = Metrics::ensure_hourly_stats_table(&executor).await {
+                        error!("시간별 통계 테이블 초기화 실패: {}", e);
+                    } else {
+                        info!("시간별 통계 테이블 초기화 완료");
+                    }
+                },
+                Err(e) => {
+                    error!("쿼리 실행기 가져오기 실패: {}", e);
                 }
-            },
-            Err(e) => {
-                error!("쿼리 실행기 가져오기 실패: {}", e);
             }
-        }
-    });
+        });
+    }
     
     // 주기적인 DB 저장 설정 (1초마다)
     let metrics_clone = Arc::clone(&metrics);
     tokio::spawn(async move {
         // DB 테이블 생성 확인
-        if let Err(e) = Metrics::ensure_stats_table().await {
+        if db_enabled && let Err(e) = Metrics::ensure_stats_table().await {
             error!("메트릭스 통계 테이블 생성 실패: {}", e);
         }
         
@@ -66,6 +76,15 @@ static METRICS_INSTANCE: Lazy<Arc<Metrics>> = Lazy::new(|| {
             if now.minute() == 0 && now.second() == 0 {
                 debug!("매 시간 시작: 전송량 카운터 리셋");
                 metrics_clone.reset_transfer_counters();
+                metrics_clone.log_acl_stats();
+                metrics_clone.log_session_close_stats();
+                metrics_clone.log_session_bytes();
+                metrics_clone.log_upstream_latency();
+                metrics_clone.log_tenant_stats();
+            }
+            
+            if !db_enabled {
+                continue;
             }
             
             if let Err(e) = metrics_clone.save_stats_to_db().await {
@@ -84,6 +103,80 @@ pub struct Metrics {
     tls_active_connections: AtomicU64,
     tls_bytes_transferred_in: AtomicU64,
     tls_bytes_transferred_out: AtomicU64,
+    webhook_delivered: AtomicU64,  // 웹훅 전송 성공 수
+    webhook_failed: AtomicU64,     // 웹훅 전송 실패 수 (재시도 소진, 직렬화 실패)
+    webhook_dropped: AtomicU64,    // 큐가 가득 차 보내지 않고 버린 웹훅 이벤트 수 (엔드포인트별 큐 포함)
+    event_stream_dropped: AtomicU64, // 느린 시청자의 큐가 가득 차 버린 실시간 이벤트 수 (GET /events)
+    upstream_pool_hits: AtomicU64,    // 업스트림 연결 풀 재사용 수
+    upstream_pool_misses: AtomicU64,  // 업스트림 연결 풀 미스 수 (새 연결)
+    upstream_pool_idle: AtomicU64,    // 업스트림 연결 풀 유휴 연결 수
+    warm_pool_ready: AtomicU64,       // 미리 연결해 둔 업스트림 TLS 연결 수
+    warm_pool_capacity: AtomicU64,    // 미리 연결 풀 목표 연결 수 (업스트림 수 x 업스트림당 연결 수)
+    warm_pool_hits: AtomicU64,        // 요청이 미리 연결된 연결을 바로 사용한 수
+    warm_pool_replaced: AtomicU64,    // 끊어졌거나 교체 시점이 지나 폐기한 미리 연결 수
+    sessions_total: AtomicU64,     // 시작 이후 처리한 전체 세션 수
+    bytes_total: AtomicU64,        // 시작 이후 전송한 전체 바이트 수 (시간별 리셋과 무관)
+    acl_blocked: AtomicU64,        // ACL로 실제 차단된 요청 수
+    acl_audit_matched: AtomicU64,  // 감사 모드에서 차단 규칙에 일치했지만 허용된 요청 수
+    acl_redirected: AtomicU64,     // ACL redirect 동작으로 302 응답한 요청 수
+    acl_tarpitted: AtomicU64,      // ACL tarpit 동작으로 붙잡아 둔 요청 수
+    acl_allowed: AtomicU64,        // ACL allow 동작으로 규칙에 일치했지만 허용된 요청 수
+    acl_undecided_allowed: AtomicU64, // 차단 여부를 판단할 수 없어 허용한 요청 수 (fail-open)
+    acl_undecided_blocked: AtomicU64, // 차단 여부를 판단할 수 없어 차단한 요청 수 (fail-closed)
+    acl_rules: [AtomicU64; 4],     // 마지막으로 로드한 차단 규칙 수 (AclRuleKind 순서)
+    acl_evaluations: AtomicU64,    // 차단 규칙 평가 수 (캐시 적중 포함)
+    acl_matches: [AtomicU64; 4],   // 규칙 유형별 일치 수 (AclRuleKind 순서)
+    dns_lookups: [AtomicU64; 6],     // 업스트림 이름 해석 결과별 수 (DnsOutcome 순서)
+    host_limit_queued: AtomicU64,    // 호스트별 동시 연결 한도로 대기한 연결 수
+    host_limit_rejected: AtomicU64,  // 호스트별 동시 연결 한도로 거부한 연결 수
+    overload_global_rejected: AtomicU64, // 전체 동시 세션 한도로 거부한 요청 수
+    overload_rate_limited: AtomicU64,    // 초당 요청 수 한도로 거부한 요청 수
+    client_ip_rejected: AtomicU64,       // 클라이언트 IP별 동시 세션 한도로 닫은 연결 수
+    maintenance_rejected: AtomicU64,     // 점검 모드에서 점검 응답으로 거절한 요청 수
+    connect_pipeline_forwarded: AtomicU64,  // CONNECT 뒤에 이어 붙은 데이터를 터널 데이터로 전달한 수
+    connect_pipeline_rejected: AtomicU64,   // CONNECT 뒤에 이어 붙은 데이터로 거부한 수
+    connect_pipeline_ambiguous: AtomicU64,  // CONNECT 뒤에 이어 붙은 데이터 종류를 판단할 수 없었던 수
+    resets_propagated: AtomicU64,        // 한쪽의 RST를 다른 쪽에 RST로 전달한 세션 수
+    influx_push_failed: AtomicU64,   // InfluxDB 메트릭 UDP 전송 실패 수
+    accept_fd_exhausted: AtomicU64,  // 파일 디스크립터 부족(EMFILE/ENFILE)으로 실패한 accept 수
+    source_filtered: AtomicU64,      // 출발지 IP 필터로 수락 직후 닫은 연결 수
+    upstream_cert_warned: AtomicU64, // warn 등급으로 검증 실패를 허용한 업스트림 연결 수
+    max_duration_closed: AtomicU64,  // 최대 세션 시간 초과로 종료된 세션 수
+    session_panics: AtomicU64,       // 패닉으로 종료된 세션 수
+    
+    // 종료 사유별 세션 수 (세션마다 하나의 사유로 집계)
+    session_close_eof: AtomicU64,
+    session_close_idle_timeout: AtomicU64,
+    session_close_max_duration: AtomicU64,
+    session_close_client_reset: AtomicU64,
+    session_close_upstream_reset: AtomicU64,
+    session_close_blocked: AtomicU64,
+    session_close_error: AtomicU64,
+    session_close_forced_shutdown: AtomicU64,
+    session_bytes_up: SizeHistogram,    // 릴레이한 세션의 업로드 바이트 수 분포 (세션 종료 시 집계)
+    session_bytes_down: SizeHistogram,  // 릴레이한 세션의 다운로드 바이트 수 분포 (세션 종료 시 집계)
+    passthrough_responses: AtomicU64,  // Content-Type 기준으로 본문을 그대로 전달한 응답 수
+    inspection_limit_exceeded: AtomicU64,  // 검사 응답 크기 한도(inspection_limit)를 넘은 응답 수
+    inspection_cap_exceeded: AtomicU64,    // 검사용 버퍼링 전체 상한(max_inspection_bytes)을 넘어 패스스루로 전환한 요청/응답 수
+    flow_control_paused: AtomicU64,        // 미전송 버퍼가 흐름 제어 상한 워터마크에 닿아 터널 읽기를 멈춘 횟수
+    fd_reserve_refused: AtomicU64,         // 남은 파일 디스크립터가 예비분(overload.min_free_fds)보다 적어 수락 직후 닫은 연결 수
+    db_partitions_quarantined: AtomicU64,  // 현재 격리 중인 DB 파티션 수 (게이지)
+    db_quarantined_logs: AtomicU64,        // 격리한 파티션으로 가려다 데드레터 파일에 기록한 로그 수
+    upstream_certs_logged: AtomicU64,      // 기록한 업스트림 인증서 체인 수 (중복 제외)
+    upstream_cert_changes: AtomicU64,      // 같은 호스트의 업스트림 리프 인증서가 바뀐 횟수
+    tls_handshakes: AtomicU64,     // 세션 재개가 활성화된 클라이언트 측 TLS 핸드셰이크 수
+    tls_resumed: AtomicU64,        // 그 중 세션 재개로 완료된 핸드셰이크 수
+    tls_no_sni: AtomicU64,         // SNI 없는 ClientHello 수
+    tls_client_hello_incomplete: AtomicU64, // 시간/크기 한도 안에 끝까지 받지 못한 ClientHello 수
+    cert_cache_hits: AtomicU64,    // 리프 인증서 캐시 적중 수
+    cert_cache_misses: AtomicU64,  // 리프 인증서 캐시 미스 수 (새 인증서 생성)
+    cert_cache_entries: AtomicU64, // 리프 인증서 캐시에 저장된 인증서 수
+    cert_cache_miss_rate: AtomicU64,  // 최근 구간(cert_cache_alert.window_seconds)의 캐시 미스율 (퍼센트)
+    circuit_states: std::sync::Mutex<lru::LruCache<String, &'static str>>,  // 닫히지 않은 업스트림 회로 상태 (호스트 수 제한)
+    circuit_opened: AtomicU64,     // 업스트림 회로가 열린 누적 횟수
+    upstream_latency: std::sync::Mutex<lru::LruCache<String, UpstreamLatency>>,  // 업스트림 호스트별 구간 시간 누계 (호스트 수 제한)
+    worker_loads: std::sync::RwLock<Vec<Arc<WorkerLoad>>>,  // 워커별 부하 (worker_metrics_enabled인 경우에만 등록)
+    tenant_stats: std::sync::Mutex<std::collections::HashMap<String, TenantStats>>,  // 테넌트 태그별 세션/차단 수 (태그 수는 설정으로 제한됨)
     start_time: Instant,
     last_reset_time: std::sync::RwLock<Instant>,  // 마지막 리셋 시간
 }
@@ -103,6 +196,80 @@ impl Metrics {
             tls_active_connections: AtomicU64::new(0),
             tls_bytes_transferred_in: AtomicU64::new(0),
             tls_bytes_transferred_out: AtomicU64::new(0),
+            webhook_delivered: AtomicU64::new(0),
+            webhook_failed: AtomicU64::new(0),
+            webhook_dropped: AtomicU64::new(0),
+            event_stream_dropped: AtomicU64::new(0),
+            upstream_pool_hits: AtomicU64::new(0),
+            upstream_pool_misses: AtomicU64::new(0),
+            upstream_pool_idle: AtomicU64::new(0),
+            warm_pool_ready: AtomicU64::new(0),
+            warm_pool_capacity: AtomicU64::new(0),
+            warm_pool_hits: AtomicU64::new(0),
+            warm_pool_replaced: AtomicU64::new(0),
+            sessions_total: AtomicU64::new(0),
+            bytes_total: AtomicU64::new(0),
+            acl_blocked: AtomicU64::new(0),
+            acl_audit_matched: AtomicU64::new(0),
+            acl_redirected: AtomicU64::new(0),
+            acl_tarpitted: AtomicU64::new(0),
+            acl_allowed: AtomicU64::new(0),
+            acl_undecided_allowed: AtomicU64::new(0),
+            acl_undecided_blocked: AtomicU64::new(0),
+            acl_rules: Default::default(),
+            acl_evaluations: AtomicU64::new(0),
+            acl_matches: Default::default(),
+            dns_lookups: Default::default(),
+            host_limit_queued: AtomicU64::new(0),
+            host_limit_rejected: AtomicU64::new(0),
+            overload_global_rejected: AtomicU64::new(0),
+            overload_rate_limited: AtomicU64::new(0),
+            client_ip_rejected: AtomicU64::new(0),
+            maintenance_rejected: AtomicU64::new(0),
+            connect_pipeline_forwarded: AtomicU64::new(0),
+            connect_pipeline_rejected: AtomicU64::new(0),
+            connect_pipeline_ambiguous: AtomicU64::new(0),
+            resets_propagated: AtomicU64::new(0),
+            influx_push_failed: AtomicU64::new(0),
+            accept_fd_exhausted: AtomicU64::new(0),
+            source_filtered: AtomicU64::new(0),
+            upstream_cert_warned: AtomicU64::new(0),
+            max_duration_closed: AtomicU64::new(0),
+            session_panics: AtomicU64::new(0),
+            session_close_eof: AtomicU64::new(0),
+            session_close_idle_timeout: AtomicU64::new(0),
+            session_close_max_duration: AtomicU64::new(0),
+            session_close_client_reset: AtomicU64::new(0),
+            session_close_upstream_reset: AtomicU64::new(0),
+            session_close_blocked: AtomicU64::new(0),
+            session_close_error: AtomicU64::new(0),
+            session_close_forced_shutdown: AtomicU64::new(0),
+            session_bytes_up: SizeHistogram::default(),
+            session_bytes_down: SizeHistogram::default(),
+            passthrough_responses: AtomicU64::new(0),
+            inspection_limit_exceeded: AtomicU64::new(0),
+            inspection_cap_exceeded: AtomicU64::new(0),
+            flow_control_paused: AtomicU64::new(0),
+            fd_reserve_refused: AtomicU64::new(0),
+            db_partitions_quarantined: AtomicU64::new(0),
+            db_quarantined_logs: AtomicU64::new(0),
+            upstream_certs_logged: AtomicU64::new(0),
+            upstream_cert_changes: AtomicU64::new(0),
+            tls_handshakes: AtomicU64::new(0),
+            tls_resumed: AtomicU64::new(0),
+            tls_no_sni: AtomicU64::new(0),
+            tls_client_hello_incomplete: AtomicU64::new(0),
+            cert_cache_hits: AtomicU64::new(0),
+            cert_cache_misses: AtomicU64::new(0),
+            cert_cache_entries: AtomicU64::new(0),
+            cert_cache_miss_rate: AtomicU64::new(0),
+            circuit_states: std::sync::Mutex::new(lru::LruCache::new(
+                std::num::NonZeroUsize::new(crate::constants::CIRCUIT_BREAKER_MAX_HOSTS).unwrap())),
+            circuit_opened: AtomicU64::new(0),
+            upstream_latency: std::sync::Mutex::new(lru::LruCache::new(
+                std::num::NonZeroUsize::new(crate::constants::UPSTREAM_LATENCY_MAX_HOSTS).unwrap())),
+            worker_loads: std::sync::RwLock::new(Vec::new()),
+            tenant_stats: std::sync::Mutex::new(std::collections::HashMap::new()),
             start_time: Instant::now(),
             last_reset_time: std::sync::RwLock::new(Instant::now()),  // 초기화
         }
@@ -121,11 +288,13 @@ impl Metrics {
         let metrics_clone = metrics.clone(); // 비동기 작업을 위한 복제
         
         // 비동기 작업으로 시간별 통계 저장
-        tokio::spawn(async move {
-            if let Err(e) = Self::save_hourly_stats(metrics_clone).await {
-                error!("시간별 통계 저장 실패: {}", e);
-            }
-        });
+        if db::config::DbConfig::is_enabled() {
+            tokio::spawn(async move {
+                if let Err(e) = Self::save_hourly_stats(metrics_clone).await {
+                    error!("시간별 통계 저장 실패: {}", e);
+                }
+            });
+        }
         
         // 카운터 리셋
         self.http_bytes_transferred_in.store(0, Ordering::Relaxed);
@@ -473,21 +642,685 @@ impl Metrics {
     // HTTP 수신 바이트 추가
     pub fn add_http_bytes_in(&self, bytes: u64) {
         self.http_bytes_transferred_in.fetch_add(bytes, Ordering::Relaxed);
+        self.bytes_total.fetch_add(bytes, Ordering::Relaxed);
     }
     
     // HTTP 송신 바이트 추가
     pub fn add_http_bytes_out(&self, bytes: u64) {
         self.http_bytes_transferred_out.fetch_add(bytes, Ordering::Relaxed);
+        self.bytes_total.fetch_add(bytes, Ordering::Relaxed);
     }
     
     // TLS 수신 바이트 추가
     pub fn add_tls_bytes_in(&self, bytes: u64) {
         self.tls_bytes_transferred_in.fetch_add(bytes, Ordering::Relaxed);
+        self.bytes_total.fetch_add(bytes, Ordering::Relaxed);
     }
     
     // TLS 송신 바이트 추가
     pub fn add_tls_bytes_out(&self, bytes: u64) {
         self.tls_bytes_transferred_out.fetch_add(bytes, Ordering::Relaxed);
+        self.bytes_total.fetch_add(bytes, Ordering::Relaxed);
+    }
+    
+    // 웹훅 전송 성공 카운트
+    pub fn webhook_delivered(&self) {
+        self.webhook_delivered.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    // 웹훅 전송 실패 카운트
+    pub fn webhook_failed(&self) {
+        let failed = self.webhook_failed.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("웹훅 전송 실패 누적: {}", failed);
+    }
+    
+    // 큐가 가득 차 버린 웹훅 이벤트 카운트 (누적 수 반환)
+    pub fn webhook_dropped(&self) -> u64 {
+        let dropped = self.webhook_dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("웹훅 이벤트 버림 누적: {}", dropped);
+        dropped
+    }
+    
+    // 실시간 이벤트 스트림에서 버린 이벤트 카운트
+    pub fn event_stream_dropped(&self, count: u64) {
+        let dropped = self.event_stream_dropped.fetch_add(count, Ordering::Relaxed) + count;
+        debug!("실시간 이벤트 버림 누적: {}", dropped);
+    }
+    
+    // 업스트림 연결 풀 재사용 카운트
+    pub fn upstream_pool_hit(&self) {
+        self.upstream_pool_hits.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    // 업스트림 연결 풀 미스 카운트
+    pub fn upstream_pool_miss(&self) {
+        self.upstream_pool_misses.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    // 업스트림 연결 풀 유휴 연결 수 갱신
+    pub fn set_upstream_pool_idle(&self, idle: u64) {
+        self.upstream_pool_idle.store(idle, Ordering::Relaxed);
+    }
+    
+    // 업스트림 연결 풀 통계 (재사용, 미스, 유휴)
+    pub fn upstream_pool_stats(&self) -> (u64, u64, u64) {
+        (
+            self.upstream_pool_hits.load(Ordering::Relaxed),
+            self.upstream_pool_misses.load(Ordering::Relaxed),
+            self.upstream_pool_idle.load(Ordering::Relaxed),
+        )
+    }
+    
+    // 미리 연결 풀 점유 상태 갱신 (준비된 연결 수, 목표 연결 수)
+    pub fn set_warm_pool_occupancy(&self, ready: u64, capacity: u64) {
+        self.warm_pool_ready.store(ready, Ordering::Relaxed);
+        self.warm_pool_capacity.store(capacity, Ordering::Relaxed);
+    }
+    
+    // 미리 연결된 연결 사용 카운트
+    pub fn warm_pool_hit(&self) {
+        self.warm_pool_hits.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    // 미리 연결 교체 카운트
+    pub fn warm_pool_replaced(&self, count: u64) {
+        self.warm_pool_replaced.fetch_add(count, Ordering::Relaxed);
+    }
+    
+    // 미리 연결 풀 통계 (준비, 목표, 사용, 교체)
+    pub fn warm_pool_stats(&self) -> (u64, u64, u64, u64) {
+        (
+            self.warm_pool_ready.load(Ordering::Relaxed),
+            self.warm_pool_capacity.load(Ordering::Relaxed),
+            self.warm_pool_hits.load(Ordering::Relaxed),
+            self.warm_pool_replaced.load(Ordering::Relaxed),
+        )
+    }
+    
+    // ACL 차단 카운트
+    pub fn acl_blocked(&self) {
+        let blocked = self.acl_blocked.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("ACL 차단 누적: {}", blocked);
+    }
+    
+    // ACL 감사 모드 일치 카운트
+    pub fn acl_audit_matched(&self) {
+        let matched = self.acl_audit_matched.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("ACL 감사 모드 일치 누적: {}", matched);
+    }
+    
+    // ACL redirect 동작 카운트
+    pub fn acl_redirected(&self) {
+        let redirected = self.acl_redirected.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("ACL redirect 누적: {}", redirected);
+    }
+    
+    // ACL tarpit 동작 카운트
+    pub fn acl_tarpitted(&self) {
+        let tarpitted = self.acl_tarpitted.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("ACL tarpit 누적: {}", tarpitted);
+    }
+    
+    // ACL allow 동작 카운트
+    pub fn acl_allowed(&self) {
+        let allowed = self.acl_allowed.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("ACL allow 누적: {}", allowed);
+    }
+    
+    // 판단 불가로 허용(fail-open)한 요청 카운트
+    pub fn acl_undecided_allowed(&self) {
+        let allowed = self.acl_undecided_allowed.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("ACL 판단 불가 허용 누적: {}", allowed);
+    }
+    
+    // 판단 불가로 차단(fail-closed)한 요청 카운트
+    pub fn acl_undecided_blocked(&self) {
+        let blocked = self.acl_undecided_blocked.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("ACL 판단 불가 차단 누적: {}", blocked);
+    }
+    
+    // 차단 목록 로드 후 유형별 규칙 수 갱신
+    pub fn set_acl_rule_counts(&self, exact: u64, wildcard: u64, regex: u64) {
+        for (kind, count) in [(AclRuleKind::Exact, exact), (AclRuleKind::Wildcard, wildcard), (AclRuleKind::Regex, regex)] {
+            self.acl_rules[kind as usize].store(count, Ordering::Relaxed);
+        }
+        if exact + wildcard + regex + self.acl_rules[AclRuleKind::Filter as usize].load(Ordering::Relaxed) == 0 {
+            warn!("로드된 도메인 차단 규칙이 없습니다. 차단 목록 설정 또는 DB를 확인하세요.");
+        }
+    }
+    
+    // 필터 목록 로드 후 필터 규칙 수 갱신 (차단 목록보다 먼저 로드)
+    pub fn set_acl_filter_rule_count(&self, filter: u64) {
+        self.acl_rules[AclRuleKind::Filter as usize].store(filter, Ordering::Relaxed);
+    }
+    
+    // 차단 규칙 평가 카운트
+    pub fn acl_evaluated(&self) {
+        self.acl_evaluations.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    // 차단 규칙 일치 카운트 (규칙 유형별)
+    pub fn acl_rule_matched(&self, kind: AclRuleKind) {
+        self.acl_matches[kind as usize].fetch_add(1, Ordering::Relaxed);
+    }
+    
+    // 도메인 차단 통계
+    pub fn acl_stats(&self) -> AclStats {
+        let load = |counters: &[AtomicU64; 4]| counters.each_ref().map(|c| c.load(Ordering::Relaxed));
+        AclStats {
+            rules: load(&self.acl_rules),
+            evaluations: self.acl_evaluations.load(Ordering::Relaxed),
+            matches: load(&self.acl_matches),
+            actions: [
+                self.acl_blocked.load(Ordering::Relaxed),
+                self.acl_redirected.load(Ordering::Relaxed),
+                self.acl_tarpitted.load(Ordering::Relaxed),
+                self.acl_allowed.load(Ordering::Relaxed),
+            ],
+            audit_matched: self.acl_audit_matched.load(Ordering::Relaxed),
+            undecided: [
+                self.acl_undecided_allowed.load(Ordering::Relaxed),
+                self.acl_undecided_blocked.load(Ordering::Relaxed),
+            ],
+        }
+    }
+    
+    // 도메인 차단 통계 로깅
+    pub fn log_acl_stats(&self) {
+        let stats = self.acl_stats();
+        let [exact, wildcard, regex, filter] = stats.rules;
+        let [exact_hits, wildcard_hits, regex_hits, filter_hits] = stats.matches;
+        info!("[ACL 통계] 규칙 {} 개 (정확 {}, 와일드카드 {}, 정규표현식 {}, 필터 {}), 평가 {}, 일치 {} (정확 {}, 와일드카드 {}, 정규표현식 {}, 필터 {})",
+              exact + wildcard + regex + filter, exact, wildcard, regex, filter, stats.evaluations,
+              stats.matches_total(), exact_hits, wildcard_hits, regex_hits, filter_hits);
+        let [blocked, redirected, tarpitted, allowed] = stats.actions;
+        info!("[ACL 통계] 동작: 차단 {}, redirect {}, tarpit {}, allow {}, 감사 모드 일치 {}",
+              blocked, redirected, tarpitted, allowed, stats.audit_matched);
+        let [undecided_allowed, undecided_blocked] = stats.undecided;
+        if undecided_allowed + undecided_blocked > 0 {
+            warn!("[ACL 통계] 판단 불가: 허용 {}, 차단 {}", undecided_allowed, undecided_blocked);
+        }
+    }
+    
+    // 세션 종료 사유 통계 로깅
+    pub fn log_session_close_stats(&self) {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        info!("[세션 종료 통계] eof {}, idle_timeout {}, max_duration {}, client_reset {}, upstream_reset {}, blocked {}, error {}, forced_shutdown {}",
+              load(&self.session_close_eof), load(&self.session_close_idle_timeout), load(&self.session_close_max_duration),
+              load(&self.session_close_client_reset), load(&self.session_close_upstream_reset), load(&self.session_close_blocked),
+              load(&self.session_close_error), load(&self.session_close_forced_shutdown));
+    }
+    
+    // 업스트림 호스트별 구간 시간 누적 (upstream_timing_enabled인 경우 응답마다 호출)
+    pub fn record_upstream_timing(&self, host: &str, timing: &UpstreamTiming) {
+        let mut latency = self.upstream_latency.lock().unwrap();
+        let entry = latency.get_or_insert_mut(host.to_ascii_lowercase(), UpstreamLatency::default);
+        entry.dns.record(timing.dns);
+        entry.connect.record(timing.connect);
+        entry.tls_handshake.record(timing.tls_handshake);
+        entry.first_byte.record(timing.first_byte);
+    }
+    
+    // 평균 첫 바이트 시간이 긴 업스트림 호스트 순으로 구간 시간 로깅
+    pub fn log_upstream_latency(&self) {
+        let mut hosts: Vec<(String, UpstreamLatency)> = self.upstream_latency.lock().unwrap()
+            .iter()
+            .map(|(host, latency)| (host.clone(), *latency))
+            .collect();
+        if hosts.is_empty() {
+            return;
+        }
+        hosts.sort_by_key(|(_, latency)| std::cmp::Reverse(latency.first_byte.average_ms().unwrap_or(0)));
+        
+        let total = hosts.len();
+        for (host, latency) in hosts.into_iter().take(crate::constants::UPSTREAM_LATENCY_LOG_TOP) {
+            info!("[업스트림 지연] {}: DNS {}, 연결 {}, TLS {}, 첫 바이트 {}",
+                  host, latency.dns, latency.connect, latency.tls_handshake, latency.first_byte);
+        }
+        debug!("업스트림 지연 집계 호스트 수: {}", total);
+    }
+    
+    // 업스트림 이름 해석 결과 카운트
+    pub fn dns_lookup(&self, outcome: DnsOutcome) {
+        let count = self.dns_lookups[outcome as usize].fetch_add(1, Ordering::Relaxed) + 1;
+        if outcome != DnsOutcome::Success && outcome != DnsOutcome::CacheHit {
+            debug!("DNS {} 누적: {}", outcome.name(), count);
+        }
+    }
+    
+    // 호스트별 동시 연결 한도 대기 카운트
+    pub fn upstream_host_limit_queued(&self) {
+        let queued = self.host_limit_queued.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("호스트별 연결 한도 대기 누적: {}", queued);
+    }
+    
+    // 호스트별 동시 연결 한도 거부 카운트
+    pub fn upstream_host_limit_rejected(&self) {
+        let rejected = self.host_limit_rejected.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("호스트별 연결 한도 거부 누적: {}", rejected);
+    }
+    
+    // 전체 동시 세션 한도 거부 카운트
+    pub fn overload_global_rejected(&self) {
+        let rejected = self.overload_global_rejected.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("전체 동시 세션 한도 거부 누적: {}", rejected);
+    }
+    
+    // 초당 요청 수 한도 거부 카운트
+    pub fn overload_rate_limited(&self) {
+        let rejected = self.overload_rate_limited.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("초당 요청 수 한도 거부 누적: {}", rejected);
+    }
+    
+    // 클라이언트 IP별 동시 세션 한도 거부 카운트
+    pub fn client_ip_rejected(&self) {
+        let rejected = self.client_ip_rejected.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("클라이언트 IP별 동시 세션 한도 거부 누적: {}", rejected);
+    }
+    
+    // 점검 모드 거절 카운트
+    pub fn maintenance_rejected(&self) {
+        let rejected = self.maintenance_rejected.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("점검 모드 거절 누적: {}", rejected);
+    }
+    
+    // CONNECT 뒤 이어 붙은 데이터 전달 카운트
+    pub fn connect_pipeline_forwarded(&self) {
+        let forwarded = self.connect_pipeline_forwarded.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("CONNECT 이어 붙은 데이터 전달 누적: {}", forwarded);
+    }
+    
+    // CONNECT 뒤 이어 붙은 데이터 거부 카운트
+    pub fn connect_pipeline_rejected(&self) {
+        let rejected = self.connect_pipeline_rejected.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("CONNECT 이어 붙은 데이터 거부 누적: {}", rejected);
+    }
+    
+    // CONNECT 뒤 이어 붙은 데이터 판단 불가 카운트
+    pub fn connect_pipeline_ambiguous(&self) {
+        let ambiguous = self.connect_pipeline_ambiguous.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("CONNECT 이어 붙은 데이터 판단 불가 누적: {}", ambiguous);
+    }
+    
+    // 누적 CONNECT 이어 붙은 데이터 처리 수 (전달, 거부, 판단 불가)
+    #[cfg(feature = "self-test")]
+    pub fn connect_pipeline_stats(&self) -> (u64, u64, u64) {
+        (
+            self.connect_pipeline_forwarded.load(Ordering::Relaxed),
+            self.connect_pipeline_rejected.load(Ordering::Relaxed),
+            self.connect_pipeline_ambiguous.load(Ordering::Relaxed),
+        )
+    }
+    
+    // RST 전달 카운트
+    pub fn reset_propagated(&self) {
+        let propagated = self.resets_propagated.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("RST 전달 누적: {}", propagated);
+    }
+    
+    // 테넌트 세션 시작 카운트
+    pub fn tenant_session_started(&self, tenant: &str) {
+        let mut stats = self.tenant_stats.lock().unwrap();
+        let entry = stats.entry(tenant.to_string()).or_default();
+        entry.sessions += 1;
+        entry.active += 1;
+    }
+    
+    // 테넌트 세션 종료
+    pub fn tenant_session_finished(&self, tenant: &str) {
+        if let Some(entry) = self.tenant_stats.lock().unwrap().get_mut(tenant) {
+            entry.active = entry.active.saturating_sub(1);
+        }
+    }
+    
+    // 테넌트 차단 요청 카운트
+    pub fn tenant_blocked(&self, tenant: &str) {
+        self.tenant_stats.lock().unwrap().entry(tenant.to_string()).or_default().blocked += 1;
+    }
+    
+    // 테넌트 태그별 통계 (태그 순서)
+    pub fn tenant_stats(&self) -> Vec<(String, TenantStats)> {
+        let mut stats: Vec<(String, TenantStats)> = self.tenant_stats.lock().unwrap()
+            .iter()
+            .map(|(tenant, stats)| (tenant.clone(), *stats))
+            .collect();
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+        stats
+    }
+    
+    // 테넌트 태그별 통계 로깅 (tenants 설정 시)
+    pub fn log_tenant_stats(&self) {
+        for (tenant, stats) in self.tenant_stats() {
+            info!("[테넌트] {}: 세션 {} (진행 중 {}), 차단 {}", tenant, stats.sessions, stats.active, stats.blocked);
+        }
+    }
+    
+    // InfluxDB 메트릭 전송 실패 카운트
+    pub fn influx_push_failed(&self) {
+        let failed = self.influx_push_failed.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("InfluxDB 메트릭 전송 실패 누적: {}", failed);
+    }
+    
+    // 파일 디스크립터 부족으로 인한 accept 실패 카운트 (누적 값 반환)
+    pub fn accept_fd_exhausted(&self) -> u64 {
+        let exhausted = self.accept_fd_exhausted.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("파일 디스크립터 부족 accept 실패 누적: {}", exhausted);
+        exhausted
+    }
+    
+    // 출발지 IP 필터로 닫은 연결 카운트
+    pub fn source_filtered(&self) {
+        let filtered = self.source_filtered.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("출발지 IP 필터 차단 누적: {}", filtered);
+    }
+    
+    // warn 등급 업스트림 인증서 검증 실패 카운트
+    pub fn upstream_cert_warned(&self) {
+        let warned = self.upstream_cert_warned.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("업스트림 인증서 경고 허용 누적: {}", warned);
+    }
+    
+    // 외부 전송용 필드 하나의 현재 값 (없는 이름이면 0)
+    #[cfg(any(test, feature = "self-test"))]
+    pub fn get(&self, name: &str) -> u64 {
+        self.exported_fields().iter().find(|(field, _)| *field == name).map_or(0, |(_, value)| *value)
+    }
+    
+    // 외부 전송용 카운터/게이지 (필드 이름, 값) 목록
+    pub fn exported_fields(&self) -> [(&'static str, u64); 80] {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        [
+            ("http_active_connections", load(&self.http_active_connections)),
+            ("tls_active_connections", load(&self.tls_active_connections)),
+            ("http_bytes_in", load(&self.http_bytes_transferred_in)),
+            ("http_bytes_out", load(&self.http_bytes_transferred_out)),
+            ("tls_bytes_in", load(&self.tls_bytes_transferred_in)),
+            ("tls_bytes_out", load(&self.tls_bytes_transferred_out)),
+            ("sessions_total", load(&self.sessions_total)),
+            ("bytes_total", load(&self.bytes_total)),
+            ("acl_rules_exact", load(&self.acl_rules[AclRuleKind::Exact as usize])),
+            ("acl_rules_wildcard", load(&self.acl_rules[AclRuleKind::Wildcard as usize])),
+            ("acl_rules_regex", load(&self.acl_rules[AclRuleKind::Regex as usize])),
+            ("acl_rules_filter", load(&self.acl_rules[AclRuleKind::Filter as usize])),
+            ("acl_evaluations", load(&self.acl_evaluations)),
+            ("acl_matches_exact", load(&self.acl_matches[AclRuleKind::Exact as usize])),
+            ("acl_matches_wildcard", load(&self.acl_matches[AclRuleKind::Wildcard as usize])),
+            ("acl_matches_regex", load(&self.acl_matches[AclRuleKind::Regex as usize])),
+            ("acl_matches_filter", load(&self.acl_matches[AclRuleKind::Filter as usize])),
+            ("acl_blocked", load(&self.acl_blocked)),
+            ("acl_redirected", load(&self.acl_redirected)),
+            ("acl_tarpitted", load(&self.acl_tarpitted)),
+            ("acl_allowed", load(&self.acl_allowed)),
+            ("acl_audit_matched", load(&self.acl_audit_matched)),
+            ("acl_undecided_allowed", load(&self.acl_undecided_allowed)),
+            ("acl_undecided_blocked", load(&self.acl_undecided_blocked)),
+            ("dns_lookup_success", load(&self.dns_lookups[DnsOutcome::Success as usize])),
+            ("dns_lookup_cache_hit", load(&self.dns_lookups[DnsOutcome::CacheHit as usize])),
+            ("dns_lookup_nxdomain", load(&self.dns_lookups[DnsOutcome::NxDomain as usize])),
+            ("dns_lookup_servfail", load(&self.dns_lookups[DnsOutcome::ServFail as usize])),
+            ("dns_timeouts", load(&self.dns_lookups[DnsOutcome::Timeout as usize])),
+            ("dns_lookup_error", load(&self.dns_lookups[DnsOutcome::Error as usize])),
+            ("host_limit_queued", load(&self.host_limit_queued)),
+            ("host_limit_rejected", load(&self.host_limit_rejected)),
+            ("overload_global_rejected", load(&self.overload_global_rejected)),
+            ("overload_rate_limited", load(&self.overload_rate_limited)),
+            ("client_ip_rejected", load(&self.client_ip_rejected)),
+            ("maintenance_rejected", load(&self.maintenance_rejected)),
+            ("connect_pipeline_forwarded", load(&self.connect_pipeline_forwarded)),
+            ("connect_pipeline_rejected", load(&self.connect_pipeline_rejected)),
+            ("connect_pipeline_ambiguous", load(&self.connect_pipeline_ambiguous)),
+            ("resets_propagated", load(&self.resets_propagated)),
+            ("max_duration_closed", load(&self.max_duration_closed)),
+            ("session_panics", load(&self.session_panics)),
+            ("session_close_eof", load(&self.session_close_eof)),
+            ("session_close_idle_timeout", load(&self.session_close_idle_timeout)),
+            ("session_close_max_duration", load(&self.session_close_max_duration)),
+            ("session_close_client_reset", load(&self.session_close_client_reset)),
+            ("session_close_upstream_reset", load(&self.session_close_upstream_reset)),
+            ("session_close_blocked", load(&self.session_close_blocked)),
+            ("session_close_error", load(&self.session_close_error)),
+            ("session_close_forced_shutdown", load(&self.session_close_forced_shutdown)),
+            ("passthrough_responses", load(&self.passthrough_responses)),
+            ("inspection_limit_exceeded", load(&self.inspection_limit_exceeded)),
+            ("inspection_cap_exceeded", load(&self.inspection_cap_exceeded)),
+            ("flow_control_paused", load(&self.flow_control_paused)),
+            ("fd_reserve_refused", load(&self.fd_reserve_refused)),
+            ("db_partitions_quarantined", load(&self.db_partitions_quarantined)),
+            ("db_quarantined_logs", load(&self.db_quarantined_logs)),
+            ("upstream_certs_logged", load(&self.upstream_certs_logged)),
+            ("upstream_cert_changes", load(&self.upstream_cert_changes)),
+            ("tls_handshakes", load(&self.tls_handshakes)),
+            ("tls_resumed", load(&self.tls_resumed)),
+            ("tls_no_sni", load(&self.tls_no_sni)),
+            ("tls_client_hello_incomplete", load(&self.tls_client_hello_incomplete)),
+            ("cert_cache_hits", load(&self.cert_cache_hits)),
+            ("cert_cache_misses", load(&self.cert_cache_misses)),
+            ("cert_cache_entries", load(&self.cert_cache_entries)),
+            ("cert_cache_miss_rate_percent", load(&self.cert_cache_miss_rate)),
+            ("circuit_opened", load(&self.circuit_opened)),
+            ("circuit_open_hosts", self.circuit_states.lock().unwrap().len() as u64),
+            ("upstream_pool_hits", load(&self.upstream_pool_hits)),
+            ("upstream_pool_misses", load(&self.upstream_pool_misses)),
+            ("warm_pool_hits", load(&self.warm_pool_hits)),
+            ("webhook_failed", load(&self.webhook_failed)),
+            ("webhook_dropped", load(&self.webhook_dropped)),
+            ("event_stream_dropped", load(&self.event_stream_dropped)),
+            ("influx_push_failed", load(&self.influx_push_failed)),
+            ("accept_fd_exhausted", load(&self.accept_fd_exhausted)),
+            ("source_filtered", load(&self.source_filtered)),
+            ("upstream_cert_warned", load(&self.upstream_cert_warned)),
+            ("uptime_seconds", self.start_time.elapsed().as_secs()),
+        ]
+    }
+    
+    // 최대 세션 시간 초과 종료 카운트
+    pub fn session_max_duration_closed(&self) {
+        let closed = self.max_duration_closed.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("최대 세션 시간 초과 종료 누적: {}", closed);
+    }
+    
+    // 세션 패닉 카운트
+    pub fn session_panicked(&self) {
+        let panics = self.session_panics.fetch_add(1, Ordering::Relaxed) + 1;
+        warn!("세션 패닉 누적: {}", panics);
+    }
+    
+    // 세션 종료 사유 카운트 (강제 종료는 남은 세션 수만큼 한 번에 집계)
+    pub fn session_closed(&self, reason: SessionCloseReason, sessions: u64) {
+        let counter = match reason {
+            SessionCloseReason::Eof => &self.session_close_eof,
+            SessionCloseReason::IdleTimeout => &self.session_close_idle_timeout,
+            SessionCloseReason::MaxDuration => &self.session_close_max_duration,
+            SessionCloseReason::ClientReset => &self.session_close_client_reset,
+            SessionCloseReason::UpstreamReset => &self.session_close_upstream_reset,
+            SessionCloseReason::Blocked => &self.session_close_blocked,
+            SessionCloseReason::Error => &self.session_close_error,
+            SessionCloseReason::ForcedShutdown => &self.session_close_forced_shutdown,
+        };
+        let closed = counter.fetch_add(sessions, Ordering::Relaxed) + sessions;
+        debug!("세션 종료 사유 {} 누적: {}", reason.name(), closed);
+    }
+    
+    // 세션 크기 분포 집계 (릴레이한 세션 종료 시 방향별로 구간 하나씩 증가)
+    pub fn record_session_bytes(&self, up: u64, down: u64) {
+        self.session_bytes_up.record(up);
+        self.session_bytes_down.record(down);
+    }
+    
+    // 방향별 세션 크기 분포 (내보내는 이름과 분포)
+    pub fn session_bytes_histograms(&self) -> [(&'static str, &SizeHistogram); 2] {
+        [("session_bytes_up", &self.session_bytes_up), ("session_bytes_down", &self.session_bytes_down)]
+    }
+    
+    // 세션 크기 분포 로깅
+    pub fn log_session_bytes(&self) {
+        for (name, histogram) in self.session_bytes_histograms() {
+            let buckets: Vec<String> = histogram.exported_fields().iter()
+                .map(|(bucket, count)| format!("{} {}", bucket, count))
+                .collect();
+            info!("[세션 크기 분포] {}: {}", name, buckets.join(", "));
+        }
+    }
+    
+    // 패닉으로 종료된 세션 수
+    #[cfg(feature = "self-test")]
+    pub fn session_panics(&self) -> u64 {
+        self.session_panics.load(Ordering::Relaxed)
+    }
+    
+    // 본문 패스스루 응답 카운트
+    pub fn passthrough_response(&self) {
+        let count = self.passthrough_responses.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("본문 패스스루 응답 누적: {}", count);
+    }
+    
+    // 검사 응답 크기 한도 초과 카운트
+    pub fn inspection_limit_exceeded(&self) {
+        let count = self.inspection_limit_exceeded.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("검사 응답 크기 한도 초과 누적: {}", count);
+    }
+    
+    // 검사용 버퍼링 전체 상한 초과 카운트
+    pub fn inspection_cap_exceeded(&self) {
+        let count = self.inspection_cap_exceeded.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("검사용 버퍼링 전체 상한 초과 누적: {}", count);
+    }
+    
+    // 흐름 제어로 터널 읽기를 멈춘 횟수 카운트
+    pub fn flow_control_paused(&self) {
+        let count = self.flow_control_paused.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("흐름 제어 읽기 중지 누적: {}", count);
+    }
+    
+    // fd 예비분 부족으로 거부한 연결 카운트 (누적 값 반환)
+    pub fn fd_reserve_refused(&self) -> u64 {
+        let refused = self.fd_reserve_refused.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("fd 예비분 부족 연결 거부 누적: {}", refused);
+        refused
+    }
+    
+    // DB 파티션 격리 (현재 격리 중인 파티션 수 반환)
+    pub fn db_partition_quarantined(&self) -> u64 {
+        self.db_partitions_quarantined.fetch_add(1, Ordering::Relaxed) + 1
+    }
+    
+    // DB 파티션 격리 해제
+    pub fn db_partition_released(&self) {
+        let _ = self.db_partitions_quarantined.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| count.checked_sub(1));
+    }
+    
+    // 격리한 파티션 대신 데드레터 파일에 기록한 로그 카운트
+    pub fn db_quarantined_logs(&self, count: usize) {
+        let total = self.db_quarantined_logs.fetch_add(count as u64, Ordering::Relaxed) + count as u64;
+        debug!("격리 파티션 데드레터 기록 누적: {}", total);
+    }
+    
+    // 업스트림 인증서 체인 기록 카운트 (리프 인증서가 바뀌었으면 변경 횟수도 집계)
+    pub fn upstream_cert_logged(&self, changed: bool) {
+        let total = self.upstream_certs_logged.fetch_add(1, Ordering::Relaxed) + 1;
+        if changed {
+            let changes = self.upstream_cert_changes.fetch_add(1, Ordering::Relaxed) + 1;
+            debug!("업스트림 인증서 변경 누적: {}", changes);
+        }
+        debug!("업스트림 인증서 체인 기록 누적: {}", total);
+    }
+    
+    // 클라이언트 측 TLS 핸드셰이크 완료 처리 (세션 재개 적중률 집계)
+    pub fn tls_handshake_completed(&self, resumed: bool) {
+        let total = self.tls_handshakes.fetch_add(1, Ordering::Relaxed) + 1;
+        let hits = if resumed {
+            self.tls_resumed.fetch_add(1, Ordering::Relaxed) + 1
+        } else {
+            self.tls_resumed.load(Ordering::Relaxed)
+        };
+        debug!("TLS 세션 재개 적중률: {:.1}% ({}/{})", hits as f64 * 100.0 / total as f64, hits, total);
+    }
+    
+    // SNI 없는 ClientHello 카운트
+    pub fn tls_no_sni(&self) {
+        let count = self.tls_no_sni.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("SNI 없는 ClientHello 누적: {}", count);
+    }
+    
+    // 끝까지 받지 못한 ClientHello 카운트
+    pub fn tls_client_hello_incomplete(&self) {
+        self.tls_client_hello_incomplete.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    // 리프 인증서 캐시 조회 결과 반영 (캐시 크기와 최근 구간 미스율 게이지 포함)
+    pub fn cert_cache_lookup(&self, hit: bool, entries: u64, miss_rate_percent: u64) {
+        if hit {
+            self.cert_cache_hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.cert_cache_misses.fetch_add(1, Ordering::Relaxed);
+        }
+        self.cert_cache_entries.store(entries, Ordering::Relaxed);
+        self.cert_cache_miss_rate.store(miss_rate_percent, Ordering::Relaxed);
+    }
+    
+    // 업스트림 호스트별 회로 상태 갱신 (닫힌 회로는 목록에서 제거)
+    pub fn set_circuit_state(&self, host: &str, state: &'static str) {
+        let mut states = self.circuit_states.lock().unwrap();
+        if state == "closed" {
+            states.pop(host);
+        } else {
+            states.put(host.to_string(), state);
+        }
+        
+        let opened = if state == "open" {
+            self.circuit_opened.fetch_add(1, Ordering::Relaxed) + 1
+        } else {
+            self.circuit_opened.load(Ordering::Relaxed)
+        };
+        let open_hosts = states.iter().filter(|(_, s)| **s == "open").count();
+        debug!("업스트림 회로 상태: {} -> {} (열린 회로 {} 개, 누적 열림 {})", host, state, open_hosts, opened);
+    }
+    
+    // 닫히지 않은(열림/반열림) 업스트림 회로의 (호스트, 상태) 목록 (최근에 바뀐 순)
+    pub fn open_circuits(&self) -> Vec<(String, &'static str)> {
+        self.circuit_states.lock().unwrap().iter().map(|(host, state)| (host.clone(), *state)).collect()
+    }
+    
+    // 종료 보고서 생성
+    pub fn shutdown_report(&self, exit_reason: impl Into<String>) -> ShutdownReport {
+        ShutdownReport {
+            sessions_total: self.sessions_total.load(Ordering::Relaxed),
+            bytes_total: self.bytes_total.load(Ordering::Relaxed),
+            blocked_total: self.acl_blocked.load(Ordering::Relaxed),
+            uptime_seconds: self.start_time.elapsed().as_secs(),
+            exit_reason: exit_reason.into(),
+        }
+    }
+    
+    // 워커 부하 카운터 등록 (등록 순서가 워커 번호)
+    pub fn register_worker(&self) -> Arc<WorkerLoad> {
+        let load = Arc::new(WorkerLoad::default());
+        self.worker_loads.write().unwrap().push(Arc::clone(&load));
+        load
+    }
+    
+    // 워커별 부하와 tokio 런타임 지표 로깅
+    pub fn log_worker_loads(&self) {
+        let loads = self.worker_loads.read().unwrap();
+        let total_accepted: u64 = loads.iter().map(|l| l.accepted.load(Ordering::Relaxed)).sum();
+        
+        for (worker_id, load) in loads.iter().enumerate() {
+            let accepted = load.accepted.load(Ordering::Relaxed);
+            let share = if total_accepted > 0 { accepted as f64 * 100.0 / total_accepted as f64 } else { 0.0 };
+            info!("[워커 부하] worker #{}: 활성 세션 {}, 수락 {} ({:.1}%)",
+                  worker_id, load.active.load(Ordering::Relaxed), accepted, share);
+        }
+        
+        // tokio 런타임 지표 (워커 스레드별 busy 시간 포함)
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let runtime = handle.metrics();
+            info!("[런타임] 워커 스레드 {}, 실행 중 태스크 {}, 전역 큐 대기 {}",
+                  runtime.num_workers(), runtime.num_alive_tasks(), runtime.global_queue_depth());
+            for worker in 0..runtime.num_workers() {
+                debug!("[런타임] 워커 스레드 #{}: busy {:?}, park {} 회",
+                       worker, runtime.worker_total_busy_duration(worker), runtime.worker_park_count(worker));
+            }
+        }
     }
     
     // 연결 종료 처리
@@ -505,6 +1338,7 @@ impl Metrics {
     
     // 연결 시작 처리 - 추가
     pub fn connection_opened(&self, https_flag: bool) {
+        self.sessions_total.fetch_add(1, Ordering::Relaxed);
         if https_flag {
             self.tls_active_connections.fetch_add(1, Ordering::Relaxed);
             debug!("HTTPS 연결 시작, 현재 활성 HTTPS 연결: {}", 
@@ -581,6 +1415,232 @@ impl Metrics {
     }
 }
 
+/// 프로세스 종료 시 남기는 요약
+pub struct ShutdownReport {
+    pub sessions_total: u64,
+    pub bytes_total: u64,
+    pub blocked_total: u64,
+    pub uptime_seconds: u64,
+    pub exit_reason: String,
+}
+
+impl ShutdownReport {
+    /// 로그 출력 (한 줄 요약)
+    pub fn log(&self) {
+        info!("[종료 보고] 사유: {}, 가동 시간: {}초, 세션: {}, 전송량: {} MB, 차단: {}",
+              self.exit_reason, self.uptime_seconds, self.sessions_total,
+              Metrics::bytes_to_mb(self.bytes_total), self.blocked_total);
+    }
+    
+    /// DB에 종료 이벤트 기록 (DB가 활성화되어 있고 연결 가능한 경우)
+    pub async fn save_to_db(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if !db::config::DbConfig::is_enabled() {
+            return Ok(());
+        }
+        
+        let client = db::pool::get_client().await?;
+        client.execute(shutdown_events::CREATE_TABLE, &[]).await?;
+        client.execute(shutdown_events::INSERT_EVENT, &[
+            &(self.sessions_total as i64),
+            &(self.bytes_total as i64),
+            &(self.blocked_total as i64),
+            &(self.uptime_seconds as i64),
+            &self.exit_reason,
+        ]).await?;
+        Ok(())
+    }
+}
+
+/// 업스트림 이름 해석 결과 (통계 배열 인덱스)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DnsOutcome {
+    /// DNS로 주소를 얻음
+    Success = 0,
+    /// 미리 해석해 둔 주소 사용 (DNS 조회 없음)
+    CacheHit = 1,
+    /// 이름이 없음 (EAI_NONAME)
+    NxDomain = 2,
+    /// 일시적/영구적 서버 실패 (EAI_AGAIN, EAI_FAIL)
+    ServFail = 3,
+    /// dns_timeout_ms 초과
+    Timeout = 4,
+    /// 그 밖의 오류
+    Error = 5,
+}
+
+impl DnsOutcome {
+    /// 로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            DnsOutcome::Success => "success",
+            DnsOutcome::CacheHit => "cache_hit",
+            DnsOutcome::NxDomain => "nxdomain",
+            DnsOutcome::ServFail => "servfail",
+            DnsOutcome::Timeout => "timeout",
+            DnsOutcome::Error => "error",
+        }
+    }
+}
+
+/// 도메인 차단 규칙 유형 (통계 배열 인덱스)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AclRuleKind {
+    /// blocked_domains / domain_blocks의 정확한 도메인
+    Exact = 0,
+    /// `*`를 쓰는 와일드카드 패턴
+    Wildcard = 1,
+    /// `regex:` 접두사 정규표현식 패턴
+    Regex = 2,
+    /// blocked_filter_lists의 Adblock Plus `||도메인^` 규칙
+    Filter = 3,
+}
+
+impl AclRuleKind {
+    /// 설정(acl_actions.rules의 `kind:<이름>`)에서 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            AclRuleKind::Exact => "exact",
+            AclRuleKind::Wildcard => "wildcard",
+            AclRuleKind::Regex => "regex",
+            AclRuleKind::Filter => "filter",
+        }
+    }
+}
+
+/// 도메인 차단 통계 스냅샷 (배열은 AclRuleKind 순서)
+#[derive(Debug, Clone, Copy)]
+pub struct AclStats {
+    pub rules: [u64; 4],
+    pub evaluations: u64,
+    pub matches: [u64; 4],
+    /// 동작별 적용 수 (block, redirect, tarpit, allow 순서)
+    pub actions: [u64; 4],
+    pub audit_matched: u64,
+    /// 판단 불가로 처리한 수 (허용, 차단 순서)
+    pub undecided: [u64; 2],
+}
+
+impl AclStats {
+    /// 전체 일치 수
+    pub fn matches_total(&self) -> u64 {
+        self.matches.iter().sum()
+    }
+}
+
+/// 테넌트 태그 하나의 누적 통계
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TenantStats {
+    /// 누적 세션 수
+    pub sessions: u64,
+    /// 진행 중인 세션 수
+    pub active: u64,
+    /// 차단된 요청 수
+    pub blocked: u64,
+}
+
+impl TenantStats {
+    /// 내보내는 값 이름과 값 목록
+    pub fn exported_fields(&self) -> [(&'static str, u64); 3] {
+        [
+            ("sessions", self.sessions),
+            ("active_sessions", self.active),
+            ("blocked", self.blocked),
+        ]
+    }
+}
+
+/// 업스트림 호스트 한 곳의 구간별 시간 누계
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UpstreamLatency {
+    pub dns: PhaseLatency,
+    pub connect: PhaseLatency,
+    pub tls_handshake: PhaseLatency,
+    pub first_byte: PhaseLatency,
+}
+
+/// 구간 하나의 측정 횟수, 합계, 최댓값 (밀리초)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseLatency {
+    pub count: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+impl PhaseLatency {
+    fn record(&mut self, phase: Option<Duration>) {
+        let Some(phase) = phase else { return };
+        let ms = phase.as_millis() as u64;
+        self.count += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+    
+    /// 평균 (측정 없으면 None)
+    pub fn average_ms(&self) -> Option<u64> {
+        (self.count > 0).then(|| self.total_ms / self.count)
+    }
+}
+
+impl std::fmt::Display for PhaseLatency {
+    /// `평균 12ms/최대 40ms (5건)`, 측정 없으면 `-`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.average_ms() {
+            Some(average) => write!(f, "평균 {}ms/최대 {}ms ({}건)", average, self.max_ms, self.count),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+/// 세션 크기 분포 구간 이름 (1KiB부터 4배 간격의 상한, 마지막은 1GiB 초과)
+pub const SIZE_HISTOGRAM_BUCKETS: [&str; 12] = [
+    "le_1k", "le_4k", "le_16k", "le_64k", "le_256k", "le_1m",
+    "le_4m", "le_16m", "le_64m", "le_256m", "le_1g", "le_inf",
+];
+
+/// 바이트 수 분포 (로그 구간별 세션 수, 누적 아님)
+#[derive(Default)]
+pub struct SizeHistogram {
+    buckets: [AtomicU64; SIZE_HISTOGRAM_BUCKETS.len()],
+}
+
+impl SizeHistogram {
+    /// 바이트 수가 속하는 구간 (1KiB 이하는 0, 이후 4배마다 한 구간)
+    fn bucket(bytes: u64) -> usize {
+        let bits = (u64::BITS - bytes.saturating_sub(1).leading_zeros()) as usize;
+        (bits.saturating_sub(10).div_ceil(2)).min(SIZE_HISTOGRAM_BUCKETS.len() - 1)
+    }
+    
+    // 구간 하나 증가
+    fn record(&self, bytes: u64) {
+        self.buckets[Self::bucket(bytes)].fetch_add(1, Ordering::Relaxed);
+    }
+    
+    /// 내보내는 구간 이름과 세션 수 목록
+    pub fn exported_fields(&self) -> [(&'static str, u64); SIZE_HISTOGRAM_BUCKETS.len()] {
+        std::array::from_fn(|index| (SIZE_HISTOGRAM_BUCKETS[index], self.buckets[index].load(Ordering::Relaxed)))
+    }
+}
+
+/// 워커 한 개의 부하 카운터
+#[derive(Default)]
+pub struct WorkerLoad {
+    accepted: AtomicU64,  // 워커가 받은 누적 연결 수
+    active: AtomicU64,    // 워커가 시작해 아직 진행 중인 세션 수
+}
+
+impl WorkerLoad {
+    // 세션 시작 처리
+    pub fn session_started(&self) {
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+        self.active.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    // 세션 종료 처리
+    pub fn session_finished(&self) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 struct MetricsSnapshot {
     http_active_connections: u64,
     http_bytes_transferred_in: u64,
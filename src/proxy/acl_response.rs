@@ -0,0 +1,35 @@
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// ACL redirect 동작: 302로 안내 페이지로 이동시키고 연결 종료
+pub async fn send_redirect<S: AsyncWrite + Unpin>(stream: &mut S, location: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 302 Found\r\nLocation: {}\r\nCache-Control: no-store\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        location
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+    stream.shutdown().await
+}
+
+/// ACL tarpit 동작: 상태 줄을 보낸 뒤 헤더를 끝내지 않고 `interval`마다 헤더 한 줄씩 보내다가
+/// `max_hold`가 지나면 연결 종료, 연결을 붙잡아 둔 시간 반환
+///
+/// 클라이언트가 먼저 연결을 끊으면 쓰기 오류로 바로 끝납니다.
+pub async fn tarpit<S: AsyncWrite + Unpin>(stream: &mut S, interval: Duration, max_hold: Duration) -> std::io::Result<Duration> {
+    let started = Instant::now();
+    stream.write_all(b"HTTP/1.1 200 OK\r\n").await?;
+    stream.flush().await?;
+
+    let mut line = 0u64;
+    while started.elapsed() + interval <= max_hold {
+        tokio::time::sleep(interval).await;
+        line += 1;
+        stream.write_all(format!("X-Wait-{}: 1\r\n", line).as_bytes()).await?;
+        stream.flush().await?;
+    }
+
+    let _ = stream.shutdown().await;
+    Ok(started.elapsed())
+}
@@ -0,0 +1,296 @@
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{debug, info, warn};
+use lru::LruCache;
+use once_cell::sync::OnceCell;
+
+use crate::config::Config;
+use crate::constants::CIRCUIT_BREAKER_MAX_HOSTS;
+use crate::metrics::Metrics;
+
+// 전역 업스트림 회로 차단기 (circuit_breaker_enabled인 경우에만 초기화)
+static CIRCUIT_BREAKER: OnceCell<Arc<CircuitBreaker>> = OnceCell::new();
+
+/// 호스트별 회로 상태
+#[derive(Debug, Clone, Copy)]
+enum CircuitState {
+    /// 정상: 구간 시작 시각부터의 연속 실패 수 집계
+    Closed { failures: u32, window_start: Instant },
+    /// 차단: 지정 시각까지 연결 시도 없이 즉시 실패
+    Open { until: Instant },
+    /// 시험 연결 1건 진행 중 (결과에 따라 닫히거나 다시 열림, 지정 시각까지 결과가 없으면 새 시험 연결 허용)
+    HalfOpen { deadline: Instant },
+}
+
+impl CircuitState {
+    /// 메트릭/로그용 상태 이름
+    fn name(&self) -> &'static str {
+        match self {
+            CircuitState::Closed { .. } => "closed",
+            CircuitState::Open { .. } => "open",
+            CircuitState::HalfOpen { .. } => "half-open",
+        }
+    }
+}
+
+/// 업스트림 호스트별 회로 차단기
+///
+/// 구간 내 연속 실패가 임계값에 도달하면 대기 시간 동안 해당 호스트로의 연결을 즉시 실패시키고,
+/// 대기 시간이 지나면 시험 연결 1건으로 회복 여부를 확인합니다. 시험 연결의 결과가 대기 시간 안에
+/// 기록되지 않으면(연결 취소 등) 다음 연결을 새 시험 연결로 사용합니다.
+pub struct CircuitBreaker {
+    states: Mutex<LruCache<String, CircuitState>>,
+    failure_threshold: u32,
+    window: Duration,
+    cooldown: Duration,
+    metrics: Arc<Metrics>,
+}
+
+/// 설정에 따라 전역 회로 차단기 초기화
+pub fn init_circuit_breaker(config: &Config) {
+    if !config.circuit_breaker_enabled {
+        return;
+    }
+
+    let breaker = Arc::new(CircuitBreaker::new(
+        config.circuit_breaker_failure_threshold,
+        Duration::from_secs(config.circuit_breaker_window_seconds),
+        Duration::from_secs(config.circuit_breaker_cooldown_seconds),
+    ));
+
+    if CIRCUIT_BREAKER.set(breaker).is_ok() {
+        info!("업스트림 회로 차단기 활성화: {}초 내 연속 실패 {}회 시 {}초 차단",
+              config.circuit_breaker_window_seconds, config.circuit_breaker_failure_threshold,
+              config.circuit_breaker_cooldown_seconds);
+    }
+}
+
+/// 전역 회로 차단기 (비활성화 시 None)
+pub fn circuit_breaker() -> Option<Arc<CircuitBreaker>> {
+    CIRCUIT_BREAKER.get().cloned()
+}
+
+/// 회로 차단기 연결 허가 (결과를 기록하지 않고 버려지면 시험 연결을 되돌림)
+pub struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    key: &'a str,
+    recorded: bool,
+}
+
+impl CircuitPermit<'_> {
+    /// 연결 결과 기록
+    pub fn record(mut self, success: bool) {
+        self.recorded = true;
+        if success {
+            self.breaker.record_success(self.key);
+        } else {
+            self.breaker.record_failure(self.key);
+        }
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if !self.recorded {
+            self.breaker.abandon(self.key);
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// 회로 차단기 생성 (구간 내 연속 실패 임계값, 실패를 세는 구간, 차단 시간)
+    pub fn new(failure_threshold: u32, window: Duration, cooldown: Duration) -> Self {
+        Self {
+            states: Mutex::new(LruCache::new(NonZeroUsize::new(CIRCUIT_BREAKER_MAX_HOSTS).unwrap())),
+            failure_threshold,
+            window,
+            cooldown,
+            metrics: Metrics::new(),
+        }
+    }
+
+    /// 지금 연결하면 즉시 거부되는지 확인 (상태는 바꾸지 않음)
+    pub fn is_open(&self, key: &str) -> bool {
+        self.retry_in(key).is_some()
+    }
+
+    /// 연결이 거부되는 남은 시간 (거부되지 않으면 None, 상태는 바꾸지 않음)
+    pub fn retry_in(&self, key: &str) -> Option<Duration> {
+        let now = Instant::now();
+        match self.states.lock().unwrap().peek(key) {
+            Some(CircuitState::Open { until }) if now < *until => Some(*until - now),
+            Some(CircuitState::HalfOpen { deadline }) if now < *deadline => Some(*deadline - now),
+            _ => None,
+        }
+    }
+
+    /// 연결 시도 허가 (거부 시 남은 차단 시간 반환)
+    ///
+    /// 허가를 받은 연결은 `CircuitPermit::record`로 결과를 기록해야 하며, 기록하지 않고 버려지면
+    /// (이름 해석 실패, 연결 취소 등) 시험 연결을 되돌려 다음 연결이 바로 시험 연결이 되게 합니다.
+    pub fn acquire<'a>(&'a self, key: &'a str) -> Result<CircuitPermit<'a>, Duration> {
+        self.try_acquire(key)?;
+        Ok(CircuitPermit { breaker: self, key, recorded: false })
+    }
+
+    /// 결과 없이 끝난 연결 시도 처리 (시험 연결이었으면 열림 상태로 되돌림)
+    fn abandon(&self, key: &str) {
+        let mut states = self.states.lock().unwrap();
+        if let Some(state @ CircuitState::HalfOpen { .. }) = states.get_mut(key) {
+            *state = CircuitState::Open { until: Instant::now() };
+            debug!("[회로 차단기] {} 시험 연결 결과 없음, 다음 연결에서 다시 시험", key);
+        }
+    }
+
+    /// 연결 시도 허용 여부 확인 (거부 시 남은 차단 시간 반환)
+    fn try_acquire(&self, key: &str) -> Result<(), Duration> {
+        let mut states = self.states.lock().unwrap();
+        let now = Instant::now();
+
+        match states.get(key).copied() {
+            Some(CircuitState::Open { until }) if now < until => Err(until - now),
+            Some(CircuitState::Open { .. }) => {
+                // 대기 시간 경과: 이번 연결을 시험 연결로 사용
+                let trial = CircuitState::HalfOpen { deadline: now + self.cooldown };
+                states.put(key.to_string(), trial);
+                drop(states);
+                self.transition(key, trial);
+                Ok(())
+            },
+            // 시험 연결 결과가 나올 때까지 다른 연결은 거부
+            Some(CircuitState::HalfOpen { deadline }) if now < deadline => Err(deadline - now),
+            Some(CircuitState::HalfOpen { .. }) => {
+                // 시험 연결 결과가 대기 시간 안에 오지 않음 (취소 등): 이번 연결을 새 시험 연결로 사용
+                warn!("[회로 차단기] {} 시험 연결 결과 없음, 새 시험 연결 시도", key);
+                states.put(key.to_string(), CircuitState::HalfOpen { deadline: now + self.cooldown });
+                Ok(())
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// 연결 성공 기록 (열림/반열림 상태였으면 닫음)
+    pub fn record_success(&self, key: &str) {
+        let previous = self.states.lock().unwrap().pop(key);
+
+        if matches!(previous, Some(CircuitState::Open { .. } | CircuitState::HalfOpen { .. })) {
+            self.transition(key, CircuitState::Closed { failures: 0, window_start: Instant::now() });
+        }
+    }
+
+    /// 연결 실패 기록 (임계값 도달 또는 시험 연결 실패 시 회로 열림)
+    pub fn record_failure(&self, key: &str) {
+        let mut states = self.states.lock().unwrap();
+        let now = Instant::now();
+
+        let next = match states.get(key).copied() {
+            Some(CircuitState::Closed { failures, window_start }) if now.duration_since(window_start) < self.window => {
+                CircuitState::Closed { failures: failures + 1, window_start }
+            },
+            Some(CircuitState::Open { until }) => CircuitState::Open { until },
+            Some(CircuitState::HalfOpen { .. }) => CircuitState::Open { until: now + self.cooldown },
+            _ => CircuitState::Closed { failures: 1, window_start: now },
+        };
+
+        let next = match next {
+            CircuitState::Closed { failures, .. } if failures >= self.failure_threshold => {
+                CircuitState::Open { until: now + self.cooldown }
+            },
+            state => state,
+        };
+
+        let opened = matches!(next, CircuitState::Open { .. })
+            && !matches!(states.peek(key), Some(CircuitState::Open { .. }));
+        states.put(key.to_string(), next);
+        drop(states);
+
+        if opened {
+            self.transition(key, next);
+        }
+    }
+
+    /// 상태 전이 로깅 및 메트릭 반영
+    fn transition(&self, key: &str, state: CircuitState) {
+        match state {
+            CircuitState::Open { .. } => warn!("[회로 차단기] {} 열림: {}초 동안 연결을 즉시 실패 처리합니다", key, self.cooldown.as_secs()),
+            CircuitState::HalfOpen { .. } => info!("[회로 차단기] {} 반열림: 시험 연결 시도", key),
+            CircuitState::Closed { .. } => info!("[회로 차단기] {} 닫힘: 연결 회복", key),
+        }
+        self.metrics.set_circuit_state(key, state.name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 메트릭스 전역 인스턴스가 주기 저장 태스크를 띄우므로 tokio 런타임에서 실행 (DB는 사용 안 함)
+    #[tokio::test]
+    async fn lost_trial_result_allows_new_trial_after_deadline() {
+        crate::db::config::DbConfig::disable();
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60), Duration::from_millis(50));
+        let key = "lost-trial.test:443";
+        breaker.record_failure(key);
+        assert!(breaker.try_acquire(key).is_err());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.try_acquire(key).is_ok(), "대기 시간 후 시험 연결 허용");
+        // 시험 연결 결과를 기록하지 않음 (취소된 연결)
+        assert!(breaker.try_acquire(key).is_err(), "시험 연결 중에는 거부");
+        assert!(breaker.is_open(key));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!breaker.is_open(key));
+        assert!(breaker.try_acquire(key).is_ok(), "시험 연결 결과가 없으면 새 시험 연결 허용");
+        breaker.record_success(key);
+        assert!(breaker.try_acquire(key).is_ok());
+        assert!(breaker.try_acquire(key).is_ok());
+    }
+
+    #[tokio::test]
+    async fn dropped_permit_returns_trial() {
+        crate::db::config::DbConfig::disable();
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60), Duration::from_millis(30));
+        let key = "dropped-permit.test:443";
+        breaker.acquire(key).unwrap().record(false);
+        assert!(breaker.acquire(key).is_err());
+
+        std::thread::sleep(Duration::from_millis(40));
+        // 이름 해석 실패나 취소로 결과 없이 버려진 시험 연결
+        drop(breaker.acquire(key).unwrap());
+        assert!(!breaker.is_open(key));
+        let trial = breaker.acquire(key).expect("버려진 시험 연결 뒤에는 바로 다시 시험");
+        assert!(breaker.acquire(key).is_err());
+        trial.record(true);
+        assert!(breaker.acquire(key).is_ok());
+    }
+
+    #[tokio::test]
+    async fn failed_trial_reopens() {
+        crate::db::config::DbConfig::disable();
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60), Duration::from_millis(30));
+        let key = "failed-trial.test:443";
+        breaker.record_failure(key);
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(breaker.try_acquire(key).is_ok());
+        breaker.record_failure(key);
+        assert!(breaker.try_acquire(key).is_err());
+    }
+
+    #[tokio::test]
+    async fn open_circuits_are_exported_until_closed() {
+        crate::db::config::DbConfig::disable();
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60), Duration::from_millis(30));
+        let key = "exported-circuit.test:443";
+        let state = |key: &str| Metrics::new().open_circuits().into_iter().find(|(host, _)| host == key).map(|(_, state)| state);
+        breaker.record_failure(key);
+        assert_eq!(state(key), Some("open"));
+        assert!(Metrics::new().get("circuit_open_hosts") >= 1);
+
+        std::thread::sleep(Duration::from_millis(40));
+        breaker.acquire(key).unwrap().record(true);
+        assert_eq!(state(key), None);
+    }
+}
@@ -0,0 +1,245 @@
+// CONNECT 요청에 이어 붙어 온 바이트 처리
+// 클라이언트가 200 응답을 기다리지 않고 CONNECT 헤더 뒤에 데이터를 함께 보내면 그 바이트는 요청을 읽을 때
+// 이미 버퍼로 들어와 있습니다. 그대로 버리면 터널 데이터가 깨지고, HTTP 요청을 이어 붙인 경우에는
+// 프록시와 업스트림이 요청 경계를 다르게 해석하는 요청 밀반입(smuggling)이 될 수 있으므로
+// connect_pipelining 설정에 따라 터널 데이터로 전달하거나 거부합니다.
+// 전달하는 데이터는 PrefixedStream으로 연결 앞에 되돌려 놓으므로, 이어 붙은 ClientHello도 가로채기와 검사를 거칩니다.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+
+use crate::config::ConnectPipelinePolicy;
+use crate::tls::client_hello::TLS_HANDSHAKE_RECORD;
+
+/// HTTP 요청 시작으로 판단하는 메서드 (일부만 받은 경우도 접두사로 비교)
+const PIPELINED_REQUEST_METHODS: [&str; 9] = ["GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS", "PATCH", "TRACE", "CONNECT"];
+
+/// 요청 줄 메서드 토큰 최대 길이 (이보다 길면 HTTP 요청으로 보지 않음)
+const MAX_METHOD_TOKEN_LEN: usize = 20;
+
+/// CONNECT 뒤에 이어 붙어 온 데이터 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelinedPayload {
+    /// TLS 핸드셰이크 레코드 (200 응답 전에 보낸 ClientHello)
+    Tls,
+    /// HTTP 요청 줄 (요청 밀반입 의심)
+    HttpRequest,
+    /// 어느 쪽인지 판단할 수 없는 데이터
+    Unknown,
+}
+
+impl PipelinedPayload {
+    /// 로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelinedPayload::Tls => "tls",
+            PipelinedPayload::HttpRequest => "http_request",
+            PipelinedPayload::Unknown => "unknown",
+        }
+    }
+}
+
+/// 이어 붙은 데이터 처리 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineAction {
+    /// 터널 데이터의 시작으로 업스트림에 전달
+    Forward,
+    /// 400으로 거부
+    Reject,
+}
+
+/// 요청 버퍼에서 CONNECT 헤더 끝 뒤에 이어 붙은 데이터의 시작 위치 (없으면 None)
+pub fn pipelined_offset(request: &[u8]) -> Option<usize> {
+    request.windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|end| end + 4)
+        .filter(|offset| *offset < request.len())
+}
+
+/// 이어 붙은 데이터 종류 판별
+pub fn classify(bytes: &[u8]) -> PipelinedPayload {
+    if bytes.first() == Some(&TLS_HANDSHAKE_RECORD) {
+        return PipelinedPayload::Tls;
+    }
+
+    // 대문자 토큰 뒤에 공백이 오면 요청 줄로 판단 (메서드 이름은 확장될 수 있으므로 목록과 비교하지 않음)
+    let token_len = bytes.iter().take_while(|b| b.is_ascii_uppercase()).count();
+    if token_len > 0 && token_len <= MAX_METHOD_TOKEN_LEN && bytes.get(token_len) == Some(&b' ') {
+        return PipelinedPayload::HttpRequest;
+    }
+    // 공백 전까지만 받은 경우 알려진 메서드의 접두사이면 요청으로 판단
+    if token_len == bytes.len() {
+        let token = String::from_utf8_lossy(bytes);
+        if PIPELINED_REQUEST_METHODS.iter().any(|method| method.starts_with(token.as_ref())) {
+            return PipelinedPayload::HttpRequest;
+        }
+    }
+    PipelinedPayload::Unknown
+}
+
+/// 설정과 데이터 종류에 따른 처리
+pub fn decide(policy: ConnectPipelinePolicy, payload: PipelinedPayload) -> PipelineAction {
+    match (policy, payload) {
+        (ConnectPipelinePolicy::Reject, _) => PipelineAction::Reject,
+        (ConnectPipelinePolicy::RejectRequests, PipelinedPayload::HttpRequest) => PipelineAction::Reject,
+        _ => PipelineAction::Forward,
+    }
+}
+
+/// 판단할 수 없어 기록/집계할 경우인지 (종류를 알 수 없거나, 요청처럼 보이지만 설정에 따라 전달)
+pub fn is_ambiguous(payload: PipelinedPayload, action: PipelineAction) -> bool {
+    match payload {
+        PipelinedPayload::Unknown => true,
+        PipelinedPayload::HttpRequest => action == PipelineAction::Forward,
+        PipelinedPayload::Tls => false,
+    }
+}
+
+/// 이미 읽은 바이트를 앞에 되돌려 놓은 클라이언트 연결
+///
+/// 읽기와 `peek`은 앞에 둔 바이트부터 돌려주고, 쓰기는 그대로 소켓에 씁니다.
+#[derive(Debug)]
+pub struct PrefixedStream {
+    prefix: Vec<u8>,
+    pos: usize,
+    inner: TcpStream,
+}
+
+impl PrefixedStream {
+    /// `prefix`를 먼저 읽도록 연결 감싸기
+    pub fn new(prefix: Vec<u8>, inner: TcpStream) -> Self {
+        Self { prefix, pos: 0, inner }
+    }
+
+    /// 되돌려 놓은 바이트가 없는 연결
+    pub fn plain(inner: TcpStream) -> Self {
+        Self::new(Vec::new(), inner)
+    }
+
+    /// 아직 읽지 않은 앞부분 바이트
+    fn remaining(&self) -> &[u8] {
+        &self.prefix[self.pos..]
+    }
+
+    /// 소켓 참조 (주소 조회와 소켓 옵션용)
+    pub fn tcp(&self) -> &TcpStream {
+        &self.inner
+    }
+
+    /// 읽지 않은 앞부분 바이트와 소켓으로 분리
+    pub fn into_parts(self) -> (Vec<u8>, TcpStream) {
+        let mut prefix = self.prefix;
+        prefix.drain(..self.pos);
+        (prefix, self.inner)
+    }
+
+    /// 데이터를 소비하지 않고 읽기 (앞부분 바이트가 있으면 기다리지 않고 소켓에 이미 도착한 만큼만 덧붙임)
+    pub async fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.remaining();
+        if remaining.is_empty() {
+            return self.inner.peek(buf).await;
+        }
+
+        let n = remaining.len().min(buf.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        if n == buf.len() {
+            return Ok(n);
+        }
+        let mut rest = ReadBuf::new(&mut buf[n..]);
+        let extra = std::future::poll_fn(|cx| match self.inner.poll_peek(cx, &mut rest) {
+            Poll::Ready(Ok(extra)) => Poll::Ready(extra),
+            _ => Poll::Ready(0),
+        }).await;
+        Ok(n + extra)
+    }
+}
+
+impl AsyncRead for PrefixedStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let remaining = self.remaining();
+        if remaining.is_empty() {
+            return Pin::new(&mut self.inner).poll_read(cx, buf);
+        }
+        let n = remaining.len().min(buf.remaining());
+        buf.put_slice(&remaining[..n]);
+        self.pos += n;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for PrefixedStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    async fn pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        (client, server)
+    }
+
+    #[tokio::test]
+    async fn prefix_is_read_before_socket() {
+        let (mut client, server) = pair().await;
+        client.write_all(b" world").await.unwrap();
+        drop(client);
+
+        let mut stream = PrefixedStream::new(b"hello".to_vec(), server);
+        let mut peeked = [0u8; 3];
+        assert_eq!(stream.peek(&mut peeked).await.unwrap(), 3);
+        assert_eq!(&peeked, b"hel");
+
+        let mut read = Vec::new();
+        stream.read_to_end(&mut read).await.unwrap();
+        assert_eq!(read, b"hello world");
+    }
+
+    #[tokio::test]
+    async fn peek_appends_arrived_socket_bytes() {
+        let (mut client, server) = pair().await;
+        client.write_all(b"def").await.unwrap();
+
+        let stream = PrefixedStream::new(b"abc".to_vec(), server);
+        let mut peeked = [0u8; 16];
+        let mut n = 0;
+        for _ in 0..50 {
+            n = stream.peek(&mut peeked).await.unwrap();
+            if n == 6 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(&peeked[..n], b"abcdef");
+    }
+
+    #[tokio::test]
+    async fn into_parts_keeps_unread_prefix() {
+        let (_client, server) = pair().await;
+        let mut stream = PrefixedStream::new(b"abcdef".to_vec(), server);
+        let mut read = [0u8; 2];
+        stream.read_exact(&mut read).await.unwrap();
+
+        let (prefix, _) = stream.into_parts();
+        assert_eq!(prefix, b"cdef");
+    }
+}
@@ -0,0 +1,359 @@
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpSocket, TcpStream};
+
+use crate::config::{AddressFamilyOrder, Config, ConnectFallbackConfig, EgressPool, IpFamilyPolicy};
+use crate::metrics::{DnsOutcome, Metrics};
+use crate::proxy::breaker::circuit_breaker;
+use crate::proxy::dns_prewarm::prewarmed_addresses;
+use crate::proxy::dscp::mark_upstream;
+use crate::proxy::parents::{parent_breaker, parent_key};
+use crate::proxy::sockbuf::apply_socket_buffers;
+use crate::proxy::target::{dial_address, format_authority, parse_authority};
+use crate::proxy::timing::UpstreamTiming;
+
+/// 상위 프록시 CONNECT 응답 헤더 최대 길이
+const PARENT_RESPONSE_MAX_LEN: usize = 8192;
+
+/// 업스트림에 TCP 연결 (egress가 주어지면 해당 출구의 로컬 주소 바인딩 또는 상위 프록시 사용)
+///
+/// 회로가 열려 있으면 연결을 시도하지 않고 즉시 `ConnectionRefused` 오류를 반환하고,
+/// 이름 해석이 `dns_timeout_ms`를 넘으면 연결 실패와 구분되는 `dns-timeout` 오류를 반환합니다.
+/// `upstream_timing_enabled`이면 이름 해석과 연결에 걸린 시간을 `timing`에 기록하고,
+/// `upstream_dscp`가 설정되어 있으면 연결된 소켓에 대상 호스트의 DSCP 값을 표시하고,
+/// `upstream_socket_buffers`가 설정되어 있으면 송수신 버퍼 크기를 적용합니다.
+pub async fn connect_upstream(host: &str, port: u16, config: &Config, egress: Option<&EgressPool>,
+                              timing: &mut UpstreamTiming) -> io::Result<TcpStream> {
+    // 상위 프록시 경유 연결의 성패는 대상 호스트 상태와 무관하므로 회로 차단기를 거치지 않음
+    if let Some(parent) = egress.and_then(|pool| pool.parent_proxy.as_deref()) {
+        return connect_through_parent(parent, host, port, config, timing).await;
+    }
+    let source = egress.and_then(|pool| pool.source_address);
+
+    let breaker = circuit_breaker();
+    let key = format_authority(host, port);
+    let circuit_open = |remaining: Duration| io::Error::new(
+        io::ErrorKind::ConnectionRefused,
+        format!("circuit open for {} (retry in {}s)", key, remaining.as_millis().div_ceil(1000)),
+    );
+
+    if let Some(remaining) = breaker.as_ref().and_then(|breaker| breaker.retry_in(&key)) {
+        return Err(circuit_open(remaining));
+    }
+
+    // 이름 해석 실패는 업스트림 장애가 아니므로 회로 차단기에 반영하지 않음 (시험 연결은 해석 후에 잡음)
+    let started = UpstreamTiming::start(config.upstream_timing_enabled);
+    let addrs = resolve(host, port, config).await?;
+    timing.dns = started.map(|started| started.elapsed());
+
+    // 연결이 끝나기 전에 취소되면 허가가 버려지면서 시험 연결을 되돌림
+    let permit = match breaker.as_deref().map(|breaker| breaker.acquire(&key)).transpose() {
+        Ok(permit) => permit,
+        Err(remaining) => return Err(circuit_open(remaining)),
+    };
+
+    let started = UpstreamTiming::start(config.upstream_timing_enabled);
+    let result = connect_any(host, &addrs, source, &config.connect_fallback).await;
+    if let Ok(stream) = &result {
+        timing.connect = started.map(|started| started.elapsed());
+        mark_upstream(stream, host, &config.upstream_dscp);
+        apply_socket_buffers(stream, &config.upstream_socket_buffers, "업스트림", host);
+    }
+
+    if let Some(permit) = permit {
+        permit.record(result.is_ok());
+    }
+    result
+}
+
+/// 제한 시간 안에 호스트 이름 해석 (`upstream_ip_family`에 따라 주소 체계를 거르거나 정렬)
+///
+/// `static_hosts`에 지정한 이름은 DNS를 조회하지 않고 지정한 주소를 순서대로 사용하고,
+/// `dns_prewarm.hosts`의 이름은 시작 시 미리 해석해 둔(주기적으로 갱신하는) 주소를 사용합니다.
+/// `ipv4_only`/`ipv6_only`는 해석 결과에서 다른 주소 체계의 주소를 버리므로 그 체계로는 연결을 시도하지 않습니다.
+async fn resolve(host: &str, port: u16, config: &Config) -> io::Result<Vec<SocketAddr>> {
+    let policy = config.upstream_ip_family;
+    if let Some(ips) = config.static_host_addresses(host) {
+        debug!("static_hosts 주소 사용: {} -> {:?}", host, ips);
+        let addrs = ips.iter().map(|ip| SocketAddr::new(*ip, port)).collect();
+        return apply_family_policy(host, addrs, policy);
+    }
+    if let Some(ips) = prewarmed_addresses(host) {
+        debug!("미리 해석한 주소 사용: {} -> {:?}", host, ips);
+        Metrics::new().dns_lookup(DnsOutcome::CacheHit);
+        let addrs = ips.iter().map(|ip| SocketAddr::new(*ip, port)).collect();
+        return apply_family_policy(host, addrs, policy);
+    }
+    let result = lookup(host, port, config).await;
+    // IP 리터럴은 DNS를 조회하지 않으므로 집계하지 않음
+    if host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().is_err() {
+        Metrics::new().dns_lookup(match &result {
+            Ok(_) => DnsOutcome::Success,
+            Err(e) => dns_failure_outcome(e),
+        });
+    }
+    apply_family_policy(host, result?, policy)
+}
+
+/// 이름 해석 오류를 결과 유형으로 분류 (getaddrinfo 오류 코드로 구분)
+pub(crate) fn dns_failure_outcome(e: &io::Error) -> DnsOutcome {
+    if e.kind() == io::ErrorKind::TimedOut {
+        return DnsOutcome::Timeout;
+    }
+    match gai_error_code(e) {
+        Some(libc::EAI_NONAME) => DnsOutcome::NxDomain,
+        Some(libc::EAI_AGAIN | libc::EAI_FAIL) => DnsOutcome::ServFail,
+        _ => DnsOutcome::Error,
+    }
+}
+
+/// 분류에 쓰는 getaddrinfo 오류 코드
+const GAI_ERROR_CODES: [libc::c_int; 3] = [libc::EAI_NONAME, libc::EAI_AGAIN, libc::EAI_FAIL];
+
+/// 이름 해석 오류의 getaddrinfo 오류 코드 (EAI_*)
+///
+/// 표준 라이브러리는 코드를 `failed to lookup address information: <gai_strerror(code)>` 문구로만 남기므로,
+/// 같은 libc가 각 코드에 돌려주는 문구와 통째로 같은 코드를 찾습니다 (EAI_SYSTEM은 OS 오류로 바뀌어 해당 없음).
+fn gai_error_code(e: &io::Error) -> Option<libc::c_int> {
+    if e.raw_os_error().is_some() {
+        return None;
+    }
+    let message = e.to_string();
+    let detail = message.strip_prefix("failed to lookup address information: ")?;
+    GAI_ERROR_CODES.into_iter().find(|code| gai_strerror(*code) == detail)
+}
+
+/// getaddrinfo 오류 코드의 설명 문구
+fn gai_strerror(code: libc::c_int) -> String {
+    // SAFETY: gai_strerror는 모든 코드에 대해 정적 문자열을 반환
+    unsafe { std::ffi::CStr::from_ptr(libc::gai_strerror(code)) }.to_string_lossy().into_owned()
+}
+
+/// 제한 시간(`dns_timeout_ms`) 안에 DNS로 이름 해석 (`static_hosts`와 미리 해석한 주소는 보지 않음)
+pub(crate) async fn lookup(host: &str, port: u16, config: &Config) -> io::Result<Vec<SocketAddr>> {
+    let timeout = Duration::from_millis(config.dns_timeout_ms);
+    let policy = config.upstream_ip_family;
+    let query = async {
+        let addrs = tokio::net::lookup_host(dial_address(host, port)).await?;
+        // 한 주소 체계만 쓰면 다른 체계의 주소는 미리 해석한 목록에도 남기지 않음
+        Ok::<_, io::Error>(match policy {
+            IpFamilyPolicy::Ipv4Only => addrs.filter(|addr| addr.is_ipv4()).collect(),
+            IpFamilyPolicy::Ipv6Only => addrs.filter(|addr| addr.is_ipv6()).collect(),
+            _ => addrs.collect::<Vec<_>>(),
+        })
+    };
+
+    match tokio::time::timeout(timeout, query).await {
+        Ok(addrs) => addrs,
+        Err(_) => {
+            warn!("dns-timeout: {} 이름 해석이 {}ms를 초과했습니다", host, config.dns_timeout_ms);
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("dns-timeout: resolving {} exceeded {}ms", host, config.dns_timeout_ms),
+            ))
+        }
+    }
+}
+
+/// 해석 결과에 주소 체계 정책 적용 (남은 주소가 없으면 오류)
+fn apply_family_policy(host: &str, mut addrs: Vec<SocketAddr>, policy: IpFamilyPolicy) -> io::Result<Vec<SocketAddr>> {
+    match policy {
+        IpFamilyPolicy::DualStack => return Ok(addrs),
+        IpFamilyPolicy::Ipv4Only => addrs.retain(|addr| addr.is_ipv4()),
+        IpFamilyPolicy::Ipv6Only => addrs.retain(|addr| addr.is_ipv6()),
+        // 같은 주소 체계 안에서는 해석 결과 순서 유지
+        IpFamilyPolicy::PreferIpv4 => addrs.sort_by_key(|addr| addr.is_ipv6()),
+        IpFamilyPolicy::PreferIpv6 => addrs.sort_by_key(|addr| addr.is_ipv4()),
+    }
+    if addrs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            format!("no address of {} allowed by upstream_ip_family {}", host, policy.name()),
+        ));
+    }
+    Ok(addrs)
+}
+
+/// 해석된 주소에 주소 체계 순서대로 연결 시도 (첫 성공 반환)
+///
+/// 먼저 시도할 주소 체계의 주소를 최대 `attempts_per_family`개 시도하고, 모두 실패하면 같은 해석 결과의
+/// 다른 주소 체계로 넘어갑니다. 로컬 주소가 주어지면 같은 주소 체계만 시도합니다.
+async fn connect_any(host: &str, addrs: &[SocketAddr], source: Option<IpAddr>, fallback: &ConnectFallbackConfig) -> io::Result<TcpStream> {
+    let attempt_timeout = Duration::from_millis(fallback.attempt_timeout_ms);
+    let mut last_error = None;
+    let mut failed = 0;
+
+    for (index, ipv6) in family_sequence(addrs, source, fallback.family_order).into_iter().enumerate() {
+        for addr in addrs.iter().filter(|addr| addr.is_ipv6() == ipv6).take(fallback.attempts_per_family) {
+            let attempt = async {
+                match source {
+                    Some(source) => connect_from(source, *addr).await,
+                    None => TcpStream::connect(addr).await,
+                }
+            };
+            let result = match tokio::time::timeout(attempt_timeout, attempt).await {
+                Ok(result) => result,
+                Err(_) => Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("connect to {} timed out after {}ms", addr, fallback.attempt_timeout_ms),
+                )),
+            };
+
+            match result {
+                Ok(stream) if index > 0 => {
+                    info!("주소 체계 대체 연결 성공: {} -> {} ({}, 앞선 {} 연결 {}회 실패)",
+                          host, addr, family_name(ipv6), family_name(!ipv6), failed);
+                    return Ok(stream);
+                },
+                Ok(stream) => {
+                    debug!("업스트림 연결 성공: {} -> {} ({})", host, addr, family_name(ipv6));
+                    return Ok(stream);
+                },
+                Err(e) => {
+                    debug!("업스트림 연결 실패: {} -> {} ({}): {}", host, addr, family_name(ipv6), e);
+                    failed += 1;
+                    last_error = Some(e);
+                },
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| match source {
+        Some(source) => io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            format!("no address of {} matches the address family of egress source {}", host, source),
+        ),
+        None => io::Error::new(io::ErrorKind::NotFound, "no address resolved"),
+    }))
+}
+
+/// 연결을 시도할 주소 체계 순서 (true = IPv6)
+fn family_sequence(addrs: &[SocketAddr], source: Option<IpAddr>, order: AddressFamilyOrder) -> Vec<bool> {
+    if let Some(source) = source {
+        return vec![source.is_ipv6()];
+    }
+
+    let first = match order {
+        AddressFamilyOrder::Resolver => addrs.first().is_some_and(|addr| addr.is_ipv6()),
+        AddressFamilyOrder::Ipv6First => true,
+        AddressFamilyOrder::Ipv4First => false,
+    };
+    vec![first, !first]
+}
+
+fn family_name(ipv6: bool) -> &'static str {
+    if ipv6 { "IPv6" } else { "IPv4" }
+}
+
+/// 지정한 로컬 주소에 바인딩한 소켓으로 연결
+async fn connect_from(source: IpAddr, addr: SocketAddr) -> io::Result<TcpStream> {
+    let socket = if source.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+    socket.bind(SocketAddr::new(source, 0))?;
+    socket.connect(addr).await
+}
+
+/// 상위 프록시에 CONNECT 요청을 보내 대상까지의 터널 연결
+///
+/// `parent_proxies`를 설정했으면 상위 프록시별 회로 차단기로 연결 성패를 추적해, 연속 실패한 상위 프록시는
+/// 대기 시간 동안 즉시 실패시킵니다. 상위 프록시가 CONNECT를 거절한 응답(대상 문제)은 실패로 세지 않습니다.
+/// 구간 시간은 상위 프록시 기준입니다 (연결 시간에 CONNECT 응답 대기 포함).
+async fn connect_through_parent(parent: &str, host: &str, port: u16, config: &Config,
+                                timing: &mut UpstreamTiming) -> io::Result<TcpStream> {
+    let breaker = parent_breaker();
+    let key = parent_key(parent);
+    // 연결이 끝나기 전에 취소되면 허가가 버려지면서 시험 연결을 되돌림
+    let permit = match breaker.as_deref().map(|breaker| breaker.acquire(&key)).transpose() {
+        Ok(permit) => permit,
+        Err(remaining) => return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("circuit open for parent proxy {} (retry in {}s)", parent, remaining.as_millis().div_ceil(1000)),
+        )),
+    };
+
+    let mut answered = false;
+    let result = parent_connect(parent, host, port, config, timing, &mut answered).await;
+    if let Some(permit) = permit {
+        permit.record(result.is_ok() || answered);
+    }
+    result
+}
+
+/// 상위 프록시 연결과 CONNECT 핸드셰이크 (상위 프록시가 상태 줄로 응답했으면 `answered` 설정)
+async fn parent_connect(parent: &str, host: &str, port: u16, config: &Config,
+                        timing: &mut UpstreamTiming, answered: &mut bool) -> io::Result<TcpStream> {
+    let (parent_host, parent_port) = parse_authority(parent, 0)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("invalid parent proxy: {}", parent)))?;
+    let started = UpstreamTiming::start(config.upstream_timing_enabled);
+    let addrs = resolve(&parent_host, parent_port, config).await?;
+    timing.dns = started.map(|started| started.elapsed());
+
+    let started = UpstreamTiming::start(config.upstream_timing_enabled);
+    let mut stream = connect_any(&parent_host, &addrs, None, &config.connect_fallback).await?;
+    // 상위 프록시까지의 구간도 대상 호스트 기준으로 표시
+    mark_upstream(&stream, host, &config.upstream_dscp);
+    apply_socket_buffers(&stream, &config.upstream_socket_buffers, "업스트림", &parent_host);
+
+    let target = format_authority(host, port);
+    let request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n\r\n", target);
+
+    let handshake = async {
+        stream.write_all(request.as_bytes()).await?;
+
+        // 터널 데이터를 소비하지 않도록 응답 헤더 끝까지 한 바이트씩 읽기
+        let mut response = Vec::new();
+        let mut byte = [0u8; 1];
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= PARENT_RESPONSE_MAX_LEN || stream.read(&mut byte).await? == 0 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "parent proxy closed or sent an oversized CONNECT response"));
+            }
+            response.push(byte[0]);
+        }
+
+        *answered = true;
+        let response = String::from_utf8_lossy(&response);
+        let status_line = response.lines().next().unwrap_or("");
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("parent proxy {} refused CONNECT {}: {}", parent, target, status_line),
+            ));
+        }
+        Ok(())
+    };
+
+    match tokio::time::timeout(Duration::from_millis(config.timeout_ms as u64), handshake).await {
+        Ok(Ok(())) => {
+            timing.connect = started.map(|started| started.elapsed());
+            Ok(stream)
+        },
+        Ok(Err(e)) => Err(e),
+        Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, format!("parent proxy {} CONNECT {} timed out", parent, target))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dns_failures_are_classified_by_gai_code() {
+        let gai_error = |code| io::Error::other(format!("failed to lookup address information: {}", gai_strerror(code)));
+        let cases = [
+            (gai_error(libc::EAI_NONAME), DnsOutcome::NxDomain),
+            (gai_error(libc::EAI_AGAIN), DnsOutcome::ServFail),
+            (gai_error(libc::EAI_FAIL), DnsOutcome::ServFail),
+            (gai_error(libc::EAI_MEMORY), DnsOutcome::Error),
+            (io::Error::new(io::ErrorKind::TimedOut, "dns-timeout"), DnsOutcome::Timeout),
+            (io::Error::other("host contains NUL byte"), DnsOutcome::Error),
+            // 문구 끝만 같은 다른 오류는 코드로 보지 않음
+            (io::Error::other(format!("proxy said: {}", gai_strerror(libc::EAI_NONAME))), DnsOutcome::Error),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(dns_failure_outcome(&error), expected, "{}", error);
+        }
+    }
+}
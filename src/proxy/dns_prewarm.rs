@@ -0,0 +1,119 @@
+// 업스트림 이름 미리 해석 (dns_prewarm)
+// 지연에 민감한 호스트를 서버 시작 시 해석해 두고, 첫 요청부터 DNS 조회 없이 그 주소로 연결합니다.
+// 백그라운드에서 `refresh_seconds`마다 다시 해석하며, 갱신에 실패하면 `max_age_seconds`까지 이전 주소를 계속 씁니다.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use tokio::task::{JoinHandle, JoinSet};
+
+use crate::config::Config;
+use crate::proxy::dialer::lookup;
+
+// 미리 해석한 호스트별 주소 (호스트는 소문자, 끝의 점 제거)
+static PREWARMED: Lazy<RwLock<HashMap<String, Prewarmed>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+// 해석한 주소와 만료 시각 (해석 시각 + max_age_seconds)
+struct Prewarmed {
+    ips: Vec<IpAddr>,
+    expires_at: Instant,
+}
+
+fn normalize(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// 미리 해석해 둔 주소 (목록에 없거나 아직 해석하지 못했거나 만료된 호스트는 None)
+pub fn prewarmed_addresses(host: &str) -> Option<Vec<IpAddr>> {
+    let prewarmed = PREWARMED.read().unwrap();
+    if prewarmed.is_empty() {
+        return None;
+    }
+    prewarmed.get(&normalize(host))
+        .filter(|entry| entry.expires_at > Instant::now())
+        .map(|entry| entry.ips.clone())
+}
+
+/// `dns_prewarm.hosts`를 동시에 해석해 저장하고 해석에 성공한 호스트 수 반환
+///
+/// 실패한 호스트는 경고만 남기며, 이전에 해석한 주소가 있으면 만료될 때까지 그대로 둡니다.
+pub async fn prewarm_dns(config: &Arc<Config>) -> usize {
+    let max_age = Duration::from_secs(config.dns_prewarm.max_age_seconds);
+    let mut lookups = JoinSet::new();
+    for host in &config.dns_prewarm.hosts {
+        let host = normalize(host);
+        let config = Arc::clone(config);
+        lookups.spawn(async move {
+            let result = lookup(&host, 0, &config).await;
+            (host, result)
+        });
+    }
+
+    let mut resolved = 0;
+    while let Some(joined) = lookups.join_next().await {
+        let Ok((host, result)) = joined else {
+            continue;
+        };
+        match result {
+            Ok(addrs) if !addrs.is_empty() => {
+                let ips: Vec<IpAddr> = addrs.iter().map(|addr| addr.ip()).collect();
+                let entry = Prewarmed { ips: ips.clone(), expires_at: Instant::now() + max_age };
+                let previous = PREWARMED.write().unwrap().insert(host.clone(), entry);
+                if previous.is_none_or(|previous| previous.ips != ips) {
+                    debug!("DNS 미리 해석: {} -> {:?}", host, ips);
+                }
+                resolved += 1;
+            },
+            Ok(_) => warn!("DNS 미리 해석 실패: {} (주소 없음)", host),
+            Err(e) => warn!("DNS 미리 해석 실패: {} ({})", host, e),
+        }
+    }
+    // 갱신에 계속 실패해 만료된 주소 정리
+    let now = Instant::now();
+    PREWARMED.write().unwrap().retain(|host, entry| {
+        let live = entry.expires_at > now;
+        if !live {
+            warn!("DNS 미리 해석 주소 만료: {} (max_age_seconds 동안 갱신 실패)", host);
+        }
+        live
+    });
+    resolved
+}
+
+/// `refresh_seconds`마다 미리 해석한 주소를 갱신하는 백그라운드 태스크 시작
+fn spawn_dns_refresh(config: Arc<Config>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let period = Duration::from_secs(config.dns_prewarm.refresh_seconds);
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            let resolved = prewarm_dns(&config).await;
+            debug!("DNS 미리 해석 갱신: {}/{}개 호스트", resolved, config.dns_prewarm.hosts.len());
+        }
+    })
+}
+
+/// 서버 시작 시 미리 해석하고 갱신 태스크 시작 (실패해도 시작은 계속)
+///
+/// 반환한 갱신 태스크는 서버 종료 시 `stop_dns_prewarm`으로 멈춥니다.
+pub async fn init_dns_prewarm(config: &Arc<Config>) -> Option<JoinHandle<()>> {
+    if !config.dns_prewarm.is_enabled() {
+        return None;
+    }
+    let resolved = prewarm_dns(config).await;
+    info!("DNS 미리 해석: {}/{}개 호스트 (갱신 주기 {}초)",
+          resolved, config.dns_prewarm.hosts.len(), config.dns_prewarm.refresh_seconds);
+    Some(spawn_dns_refresh(Arc::clone(config)))
+}
+
+/// 갱신 태스크를 멈추고 미리 해석한 주소를 비움 (서버 종료 시)
+pub fn stop_dns_prewarm(refresh: Option<JoinHandle<()>>) {
+    if let Some(refresh) = refresh {
+        refresh.abort();
+        PREWARMED.write().unwrap().clear();
+    }
+}
@@ -0,0 +1,86 @@
+// 업스트림 소켓 DSCP 표시 (upstream_dscp 설정 시)
+// 네트워크 장비가 DSCP로 QoS를 적용할 수 있도록 업스트림 연결 직후 IPv4 TOS 또는 IPv6 traffic class에 값을 씁니다.
+// 하위 2비트(ECN)는 커널이 관리하므로 DSCP 값을 2비트 올려서 설정합니다.
+
+use std::io;
+
+use log::{debug, warn};
+use nix::sys::socket::{getsockopt, setsockopt, sockopt};
+use tokio::net::TcpStream;
+
+use crate::config::UpstreamDscpConfig;
+
+/// 호스트에 적용할 DSCP 값 (정확한 호스트, 가까운 `*.상위도메인`, default 순)
+pub fn dscp_for(settings: &UpstreamDscpConfig, host: &str) -> Option<u8> {
+    if settings.overrides.is_empty() {
+        return settings.default;
+    }
+    let host = normalize(host);
+    let find = |key: &str| settings.overrides.iter()
+        .find(|(pattern, _)| normalize(pattern) == key)
+        .map(|(_, dscp)| *dscp);
+
+    if let Some(dscp) = find(&host) {
+        return Some(dscp);
+    }
+    let mut parent = host.as_str();
+    while let Some((_, rest)) = parent.split_once('.') {
+        if let Some(dscp) = find(&format!("*.{}", rest)) {
+            return Some(dscp);
+        }
+        parent = rest;
+    }
+    settings.default
+}
+
+/// 연결된 업스트림 소켓에 DSCP 표시 (실패해도 연결은 그대로 사용)
+///
+/// 설정 후 getsockopt로 다시 읽어 실제 값이 다르면 경고를 남깁니다.
+pub fn mark_upstream(stream: &TcpStream, host: &str, settings: &UpstreamDscpConfig) {
+    if !settings.is_enabled() {
+        return;
+    }
+    let Some(dscp) = dscp_for(settings, host) else {
+        return;
+    };
+    let ipv6 = match stream.local_addr() {
+        Ok(addr) => addr.is_ipv6(),
+        Err(e) => {
+            warn!("업스트림 DSCP 표시 실패: {} (로컬 주소 확인 불가: {})", host, e);
+            return;
+        }
+    };
+
+    match apply(stream, ipv6, dscp) {
+        Ok(applied) if applied == dscp => {
+            debug!("업스트림 DSCP 표시: {} dscp={} ({} 0x{:02x})", host, dscp, field_name(ipv6), (dscp as u32) << 2);
+        },
+        Ok(applied) => {
+            warn!("업스트림 DSCP 표시 값 불일치: {} 설정 {}, 소켓 값 {} ({})", host, dscp, applied, field_name(ipv6));
+        },
+        Err(e) => {
+            warn!("업스트림 DSCP 표시 실패: {} dscp={} ({}): {}", host, dscp, field_name(ipv6), e);
+        },
+    }
+}
+
+/// 값을 설정하고 소켓에서 다시 읽은 DSCP 값 반환
+fn apply(stream: &TcpStream, ipv6: bool, dscp: u8) -> io::Result<u8> {
+    let value = (dscp as i32) << 2;
+    let applied = if ipv6 {
+        setsockopt(stream, sockopt::Ipv6TClass, &value)?;
+        getsockopt(stream, sockopt::Ipv6TClass)?
+    } else {
+        setsockopt(stream, sockopt::Ipv4Tos, &value)?;
+        getsockopt(stream, sockopt::Ipv4Tos)?
+    };
+    Ok((applied >> 2) as u8)
+}
+
+fn field_name(ipv6: bool) -> &'static str {
+    if ipv6 { "IPv6 traffic class" } else { "IPv4 TOS" }
+}
+
+fn normalize(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
@@ -0,0 +1,127 @@
+use std::borrow::Cow;
+
+use log::{info, warn};
+
+use crate::config::{Config, EgressPool};
+
+/// 요청 헤더(`egress_header`) 값으로 출구 선택
+///
+/// 헤더가 없거나 `egress_pools`에 없는 값이면 기본 출구(None)를 사용합니다.
+pub fn select_egress<'a>(config: &'a Config, request: &str, session_id: &str) -> Option<(&'a str, &'a EgressPool)> {
+    let header = config.egress_header.as_deref()?;
+    let value = header_value(request, header)?;
+
+    match config.egress_pools.get_key_value(value) {
+        Some((name, pool)) => {
+            info!("[Session:{}] 출구 선택: {} ({}: {})", session_id, name, header, value);
+            Some((name.as_str(), pool))
+        },
+        None => {
+            warn!("[Session:{}] 허용되지 않은 출구 값을 무시하고 기본 출구 사용: {}: {}", session_id, header, value);
+            None
+        }
+    }
+}
+
+/// 요청 헤더 블록에서 출구 선택 헤더 제거 (업스트림으로 전달하지 않음)
+pub fn strip_egress_header(config: &Config, request: &str) -> String {
+    let Some(header) = config.egress_header.as_deref() else {
+        return request.to_string();
+    };
+    let head_end = request.find("\r\n\r\n").map(|pos| pos + 2).unwrap_or(request.len());
+    let (head, body) = request.split_at(head_end);
+
+    let mut stripped = String::with_capacity(request.len());
+    for line in head.split_inclusive("\r\n") {
+        if !is_header(line, header) {
+            stripped.push_str(line);
+        }
+    }
+    stripped.push_str(body);
+    stripped
+}
+
+/// 전달할 요청 조각에서 출구 선택 헤더 줄 제거 (가로챈 연결의 keep-alive 요청마다, 조각 단위)
+///
+/// 조각은 요청 시작 줄부터이거나 아직 끝나지 않은 헤더 블록의 이어지는 부분이어야 합니다.
+/// 헤더 블록이 이 조각에서 끝났으면(또는 출구 선택 헤더를 쓰지 않으면) 두 번째 값이 true입니다.
+pub fn strip_egress_header_chunk<'a>(config: &Config, data: &'a [u8]) -> (Cow<'a, [u8]>, bool) {
+    let Some(header) = config.egress_header.as_deref() else {
+        return (Cow::Borrowed(data), true);
+    };
+    let finish = |stripped: Option<Vec<u8>>, rest: usize| match stripped {
+        Some(mut stripped) => {
+            stripped.extend_from_slice(&data[rest..]);
+            Cow::Owned(stripped)
+        },
+        None => Cow::Borrowed(data),
+    };
+
+    let mut stripped: Option<Vec<u8>> = None;
+    let mut pos = 0;
+    while let Some(len) = data[pos..].windows(2).position(|window| window == b"\r\n") {
+        let next = pos + len + 2;
+        let line = &data[pos..pos + len];
+        if line.is_empty() {
+            return (finish(stripped, pos), true);
+        }
+        if std::str::from_utf8(line).is_ok_and(|line| is_header(line, header)) {
+            stripped.get_or_insert_with(|| data[..pos].to_vec());
+        } else if let Some(stripped) = stripped.as_mut() {
+            stripped.extend_from_slice(&data[pos..next]);
+        }
+        pos = next;
+    }
+    (finish(stripped, pos), false)
+}
+
+/// 요청 헤더 블록에서 헤더 값 찾기 (이름은 대소문자 무시)
+fn header_value<'a>(request: &'a str, header: &str) -> Option<&'a str> {
+    request
+        .split("\r\n")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .find(|line| is_header(line, header))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, value)| value.trim())
+        .filter(|value| !value.is_empty())
+}
+
+fn is_header(line: &str, header: &str) -> bool {
+    line.split_once(':')
+        .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case(header))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        let mut config = Config::new();
+        config.egress_header = Some("X-Egress".to_string());
+        config
+    }
+
+    #[test]
+    fn strips_header_from_each_request_chunk() {
+        let config = config();
+        let (stripped, ended) = strip_egress_header_chunk(&config, b"GET /a HTTP/1.1\r\nHost: a\r\nx-egress: eu\r\n\r\nbody x-egress: eu\r\n");
+        assert!(ended);
+        assert_eq!(&stripped[..], b"GET /a HTTP/1.1\r\nHost: a\r\n\r\nbody x-egress: eu\r\n");
+
+        // 헤더 블록이 여러 조각에 걸치면 이어지는 조각에서도 제거
+        let (stripped, ended) = strip_egress_header_chunk(&config, b"GET /b HTTP/1.1\r\nHost: a\r\n");
+        assert!(!ended);
+        assert_eq!(&stripped[..], b"GET /b HTTP/1.1\r\nHost: a\r\n");
+        let (stripped, ended) = strip_egress_header_chunk(&config, b"X-Egress: us\r\nAccept: */*\r\n\r\n");
+        assert!(ended);
+        assert_eq!(&stripped[..], b"Accept: */*\r\n\r\n");
+    }
+
+    #[test]
+    fn keeps_chunk_without_egress_header_setting() {
+        let (stripped, ended) = strip_egress_header_chunk(&Config::new(), b"GET / HTTP/1.1\r\nX-Egress: eu\r\n");
+        assert!(ended);
+        assert!(matches!(stripped, Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,287 @@
+// 프록시가 직접 만드는 오류 응답 (차단, 과부하, 업스트림 연결 실패 등)
+// 요청의 Accept 헤더로 브라우저에는 HTML, API 클라이언트에는 JSON 본문을 보내며, 본문은 내장 템플릿 또는
+// error_pages 설정의 템플릿 파일에 요청 ID와 사유 코드 등을 채워 만듭니다.
+
+use std::io;
+
+use chrono::Local;
+use log::info;
+use once_cell::sync::OnceCell;
+
+use crate::config::ErrorPagesConfig;
+
+/// 내장 HTML 템플릿
+const DEFAULT_HTML_TEMPLATE: &str = "<!DOCTYPE html>\
+<html>\
+<head>\
+<title>{{title}}</title>\
+<meta charset=\"UTF-8\">\
+<style>\
+body { font-family: Arial, sans-serif; margin: 0; padding: 20px; background-color: #f5f5f5; }\
+.container { max-width: 800px; margin: 40px auto; background: white; padding: 30px; border-radius: 8px; box-shadow: 0 2px 10px rgba(0,0,0,0.1); }\
+h1 { color: #e74c3c; margin-top: 0; }\
+.info { background-color: #f8f9fa; padding: 15px; border-left: 4px solid #e74c3c; margin: 20px 0; }\
+</style>\
+</head>\
+<body>\
+<div class=\"container\">\
+<h1>{{title}}</h1>\
+<p>{{message}}</p>\
+<div class=\"info\">\
+<p><strong>호스트:</strong> {{host}}</p>\
+<p><strong>사유 코드:</strong> {{reason}} ({{status}})</p>\
+<p><strong>요청 ID:</strong> {{request_id}}</p>\
+<p><strong>시간:</strong> {{timestamp}}</p>\
+</div>\
+<p>문의사항이 있으시면 요청 ID와 함께 네트워크 관리자에게 연락하세요.</p>\
+</div>\
+</body>\
+</html>";
+
+/// 내장 JSON 템플릿
+const DEFAULT_JSON_TEMPLATE: &str = "{\"error\":{\"status\":{{status}},\"reason\":\"{{reason}}\",\"message\":\"{{message}}\",\
+\"detail\":\"{{detail}}\",\"host\":\"{{host}}\",\"request_id\":\"{{request_id}}\",\"timestamp\":\"{{timestamp}}\"}}";
+
+// 설정 파일에서 읽은 템플릿 (초기화 전에는 내장 템플릿 사용)
+static ERROR_TEMPLATES: OnceCell<ErrorTemplates> = OnceCell::new();
+
+/// 오류 응답 본문 템플릿
+struct ErrorTemplates {
+    html: String,
+    json: String,
+}
+
+/// 오류 응답 사유
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorReason {
+    /// 차단 규칙에 일치
+    Blocked,
+    /// 과부하(전체/호스트별 연결 한도, 초당 요청 수 한도)
+    Overload,
+    /// 업스트림 연결 실패
+    UpstreamUnreachable,
+    /// 업스트림 연결 또는 이름 해석 시간 초과
+    UpstreamTimeout,
+    /// 업스트림 회로 차단기가 열려 있음
+    CircuitOpen,
+    /// 잘못된 요청 (CONNECT 뒤에 이어 붙은 데이터 등)
+    BadRequest,
+    /// 점검 모드
+    Maintenance,
+}
+
+impl ErrorReason {
+    /// 응답 본문과 X-Proxy-Error 헤더의 사유 코드
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorReason::Blocked => "blocked",
+            ErrorReason::Overload => "overload",
+            ErrorReason::UpstreamUnreachable => "upstream_unreachable",
+            ErrorReason::UpstreamTimeout => "upstream_timeout",
+            ErrorReason::CircuitOpen => "circuit_open",
+            ErrorReason::BadRequest => "bad_request",
+            ErrorReason::Maintenance => "maintenance",
+        }
+    }
+
+    /// HTTP 상태 코드와 문구
+    pub fn status(&self) -> (u16, &'static str) {
+        match self {
+            ErrorReason::Blocked => (403, "Forbidden"),
+            ErrorReason::Overload | ErrorReason::CircuitOpen | ErrorReason::Maintenance => (503, "Service Unavailable"),
+            ErrorReason::UpstreamUnreachable => (502, "Bad Gateway"),
+            ErrorReason::UpstreamTimeout => (504, "Gateway Timeout"),
+            ErrorReason::BadRequest => (400, "Bad Request"),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            ErrorReason::Blocked => "접속이 차단되었습니다",
+            ErrorReason::Overload => "요청이 많아 잠시 처리할 수 없습니다",
+            ErrorReason::UpstreamUnreachable | ErrorReason::CircuitOpen => "사이트에 연결할 수 없습니다",
+            ErrorReason::UpstreamTimeout => "사이트 응답 시간이 초과되었습니다",
+            ErrorReason::BadRequest => "잘못된 요청입니다",
+            ErrorReason::Maintenance => "점검 중입니다",
+        }
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            ErrorReason::Blocked => "관리자 정책에 따라 요청하신 사이트에 대한 접속이 차단되었습니다.",
+            ErrorReason::Overload => "프록시가 처리할 수 있는 요청 수를 넘었습니다. 잠시 후 다시 시도하세요.",
+            ErrorReason::UpstreamUnreachable => "프록시가 요청하신 사이트에 연결하지 못했습니다.",
+            ErrorReason::UpstreamTimeout => "프록시가 정해진 시간 안에 요청하신 사이트에 연결하지 못했습니다.",
+            ErrorReason::CircuitOpen => "요청하신 사이트에 연속으로 연결하지 못해 잠시 연결을 시도하지 않습니다.",
+            ErrorReason::BadRequest => "프록시가 처리할 수 없는 형식의 요청입니다.",
+            ErrorReason::Maintenance => "프록시 점검 중이라 요청을 처리할 수 없습니다. 점검이 끝난 뒤 다시 시도하세요.",
+        }
+    }
+
+    /// 업스트림 연결 오류의 사유 (`connect_upstream` 오류 기준)
+    pub fn from_upstream_error(error: &io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::TimedOut => ErrorReason::UpstreamTimeout,
+            io::ErrorKind::ConnectionRefused if error.to_string().starts_with("circuit open") => ErrorReason::CircuitOpen,
+            _ => ErrorReason::UpstreamUnreachable,
+        }
+    }
+}
+
+/// 오류 응답 본문 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Html,
+    Json,
+}
+
+impl ErrorFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            ErrorFormat::Html => "text/html; charset=UTF-8",
+            ErrorFormat::Json => "application/json",
+        }
+    }
+}
+
+/// 요청 헤더의 Accept로 본문 형식 결정
+///
+/// text/html의 q 값이 JSON(application/json, +json)보다 높으면 HTML, 그렇지 않으면 JSON입니다.
+/// Accept가 없거나 `*/*`뿐이면 API 클라이언트로 보고 JSON을 보냅니다.
+pub fn negotiate(request: &[u8]) -> ErrorFormat {
+    let head_end = request.windows(4).position(|window| window == b"\r\n\r\n").unwrap_or(request.len());
+    let head = String::from_utf8_lossy(&request[..head_end]);
+    let Some(accept) = head.lines().skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("accept"))
+        .map(|(_, value)| value.to_ascii_lowercase()) else {
+        return ErrorFormat::Json;
+    };
+
+    let mut html = 0.0f32;
+    let mut json = 0.0f32;
+    for item in accept.split(',') {
+        let mut parts = item.split(';');
+        let media_type = parts.next().unwrap_or_default().trim();
+        let quality = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if media_type == "text/html" || media_type == "application/xhtml+xml" {
+            html = html.max(quality);
+        } else if media_type == "application/json" || media_type.ends_with("+json") {
+            json = json.max(quality);
+        }
+    }
+    if html > json { ErrorFormat::Html } else { ErrorFormat::Json }
+}
+
+/// 설정의 템플릿 파일 읽기 (지정하지 않은 형식은 내장 템플릿)
+pub fn init_error_pages(config: &ErrorPagesConfig) -> Result<(), String> {
+    let read = |path: &Option<String>, default: &str| match path {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("오류 응답 템플릿 읽기 실패 ({}): {}", path, e)),
+        None => Ok(default.to_string()),
+    };
+    let templates = ErrorTemplates {
+        html: read(&config.html_template, DEFAULT_HTML_TEMPLATE)?,
+        json: read(&config.json_template, DEFAULT_JSON_TEMPLATE)?,
+    };
+    if config.html_template.is_some() || config.json_template.is_some() {
+        info!("오류 응답 템플릿: HTML {}, JSON {}",
+              config.html_template.as_deref().unwrap_or("내장"), config.json_template.as_deref().unwrap_or("내장"));
+    }
+    let _ = ERROR_TEMPLATES.set(templates);
+    Ok(())
+}
+
+/// 프록시가 클라이언트에 보내는 오류 응답
+pub struct ErrorResponse<'a> {
+    reason: ErrorReason,
+    request_id: &'a str,
+    host: &'a str,
+    detail: Option<String>,
+    retry_after: Option<u64>,
+    html_template: Option<&'a str>,
+}
+
+impl<'a> ErrorResponse<'a> {
+    pub fn new(reason: ErrorReason, request_id: &'a str, host: &'a str) -> Self {
+        Self { reason, request_id, host, detail: None, retry_after: None, html_template: None }
+    }
+
+    /// 사유 설명 (JSON의 detail, 템플릿의 {{detail}})
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Retry-After 헤더 (초)
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self
+    }
+
+    /// 이 응답에만 쓸 HTML 템플릿 (점검 페이지 등, JSON 본문은 그대로)
+    pub fn with_html_template(mut self, template: Option<&'a str>) -> Self {
+        self.html_template = template;
+        self
+    }
+
+    /// 상태 줄과 헤더를 포함한 전체 HTTP 응답
+    pub fn render(&self, format: ErrorFormat) -> String {
+        let body = self.render_body(format);
+        let (status, status_text) = self.reason.status();
+        let retry_after = self.retry_after.map(|seconds| format!("Retry-After: {}\r\n", seconds)).unwrap_or_default();
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nX-Request-Id: {}\r\nX-Proxy-Error: {}\r\n{}Connection: close\r\n\r\n{}",
+            status, status_text, format.content_type(), body.len(), self.request_id, self.reason.code(), retry_after, body
+        )
+    }
+
+    /// 템플릿의 `{{이름}}` 자리에 형식에 맞게 이스케이프한 값 채우기
+    fn render_body(&self, format: ErrorFormat) -> String {
+        let template = match (ERROR_TEMPLATES.get(), format) {
+            (_, ErrorFormat::Html) if let Some(template) = self.html_template => template,
+            (Some(templates), ErrorFormat::Html) => templates.html.as_str(),
+            (Some(templates), ErrorFormat::Json) => templates.json.as_str(),
+            (None, ErrorFormat::Html) => DEFAULT_HTML_TEMPLATE,
+            (None, ErrorFormat::Json) => DEFAULT_JSON_TEMPLATE,
+        };
+        let status = self.reason.status().0.to_string();
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let values = [
+            ("status", status.as_str()),
+            ("reason", self.reason.code()),
+            ("title", self.reason.title()),
+            ("message", self.reason.message()),
+            ("detail", self.detail.as_deref().unwrap_or_default()),
+            ("host", self.host),
+            ("request_id", self.request_id),
+            ("timestamp", timestamp.as_str()),
+        ];
+
+        let mut body = template.to_string();
+        for (name, value) in values {
+            let placeholder = format!("{{{{{}}}}}", name);
+            if body.contains(&placeholder) {
+                body = body.replace(&placeholder, &escape(value, format));
+            }
+        }
+        body
+    }
+}
+
+fn escape(value: &str, format: ErrorFormat) -> String {
+    match format {
+        ErrorFormat::Html => value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;"),
+        ErrorFormat::Json => {
+            let quoted = serde_json::to_string(value).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        },
+    }
+}
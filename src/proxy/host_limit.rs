@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{debug, info, warn};
+use once_cell::sync::OnceCell;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::Config;
+use crate::metrics::Metrics;
+
+// 전역 업스트림 호스트별 동시 연결 제한 (upstream_host_limit 설정 시에만 초기화)
+static HOST_LIMITER: OnceCell<Arc<HostLimiter>> = OnceCell::new();
+
+/// 호스트 한 곳의 연결 슬롯
+struct HostSlots {
+    semaphore: Arc<Semaphore>,
+    limit: usize,
+    last_used: Instant,
+}
+
+/// 호스트별 동시 업스트림 연결 수 제한
+///
+/// 연결마다 슬롯을 하나 잡고 세션이 끝나 슬롯(permit)이 해제될 때 반납합니다.
+/// 한도에 도달하면 `queue_timeout_ms` 동안 빈 슬롯을 기다리고, 그래도 없으면 거부합니다.
+pub struct HostLimiter {
+    hosts: Mutex<HashMap<String, HostSlots>>,
+    default_limit: usize,
+    overrides: HashMap<String, usize>,
+    queue_timeout: Duration,
+    metrics: Arc<Metrics>,
+}
+
+/// 호스트 연결 한도 초과로 거부됨
+#[derive(Debug)]
+pub struct HostLimitExceeded {
+    pub host: String,
+    pub limit: usize,
+}
+
+impl fmt::Display for HostLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "upstream connection limit reached for {} ({} in flight)", self.host, self.limit)
+    }
+}
+
+/// 설정에 따라 전역 호스트별 연결 제한 초기화 (유휴 항목 정리 태스크 시작)
+pub fn init_host_limiter(config: &Config) {
+    let settings = &config.upstream_host_limit;
+    if !settings.is_enabled() {
+        return;
+    }
+
+    let limiter = Arc::new(HostLimiter {
+        hosts: Mutex::new(HashMap::new()),
+        default_limit: settings.max_connections,
+        overrides: settings.overrides.iter().map(|(host, limit)| (normalize(host), *limit)).collect(),
+        queue_timeout: Duration::from_millis(settings.queue_timeout_ms),
+        metrics: Metrics::new(),
+    });
+    if HOST_LIMITER.set(Arc::clone(&limiter)).is_err() {
+        return;
+    }
+    info!("업스트림 호스트별 동시 연결 제한 활성화: 기본 {} (0 - 제한 없음), 호스트별 지정 {} 개, 대기 {}ms",
+          settings.max_connections, settings.overrides.len(), settings.queue_timeout_ms);
+
+    let idle_expiry = Duration::from_secs(settings.idle_expiry_seconds);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(idle_expiry);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            limiter.expire_idle(idle_expiry);
+        }
+    });
+}
+
+/// 전역 호스트별 연결 제한 (비활성화 시 None)
+pub fn host_limiter() -> Option<Arc<HostLimiter>> {
+    HOST_LIMITER.get().cloned()
+}
+
+impl HostLimiter {
+    /// 호스트 연결 슬롯 획득 (제한 없는 호스트는 None)
+    ///
+    /// 반환된 permit을 업스트림 연결을 쓰는 동안 보관해야 합니다.
+    pub async fn acquire(&self, host: &str) -> Result<Option<OwnedSemaphorePermit>, HostLimitExceeded> {
+        let host = normalize(host);
+        let limit = self.overrides.get(&host).copied().unwrap_or(self.default_limit);
+        if limit == 0 {
+            return Ok(None);
+        }
+
+        let semaphore = {
+            let mut hosts = self.hosts.lock().unwrap();
+            let slots = hosts.entry(host.clone()).or_insert_with(|| HostSlots {
+                semaphore: Arc::new(Semaphore::new(limit)),
+                limit,
+                last_used: Instant::now(),
+            });
+            slots.last_used = Instant::now();
+            Arc::clone(&slots.semaphore)
+        };
+
+        if let Ok(permit) = Arc::clone(&semaphore).try_acquire_owned() {
+            return Ok(Some(permit));
+        }
+
+        if !self.queue_timeout.is_zero() {
+            self.metrics.upstream_host_limit_queued();
+            if let Ok(Ok(permit)) = tokio::time::timeout(self.queue_timeout, semaphore.acquire_owned()).await {
+                debug!("업스트림 연결 슬롯 대기 후 획득: {}", host);
+                return Ok(Some(permit));
+            }
+        }
+
+        warn!("업스트림 동시 연결 한도 초과로 거부: {} (한도 {})", host, limit);
+        self.metrics.upstream_host_limit_rejected();
+        Err(HostLimitExceeded { host, limit })
+    }
+
+    /// 사용 중인 연결이 없고 유휴 시간이 지난 호스트 항목 제거
+    fn expire_idle(&self, idle_expiry: Duration) {
+        let mut hosts = self.hosts.lock().unwrap();
+        let before = hosts.len();
+        hosts.retain(|_, slots| {
+            slots.semaphore.available_permits() < slots.limit || slots.last_used.elapsed() < idle_expiry
+        });
+        if hosts.len() < before {
+            debug!("업스트림 연결 제한 유휴 호스트 {} 개 정리 (남은 호스트 {} 개)", before - hosts.len(), hosts.len());
+        }
+    }
+}
+
+fn normalize(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
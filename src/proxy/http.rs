@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::sync::Arc;
 use std::time::{Instant, Duration};
 
@@ -8,7 +9,14 @@ use bytes::{BytesMut, BufMut};
 use base64::{self, engine::general_purpose::STANDARD, Engine};
 
 use crate::metrics::Metrics;
-use crate::config::Config;
+use crate::config::{Config, InspectionOverflowAction};
+use crate::buffer::read_chunk;
+use crate::proxy::passthrough::{is_interim_response, is_persistent, is_protocol_switch, passthrough_tracker, response_body_tracker, BodyTracker};
+use crate::proxy::pool::ReusableConnection;
+use crate::proxy::timing::UpstreamTiming;
+use crate::session::close::{CloseRecorder, SessionCloseReason};
+use crate::session::transfer::TransferRecorder;
+use crate::proxy::via::ViaInjector;
 use crate::logging::{Logger, LogFormatter};
 use crate::error::{ProxyError, Result, http_err, internal_err};
 
@@ -27,20 +35,6 @@ fn find_header_end(buffer: &[u8]) -> Option<usize> {
     None
 }
 
-/// 헤더에서 Content-Length 값 추출
-fn extract_content_length(headers: &str) -> Option<usize> {
-    for line in headers.lines() {
-        if line.to_lowercase().starts_with("content-length:") {
-            if let Some(length_str) = line.split(':').nth(1) {
-                if let Ok(length) = length_str.trim().parse::<usize>() {
-                    return Some(length);
-                }
-            }
-        }
-    }
-    None
-}
-
 /// HTTP 응답 상태 코드 추출
 fn extract_status_code(headers: &str) -> Option<u16> {
     let first_line = headers.lines().next()?;
@@ -90,6 +84,11 @@ fn parse_http_request_basic(request: &str) -> (String, String, Option<String>, O
 }
 
 /// 간소화된 HTTP 프록시 함수
+///
+/// 응답 본문 경계는 요청 메서드와 상태 코드를 고려해 정합니다 (HEAD 응답과 1xx/204/304는 본문 없음,
+/// 길이 정보가 없으면 서버가 연결을 닫을 때까지). 응답이 Content-Length 또는 chunked 종료로 온전히 끝났고
+/// 요청과 응답 모두 연결 유지(HTTP/1.1 기본, HTTP/1.0은 `Connection: keep-alive`)이면 재사용 가능한 서버 연결을 반환합니다.
+/// 101 응답으로 프로토콜을 전환한 연결은 더 이상 HTTP 메시지 경계를 알 수 없으므로 반환하지 않습니다.
 pub async fn proxy_http_streams(
     mut client_stream: TcpStream,
     mut server_stream: TcpStream,
@@ -100,7 +99,11 @@ pub async fn proxy_http_streams(
     initial_request: Option<Vec<u8>>,
     already_logged: bool,
     logger: Option<Arc<Logger>>,
-) -> Result<()> {
+    host: &str,
+    timing: Option<UpstreamTiming>,
+    close: Option<&CloseRecorder>,
+    transfer: Option<&TransferRecorder>,
+) -> Result<Option<ReusableConnection>> {
     // 세션 ID를 문자열로 복제하여 일관된 사용 보장
     let session_id_str = session_id.to_string();
     
@@ -113,6 +116,15 @@ pub async fn proxy_http_streams(
     let mut client_buf = BytesMut::with_capacity(buffer_size);
     let mut server_buf = BytesMut::with_capacity(buffer_size);
     
+    // Via/X-Proxy 헤더 삽입기 (proxy_headers가 활성화된 경우)
+    let mut via = config.as_ref().and_then(|c| ViaInjector::new(c));
+    
+    // 응답 본문 경계와 서버 연결 재사용 판단에 쓰는 요청 정보 (HEAD 여부, 요청의 연결 유지 여부)
+    let request_head = initial_request.as_deref()
+        .map(|request| &request[..find_header_end(request).unwrap_or(request.len())]);
+    let head_request = request_head.is_some_and(|head| head.starts_with(b"HEAD "));
+    let request_persistent = request_head.is_some_and(is_persistent);
+    
     // 초기 요청이 있으면 서버로 전송
     if let Some(initial_data) = initial_request {
         // 초기 요청 데이터 복사
@@ -147,10 +159,23 @@ pub async fn proxy_http_streams(
         }
         
         // 서버로 전송
-        if let Err(e) = server_stream.write_all(&client_buf).await {
+        let request = match via.as_mut() {
+            Some(via) => {
+                via.start_message();
+                via.apply(&client_buf)
+            },
+            None => Cow::Borrowed(&client_buf[..]),
+        };
+        if let Some(transfer) = transfer {
+            transfer.relay_started();
+        }
+        if let Err(e) = server_stream.write_all(&request).await {
             error!("[Session:{}] 서버에 초기 요청 전송 실패: {}", session_id_str, e);
             return Err(ProxyError::from(e));
         }
+        if let Some(transfer) = transfer {
+            transfer.add_up(request.len() as u64);
+        }
         
         // 버퍼 비우기
         client_buf.clear();
@@ -164,17 +189,25 @@ pub async fn proxy_http_streams(
     // 타임아웃 설정
     let timeout_duration = Duration::from_millis(timeout_ms);
     
-    // 완료 플래그
-    let mut _is_complete = false;
-    
-    // 헤더 끝 위치
+    // 최종 응답 헤더 시작/끝 위치 (1xx 중간 응답은 건너뜀)
+    let mut head_start = 0;
     let mut header_end_pos = None;
     
-    // 응답 완료 감지를 위한 변수
-    let mut content_length: Option<usize> = None;
-    let mut is_chunked = false;
-    let mut received_bytes = 0;
-    let mut chunked_complete = false;
+    // 응답 본문 추적기와 추적기에 반영한 버퍼 위치
+    let mut body: Option<BodyTracker> = None;
+    let mut body_fed = 0;
+    
+    // 서버 연결 재사용 가능 여부 (요청과 응답이 모두 연결 유지이고 응답이 정확히 끝난 경우)
+    let mut keep_alive = false;
+    let mut reusable = false;
+    
+    // 본문 패스스루 (Content-Type이 passthrough_content_types에 해당하거나 검사 크기 한도를 넘은 경우)
+    let mut passthrough: Option<BodyTracker> = None;
+    let mut over_inspection_limit = false;
+    let mut response_size = None;
+    
+    // 업스트림 첫 바이트 수신 시간 (구간 시간 측정 시)
+    let mut first_byte = None;
     
     // 서버로부터 응답 읽기
     loop {
@@ -189,59 +222,104 @@ pub async fn proxy_http_streams(
                     }
                     Ok(n) => {
                         debug!("[Session:{}] 서버로부터 {}바이트 수신", session_id_str, n);
+                        if timing.is_some() && first_byte.is_none() {
+                            first_byte = Some(request_start_time.elapsed());
+                        }
                         
-                        // 헤더 끝 위치를 아직 찾지 못했다면 찾기
-                        if header_end_pos.is_none() {
-                            if let Some(pos) = find_header_end(&server_buf) {
-                                header_end_pos = Some(pos);
-                                
-                                // 헤더를 찾았으면 Content-Length 또는 Transfer-Encoding: chunked 확인
-                                let headers = String::from_utf8_lossy(&server_buf[..pos]).to_string();
-                                
-                                // Content-Length 확인
-                                if let Some(length) = extract_content_length(&headers) {
-                                    content_length = Some(length);
-                                    debug!("[Session:{}] Content-Length: {}", session_id_str, length);
-                                }
-                                
-                                // Transfer-Encoding: chunked 확인
-                                if headers.to_lowercase().contains("transfer-encoding: chunked") {
-                                    is_chunked = true;
-                                    debug!("[Session:{}] Chunked encoding detected", session_id_str);
-                                }
+                        // 응답 시작이면 Via/X-Proxy 헤더 삽입 대상으로 표시
+                        if let Some(via) = via.as_mut()
+                            && server_buf.len() == n {
+                            via.start_message();
+                        }
+                        
+                        // 헤더 끝 위치를 아직 찾지 못했다면 찾기 (1xx 중간 응답 뒤에는 최종 응답 헤더가 이어짐)
+                        while header_end_pos.is_none()
+                            && let Some(end) = find_header_end(&server_buf[head_start..]) {
+                            let pos = head_start + end;
+                            let head = &server_buf[head_start..pos];
+                            if is_interim_response(head) {
+                                debug!("[Session:{}] 1xx 중간 응답 전달", session_id_str);
+                                head_start = pos + 4;
+                                continue;
+                            }
+                            header_end_pos = Some(pos);
+                            
+                            let tracker = response_body_tracker(head_request, head);
+                            debug!("[Session:{}] 응답 본문 경계: {:?}", session_id_str, tracker);
+                            // 프로토콜 전환(101) 뒤에는 터널과 같으므로 재사용하지 않음
+                            keep_alive = request_persistent && is_persistent(head) && !is_protocol_switch(head);
+                            if let Some(config) = &config
+                                && passthrough_tracker(config, head).is_some() {
+                                passthrough = Some(tracker);
                             }
+                            body = Some(tracker);
+                            body_fed = pos + 4;
                         }
                         
                         // 클라이언트에 전송
-                        if let Err(e) = client_stream.write_all(&server_buf[server_buf.len() - n..]).await {
+                        let received = &server_buf[server_buf.len() - n..];
+                        let response = match via.as_mut() {
+                            Some(via) => via.apply(received),
+                            None => Cow::Borrowed(received),
+                        };
+                        if let Err(e) = client_stream.write_all(&response).await {
                             error!("[Session:{}] 클라이언트에 응답 전송 실패: {}", session_id_str, e);
                             return Err(ProxyError::Http(format!("클라이언트에 응답 전송 실패: {}", e)));
                         }
+                        if let Some(transfer) = transfer {
+                            transfer.add_down(response.len() as u64);
+                        }
+                        
+                        // 검사 크기 한도를 넘으면 검사를 멈추고 패스스루로 전환하거나 중단
+                        if passthrough.is_none()
+                            && let Some(config) = &config
+                            && config.inspection_limit.exceeded(server_buf.len()) {
+                            metrics.inspection_limit_exceeded();
+                            let limit = config.inspection_limit.max_response_bytes.unwrap_or_default();
+                            let on_exceed = config.inspection_limit.on_exceed;
+                            warn!("[Session:{}] 응답이 검사 크기 한도 {}바이트를 넘음 (on_exceed {}): {}",
+                                  session_id_str, limit, on_exceed.name(), host);
+                            match (on_exceed, header_end_pos) {
+                                (InspectionOverflowAction::Passthrough, Some(pos)) => {
+                                    passthrough = Some(response_body_tracker(head_request, &server_buf[head_start..pos]));
+                                    over_inspection_limit = true;
+                                },
+                                // 헤더 끝을 찾지 못했으면 본문 경계를 알 수 없으므로 중단
+                                _ => return Err(ProxyError::Http(format!("inspected response exceeded {} bytes", limit))),
+                            }
+                        }
                         
-                        // 응답 완료 감지 로직
-                        if let Some(pos) = header_end_pos {
-                            if let Some(length) = content_length {
-                                // Content-Length 기반 완료 감지
-                                received_bytes += n;
-                                let body_received = server_buf.len() - pos - 4; // 헤더 끝 패턴(\r\n\r\n) 길이 4 빼기
-                                
-                                if body_received >= length {
-                                    debug!("[Session:{}] 응답 완료 감지: Content-Length 기반 ({}바이트)", session_id_str, body_received);
-                                    _is_complete = true;
-                                    break;
-                                }
-                            } else if is_chunked {
-                                // Chunked 인코딩 기반 완료 감지
-                                // 마지막 청크(0 크기 청크)를 찾음
-                                let buf_str = String::from_utf8_lossy(&server_buf).to_string();
-                                if buf_str.contains("\r\n0\r\n\r\n") {
-                                    debug!("[Session:{}] 응답 완료 감지: Chunked 인코딩 종료 마커 발견", session_id_str);
-                                    chunked_complete = true;
-                                    _is_complete = true;
-                                    break;
-                                }
+                        // 검사용 버퍼링 전체 상한을 넘으면 on_exceed와 관계없이 나머지 본문은 검사 없이 전달
+                        if passthrough.is_none()
+                            && let Some(config) = &config
+                            && config.inspection_cap_exceeded(server_buf.len()) {
+                            metrics.inspection_cap_exceeded();
+                            warn!("[Session:{}] 응답이 검사용 버퍼링 상한 {}바이트를 넘어 패스스루로 전환: {}",
+                                  session_id_str, config.max_inspection_bytes, host);
+                            match header_end_pos {
+                                Some(pos) => {
+                                    passthrough = Some(response_body_tracker(head_request, &server_buf[head_start..pos]));
+                                    over_inspection_limit = true;
+                                },
+                                // 헤더만으로 상한을 넘었으면 본문 경계를 알 수 없으므로 중단
+                                None => return Err(ProxyError::Http(format!("response head exceeded {} bytes", config.max_inspection_bytes))),
                             }
                         }
+                        
+                        // 패스스루 대상이면 나머지 본문은 버퍼에 모으지 않고 별도로 전달
+                        if passthrough.is_some() {
+                            break;
+                        }
+                        
+                        // 응답 완료 감지 (길이 정보가 없으면 서버가 연결을 닫을 때까지 읽음)
+                        if let Some(tracker) = body.as_mut() {
+                            if let Some(used) = tracker.feed(&server_buf[body_fed..]) {
+                                debug!("[Session:{}] 응답 완료 감지 ({}바이트)", session_id_str, server_buf.len() - head_start);
+                                reusable = keep_alive && body_fed + used == server_buf.len();
+                                break;
+                            }
+                            body_fed = server_buf.len();
+                        }
                     }
                     Err(e) => {
                         error!("[Session:{}] 서버로부터 읽기 오류: {}", session_id_str, e);
@@ -251,23 +329,82 @@ pub async fn proxy_http_streams(
             }
             Err(_) => {
                 warn!("[Session:{}] 서버 읽기 타임아웃, 정상 종료로 처리", session_id_str);
-                _is_complete = true;
+                if let Some(close) = close {
+                    close.record(SessionCloseReason::IdleTimeout);
+                }
                 break;
             }
         }
     }
     
+    if let (Some(mut tracker), Some(pos)) = (passthrough, header_end_pos) {
+        if !over_inspection_limit {
+            metrics.passthrough_response();
+        }
+        debug!("[Session:{}] 본문 패스스루 시작", session_id_str);
+        
+        // 이미 받은 본문 조각 반영
+        let body_start = pos + 4;
+        let mut body_bytes = server_buf.len() - body_start;
+        let mut exact_end = tracker.feed(&server_buf[body_start..]).map(|used| used == body_bytes);
+        
+        // 로깅용으로 헤더와 본문 앞부분만 남김
+        server_buf.truncate(std::cmp::min(body_start + 1024, server_buf.len()));
+        
+        let mut chunk = BytesMut::with_capacity(buffer_size);
+        let read_chunk_size = config.as_ref().and_then(|c| c.read_chunk_size);
+        while exact_end.is_none() {
+            chunk.clear();
+            match tokio::time::timeout(timeout_duration, server_stream.read_buf(&mut read_chunk(&mut chunk, read_chunk_size))).await {
+                Ok(Ok(0)) => break,
+                Ok(Ok(n)) => {
+                    if let Err(e) = client_stream.write_all(&chunk).await {
+                        error!("[Session:{}] 클라이언트에 응답 전송 실패: {}", session_id_str, e);
+                        return Err(ProxyError::Http(format!("클라이언트에 응답 전송 실패: {}", e)));
+                    }
+                    if let Some(transfer) = transfer {
+                        transfer.add_down(n as u64);
+                    }
+                    body_bytes += n;
+                    if let Some(used) = tracker.feed(&chunk) {
+                        exact_end = Some(used == n);
+                    }
+                }
+                Ok(Err(e)) => {
+                    error!("[Session:{}] 서버로부터 읽기 오류: {}", session_id_str, e);
+                    return Err(ProxyError::Http(format!("서버로부터 읽기 오류: {}", e)));
+                }
+                Err(_) => {
+                    warn!("[Session:{}] 서버 읽기 타임아웃, 정상 종료로 처리", session_id_str);
+                    if let Some(close) = close {
+                        close.record(SessionCloseReason::IdleTimeout);
+                    }
+                    break;
+                }
+            }
+        }
+        
+        reusable = keep_alive && exact_end == Some(true) && !tracker.until_close();
+        response_size = Some(body_start + body_bytes);
+    }
+    
     // 응답 시간 계산
     let response_time = request_start_time.elapsed().as_millis() as u64;
     
+    // 업스트림 구간 시간 (연결 구간은 세션이 새로 맺은 연결인 경우에만 있음)
+    let timing = timing.map(|timing| UpstreamTiming { first_byte, ..timing });
+    if let Some(timing) = &timing {
+        metrics.record_upstream_timing(host, timing);
+    }
+    
     // 응답 로깅
     if let Some(logger) = &logger {
         // 헤더 추출
         let headers = if let Some(pos) = header_end_pos {
-            if let Ok(headers_text) = std::str::from_utf8(&server_buf[..pos]) {
+            if let Ok(headers_text) = std::str::from_utf8(&server_buf[head_start..pos]) {
                 headers_text.to_string()
             } else {
-                String::from_utf8_lossy(&server_buf[..pos]).into_owned()
+                String::from_utf8_lossy(&server_buf[head_start..pos]).into_owned()
             }
         } else {
             // 헤더 끝을 찾지 못한 경우 전체 버퍼를 사용
@@ -299,9 +436,12 @@ pub async fn proxy_http_streams(
             session_id_str.clone(),
             extract_status_code(&headers).unwrap_or(0),
             response_time,
-            server_buf.len(), // response_size
+            response_size.unwrap_or(server_buf.len()), // response_size
             headers,
             body_preview,
+            timing,
+            false,
+            None,
         ).await {
             error!("[Session:{}] 응답 로깅 실패: {}", session_id_str, e);
         }
@@ -310,7 +450,7 @@ pub async fn proxy_http_streams(
     // 세션 완료 로깅
     debug!("[Session:{}] HTTP 세션 완료: {} ms", session_id_str, response_time);
     
-    Ok(())
+    Ok(reusable.then(|| ReusableConnection::after_http_exchange(server_stream)))
 }
 
 /// 클라이언트와 서버 간 양방향 HTTP 프록시
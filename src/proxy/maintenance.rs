@@ -0,0 +1,160 @@
+// 점검 모드 (maintenance 설정, 관리 엔드포인트 /maintenance)
+// 점검 중에는 새 요청을 업스트림에 연결하지 않고 503 점검 응답으로 거절합니다.
+// 진행 중인 세션은 기본적으로 끝날 때까지 두고, close_existing이면 전환 시점에 닫습니다.
+
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+
+use chrono::Local;
+use log::info;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use tokio::sync::watch;
+
+use crate::config::MaintenanceConfig;
+use crate::metrics::Metrics;
+use crate::proxy::error_page::{ErrorReason, ErrorResponse, negotiate};
+
+// 점검 모드 상태 (설정 전에도 꺼진 상태로 사용 가능)
+static MAINTENANCE: Lazy<Maintenance> = Lazy::new(|| Maintenance {
+    active: AtomicBool::new(false),
+    window: RwLock::new(None),
+    rejected: AtomicU64::new(0),
+    close_sessions: watch::channel(0).0,
+});
+
+// 점검 응답 설정 (초기화 전에는 기본값)
+static MAINTENANCE_SETTINGS: OnceCell<MaintenanceSettings> = OnceCell::new();
+
+struct MaintenanceSettings {
+    page: Option<String>,
+    retry_after_seconds: u64,
+    close_existing: bool,
+}
+
+/// 진행 중인 점검 구간
+struct MaintenanceWindow {
+    started_at: Instant,
+    since: String,
+    reason: Option<String>,
+    changed_by: String,
+}
+
+struct Maintenance {
+    active: AtomicBool,
+    window: RwLock<Option<MaintenanceWindow>>,
+    /// 이번 점검 구간에 거절한 요청 수
+    rejected: AtomicU64,
+    /// 진행 중인 세션을 닫을 때마다 값이 바뀜
+    close_sessions: watch::Sender<u64>,
+}
+
+/// 현재 점검 모드 상태 (관리 엔드포인트 응답)
+#[derive(Debug, Serialize)]
+pub struct MaintenanceState {
+    pub status: &'static str,
+    pub maintenance: bool,
+    /// 점검 모드 시작 시각
+    pub since: Option<String>,
+    pub reason: Option<String>,
+    /// 전환한 주체 (`config` 또는 `admin:<요청 IP>`)
+    pub changed_by: Option<String>,
+    /// 이번 점검 구간에 점검 응답으로 거절한 요청 수
+    pub rejected: u64,
+    /// 이번 전환으로 진행 중인 세션을 닫았는지
+    pub closed_existing: bool,
+}
+
+/// 설정의 점검 페이지를 읽고, `enabled`이면 점검 모드로 시작
+pub fn init_maintenance(config: &MaintenanceConfig) -> std::result::Result<(), String> {
+    let page = match &config.page_file {
+        Some(path) => Some(std::fs::read_to_string(path).map_err(|e| format!("점검 페이지 읽기 실패 ({}): {}", path, e))?),
+        None => None,
+    };
+    let _ = MAINTENANCE_SETTINGS.set(MaintenanceSettings {
+        page,
+        retry_after_seconds: config.retry_after_seconds,
+        close_existing: config.close_existing,
+    });
+    if config.enabled {
+        set_maintenance(true, None, Some("maintenance.enabled"), "config");
+    }
+    Ok(())
+}
+
+/// 점검 모드인지
+pub fn is_maintenance() -> bool {
+    MAINTENANCE.active.load(Ordering::Relaxed)
+}
+
+/// 점검 모드 전환 (변경 후 상태 반환)
+///
+/// 켤 때 `close_existing`(없으면 설정 값)이면 진행 중인 세션도 닫으며, 이미 점검 중이어도 적용됩니다.
+pub fn set_maintenance(enabled: bool, close_existing: Option<bool>, reason: Option<&str>, changed_by: &str) -> MaintenanceState {
+    let mut window = MAINTENANCE.window.write().unwrap();
+    let mut closed_existing = false;
+    if enabled {
+        if window.is_none() {
+            MAINTENANCE.rejected.store(0, Ordering::Relaxed);
+            *window = Some(MaintenanceWindow {
+                started_at: Instant::now(),
+                since: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                reason: reason.map(str::to_string),
+                changed_by: changed_by.to_string(),
+            });
+            MAINTENANCE.active.store(true, Ordering::Relaxed);
+            info!("[MAINTENANCE] 점검 모드 시작 (전환 {}, 사유: {})", changed_by, reason.unwrap_or("-"));
+        }
+        let settings_close = MAINTENANCE_SETTINGS.get().is_some_and(|settings| settings.close_existing);
+        if close_existing.unwrap_or(settings_close) {
+            MAINTENANCE.close_sessions.send_modify(|generation| *generation += 1);
+            closed_existing = true;
+            info!("[MAINTENANCE] 진행 중인 세션 종료 요청 (전환 {})", changed_by);
+        }
+    } else if let Some(ended) = window.take() {
+        MAINTENANCE.active.store(false, Ordering::Relaxed);
+        info!("[MAINTENANCE] 점검 모드 종료 (전환 {}, {}초 동안 요청 {} 건 거절)",
+              changed_by, ended.started_at.elapsed().as_secs(), MAINTENANCE.rejected.load(Ordering::Relaxed));
+    }
+    state(window.as_ref(), closed_existing)
+}
+
+/// 현재 점검 모드 상태
+pub fn maintenance_state() -> MaintenanceState {
+    state(MAINTENANCE.window.read().unwrap().as_ref(), false)
+}
+
+fn state(window: Option<&MaintenanceWindow>, closed_existing: bool) -> MaintenanceState {
+    MaintenanceState {
+        status: "ok",
+        maintenance: window.is_some(),
+        since: window.map(|window| window.since.clone()),
+        reason: window.and_then(|window| window.reason.clone()),
+        changed_by: window.map(|window| window.changed_by.clone()),
+        rejected: if window.is_some() { MAINTENANCE.rejected.load(Ordering::Relaxed) } else { 0 },
+        closed_existing,
+    }
+}
+
+/// 점검 응답으로 거절한 요청 집계 (SOCKS4처럼 응답 본문이 없는 거절 포함)
+pub fn count_rejected() {
+    MAINTENANCE.rejected.fetch_add(1, Ordering::Relaxed);
+    Metrics::new().maintenance_rejected();
+}
+
+/// 요청에 보낼 503 점검 응답 (Accept에 따라 HTML/JSON, 거절 집계 포함)
+pub fn reject_response(request_id: &str, host: &str, request: &[u8]) -> String {
+    count_rejected();
+    let settings = MAINTENANCE_SETTINGS.get();
+    let retry_after = settings.map_or_else(|| MaintenanceConfig::default().retry_after_seconds, |settings| settings.retry_after_seconds);
+    ErrorResponse::new(ErrorReason::Maintenance, request_id, host)
+        .with_retry_after(retry_after)
+        .with_html_template(settings.and_then(|settings| settings.page.as_deref()))
+        .render(negotiate(request))
+}
+
+/// 진행 중인 세션 종료 요청 수신기 (세션 시작 시 구독, 이후 요청이 오면 `changed()`가 완료됨)
+pub fn close_signal() -> watch::Receiver<u64> {
+    MAINTENANCE.close_sessions.subscribe()
+}
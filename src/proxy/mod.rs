@@ -1,4 +1,27 @@
 // 프록시 관련 기능을 구현하는 모듈
 
+pub mod acl_response;
+pub mod breaker;
+pub mod connect_pipeline;
+pub mod dialer;
+pub mod dns_prewarm;
+pub mod dscp;
+pub mod egress;
+pub mod error_page;
+pub mod host_limit;
 pub mod http;
-pub mod tls; 
\ No newline at end of file
+pub mod maintenance;
+pub mod overload;
+pub mod parents;
+pub mod passthrough;
+pub mod pool;
+pub mod relay;
+pub mod security_headers;
+pub mod sockbuf;
+pub mod socks;
+pub mod target;
+pub mod throttle;
+pub mod timing;
+pub mod tls;
+pub mod via;
+pub mod warm;
\ No newline at end of file
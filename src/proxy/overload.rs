@@ -0,0 +1,142 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use log::{info, warn};
+use once_cell::sync::OnceCell;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::Config;
+use crate::metrics::Metrics;
+use crate::proxy::host_limit::HostLimitExceeded;
+
+// 전역 과부하 한도 (overload 설정 시에만 초기화)
+static OVERLOAD_GUARD: OnceCell<Arc<OverloadGuard>> = OnceCell::new();
+
+/// 과부하 거부 원인
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverloadCause {
+    /// 전체 동시 세션 수 한도 (overload.max_connections)
+    GlobalCap,
+    /// 업스트림 호스트별 동시 연결 한도 (upstream_host_limit)
+    HostCap,
+    /// 초당 요청 수 한도 (overload.max_requests_per_second)
+    RateLimit,
+}
+
+impl OverloadCause {
+    /// 로그/응답 본문에 쓰는 원인 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            OverloadCause::GlobalCap => "global_cap",
+            OverloadCause::HostCap => "host_cap",
+            OverloadCause::RateLimit => "rate_limit",
+        }
+    }
+}
+
+/// 과부하로 거부됨
+#[derive(Debug)]
+pub struct OverloadRejected {
+    pub cause: OverloadCause,
+    pub detail: String,
+}
+
+impl fmt::Display for OverloadRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "proxy overloaded ({}): {}", self.cause.name(), self.detail)
+    }
+}
+
+impl From<HostLimitExceeded> for OverloadRejected {
+    fn from(error: HostLimitExceeded) -> Self {
+        Self {
+            cause: OverloadCause::HostCap,
+            detail: error.to_string(),
+        }
+    }
+}
+
+/// 초당 요청 수 토큰 버킷 (남은 토큰, 마지막 충전 시각)
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// 전체 동시 세션 수와 초당 요청 수 제한
+///
+/// 동시 세션 슬롯은 세션이 끝나 permit이 해제될 때 반납합니다.
+/// 초당 요청 수는 토큰 버킷으로 세며 순간 허용량은 초당 한도와 같습니다.
+pub struct OverloadGuard {
+    connections: Option<Arc<Semaphore>>,
+    max_connections: usize,
+    rate: Option<Mutex<TokenBucket>>,
+    max_requests_per_second: u32,
+    metrics: Arc<Metrics>,
+}
+
+/// 설정에 따라 전역 과부하 한도 초기화
+pub fn init_overload_guard(config: &Config) {
+    let settings = &config.overload;
+    if !settings.is_enabled() {
+        return;
+    }
+
+    let guard = OverloadGuard {
+        connections: (settings.max_connections > 0).then(|| Arc::new(Semaphore::new(settings.max_connections))),
+        max_connections: settings.max_connections,
+        rate: (settings.max_requests_per_second > 0).then(|| Mutex::new(TokenBucket {
+            tokens: settings.max_requests_per_second as f64,
+            refilled_at: Instant::now(),
+        })),
+        max_requests_per_second: settings.max_requests_per_second,
+        metrics: Metrics::new(),
+    };
+    if OVERLOAD_GUARD.set(Arc::new(guard)).is_ok() {
+        info!("과부하 한도 활성화: 동시 세션 {}, 초당 요청 {} (0 - 제한 없음), Retry-After {}초",
+              settings.max_connections, settings.max_requests_per_second, settings.retry_after_seconds);
+    }
+}
+
+/// 전역 과부하 한도 (비활성화 시 None)
+pub fn overload_guard() -> Option<Arc<OverloadGuard>> {
+    OVERLOAD_GUARD.get().cloned()
+}
+
+impl OverloadGuard {
+    /// 새 요청 허용 여부 확인 (동시 세션 한도가 있으면 슬롯 반환)
+    ///
+    /// 반환된 permit을 세션이 끝날 때까지 보관해야 합니다.
+    pub fn admit(&self) -> Result<Option<OwnedSemaphorePermit>, OverloadRejected> {
+        if let Some(rate) = &self.rate {
+            let mut bucket = rate.lock().unwrap();
+            let now = Instant::now();
+            let limit = self.max_requests_per_second as f64;
+            bucket.tokens = (bucket.tokens + now.duration_since(bucket.refilled_at).as_secs_f64() * limit).min(limit);
+            bucket.refilled_at = now;
+            if bucket.tokens < 1.0 {
+                self.metrics.overload_rate_limited();
+                return Err(OverloadRejected {
+                    cause: OverloadCause::RateLimit,
+                    detail: format!("more than {} requests per second", self.max_requests_per_second),
+                });
+            }
+            bucket.tokens -= 1.0;
+        }
+
+        match &self.connections {
+            Some(semaphore) => match Arc::clone(semaphore).try_acquire_owned() {
+                Ok(permit) => Ok(Some(permit)),
+                Err(_) => {
+                    warn!("전체 동시 세션 한도 초과로 거부 (한도 {})", self.max_connections);
+                    self.metrics.overload_global_rejected();
+                    Err(OverloadRejected {
+                        cause: OverloadCause::GlobalCap,
+                        detail: format!("limit of {} concurrent sessions reached", self.max_connections),
+                    })
+                }
+            },
+            None => Ok(None),
+        }
+    }
+}
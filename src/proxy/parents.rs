@@ -0,0 +1,178 @@
+// 여러 상위 프록시로 업스트림 연결 분산 (parent_proxies)
+// 연결마다 비중에 따라 상위 프록시 하나를 고르고, 연결 실패가 이어진 상위 프록시는 전용 회로 차단기로
+// 잠시 제외했다가 대기 시간이 지나면 시험 연결로 회복 여부를 확인합니다.
+// 요청 헤더로 출구(egress_pools)를 고른 세션은 그 출구를 그대로 사용합니다.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{debug, info};
+use once_cell::sync::{Lazy, OnceCell};
+use rand::Rng;
+
+use crate::config::{Config, EgressPool, ParentProxiesConfig, ParentProxy, ParentSelection};
+use crate::proxy::breaker::CircuitBreaker;
+
+// 상위 프록시 상태 추적용 회로 차단기 (parent_proxies.proxies가 있는 경우에만 초기화)
+static PARENT_BREAKER: OnceCell<Arc<CircuitBreaker>> = OnceCell::new();
+
+// 전역 상위 프록시 선택기 (순환 선택 상태 유지)
+static SELECTOR: Lazy<ParentSelector> = Lazy::new(ParentSelector::default);
+
+/// 설정에 따라 상위 프록시 회로 차단기 초기화
+pub fn init_parent_proxies(config: &Config) {
+    let parents = &config.parent_proxies;
+    if parents.proxies.is_empty() {
+        return;
+    }
+
+    let breaker = Arc::new(CircuitBreaker::new(
+        parents.failure_threshold,
+        Duration::from_secs(parents.window_seconds),
+        Duration::from_secs(parents.cooldown_seconds),
+    ));
+    if PARENT_BREAKER.set(breaker).is_ok() {
+        let list: Vec<String> = parents.proxies.iter().map(|parent| format!("{}(x{})", parent.address, parent.weight)).collect();
+        info!("상위 프록시 분산 ({}): {} - {}초 내 연속 실패 {}회 시 {}초 제외",
+              parents.strategy.name(), list.join(", "), parents.window_seconds, parents.failure_threshold, parents.cooldown_seconds);
+    }
+}
+
+/// 상위 프록시 회로 차단기 (상위 프록시를 설정하지 않았으면 None)
+pub fn parent_breaker() -> Option<Arc<CircuitBreaker>> {
+    PARENT_BREAKER.get().cloned()
+}
+
+/// 회로 차단기에서 상위 프록시를 구분하는 키 (대상 호스트와 겹치지 않도록 접두어 사용)
+pub fn parent_key(address: &str) -> String {
+    format!("parent:{}", address)
+}
+
+/// 이번 업스트림 연결에 쓸 상위 프록시 선택 (상위 프록시를 설정하지 않았으면 None)
+pub fn select_parent(config: &Config, session_id: &str) -> Option<EgressPool> {
+    let parents = &config.parent_proxies;
+    let breaker = parent_breaker();
+    let parent = SELECTOR.pick(parents, breaker.as_deref())?;
+    debug!("[Session:{}] 상위 프록시 선택: {} (weight {}, {})",
+          session_id, parent.address, parent.weight, parents.strategy.name());
+    Some(EgressPool { source_address: None, parent_proxy: Some(parent.address.clone()) })
+}
+
+/// 비중에 따른 상위 프록시 선택기
+#[derive(Default)]
+pub struct ParentSelector {
+    // 순환 선택용 현재 비중 (상위 프록시 목록과 같은 순서)
+    current: Mutex<Vec<i64>>,
+}
+
+impl ParentSelector {
+    /// 제외되지 않은 상위 프록시 중 하나 선택 (모두 제외되었으면 전체에서 선택해 즉시 실패하도록 함)
+    pub fn pick<'a>(&self, parents: &'a ParentProxiesConfig, breaker: Option<&CircuitBreaker>) -> Option<&'a ParentProxy> {
+        let weighted = |parent: &ParentProxy| parent.weight > 0;
+        let healthy = |parent: &ParentProxy| {
+            weighted(parent) && breaker.is_none_or(|breaker| !breaker.is_open(&parent_key(&parent.address)))
+        };
+        let candidates: Vec<usize> = if parents.proxies.iter().any(healthy) {
+            (0..parents.proxies.len()).filter(|&i| healthy(&parents.proxies[i])).collect()
+        } else {
+            if parents.proxies.iter().any(weighted) {
+                debug!("사용 가능한 상위 프록시가 없어 제외된 상위 프록시 중에서 선택합니다");
+            }
+            (0..parents.proxies.len()).filter(|&i| weighted(&parents.proxies[i])).collect()
+        };
+        let index = match parents.strategy {
+            ParentSelection::WeightedRandom => Self::weighted_random(parents, &candidates),
+            ParentSelection::RoundRobin => self.round_robin(parents, &candidates),
+        }?;
+        parents.proxies.get(index)
+    }
+
+    /// 비중에 비례한 무작위 선택
+    fn weighted_random(parents: &ParentProxiesConfig, candidates: &[usize]) -> Option<usize> {
+        let total: u64 = candidates.iter().map(|&i| parents.proxies[i].weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut point = rand::rng().random_range(0..total);
+        for &i in candidates {
+            let weight = parents.proxies[i].weight as u64;
+            if point < weight {
+                return Some(i);
+            }
+            point -= weight;
+        }
+        None
+    }
+
+    /// 비중을 반영한 순환 선택 (후보마다 현재 값에 비중을 더하고 가장 큰 후보를 골라 전체 비중만큼 뺌)
+    fn round_robin(&self, parents: &ParentProxiesConfig, candidates: &[usize]) -> Option<usize> {
+        let mut current = self.current.lock().unwrap();
+        // 다른 목록에 쓰면 처음부터
+        if current.len() != parents.proxies.len() {
+            *current = vec![0; parents.proxies.len()];
+        }
+        let total: i64 = candidates.iter().map(|&i| parents.proxies[i].weight as i64).sum();
+        for &i in candidates {
+            current[i] += parents.proxies[i].weight as i64;
+        }
+        let chosen = candidates.iter().copied().max_by_key(|&i| (current[i], std::cmp::Reverse(i)))?;
+        current[chosen] -= total;
+        Some(chosen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parents(strategy: ParentSelection) -> ParentProxiesConfig {
+        let parent = |address: &str, weight: u32| ParentProxy { address: address.to_string(), weight };
+        ParentProxiesConfig {
+            proxies: vec![parent("a.parent.invalid:3128", 1), parent("b.parent.invalid:3128", 3), parent("drained.parent.invalid:3128", 0)],
+            strategy,
+            ..ParentProxiesConfig::default()
+        }
+    }
+
+    // 목록 순서대로 고른 횟수
+    fn counts(parents: &ParentProxiesConfig, breaker: Option<&CircuitBreaker>, picks: usize) -> [usize; 3] {
+        let selector = ParentSelector::default();
+        let mut counts = [0; 3];
+        for _ in 0..picks {
+            let chosen = selector.pick(parents, breaker).expect("상위 프록시 선택");
+            counts[parents.proxies.iter().position(|parent| parent.address == chosen.address).unwrap()] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn round_robin_follows_weights() {
+        assert_eq!(counts(&parents(ParentSelection::RoundRobin), None, 4000), [1000, 3000, 0]);
+    }
+
+    #[test]
+    fn weighted_random_follows_weights() {
+        let random = counts(&parents(ParentSelection::WeightedRandom), None, 20000);
+        assert_eq!(random[2], 0, "weight 0은 고르지 않음");
+        assert!((4400..=5600).contains(&random[0]), "비중 1:3 분포: {:?}", random);
+    }
+
+    // 회로 차단기가 메트릭스 전역 인스턴스를 쓰므로 tokio 런타임에서 실행 (DB는 사용 안 함)
+    #[tokio::test]
+    async fn skips_excluded_parents() {
+        crate::db::config::DbConfig::disable();
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60), Duration::from_secs(60));
+        for _ in 0..2 {
+            breaker.record_failure(&parent_key("b.parent.invalid:3128"));
+        }
+        for strategy in [ParentSelection::WeightedRandom, ParentSelection::RoundRobin] {
+            assert_eq!(counts(&parents(strategy), Some(&breaker), 100), [100, 0, 0], "{}", strategy.name());
+        }
+
+        // 모두 제외되면 비중대로 골라 즉시 실패하도록 함
+        for _ in 0..2 {
+            breaker.record_failure(&parent_key("a.parent.invalid:3128"));
+        }
+        assert_eq!(counts(&parents(ParentSelection::RoundRobin), Some(&breaker), 400), [100, 300, 0]);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::config::Config;
+
+/// 본문 길이를 결정하는 방식
+#[derive(Debug, Clone, Copy)]
+enum Framing {
+    /// Content-Length: 남은 바이트 수
+    Length(u64),
+    /// Transfer-Encoding: chunked
+    Chunked(ChunkState),
+    /// 길이 정보 없음: 서버가 연결을 닫을 때까지
+    UntilClose,
+}
+
+/// chunked 본문 파서 상태
+#[derive(Debug, Clone, Copy)]
+enum ChunkState {
+    /// 청크 크기 줄 (확장 부분은 무시)
+    Size { size: u64, in_extension: bool },
+    /// 청크 크기 줄의 LF 대기
+    SizeLf { size: u64 },
+    /// 청크 데이터 (남은 바이트 수)
+    Data(u64),
+    /// 청크 데이터 뒤의 CRLF
+    DataCrLf,
+    /// 마지막 청크 뒤 트레일러 (현재 줄 길이, 빈 줄이면 종료)
+    Trailer { line_len: usize },
+    Done,
+    /// 청크 크기가 u64를 넘는 잘못된 본문
+    Malformed,
+}
+
+/// 패스스루 중인 응답 본문의 끝을 찾는 추적기
+///
+/// 본문을 버퍼에 모으지 않고 읽은 조각만 보고 Content-Length/chunked 경계를 따라갑니다.
+#[derive(Debug, Clone, Copy)]
+pub struct BodyTracker {
+    framing: Framing,
+}
+
+impl BodyTracker {
+    /// 조각을 반영하고, 이 조각 안에서 본문이 끝나면 본문이 끝난 위치(조각 기준 바이트 수)를 반환
+    pub fn feed(&mut self, data: &[u8]) -> Option<usize> {
+        match &mut self.framing {
+            Framing::UntilClose => None,
+            Framing::Length(remaining) => {
+                let take = (*remaining).min(data.len() as u64);
+                *remaining -= take;
+                (*remaining == 0).then_some(take as usize)
+            },
+            Framing::Chunked(state) => {
+                let mut pos = 0;
+                while pos < data.len() && !matches!(state, ChunkState::Done | ChunkState::Malformed) {
+                    pos += Self::step_chunked(state, &data[pos..]);
+                }
+                match state {
+                    ChunkState::Done => Some(pos),
+                    // 본문 끝을 알 수 없으므로 연결이 닫힐 때까지 전달 (연결 재사용 안 함)
+                    ChunkState::Malformed => {
+                        self.framing = Framing::UntilClose;
+                        None
+                    },
+                    _ => None,
+                }
+            },
+        }
+    }
+
+    /// 응답이 연결 종료로만 끝나는지 여부
+    pub fn until_close(&self) -> bool {
+        matches!(self.framing, Framing::UntilClose)
+    }
+
+    /// chunked 파서를 한 단계 진행하고 소비한 바이트 수 반환
+    fn step_chunked(state: &mut ChunkState, data: &[u8]) -> usize {
+        if let ChunkState::Data(remaining) = state {
+            let take = (*remaining).min(data.len() as u64);
+            *remaining -= take;
+            if *remaining == 0 {
+                *state = ChunkState::DataCrLf;
+            }
+            return take as usize;
+        }
+
+        let byte = data[0];
+        *state = match *state {
+            ChunkState::Size { size, in_extension } => match byte {
+                b'\r' => ChunkState::SizeLf { size },
+                b'\n' => Self::after_size_line(size),
+                b';' => ChunkState::Size { size, in_extension: true },
+                _ if in_extension => ChunkState::Size { size, in_extension },
+                _ => match (byte as char).to_digit(16) {
+                    Some(digit) => match size.checked_mul(16).and_then(|size| size.checked_add(digit as u64)) {
+                        Some(size) => ChunkState::Size { size, in_extension },
+                        None => ChunkState::Malformed,
+                    },
+                    None => ChunkState::Size { size, in_extension: true },
+                },
+            },
+            ChunkState::SizeLf { size } => Self::after_size_line(size),
+            ChunkState::DataCrLf => match byte {
+                b'\n' => ChunkState::Size { size: 0, in_extension: false },
+                _ => ChunkState::DataCrLf,
+            },
+            ChunkState::Trailer { line_len } => match byte {
+                b'\r' => ChunkState::Trailer { line_len },
+                b'\n' if line_len == 0 => ChunkState::Done,
+                b'\n' => ChunkState::Trailer { line_len: 0 },
+                _ => ChunkState::Trailer { line_len: line_len + 1 },
+            },
+            state => state,
+        };
+        1
+    }
+
+    /// 청크 크기 줄이 끝난 뒤 상태 (크기 0이면 트레일러)
+    fn after_size_line(size: u64) -> ChunkState {
+        if size == 0 {
+            ChunkState::Trailer { line_len: 0 }
+        } else {
+            ChunkState::Data(size)
+        }
+    }
+}
+
+/// 응답 헤더(`\r\n\r\n` 이전까지)의 Content-Type이 패스스루 대상이면 본문 추적기 반환
+pub fn passthrough_tracker(config: &Config, headers: &[u8]) -> Option<BodyTracker> {
+    if config.passthrough_content_types.is_empty() {
+        return None;
+    }
+
+    let (content_type, tracker) = parse_headers(headers);
+    if !matches_content_type(&config.passthrough_content_types, content_type.as_deref()?) {
+        return None;
+    }
+    Some(tracker)
+}
+
+/// Content-Type과 관계없이 응답 헤더의 본문 길이 정보로 본문 추적기 생성 (검사 크기 한도 초과 시)
+pub fn body_tracker(headers: &[u8]) -> BodyTracker {
+    parse_headers(headers).1
+}
+
+/// 요청 메서드까지 고려한 응답 본문 추적기 (HEAD 응답과 1xx/204/304 응답은 헤더와 관계없이 본문 없음)
+pub fn response_body_tracker(head_request: bool, headers: &[u8]) -> BodyTracker {
+    let status = status_code(headers).unwrap_or(0);
+    if head_request || (100..200).contains(&status) && status != 101 || status == 204 || status == 304 {
+        return BodyTracker { framing: Framing::Length(0) };
+    }
+    body_tracker(headers)
+}
+
+/// 최종 응답 앞에 오는 1xx 중간 응답인지 (101은 프로토콜 전환이라 제외)
+pub fn is_interim_response(headers: &[u8]) -> bool {
+    status_code(headers).is_some_and(|status| (100..200).contains(&status) && status != 101)
+}
+
+/// 101 응답으로 프로토콜을 전환했는지 (이후 연결은 HTTP 메시지가 아닌 바이트 스트림)
+pub fn is_protocol_switch(headers: &[u8]) -> bool {
+    status_code(headers) == Some(101)
+}
+
+/// 메시지(요청 또는 응답) 뒤에도 연결이 유지되는지
+///
+/// `Connection` 헤더에 `close`가 있으면 유지하지 않고, 없으면 HTTP/1.1은 유지,
+/// HTTP/1.0은 `keep-alive`가 있을 때만 유지합니다.
+pub fn is_persistent(headers: &[u8]) -> bool {
+    let headers = String::from_utf8_lossy(headers);
+    let mut lines = headers.lines();
+    let start_line = lines.next().unwrap_or_default();
+    let http_10 = start_line.starts_with("HTTP/1.0") || start_line.ends_with("HTTP/1.0");
+
+    let mut close = false;
+    let mut keep_alive = false;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !name.trim().eq_ignore_ascii_case("connection") {
+            continue;
+        }
+        for token in value.split(',').map(str::trim) {
+            close |= token.eq_ignore_ascii_case("close");
+            keep_alive |= token.eq_ignore_ascii_case("keep-alive");
+        }
+    }
+    !close && (!http_10 || keep_alive)
+}
+
+/// 응답 상태 줄의 상태 코드
+fn status_code(headers: &[u8]) -> Option<u16> {
+    let end = headers.iter().position(|&b| b == b'\n').unwrap_or(headers.len());
+    let line = std::str::from_utf8(&headers[..end]).ok()?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// 응답 헤더에서 Content-Type과 본문 추적기 추출
+fn parse_headers(headers: &[u8]) -> (Option<String>, BodyTracker) {
+    let headers = String::from_utf8_lossy(headers);
+    let mut content_type = None;
+    let mut content_length = None;
+    let mut chunked = false;
+
+    for line in headers.lines().skip(1) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-type") {
+            content_type = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse::<u64>().ok();
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.to_ascii_lowercase().contains("chunked");
+        }
+    }
+
+    let framing = if chunked {
+        Framing::Chunked(ChunkState::Size { size: 0, in_extension: false })
+    } else if let Some(length) = content_length {
+        Framing::Length(length)
+    } else {
+        Framing::UntilClose
+    };
+    (content_type, BodyTracker { framing })
+}
+
+/// Content-Type이 패턴 목록(`video/*`, `application/octet-stream` 등)에 해당하는지 확인
+fn matches_content_type(patterns: &[String], content_type: &str) -> bool {
+    let media_type = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        match pattern.strip_suffix("/*") {
+            Some(main_type) => media_type.split('/').next() == Some(main_type),
+            None => media_type == pattern,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunked() -> BodyTracker {
+        body_tracker(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked")
+    }
+
+    #[test]
+    fn chunked_body_ends_after_last_chunk() {
+        let mut tracker = chunked();
+        assert_eq!(tracker.feed(b"5\r\nhello\r\n0\r\n\r\nnext"), Some(15));
+    }
+
+    #[test]
+    fn oversized_chunk_size_is_malformed() {
+        let mut tracker = chunked();
+        assert_eq!(tracker.feed(b"10000000000000000\r\n"), None);
+        assert!(tracker.until_close(), "크기가 u64를 넘으면 연결 종료까지 전달");
+        assert_eq!(tracker.feed(b"0\r\n\r\n"), None);
+    }
+}
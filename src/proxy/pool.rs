@@ -0,0 +1,224 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{debug, info};
+use once_cell::sync::OnceCell;
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream as ClientTlsStream;
+
+use crate::config::Config;
+use crate::metrics::Metrics;
+
+/// HTTP/1.1 연결의 ALPN 식별자 (평문 연결과 ALPN을 협상하지 않은 TLS 연결도 HTTP/1.1로 봄)
+pub const ALPN_HTTP1: &str = "http/1.1";
+
+/// 업스트림 연결 풀 키
+///
+/// 꺼낼 때는 중계할 수 있는 프로토콜(HTTP/1.1)로 찾고, 반환할 때는 연결에서 실제로 협상한 프로토콜로 넣으므로
+/// 다른 프로토콜을 협상한 연결은 HTTP/1.1 요청에 재사용되지 않습니다.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PoolKey {
+    pub host: String,
+    pub port: u16,
+    /// 업스트림과 협상한 ALPN
+    pub alpn: String,
+    /// 가로챈 연결이면 업스트림에 보낸 SNI (평문 연결이면 None)
+    pub sni: Option<String>,
+}
+
+impl PoolKey {
+    /// 평문 HTTP 연결 키 (ALPN 협상이 없으므로 HTTP/1.1)
+    pub fn plain(host: &str, port: u16) -> Self {
+        Self { host: host.to_string(), port, alpn: ALPN_HTTP1.to_string(), sni: None }
+    }
+
+    /// 가로챈 HTTP/1.1 요청에 쓸 TLS 연결 키 (같은 SNI로 맺고 HTTP/1.1을 협상한 연결만 일치)
+    pub fn intercepted(host: &str, port: u16, sni: &str) -> Self {
+        Self { host: host.to_string(), port, alpn: ALPN_HTTP1.to_string(), sni: Some(sni.to_string()) }
+    }
+
+    /// 맺은 TLS 연결의 키 (연결에서 협상한 ALPN 사용)
+    pub fn negotiated(host: &str, port: u16, sni: &str, stream: &ClientTlsStream<TcpStream>) -> Self {
+        let alpn = stream.get_ref().1.alpn_protocol()
+            .map_or_else(|| ALPN_HTTP1.to_string(), |alpn| String::from_utf8_lossy(alpn).into_owned());
+        Self { host: host.to_string(), port, alpn, sni: Some(sni.to_string()) }
+    }
+}
+
+// 전역 업스트림 연결 풀 (upstream_pool_enabled인 경우에만 초기화)
+static UPSTREAM_POOL: OnceCell<Arc<UpstreamPool>> = OnceCell::new();
+
+/// 풀에 보관하는 업스트림 연결 (평문 TCP 또는 가로챈 세션의 업스트림 TLS)
+enum PooledStream {
+    Plain(TcpStream),
+    Tls(Box<ClientTlsStream<TcpStream>>),
+}
+
+impl PooledStream {
+    fn tcp(&self) -> &TcpStream {
+        match self {
+            PooledStream::Plain(stream) => stream,
+            PooledStream::Tls(stream) => stream.get_ref().0,
+        }
+    }
+}
+
+/// 풀에 반환할 수 있는 업스트림 연결
+///
+/// HTTP 메시지 단위로 중계해 응답 경계까지 정확히 읽은 연결만 이 타입으로 감쌀 수 있습니다
+/// (평문은 `proxy::http`, 가로챈 TLS는 `proxy::tls`).
+/// 가로채지 않은 CONNECT 터널이나 101 응답으로 프로토콜을 전환한 연결은 바이트를 그대로 중계하므로
+/// 다음 요청이 이전 스트림의 나머지 바이트를 응답으로 받을 수 있어 감싸지 않으며, 따라서 풀에 들어갈 수 없습니다.
+pub struct ReusableConnection(PooledStream);
+
+impl ReusableConnection {
+    /// 요청과 응답이 모두 연결 유지이고 응답 본문이 정확히 끝난 HTTP 교환 뒤의 연결
+    pub(crate) fn after_http_exchange(stream: TcpStream) -> Self {
+        Self(PooledStream::Plain(stream))
+    }
+
+    /// 가로챈 세션에서 클라이언트가 요청 사이에 연결을 닫고, 모든 응답이 연결 유지로 정확히 끝난 뒤의 업스트림 TLS 연결
+    pub(crate) fn after_tls_exchange(stream: ClientTlsStream<TcpStream>) -> Self {
+        Self(PooledStream::Tls(Box::new(stream)))
+    }
+}
+
+/// 유휴 상태로 보관 중인 연결
+struct IdleConnection {
+    stream: PooledStream,
+    idle_since: Instant,
+}
+
+/// keep-alive 업스트림 연결 풀
+pub struct UpstreamPool {
+    idle: Mutex<HashMap<PoolKey, VecDeque<IdleConnection>>>,
+    max_idle_per_host: usize,
+    idle_timeout: Duration,
+    metrics: Arc<Metrics>,
+}
+
+/// 설정에 따라 전역 업스트림 연결 풀 초기화 및 만료 연결 정리 태스크 시작
+pub fn init_upstream_pool(config: &Config) {
+    if !config.upstream_pool_enabled {
+        return;
+    }
+
+    let pool = Arc::new(UpstreamPool {
+        idle: Mutex::new(HashMap::new()),
+        max_idle_per_host: config.upstream_pool_max_idle_per_host,
+        idle_timeout: Duration::from_secs(config.upstream_pool_idle_timeout_seconds),
+        metrics: Metrics::new(),
+    });
+
+    if UPSTREAM_POOL.set(Arc::clone(&pool)).is_err() {
+        return;
+    }
+
+    info!("업스트림 연결 풀 활성화: 호스트당 최대 유휴 {}, 유휴 타임아웃 {}초",
+          config.upstream_pool_max_idle_per_host, config.upstream_pool_idle_timeout_seconds);
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(pool.idle_timeout);
+        loop {
+            interval.tick().await;
+            pool.evict_expired();
+
+            let (hits, misses, idle) = pool.metrics.upstream_pool_stats();
+            let total = hits + misses;
+            let hit_rate = if total > 0 { hits as f64 * 100.0 / total as f64 } else { 0.0 };
+            debug!("업스트림 연결 풀: 유휴 {} 개, 적중률 {:.1}% ({}/{})", idle, hit_rate, hits, total);
+        }
+    });
+}
+
+/// 전역 업스트림 연결 풀 (비활성화 시 None)
+///
+/// 평문 HTTP 요청과 가로챈 HTTPS 세션의 업스트림 연결을 꺼내고 반환합니다.
+/// 가로채지 않은 CONNECT 터널은 풀을 쓰지 않고 항상 새로 연결합니다.
+pub fn upstream_pool() -> Option<Arc<UpstreamPool>> {
+    UPSTREAM_POOL.get().cloned()
+}
+
+impl UpstreamPool {
+    /// 평문 HTTP 요청에 재사용할 유휴 연결 꺼내기
+    pub fn checkout(&self, key: &PoolKey) -> Option<TcpStream> {
+        match self.take(key)? {
+            PooledStream::Plain(stream) => Some(stream),
+            PooledStream::Tls(_) => None,
+        }
+    }
+
+    /// 가로챈 세션에 재사용할 업스트림 TLS 연결 꺼내기
+    pub fn checkout_tls(&self, key: &PoolKey) -> Option<ClientTlsStream<TcpStream>> {
+        match self.take(key)? {
+            PooledStream::Tls(stream) => Some(*stream),
+            PooledStream::Plain(_) => None,
+        }
+    }
+
+    /// 재사용 가능한 유휴 연결 꺼내기 (가장 최근에 반환된 연결 우선)
+    fn take(&self, key: &PoolKey) -> Option<PooledStream> {
+        let mut idle = self.idle.lock().unwrap();
+        let mut found = None;
+
+        if let Some(queue) = idle.get_mut(key) {
+            while let Some(conn) = queue.pop_back() {
+                if conn.idle_since.elapsed() < self.idle_timeout && is_reusable(conn.stream.tcp()) {
+                    found = Some(conn.stream);
+                    break;
+                }
+                debug!("업스트림 유휴 연결 폐기: {}:{} (만료 또는 종료됨)", key.host, key.port);
+            }
+        }
+
+        self.metrics.set_upstream_pool_idle(Self::count(&idle));
+        drop(idle);
+
+        if found.is_some() {
+            self.metrics.upstream_pool_hit();
+        } else {
+            self.metrics.upstream_pool_miss();
+        }
+        found
+    }
+
+    /// 응답이 끝난 연결을 풀에 반환 (호스트당 최대 개수 초과 시 닫음)
+    pub fn release(&self, key: PoolKey, connection: ReusableConnection) {
+        let ReusableConnection(stream) = connection;
+        let mut idle = self.idle.lock().unwrap();
+        let queue = idle.entry(key).or_default();
+
+        if queue.len() >= self.max_idle_per_host {
+            return;
+        }
+        queue.push_back(IdleConnection { stream, idle_since: Instant::now() });
+
+        self.metrics.set_upstream_pool_idle(Self::count(&idle));
+    }
+
+    /// 유휴 타임아웃이 지난 연결 정리
+    fn evict_expired(&self) {
+        let mut idle = self.idle.lock().unwrap();
+        for queue in idle.values_mut() {
+            queue.retain(|conn| conn.idle_since.elapsed() < self.idle_timeout);
+        }
+        idle.retain(|_, queue| !queue.is_empty());
+
+        self.metrics.set_upstream_pool_idle(Self::count(&idle));
+    }
+
+    /// 전체 유휴 연결 수
+    fn count(idle: &HashMap<PoolKey, VecDeque<IdleConnection>>) -> u64 {
+        idle.values().map(|queue| queue.len() as u64).sum()
+    }
+}
+
+/// 연결이 재사용 가능한지 확인 (상대가 닫았거나 읽지 않은 데이터가 남아 있으면 불가)
+fn is_reusable(stream: &TcpStream) -> bool {
+    let mut probe = [0u8; 1];
+    match stream.try_read(&mut probe) {
+        Err(e) => e.kind() == std::io::ErrorKind::WouldBlock,
+        Ok(_) => false,
+    }
+}
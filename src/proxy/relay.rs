@@ -0,0 +1,331 @@
+// 세션 종료 방식(FIN/RST) 보존
+// 한쪽 피어가 RST로 연결을 끊으면 다른 쪽에도 FIN 대신 RST(SO_LINGER 0 후 닫기)로 전달해
+// RST로 오류를 알리는 프로토콜의 의미가 프록시를 거쳐도 유지되도록 합니다.
+
+use std::io;
+use std::net::Shutdown;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use bytes::{Buf, BytesMut};
+use log::debug;
+use socket2::{SockRef, Socket};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+
+use crate::config::{FlowControlConfig, WriteCoalescingConfig};
+use crate::constants::BUFFER_SIZE_MEDIUM;
+use crate::metrics::Metrics;
+
+/// 세션 종료 방식
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CloseKind {
+    /// 정상 종료 (FIN)
+    #[default]
+    Fin,
+    /// 클라이언트가 RST로 종료해 업스트림에도 RST 전달
+    ClientReset,
+    /// 업스트림이 RST로 종료해 클라이언트에도 RST 전달
+    UpstreamReset,
+}
+
+impl CloseKind {
+    /// 로그에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            CloseKind::Fin => "fin",
+            CloseKind::ClientReset => "client_reset",
+            CloseKind::UpstreamReset => "upstream_reset",
+        }
+    }
+}
+
+/// RST로 끊긴 연결의 읽기/쓰기 오류인지
+pub fn is_reset(error: &io::Error) -> bool {
+    matches!(error.kind(), io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionAborted | io::ErrorKind::BrokenPipe)
+}
+
+/// 클라이언트/업스트림 소켓 사이의 RST 전달
+///
+/// 스트림을 분할하거나 TLS로 감싼 뒤에도 설정할 수 있도록 소켓을 복제해 둡니다.
+/// SO_LINGER는 소켓 단위 설정이므로 복제한 소켓에 설정해도 원래 스트림이 닫힐 때 RST가 나갑니다.
+pub struct ResetPropagation {
+    client: Socket,
+    upstream: Socket,
+    // 먼저 감지한 RST (이후 감지는 무시)
+    reset: OnceLock<CloseKind>,
+}
+
+impl ResetPropagation {
+    pub fn new(client: &TcpStream, upstream: &TcpStream) -> io::Result<Self> {
+        Ok(Self {
+            client: SockRef::from(client).try_clone()?,
+            upstream: SockRef::from(upstream).try_clone()?,
+            reset: OnceLock::new(),
+        })
+    }
+
+    /// 클라이언트 쪽 읽기/쓰기에서 RST 감지
+    pub fn client_reset(&self) {
+        self.propagate(CloseKind::ClientReset, &self.upstream);
+    }
+
+    /// 업스트림 쪽 읽기/쓰기에서 RST 감지
+    pub fn upstream_reset(&self) {
+        self.propagate(CloseKind::UpstreamReset, &self.client);
+    }
+
+    /// 클라이언트 쪽(`client_side`) 또는 업스트림 쪽에서 RST 감지
+    fn side_reset(&self, client_side: bool) {
+        if client_side {
+            self.client_reset();
+        } else {
+            self.upstream_reset();
+        }
+    }
+
+    /// RST를 감지했는지 (정상 종료 FIN을 보내지 않아야 함)
+    pub fn is_reset(&self) -> bool {
+        self.reset.get().is_some()
+    }
+
+    /// 세션 종료 방식
+    pub fn close_kind(&self) -> CloseKind {
+        self.reset.get().copied().unwrap_or_default()
+    }
+
+    /// 세션 중단: 양쪽 소켓이 닫힐 때 RST를 보내도록 설정하고 읽기 방향을 닫아 양쪽 전달을 바로 끝냄
+    pub fn abort(&self) {
+        for socket in [&self.client, &self.upstream] {
+            if let Err(e) = socket.set_linger(Some(Duration::ZERO)) {
+                debug!("SO_LINGER 0 설정 실패: {}", e);
+            }
+            let _ = socket.shutdown(Shutdown::Read);
+        }
+    }
+
+    /// 다른 쪽 소켓이 닫힐 때 RST를 보내도록 설정하고, 양쪽 읽기 방향을 닫아 남은 전달 방향도 바로 끝냄
+    fn propagate(&self, kind: CloseKind, other: &Socket) {
+        if self.reset.set(kind).is_err() {
+            return;
+        }
+        debug!("RST 감지 ({}), 상대 연결도 RST로 종료", kind.name());
+        if let Err(e) = other.set_linger(Some(Duration::ZERO)) {
+            debug!("SO_LINGER 0 설정 실패: {}", e);
+        }
+        let _ = self.client.shutdown(Shutdown::Read);
+        let _ = self.upstream.shutdown(Shutdown::Read);
+    }
+}
+
+/// 평문 TCP 양방향 전달 (copy_bidirectional과 같지만 RST는 다른 쪽에 RST로 전달)
+///
+/// `coalescing`을 사용하면 클라이언트→업스트림 방향의 작은 조각을 모아 한 번에 씁니다.
+/// 방향마다 아직 쓰지 못한 데이터는 `flow`의 워터마크 사이에서만 쌓입니다 (사용하지 않으면 한 번 읽은 만큼).
+/// 한 번에 읽는 크기는 `read_chunk_size`(없으면 중간 버퍼 크기)입니다.
+/// 반환값: (클라이언트→업스트림 바이트, 업스트림→클라이언트 바이트, 종료 방식)
+pub async fn relay_tcp(
+    client: &mut TcpStream,
+    upstream: &mut TcpStream,
+    coalescing: &WriteCoalescingConfig,
+    flow: &FlowControlConfig,
+    read_chunk_size: Option<usize>,
+) -> io::Result<(u64, u64, CloseKind)> {
+    let resets = ResetPropagation::new(client, upstream)?;
+    let (mut client_read, mut client_write) = client.split();
+    let (mut upstream_read, mut upstream_write) = upstream.split();
+    let coalescing = Some(coalescing).filter(|settings| settings.is_enabled());
+    let flow = Some(flow).filter(|settings| settings.is_enabled());
+    let chunk_size = read_chunk_size.unwrap_or(BUFFER_SIZE_MEDIUM);
+
+    let (bytes_in, bytes_out) = tokio::join!(
+        pump(&mut client_read, &mut upstream_write, &resets, true, coalescing, flow, chunk_size),
+        pump(&mut upstream_read, &mut client_write, &resets, false, None, flow, chunk_size),
+    );
+    Ok((bytes_in?, bytes_out?, resets.close_kind()))
+}
+
+/// 한 방향 전달: 읽는 쪽이 FIN이면 남은 데이터를 모두 쓴 뒤 쓰는 쪽에도 FIN, 어느 쪽이든 RST면 전달하고 중단
+///
+/// 읽기와 쓰기를 함께 진행하며, 쓰지 못한 데이터가 `high_watermark`에 닿으면 `low_watermark` 이하로 줄 때까지 읽기를 멈춥니다.
+/// `flow`가 없으면 `chunk_size`를 상한으로 두고 모두 쓴 뒤 다시 읽습니다 (흐름 제어 메트릭에는 세지 않음).
+/// `coalescing`이 있으면 모은 데이터가 `max_bytes`보다 작은 동안 첫 조각을 받은 뒤 `max_delay_ms`까지 쓰기를 미룹니다.
+async fn pump<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    resets: &ResetPropagation,
+    from_client: bool,
+    coalescing: Option<&WriteCoalescingConfig>,
+    flow: Option<&FlowControlConfig>,
+    chunk_size: usize,
+) -> io::Result<u64>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buffer = vec![0u8; chunk_size];
+    let (high_watermark, low_watermark) = flow.map_or((chunk_size, 0), |flow| (flow.high_watermark, flow.low_watermark));
+    let mut pending = BytesMut::new();
+    let mut total = 0u64;
+    let mut reads = 0u64;
+    let mut writes = 0u64;
+    let mut finished = false;
+    let mut paused = false;
+    // 쓰기 합치기 중 모은 데이터를 써야 하는 시각
+    let mut flush_at: Option<Instant> = None;
+    loop {
+        if finished && pending.is_empty() {
+            if !resets.is_reset() {
+                let _ = writer.shutdown().await;
+            }
+            log_coalesced(coalescing, reads, writes);
+            return Ok(total);
+        }
+
+        // 작은 조각이면 한도가 차거나 대기 시간이 지날 때까지 더 모음 (그 사이 FIN이면 모은 데이터를 바로 씀)
+        let coalesce_until = flush_at.filter(|_| !finished && coalescing.is_some_and(|settings| pending.len() < settings.max_bytes));
+        // 상한 워터마크를 넘지 않을 만큼만 읽음
+        let room = buffer.len().min(high_watermark.saturating_sub(pending.len()));
+
+        tokio::select! {
+            read = reader.read(&mut buffer[..room]), if !finished && !paused => match read {
+                Ok(0) => finished = true,
+                Ok(n) => {
+                    reads += 1;
+                    if pending.is_empty() {
+                        flush_at = coalescing.map(|settings| Instant::now() + Duration::from_millis(settings.max_delay_ms));
+                    }
+                    pending.extend_from_slice(&buffer[..n]);
+                    if pending.len() >= high_watermark {
+                        paused = true;
+                        if flow.is_some() {
+                            Metrics::new().flow_control_paused();
+                            debug!("[흐름 제어] 미전송 {}바이트로 {} 읽기 중지", pending.len(), direction(from_client));
+                        }
+                    }
+                },
+                Err(e) if is_reset(&e) => {
+                    resets.side_reset(from_client);
+                    return Ok(total);
+                },
+                Err(e) => return Err(e),
+            },
+            written = writer.write(&pending), if !pending.is_empty() && coalesce_until.is_none() => match written {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    pending.advance(n);
+                    total += n as u64;
+                    writes += 1;
+                    if paused && pending.len() <= low_watermark {
+                        paused = false;
+                        if flow.is_some() {
+                            debug!("[흐름 제어] 미전송 {}바이트로 {} 읽기 재개", pending.len(), direction(from_client));
+                        }
+                    }
+                },
+                Err(e) if is_reset(&e) => {
+                    resets.side_reset(!from_client);
+                    return Ok(total);
+                },
+                Err(e) => return Err(e),
+            },
+            _ = tokio::time::sleep_until(coalesce_until.unwrap_or_else(Instant::now)), if coalesce_until.is_some() => flush_at = None,
+        }
+    }
+}
+
+/// 로그에 쓰는 전달 방향
+fn direction(from_client: bool) -> &'static str {
+    if from_client { "클라이언트→업스트림" } else { "업스트림→클라이언트" }
+}
+
+/// 쓰기 합치기 결과 (읽기 횟수 대비 업스트림 쓰기 횟수)
+fn log_coalesced(coalescing: Option<&WriteCoalescingConfig>, reads: u64, writes: u64) {
+    if coalescing.is_some() && reads > 0 {
+        debug!("[쓰기 합치기] 클라이언트 읽기 {}회를 업스트림 쓰기 {}회로 전달", reads, writes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+    use crate::config::SocketBufferConfig;
+    use crate::constants::BUFFER_SIZE_SMALL;
+    use crate::proxy::sockbuf::apply_socket_buffers;
+
+    const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn pattern(index: usize) -> u8 {
+        (index % 251) as u8
+    }
+
+    // 커널 버퍼가 데이터를 대신 쌓지 않도록 버퍼 크기를 고정한 (연결한 쪽, 받은 쪽) 소켓 쌍
+    async fn small_buffer_pair() -> (TcpStream, TcpStream) {
+        let small = SocketBufferConfig { recv_bytes: Some(BUFFER_SIZE_SMALL), send_bytes: Some(BUFFER_SIZE_SMALL) };
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let connected = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (accepted, _) = listener.accept().await.unwrap();
+        for stream in [&connected, &accepted] {
+            apply_socket_buffers(stream, &small, "test", "loopback");
+        }
+        (connected, accepted)
+    }
+
+    #[test]
+    fn read_limit_follows_high_watermark() {
+        let flow = FlowControlConfig { high_watermark: BUFFER_SIZE_SMALL, low_watermark: BUFFER_SIZE_SMALL / 4 };
+        assert!(!FlowControlConfig::default().is_enabled());
+        assert_eq!(flow.read_limit(None), Some(BUFFER_SIZE_SMALL));
+        assert_eq!(flow.read_limit(Some(BUFFER_SIZE_SMALL / 2)), Some(BUFFER_SIZE_SMALL / 2));
+        assert_eq!(FlowControlConfig::default().read_limit(Some(1024)), Some(1024));
+    }
+
+    #[tokio::test]
+    async fn reads_pause_while_client_is_not_reading() {
+        const TOTAL: usize = 4 * 1024 * 1024;
+        crate::db::config::DbConfig::disable();
+        let (mut client, mut client_side) = small_buffer_pair().await;
+        let (mut upstream_side, mut upstream) = small_buffer_pair().await;
+
+        // 중계를 시작하기 전에 기준값을 읽어야 곧바로 멈춘 경우도 셈
+        let metrics = Metrics::new();
+        let paused = || metrics.get("flow_control_paused");
+        let paused_before = paused();
+
+        let flow = FlowControlConfig { high_watermark: BUFFER_SIZE_SMALL, low_watermark: BUFFER_SIZE_SMALL / 4 };
+        let relay = tokio::spawn(async move {
+            relay_tcp(&mut client_side, &mut upstream_side, &WriteCoalescingConfig::default(), &flow, None).await
+        });
+        let (sent_tx, mut sent) = oneshot::channel();
+        let data: Vec<u8> = (0..TOTAL).map(pattern).collect();
+        let sender = tokio::spawn(async move {
+            let result = upstream.write_all(&data).await;
+            let _ = upstream.shutdown().await;
+            let _ = sent_tx.send(result.is_ok());
+            // 클라이언트가 다 읽고 닫을 때까지 유지
+            let mut rest = Vec::new();
+            let _ = upstream.read_to_end(&mut rest).await;
+        });
+
+        // 클라이언트가 읽지 않는 동안 업스트림 쪽 읽기가 멈춰 빠른 쪽의 쓰기도 끝나지 못해야 함
+        let started = Instant::now();
+        while paused() == paused_before {
+            assert!(started.elapsed() < IO_TIMEOUT, "relay reads did not pause at the high watermark");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(sent.try_recv().is_err(), "fast upstream finished writing while the client was not reading");
+
+        let mut received = Vec::with_capacity(TOTAL);
+        tokio::time::timeout(IO_TIMEOUT * 2, client.read_to_end(&mut received)).await.unwrap().unwrap();
+        assert_eq!(received.len(), TOTAL);
+        assert!(received.iter().enumerate().all(|(index, byte)| *byte == pattern(index)));
+
+        drop(client);
+        let _ = tokio::time::timeout(IO_TIMEOUT, relay).await;
+        sender.abort();
+    }
+}
@@ -0,0 +1,191 @@
+// 가로챈 HTTPS 응답에 보안 헤더 삽입 (security_headers)
+// 같은 이름의 헤더가 이미 있는지 알아야 하므로 Via 삽입기와 달리 응답 헤더 블록이 끝날 때까지 전달을 미룹니다.
+// 본문은 손대지 않고, 헤더 블록을 찾지 못하고 한도를 넘으면 삽입을 포기하고 모은 조각을 그대로 보냅니다.
+
+use std::borrow::Cow;
+
+use crate::config::{Config, SecurityHeaderMode, SecurityHeaderRule};
+use crate::proxy::passthrough::is_interim_response;
+
+/// 헤더 블록 끝을 찾지 못하면 삽입을 포기하는 길이
+const MAX_RESPONSE_HEAD_LEN: usize = 64 * 1024;
+
+const HEADER_END: &[u8] = b"\r\n\r\n";
+
+/// 응답 헤더 블록에 설정한 보안 헤더를 넣는 삽입기 (연결마다 하나)
+pub struct SecurityHeaderInjector {
+    rules: Vec<SecurityHeaderRule>,
+    /// 헤더 블록 끝을 기다리는 중인지 여부
+    pending: bool,
+    /// 전달을 미룬 응답 앞부분
+    head: Vec<u8>,
+}
+
+impl SecurityHeaderInjector {
+    /// security_headers가 활성화된 경우에만 삽입기 생성
+    pub fn new(config: &Config) -> Option<Self> {
+        if !config.security_headers.enabled {
+            return None;
+        }
+
+        Some(Self {
+            rules: config.security_headers.resolved(),
+            pending: false,
+            head: Vec::new(),
+        })
+    }
+
+    /// 다음에 전달할 조각부터 새 응답이 시작됨을 표시
+    pub fn start_message(&mut self) {
+        self.pending = true;
+        self.head.clear();
+    }
+
+    /// 전달할 조각 처리 (헤더 블록이 끝나기 전이면 빈 조각을 돌려주고 내부에 보관)
+    pub fn apply<'a>(&mut self, data: Cow<'a, [u8]>) -> Cow<'a, [u8]> {
+        if !self.pending {
+            return data;
+        }
+
+        self.head.extend_from_slice(&data);
+        let mut output = Vec::with_capacity(self.head.len() + 256);
+        let mut start = 0;
+        loop {
+            let Some(end) = find_header_end(&self.head[start..]).map(|end| start + end) else {
+                if self.head.len() - start > MAX_RESPONSE_HEAD_LEN {
+                    // 헤더 블록이 너무 길면 삽입하지 않고 그대로 전달
+                    self.pending = false;
+                    output.extend_from_slice(&self.head);
+                    self.head.clear();
+                } else if start > 0 {
+                    output.extend_from_slice(&self.head[..start]);
+                    self.head.drain(..start);
+                }
+                return Cow::Owned(output);
+            };
+
+            let head = &self.head[start..end];
+            if is_interim_response(head) {
+                // 1xx 중간 응답은 그대로 보내고 이어지는 최종 응답 헤더를 기다림
+                start = end + HEADER_END.len();
+                continue;
+            }
+
+            output.extend_from_slice(&self.head[..start]);
+            output.extend_from_slice(&inject_headers(head, &self.rules));
+            output.extend_from_slice(&self.head[end..]);
+            self.pending = false;
+            self.head.clear();
+            return Cow::Owned(output);
+        }
+    }
+
+    /// 연결이 끝날 때 아직 보내지 못한 응답 앞부분 (헤더 블록이 끝나지 않은 채 종료된 경우)
+    pub fn take_pending(&mut self) -> Vec<u8> {
+        self.pending = false;
+        std::mem::take(&mut self.head)
+    }
+}
+
+fn find_header_end(data: &[u8]) -> Option<usize> {
+    data.windows(HEADER_END.len()).position(|window| window == HEADER_END)
+}
+
+/// 헤더 블록(시작 줄 포함, 마지막 빈 줄 제외)에 보안 헤더 적용
+///
+/// HTTP/1.x 응답이 아니면 그대로 돌려줍니다.
+pub fn inject_headers(head: &[u8], rules: &[SecurityHeaderRule]) -> Vec<u8> {
+    let Ok(text) = std::str::from_utf8(head) else {
+        return head.to_vec();
+    };
+    let mut lines = text.split("\r\n");
+    let start_line = lines.next().unwrap_or_default();
+    if !start_line.starts_with("HTTP/1.") {
+        return head.to_vec();
+    }
+
+    let header_name = |line: &str| line.split_once(':').map(|(name, _)| name.trim().to_string());
+    let mut present: Vec<String> = Vec::new();
+    let mut kept: Vec<&str> = Vec::new();
+    for line in lines {
+        let name = header_name(line);
+        let overwritten = name.as_ref().is_some_and(|name| rules.iter().any(|rule| {
+            rule.mode == Some(SecurityHeaderMode::Overwrite) && rule.name.eq_ignore_ascii_case(name)
+        }));
+        if let Some(name) = name {
+            present.push(name);
+        }
+        if !overwritten {
+            kept.push(line);
+        }
+    }
+
+    let mut output = String::with_capacity(head.len() + 256);
+    output.push_str(start_line);
+    for line in kept {
+        output.push_str("\r\n");
+        output.push_str(line);
+    }
+    for rule in rules {
+        let exists = present.iter().any(|name| rule.name.eq_ignore_ascii_case(name));
+        if rule.mode == Some(SecurityHeaderMode::Overwrite) || !exists {
+            output.push_str(&format!("\r\n{}: {}", rule.name, rule.value));
+        }
+    }
+    output.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SecurityHeaderPreset;
+
+    fn injector(config: &Config) -> SecurityHeaderInjector {
+        let mut injector = SecurityHeaderInjector::new(config).expect("security header injector should be enabled");
+        injector.start_message();
+        injector
+    }
+
+    #[test]
+    fn head_is_held_until_complete_and_rules_follow_their_mode() {
+        let mut config = Config::new();
+        config.security_headers.enabled = true;
+        config.security_headers.preset = SecurityHeaderPreset::Basic;
+        config.security_headers.headers = vec![
+            SecurityHeaderRule { name: "Content-Security-Policy".to_string(), value: "frame-ancestors 'self'".to_string(),
+                                 mode: Some(SecurityHeaderMode::Overwrite) },
+        ];
+        config.validate().unwrap();
+        let mut injector = injector(&config);
+
+        let response = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\nx-frame-options: DENY\r\n\
+            Content-Security-Policy: default-src *\r\ncontent-security-policy: script-src *\r\nContent-Length: 2\r\n\r\nok";
+        let mut forwarded = Vec::new();
+        for chunk in response.chunks(7) {
+            let data = injector.apply(Cow::Borrowed(chunk));
+            // 최종 응답 헤더 블록은 한 번에 (삽입한 헤더와 함께) 전달
+            let text = String::from_utf8_lossy(&data);
+            assert_eq!(text.contains("200 OK"), text.contains("nosniff"), "partial head forwarded: {:?}", text);
+            forwarded.extend_from_slice(&data);
+        }
+        forwarded.extend_from_slice(&injector.take_pending());
+        assert_eq!(String::from_utf8_lossy(&forwarded), "HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\nx-frame-options: DENY\r\nContent-Length: 2\r\n\
+            X-Content-Type-Options: nosniff\r\nReferrer-Policy: strict-origin-when-cross-origin\r\n\
+            Content-Security-Policy: frame-ancestors 'self'\r\n\r\nok");
+
+        // 헤더 블록이 끝난 뒤의 조각은 그대로 전달
+        assert!(matches!(injector.apply(Cow::Borrowed(b"more".as_slice())), Cow::Borrowed(b"more")));
+    }
+
+    #[test]
+    fn overwrite_mode_replaces_existing_headers() {
+        let mut config = Config::new();
+        config.security_headers.enabled = true;
+        config.security_headers.preset = SecurityHeaderPreset::Basic;
+        config.security_headers.mode = SecurityHeaderMode::Overwrite;
+        let data = injector(&config).apply(Cow::Borrowed(b"HTTP/1.1 204 No Content\r\nX-Frame-Options: DENY\r\n\r\n".as_slice()));
+        let text = String::from_utf8_lossy(&data);
+        assert!(!text.contains("DENY"), "{:?}", text);
+        assert!(text.contains("\r\nX-Frame-Options: SAMEORIGIN\r\n"), "{:?}", text);
+    }
+}
@@ -0,0 +1,73 @@
+// 클라이언트/업스트림 TCP 소켓 송수신 버퍼 크기 (client_socket_buffers, upstream_socket_buffers)
+// 대역폭 지연 곱이 큰 링크에서 처리량을 높이도록 accept/connect 직후 SO_RCVBUF/SO_SNDBUF를 설정합니다.
+// 커널은 요청 값을 조정(Linux는 2배로 기록, net.core.rmem_max/wmem_max로 제한)하므로 실제 적용 값을 다시 읽어 debug로 남깁니다.
+
+use log::{debug, warn};
+use socket2::SockRef;
+use tokio::net::TcpStream;
+
+use crate::config::{Config, MAX_SOCKET_BUFFER_BYTES, MIN_SOCKET_BUFFER_BYTES, SocketBufferConfig};
+
+/// 범위 밖의 소켓 버퍼 크기 경고 (시작 시 한 번, 연결마다 조정한 값을 적용)
+pub fn check_socket_buffers(config: &Config) {
+    for (name, settings) in [("client_socket_buffers", &config.client_socket_buffers), ("upstream_socket_buffers", &config.upstream_socket_buffers)] {
+        for (field, bytes) in [("recv_bytes", settings.recv_bytes), ("send_bytes", settings.send_bytes)] {
+            let Some(bytes) = bytes else {
+                continue;
+            };
+            let clamped = SocketBufferConfig::clamp(bytes);
+            if clamped != bytes {
+                warn!("{}.{} 값 {}은(는) 허용 범위({} ~ {}) 밖이므로 {}로 적용합니다",
+                      name, field, bytes, MIN_SOCKET_BUFFER_BYTES, MAX_SOCKET_BUFFER_BYTES, clamped);
+            }
+        }
+    }
+}
+
+/// 연결된 소켓에 버퍼 크기 설정 (실패해도 연결은 그대로 사용)
+///
+/// `peer`는 로그에 남길 연결 상대 (클라이언트 주소 또는 업스트림 호스트)입니다.
+pub fn apply_socket_buffers(stream: &TcpStream, settings: &SocketBufferConfig, side: &str, peer: &str) {
+    if !settings.is_enabled() {
+        return;
+    }
+    let socket = SockRef::from(stream);
+    if let Some(bytes) = settings.recv_bytes {
+        let requested = SocketBufferConfig::clamp(bytes);
+        match socket.set_recv_buffer_size(requested).and_then(|_| socket.recv_buffer_size()) {
+            Ok(effective) => debug!("{} 소켓 수신 버퍼: {} 요청 {}, 적용 {}", side, peer, requested, effective),
+            Err(e) => warn!("{} 소켓 수신 버퍼 설정 실패: {} ({}): {}", side, peer, requested, e),
+        }
+    }
+    if let Some(bytes) = settings.send_bytes {
+        let requested = SocketBufferConfig::clamp(bytes);
+        match socket.set_send_buffer_size(requested).and_then(|_| socket.send_buffer_size()) {
+            Ok(effective) => debug!("{} 소켓 송신 버퍼: {} 요청 {}, 적용 {}", side, peer, requested, effective),
+            Err(e) => warn!("{} 소켓 송신 버퍼 설정 실패: {} ({}): {}", side, peer, requested, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn requested_sizes_are_applied_and_clamped() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (accepted, _) = listener.accept().await.unwrap();
+        let cases = [
+            (&client, SocketBufferConfig { recv_bytes: Some(512 * 1024), send_bytes: Some(384 * 1024) }, (512 * 1024, 384 * 1024)),
+            (&accepted, SocketBufferConfig { recv_bytes: Some(1), send_bytes: Some(0) }, (MIN_SOCKET_BUFFER_BYTES, MIN_SOCKET_BUFFER_BYTES)),
+        ];
+        for (stream, settings, (recv, send)) in cases {
+            apply_socket_buffers(stream, &settings, "test", "loopback");
+            let socket = SockRef::from(stream);
+            // 커널이 값을 키워 기록할 수 있으므로(Linux는 2배) 요청 값 이상인지만 확인
+            assert!(socket.recv_buffer_size().unwrap() >= recv, "{:?}", settings);
+            assert!(socket.send_buffer_size().unwrap() >= send, "{:?}", settings);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::net::Ipv4Addr;
+
+/// SOCKS4 버전 바이트 (요청 첫 바이트)
+pub const SOCKS4_VERSION: u8 = 0x04;
+
+/// SOCKS4 요청의 최대 길이 (사용자 ID와 4a 호스트명 포함)
+pub const SOCKS4_MAX_REQUEST_LEN: usize = 512;
+
+/// SOCKS4 명령
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Socks4Command {
+    Connect,
+    Bind,
+}
+
+/// SOCKS4/4a 요청
+#[derive(Debug, Clone)]
+pub struct Socks4Request {
+    pub command: Socks4Command,
+    /// 대상 호스트 (SOCKS4는 IPv4 주소, SOCKS4a는 호스트명)
+    pub host: String,
+    pub port: u16,
+    pub user_id: String,
+}
+
+/// SOCKS4 요청 파싱 결과
+#[derive(Debug)]
+pub enum Socks4Parse {
+    /// 요청이 아직 다 도착하지 않음
+    Incomplete,
+    /// 요청 형식 오류
+    Invalid(&'static str),
+    /// 파싱된 요청
+    Request(Socks4Request),
+}
+
+/// SOCKS4/4a 요청 파싱
+///
+/// `VN(0x04) CD DSTPORT(2) DSTIP(4) USERID NUL [HOST NUL]` 형식이며,
+/// DSTIP가 `0.0.0.x`(x != 0)이면 사용자 ID 뒤에 호스트명이 오는 SOCKS4a 요청입니다.
+pub fn parse_socks4(data: &[u8]) -> Socks4Parse {
+    if data.len() < 8 {
+        return Socks4Parse::Incomplete;
+    }
+    if data[0] != SOCKS4_VERSION {
+        return Socks4Parse::Invalid("not a SOCKS4 request");
+    }
+
+    let command = match data[1] {
+        0x01 => Socks4Command::Connect,
+        0x02 => Socks4Command::Bind,
+        _ => return Socks4Parse::Invalid("unknown SOCKS4 command"),
+    };
+    let port = u16::from_be_bytes([data[2], data[3]]);
+    let ip = Ipv4Addr::new(data[4], data[5], data[6], data[7]);
+
+    let Some(user_id_len) = data[8..].iter().position(|&b| b == 0) else {
+        return Socks4Parse::Incomplete;
+    };
+    let user_id = String::from_utf8_lossy(&data[8..8 + user_id_len]).to_string();
+    let host_start = 8 + user_id_len + 1;
+
+    let [0, 0, 0, last] = ip.octets() else {
+        return Socks4Parse::Request(Socks4Request { command, host: ip.to_string(), port, user_id });
+    };
+    if last == 0 {
+        return Socks4Parse::Invalid("invalid SOCKS4 destination address 0.0.0.0");
+    }
+
+    // SOCKS4a: 사용자 ID 뒤의 호스트명
+    let Some(host_len) = data[host_start..].iter().position(|&b| b == 0) else {
+        return Socks4Parse::Incomplete;
+    };
+    let host = match std::str::from_utf8(&data[host_start..host_start + host_len]) {
+        Ok(host) if !host.is_empty() => host.to_string(),
+        _ => return Socks4Parse::Invalid("invalid SOCKS4a hostname"),
+    };
+
+    Socks4Parse::Request(Socks4Request { command, host, port, user_id })
+}
+
+/// SOCKS4 응답 (`0x00`, 허용 0x5A / 거부 0x5B, 포트/주소는 무시되므로 0)
+pub fn socks4_reply(granted: bool) -> [u8; 8] {
+    [0x00, if granted { 0x5A } else { 0x5B }, 0, 0, 0, 0, 0, 0]
+}
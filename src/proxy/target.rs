@@ -0,0 +1,130 @@
+use std::net::{IpAddr, Ipv6Addr};
+
+/// 요청 대상(`host:port`, `[v6]:port`, `v6` 등)을 호스트와 포트로 분리합니다.
+///
+/// - `example.com:8443` → (`example.com`, 8443)
+/// - `192.0.2.1` → (`192.0.2.1`, 기본 포트)
+/// - `[2001:db8::1]:443` → (`2001:db8::1`, 443)
+/// - `[fe80::1%25eth0]:443` → (`fe80::1%eth0`, 443) (URL 인코딩된 zone 식별자 복원)
+/// - `2001:db8::1` → (`2001:db8::1`, 기본 포트) (대괄호 없는 IPv6는 포트 없이 주소 전체로 취급)
+///
+/// 반환되는 호스트에는 대괄호가 없으며, 포트가 잘못되었거나 호스트가 비어 있으면 None을 반환합니다.
+pub fn parse_authority(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    let authority = authority.trim();
+
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        // 대괄호 IPv6: 닫는 대괄호 뒤의 콜론만 포트 구분자
+        let end = rest.find(']')?;
+        let host = &rest[..end];
+        let port = match &rest[end + 1..] {
+            "" => default_port,
+            tail => tail.strip_prefix(':')?.parse::<u16>().ok()?,
+        };
+        (host.replace("%25", "%"), port)
+    } else if authority.matches(':').count() > 1 {
+        // 대괄호 없는 IPv6 리터럴
+        (authority.to_string(), default_port)
+    } else if let Some((host, port)) = authority.rsplit_once(':') {
+        (host.to_string(), port.parse::<u16>().ok()?)
+    } else {
+        (authority.to_string(), default_port)
+    };
+
+    if host.is_empty() {
+        return None;
+    }
+    Some((host, port))
+}
+
+/// 호스트 이름의 정규 형식 (IDNA ASCII/punycode, 소문자, 끝의 점 제거)
+///
+/// - `München.DE.` → `xn--mnchen-3ya.de`
+/// - `xn--MNCHEN-3ya.de` → `xn--mnchen-3ya.de`
+///
+/// IP 주소는 그대로 두고, IDNA 변환에 실패한 이름은 소문자로만 바꿉니다.
+pub fn canonical_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('.');
+    if host.parse::<IpAddr>().is_ok() || is_ipv6_host(host) {
+        return host.to_string();
+    }
+    idna::domain_to_ascii(host).unwrap_or_else(|_| host.to_lowercase())
+}
+
+/// 호스트가 IPv6 주소(zone 식별자 포함 가능)인지 확인
+pub fn is_ipv6_host(host: &str) -> bool {
+    let address = host.split('%').next().unwrap_or(host);
+    address.parse::<Ipv6Addr>().is_ok()
+}
+
+/// 로그/헤더용 `host:port` 문자열 (IPv6는 대괄호로 감쌈)
+pub fn format_authority(host: &str, port: u16) -> String {
+    if is_ipv6_host(host) {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+/// 연결용 주소 문자열 (IPv6 zone의 인터페이스 이름은 인덱스로 변환)
+pub fn dial_address(host: &str, port: u16) -> String {
+    match host.split_once('%') {
+        Some((address, zone)) if is_ipv6_host(host) && zone.parse::<u32>().is_err() => {
+            match nix::net::if_::if_nametoindex(zone) {
+                Ok(index) => format!("[{}%{}]:{}", address, index, port),
+                Err(_) => format_authority(host, port),
+            }
+        },
+        _ => format_authority(host, port),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_authority_forms() {
+        let parsed = |authority| parse_authority(authority, 443);
+        assert_eq!(parsed("192.0.2.1:8443"), Some(("192.0.2.1".to_string(), 8443)));
+        assert_eq!(parsed("192.0.2.1"), Some(("192.0.2.1".to_string(), 443)));
+        assert_eq!(parsed("[2001:db8::1]:8443"), Some(("2001:db8::1".to_string(), 8443)));
+        assert_eq!(parsed("[2001:db8::1]"), Some(("2001:db8::1".to_string(), 443)));
+        assert_eq!(parsed("2001:db8::1"), Some(("2001:db8::1".to_string(), 443)));
+        assert_eq!(parsed("[fe80::1%25eth0]:443"), Some(("fe80::1%eth0".to_string(), 443)));
+        assert_eq!(parsed("Example.COM:80"), Some(("Example.COM".to_string(), 80)));
+        assert_eq!(parsed("example.com"), Some(("example.com".to_string(), 443)));
+    }
+
+    #[test]
+    fn rejects_malformed_authority() {
+        for authority in ["", ":443", "[]:443", "example.com:", "example.com:65536", "[2001:db8::1]443", "[2001:db8::1"] {
+            assert_eq!(parse_authority(authority, 443), None, "{}", authority);
+        }
+    }
+
+    #[test]
+    fn authority_round_trips() {
+        let cases = [
+            ("192.0.2.1", 443, "192.0.2.1:443"),
+            ("2001:db8::1", 8443, "[2001:db8::1]:8443"),
+            ("::1", 80, "[::1]:80"),
+            ("fe80::1%eth0", 443, "[fe80::1%eth0]:443"),
+            ("example.com", 8080, "example.com:8080"),
+            ("xn--mnchen-3ya.de", 443, "xn--mnchen-3ya.de:443"),
+        ];
+        for (host, port, formatted) in cases {
+            assert_eq!(format_authority(host, port), formatted);
+            assert_eq!(parse_authority(formatted, 1), Some((host.to_string(), port)), "{}", formatted);
+        }
+    }
+
+    #[test]
+    fn canonical_host_folds_case_trailing_dot_and_idn() {
+        for host in ["münchen.de", "München.DE.", "MÜNCHEN.de", "xn--mnchen-3ya.de", "XN--MNCHEN-3YA.DE.", " münchen.de "] {
+            assert_eq!(canonical_host(host), "xn--mnchen-3ya.de", "{}", host);
+        }
+        assert_eq!(canonical_host("WWW.Example.COM."), "www.example.com");
+        assert_eq!(canonical_host("192.0.2.1"), "192.0.2.1");
+        assert_eq!(canonical_host("2001:DB8::1"), "2001:DB8::1");
+    }
+}
@@ -0,0 +1,82 @@
+// 목적지 분류별 세션 대역폭 제한 (category_throttle 설정 시)
+// 세션 시작 시 목적지 호스트로 분류를 정하고 클라이언트 소켓에 SO_MAX_PACING_RATE를 설정합니다.
+// 커널이 클라이언트로 보내는 속도를 제한하므로 HTTP, 가로챈 TLS, 불투명 터널 모두에 같은 한도가 적용되고,
+// 송신 버퍼가 차면 업스트림 읽기도 그 속도로 늦춰집니다.
+
+use std::io;
+use std::os::fd::AsRawFd;
+
+use log::{debug, info, warn};
+use tokio::net::TcpStream;
+
+use crate::config::CategoryThrottleConfig;
+use crate::proxy::target::canonical_host;
+
+/// 호스트가 속한 분류 이름과 초당 바이트 한도 (정확한 호스트, 가까운 `*.상위도메인` 순, 없으면 기본 한도)
+pub fn rate_for<'a>(settings: &'a CategoryThrottleConfig, host: &str) -> (Option<&'a str>, u64) {
+    let host = canonical_host(host);
+    let find = |key: &str| settings.categories.iter()
+        .find(|category| category.hosts.iter().any(|pattern| canonical_pattern(pattern) == key));
+
+    let mut matched = find(&host);
+    let mut parent = host.as_str();
+    while matched.is_none() && let Some((_, rest)) = parent.split_once('.') {
+        matched = find(&format!("*.{}", rest));
+        parent = rest;
+    }
+    match matched {
+        Some(category) => (Some(category.name.as_str()), category.bytes_per_second),
+        None => (None, settings.default_bytes_per_second),
+    }
+}
+
+/// 세션의 클라이언트 소켓에 목적지 분류의 대역폭 한도 적용 (실패해도 세션은 제한 없이 계속)
+pub fn throttle_client(stream: &TcpStream, host: &str, settings: &CategoryThrottleConfig, session_id: &str) {
+    if !settings.is_enabled() {
+        return;
+    }
+    let (category, bytes_per_second) = rate_for(settings, host);
+    let category = category.unwrap_or("default");
+    if bytes_per_second == 0 {
+        debug!("[Session:{}] 대역폭 분류 {}: {} (제한 없음)", session_id, category, host);
+        return;
+    }
+
+    match set_max_pacing_rate(stream, bytes_per_second) {
+        Ok(()) => info!("[Session:{}] 대역폭 분류 {}: {} ({} bytes/s)", session_id, category, host, bytes_per_second),
+        Err(e) => warn!("[Session:{}] 대역폭 제한 적용 실패: {} 분류 {} ({} bytes/s): {}", session_id, host, category, bytes_per_second, e),
+    }
+}
+
+/// 소켓 송신 속도 상한 (한도는 설정 검증에서 u32 범위로 확인됨)
+#[cfg(target_os = "linux")]
+fn set_max_pacing_rate(stream: &TcpStream, bytes_per_second: u64) -> io::Result<()> {
+    let optval = bytes_per_second.min(u32::MAX as u64 - 1) as u32;
+    // SAFETY: 유효한 소켓 fd와 optval 크기를 그대로 전달
+    let result = unsafe {
+        libc::setsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_MAX_PACING_RATE,
+            &optval as *const _ as *const libc::c_void,
+            std::mem::size_of_val(&optval) as libc::socklen_t,
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_max_pacing_rate(_stream: &TcpStream, _bytes_per_second: u64) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "SO_MAX_PACING_RATE is linux only"))
+}
+
+/// 분류 호스트 패턴의 정규 형식 (`*.` 접두사는 그대로 두고 나머지를 정규 형식으로)
+fn canonical_pattern(pattern: &str) -> String {
+    match pattern.trim().strip_prefix("*.") {
+        Some(domain) => format!("*.{}", canonical_host(domain)),
+        None => canonical_host(pattern),
+    }
+}
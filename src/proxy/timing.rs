@@ -0,0 +1,48 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// 업스트림 구간별 소요 시간 (측정하지 않았거나 해당하지 않는 구간은 None)
+///
+/// 이름 해석, TCP 연결, TLS 핸드셰이크는 세션이 업스트림 연결을 새로 맺은 경우에만 기록되고
+/// (연결 풀이나 미리 연결 풀의 연결을 쓰면 None), 첫 바이트 시간은 응답마다 기록됩니다.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UpstreamTiming {
+    pub dns: Option<Duration>,
+    pub connect: Option<Duration>,
+    pub tls_handshake: Option<Duration>,
+    pub first_byte: Option<Duration>,
+}
+
+impl UpstreamTiming {
+    /// 측정 시작 시각 (측정 비활성화 시 None)
+    pub fn start(enabled: bool) -> Option<Instant> {
+        enabled.then(Instant::now)
+    }
+
+    /// 구간 값을 밀리초로 변환 (DB 저장용)
+    pub fn millis(phase: Option<Duration>) -> Option<i64> {
+        phase.map(|duration| duration.as_millis() as i64)
+    }
+}
+
+impl fmt::Display for UpstreamTiming {
+    /// 접근 로그 형식 (측정한 구간만): `dns=3ms connect=12ms tls=40ms ttfb=85ms`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phases = [
+            ("dns", self.dns),
+            ("connect", self.connect),
+            ("tls", self.tls_handshake),
+            ("ttfb", self.first_byte),
+        ];
+        let mut first = true;
+        for (name, phase) in phases {
+            let Some(duration) = phase else { continue };
+            if !first {
+                write!(f, " ")?;
+            }
+            write!(f, "{}={}ms", name, duration.as_millis())?;
+            first = false;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
+use std::borrow::Cow;
 use std::sync::Arc;
 use std::io;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::collections::HashMap;
 use std::sync::RwLock;
 use std::error::Error;
@@ -16,14 +17,25 @@ use memmem::{Searcher, TwoWaySearcher};
 use base64::{self, engine::general_purpose::STANDARD, Engine};
 
 use crate::metrics::Metrics;
+use crate::buffer::{read_chunk, BufferPool, SessionBuffer};
 use crate::constants;
-use crate::config::Config;
+use crate::config::{Config, InspectionOverflowAction};
+use crate::proxy::passthrough::{body_tracker, is_interim_response, is_persistent, is_protocol_switch, passthrough_tracker, response_body_tracker, BodyTracker};
+use crate::proxy::connect_pipeline::PrefixedStream;
+use crate::proxy::egress::strip_egress_header_chunk;
+use crate::proxy::pool::{upstream_pool, ReusableConnection};
+use crate::proxy::relay::{CloseKind, ResetPropagation, is_reset};
+use crate::proxy::security_headers::SecurityHeaderInjector;
+use crate::proxy::timing::UpstreamTiming;
+use crate::proxy::via::ViaInjector;
+use crate::tls::params::{TlsSessionParams, TlsSideParams};
+use crate::tls::cert_chain::record_upstream_chain;
+use crate::session::transfer::TransferRecorder;
 use crate::logging::{Logger, LogFormatter};
 use crate::error::{ProxyError, Result, tls_err, internal_err};
 
 // 패턴 상수 정의 - 전역으로 이동하여 매번 생성하지 않도록 함
 const HEADER_END_PATTERN: &[u8] = b"\r\n\r\n";
-const CHUNK_END_PATTERN: &[u8] = b"\r\n0\r\n\r\n";
 
 // HTTP 요청 메서드 집합
 const HTTP_METHODS: [&str; 6] = ["GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS"];
@@ -70,60 +82,107 @@ fn parse_http_request<'a>(buffer: &'a [u8], header_searcher: &TwoWaySearcher) ->
     (None, None, String::new(), None)
 }
 
-/// 응답의 완료 여부 확인
-fn is_response_complete(
-    resp_bytes: &[u8], 
-    headers_end_pos: usize, 
-    chunk_searcher: &TwoWaySearcher
-) -> (bool, Option<usize>, bool) {
-    // 헤더 파싱
-    let mut headers = [httparse::EMPTY_HEADER; 64];
-    let mut resp = httparse::Response::new(&mut headers);
-    
-    if let Ok(_) = resp.parse(&resp_bytes[..headers_end_pos]) {
-        // Content-Length 또는 Transfer-Encoding 찾기
-        let mut content_length = None;
-        let mut is_chunked = false;
+/// 응답의 완료 여부 확인 (HEAD 응답과 1xx/204/304는 헤더로 끝나고, 길이 정보가 없으면 연결 종료로만 끝남)
+fn is_response_complete(resp_bytes: &[u8], headers_end_pos: usize, head_request: bool) -> bool {
+    let mut tracker = response_body_tracker(head_request, &resp_bytes[..headers_end_pos]);
+    tracker.feed(&resp_bytes[headers_end_pos + 4..]).is_some()
+}
+
+/// 응답 하나의 업스트림 구간 시간 (측정 비활성화 시 None, 연결 구간은 연결의 첫 응답에만 포함)
+fn response_timing(connection: &mut Option<UpstreamTiming>, enabled: bool, first_byte: Option<Duration>) -> Option<UpstreamTiming> {
+    enabled.then(|| UpstreamTiming { first_byte, ..connection.take().unwrap_or_default() })
+}
+
+/// 세션 동안 바뀌지 않는 HTTPS 응답 로깅 문맥
+struct TlsResponseLog<'a> {
+    logger: Option<&'a Arc<Logger>>,
+    session_id_str: &'a str,
+    metrics: &'a Metrics,
+    host: &'a str,
+    cert_warning: bool,
+    tls_params: Option<&'a TlsSessionParams>,
+}
+
+impl TlsResponseLog<'_> {
+    /// 완료된 HTTPS 응답 로깅 (헤더와 본문 미리보기는 resp_bytes에서, 크기는 response_size 사용)
+    async fn log(
+        &self,
+        resp_id: u64,
+        start_time: Instant,
+        resp_bytes: &[u8],
+        headers_end_pos: usize,
+        response_size: usize,
+        timing: Option<UpstreamTiming>,
+    ) {
+        let session_id_str = self.session_id_str;
+        let response_time = start_time.elapsed().as_millis() as u64;
+        if let Some(timing) = &timing {
+            self.metrics.record_upstream_timing(self.host, timing);
+        }
         
-        for header in headers.iter() {
-            if header.name.is_empty() {
-                break;
-            }
-            
-            if header.name.eq_ignore_ascii_case("content-length") {
-                if let Ok(len_str) = std::str::from_utf8(header.value) {
-                    if let Ok(len) = len_str.trim().parse::<usize>() {
-                        content_length = Some(len);
-                    }
-                }
-            } else if header.name.eq_ignore_ascii_case("transfer-encoding") {
-                if let Ok(value) = std::str::from_utf8(header.value) {
-                    is_chunked = value.to_lowercase().contains("chunked");
-                }
-            }
+        // 상태 코드 추출
+        let mut status_code = 200;
+        if let Ok(status_line) = std::str::from_utf8(&resp_bytes[..headers_end_pos])
+            && let Some(first_line) = status_line.lines().next()
+            && let Some(code_str) = first_line.split_whitespace().nth(1)
+            && let Ok(code) = code_str.parse::<u16>() {
+            status_code = code;
         }
         
-        // 응답 완료 확인
-        if is_chunked {
-            // chunked 인코딩의 경우 마지막 청크 패턴 확인
-            let is_complete = chunk_searcher.search_in(resp_bytes).is_some();
-            return (is_complete, None, true);
-        } else if let Some(len) = content_length {
-            // Content-Length가 있는 경우, 헤더 끝 이후의 본문 길이 확인
-            let body_length = resp_bytes.len() - headers_end_pos;
-            return (body_length >= len, Some(len), false);
+        // 헤더 추출
+        let headers = if let Ok(headers_text) = std::str::from_utf8(&resp_bytes[..headers_end_pos]) {
+            headers_text.to_string()
         } else {
-            // Content-Length도 없고 chunked도 아닌 경우, 헤더 수신 후 즉시 응답 완료로 처리
-            return (true, None, false);
+            String::from_utf8_lossy(&resp_bytes[..headers_end_pos]).into_owned()
+        };
+        
+        // 응답 본문 미리보기 (최대 1KB)
+        let body_preview = if resp_bytes.len() > headers_end_pos + 4 {
+            let preview_end = std::cmp::min(headers_end_pos + 4 + 1024, resp_bytes.len());
+            let body_slice = &resp_bytes[headers_end_pos + 4..preview_end];
+        
+            if let Ok(body_text) = std::str::from_utf8(body_slice) {
+                Some(LogFormatter::summarize_body(body_text, 1024))
+            } else {
+                // 바이너리 데이터를 base64로 인코딩
+                let base64_data = STANDARD.encode(body_slice);
+                Some(LogFormatter::summarize_body(&base64_data, 1024))
+            }
+        } else {
+            None
+        };
+        
+        // 응답 로그 저장
+        if let Some(logger) = self.logger {
+            if let Err(e) = logger.log_response(
+                session_id_str.to_string(),
+                status_code,
+                response_time,
+                response_size,
+                headers,
+                body_preview,
+                timing,
+                self.cert_warning,
+                self.tls_params.cloned(),
+            ).await {
+                warn!("[Session:{}] HTTPS 응답 로깅 실패: {}", session_id_str, e);
+            } else {
+                debug!("[Session:{}] HTTPS 응답 로깅 성공: {} ms, {} bytes", 
+                    session_id_str, response_time, response_size);
+            }
         }
+        
+        info!("[Session:{}] HTTPS 응답 #{} 완료: {} ms, {} 바이트, 상태 코드: {}", 
+             session_id_str, resp_id, response_time, response_size, status_code);
     }
-    
-    (false, None, false)
 }
-
+    
 /// TLS 스트림 간에 데이터를 전달하고 검사합니다
+///
+/// 업스트림 연결 풀이 켜져 있고 클라이언트가 요청 사이에서 연결을 닫았으면, 남은 응답을 모두 전달한 뒤
+/// 업스트림 연결을 닫지 않고 돌려줍니다 (모든 요청과 응답이 연결 유지이고 응답이 정확히 끝난 경우에만).
 pub async fn proxy_tls_streams(
-    client_stream: ServerTlsStream<TcpStream>,
+    client_stream: ServerTlsStream<PrefixedStream>,
     server_stream: ClientTlsStream<TcpStream>,
     metrics: Arc<Metrics>,
     session_id: &str,
@@ -131,9 +190,13 @@ pub async fn proxy_tls_streams(
     request_start_time: Instant,
     logger: Option<Arc<Logger>>,
     config: Option<Arc<Config>>,
-) -> Result<()> {
+    buffer_pool: Option<Arc<BufferPool>>,
+    timing: Option<UpstreamTiming>,
+    cert_warning: bool,
+    transfer: Option<&TransferRecorder>,
+) -> Result<(CloseKind, Option<ReusableConnection>)> {
     // 클라이언트 IP 주소 가져오기 (스트림 분할 전에)
-    let client_ip = client_stream.get_ref().0.peer_addr()
+    let client_ip = client_stream.get_ref().0.tcp().peer_addr()
         .map(|addr| addr.ip().to_string())
         .unwrap_or_else(|_| "Unknown IP".to_string());
     
@@ -146,6 +209,35 @@ pub async fn proxy_tls_streams(
     info!("[Session:{}] Connected to IP: {} for host: {}, client IP: {}", 
          session_id, server_ip, host, client_ip);
     
+    // 양쪽 핸드셰이크에서 협상된 TLS 값 기록 (tls_params_logging인 경우)
+    let tls_params = config.as_ref().filter(|config| config.tls_params_logging).map(|config| TlsSessionParams {
+        client: TlsSideParams::from_connection(client_stream.get_ref().1),
+        upstream: TlsSideParams::from_connection(server_stream.get_ref().1),
+        upstream_sni: TlsSessionParams::sent_sni(config.sni_for(host)),
+    });
+    if let Some(params) = &tls_params {
+        info!("[Session:{}] [TLS-PARAMS] {}", session_id, params);
+    }
+    
+    // 업스트림이 제시한 인증서 체인 기록 (upstream_cert_logging인 경우)
+    if let Some(config) = config.as_ref().filter(|config| config.upstream_cert_logging.enabled)
+        && let Some(chain) = server_stream.get_ref().1.peer_certificates() {
+        record_upstream_chain(session_id, host, chain, &config.upstream_cert_logging);
+    }
+    
+    // 한쪽이 RST로 끊으면 다른 쪽에도 RST로 전달 (분할 전에 소켓 복제)
+    let resets = match ResetPropagation::new(client_stream.get_ref().0.tcp(), server_stream.get_ref().0) {
+        Ok(resets) => Some(Arc::new(resets)),
+        Err(e) => {
+            debug!("[Session:{}] RST 전달용 소켓 복제 실패: {}", session_id, e);
+            None
+        }
+    };
+    
+    if let Some(transfer) = transfer {
+        transfer.relay_started();
+    }
+    
     // 양방향 데이터 전송 및 검사 로직 구현
     let (mut client_read, mut client_write) = tokio::io::split(client_stream);
     
@@ -161,6 +253,10 @@ pub async fn proxy_tls_streams(
     let parsing_request = Arc::new(RwLock::new(false));
     let current_request_id = Arc::new(RwLock::new(0_u64));
     
+    // 업스트림 연결을 풀에 돌려줄 수 있는지 (클라이언트가 요청 사이에서 닫으면 true를 보냄)
+    let pooling = upstream_pool().is_some();
+    let (client_done_tx, mut client_done) = tokio::sync::watch::channel(false);
+    
     // 클라이언트 -> 서버 방향 프록시
     let client_to_server = {
         let request_times = Arc::clone(&request_times);
@@ -173,6 +269,8 @@ pub async fn proxy_tls_streams(
         let session_id_str = session_id.to_string();
         let host_str = host.to_string();
         let config_clone = config.clone(); // config 클론
+        let buffer_pool_clone = buffer_pool.clone();
+        let resets = resets.clone();
         
         async move {
             let mut total_bytes = 0u64;
@@ -182,22 +280,39 @@ pub async fn proxy_tls_streams(
                 .unwrap_or(constants::BUFFER_SIZE_MEDIUM);
                 
             let mut req_buffer = BytesMut::with_capacity(buffer_size);
+            // 검사용 버퍼링 상한을 넘어 끝 위치만 추적 중인 요청 본문
+            let mut request_passthrough: Option<BodyTracker> = None;
             
             // 패턴 검색기 초기화 - 각 클로저에서 별도로 생성
             let header_searcher = TwoWaySearcher::new(HEADER_END_PATTERN);
             
-            // 버퍼 재사용을 위한 초기화
-            let mut buffer = BytesMut::with_capacity(constants::BUFFER_SIZE_MEDIUM);
+            // 버퍼 재사용을 위한 초기화 - 소형으로 시작하여 처리량에 따라 승격
+            let mut session_buffer = SessionBuffer::new(buffer_pool_clone);
+            
+            // Via/X-Proxy 헤더 삽입기 (proxy_headers가 활성화된 경우)
+            let mut via = config_clone.as_ref().and_then(|c| ViaInjector::new(c));
+            // 흐름 제어를 사용하면 한 번에 high_watermark까지만 읽어 방향별 미전송 데이터를 제한
+            let read_chunk_size = config_clone.as_ref().and_then(|c| c.flow_control.read_limit(c.read_chunk_size));
+            // 출구 선택 헤더를 지울 요청 헤더 블록이 진행 중인지 (keep-alive 요청마다)
+            let mut egress_head = false;
+            // 모든 요청이 연결 유지를 요청했는지, 클라이언트가 정상적으로 닫았는지
+            let mut requests_persistent = true;
+            let mut client_closed = false;
             
             loop {
+                let buffer = session_buffer.buffer_mut();
                 buffer.clear(); // 버퍼 재사용 
                 
-                match client_read.read_buf(&mut buffer).await {
-                    Ok(0) => break, // 연결 종료
+                match client_read.read_buf(&mut read_chunk(buffer, read_chunk_size)).await {
+                    Ok(0) => {
+                        // 연결 종료
+                        client_closed = true;
+                        break;
+                    },
                     Ok(n) => {
                         // 요청 버퍼가 비어있고 새로운 요청이 시작되는 경우
                         if !*parsing_request.read().unwrap() && !buffer.is_empty() {
-                            let (method, path, header_str, body) = parse_http_request(&buffer, &header_searcher);
+                            let (method, path, header_str, body) = parse_http_request(buffer, &header_searcher);
                             
                             if let Some(method_str) = method {
                                 if HTTP_METHODS.contains(&method_str) {
@@ -208,15 +323,20 @@ pub async fn proxy_tls_streams(
                                         *req_id
                                     };
                                     
-                                    // 요청 시작 시간 기록
+                                    // 요청 시작 시간과 HEAD 여부 기록 (HEAD 응답은 본문 없이 끝남)
                                     {
-                                        request_times.write().unwrap().insert(request_id, Instant::now());
+                                        request_times.write().unwrap().insert(request_id, (Instant::now(), method_str == "HEAD"));
                                     }
                                     
                                     {
                                         *parsing_request.write().unwrap() = true;
                                     }
                                     
+                                    if let Some(via) = via.as_mut() {
+                                        via.start_message();
+                                    }
+                                    egress_head = true;
+                                    
                                     debug!("[Session:{}] 새 HTTPS 요청 #{} 감지: {} {}", 
                                           session_id_str, request_id, method_str, path.unwrap_or(""));
 
@@ -242,12 +362,20 @@ pub async fn proxy_tls_streams(
                                     
                                     // 요청 버퍼 초기화
                                     req_buffer.clear();
-                                    req_buffer.put_slice(&buffer);
+                                    req_buffer.put_slice(buffer);
+                                    requests_persistent &= header_searcher.search_in(buffer)
+                                        .is_some_and(|header_end_pos| is_persistent(&buffer[..header_end_pos]));
                                 }
                             }
+                        } else if let Some(tracker) = request_passthrough.as_mut() {
+                            // 상한을 넘은 요청 본문: 버퍼에 모으지 않고 끝나면 다음 요청 감지로 돌아감
+                            if tracker.feed(buffer).is_some() {
+                                request_passthrough = None;
+                                *parsing_request.write().unwrap() = false;
+                            }
                         } else if *parsing_request.read().unwrap() {
                             // 기존 요청에 데이터 추가
-                            req_buffer.put_slice(&buffer);
+                            req_buffer.put_slice(buffer);
                             
                             // 요청 완료 여부 확인 (헤더 끝 확인)
                             if let Some(header_end_pos) = header_searcher.search_in(&req_buffer) {
@@ -284,20 +412,65 @@ pub async fn proxy_tls_streams(
                                     }
                                 }
                             }
+                            
+                            // 검사용 버퍼링 전체 상한을 넘은 요청은 더 모으지 않고 나머지 본문을 그대로 전달
+                            if *parsing_request.read().unwrap()
+                                && let Some(config) = &config_clone
+                                && config.inspection_cap_exceeded(req_buffer.len()) {
+                                metrics_clone.inspection_cap_exceeded();
+                                warn!("[Session:{}] HTTPS 요청이 검사용 버퍼링 상한 {}바이트를 넘어 패스스루로 전환: {}",
+                                      session_id_str, config.max_inspection_bytes, host_str);
+                                let tracker = header_searcher.search_in(&req_buffer).map(|header_end_pos| {
+                                    let mut tracker = body_tracker(&req_buffer[..header_end_pos]);
+                                    tracker.feed(&req_buffer[header_end_pos + 4..]);
+                                    tracker
+                                });
+                                match tracker {
+                                    Some(tracker) if !tracker.until_close() => request_passthrough = Some(tracker),
+                                    // 본문 경계를 알 수 없으면 요청 추적을 그만둠
+                                    _ => *parsing_request.write().unwrap() = false,
+                                }
+                                req_buffer.clear();
+                            }
                         }
                         
-                        // 서버로 데이터 전송
-                        if let Err(e) = server_write.write_all(&buffer).await {
+                        // 서버로 데이터 전송 (요청 헤더 블록의 출구 선택 헤더는 업스트림으로 전달하지 않음)
+                        let request = match &config_clone {
+                            Some(config) if egress_head => {
+                                let (stripped, head_ended) = strip_egress_header_chunk(config, buffer);
+                                egress_head = !head_ended;
+                                stripped
+                            },
+                            _ => Cow::Borrowed(&buffer[..]),
+                        };
+                        let data = match via.as_mut() {
+                            Some(via) => via.apply(&request),
+                            None => Cow::Borrowed(&request[..]),
+                        };
+                        if let Err(e) = server_write.write_all(&data).await {
+                            if let Some(resets) = resets.as_ref().filter(|_| is_reset(&e)) {
+                                debug!("[Session:{}] 서버가 RST로 종료", session_id_str);
+                                resets.upstream_reset();
+                                break;
+                            }
                             error!("[Session:{}] 서버 쓰기 오류: {}", session_id_str, e);
                             return Err(e.into());
                         }
                         
                         total_bytes += n as u64;
                         metrics_clone.add_tls_bytes_in(n as u64);
+                        if let Some(transfer) = transfer {
+                            transfer.add_up(n as u64);
+                        }
+                        session_buffer.record_read(n);
                     },
                     Err(e) => {
                         if e.kind() == io::ErrorKind::UnexpectedEof {
                             debug!("[Session:{}] 클라이언트 연결 종료", session_id_str);
+                            client_closed = true;
+                        } else if let Some(resets) = resets.as_ref().filter(|_| is_reset(&e)) {
+                            debug!("[Session:{}] 클라이언트가 RST로 종료", session_id_str);
+                            resets.client_reset();
                         } else {
                             error!("[Session:{}] 클라이언트 읽기 오류: {}", session_id_str, e);
                         }
@@ -308,10 +481,25 @@ pub async fn proxy_tls_streams(
             
             debug!("[Session:{}] 클라이언트→서버 전송 완료: {} 바이트", session_id_str, total_bytes);
             
-            // 서버 쓰기 스트림 종료
-            let _ = server_write.shutdown().await;
+            // 요청 사이에서 닫혔으면 업스트림 연결을 닫지 않고 남은 응답이 끝나기를 기다려 풀에 돌려줌
+            let reset = resets.as_ref().is_some_and(|resets| resets.is_reset());
+            // 마지막 요청이 본문까지 모두 전달됐는지 (요청 감지 상태는 다음 조각이 와야 풀리므로 버퍼로 확인)
+            let request_complete = !*parsing_request.read().unwrap()
+                || header_searcher.search_in(&req_buffer).is_some_and(|header_end_pos| {
+                    let mut tracker = body_tracker(&req_buffer[..header_end_pos]);
+                    tracker.until_close() || tracker.feed(&req_buffer[header_end_pos + 4..]).is_some()
+                });
+            if pooling && client_closed && !reset && requests_persistent && request_complete && request_passthrough.is_none() {
+                let _ = client_done_tx.send(true);
+                return Ok(Some(server_write));
+            }
+            
+            // 서버 쓰기 스트림 종료 (RST를 전달하는 경우 FIN을 보내지 않음)
+            if !reset {
+                let _ = server_write.shutdown().await;
+            }
             
-            Ok(())
+            Ok(None)
         }
     };
     
@@ -322,8 +510,20 @@ pub async fn proxy_tls_streams(
         let session_id_str = session_id.to_string();
         let logger_clone = logger.clone();
         let config_clone = config.clone(); // config 클론
+        let buffer_pool_clone = buffer_pool.clone();
+        let host_str = host.to_string();
+        let tls_params = tls_params.clone();
+        let resets = resets.clone();
         
         async move {
+            let response_log = TlsResponseLog {
+                logger: logger_clone.as_ref(),
+                session_id_str: &session_id_str,
+                metrics: &metrics_clone,
+                host: &host_str,
+                cert_warning,
+                tls_params: tls_params.as_ref(),
+            };
             let mut total_bytes = 0u64;
             // config에서 buffer_size 가져오기
             let buffer_size = config_clone.as_ref()
@@ -333,120 +533,230 @@ pub async fn proxy_tls_streams(
             let mut resp_buffer = BytesMut::with_capacity(buffer_size);
             let mut current_resp_id = 0u64;
             
+            // 업스트림 구간 시간 (연결 구간은 첫 응답 기록 시 꺼내 감, 첫 바이트 시간은 응답마다)
+            let timing_enabled = timing.is_some();
+            let mut connection_timing = timing;
+            let mut first_byte = None;
+            
+            // 패스스루 중인 응답 (본문 추적기, 헤더 끝 위치, 지금까지 전달한 응답 크기)
+            let mut passthrough: Option<(BodyTracker, usize, usize)> = None;
+            
             // 패턴 검색기 초기화
             let header_searcher = TwoWaySearcher::new(HEADER_END_PATTERN);
-            let chunk_searcher = TwoWaySearcher::new(CHUNK_END_PATTERN);
             
-            // 버퍼 재사용을 위한 초기화
-            let mut buffer = BytesMut::with_capacity(constants::BUFFER_SIZE_MEDIUM);
+            // 버퍼 재사용을 위한 초기화 - 소형으로 시작하여 처리량에 따라 승격
+            let mut session_buffer = SessionBuffer::new(buffer_pool_clone);
+            
+            // Via/X-Proxy 헤더 삽입기 (proxy_headers가 활성화된 경우)
+            let mut via = config_clone.as_ref().and_then(|c| ViaInjector::new(c));
+            // 보안 헤더 삽입기 (security_headers가 활성화된 경우, 응답 헤더 블록이 끝날 때까지 전달을 미룸)
+            let mut security = config_clone.as_ref().and_then(|c| SecurityHeaderInjector::new(c));
+            // 흐름 제어를 사용하면 한 번에 high_watermark까지만 읽어 방향별 미전송 데이터를 제한
+            let read_chunk_size = config_clone.as_ref().and_then(|c| c.flow_control.read_limit(c.read_chunk_size));
+            // 모든 응답이 연결 유지이고 본문 길이로 정확히 끝났는지, 풀에 돌려주려고 읽기를 멈췄는지
+            let mut responses_persistent = true;
+            let mut released = false;
             
             loop {
+                let buffer = session_buffer.buffer_mut();
                 buffer.clear(); // 버퍼 재사용
                 
-                match server_read.read_buf(&mut buffer).await {
+                // 클라이언트가 요청 사이에서 닫았고 진행 중인 응답이 없으면 읽기를 멈춤
+                let idle = pooling && responses_persistent && resp_buffer.is_empty() && passthrough.is_none()
+                    && request_times.read().unwrap().is_empty();
+                let read = {
+                    let mut chunk = read_chunk(buffer, read_chunk_size);
+                    tokio::select! {
+                        read = server_read.read_buf(&mut chunk) => read,
+                        _ = client_done.wait_for(|done| *done), if idle => {
+                            released = true;
+                            break;
+                        },
+                    }
+                };
+                
+                match read {
                     Ok(0) => break, // 연결 종료
                     Ok(n) => {
-                        // 응답 버퍼가 비어있고 새로운 응답이 시작되는 경우
-                        if resp_buffer.is_empty() && !buffer.is_empty() {
-                            // 새 응답 처리 시작
-                            current_resp_id = *current_request_id.read().unwrap();
-                            resp_buffer.put_slice(&buffer);
-                            
-                            debug!("[Session:{}] 새 HTTPS 응답 #{} 시작", session_id_str, current_resp_id);
+                        if let Some((mut tracker, headers_end_pos, size)) = passthrough.take() {
+                            // 패스스루 중인 응답: 본문 끝 위치만 추적하고 버퍼에 모으지 않음
+                            match tracker.feed(buffer) {
+                                None => passthrough = Some((tracker, headers_end_pos, size + n)),
+                                Some(used) => {
+                                    let start_time_opt = {
+                                        request_times.write().unwrap().remove(&current_resp_id).map(|(start_time, _)| start_time)
+                                    };
+                                    
+                                    if let Some(start_time) = start_time_opt {
+                                        let timing = response_timing(&mut connection_timing, timing_enabled, first_byte);
+                                        response_log.log(current_resp_id, start_time, &resp_buffer, headers_end_pos, size + used, timing).await;
+                                    }
+                                    
+                                    // 같은 조각에 이어진 다음 응답의 시작 부분은 새 응답으로 처리
+                                    resp_buffer.clear();
+                                    if used < buffer.len() {
+                                        current_resp_id = *current_request_id.read().unwrap();
+                                        resp_buffer.put_slice(&buffer[used..]);
+                                        if timing_enabled {
+                                            first_byte = request_times.read().unwrap().get(&current_resp_id).map(|(start_time, _)| start_time.elapsed());
+                                        }
+                                    }
+                                }
+                            }
                         } else {
-                            // 기존 응답에 데이터 추가
-                            resp_buffer.put_slice(&buffer);
-                        }
-                        
-                        // 응답 헤더 끝 위치 확인
-                        if let Some(headers_end_pos) = header_searcher.search_in(&resp_buffer) {
-                            // 응답 완료 여부 확인
-                            let (is_complete, _content_length, _is_chunked) = 
-                                is_response_complete(&resp_buffer, headers_end_pos, &chunk_searcher);
+                            // 응답 버퍼가 비어있고 새로운 응답이 시작되는 경우
+                            if resp_buffer.is_empty() && !buffer.is_empty() {
+                                // 새 응답 처리 시작
+                                current_resp_id = *current_request_id.read().unwrap();
+                                resp_buffer.put_slice(buffer);
+                                if timing_enabled {
+                                    first_byte = request_times.read().unwrap().get(&current_resp_id).map(|(start_time, _)| start_time.elapsed());
+                                }
+                            
+                                debug!("[Session:{}] 새 HTTPS 응답 #{} 시작", session_id_str, current_resp_id);
+                                
+                                if let Some(via) = via.as_mut() {
+                                    via.start_message();
+                                }
+                                if let Some(security) = security.as_mut() {
+                                    security.start_message();
+                                }
+                            } else {
+                                // 기존 응답에 데이터 추가
+                                resp_buffer.put_slice(buffer);
+                            }
+                            
+                            // 1xx 중간 응답은 기록하지 않고 이어지는 최종 응답 헤더부터 다시 확인
+                            while let Some(headers_end_pos) = header_searcher.search_in(&resp_buffer)
+                                && is_interim_response(&resp_buffer[..headers_end_pos]) {
+                                debug!("[Session:{}] HTTPS 응답 #{} 1xx 중간 응답 전달", session_id_str, current_resp_id);
+                                let _ = resp_buffer.split_to(headers_end_pos + 4);
+                            }
+                            
+                            // 응답 헤더 끝 위치 확인
+                            if let Some(headers_end_pos) = header_searcher.search_in(&resp_buffer) {
+                                let head = &resp_buffer[..headers_end_pos];
+                                responses_persistent &= is_persistent(head) && !is_protocol_switch(head)
+                                    && !response_body_tracker(false, head).until_close();
+                                
+                                // 응답 완료 여부 확인
+                                let head_request = request_times.read().unwrap().get(&current_resp_id).is_some_and(|(_, head)| *head);
+                                let is_complete = is_response_complete(&resp_buffer, headers_end_pos, head_request);
                             
-                            // 응답 완료 시 처리
-                            if is_complete {
-                                // 응답 시간 계산
-                                let start_time_opt = {
-                                    request_times.write().unwrap().remove(&current_resp_id)
+                                // 패스스루 대상 Content-Type이고 본문이 남아 있으면 이후 본문은 버퍼에 모으지 않음
+                                let tracker = match &config_clone {
+                                    Some(config) if !is_complete => passthrough_tracker(config, &resp_buffer[..headers_end_pos]),
+                                    _ => None,
                                 };
+                            
+                                if let Some(mut tracker) = tracker
+                                    && tracker.feed(&resp_buffer[headers_end_pos + 4..]).is_none() {
+                                    metrics_clone.passthrough_response();
+                                    debug!("[Session:{}] HTTPS 응답 #{} 본문 패스스루 시작", session_id_str, current_resp_id);
                                 
-                                if let Some(start_time) = start_time_opt {
-                                    let response_time = start_time.elapsed().as_millis() as u64;
-                                    
-                                    // 상태 코드 추출
-                                    let mut status_code = 200;
-                                    if let Ok(status_line) = std::str::from_utf8(&resp_buffer[..headers_end_pos]) {
-                                        if let Some(first_line) = status_line.lines().next() {
-                                            if let Some(code_str) = first_line.split_whitespace().nth(1) {
-                                                if let Ok(code) = code_str.parse::<u16>() {
-                                                    status_code = code;
-                                                }
-                                            }
-                                        }
-                                    }
-                                    
-                                    // 헤더 추출
-                                    let headers = if let Ok(headers_text) = std::str::from_utf8(&resp_buffer[..headers_end_pos]) {
-                                        headers_text.to_string()
-                                    } else {
-                                        String::from_utf8_lossy(&resp_buffer[..headers_end_pos]).into_owned()
+                                    // 로깅용으로 헤더와 본문 앞부분만 남김
+                                    passthrough = Some((tracker, headers_end_pos, resp_buffer.len()));
+                                    resp_buffer.truncate(std::cmp::min(headers_end_pos + 4 + 1024, resp_buffer.len()));
+                                } else if is_complete {
+                                    // 응답 완료 시 처리
+                                    let start_time_opt = {
+                                        request_times.write().unwrap().remove(&current_resp_id).map(|(start_time, _)| start_time)
                                     };
-                                    
-                                    // 응답 본문 미리보기 (최대 1KB)
-                                    let body_preview = if resp_buffer.len() > headers_end_pos + 4 {
-                                        let preview_end = std::cmp::min(headers_end_pos + 4 + 1024, resp_buffer.len());
-                                        let body_slice = &resp_buffer[headers_end_pos + 4..preview_end];
-                                        
-                                        if let Ok(body_text) = std::str::from_utf8(body_slice) {
-                                            Some(LogFormatter::summarize_body(body_text, 1024))
-                                        } else {
-                                            // 바이너리 데이터를 base64로 인코딩
-                                            let base64_data = STANDARD.encode(body_slice);
-                                            Some(LogFormatter::summarize_body(&base64_data, 1024))
+                                
+                                    if let Some(start_time) = start_time_opt {
+                                        let timing = response_timing(&mut connection_timing, timing_enabled, first_byte);
+                                        response_log.log(current_resp_id, start_time, &resp_buffer, headers_end_pos, resp_buffer.len(), timing).await;
+                                    }
+                                
+                                    // 응답 버퍼 초기화
+                                    resp_buffer.clear();
+                                }
+                            }
+                            
+                            // 아직 끝나지 않은 응답이 검사 크기 한도를 넘으면 검사를 멈추고 패스스루로 전환하거나 중단
+                            if passthrough.is_none()
+                                && let Some(config) = &config_clone
+                                && config.inspection_limit.exceeded(resp_buffer.len()) {
+                                metrics_clone.inspection_limit_exceeded();
+                                let limit = config.inspection_limit.max_response_bytes.unwrap_or_default();
+                                let on_exceed = config.inspection_limit.on_exceed;
+                                warn!("[Session:{}] HTTPS 응답 #{}이 검사 크기 한도 {}바이트를 넘음 (on_exceed {}): {}",
+                                      session_id_str, current_resp_id, limit, on_exceed.name(), host_str);
+                                match (on_exceed, header_searcher.search_in(&resp_buffer)) {
+                                    (InspectionOverflowAction::Passthrough, Some(headers_end_pos)) => {
+                                        let mut tracker = body_tracker(&resp_buffer[..headers_end_pos]);
+                                        tracker.feed(&resp_buffer[headers_end_pos + 4..]);
+                                        passthrough = Some((tracker, headers_end_pos, resp_buffer.len()));
+                                        resp_buffer.truncate(std::cmp::min(headers_end_pos + 4 + 1024, resp_buffer.len()));
+                                    },
+                                    // 헤더 끝을 찾지 못했으면 본문 경계를 알 수 없으므로 중단
+                                    _ => {
+                                        // 클라이언트→서버 방향도 기다리지 않고 양쪽 연결을 끊음
+                                        if let Some(resets) = resets.as_ref() {
+                                            resets.abort();
                                         }
-                                    } else {
-                                        None
-                                    };
-                                    
-                                    // 응답 로그 저장
-                                    if let Some(logger) = &logger_clone {
-                                        if let Err(e) = logger.log_response(
-                                            session_id_str.clone(),
-                                            status_code,
-                                            response_time,
-                                            resp_buffer.len(),
-                                            headers,
-                                            body_preview
-                                        ).await {
-                                            warn!("[Session:{}] HTTPS 응답 로깅 실패: {}", session_id_str, e);
-                                        } else {
-                                            debug!("[Session:{}] HTTPS 응답 로깅 성공: {} ms, {} bytes", 
-                                                session_id_str, response_time, resp_buffer.len());
+                                        return Err(ProxyError::Http(format!("inspected response exceeded {} bytes", limit)));
+                                    },
+                                }
+                            }
+                            
+                            // 검사용 버퍼링 전체 상한을 넘으면 on_exceed와 관계없이 나머지 본문은 검사 없이 전달
+                            if passthrough.is_none()
+                                && let Some(config) = &config_clone
+                                && config.inspection_cap_exceeded(resp_buffer.len()) {
+                                metrics_clone.inspection_cap_exceeded();
+                                warn!("[Session:{}] HTTPS 응답 #{}이 검사용 버퍼링 상한 {}바이트를 넘어 패스스루로 전환: {}",
+                                      session_id_str, current_resp_id, config.max_inspection_bytes, host_str);
+                                match header_searcher.search_in(&resp_buffer) {
+                                    Some(headers_end_pos) => {
+                                        let mut tracker = body_tracker(&resp_buffer[..headers_end_pos]);
+                                        tracker.feed(&resp_buffer[headers_end_pos + 4..]);
+                                        passthrough = Some((tracker, headers_end_pos, resp_buffer.len()));
+                                        resp_buffer.truncate(std::cmp::min(headers_end_pos + 4 + 1024, resp_buffer.len()));
+                                    },
+                                    // 헤더만으로 상한을 넘었으면 본문 경계를 알 수 없으므로 양쪽 연결을 끊음
+                                    None => {
+                                        if let Some(resets) = resets.as_ref() {
+                                            resets.abort();
                                         }
-                                    }
-                                    
-                                    info!("[Session:{}] HTTPS 응답 #{} 완료: {} ms, {} 바이트, 상태 코드: {}", 
-                                         session_id_str, current_resp_id, response_time, resp_buffer.len(), status_code);
+                                        return Err(ProxyError::Http(format!("response head exceeded {} bytes", config.max_inspection_bytes)));
+                                    },
                                 }
-                                
-                                // 응답 버퍼 초기화
-                                resp_buffer.clear();
                             }
                         }
-                        
+                            
                         // 클라이언트에 데이터 전송
-                        if let Err(e) = client_write.write_all(&buffer).await {
+                        let data = match via.as_mut() {
+                            Some(via) => via.apply(buffer),
+                            None => Cow::Borrowed(&buffer[..]),
+                        };
+                        let data = match security.as_mut() {
+                            Some(security) => security.apply(data),
+                            None => data,
+                        };
+                        if let Err(e) = client_write.write_all(&data).await {
+                            if let Some(resets) = resets.as_ref().filter(|_| is_reset(&e)) {
+                                debug!("[Session:{}] 클라이언트가 RST로 종료", session_id_str);
+                                resets.client_reset();
+                                break;
+                            }
                             error!("[Session:{}] 클라이언트 쓰기 오류: {}", session_id_str, e);
                             return Err(e.into());
                         }
                         
                         total_bytes += n as u64;
                         metrics_clone.add_tls_bytes_out(n as u64);
+                        if let Some(transfer) = transfer {
+                            transfer.add_down(n as u64);
+                        }
+                        session_buffer.record_read(n);
                     },
                     Err(e) => {
                         if e.kind() == io::ErrorKind::UnexpectedEof {
                             debug!("[Session:{}] 서버 연결 종료", session_id_str);
+                        } else if let Some(resets) = resets.as_ref().filter(|_| is_reset(&e)) {
+                            debug!("[Session:{}] 서버가 RST로 종료", session_id_str);
+                            resets.upstream_reset();
                         } else {
                             error!("[Session:{}] 서버 읽기 오류: {}", session_id_str, e);
                         }
@@ -455,12 +765,34 @@ pub async fn proxy_tls_streams(
                 }
             }
             
+            // 길이 정보 없이 연결 종료로 끝나는 응답(HTTP/1.0 등)은 서버가 연결을 닫은 시점에 기록
+            let pending = match passthrough.take() {
+                Some((_, headers_end_pos, size)) => Some((headers_end_pos, size)),
+                None => header_searcher.search_in(&resp_buffer).map(|headers_end_pos| (headers_end_pos, resp_buffer.len())),
+            };
+            let start_time = pending.and_then(|_| request_times.write().unwrap().remove(&current_resp_id).map(|(start_time, _)| start_time));
+            if let (Some((headers_end_pos, size)), Some(start_time)) = (pending, start_time) {
+                debug!("[Session:{}] HTTPS 응답 #{} 연결 종료로 완료 ({}바이트)", session_id_str, current_resp_id, size);
+                let timing = response_timing(&mut connection_timing, timing_enabled, first_byte);
+                response_log.log(current_resp_id, start_time, &resp_buffer, headers_end_pos, size, timing).await;
+            }
+            
+            // 헤더 블록이 끝나기 전에 연결이 끝났으면 미뤄 둔 응답 앞부분을 그대로 전달
+            if let Some(security) = security.as_mut() {
+                let pending = security.take_pending();
+                if !pending.is_empty() {
+                    let _ = client_write.write_all(&pending).await;
+                }
+            }
+            
             debug!("[Session:{}] 서버→클라이언트 전송 완료: {} 바이트", session_id_str, total_bytes);
             
-            // 클라이언트 쓰기 스트림 종료
-            let _ = client_write.shutdown().await;
+            // 클라이언트 쓰기 스트림 종료 (RST를 전달하는 경우 FIN을 보내지 않음)
+            if !resets.as_ref().is_some_and(|resets| resets.is_reset()) {
+                let _ = client_write.shutdown().await;
+            }
             
-            Ok(())
+            Ok(released.then_some(server_read))
         }
     };
     
@@ -469,15 +801,23 @@ pub async fn proxy_tls_streams(
     
     // 전체 응답 시간 계산 및 로깅
     let total_response_time = request_start_time.elapsed().as_millis() as u64;
-    info!("[Session:{}] TLS 프록시 완료: {} ms", session_id, total_response_time);
+    let close = resets.as_ref().map(|resets| resets.close_kind()).unwrap_or_default();
+    info!("[Session:{}] TLS 프록시 완료: {} ms, 종료: {}", session_id, total_response_time, close.name());
     
     // 에러 처리 - 둘 중 하나라도 에러가 있으면 반환
-    if let Err(e) = client_result {
-        return Err(e);
-    }
-    if let Err(e) = server_result {
-        return Err(e);
-    }
+    let server_write = match client_result {
+        Ok(server_write) => server_write,
+        Err(e) => return Err(e),
+    };
+    let server_read = match server_result {
+        Ok(server_read) => server_read,
+        Err(e) => return Err(e),
+    };
     
-    Ok(())
+    // 양쪽 모두 연결을 남겨 두었으면 다시 합쳐 풀에 돌려줄 연결로 반환
+    let reusable = match (server_read, server_write) {
+        (Some(server_read), Some(server_write)) => Some(ReusableConnection::after_tls_exchange(server_read.unsplit(server_write))),
+        _ => None,
+    };
+    Ok((close, reusable))
 } 
\ No newline at end of file
@@ -0,0 +1,87 @@
+use std::borrow::Cow;
+
+use crate::config::Config;
+
+/// 시작 줄을 찾지 못하면 삽입을 포기하는 길이
+const MAX_START_LINE_LEN: usize = 8192;
+
+/// 가로챈 HTTP/1.x 메시지에 Via(RFC 7230 5.7.1)와 X-Proxy 헤더를 추가하는 삽입기
+///
+/// 헤더는 메시지 시작 줄 바로 뒤에 넣으므로 헤더 블록 전체를 모으지 않고 전달하는 조각 단위로 동작합니다.
+pub struct ViaInjector {
+    pseudonym: String,
+    x_proxy: Option<String>,
+    /// 시작 줄 끝을 기다리는 중인지 여부
+    pending: bool,
+    /// 지금까지 읽은 시작 줄
+    start_line: Vec<u8>,
+}
+
+impl ViaInjector {
+    /// proxy_headers가 활성화된 경우에만 삽입기 생성
+    pub fn new(config: &Config) -> Option<Self> {
+        let proxy_headers = &config.proxy_headers;
+        if !proxy_headers.enabled {
+            return None;
+        }
+
+        Some(Self {
+            pseudonym: proxy_headers.via_pseudonym.clone(),
+            x_proxy: proxy_headers.x_proxy.clone(),
+            pending: false,
+            start_line: Vec::new(),
+        })
+    }
+
+    /// 다음에 전달할 조각부터 새 메시지가 시작됨을 표시
+    pub fn start_message(&mut self) {
+        self.pending = true;
+        self.start_line.clear();
+    }
+
+    /// 전달할 조각에 헤더 삽입 (시작 줄이 이 조각에서 끝나지 않으면 그대로 반환)
+    pub fn apply<'a>(&mut self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        if !self.pending {
+            return Cow::Borrowed(data);
+        }
+
+        let Some(line_end) = data.iter().position(|&b| b == b'\n') else {
+            self.start_line.extend_from_slice(data);
+            if self.start_line.len() > MAX_START_LINE_LEN {
+                self.pending = false;
+            }
+            return Cow::Borrowed(data);
+        };
+
+        self.pending = false;
+        self.start_line.extend_from_slice(&data[..line_end]);
+        let Some(version) = http1_version(&self.start_line) else {
+            return Cow::Borrowed(data);
+        };
+
+        let mut headers = format!("Via: {} {}\r\n", version, self.pseudonym);
+        if let Some(x_proxy) = &self.x_proxy {
+            headers.push_str(&format!("X-Proxy: {}\r\n", x_proxy));
+        }
+
+        let mut injected = Vec::with_capacity(data.len() + headers.len());
+        injected.extend_from_slice(&data[..=line_end]);
+        injected.extend_from_slice(headers.as_bytes());
+        injected.extend_from_slice(&data[line_end + 1..]);
+        Cow::Owned(injected)
+    }
+}
+
+/// 요청(`GET / HTTP/1.1`) 또는 응답(`HTTP/1.1 200 OK`) 시작 줄에서 Via용 프로토콜 버전(`1.1`) 추출
+///
+/// HTTP/1.x가 아니면 None (바이너리 프로토콜에는 삽입하지 않음)
+fn http1_version(start_line: &[u8]) -> Option<&str> {
+    let line = std::str::from_utf8(start_line).ok()?.trim_end_matches('\r');
+    let token = if line.starts_with("HTTP/") {
+        line.split(' ').next()?
+    } else {
+        line.rsplit(' ').next()?
+    };
+
+    token.strip_prefix("HTTP/").filter(|version| version.starts_with("1."))
+}
@@ -0,0 +1,197 @@
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use log::{debug, info, warn};
+use once_cell::sync::OnceCell;
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::net::TcpStream;
+use tokio::sync::Notify;
+use tokio::task::JoinSet;
+use tokio_rustls::client::TlsStream as ClientTlsStream;
+
+use crate::config::Config;
+use crate::metrics::Metrics;
+use crate::proxy::target::{format_authority, parse_authority};
+use crate::tls::connect_tls;
+
+/// 미리 연결 풀 키 (소문자 호스트, 포트)
+type WarmKey = (String, u16);
+
+// 전역 미리 연결 풀 (warm_pool.upstreams가 지정된 경우에만 초기화)
+static WARM_POOL: OnceCell<Arc<WarmPool>> = OnceCell::new();
+
+/// 핸드셰이크까지 마친 채 대기 중인 업스트림 TLS 연결
+struct WarmConnection {
+    stream: ClientTlsStream<TcpStream>,
+    established: Instant,
+}
+
+/// 고정 업스트림별로 TLS 연결을 미리 맺어 두는 풀
+///
+/// 업스트림 연결 풀이 응답이 끝난 평문 연결을 돌려받아 재사용하는 것과 달리,
+/// 요청이 오기 전에 연결을 만들어 두고 꺼내 간 연결은 돌려받지 않습니다.
+pub struct WarmPool {
+    config: Arc<Config>,
+    ready: Mutex<HashMap<WarmKey, VecDeque<WarmConnection>>>,
+    size: usize,
+    refresh: Duration,
+    // 연결을 꺼내 간 직후 보충 태스크를 깨움
+    refill: Notify,
+    metrics: Arc<Metrics>,
+}
+
+/// 설정에 따라 전역 미리 연결 풀 초기화 및 상태 확인/보충 태스크 시작
+pub fn init_warm_pool(config: &Arc<Config>) {
+    let warm = &config.warm_pool;
+    if warm.upstreams.is_empty() {
+        return;
+    }
+
+    let ready = warm.upstreams.iter()
+        .filter_map(|upstream| parse_authority(upstream, 443))
+        .map(|(host, port)| ((host.to_ascii_lowercase(), port), VecDeque::new()))
+        .collect::<HashMap<_, _>>();
+    let capacity = (ready.len() * warm.size) as u64;
+
+    let pool = Arc::new(WarmPool {
+        config: Arc::clone(config),
+        ready: Mutex::new(ready),
+        size: warm.size,
+        refresh: Duration::from_secs(warm.refresh_seconds),
+        refill: Notify::new(),
+        metrics: Metrics::new(),
+    });
+
+    if WARM_POOL.set(Arc::clone(&pool)).is_err() {
+        return;
+    }
+    pool.metrics.set_warm_pool_occupancy(0, capacity);
+
+    info!("미리 연결 풀 활성화: 업스트림 {:?}, 업스트림당 {} 개, {}초마다 교체",
+          warm.upstreams, warm.size, warm.refresh_seconds);
+
+    let check_interval = Duration::from_secs(warm.check_interval_seconds);
+    tokio::spawn(async move {
+        loop {
+            pool.maintain().await;
+            tokio::select! {
+                _ = tokio::time::sleep(check_interval) => {},
+                _ = pool.refill.notified() => {},
+            }
+        }
+    });
+}
+
+/// 전역 미리 연결 풀 (비활성화 시 None)
+pub fn warm_pool() -> Option<Arc<WarmPool>> {
+    WARM_POOL.get().cloned()
+}
+
+impl WarmPool {
+    /// 미리 연결된 TLS 연결 꺼내기 (고정 업스트림이 아니거나 준비된 연결이 없으면 None)
+    pub fn take(&self, host: &str, port: u16) -> Option<ClientTlsStream<TcpStream>> {
+        let key = (host.to_ascii_lowercase(), port);
+        let mut ready = self.ready.lock().unwrap();
+        let queue = ready.get_mut(&key)?;
+
+        // 가장 최근에 맺은 연결 우선 (교체 시점까지 남은 시간이 가장 김)
+        let mut found = None;
+        let mut discarded = 0;
+        while let Some(mut conn) = queue.pop_back() {
+            if conn.established.elapsed() < self.refresh && is_alive(&mut conn.stream) {
+                found = Some(conn.stream);
+                break;
+            }
+            discarded += 1;
+        }
+
+        let ready_count = Self::count(&ready);
+        drop(ready);
+
+        self.metrics.warm_pool_replaced(discarded);
+        self.metrics.set_warm_pool_occupancy(ready_count, self.capacity());
+        self.refill.notify_one();
+
+        if found.is_some() {
+            self.metrics.warm_pool_hit();
+        }
+        found
+    }
+
+    /// 끊어졌거나 교체 시점이 지난 연결을 정리하고 부족한 연결 보충
+    async fn maintain(&self) {
+        let missing: Vec<(WarmKey, usize)> = {
+            let mut ready = self.ready.lock().unwrap();
+            let mut discarded = 0;
+            for ((host, port), queue) in ready.iter_mut() {
+                let before = queue.len();
+                queue.retain_mut(|conn| conn.established.elapsed() < self.refresh && is_alive(&mut conn.stream));
+                if queue.len() < before {
+                    debug!("미리 연결 폐기: {}:{} {} 개 (종료됨 또는 교체 시점 지남)", host, port, before - queue.len());
+                }
+                discarded += (before - queue.len()) as u64;
+            }
+            self.metrics.warm_pool_replaced(discarded);
+
+            ready.iter()
+                .filter(|(_, queue)| queue.len() < self.size)
+                .map(|(key, queue)| (key.clone(), self.size - queue.len()))
+                .collect()
+        };
+
+        let mut connecting = JoinSet::new();
+        for ((host, port), count) in missing {
+            for _ in 0..count {
+                let config = Arc::clone(&self.config);
+                let key = (host.clone(), port);
+                connecting.spawn(async move {
+                    let result = connect_tls(&format_authority(&key.0, key.1), &config).await;
+                    (key, result)
+                });
+            }
+        }
+
+        while let Some(joined) = connecting.join_next().await {
+            let Ok((key, result)) = joined else { continue };
+            match result {
+                Ok(stream) => {
+                    let mut ready = self.ready.lock().unwrap();
+                    if let Some(queue) = ready.get_mut(&key) {
+                        queue.push_back(WarmConnection { stream, established: Instant::now() });
+                    }
+                },
+                Err(e) => warn!("미리 연결 실패: {}:{} - {}", key.0, key.1, e),
+            }
+        }
+
+        let ready_count = Self::count(&self.ready.lock().unwrap());
+        self.metrics.set_warm_pool_occupancy(ready_count, self.capacity());
+
+        let (ready, capacity, hits, replaced) = self.metrics.warm_pool_stats();
+        debug!("미리 연결 풀: 준비 {}/{} 개, 사용 {}, 교체 {}", ready, capacity, hits, replaced);
+    }
+
+    /// 목표 연결 수
+    fn capacity(&self) -> u64 {
+        (self.ready.lock().unwrap().len() * self.size) as u64
+    }
+
+    /// 전체 준비된 연결 수
+    fn count(ready: &HashMap<WarmKey, VecDeque<WarmConnection>>) -> u64 {
+        ready.values().map(|queue| queue.len() as u64).sum()
+    }
+}
+
+/// 대기 중인 TLS 연결이 살아 있는지 확인
+///
+/// 읽을 데이터가 없어 대기(Pending)해야 정상입니다. 서버가 보낸 세션 티켓 같은 TLS 레코드는
+/// rustls가 내부에서 처리하므로, 애플리케이션 데이터나 EOF(close_notify 포함), 오류는 사용할 수 없는 연결입니다.
+fn is_alive(stream: &mut ClientTlsStream<TcpStream>) -> bool {
+    let mut probe = [0u8; 1];
+    let mut buf = ReadBuf::new(&mut probe);
+    let mut cx = Context::from_waker(Waker::noop());
+    matches!(Pin::new(stream).poll_read(&mut cx, &mut buf), Poll::Pending)
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use log::{debug, warn};
+
+use crate::metrics::Metrics;
+
+/// 클라이언트 IP별 동시 세션 수 제한
+///
+/// 세션마다 IP의 슬롯을 하나 잡고 세션이 끝나 슬롯이 해제될 때 반납합니다.
+/// 세션이 하나도 없는 IP 항목은 반납 즉시 지웁니다.
+pub struct ClientSessionLimit {
+    sessions: Mutex<HashMap<IpAddr, usize>>,
+    max_sessions: usize,
+    metrics: Arc<Metrics>,
+}
+
+/// 클라이언트 IP 하나의 세션 슬롯 (해제 시 반납)
+pub struct ClientSessionSlot {
+    limit: Arc<ClientSessionLimit>,
+    ip: IpAddr,
+}
+
+impl ClientSessionLimit {
+    pub fn new(max_sessions: usize) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            max_sessions,
+            metrics: Metrics::new(),
+        }
+    }
+
+    /// 새 세션의 슬롯 획득 (한도에 도달했으면 거부 사유를 기록하고 None)
+    pub fn try_acquire(self: &Arc<Self>, ip: IpAddr) -> Option<ClientSessionSlot> {
+        // IPv4 매핑 IPv6 주소(::ffff:a.b.c.d)는 IPv4 주소와 같은 클라이언트로 셈
+        let ip = ip.to_canonical();
+        let mut sessions = self.sessions.lock().unwrap();
+        let count = sessions.entry(ip).or_insert(0);
+        if *count >= self.max_sessions {
+            let active = *count;
+            drop(sessions);
+            warn!("클라이언트 IP별 동시 세션 한도 초과로 연결 거부: {} (활성 {}, 한도 {})", ip, active, self.max_sessions);
+            self.metrics.client_ip_rejected();
+            return None;
+        }
+        *count += 1;
+        Some(ClientSessionSlot { limit: Arc::clone(self), ip })
+    }
+
+    fn release(&self, ip: IpAddr) {
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(count) = sessions.get_mut(&ip) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                sessions.remove(&ip);
+                debug!("클라이언트 IP 세션 항목 정리: {} (남은 IP {} 개)", ip, sessions.len());
+            }
+        }
+    }
+}
+
+impl Drop for ClientSessionSlot {
+    fn drop(&mut self) {
+        self.limit.release(self.ip);
+    }
+}
@@ -0,0 +1,106 @@
+// 남은 파일 디스크립터 예비분 확인 (overload.min_free_fds)
+// 남은 fd가 예비분보다 적으면 새 연결을 수락 직후 닫아, fd가 모두 떨어져 로그 파일이나 업스트림 연결까지
+// 열지 못하게 되는 상황을 막습니다. 사용 중인 fd 수는 주기적으로 /proc/self/fd를 세어 두고, 수락 경로에서는
+// 그 값과 방금 받은 소켓 번호(커널은 비어 있는 가장 작은 번호를 주므로 그보다 작은 번호는 모두 사용 중)만 비교합니다.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use std::sync::Mutex;
+
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use tokio::net::TcpStream;
+
+use crate::constants::{ACCEPT_FD_WARN_INTERVAL_SECS, FD_RESERVE_SAMPLE_INTERVAL_MS};
+use crate::metrics::Metrics;
+
+// 유지할 남은 fd 수 (0이면 확인하지 않음)
+static MIN_FREE_FDS: AtomicU64 = AtomicU64::new(0);
+
+// 마지막으로 확인한 fd 한도(RLIMIT_NOFILE soft)와 사용 중인 fd 수
+static NOFILE_LIMIT: AtomicU64 = AtomicU64::new(u64::MAX);
+static FDS_IN_USE: AtomicU64 = AtomicU64::new(0);
+
+// 예비분 부족으로 연결을 거부하는 중인지 (회복 로그용)
+static REFUSING: AtomicBool = AtomicBool::new(false);
+
+// 마지막 거부 경고 시각
+static WARNED_AT: Lazy<Mutex<Option<Instant>>> = Lazy::new(|| Mutex::new(None));
+
+// 주기 확인 태스크를 이미 시작했는지
+static SAMPLER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// fd 예비분 적용 (0이면 끔, 켜면 fd 한도와 사용량 주기 확인 시작)
+pub fn set_fd_reserve(min_free: u64) {
+    MIN_FREE_FDS.store(min_free, Ordering::Release);
+    REFUSING.store(false, Ordering::Relaxed);
+    if min_free == 0 {
+        return;
+    }
+
+    sample_fd_usage();
+    info!("fd 예비분: 남은 파일 디스크립터 {} 개 미만이면 새 연결 거부 (한도 {}, 사용 중 {})",
+          min_free, NOFILE_LIMIT.load(Ordering::Relaxed), FDS_IN_USE.load(Ordering::Relaxed));
+    if !SAMPLER_STARTED.swap(true, Ordering::AcqRel) {
+        tokio::spawn(async {
+            let mut interval = tokio::time::interval(Duration::from_millis(FD_RESERVE_SAMPLE_INTERVAL_MS));
+            loop {
+                interval.tick().await;
+                if MIN_FREE_FDS.load(Ordering::Acquire) > 0 {
+                    let _ = tokio::task::spawn_blocking(sample_fd_usage).await;
+                }
+            }
+        });
+    }
+}
+
+/// 수락한 연결을 받을지 여부 (예비분보다 남은 fd가 적으면 경고와 카운터를 남기고 false)
+pub fn fd_reserve_admits(stream: &TcpStream, metrics: &Metrics) -> bool {
+    let min_free = MIN_FREE_FDS.load(Ordering::Acquire);
+    if min_free == 0 {
+        return true;
+    }
+
+    let free = free_fds(stream);
+    if free >= min_free {
+        if REFUSING.swap(false, Ordering::Relaxed) {
+            info!("남은 파일 디스크립터 {} 개로 회복, 연결 수락 재개 (예비분 {})", free, min_free);
+        }
+        return true;
+    }
+
+    REFUSING.store(true, Ordering::Relaxed);
+    let refused = metrics.fd_reserve_refused();
+    let mut warned_at = WARNED_AT.lock().unwrap();
+    if warned_at.is_none_or(|at| at.elapsed() >= Duration::from_secs(ACCEPT_FD_WARN_INTERVAL_SECS)) {
+        warn!("남은 파일 디스크립터 {} 개로 예비분 {} 개 미만, 새 연결 거부 (한도 {}, 누적 {} 회)",
+              free, min_free, NOFILE_LIMIT.load(Ordering::Relaxed), refused);
+        *warned_at = Some(Instant::now());
+    }
+    false
+}
+
+/// 남은 fd 수 추정 (주기 확인 값과 방금 받은 소켓 번호 중 큰 사용량 기준)
+fn free_fds(stream: &TcpStream) -> u64 {
+    let in_use = FDS_IN_USE.load(Ordering::Relaxed);
+    #[cfg(unix)]
+    let in_use = {
+        use std::os::fd::AsRawFd;
+        in_use.max(stream.as_raw_fd() as u64 + 1)
+    };
+    #[cfg(not(unix))]
+    let _ = stream;
+    NOFILE_LIMIT.load(Ordering::Relaxed).saturating_sub(in_use)
+}
+
+/// fd 한도와 사용 중인 fd 수 갱신
+fn sample_fd_usage() {
+    #[cfg(unix)]
+    if let Ok((soft, _)) = nix::sys::resource::getrlimit(nix::sys::resource::Resource::RLIMIT_NOFILE) {
+        NOFILE_LIMIT.store(soft, Ordering::Relaxed);
+    }
+    // /proc이 없는 운영체제에서는 소켓 번호만으로 판단
+    if let Ok(entries) = std::fs::read_dir("/proc/self/fd") {
+        FDS_IN_USE.store(entries.count() as u64, Ordering::Relaxed);
+    }
+}
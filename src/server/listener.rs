@@ -0,0 +1,83 @@
+// 리스너별 정책 (listeners)
+// bind_host:bind_port 외에 추가로 연 리스너마다 TLS 가로채기 정책과 CONNECT 접근 제어 정책을 따로 적용합니다.
+// 기본 리스너로 들어온 연결은 정책이 없으며(None) 전역 설정을 그대로 따릅니다.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tokio::net::TcpListener;
+
+use crate::acl::connect_acl::ConnectAcl;
+use crate::config::{Config, InterceptionPolicy, ListenerConfig};
+
+/// 추가 리스너 하나의 정책
+#[derive(Debug)]
+pub struct ListenerPolicy {
+    pub name: String,
+    pub interception: InterceptionPolicy,
+    /// 가로채지 않을 호스트 (소문자, 정확한 호스트 또는 `*.도메인`)
+    bypass_hosts: HashSet<String>,
+    /// 리스너가 참조하는 CONNECT 접근 제어 (없으면 전역 connect_acl)
+    connect_acl: Option<Arc<ConnectAcl>>,
+}
+
+impl ListenerPolicy {
+    /// 설정에서 정책 생성 (참조한 connect_acl_policies 이름은 설정 검증에서 확인됨)
+    pub fn from_config(listener: &ListenerConfig, config: &Config) -> Self {
+        Self {
+            name: listener.name.trim().to_string(),
+            interception: listener.interception,
+            bypass_hosts: listener.bypass_hosts.iter().map(|host| normalize(host)).collect(),
+            connect_acl: listener.connect_acl.as_ref()
+                .and_then(|name| config.connect_acl_policies.get(name))
+                .map(|policy| Arc::new(ConnectAcl::from_config(policy))),
+        }
+    }
+
+    /// 호스트(또는 SNI)가 리스너의 bypass_hosts에 있는지 (정확한 호스트 또는 `*.상위도메인`)
+    pub fn bypasses(&self, host: &str) -> bool {
+        if self.bypass_hosts.is_empty() {
+            return false;
+        }
+        let host = normalize(host);
+        if self.bypass_hosts.contains(&host) {
+            return true;
+        }
+        let mut parent = host.as_str();
+        while let Some((_, rest)) = parent.split_once('.') {
+            if self.bypass_hosts.contains(&format!("*.{}", rest)) {
+                return true;
+            }
+            parent = rest;
+        }
+        false
+    }
+
+    /// 리스너에 지정한 CONNECT 접근 제어
+    pub fn connect_acl(&self) -> Option<&Arc<ConnectAcl>> {
+        self.connect_acl.as_ref()
+    }
+}
+
+fn normalize(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// 연결을 수락할 리스너와 그 리스너의 정책 (기본 리스너는 None)
+pub struct BoundListener {
+    pub(crate) listener: TcpListener,
+    pub(crate) policy: Option<Arc<ListenerPolicy>>,
+}
+
+impl BoundListener {
+    #[cfg(feature = "self-test")]
+    pub fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// 추가 리스너 이름 (기본 리스너면 None)
+    #[cfg(feature = "self-test")]
+    pub fn name(&self) -> Option<&str> {
+        self.policy.as_ref().map(|policy| policy.name.as_str())
+    }
+}
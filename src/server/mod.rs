@@ -1,17 +1,75 @@
+pub mod client_limit;
+pub mod fd_reserve;
+pub mod listener;
+pub mod source_filter;
+pub mod tenant;
+
 use std::sync::{Arc};
-use log::{error, info};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::net::SocketAddr;
+use std::panic::AssertUnwindSafe;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use log::{debug, error, info, warn};
 
-use tokio::net::TcpListener;
-use tokio::sync::{mpsc};
+use tokio::net::{TcpListener, TcpSocket, TcpStream};
+use tokio::sync::{mpsc, watch};
 use num_cpus;
 
-use crate::config::Config;
+use crate::config::{Config, ShutdownExpiryAction};
+use crate::constants::{ACCEPT_FD_BACKOFF_MS, ACCEPT_FD_WARN_INTERVAL_SECS};
 use crate::metrics::{Metrics};
 use crate::buffer::BufferPool;
 use crate::session::Session;
+use crate::session::close::SessionCloseReason;
 use crate::logging::Logger;
 use crate::acl::domain_blocker::DomainBlocker;
+use crate::proxy::dns_prewarm::{init_dns_prewarm, stop_dns_prewarm};
 use crate::error::{ProxyError, Result, internal_err};
+use client_limit::ClientSessionLimit;
+use fd_reserve::fd_reserve_admits;
+use listener::{BoundListener, ListenerPolicy};
+use source_filter::source_allowed;
+use tenant::TenantMap;
+
+/// 워커에 넘기는 수락한 연결 (추가 리스너로 들어왔으면 그 리스너의 정책 포함)
+type Accepted = (TcpStream, SocketAddr, Option<Arc<ListenerPolicy>>);
+
+/// 세션 처리 실행 (패닉이 워커나 프로세스로 전파되지 않도록 잡아서 기록)
+///
+/// 패닉이 나면 세션 future가 해제되면서 소유한 클라이언트/업스트림 소켓도 닫힙니다.
+pub async fn run_session_isolated(session_id: &str, metrics: &Metrics, task: impl Future<Output = Result<()>>) {
+    let mut task = std::pin::pin!(task);
+    let outcome = std::future::poll_fn(|cx| {
+        match std::panic::catch_unwind(AssertUnwindSafe(|| task.as_mut().poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(result)) => Poll::Ready(Ok(result)),
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }).await;
+
+    match outcome {
+        Ok(Ok(())) => {},
+        Ok(Err(e)) => error!("An error occurred while processing the session: {}", e),
+        Err(panic) => {
+            let message = panic.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            error!("[Session:{}] 세션 처리 중 패닉, 해당 세션만 종료: {}", session_id, message);
+            metrics.session_panicked();
+            metrics.session_closed(SessionCloseReason::Error, 1);
+        }
+    }
+}
+
+/// 서버 종료 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownKind {
+    /// 종료 시그널 후 graceful_shutdown 설정대로 세션을 기다리거나 닫고 종료
+    Graceful,
+    /// 종료 대기 중 SIGINT를 다시 받아 남은 세션을 기다리지 않고 종료 (종료 코드 130)
+    Forced,
+}
 
 pub struct ProxyServer {
     config: Arc<Config>,
@@ -19,11 +77,24 @@ pub struct ProxyServer {
     buffer_pool: Option<Arc<BufferPool>>,
     logger: Arc<Logger>,
     domain_blocker: Arc<DomainBlocker>,
+    // 클라이언트 IP별 동시 세션 수 (overload.max_sessions_per_client_ip 설정 시)
+    client_sessions: Option<Arc<ClientSessionLimit>>,
+    // 클라이언트 주소 대역별 테넌트 태그 (tenants.ranges 설정 시)
+    tenants: Option<Arc<TenantMap>>,
+    // 리스너별 누적 수락 연결 수 (serve 시작 시 등록)
+    listener_accepts: std::sync::RwLock<Vec<Arc<AtomicU64>>>,
 }
 
 impl ProxyServer {
     pub fn new(config: Arc<Config>, metrics: Arc<Metrics>, buffer_pool: Option<Arc<BufferPool>>, logger: Arc<Logger>, domain_blocker: Arc<DomainBlocker>) -> Self {
+        let max_sessions = config.overload.max_sessions_per_client_ip;
+        if max_sessions > 0 {
+            info!("클라이언트 IP별 동시 세션 한도: {}", max_sessions);
+        }
         Self {
+            client_sessions: (max_sessions > 0).then(|| Arc::new(ClientSessionLimit::new(max_sessions))),
+            tenants: TenantMap::from_config(&config.tenants).map(Arc::new),
+            listener_accepts: std::sync::RwLock::new(Vec::new()),
             config,
             metrics,
             buffer_pool,
@@ -32,15 +103,132 @@ impl ProxyServer {
         }
     }
 
-    pub async fn run(&self) -> Result<()> {
+    pub async fn run(&self) -> Result<ShutdownKind> {
+        // 지연에 민감한 업스트림 이름 미리 해석 (dns_prewarm.hosts 설정 시, 실패해도 시작은 계속)
+        let dns_refresh = init_dns_prewarm(&self.config).await;
+        let result = match self.bind().await {
+            Ok(listeners) => self.serve(listeners, Self::shutdown_signal()).await,
+            Err(e) => Err(e),
+        };
+        stop_dns_prewarm(dns_refresh);
+        result
+    }
+
+    /// 종료 시그널(SIGINT, SIGTERM) 대기
+    async fn shutdown_signal() {
+        #[cfg(unix)]
+        let terminate = async {
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(mut signal) => {
+                    signal.recv().await;
+                }
+                Err(e) => {
+                    warn!("can't install SIGTERM handler: {}", e);
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("SIGINT received"),
+            _ = terminate => info!("SIGTERM received"),
+        }
+    }
+
+    /// 설정된 주소로 리스너 생성
+    ///
+    /// `reuseport_enabled`이면 같은 주소에 SO_REUSEPORT 리스너를 여러 개 열어 커널이 연결을 나눠 주도록 하고,
+    /// 지원하지 않거나 추가 리스너를 열 수 없으면 리스너 하나로 실행합니다.
+    /// `listeners`에 지정한 추가 리스너는 그 뒤에 정책과 함께 붙습니다 (추가 리스너는 SO_REUSEPORT를 쓰지 않음).
+    pub async fn bind(&self) -> Result<Vec<BoundListener>> {
         let addr = format!("{}:{}", self.config.bind_host, self.config.bind_port);
-        let listener = TcpListener::bind(&addr).await?;
+        let socket_addr = tokio::net::lookup_host(&addr).await?
+            .next()
+            .ok_or_else(|| internal_err(format!("bind address not resolved: {}", addr)))?;
 
-        info!("proxy server start at: {}", addr);
+        let count = self.reuseport_listener_count();
+        let first = match self.bind_listener(socket_addr, count > 1) {
+            Ok(listener) => listener,
+            Err(e) if count > 1 => {
+                warn!("SO_REUSEPORT 리스너 생성 실패, 리스너 하나로 실행: {}", e);
+                self.bind_listener(socket_addr, false)?
+            }
+            Err(e) => return Err(e),
+        };
+        // 포트 0으로 바인딩한 경우에도 추가 리스너가 같은 포트를 쓰도록 실제 주소 사용
+        let local_addr = first.local_addr()?;
+        let mut listeners = vec![first];
+        for _ in 1..count {
+            match self.bind_listener(local_addr, true) {
+                Ok(listener) => listeners.push(listener),
+                Err(e) => {
+                    warn!("SO_REUSEPORT 리스너 추가 실패, 리스너 {} 개로 실행: {}", listeners.len(), e);
+                    break;
+                }
+            }
+        }
+
+        let backlog = self.config.listen_backlog;
+        match Self::os_max_backlog() {
+            Some(max) if backlog > max => {
+                warn!("listen backlog {} exceeds net.core.somaxconn {}, kernel will clamp to {}", backlog, max, max);
+                info!("listen backlog: {} (effective: {})", backlog, max);
+            }
+            _ => info!("listen backlog: {}", backlog),
+        }
+        if self.config.accept_batch_size > 1 {
+            info!("accept batch size: {}", self.config.accept_batch_size);
+        }
+        if listeners.len() > 1 {
+            info!("SO_REUSEPORT listeners: {}", listeners.len());
+        }
+
+        info!("proxy server start at: {}", local_addr);
+        let mut listeners: Vec<BoundListener> = listeners.into_iter()
+            .map(|listener| BoundListener { listener, policy: None })
+            .collect();
+        for settings in &self.config.listeners {
+            let host = settings.bind_host.as_deref().unwrap_or(&self.config.bind_host);
+            let addr = format!("{}:{}", host, settings.bind_port);
+            let socket_addr = tokio::net::lookup_host(&addr).await?
+                .next()
+                .ok_or_else(|| internal_err(format!("listener {} bind address not resolved: {}", settings.name, addr)))?;
+            let listener = self.bind_listener(socket_addr, false)?;
+            let policy = ListenerPolicy::from_config(settings, &self.config);
+            info!("추가 리스너 {} 시작: {} (가로채기 {}, CONNECT 접근 제어 {})", policy.name, listener.local_addr()?,
+                  policy.interception.name(), settings.connect_acl.as_deref().unwrap_or("전역"));
+            listeners.push(BoundListener { listener, policy: Some(Arc::new(policy)) });
+        }
+        Ok(listeners)
+    }
 
+    /// 열 리스너 수 (SO_REUSEPORT를 쓰지 않으면 1)
+    fn reuseport_listener_count(&self) -> usize {
+        if !self.config.reuseport_enabled {
+            return 1;
+        }
+        if !cfg!(target_os = "linux") {
+            warn!("reuseport_enabled는 Linux에서만 지원합니다, 리스너 하나로 실행");
+            return 1;
+        }
+        match self.config.reuseport_listeners {
+            0 => num_cpus::get(),
+            count => count,
+        }
+    }
+
+    /// 리스너에서 연결을 수락하여 워커에게 분배 (리스너마다 수락 태스크 하나)
+    ///
+    /// shutdown이 완료되면 수락을 중단하고, 진행 중인 세션을 `graceful_shutdown` 설정에 따라 기다린 뒤 종료 방식을 반환합니다.
+    pub async fn serve(&self, listeners: Vec<BoundListener>, shutdown: impl Future<Output = ()>) -> Result<ShutdownKind> {
         let worker_count = num_cpus::get();
+        // 진행 중인 세션 수 (종료 대기용)
+        let active_sessions = Arc::new(AtomicUsize::new(0));
 
-        let (tx, rx) = mpsc::channel(1000);
+        let (tx, rx) = mpsc::channel::<Accepted>(1000);
         let rx = Arc::new(tokio::sync::Mutex::new(rx));
 
         for worker_id in 0..worker_count {
@@ -50,12 +238,16 @@ impl ProxyServer {
             let worker_buffer_pool = self.buffer_pool.clone();
             let worker_logger = self.logger.clone();
             let worker_domain_blocker = self.domain_blocker.clone();
+            let worker_load = self.config.worker_metrics_enabled.then(|| self.metrics.register_worker());
+            let worker_active_sessions = active_sessions.clone();
+            let worker_client_sessions = self.client_sessions.clone();
+            let worker_tenants = self.tenants.clone();
 
             tokio::spawn(async move {
                 info!("worker #{} start", worker_id);
 
                 loop {
-                    let (client_stream, client_addr) = {
+                    let (client_stream, client_addr, listener_policy) = {
                         let mut rx_guard = worker_rx.lock().await;
                         match rx_guard.recv().await {
                             Some(conn) => conn,
@@ -63,7 +255,16 @@ impl ProxyServer {
                         }
                     };
 
-                    let session = Session::new(
+                    // 클라이언트 IP별 동시 세션 한도 (넘으면 요청을 읽지 않고 연결을 닫음)
+                    let client_slot = match &worker_client_sessions {
+                        Some(limit) => match limit.try_acquire(client_addr.ip()) {
+                            Some(slot) => Some(slot),
+                            None => continue,
+                        },
+                        None => None,
+                    };
+
+                    let mut session = Session::new(
                         client_stream,
                         client_addr,
                         worker_metrics.clone(),
@@ -73,27 +274,262 @@ impl ProxyServer {
                         worker_domain_blocker.clone(),
                     );
 
+                    if let Some(policy) = listener_policy {
+                        session = session.with_listener(policy);
+                    }
+
+                    // 세션 시작 시 테넌트 태그 결정 (세션 중에는 바뀌지 않음)
+                    let tenant = worker_tenants.as_ref().map(|tenants| tenants.resolve(client_addr.ip()));
+                    if let Some(tenant) = &tenant {
+                        session = session.with_tenant(tenant.clone());
+                        worker_metrics.tenant_session_started(tenant);
+                    }
+
+                    let session_load = worker_load.clone();
+                    if let Some(load) = &session_load {
+                        load.session_started();
+                    }
+
+                    let session_metrics = worker_metrics.clone();
+                    let session_active = worker_active_sessions.clone();
+                    session_active.fetch_add(1, Ordering::Relaxed);
                     tokio::spawn(async move {
-                        if let Err(e) = session.handle().await {
-                            error!("An error occurred while processing the session: {}", e);
+                        let session_id = session.session_id().to_string();
+                        run_session_isolated(&session_id, &session_metrics, session.handle()).await;
+                        if let Some(load) = session_load {
+                            load.session_finished();
+                        }
+                        session_active.fetch_sub(1, Ordering::Relaxed);
+                        if let Some(tenant) = &tenant {
+                            session_metrics.tenant_session_finished(tenant);
                         }
+                        drop(client_slot);
                     });
                 }
             });
         }
 
+        // 워커별 부하 주기 로깅
+        if self.config.worker_metrics_enabled {
+            let metrics = self.metrics.clone();
+            let period = Duration::from_secs(self.config.worker_metrics_interval_seconds);
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(period);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    metrics.log_worker_loads();
+                }
+            });
+        }
+
         // 연결 수락 및 워커에게 분배
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let listener_count = listeners.len();
+        let mut accept_tasks = Vec::with_capacity(listener_count);
+        for BoundListener { listener, policy } in listeners {
+            let accepted = Arc::new(AtomicU64::new(0));
+            self.listener_accepts.write().unwrap().push(accepted.clone());
+            accept_tasks.push(tokio::spawn(Self::accept_loop(
+                listener,
+                policy,
+                tx.clone(),
+                self.metrics.clone(),
+                self.config.accept_batch_size,
+                stop_rx.clone(),
+                accepted,
+            )));
+        }
+
+        shutdown.await;
+        info!("proxy server shutdown: stop accepting");
+        let _ = stop_tx.send(true);
+        for task in accept_tasks {
+            let _ = task.await;
+        }
+        if listener_count > 1 {
+            info!("리스너별 수락 연결 수: {:?}", self.listener_accepts());
+        }
+
+        // 워커가 대기 중인 연결까지 처리한 뒤 종료하도록 채널을 닫고 진행 중인 세션 대기
+        drop(tx);
+        let kind = self.drain_sessions(&active_sessions).await;
+
+        // 싱크 대기열에 남은 로그 내보내기 (느린 싱크는 제한 시간까지만 대기)
+        if let Err(e) = self.logger.flush().await {
+            warn!("종료 시 로그 플러시 실패: {}", e);
+        }
+        for stats in self.logger.sink_stats() {
+            info!("로그 싱크 {}: 기록 {}, 실패 {}, 버림 {}", stats.name, stats.written, stats.failed, stats.dropped);
+        }
+        Ok(kind)
+    }
+
+    /// 리스너별 누적 수락 연결 수 (`serve`에 넘긴 리스너 순서)
+    pub fn listener_accepts(&self) -> Vec<u64> {
+        self.listener_accepts.read().unwrap().iter()
+            .map(|accepted| accepted.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    /// 리스너 하나의 수락 루프 (stop을 받으면 리스너를 닫고 반환)
+    async fn accept_loop(
+        listener: TcpListener,
+        policy: Option<Arc<ListenerPolicy>>,
+        tx: mpsc::Sender<Accepted>,
+        metrics: Arc<Metrics>,
+        batch_size: usize,
+        mut stop: watch::Receiver<bool>,
+        accepted_count: Arc<AtomicU64>,
+    ) {
+        let mut fd_warned_at: Option<Instant> = None;
         loop {
-            match listener.accept().await {
-                Ok((client_stream, client_addr)) => {
-                    if let Err(e) = tx.send((client_stream, client_addr)).await {
-                        error!("can't send session to rx: {}", e);
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = stop.changed() => break,
+            };
+            
+            match accepted {
+                Ok(conn) => {
+                    accepted_count.fetch_add(1, Ordering::Relaxed);
+                    Self::dispatch(&tx, conn, &policy, &metrics).await;
+                    
+                    // 이미 대기 중인 연결은 추가 대기 없이 배치로 수락
+                    for _ in 1..batch_size {
+                        match std::future::poll_fn(|cx| Poll::Ready(listener.poll_accept(cx))).await {
+                            Poll::Ready(Ok(conn)) => {
+                                accepted_count.fetch_add(1, Ordering::Relaxed);
+                                Self::dispatch(&tx, conn, &policy, &metrics).await;
+                            }
+                            Poll::Ready(Err(e)) => {
+                                Self::handle_accept_error(&metrics, &e, &mut fd_warned_at).await;
+                                break;
+                            }
+                            Poll::Pending => break,
+                        }
                     }
                 }
                 Err(e) => {
-                    error!("can't accept from listener: {}", e);
+                    Self::handle_accept_error(&metrics, &e, &mut fd_warned_at).await;
                 }
             }
         }
     }
+    
+    /// accept 실패 처리
+    ///
+    /// 파일 디스크립터 부족(EMFILE/ENFILE)은 대기 연결이 그대로 남아 바로 다시 실패하므로
+    /// 잠시 쉬었다가 다시 받습니다 (경고 로그는 `ACCEPT_FD_WARN_INTERVAL_SECS`마다 한 번).
+    async fn handle_accept_error(metrics: &Metrics, e: &std::io::Error, warned_at: &mut Option<Instant>) {
+        if !matches!(e.raw_os_error(), Some(libc::EMFILE) | Some(libc::ENFILE)) {
+            error!("can't accept from listener: {}", e);
+            return;
+        }
+        
+        let exhausted = metrics.accept_fd_exhausted();
+        if warned_at.is_none_or(|at| at.elapsed() >= Duration::from_secs(ACCEPT_FD_WARN_INTERVAL_SECS)) {
+            warn!("파일 디스크립터 부족으로 연결 수락 실패: {} ({}ms 후 재시도, 누적 {} 회)", e, ACCEPT_FD_BACKOFF_MS, exhausted);
+            *warned_at = Some(Instant::now());
+        }
+        tokio::time::sleep(Duration::from_millis(ACCEPT_FD_BACKOFF_MS)).await;
+    }
+    
+    /// 종료 시그널 후 진행 중인 세션이 끝나기를 기다림 (매초 남은 세션 수와 남은 시간 기록)
+    ///
+    /// 대기 시간이 지나면 `on_expiry`에 따라 남은 세션을 강제로 닫거나(반환 후 런타임 종료와 함께 닫힘)
+    /// 모든 세션이 끝날 때까지 계속 기다립니다. 대기 중 SIGINT를 다시 받으면 남은 세션을 기다리지 않고
+    /// `ShutdownKind::Forced`를 반환합니다 (종료 보고 후 호출한 쪽에서 종료 코드 130으로 종료).
+    async fn drain_sessions(&self, active_sessions: &AtomicUsize) -> ShutdownKind {
+        let settings = &self.config.graceful_shutdown;
+        let grace = Duration::from_secs(settings.grace_seconds);
+        let started = Instant::now();
+        
+        let second_interrupt = tokio::signal::ctrl_c();
+        tokio::pin!(second_interrupt);
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+        ticker.tick().await;
+        
+        loop {
+            let active = active_sessions.load(Ordering::Relaxed);
+            if active == 0 {
+                info!("종료 대기 완료: 진행 중인 세션 없음 ({}초 대기)", started.elapsed().as_secs());
+                return ShutdownKind::Graceful;
+            }
+            
+            let elapsed = started.elapsed();
+            if elapsed < grace {
+                let remaining = (grace - elapsed).as_secs_f64().ceil() as u64;
+                info!("종료 대기: 활성 세션 {} 개, 강제 종료까지 {}초", active, remaining);
+            } else if settings.on_expiry == ShutdownExpiryAction::ForceClose {
+                warn!("종료 대기 시간 {}초 초과: 남은 세션 {} 개를 강제로 닫습니다", settings.grace_seconds, active);
+                self.metrics.session_closed(SessionCloseReason::ForcedShutdown, active as u64);
+                return ShutdownKind::Graceful;
+            } else {
+                info!("종료 대기: 활성 세션 {} 개, {}초 경과 (모든 세션이 끝날 때까지 대기)", active, elapsed.as_secs());
+            }
+            
+            tokio::select! {
+                _ = ticker.tick() => {},
+                _ = &mut second_interrupt => {
+                    let active = active_sessions.load(Ordering::Relaxed);
+                    warn!("SIGINT received again: 남은 세션 {} 개를 닫고 즉시 종료합니다", active);
+                    self.metrics.session_closed(SessionCloseReason::ForcedShutdown, active as u64);
+                    return ShutdownKind::Forced;
+                }
+            }
+        }
+    }
+    
+    /// 수락한 연결을 워커에게 전달
+    async fn dispatch(tx: &mpsc::Sender<Accepted>, (stream, addr): (TcpStream, SocketAddr), policy: &Option<Arc<ListenerPolicy>>, metrics: &Metrics) {
+        // 출발지 IP 필터에 걸린 연결은 세션을 만들지 않고 바로 닫음
+        if !source_allowed(addr.ip()) {
+            debug!("출발지 IP 필터로 연결 닫음: {}", addr);
+            metrics.source_filtered();
+            drop(stream);
+            return;
+        }
+        // 남은 파일 디스크립터가 예비분보다 적으면 세션을 만들지 않고 닫음 (overload.min_free_fds)
+        if !fd_reserve_admits(&stream, metrics) {
+            drop(stream);
+            return;
+        }
+        if let Err(e) = tx.send((stream, addr, policy.clone())).await {
+            error!("can't send session to rx: {}", e);
+        }
+    }
+    
+    /// 설정된 backlog로 리스너 소켓 생성
+    fn bind_listener(&self, socket_addr: SocketAddr, reuseport: bool) -> Result<TcpListener> {
+        let socket = if socket_addr.is_ipv4() {
+            TcpSocket::new_v4()?
+        } else {
+            TcpSocket::new_v6()?
+        };
+        socket.set_reuseaddr(true)?;
+        #[cfg(target_os = "linux")]
+        if reuseport {
+            socket.set_reuseport(true)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = reuseport;
+        socket.bind(socket_addr)?;
+        
+        Ok(socket.listen(self.config.listen_backlog)?)
+    }
+    
+    /// 운영체제가 허용하는 최대 backlog (확인 가능한 경우)
+    fn os_max_backlog() -> Option<u32> {
+        #[cfg(target_os = "linux")]
+        {
+            std::fs::read_to_string("/proc/sys/net/core/somaxconn")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+        }
+        
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
 }
\ No newline at end of file
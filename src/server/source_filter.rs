@@ -0,0 +1,54 @@
+// 출발지 IP 필터 (source_filter)
+// 연결을 수락한 직후 세션을 만들기 전에 출발지 주소만 보고 닫습니다 (TLS 핸드셰이크나 요청 파싱 없음).
+// 필터가 꺼져 있으면 원자 변수 하나만 읽으므로 정상 연결의 수락 경로에 잠금이 더해지지 않습니다.
+
+use std::net::IpAddr;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::info;
+use once_cell::sync::Lazy;
+
+use crate::config::{SourceFilterConfig, SourceFilterMode};
+use crate::server::tenant::{cidr_contains, parse_cidr};
+
+// 필터 사용 여부 (mode가 off가 아닐 때 true)
+static SOURCE_FILTER_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 현재 적용 중인 필터 (재로드 시 교체)
+static SOURCE_FILTER: Lazy<RwLock<SourceFilter>> = Lazy::new(|| RwLock::new(SourceFilter::default()));
+
+/// 동작 방식과 대상 대역
+#[derive(Debug, Default)]
+struct SourceFilter {
+    mode: SourceFilterMode,
+    ranges: Vec<(IpAddr, u8)>,
+}
+
+/// 출발지 IP 필터 적용 (시작 시와 재로드 시, 형식은 설정 검증에서 확인됨)
+pub fn set_source_filter(config: &SourceFilterConfig) {
+    let filter = SourceFilter {
+        mode: config.mode,
+        ranges: config.cidrs.iter().filter_map(|cidr| parse_cidr(cidr).ok()).collect(),
+    };
+    if filter.mode != SourceFilterMode::Off {
+        info!("출발지 IP 필터: {} (대역 {}개)", filter.mode.name(), filter.ranges.len());
+    }
+    let enabled = filter.mode != SourceFilterMode::Off;
+    *SOURCE_FILTER.write().unwrap() = filter;
+    SOURCE_FILTER_ENABLED.store(enabled, Ordering::Release);
+}
+
+/// 출발지 주소에서 온 연결을 받을지 여부
+pub fn source_allowed(ip: IpAddr) -> bool {
+    if !SOURCE_FILTER_ENABLED.load(Ordering::Acquire) {
+        return true;
+    }
+    let filter = SOURCE_FILTER.read().unwrap();
+    let listed = filter.ranges.iter().any(|range| cidr_contains(*range, ip));
+    match filter.mode {
+        SourceFilterMode::Off => true,
+        SourceFilterMode::Deny => !listed,
+        SourceFilterMode::Allow => listed,
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use log::info;
+
+use crate::config::TenantConfig;
+
+/// 접두사 길이 하나의 대역 (네트워크 주소 → 태그)
+struct PrefixTable {
+    prefix_len: u8,
+    networks: HashMap<u128, Arc<str>>,
+}
+
+/// 클라이언트 주소 → 테넌트 태그 조회
+///
+/// 대역을 접두사 길이별 해시 테이블로 나눠 두고 긴 접두사부터 찾으므로
+/// 조회 비용은 대역 수가 아니라 서로 다른 접두사 길이 수에 비례합니다.
+pub struct TenantMap {
+    v4: Vec<PrefixTable>,
+    v6: Vec<PrefixTable>,
+    default_tag: Arc<str>,
+}
+
+/// `주소` 또는 `주소/접두사 길이` 해석 (주소는 네트워크 주소로 맞춤)
+pub fn parse_cidr(cidr: &str) -> Result<(IpAddr, u8), String> {
+    let (addr, prefix_len) = match cidr.trim().split_once('/') {
+        Some((addr, len)) => (addr, Some(len)),
+        None => (cidr.trim(), None),
+    };
+    let addr: IpAddr = addr.parse().map_err(|_| "IP 주소가 아닙니다".to_string())?;
+    let max_len = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix_len = match prefix_len {
+        Some(len) => len.parse::<u8>().ok().filter(|len| *len <= max_len)
+            .ok_or_else(|| format!("접두사 길이는 0 ~ {}이어야 합니다", max_len))?,
+        None => max_len,
+    };
+    Ok((addr, prefix_len))
+}
+
+/// 주소가 대역(`parse_cidr` 결과)에 속하는지 확인 (IPv4 매핑 IPv6 주소는 IPv4로 비교)
+pub fn cidr_contains(range: (IpAddr, u8), ip: IpAddr) -> bool {
+    let (network_addr, prefix_len) = range;
+    let ip = ip.to_canonical();
+    if network_addr.is_ipv4() != ip.is_ipv4() {
+        return false;
+    }
+    let (network_bits, total_len) = address_bits(network_addr);
+    let (bits, _) = address_bits(ip);
+    network(network_bits, total_len, prefix_len) == network(bits, total_len, prefix_len)
+}
+
+/// 주소를 128비트 값과 주소 체계의 전체 길이로 변환
+fn address_bits(ip: IpAddr) -> (u128, u8) {
+    match ip {
+        IpAddr::V4(ip) => (u32::from(ip) as u128, 32),
+        IpAddr::V6(ip) => (u128::from(ip), 128),
+    }
+}
+
+/// 접두사 길이만 남긴 네트워크 주소
+fn network(bits: u128, total_len: u8, prefix_len: u8) -> u128 {
+    let host_bits = (total_len - prefix_len) as u32;
+    bits.checked_shr(host_bits).unwrap_or(0)
+}
+
+impl TenantMap {
+    /// 설정에서 조회 테이블 생성 (대역이 없으면 None, 형식은 설정 검증에서 확인됨)
+    pub fn from_config(config: &TenantConfig) -> Option<Self> {
+        if !config.is_enabled() {
+            return None;
+        }
+
+        let mut v4: Vec<PrefixTable> = Vec::new();
+        let mut v6: Vec<PrefixTable> = Vec::new();
+        for range in &config.ranges {
+            let Ok((addr, prefix_len)) = parse_cidr(&range.cidr) else { continue };
+            let (bits, total_len) = address_bits(addr);
+            let tables = if addr.is_ipv4() { &mut v4 } else { &mut v6 };
+            let table = match tables.iter_mut().position(|table| table.prefix_len == prefix_len) {
+                Some(index) => &mut tables[index],
+                None => {
+                    tables.push(PrefixTable { prefix_len, networks: HashMap::new() });
+                    tables.last_mut().unwrap()
+                }
+            };
+            // 같은 대역이 여러 번 있으면 먼저 적은 태그를 사용
+            table.networks.entry(network(bits, total_len, prefix_len)).or_insert_with(|| Arc::from(range.tag.as_str()));
+        }
+        v4.sort_by_key(|table| std::cmp::Reverse(table.prefix_len));
+        v6.sort_by_key(|table| std::cmp::Reverse(table.prefix_len));
+
+        info!("테넌트 태그 대역 {}개 (기본 태그 {})", config.ranges.len(), config.default_tag);
+        Some(Self { v4, v6, default_tag: Arc::from(config.default_tag.as_str()) })
+    }
+
+    /// 클라이언트 주소의 테넌트 태그 (어느 대역에도 속하지 않으면 기본 태그)
+    pub fn resolve(&self, ip: IpAddr) -> Arc<str> {
+        // IPv4 매핑 IPv6 주소(::ffff:a.b.c.d)는 IPv4 대역으로 찾음
+        let ip = ip.to_canonical();
+        let (bits, total_len) = address_bits(ip);
+        let tables = if ip.is_ipv4() { &self.v4 } else { &self.v6 };
+        tables.iter()
+            .find_map(|table| table.networks.get(&network(bits, total_len, table.prefix_len)))
+            .unwrap_or(&self.default_tag)
+            .clone()
+    }
+}
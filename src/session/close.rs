@@ -0,0 +1,136 @@
+// 세션 종료 사유 분류
+// 세션이 끝나는 경로마다 사유를 하나만 정해 메트릭스(session_close_*)로 집계합니다.
+// 처리 중 먼저 기록된 사유가 우선하고, 기록된 사유가 없으면 세션 결과(성공/실패)로 정합니다.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use crate::error::{ProxyError, Result};
+use crate::metrics::Metrics;
+use crate::proxy::relay::CloseKind;
+
+/// 세션 종료 사유
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionCloseReason {
+    /// 어느 쪽이든 정상 종료 (FIN, 응답 완료, 빈 요청)
+    Eof,
+    /// 클라이언트 요청 또는 업스트림 응답을 기다리다 시간 초과
+    IdleTimeout,
+    /// max_session_duration_seconds 초과
+    MaxDuration,
+    /// 클라이언트가 RST로 종료
+    ClientReset,
+    /// 업스트림이 RST로 종료
+    UpstreamReset,
+    /// 차단 또는 거부 (ACL 차단/redirect/tarpit, 과부하 한도, CONNECT 뒤 데이터 거부)
+    Blocked,
+    /// 처리 오류 또는 패닉
+    Error,
+    /// 종료 대기 시간이 지나 강제로 닫음
+    ForcedShutdown,
+}
+
+impl SessionCloseReason {
+    /// 로그와 메트릭스에 쓰는 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionCloseReason::Eof => "eof",
+            SessionCloseReason::IdleTimeout => "idle_timeout",
+            SessionCloseReason::MaxDuration => "max_duration",
+            SessionCloseReason::ClientReset => "client_reset",
+            SessionCloseReason::UpstreamReset => "upstream_reset",
+            SessionCloseReason::Blocked => "blocked",
+            SessionCloseReason::Error => "error",
+            SessionCloseReason::ForcedShutdown => "forced_shutdown",
+        }
+    }
+
+    /// 릴레이 종료 방식에 해당하는 사유 (FIN은 정상 종료이므로 없음)
+    pub fn from_close_kind(close: CloseKind) -> Option<Self> {
+        match close {
+            CloseKind::Fin => None,
+            CloseKind::ClientReset => Some(SessionCloseReason::ClientReset),
+            CloseKind::UpstreamReset => Some(SessionCloseReason::UpstreamReset),
+        }
+    }
+}
+
+/// 세션 처리 중 기록한 종료 사유와 릴레이 종료 방식 (처음 기록한 값만 유지)
+#[derive(Debug, Default)]
+pub struct CloseRecorder {
+    reason: OnceLock<SessionCloseReason>,
+    kind: OnceLock<CloseKind>,
+}
+
+impl CloseRecorder {
+    /// 종료 사유 기록 (이미 기록되어 있으면 무시)
+    pub fn record(&self, reason: SessionCloseReason) {
+        let _ = self.reason.set(reason);
+    }
+
+    /// 릴레이 종료 방식(FIN/RST) 기록, RST면 해당 종료 사유도 기록
+    pub fn record_close_kind(&self, kind: CloseKind) {
+        let _ = self.kind.set(kind);
+        if let Some(reason) = SessionCloseReason::from_close_kind(kind) {
+            self.record(reason);
+        }
+    }
+
+    /// 기록된 릴레이 종료 방식 (릴레이 없이 끝난 세션은 없음)
+    pub fn close_kind(&self) -> Option<CloseKind> {
+        self.kind.get().copied()
+    }
+
+    /// 기록된 사유, 없으면 세션 결과에 따른 사유
+    pub fn resolve(&self, result: &Result<()>) -> SessionCloseReason {
+        if let Some(reason) = self.reason.get() {
+            return *reason;
+        }
+        match result {
+            Ok(()) => SessionCloseReason::Eof,
+            Err(ProxyError::Timeout(_)) => SessionCloseReason::IdleTimeout,
+            Err(_) => SessionCloseReason::Error,
+        }
+    }
+}
+
+/// 세션이 연 활성 연결 게이지 (http/tls_active_connections)
+///
+/// 닫지 않은 채 해제되면(세션 패닉 등) 남은 연결을 게이지에서 뺍니다.
+pub struct ActiveConnections {
+    metrics: Arc<Metrics>,
+    http: AtomicU64,
+    tls: AtomicU64,
+}
+
+impl ActiveConnections {
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self { metrics, http: AtomicU64::new(0), tls: AtomicU64::new(0) }
+    }
+
+    fn count(&self, https_flag: bool) -> &AtomicU64 {
+        if https_flag { &self.tls } else { &self.http }
+    }
+
+    /// 연결 시작 (게이지 증가)
+    pub fn opened(&self, https_flag: bool) {
+        self.count(https_flag).fetch_add(1, Ordering::Relaxed);
+        self.metrics.connection_opened(https_flag);
+    }
+
+    /// 연결 종료 (게이지 감소)
+    pub fn closed(&self, https_flag: bool) {
+        let _ = self.count(https_flag).fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| count.checked_sub(1));
+        self.metrics.connection_closed(https_flag);
+    }
+}
+
+impl Drop for ActiveConnections {
+    fn drop(&mut self) {
+        for https_flag in [false, true] {
+            for _ in 0..self.count(https_flag).load(Ordering::Relaxed) {
+                self.metrics.connection_closed(https_flag);
+            }
+        }
+    }
+}
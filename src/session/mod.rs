@@ -1,31 +1,60 @@
+pub mod close;
+pub mod transfer;
+
 use std::net::SocketAddr;
 use std::os::fd::{AsRawFd, FromRawFd};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use socket2::Socket;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
+use tokio::sync::watch;
 use bytes::BytesMut;
 use uuid;
 
-use crate::config::Config;
+use crate::config::{ClientHelloIncompleteAction, Config, EgressPool, InterceptionPolicy, NoSniAction, TunnelDetectTimeoutAction};
 use crate::constants::*;
-use crate::metrics::Metrics;
+use crate::metrics::{AclRuleKind, Metrics};
 use crate::buffer::BufferPool;
-use crate::tls::{accept_tls_with_cert, connect_tls, generate_fake_cert};
+use crate::tls::{accept_tls_with_cert, connect_tls_via, generate_fake_cert, upstream_cert_warning};
+use crate::tls::exemption::is_tls_exempt;
+use crate::tls::client_hello::{peek_client_hello, peek_client_hello_fingerprint, ClientHelloPeek, ClientHelloSni, TunnelProtocol, TLS_HANDSHAKE_RECORD};
 use crate::proxy::http::proxy_http_streams;
+use crate::proxy::pool::{upstream_pool, PoolKey};
+use crate::proxy::warm::warm_pool;
+use crate::proxy::socks::{parse_socks4, socks4_reply, Socks4Command, Socks4Parse, Socks4Request, SOCKS4_MAX_REQUEST_LEN, SOCKS4_VERSION};
+use crate::proxy::dialer::connect_upstream;
+use crate::proxy::timing::UpstreamTiming;
+use crate::proxy::egress::{select_egress, strip_egress_header};
+use crate::proxy::parents::select_parent;
+use crate::proxy::target::{canonical_host, dial_address, format_authority, parse_authority};
+use crate::proxy::relay::{relay_tcp, CloseKind};
+use crate::proxy::sockbuf::apply_socket_buffers;
+use crate::proxy::throttle::throttle_client;
+use crate::proxy::connect_pipeline::{classify, decide, is_ambiguous, pipelined_offset, PipelineAction, PrefixedStream};
 use crate::proxy::tls::proxy_tls_streams;
-use crate::acl::domain_blocker::DomainBlocker;
+use crate::proxy::acl_response::{send_redirect, tarpit};
+use crate::proxy::host_limit::host_limiter;
+use crate::proxy::overload::{overload_guard, OverloadRejected};
+use crate::admin::pac::proxy_listener_response;
+use crate::proxy::maintenance::{self, is_maintenance};
+use crate::acl::domain_blocker::{AclDecision, DomainBlocker};
+use crate::acl::connect_acl::connect_acl;
+use crate::server::listener::ListenerPolicy;
 use crate::acl::block_page::BlockPage;
+use crate::proxy::error_page::{negotiate, ErrorReason, ErrorResponse};
 use crate::logging::Logger;
 use crate::error::{ProxyError, Result, http_err, internal_err, tls_err};
+use close::{ActiveConnections, CloseRecorder, SessionCloseReason};
+use transfer::TransferRecorder;
 
 /// HTTP 요청 파싱 결과
 #[derive(Debug)]
 struct HttpRequest {
     method: String,
+    /// 정규 형식 호스트 (canonical_host)
     host: String,
     port: u16,
 }
@@ -40,6 +69,24 @@ pub struct Session {
     domain_blocker: Arc<DomainBlocker>,
     block_page: BlockPage,
     logger: Arc<Logger>,
+    // 최대 세션 시간 기한 (세션 시작 시 한 번만 설정, 활동으로 연장되지 않음)
+    deadline: Option<tokio::time::Instant>,
+    // 요청 헤더로 선택된 업스트림 출구 (None이면 기본 출구)
+    egress: Option<EgressPool>,
+    // parent_proxies에서 마지막으로 고른 상위 프록시 주소 (세션 종료 기록에 남김)
+    parent_proxy: Mutex<Option<String>>,
+    // 클라이언트 주소 대역의 테넌트 태그 (tenants 설정 시)
+    tenant: Option<Arc<str>>,
+    // 처리 중 기록한 세션 종료 사유 (처음 기록한 사유 우선)
+    close: CloseRecorder,
+    // 이 세션이 연 활성 연결 게이지 (패닉으로 해제되어도 게이지에서 뺌)
+    active: ActiveConnections,
+    // 릴레이한 방향별 바이트 수 (종료 시 세션 크기 분포에 집계)
+    transfer: TransferRecorder,
+    // 점검 모드 전환 시 진행 중인 세션 종료 요청 (세션 시작 시 구독)
+    maintenance_close: watch::Receiver<u64>,
+    // 연결을 수락한 추가 리스너의 정책 (기본 리스너면 None)
+    listener: Option<Arc<ListenerPolicy>>,
 }
 
 impl Session {
@@ -53,6 +100,8 @@ impl Session {
         domain_blocker: Arc<DomainBlocker>
     ) -> Self {
         let session_id = Session::generate_unique_id(&client_addr);
+        let deadline = config.max_session_duration_seconds
+            .map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs));
         
         Self {
             client_stream: Some(client_stream),
@@ -64,11 +113,56 @@ impl Session {
             domain_blocker,
             block_page: BlockPage::new().with_logger(logger.clone()),
             logger,
+            deadline,
+            egress: None,
+            parent_proxy: Mutex::new(None),
+            tenant: None,
+            close: CloseRecorder::default(),
+            active: ActiveConnections::new(Arc::clone(&metrics)),
+            transfer: TransferRecorder::default(),
+            maintenance_close: maintenance::close_signal(),
+            listener: None,
         }
     }
 
+    /// 연결을 수락한 추가 리스너의 정책 지정 (가로채기/CONNECT 접근 제어를 전역 설정 대신 이 정책으로 판단)
+    pub fn with_listener(mut self, listener: Arc<ListenerPolicy>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+
+    /// 테넌트 태그 지정 (이 세션의 접근 로그와 DB 기록에 태그를 붙이는 로거로 교체)
+    pub fn with_tenant(mut self, tenant: Arc<str>) -> Self {
+        self.logger = Arc::new(self.logger.as_ref().clone().with_tenant(tenant.clone()));
+        self.block_page = BlockPage::new().with_logger(self.logger.clone());
+        self.tenant = Some(tenant);
+        self
+    }
+
+    /// 세션 처리 후 종료 사유 하나를 메트릭스에 집계
     pub async fn handle(mut self) -> Result<()> {
-        info!("[Session:{}] session start, addr: {}", self.session_id(), self.client_addr);
+        self.logger.log_session_start(self.session_id(), self.client_addr.ip());
+        let result = self.serve().await;
+        let reason = self.close.resolve(&result);
+        let parent_proxy = self.parent_proxy.lock().unwrap().take();
+        match &parent_proxy {
+            Some(parent) => debug!("[Session:{}] 세션 종료 사유: {}, 상위 프록시: {}", self.session_id(), reason.name(), parent),
+            None => debug!("[Session:{}] 세션 종료 사유: {}", self.session_id(), reason.name()),
+        }
+        let close_kind = self.close.close_kind().map(|kind| kind.name());
+        self.logger.log_session_end(self.session_id(), self.client_addr.ip(), reason.name(), close_kind, parent_proxy.as_deref());
+        self.metrics.session_closed(reason, 1);
+        if let Some((up, down)) = self.transfer.totals() {
+            self.metrics.record_session_bytes(up, down);
+        }
+        result
+    }
+
+    async fn serve(&mut self) -> Result<()> {
+        match &self.tenant {
+            Some(tenant) => info!("[Session:{}] session start, addr: {}, tenant: {}", self.session_id(), self.client_addr, tenant),
+            None => info!("[Session:{}] session start, addr: {}", self.session_id(), self.client_addr),
+        }
 
         let mut client_stream = match self.client_stream.take() {
             Some(stream) => stream,
@@ -86,47 +180,106 @@ impl Session {
         let n = match self.read_client_request(&mut client_stream, &mut buffer).await {
             Ok(0) => {
                 debug!("[Session:{}] Received an empty request, connection closed.", self.session_id());
-                if let Some(pool) = self.buffer_pool {
+                if let Some(pool) = &self.buffer_pool {
                     pool.return_buffer(buffer);
                 }
                 return Ok(());
             },
             Ok(n) => n,
             Err(e) => {
-                if let Some(pool) = self.buffer_pool {
+                if let Some(pool) = &self.buffer_pool {
                     pool.return_buffer(buffer);
                 }
                 return Err(e);
             }
         };
 
+        // SOCKS4/4a 요청 (socks_enabled인 경우 버전 바이트로 구분)
+        if self.config.socks_enabled && buffer[0] == SOCKS4_VERSION {
+            return self.handle_socks4_request(client_stream, buffer).await;
+        }
+
         // 요청 파싱
         let request_data = &buffer[0..n];
         let request_str = String::from_utf8_lossy(request_data).to_string();
+
+        // 프록시 자신에게 보낸 PAC 요청 (pac.enabled인 경우 업스트림에 전달하지 않고 바로 응답)
+        if let Some(response) = proxy_listener_response(&self.config, &request_str) {
+            info!("[Session:{}] PAC 파일 응답: {}", self.session_id(), self.client_addr);
+            client_stream.write_all(response.as_bytes()).await?;
+            if let Some(pool) = &self.buffer_pool {
+                pool.return_buffer(buffer);
+            }
+            return Ok(());
+        }
         
         // HTTP 요청 파싱
         let http_request = match self.parse_http_request(&request_str) {
             Ok(req) => req,
             Err(e) => {
                 error!("[Session:{}] Failed to parse HTTP request: {}", self.session_id(), e);
-                if let Some(pool) = self.buffer_pool {
+                if let Some(pool) = &self.buffer_pool {
                     pool.return_buffer(buffer);
                 }
                 return Err(e);
             }
         };
         
+        // 출구 선택 헤더 처리 (허용된 값만 사용하고 업스트림으로는 전달하지 않음)
+        if self.config.egress_header.is_some() {
+            let egress = select_egress(&self.config, &request_str, self.session_id()).map(|(_, pool)| pool.clone());
+            self.egress = egress;
+        }
+        let request_str = strip_egress_header(&self.config, &request_str);
+        
         let is_connect = http_request.method == "CONNECT";
         let host = &http_request.host;
         let port = http_request.port;
 
-        // 도메인 차단 확인
-        if self.domain_blocker.is_blocked(host) {
-            return self.handle_blocked_domain(client_stream, host, is_connect, &request_str, buffer).await;
+        // 점검 모드에서는 업스트림에 연결하지 않고 503 점검 응답 (CONNECT도 터널을 열기 전에 같은 응답)
+        if is_maintenance() {
+            return self.reject_maintenance(client_stream, host, is_connect, buffer).await;
+        }
+
+        // 전체 동시 세션/초당 요청 수 한도 확인 (슬롯은 세션이 끝날 때까지 보관)
+        let _overload_slot = match overload_guard() {
+            Some(guard) => match guard.admit() {
+                Ok(slot) => slot,
+                Err(e) => return self.reject_overload(client_stream, host, buffer, &e).await,
+            },
+            None => None,
+        };
+
+        // CONNECT 접근 제어 (클라이언트, 대상 호스트, 대상 포트 규칙)
+        if is_connect && !self.connect_allowed(host, port) {
+            return self.reject_connect_acl(client_stream, host, port, &request_str, buffer).await;
+        }
+
+        // 도메인 차단 확인 (CONNECT에는 302로 응답할 수 없으므로 redirect 동작은 차단으로 처리)
+        match self.domain_blocker.decide_with_category(host, !is_connect) {
+            (AclDecision::Allow, _) => {},
+            (AclDecision::Block, category) => {
+                return self.handle_blocked_domain(client_stream, host, is_connect, &request_str, buffer, category).await;
+            },
+            decision => {
+                return self.handle_acl_action(client_stream, host, is_connect, &request_str, buffer, decision).await;
+            }
         }
+        
+        // 호스트별 동시 연결 한도 확인 (슬롯은 세션이 끝날 때까지 보관)
+        let _host_slot = match host_limiter() {
+            Some(limiter) => match limiter.acquire(host).await {
+                Ok(slot) => slot,
+                Err(e) => return self.reject_overload(client_stream, host, buffer, &e.into()).await,
+            },
+            None => None,
+        };
+        
+        // 목적지 분류별 대역폭 제한 (세션 동안 유지)
+        throttle_client(&client_stream, host, &self.config.category_throttle, self.session_id());
 
         // 연결 카운터 증가
-        self.metrics.connection_opened(is_connect);
+        self.active.opened(is_connect);
 
         // 결과와 상관없이 연결 카운터가 적절하게 관리되도록 처리
         let result = if is_connect {
@@ -141,7 +294,7 @@ impl Session {
             }
         } else {
             // HTTP 요청 처리
-            match self.handle_http_request(client_stream, host, port, &request_str, n, buffer).await {
+            match self.handle_http_request(client_stream, host, port, &request_str, buffer).await {
                 Ok(()) => Ok(()),
                 Err(e) => {
                     // 핸들러 내부에서 이미 connection_closed가 호출되므로 여기서는 호출하지 않음
@@ -212,26 +365,18 @@ impl Session {
         if is_connect {
             // CONNECT 메서드 파싱
             if let Some(host_port) = parts.get(1) {
-                if let Some(idx) = host_port.rfind(':') {
-                    let host = &host_port[0..idx];
-                    let port = host_port[idx+1..].parse::<u16>()
-                        .map_err(|_| {
-                            error!("[Session:{}] Invalid port in CONNECT request", self.session_id());
-                            ProxyError::Http("Invalid port in CONNECT request".to_string())
-                        })?;
-                    
-                    Ok(HttpRequest {
-                        method,
-                        host: host.to_string(),
-                        port,
-                    })
-                } else {
-                    Ok(HttpRequest {
-                        method,
-                        host: host_port.to_string(),
-                        port: 443,
-                    })
-                }
+                // IPv6 리터럴([2001:db8::1]:443)을 포함한 대상 파싱
+                let (host, port) = parse_authority(host_port, 443)
+                    .ok_or_else(|| {
+                        error!("[Session:{}] Invalid CONNECT target: {}", self.session_id(), host_port);
+                        ProxyError::Http("Invalid port in CONNECT request".to_string())
+                    })?;
+                
+                Ok(HttpRequest {
+                    method,
+                    host: canonical_host(&host),
+                    port,
+                })
             } else {
                 error!("[Session:{}] Missing host in CONNECT request", self.session_id());
                 Err(ProxyError::Http("Missing host in CONNECT request".to_string()))
@@ -246,14 +391,10 @@ impl Session {
                 .find(|line| line.to_lowercase().starts_with("host:")) {
                 let host_value = host_line.trim_start_matches("Host:").trim_start_matches("host:").trim();
 
-                // 포트 확인
-                if let Some(idx) = host_value.rfind(':') {
-                    host = host_value[0..idx].to_string();
-                    if let Ok(p) = host_value[idx+1..].parse::<u16>() {
-                        port = p;
-                    }
-                } else {
-                    host = host_value.to_string();
+                // 포트 확인 (IPv6 리터럴 포함)
+                if let Some((h, p)) = parse_authority(host_value, 80) {
+                    host = h;
+                    port = p;
                 }
             } else if let Some(url_part) = parts.get(1) {
                 // URL에서 호스트 추출
@@ -261,14 +402,10 @@ impl Session {
                     let without_scheme = url_part.trim_start_matches("http://");
                     if let Some(host_part) = without_scheme.split('/').next() {
                         if !host_part.is_empty() {
-                            // 포트 확인
-                            if let Some(idx) = host_part.rfind(':') {
-                                host = host_part[0..idx].to_string();
-                                if let Ok(p) = host_part[idx+1..].parse::<u16>() {
-                                    port = p;
-                                }
-                            } else {
-                                host = host_part.to_string();
+                            // 포트 확인 (IPv6 리터럴 포함)
+                            if let Some((h, p)) = parse_authority(host_part, 80) {
+                                host = h;
+                                port = p;
                             }
                         } else {
                             error!("[Session:{}] Invalid host in URL", self.session_id());
@@ -288,21 +425,115 @@ impl Session {
             
             Ok(HttpRequest {
                 method,
-                host,
+                host: canonical_host(&host),
                 port,
             })
         }
     }
     
+    /// 과부하(전체/호스트별 연결 한도, 초당 요청 수 한도)로 거부할 때 503과 Retry-After 응답
+    ///
+    /// CONNECT 요청도 터널을 열기 전이므로 같은 상태 줄로 실패를 알립니다.
+    async fn reject_overload(&self, mut client_stream: TcpStream, host: &str, buffer: BytesMut, error: &OverloadRejected) -> Result<()> {
+        self.close.record(SessionCloseReason::Blocked);
+        let response = ErrorResponse::new(ErrorReason::Overload, self.session_id(), host)
+            .with_detail(error.to_string())
+            .with_retry_after(self.config.overload.retry_after_seconds)
+            .render(negotiate(&buffer));
+        if let Some(pool) = &self.buffer_pool {
+            pool.return_buffer(buffer);
+        }
+        client_stream.write_all(response.as_bytes()).await?;
+        let _ = client_stream.shutdown().await;
+        info!("[Session:{}] {}", self.session_id(), error);
+        Ok(())
+    }
+    
+    /// 점검 모드에서 새 요청을 503 점검 응답으로 거절
+    async fn reject_maintenance(&self, mut client_stream: TcpStream, host: &str, is_connect: bool, buffer: BytesMut) -> Result<()> {
+        self.close.record(SessionCloseReason::Blocked);
+        let response = maintenance::reject_response(self.session_id(), host, &buffer);
+        if let Some(pool) = &self.buffer_pool {
+            pool.return_buffer(buffer);
+        }
+        client_stream.write_all(response.as_bytes()).await?;
+        let _ = client_stream.shutdown().await;
+        info!("[Session:{}] 점검 모드로 요청 거절: {} {}", self.session_id(), if is_connect { "CONNECT" } else { "HTTP" }, host);
+        Ok(())
+    }
+    
+    /// CONNECT 접근 제어 규칙 판정 (일치한 규칙 번호와 함께 기록, 규칙이 없으면 허용)
+    fn connect_allowed(&self, host: &str, port: u16) -> bool {
+        // 추가 리스너가 정책을 지정했으면 전역 규칙 대신 사용
+        let listener_acl = self.listener.as_ref().and_then(|listener| listener.connect_acl());
+        let Some(acl) = listener_acl.or(connect_acl()) else { return true };
+        let verdict = acl.evaluate(self.client_addr.ip(), host, port);
+        match &self.listener {
+            Some(listener) if listener_acl.is_some() => info!("[Session:{}] CONNECT 접근 제어 (리스너 {}): {} -> {}: {}",
+                                                              self.session_id(), listener.name, self.client_addr.ip(), format_authority(host, port), verdict),
+            _ => info!("[Session:{}] CONNECT 접근 제어: {} -> {}: {}", self.session_id(), self.client_addr.ip(), format_authority(host, port), verdict),
+        }
+        verdict.is_allowed()
+    }
+    
+    /// 연결을 수락한 리스너 이름 (기본 리스너면 "default")
+    fn listener_name(&self) -> &str {
+        self.listener.as_ref().map(|listener| listener.name.as_str()).unwrap_or("default")
+    }
+    
+    /// CONNECT 접근 제어 규칙으로 거부 (403)
+    async fn reject_connect_acl(&self, mut client_stream: TcpStream, host: &str, port: u16, request_str: &str, buffer: BytesMut) -> Result<()> {
+        self.close.record(SessionCloseReason::Blocked);
+        self.log_blocked_request(host, request_str, &self.client_addr.ip().to_string(), true, None).await;
+        let response = ErrorResponse::new(ErrorReason::Blocked, self.session_id(), host)
+            .with_detail(format!("CONNECT to {} denied by access policy", format_authority(host, port)))
+            .render(negotiate(&buffer));
+        if let Some(pool) = &self.buffer_pool {
+            pool.return_buffer(buffer);
+        }
+        client_stream.write_all(response.as_bytes()).await?;
+        let _ = client_stream.shutdown().await;
+        Ok(())
+    }
+    
+    /// 차단 규칙의 redirect/tarpit 동작 처리
+    async fn handle_acl_action(&self, mut client_stream: TcpStream, host: &str, is_connect: bool, request_str: &str, buffer: BytesMut,
+                               (decision, category): (AclDecision, Option<AclRuleKind>)) -> Result<()> {
+        self.close.record(SessionCloseReason::Blocked);
+        let client_ip = self.client_addr.ip().to_string();
+        self.log_blocked_request(host, request_str, &client_ip, is_connect, category).await;
+        
+        if let Some(pool) = &self.buffer_pool {
+            pool.return_buffer(buffer);
+        }
+        
+        match decision {
+            AclDecision::Redirect(location) => {
+                send_redirect(&mut client_stream, &location).await?;
+                info!("[Session:{}] ACL redirect 응답 전송 완료: {} -> {}", self.session_id(), host, location);
+            },
+            AclDecision::Tarpit { interval, max_hold } => {
+                match tarpit(&mut client_stream, interval, max_hold).await {
+                    Ok(held) => info!("[Session:{}] ACL tarpit 종료: {} ({}ms 유지)", self.session_id(), host, held.as_millis()),
+                    Err(e) => debug!("[Session:{}] ACL tarpit 중 클라이언트 연결 종료: {} ({})", self.session_id(), host, e),
+                }
+            },
+            AclDecision::Allow | AclDecision::Block => {},
+        }
+        Ok(())
+    }
+    
     /// 차단된 도메인 처리
-    async fn handle_blocked_domain(&self, mut client_stream: TcpStream, host: &str, is_connect: bool, request_str: &str, buffer: BytesMut) -> Result<()> {
+    async fn handle_blocked_domain(&self, mut client_stream: TcpStream, host: &str, is_connect: bool, request_str: &str, buffer: BytesMut,
+                                   category: Option<AclRuleKind>) -> Result<()> {
+        self.close.record(SessionCloseReason::Blocked);
         info!("[Session:{}] 차단된 도메인 감지: {}", self.session_id(), host);
         
         // 클라이언트 IP 주소 가져오기
         let client_ip = self.client_addr.ip().to_string();
         
-        // 요청 로깅 (차단됨으로 표시)
-        self.log_blocked_request(host, request_str, &client_ip, is_connect).await;
+        // 요청 로깅 (차단됨으로 표시, 차단 페이지는 같은 요청을 다시 기록하지 않도록 클라이언트 주소 없이 호출)
+        self.log_blocked_request(host, request_str, &client_ip, is_connect, category).await;
         
         // 버퍼 반환
         if let Some(pool) = &self.buffer_pool {
@@ -317,7 +548,7 @@ impl Session {
                 host, 
                 &self.session_id(), 
                 Some(request_str), 
-                Some(&client_ip)
+                None
             ).await?;
         } else {
             // HTTP 요청 차단 처리
@@ -326,34 +557,50 @@ impl Session {
                 host, 
                 &self.session_id(), 
                 Some(request_str), 
-                Some(&client_ip)
+                None
             ).await?;
         }
         
         info!("[Session:{}] 차단 페이지 전송 완료: {}", self.session_id(), host);
         
         // 연결 카운터 감소
-        self.metrics.connection_closed(is_connect);
+        self.active.closed(is_connect);
         
         Ok(())
     }
     
-    /// 차단된 요청 로깅
-    async fn log_blocked_request(&self, host: &str, request_str: &str, client_ip: &str, is_tls: bool) {
+    /// 차단된 요청 로깅 (`category`는 일치한 차단 규칙 유형, 규칙 밖의 차단이면 None)
+    async fn log_blocked_request(&self, host: &str, request_str: &str, client_ip: &str, is_tls: bool, category: Option<AclRuleKind>) {
+        if let Some(tenant) = &self.tenant {
+            self.metrics.tenant_blocked(tenant);
+        }
         // Logger 인스턴스 사용 (이제 직접 사용 가능)
-        if let Err(e) = self.logger.log_rejected_request(request_str, host, client_ip, &self.session_id(), is_tls).await {
+        if let Err(e) = self.logger.log_rejected_request(request_str, host, client_ip, &self.session_id(), is_tls, category.map(|kind| kind.name())).await {
             error!("[Session:{}] 차단된 요청 로깅 실패: {}", self.session_id(), e);
         }
     }
     
     /// HTTP 요청 처리
-    async fn handle_http_request(&self, mut client_stream: TcpStream, host: &str, port: u16, request_str: &str, n: usize, buffer: BytesMut) -> Result<()> {
+    async fn handle_http_request(&self, mut client_stream: TcpStream, host: &str, port: u16, request_str: &str, buffer: BytesMut) -> Result<()> {
         // 세션 ID는 더 이상 지역 변수로 저장하지 않고 항상 self.session_id()를 직접 호출
         info!("[Session:{}] Processing HTTP request for {}", self.session_id(), host);
         
-        // 서버에 연결
-        let server_addr = format!("{}:{}", host, port);
-        let server_stream = match TcpStream::connect(&server_addr).await {
+        // 서버에 연결 (업스트림 연결 풀이 활성화되어 있으면 유휴 연결 재사용)
+        let server_addr = dial_address(host, port);
+        let pool_key = PoolKey::plain(host, port);
+        // 출구가 지정된(상위 프록시를 고른) 요청은 기본 출구로 맺은 유휴 연결을 재사용하지 않음
+        let egress = self.upstream_egress();
+        let shared_pool = upstream_pool().filter(|_| egress.is_none());
+        let pooled_stream = shared_pool.as_ref().and_then(|pool| pool.checkout(&pool_key));
+        if pooled_stream.is_some() {
+            debug!("[Session:{}] 업스트림 유휴 연결 재사용: {}", self.session_id(), server_addr);
+        }
+        let mut timing = UpstreamTiming::default();
+        let connect_result = match pooled_stream {
+            Some(stream) => Ok(stream),
+            None => connect_upstream(host, port, &self.config, egress.as_ref(), &mut timing).await,
+        };
+        let server_stream = match connect_result {
             Ok(stream) => {
                 // 실제 연결된 IP 주소 확인 및 로깅
                 let target_ip = if let Ok(peer_addr) = stream.peer_addr() {
@@ -370,27 +617,22 @@ impl Session {
             },
             Err(e) => {
                 error!("[Session:{}] Failed to connect to target server {}: {}", self.session_id(), server_addr, e);
+                self.logger.log_upstream_failure(self.session_id(), &self.client_addr.ip().to_string(), host, &e.to_string());
                 if let Some(pool) = &self.buffer_pool {
                     pool.return_buffer(buffer);
                 }
                 // 서버 연결 실패 시 연결 카운터 감소
-                self.metrics.connection_closed(false);
+                self.active.closed(false);
+                // 클라이언트에 실패 사유(502/503/504) 응답
+                let response = ErrorResponse::new(ErrorReason::from_upstream_error(&e), self.session_id(), host)
+                    .with_detail(e.to_string())
+                    .render(negotiate(request_str.as_bytes()));
+                let _ = client_stream.write_all(response.as_bytes()).await;
+                let _ = client_stream.shutdown().await;
                 return Err(e.into());
             }
         };
         
-        // 서버에 요청 전달
-        let buffer_slice = &buffer[0..n];
-        if let Err(e) = server_stream.try_write(buffer_slice) {
-            error!("[Session:{}] Failed to forward request to server: {}", self.session_id(), e);
-            if let Some(pool) = &self.buffer_pool {
-                pool.return_buffer(buffer);
-            }
-            // 요청 전달 실패 시 연결 카운터 감소
-            self.metrics.connection_closed(false);
-            return Err(e.into());
-        }
-        
         // 버퍼 반환
         if let Some(pool) = &self.buffer_pool {
             pool.return_buffer(buffer);
@@ -407,7 +649,7 @@ impl Session {
         let already_logged = true;
         
         // proxy_http_streams 호출 시 직접 self.session_id() 호출
-        match proxy_http_streams(
+        match self.run_until_deadline(proxy_http_streams(
             client_stream, 
             server_stream, 
             Arc::clone(&self.metrics), 
@@ -416,17 +658,25 @@ impl Session {
             Some(Arc::clone(&self.config)), 
             Some(initial_request),
             already_logged, // 이미 로깅되었음을 표시
-            Some(self.logger.clone()) // Logger 인스턴스 전달
-        ).await {
-            Ok(_) => {
+            Some(self.logger.clone()), // Logger 인스턴스 전달
+            host,
+            self.config.upstream_timing_enabled.then_some(timing),
+            Some(&self.close),
+            Some(&self.transfer),
+        )).await {
+            Ok(reusable) => {
+                // 응답이 온전히 끝난 keep-alive 연결만 풀에 반환 (터널과 프로토콜 전환 연결은 반환되지 않음)
+                if let (Some(connection), Some(pool)) = (reusable, shared_pool) {
+                    pool.release(pool_key, connection);
+                }
                 // 연결 종료 시 활성 연결 카운터 감소
-                self.metrics.connection_closed(false);
+                self.active.closed(false);
                 info!("[Session:{}] Completed HTTP proxy for {}", self.session_id(), host);
                 Ok(())
             },
             Err(e) => {
                 // 프록시 에러 시 연결 카운터 감소
-                self.metrics.connection_closed(false);
+                self.active.closed(false);
                 error!("[Session:{}] Error during HTTP proxy: {}", self.session_id(), e);
                 Err(e)
             }
@@ -435,15 +685,31 @@ impl Session {
     
     /// HTTPS 요청 처리
     async fn handle_https_request(&self, mut client_stream: TcpStream, host: &str, port: u16, buffer: BytesMut) -> Result<()> {
-        // 도메인 차단 여부 확인
-        if self.domain_blocker.is_blocked(host) {
-            info!("[Session:{}] 차단된 도메인 감지: {}", self.session_id(), host);
-            return self.handle_blocked_domain(client_stream, host, true, "", buffer).await;
-        }
-        
         // 서버 주소 구성
         let _server_addr = format!("{}:{}", host, port);
         
+        // 200 응답 전에 CONNECT 헤더 뒤에 이어 붙어 온 데이터 처리
+        let pipelined = pipelined_offset(&buffer);
+        if let Some(offset) = pipelined {
+            let payload = classify(&buffer[offset..]);
+            let action = decide(self.config.connect_pipelining, payload);
+            let len = buffer.len() - offset;
+            if is_ambiguous(payload, action) {
+                warn!("[Session:{}] [CONNECT-PIPELINE] 판단할 수 없는 데이터 {}바이트 ({}, 정책 {}): {}:{}",
+                      self.session_id(), len, payload.name(), self.config.connect_pipelining.name(), host, port);
+                self.metrics.connect_pipeline_ambiguous();
+            }
+            if action == PipelineAction::Reject {
+                info!("[Session:{}] [CONNECT-PIPELINE] CONNECT 뒤에 이어 붙은 데이터 {}바이트 ({}) 거부: {}:{}",
+                      self.session_id(), len, payload.name(), host, port);
+                self.metrics.connect_pipeline_rejected();
+                return self.reject_pipelined(client_stream, host, buffer).await;
+            }
+            info!("[Session:{}] [CONNECT-PIPELINE] CONNECT 뒤에 이어 붙은 데이터 {}바이트 ({})를 터널의 첫 데이터로 처리: {}:{}",
+                  self.session_id(), len, payload.name(), host, port);
+            self.metrics.connect_pipeline_forwarded();
+        }
+        
         // CONNECT 응답 전송
         let response = "HTTP/1.1 200 Connection Established\r\nConnection: keep-alive\r\n\r\n";
         if let Err(e) = client_stream.write_all(response.as_bytes()).await {
@@ -452,23 +718,200 @@ impl Session {
                 pool.return_buffer(buffer);
             }
             // 연결 실패 시 연결 카운터 감소
-            self.metrics.connection_closed(true);
+            self.active.closed(true);
             return Err(e.into());
         }
         
-        // TLS 연결 시도
-        info!("[Session:{}] TLS 연결 시도: {}", self.session_id(), host);
-        let real_tls_stream = match connect_tls(host, self.config.as_ref()).await {
-            Ok(stream) => {
-                info!("[Session:{}] TLS 연결 성공", self.session_id());
-                stream
+        // 이어 붙어 온 데이터는 연결 앞에 되돌려 놓아 터널의 첫 데이터로 읽음 (이어 붙은 ClientHello도 가로챔)
+        let client_stream = match pipelined {
+            Some(offset) => PrefixedStream::new(buffer[offset..].to_vec(), client_stream),
+            None => PrefixedStream::plain(client_stream),
+        };
+        
+        // 터널 안이 TLS가 아니면(SMTP 등 평문 TCP) 가로채기 없이 그대로 전달
+        let Some(protocol) = self.detect_tunnel_protocol(&client_stream).await else {
+            if let Some(pool) = &self.buffer_pool {
+                pool.return_buffer(buffer);
+            }
+            self.active.closed(true);
+            return Ok(());
+        };
+        info!("[Session:{}] CONNECT 터널 프로토콜: {} ({}:{})", self.session_id(), protocol.name(), host, port);
+        match protocol {
+            TunnelProtocol::Tls => self.intercept_tls(client_stream, host, port, buffer).await,
+            TunnelProtocol::Raw => self.tunnel_opaque(client_stream, host, port, buffer).await,
+        }
+    }
+    
+    /// CONNECT 뒤에 이어 붙은 데이터를 거부할 때 400 응답 후 연결 종료
+    async fn reject_pipelined(&self, mut client_stream: TcpStream, host: &str, buffer: BytesMut) -> Result<()> {
+        self.close.record(SessionCloseReason::Blocked);
+        let response = ErrorResponse::new(ErrorReason::BadRequest, self.session_id(), host)
+            .with_detail("data received before the CONNECT tunnel was established")
+            .render(negotiate(&buffer));
+        if let Some(pool) = &self.buffer_pool {
+            pool.return_buffer(buffer);
+        }
+        self.active.closed(true);
+        
+        client_stream.write_all(response.as_bytes()).await?;
+        let _ = client_stream.shutdown().await;
+        Ok(())
+    }
+    
+    /// CONNECT 터널 안의 프로토콜 판별 (클라이언트 첫 바이트가 TLS 핸드셰이크 레코드인지)
+    ///
+    /// `tunnel_detect_timeout_ms` 안에 데이터가 없으면 `tunnel_detect_timeout_action`을 따르며,
+    /// 연결을 끝내야 하면 None을 돌려줍니다 (검사 없는 평문 터널은 passthrough로 설정한 경우에만).
+    async fn detect_tunnel_protocol(&self, client_stream: &PrefixedStream) -> Option<TunnelProtocol> {
+        let peek_first_byte = |timeout: Duration| async move {
+            let mut first_byte = [0u8; 1];
+            match tokio::time::timeout(timeout, client_stream.peek(&mut first_byte)).await {
+                Ok(Ok(1)) => Some(Some(first_byte[0])),
+                Ok(_) => Some(None),
+                Err(_) => None,
+            }
+        };
+        
+        let detect_timeout = Duration::from_millis(self.config.tunnel_detect_timeout_ms);
+        let first_byte = match peek_first_byte(detect_timeout).await {
+            Some(first_byte) => first_byte,
+            None => {
+                let action = self.config.tunnel_detect_timeout_action;
+                info!("[Session:{}] CONNECT 터널 첫 바이트가 {}ms 안에 오지 않음, tunnel_detect_timeout_action {}",
+                      self.session_id(), detect_timeout.as_millis(), action.name());
+                match action {
+                    TunnelDetectTimeoutAction::Passthrough => return Some(TunnelProtocol::Raw),
+                    TunnelDetectTimeoutAction::Deny => {
+                        self.close.record(SessionCloseReason::Blocked);
+                        return None;
+                    },
+                    TunnelDetectTimeoutAction::Wait => match peek_first_byte(Duration::from_millis(self.config.timeout_ms as u64)).await {
+                        Some(first_byte) => first_byte,
+                        None => {
+                            debug!("[Session:{}] CONNECT 터널에서 데이터 없이 연결 종료", self.session_id());
+                            self.close.record(SessionCloseReason::IdleTimeout);
+                            return None;
+                        },
+                    },
+                }
             },
-            Err(e) => {
-                error!("[Session:{}] TLS 연결 실패: {}", self.session_id(), e);
-                return Err(e);
+        };
+        match first_byte {
+            Some(TLS_HANDSHAKE_RECORD) => Some(TunnelProtocol::Tls),
+            _ => Some(TunnelProtocol::Raw),
+        }
+    }
+    
+    /// 터널이 열린 클라이언트 연결의 TLS 가로채기 (업스트림 TLS 연결, 가짜 인증서로 클라이언트 TLS 수락)
+    async fn intercept_tls(&self, client_stream: PrefixedStream, host: &str, port: u16, buffer: BytesMut) -> Result<()> {
+        let interception = self.listener.as_ref().map(|listener| listener.interception).unwrap_or_default();
+        if interception == InterceptionPolicy::Never {
+            info!("[Session:{}] [TLS-EXEMPT] 리스너 {}는 가로채지 않음, 복호화 없이 전달: {}:{}",
+                  self.session_id(), self.listener_name(), host, port);
+            return self.tunnel_opaque(client_stream, host, port, buffer).await;
+        }
+        
+        let client_hello = match self.peek_client_hello_sni(&client_stream, host, port).await {
+            Ok(client_hello) => client_hello,
+            Err(ClientHelloIncompleteAction::Opaque) => return self.tunnel_opaque(client_stream, host, port, buffer).await,
+            Err(_) => {
+                if let Some(pool) = &self.buffer_pool {
+                    pool.return_buffer(buffer);
+                }
+                return Ok(());
+            },
+        };
+        
+        // 가로채기 제외 호스트(CONNECT 대상 또는 SNI)는 복호화 없이 전달
+        let sni = match &client_hello {
+            ClientHelloSni::Sni(sni) => Some(sni.as_str()),
+            _ => None,
+        };
+        // (리스너가 always면 제외 목록을 보지 않고, 리스너의 bypass_hosts도 제외 대상)
+        let exempt = |name: &&str| {
+            interception == InterceptionPolicy::BypassList
+                && (is_tls_exempt(name) || self.listener.as_ref().is_some_and(|listener| listener.bypasses(name)))
+        };
+        if let Some(exempt) = [Some(host), sni].into_iter().flatten().find(exempt) {
+            info!("[Session:{}] [TLS-EXEMPT] 가로채기 제외 호스트, 복호화 없이 전달: {} ({}:{})",
+                  self.session_id(), exempt, host, port);
+            return self.tunnel_opaque(client_stream, host, port, buffer).await;
+        }
+        
+        // SNI 없는 클라이언트는 CONNECT 대상 호스트로 가로채거나 그대로 전달
+        match client_hello {
+            ClientHelloSni::NoSni => {
+                self.metrics.tls_no_sni();
+                match self.config.no_sni_action {
+                    NoSniAction::ConnectHost => {
+                        info!("[Session:{}] [NO-SNI] SNI 없는 ClientHello, CONNECT 대상 호스트로 가로채기: {}:{}",
+                              self.session_id(), host, port);
+                    },
+                    NoSniAction::Passthrough => {
+                        info!("[Session:{}] [NO-SNI] SNI 없는 ClientHello, 복호화 없이 전달: {}:{}",
+                              self.session_id(), host, port);
+                        return self.tunnel_opaque(client_stream, host, port, buffer).await;
+                    },
+                }
+            },
+            ClientHelloSni::Sni(sni) => debug!("[Session:{}] ClientHello SNI: {}", self.session_id(), sni),
+            _ => debug!("[Session:{}] ClientHello SNI 확인 불가, CONNECT 대상 호스트 사용: {}", self.session_id(), host),
+        }
+        
+        // 지문 따라하기가 켜져 있으면 클라이언트 ClientHello 지문을 업스트림 핸드셰이크에 사용
+        let fingerprint = if self.config.client_hello_mirroring {
+            let fingerprint = peek_client_hello_fingerprint(&client_stream, self.config.client_hello_peek.max_bytes).await;
+            match &fingerprint {
+                Some(fingerprint) => debug!("[Session:{}] ClientHello JA3: {}", self.session_id(), fingerprint.ja3()),
+                None => debug!("[Session:{}] ClientHello 지문 확인 불가, 기본 핸드셰이크 사용: {}", self.session_id(), host),
             }
+            fingerprint
+        } else {
+            None
         };
         
+        // 업스트림 연결 풀의 HTTP/1.1 유휴 연결이나 고정 업스트림에 미리 연결해 둔 TLS 연결 사용
+        // (출구를 선택한 요청과 지문을 따라 하는 연결은 제외)
+        let egress = self.upstream_egress();
+        let sni = self.config.sni_for(host);
+        let shared_pool = upstream_pool().filter(|_| egress.is_none() && fingerprint.is_none());
+        let pooled_stream = shared_pool.as_ref().and_then(|pool| pool.checkout_tls(&PoolKey::intercepted(host, port, sni)));
+        let warm_stream = warm_pool()
+            .filter(|_| pooled_stream.is_none() && egress.is_none() && fingerprint.is_none())
+            .and_then(|pool| pool.take(host, port));
+        
+        let mut timing = UpstreamTiming::default();
+        let real_tls_stream = if let Some(stream) = pooled_stream {
+            debug!("[Session:{}] 업스트림 유휴 TLS 연결 재사용: {}:{}", self.session_id(), host, port);
+            stream
+        } else if let Some(stream) = warm_stream {
+            info!("[Session:{}] 미리 연결된 TLS 연결 사용: {}:{}", self.session_id(), host, port);
+            stream
+        } else {
+            // TLS 연결 시도
+            info!("[Session:{}] TLS 연결 시도: {}", self.session_id(), host);
+            match connect_tls_via(&format_authority(host, port), self.config.as_ref(), egress.as_ref(), &mut timing, fingerprint.as_ref()).await {
+                Ok(stream) => {
+                    info!("[Session:{}] TLS 연결 성공", self.session_id());
+                    stream
+                },
+                Err(e) => {
+                    error!("[Session:{}] TLS 연결 실패: {}", self.session_id(), e);
+                    self.logger.log_upstream_failure(self.session_id(), &self.client_addr.ip().to_string(), host, &e.to_string());
+                    return Err(e);
+                }
+            }
+        };
+        
+        // warn 등급 호스트는 인증서 검증 실패를 허용하되 세션에 표시
+        let cert_warning = upstream_cert_warning(&real_tls_stream, &format_authority(host, port), &self.config);
+        // 풀에 돌려줄 때는 실제로 협상된 ALPN으로 구분
+        let pool_key = PoolKey::negotiated(host, port, sni, &real_tls_stream);
+        if let Some(reason) = &cert_warning {
+            info!("[Session:{}] [CERT-WARN] 검증되지 않은 업스트림 인증서로 진행: {} ({})", self.session_id(), host, reason);
+        }
+        
         // 클라이언트 TLS 연결 수락
         info!("[Session:{}] 클라이언트 TLS 연결 수락 중", self.session_id());
         
@@ -492,7 +935,7 @@ impl Session {
                 
                 // 3. 중간에서 데이터 가로채기 - 요청 시작 시간 전달
                 let request_start_time = Instant::now();
-                match proxy_tls_streams(
+                match self.run_until_deadline(proxy_tls_streams(
                     tls_stream, 
                     real_tls_stream, 
                     Arc::clone(&self.metrics), 
@@ -500,17 +943,29 @@ impl Session {
                     host, 
                     request_start_time,
                     Some(self.logger.clone()), // Logger 인스턴스 전달
-                    Some(self.config.clone()) // Config 인스턴스 전달
-                ).await {
-                    Ok(_) => {
+                    Some(self.config.clone()), // Config 인스턴스 전달
+                    self.buffer_pool.clone(), // 세션 펌프 버퍼 풀
+                    self.config.upstream_timing_enabled.then_some(timing),
+                    cert_warning.is_some(),
+                    Some(&self.transfer),
+                )).await {
+                    Ok((close, reusable)) => {
+                        // 클라이언트가 요청 사이에서 닫은 keep-alive 업스트림 연결만 풀에 반환
+                        if let (Some(connection), Some(pool)) = (reusable, shared_pool) {
+                            pool.release(pool_key, connection);
+                        }
                         // 연결 종료 시 활성 연결 카운터 감소
-                        self.metrics.connection_closed(true);
-                        info!("[Session:{}] Completed TLS proxy for {}", self.session_id(), host);
+                        self.active.closed(true);
+                        if close != CloseKind::Fin {
+                            self.metrics.reset_propagated();
+                        }
+                        self.close.record_close_kind(close);
+                        info!("[Session:{}] Completed TLS proxy for {} (close: {})", self.session_id(), host, close.name());
                         Ok(())
                     },
                     Err(e) => {
                         // 프록시 스트림에서 에러 발생 시에도 연결 카운터 감소
-                        self.metrics.connection_closed(true);
+                        self.active.closed(true);
                         error!("[Session:{}] Error in TLS proxy: {}", self.session_id(), e);
                         Err(e)
                     }
@@ -522,12 +977,269 @@ impl Session {
                     error!("[Session:{}] 서버 인증서 검증 실패: {}", self.session_id(), e);
                 }
                 // 에러 발생 시 연결 카운터 감소
-                self.metrics.connection_closed(true);
+                self.active.closed(true);
                 Err(e)
             }
         }
     }
     
+    /// 클라이언트의 ClientHello를 소비하지 않고 읽어 SNI 확인 (끝까지 받지 못하면 client_hello_peek.on_incomplete 적용)
+    ///
+    /// 가로채기를 계속할 결과를 돌려주며, 복호화 없이 전달하거나 연결을 끊어야 하면 그 방식을 Err로 돌려줍니다.
+    async fn peek_client_hello_sni(&self, client_stream: &PrefixedStream, host: &str, port: u16) -> std::result::Result<ClientHelloSni, ClientHelloIncompleteAction> {
+        let settings = &self.config.client_hello_peek;
+        let timeout = Duration::from_millis(settings.timeout_ms.unwrap_or(self.config.timeout_ms as u64));
+        let reason = match peek_client_hello(client_stream, timeout, settings.max_bytes).await {
+            ClientHelloPeek::Parsed(client_hello) => return Ok(client_hello),
+            ClientHelloPeek::TimedOut(received) => format!("{}ms 안에 받지 못함 ({}바이트 수신)", timeout.as_millis(), received),
+            ClientHelloPeek::TooLarge(needed) => format!("크기 한도 {}바이트 초과 (최소 {}바이트)", settings.max_bytes, needed),
+        };
+        
+        self.metrics.tls_client_hello_incomplete();
+        let action = settings.on_incomplete;
+        warn!("[Session:{}] ClientHello {}, on_incomplete {}: {}:{}", self.session_id(), reason, action.name(), host, port);
+        match action {
+            ClientHelloIncompleteAction::Intercept => Ok(ClientHelloSni::Unknown),
+            action => Err(action),
+        }
+    }
+    
+    /// 복호화 없이 클라이언트와 업스트림 사이에서 바이트를 그대로 전달
+    ///
+    /// 클라이언트 연결 앞에 되돌려 놓은 데이터(CONNECT 뒤에 이어 붙어 온 데이터)가 남아 있으면 먼저 업스트림에 보냅니다.
+    async fn tunnel_opaque(&self, client_stream: PrefixedStream, host: &str, port: u16, buffer: BytesMut) -> Result<()> {
+        let (pipelined, mut client_stream) = client_stream.into_parts();
+        if let Some(pool) = &self.buffer_pool {
+            pool.return_buffer(buffer);
+        }
+        
+        // 터널은 요청 경계가 없으므로 업스트림 연결 풀을 쓰지 않고 항상 새로 연결 (끝나면 닫음)
+        let egress = self.upstream_egress();
+        let mut server_stream = match connect_upstream(host, port, &self.config, egress.as_ref(), &mut UpstreamTiming::default()).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("[Session:{}] Failed to connect to target server {}:{}: {}", self.session_id(), host, port, e);
+                self.logger.log_upstream_failure(self.session_id(), &self.client_addr.ip().to_string(), host, &e.to_string());
+                return Err(e.into());
+            }
+        };
+        
+        // 한쪽이 RST로 끊으면 다른 쪽에도 RST로 전달
+        self.transfer.relay_started();
+        let result = self.run_until_deadline(async {
+            server_stream.write_all(&pipelined).await?;
+            Ok(relay_tcp(&mut client_stream, &mut server_stream, &self.config.write_coalescing, &self.config.flow_control, self.config.read_chunk_size).await?)
+        }).await;
+        self.active.closed(true);
+        
+        let (bytes_in, bytes_out, close) = result?;
+        let bytes_in = bytes_in + pipelined.len() as u64;
+        self.metrics.add_tls_bytes_in(bytes_in);
+        self.metrics.add_tls_bytes_out(bytes_out);
+        self.transfer.add_up(bytes_in);
+        self.transfer.add_down(bytes_out);
+        if close != CloseKind::Fin {
+            self.metrics.reset_propagated();
+        }
+        self.close.record_close_kind(close);
+        info!("[Session:{}] Completed opaque tunnel for {}:{} (in {} bytes, out {} bytes, close: {})",
+              self.session_id(), host, port, bytes_in, bytes_out, close.name());
+        Ok(())
+    }
+    
+    /// SOCKS4/4a 요청 처리
+    ///
+    /// CONNECT만 지원하며, 허용 응답 후 클라이언트가 TLS를 시작하면 HTTPS와 같이 가로채고
+    /// 그 외에는 평문 HTTP 요청으로 처리합니다.
+    async fn handle_socks4_request(&self, mut client_stream: TcpStream, mut buffer: BytesMut) -> Result<()> {
+        let request = match self.read_socks4_request(&mut client_stream, &mut buffer).await {
+            Ok(request) => request,
+            Err(e) => {
+                error!("[Session:{}] SOCKS4 요청 처리 실패: {}", self.session_id(), e);
+                let _ = client_stream.write_all(&socks4_reply(false)).await;
+                if let Some(pool) = &self.buffer_pool {
+                    pool.return_buffer(buffer);
+                }
+                return Err(e);
+            }
+        };
+        
+        let host = canonical_host(&request.host);
+        let host = host.as_str();
+        let port = request.port;
+        let request_str = format!("CONNECT {} SOCKS4\r\n\r\n", format_authority(host, port));
+        info!("[Session:{}] SOCKS4 CONNECT 요청: {} (user: {})", self.session_id(), format_authority(host, port), request.user_id);
+        
+        // 점검 모드에서는 거부 응답만 보냄
+        if is_maintenance() {
+            self.close.record(SessionCloseReason::Blocked);
+            maintenance::count_rejected();
+            info!("[Session:{}] 점검 모드로 요청 거절: SOCKS4 {}", self.session_id(), host);
+            if let Some(pool) = &self.buffer_pool {
+                pool.return_buffer(buffer);
+            }
+            client_stream.write_all(&socks4_reply(false)).await?;
+            return Ok(());
+        }
+        
+        // CONNECT 접근 제어 (거부 시 SOCKS4 거부 응답)
+        if !self.connect_allowed(host, port) {
+            self.close.record(SessionCloseReason::Blocked);
+            self.log_blocked_request(host, &request_str, &self.client_addr.ip().to_string(), false, None).await;
+            if let Some(pool) = &self.buffer_pool {
+                pool.return_buffer(buffer);
+            }
+            client_stream.write_all(&socks4_reply(false)).await?;
+            return Ok(());
+        }
+        
+        // 도메인 차단 확인 (SOCKS4는 차단 페이지 대신 거부 응답, tarpit은 최대 유지 시간 동안 응답을 미룬 뒤 거부)
+        let (decision, category) = self.domain_blocker.decide_with_category(host, false);
+        if decision != AclDecision::Allow {
+            self.close.record(SessionCloseReason::Blocked);
+            info!("[Session:{}] 차단된 도메인 감지: {}", self.session_id(), host);
+            self.log_blocked_request(host, &request_str, &self.client_addr.ip().to_string(), false, category).await;
+            if let Some(pool) = &self.buffer_pool {
+                pool.return_buffer(buffer);
+            }
+            if let AclDecision::Tarpit { max_hold, .. } = decision {
+                tokio::time::sleep(max_hold).await;
+            }
+            client_stream.write_all(&socks4_reply(false)).await?;
+            return Ok(());
+        }
+        
+        // 전체 동시 세션/초당 요청 수 한도 확인 (SOCKS4는 Retry-After 없이 거부 응답만 보냄)
+        let _overload_slot = match overload_guard().map(|guard| guard.admit()).transpose() {
+            Ok(slot) => slot.flatten(),
+            Err(e) => {
+                self.close.record(SessionCloseReason::Blocked);
+                info!("[Session:{}] {}", self.session_id(), e);
+                if let Some(pool) = &self.buffer_pool {
+                    pool.return_buffer(buffer);
+                }
+                client_stream.write_all(&socks4_reply(false)).await?;
+                return Ok(());
+            }
+        };
+        
+        // 호스트별 동시 연결 한도 확인 (슬롯은 세션이 끝날 때까지 보관)
+        let _host_slot = match host_limiter() {
+            Some(limiter) => match limiter.acquire(host).await {
+                Ok(slot) => slot,
+                Err(e) => {
+                    self.close.record(SessionCloseReason::Blocked);
+                    info!("[Session:{}] {}", self.session_id(), e);
+                    if let Some(pool) = &self.buffer_pool {
+                        pool.return_buffer(buffer);
+                    }
+                    client_stream.write_all(&socks4_reply(false)).await?;
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+        
+        // 목적지 분류별 대역폭 제한 (세션 동안 유지)
+        throttle_client(&client_stream, host, &self.config.category_throttle, self.session_id());
+        
+        if let Err(e) = client_stream.write_all(&socks4_reply(true)).await {
+            error!("[Session:{}] Failed to send SOCKS4 response: {}", self.session_id(), e);
+            if let Some(pool) = &self.buffer_pool {
+                pool.return_buffer(buffer);
+            }
+            return Err(e.into());
+        }
+        
+        // 클라이언트의 첫 바이트로 TLS(핸드셰이크 레코드 0x16) 여부 판단
+        let mut first_byte = [0u8; 1];
+        let peeked = tokio::time::timeout(
+            Duration::from_millis(self.config.timeout_ms as u64),
+            client_stream.peek(&mut first_byte),
+        ).await;
+        if !matches!(peeked, Ok(Ok(1))) {
+            if peeked.is_err() {
+                self.close.record(SessionCloseReason::IdleTimeout);
+            }
+            debug!("[Session:{}] SOCKS4 터널에서 데이터 없이 연결 종료", self.session_id());
+            if let Some(pool) = &self.buffer_pool {
+                pool.return_buffer(buffer);
+            }
+            return Ok(());
+        }
+        
+        if first_byte[0] == TLS_HANDSHAKE_RECORD {
+            self.active.opened(true);
+            return self.intercept_tls(PrefixedStream::plain(client_stream), host, port, buffer).await;
+        }
+        
+        // 평문 HTTP: 터널 안의 첫 요청 읽기
+        buffer.clear();
+        let n = match self.read_client_request(&mut client_stream, &mut buffer).await {
+            Ok(n) => n,
+            Err(e) => {
+                if let Some(pool) = &self.buffer_pool {
+                    pool.return_buffer(buffer);
+                }
+                return Err(e);
+            }
+        };
+        let request_str = String::from_utf8_lossy(&buffer[..n]).to_string();
+        self.active.opened(false);
+        self.handle_http_request(client_stream, host, port, &request_str, buffer).await
+    }
+    
+    /// SOCKS4 요청 전체 읽기 및 파싱 (CONNECT 이외의 명령은 거부)
+    async fn read_socks4_request(&self, client_stream: &mut TcpStream, buffer: &mut BytesMut) -> Result<Socks4Request> {
+        loop {
+            match parse_socks4(buffer) {
+                Socks4Parse::Request(request) if request.command == Socks4Command::Bind => {
+                    return Err(http_err("SOCKS4 BIND is not supported"));
+                },
+                Socks4Parse::Request(request) => return Ok(request),
+                Socks4Parse::Invalid(reason) => return Err(http_err(reason)),
+                Socks4Parse::Incomplete if buffer.len() >= SOCKS4_MAX_REQUEST_LEN => {
+                    return Err(http_err("SOCKS4 request too long"));
+                },
+                Socks4Parse::Incomplete => {
+                    if self.read_client_request(client_stream, buffer).await? == 0 {
+                        return Err(http_err("client closed connection during SOCKS4 request"));
+                    }
+                },
+            }
+        }
+    }
+    
+    /// 최대 세션 시간 기한까지만 프록시 작업 실행
+    ///
+    /// 기한에 도달하거나 점검 모드 전환으로 진행 중인 세션 종료를 요청받으면
+    /// 작업(양방향 복사 루프)을 중단하여 연결을 닫고 기본값을 반환합니다.
+    async fn run_until_deadline<T: Default>(&self, task: impl Future<Output = Result<T>>) -> Result<T> {
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        let mut maintenance_close = self.maintenance_close.clone();
+        
+        tokio::select! {
+            result = task => result,
+            _ = deadline => {
+                info!("[Session:{}] 세션 종료 (사유: max-duration, {}초 초과)",
+                      self.session_id(), self.config.max_session_duration_seconds.unwrap_or_default());
+                self.metrics.session_max_duration_closed();
+                self.close.record(SessionCloseReason::MaxDuration);
+                Ok(T::default())
+            },
+            Ok(()) = maintenance_close.changed() => {
+                info!("[Session:{}] 세션 종료 (사유: 점검 모드 전환)", self.session_id());
+                self.close.record(SessionCloseReason::ForcedShutdown);
+                Ok(T::default())
+            },
+        }
+    }
+    
     /// HTTP 요청 로깅
     async fn log_http_request(&self, host: &str, request_str: &str, target_ip: &str, _session_id: &str) {
         // 요청 파싱 - 메서드, 경로, 헤더 등
@@ -607,8 +1319,18 @@ impl Session {
         format!("{}_{}_{:x}", uuid_part, timestamp % 1000000, hash_value % 0xFFFFFF)
     }
 
+    /// 업스트림 연결에 쓸 출구 (요청 헤더로 고른 출구가 없으면 parent_proxies에서 상위 프록시 선택)
+    fn upstream_egress(&self) -> Option<EgressPool> {
+        if self.egress.is_some() {
+            return self.egress.clone();
+        }
+        let egress = select_parent(&self.config, self.session_id())?;
+        *self.parent_proxy.lock().unwrap() = egress.parent_proxy.clone();
+        Some(egress)
+    }
+
     // 세션 ID 반환 - 이제 저장된 값 사용
-    fn session_id(&self) -> &str {
+    pub fn session_id(&self) -> &str {
         &self.session_id
     }
 
@@ -616,13 +1338,24 @@ impl Session {
     fn optimize_tcp(&self, stream: &TcpStream) -> Result<()> {
         stream.set_nodelay(TCP_NODELAY)?;
 
+        // 송수신 버퍼는 client_socket_buffers에 지정한 값, 지정하지 않은 쪽은 기본값(BUFFER_SIZE_MEDIUM)
+        let buffers = &self.config.client_socket_buffers;
+        if buffers.is_enabled() {
+            let peer = stream.peer_addr().map(|addr| addr.to_string()).unwrap_or_default();
+            apply_socket_buffers(stream, buffers, &format!("[Session:{}] 클라이언트", self.session_id()), &peer);
+        }
+
         let fd = stream.as_raw_fd();
         let sock = unsafe { Socket::from_raw_fd(fd) };
 
-        let _ = sock.set_recv_buffer_size(BUFFER_SIZE_MEDIUM)
-            .map_err(|e| debug!("[Session:{}] set receive buffer size failed: {}", self.session_id(), e));
-        let _ = sock.set_send_buffer_size(BUFFER_SIZE_MEDIUM)
-            .map_err(|e| debug!("[Session:{}] set send buffer size failed: {}", self.session_id(), e));
+        if buffers.recv_bytes.is_none() {
+            let _ = sock.set_recv_buffer_size(BUFFER_SIZE_MEDIUM)
+                .map_err(|e| debug!("[Session:{}] set receive buffer size failed: {}", self.session_id(), e));
+        }
+        if buffers.send_bytes.is_none() {
+            let _ = sock.set_send_buffer_size(BUFFER_SIZE_MEDIUM)
+                .map_err(|e| debug!("[Session:{}] set send buffer size failed: {}", self.session_id(), e));
+        }
 
         let _ = sock.set_keepalive(true)
             .map_err(|e| debug!("[Session:{}] TCP Keepalive activate failed: {}", self.session_id(), e));
@@ -0,0 +1,36 @@
+// 세션별 전송량 집계
+// 릴레이 경로(평문 HTTP, TLS 가로채기, 불투명 터널)가 전달한 바이트를 방향별로 더해 두고,
+// 세션이 끝날 때 한 번만 세션 크기 분포(session_bytes_up/down)에 반영합니다.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// 세션 하나가 주고받은 바이트 수 (업로드: 클라이언트→업스트림, 다운로드: 업스트림→클라이언트)
+#[derive(Debug, Default)]
+pub struct TransferRecorder {
+    up: AtomicU64,
+    down: AtomicU64,
+    relayed: AtomicBool,
+}
+
+impl TransferRecorder {
+    /// 업스트림과 릴레이를 시작함 (차단/거부로 끝난 세션은 분포에 넣지 않음)
+    pub fn relay_started(&self) {
+        self.relayed.store(true, Ordering::Relaxed);
+    }
+
+    /// 업스트림으로 보낸 바이트
+    pub fn add_up(&self, bytes: u64) {
+        self.up.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// 클라이언트로 보낸 바이트
+    pub fn add_down(&self, bytes: u64) {
+        self.down.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// 릴레이한 세션이면 (업로드, 다운로드) 바이트 수
+    pub fn totals(&self) -> Option<(u64, u64)> {
+        self.relayed.load(Ordering::Relaxed)
+            .then(|| (self.up.load(Ordering::Relaxed), self.down.load(Ordering::Relaxed)))
+    }
+}
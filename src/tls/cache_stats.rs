@@ -0,0 +1,154 @@
+// 리프 인증서 캐시 미스율
+// 최근 구간(cert_cache_alert.window_seconds)의 조회 수와 미스(새 인증서 생성) 수를 고정 개수의 시간 칸으로 나눠 세고,
+// 칸을 돌려 쓰므로 조회마다 상수 시간만 듭니다. 미스율이 임계값을 넘거나 다시 내려가면 한 번씩만 로그를 남깁니다.
+
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+
+use crate::config::CertCacheAlertConfig;
+
+/// 구간을 나누는 시간 칸 수
+const MISS_RATE_BUCKETS: usize = 10;
+
+/// 시간 칸 하나의 집계 (epoch는 칸이 속한 시간 번호)
+#[derive(Clone, Copy, Default)]
+struct Bucket {
+    epoch: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// 최근 구간의 조회/미스 수
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MissRate {
+    pub lookups: u64,
+    pub misses: u64,
+}
+
+impl MissRate {
+    /// 미스율 (퍼센트, 조회가 없으면 0)
+    pub fn percent(&self) -> u64 {
+        (self.misses * 100).checked_div(self.lookups).unwrap_or(0)
+    }
+}
+
+/// 고정 구간 미스율 집계기
+pub struct MissRateWindow {
+    started: Instant,
+    bucket_width: Duration,
+    buckets: [Bucket; MISS_RATE_BUCKETS],
+    threshold_percent: u8,
+    min_lookups: u64,
+    /// 임계값을 넘은 상태인지 (경고를 한 번만 남기기 위함)
+    alerting: bool,
+}
+
+impl MissRateWindow {
+    pub fn new(config: &CertCacheAlertConfig) -> Self {
+        let window = Duration::from_secs(config.window_seconds.max(1));
+        Self {
+            started: Instant::now(),
+            bucket_width: window / MISS_RATE_BUCKETS as u32,
+            buckets: [Bucket::default(); MISS_RATE_BUCKETS],
+            threshold_percent: config.miss_rate_threshold_percent,
+            min_lookups: config.min_lookups,
+            alerting: false,
+        }
+    }
+
+    fn epoch(&self, now: Instant) -> u64 {
+        (now.saturating_duration_since(self.started).as_millis() / self.bucket_width.as_millis().max(1)) as u64
+    }
+
+    /// 조회 결과 하나 집계 (hit가 false면 미스)
+    pub fn record(&mut self, hit: bool, now: Instant) {
+        let epoch = self.epoch(now);
+        let bucket = &mut self.buckets[epoch as usize % MISS_RATE_BUCKETS];
+        if bucket.epoch != epoch {
+            *bucket = Bucket { epoch, ..Bucket::default() };
+        }
+        if hit {
+            bucket.hits += 1;
+        } else {
+            bucket.misses += 1;
+        }
+    }
+
+    /// 최근 구간(현재 칸 포함 칸 수만큼)의 조회/미스 수
+    pub fn snapshot(&self, now: Instant) -> MissRate {
+        let epoch = self.epoch(now);
+        self.buckets.iter()
+            .filter(|bucket| epoch.checked_sub(bucket.epoch).is_some_and(|age| age < MISS_RATE_BUCKETS as u64))
+            .fold(MissRate::default(), |rate, bucket| MissRate {
+                lookups: rate.lookups + bucket.hits + bucket.misses,
+                misses: rate.misses + bucket.misses,
+            })
+    }
+
+    /// 임계값 경고 상태 갱신 (임계값 0이면 경고하지 않음)
+    ///
+    /// 넘어설 때 경고를, 다시 내려갈 때 정보 로그를 한 번씩 남기고 현재 경고 상태를 돌려줍니다.
+    pub fn check_threshold(&mut self, rate: MissRate) -> bool {
+        if self.threshold_percent == 0 {
+            return false;
+        }
+        let exceeded = rate.lookups >= self.min_lookups && rate.percent() >= u64::from(self.threshold_percent);
+        if exceeded && !self.alerting {
+            warn!("[인증서 캐시] 미스율 급증: 최근 {}초 조회 {}건 중 미스 {}건 ({}%, 임계값 {}%) - 공격 또는 설정 오류 가능성",
+                  self.window_seconds(), rate.lookups, rate.misses, rate.percent(), self.threshold_percent);
+        } else if !exceeded && self.alerting && rate.percent() < u64::from(self.threshold_percent) {
+            info!("[인증서 캐시] 미스율 정상화: 최근 {}초 조회 {}건 중 미스 {}건 ({}%)",
+                  self.window_seconds(), rate.lookups, rate.misses, rate.percent());
+        } else {
+            return self.alerting;
+        }
+        self.alerting = exceeded;
+        exceeded
+    }
+
+    fn window_seconds(&self) -> u64 {
+        (self.bucket_width * MISS_RATE_BUCKETS as u32).as_secs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn miss_rate_alerts_only_over_threshold_within_window() {
+        let mut window = MissRateWindow::new(&CertCacheAlertConfig {
+            window_seconds: 10,
+            miss_rate_threshold_percent: 50,
+            min_lookups: 20,
+        });
+        let start = Instant::now();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+
+        // 조회 수가 적으면 미스율이 높아도 경고하지 않음
+        for hit in [true, false, false, false] {
+            window.record(hit, at(0));
+        }
+        let rate = window.snapshot(at(0));
+        assert_eq!(rate, MissRate { lookups: 4, misses: 3 });
+        assert!(!window.check_threshold(rate));
+
+        // 생성이 몰리면 경고 상태
+        for _ in 0..20 {
+            window.record(false, at(3));
+        }
+        let rate = window.snapshot(at(3));
+        assert_eq!(rate.percent(), 95);
+        assert!(window.check_threshold(rate));
+
+        // 구간이 지나 이전 칸이 빠지면 적중만 남아 경고 해제
+        for _ in 0..30 {
+            window.record(true, at(12));
+        }
+        let rate = window.snapshot(at(12));
+        assert_eq!(rate, MissRate { lookups: 50, misses: 20 });
+        assert!(!window.check_threshold(rate));
+        assert_eq!(window.snapshot(at(14)), MissRate { lookups: 30, misses: 0 });
+    }
+}
@@ -0,0 +1,272 @@
+// 가로챈 세션의 업스트림 인증서 체인 기록 (upstream_cert_logging)
+// 업스트림이 제시한 체인의 subject, issuer, SAN, SHA-256 지문, 일련번호, 유효 기간을 DB upstream_certificates
+// 테이블이나 upstream_cert 대상 로그에 남겨, 나중에 어떤 세션이 어떤 인증서를 받았는지 찾아보거나 예상하지 못한
+// 인증서 교체를 알아챌 수 있게 합니다. 인증서 메타데이터만 기록하고 키나 인증서 원본은 남기지 않습니다.
+// 같은 호스트의 같은 리프 인증서는 dedupe_seconds 동안 한 번만 기록합니다.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use log::{debug, info, warn};
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use rustls::pki_types::CertificateDer;
+use serde_json::{json, Value};
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+
+use crate::admin::tls::certificate_fingerprint;
+use crate::config::{UpstreamCertLogTarget, UpstreamCertLoggingConfig};
+use crate::constants::{upstream_certificates, UPSTREAM_CERT_LOG_CACHE_SIZE};
+use crate::db;
+use crate::db::config::DbConfig;
+use crate::metrics::Metrics;
+
+/// 인증서 한 장의 메타데이터
+#[derive(Clone, Debug)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub sans: Vec<String>,
+    /// DER 인코딩의 SHA-256 지문 (콜론 구분 16진수)
+    pub fingerprint: String,
+    pub serial: String,
+    pub not_before: Option<DateTime<Utc>>,
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+impl CertificateInfo {
+    /// DER 인증서 파싱 (파싱할 수 없으면 지문만 채움)
+    pub fn parse(cert: &CertificateDer<'_>) -> Self {
+        let fingerprint = certificate_fingerprint(cert);
+        match x509_parser::parse_x509_certificate(cert.as_ref()) {
+            Ok((_, parsed)) => Self {
+                subject: parsed.subject().to_string(),
+                issuer: parsed.issuer().to_string(),
+                sans: certificate_sans(&parsed),
+                fingerprint,
+                serial: parsed.raw_serial_as_string(),
+                not_before: DateTime::from_timestamp(parsed.validity().not_before.timestamp(), 0),
+                not_after: DateTime::from_timestamp(parsed.validity().not_after.timestamp(), 0),
+            },
+            Err(e) => {
+                debug!("업스트림 인증서 파싱 실패 ({}): {}", fingerprint, e);
+                Self {
+                    subject: String::new(),
+                    issuer: String::new(),
+                    sans: Vec::new(),
+                    fingerprint,
+                    serial: String::new(),
+                    not_before: None,
+                    not_after: None,
+                }
+            }
+        }
+    }
+
+    /// JSON 표현 (체인 컬럼, 로그 한 줄에 사용)
+    pub fn to_json(&self) -> Value {
+        json!({
+            "subject": self.subject,
+            "issuer": self.issuer,
+            "sans": self.sans,
+            "fingerprint": self.fingerprint,
+            "serial": self.serial,
+            "not_before": self.not_before.map(|time| time.to_rfc3339()),
+            "not_after": self.not_after.map(|time| time.to_rfc3339()),
+        })
+    }
+}
+
+/// 인증서의 SAN 목록 (DNS 이름은 그대로, 나머지는 종류를 붙여 표시)
+pub fn certificate_sans(parsed: &X509Certificate<'_>) -> Vec<String> {
+    match parsed.subject_alternative_name() {
+        Ok(Some(ext)) => ext.value.general_names.iter()
+            .map(|name| match name {
+                GeneralName::DNSName(dns) => dns.to_string(),
+                GeneralName::IPAddress(ip) => format!("IP:{:?}", ip),
+                other => format!("{:?}", other),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 체인 기록 판단 결과
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeafNote {
+    /// dedupe_seconds 안에 이미 기록한 (호스트, 리프) 조합
+    Duplicate,
+    /// 기록 (이 호스트에서 직전에 기록한 리프와 다르면 그 지문)
+    Record { replaced: Option<String> },
+}
+
+// 최근 기록한 (호스트, 리프 지문) 조합과 호스트별 마지막 리프 지문
+struct SeenLeaves {
+    pairs: LruCache<(String, String), Instant>,
+    last_leaf: LruCache<String, String>,
+}
+
+static SEEN: Lazy<Mutex<SeenLeaves>> = Lazy::new(|| {
+    let size = NonZeroUsize::new(UPSTREAM_CERT_LOG_CACHE_SIZE).unwrap();
+    Mutex::new(SeenLeaves { pairs: LruCache::new(size), last_leaf: LruCache::new(size) })
+});
+
+// upstream_certificates 테이블을 만들었는지
+static TABLE_READY: AtomicBool = AtomicBool::new(false);
+
+/// 호스트의 리프 인증서를 기록할지 판단 (dedupe가 0이면 매번 기록)
+///
+/// 최근에 본 적 없는 리프가 직전 리프를 대신하면 교체로 보고, 이미 본 인증서끼리 번갈아 나오는
+/// 경우(부하 분산된 서버마다 인증서가 다른 경우)는 dedupe 시간 동안 중복으로 처리합니다.
+pub fn note_leaf(host: &str, fingerprint: &str, dedupe: Duration) -> LeafNote {
+    let mut seen = SEEN.lock().unwrap();
+    let key = (host.to_string(), fingerprint.to_string());
+    if !dedupe.is_zero() && seen.pairs.get(&key).is_some_and(|at| at.elapsed() < dedupe) {
+        return LeafNote::Duplicate;
+    }
+    seen.pairs.put(key, Instant::now());
+    let replaced = seen.last_leaf.put(host.to_string(), fingerprint.to_string())
+        .filter(|previous| previous != fingerprint);
+    LeafNote::Record { replaced }
+}
+
+/// 업스트림 인증서 체인 기록 (리프부터 순서대로, 중복이면 건너뜀)
+///
+/// 리프 지문으로 먼저 중복을 거르고, 기록할 때만 체인 전체를 파싱합니다.
+pub fn record_upstream_chain(session_id: &str, host: &str, chain: &[CertificateDer<'_>], config: &UpstreamCertLoggingConfig) {
+    let Some(leaf) = chain.first() else {
+        return;
+    };
+    let fingerprint = certificate_fingerprint(leaf);
+
+    let replaced = match note_leaf(host, &fingerprint, Duration::from_secs(config.dedupe_seconds)) {
+        LeafNote::Duplicate => {
+            debug!("[Session:{}] 업스트림 인증서 {} ({}) 최근 기록됨, 건너뜀", session_id, fingerprint, host);
+            return;
+        },
+        LeafNote::Record { replaced } => replaced,
+    };
+    let chain: Vec<CertificateInfo> = chain.iter().map(CertificateInfo::parse).collect();
+    let leaf = &chain[0];
+    if let Some(previous) = &replaced {
+        warn!("[Session:{}] [CERT-CHANGE] {} 업스트림 리프 인증서 변경: {} -> {} (subject: {}, issuer: {})",
+              session_id, host, previous, leaf.fingerprint, leaf.subject, leaf.issuer);
+    }
+    Metrics::new().upstream_cert_logged(replaced.is_some());
+
+    let chain_json = Value::Array(chain.iter().map(CertificateInfo::to_json).collect());
+    if config.target == UpstreamCertLogTarget::Table && DbConfig::is_enabled() {
+        let session_id = session_id.to_string();
+        let host = host.to_string();
+        let leaf = leaf.clone();
+        tokio::spawn(async move {
+            if let Err(e) = insert_chain(&session_id, &host, &leaf, &chain_json).await {
+                // 기록하지 못한 리프는 다음 연결에서 다시 기록하도록 중복 기억에서 뺌
+                forget_leaf(&host, &leaf.fingerprint);
+                warn!("[Session:{}] 업스트림 인증서 체인 DB 기록 실패: {}", session_id, e);
+            }
+        });
+    } else {
+        info!(target: "upstream_cert", "{}", json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "session_id": session_id,
+            "host": host,
+            "chain": chain_json,
+        }));
+    }
+}
+
+/// upstream_certificates 테이블에 체인 한 건 삽입 (처음 한 번 테이블과 인덱스 생성)
+async fn insert_chain(session_id: &str, host: &str, leaf: &CertificateInfo, chain: &Value)
+    -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = db::pool::get_client().await?;
+    if !TABLE_READY.load(Ordering::Acquire) {
+        client.execute(upstream_certificates::CREATE_TABLE, &[]).await?;
+        for index_query in upstream_certificates::CREATE_INDICES.iter() {
+            client.execute(*index_query, &[]).await?;
+        }
+        TABLE_READY.store(true, Ordering::Release);
+    }
+    client.execute(upstream_certificates::INSERT_CHAIN, &[
+        &session_id, &host, &leaf.fingerprint, &leaf.subject, &leaf.issuer,
+        &leaf.not_before, &leaf.not_after, &chain.to_string(),
+    ]).await?;
+    Ok(())
+}
+
+/// 기록한 (호스트, 리프) 조합을 중복 기억에서 뺌 (기록 실패 시 다음 연결에서 다시 기록)
+pub fn forget_leaf(host: &str, fingerprint: &str) {
+    SEEN.lock().unwrap().pairs.pop(&(host.to_string(), fingerprint.to_string()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::generate_simple_self_signed;
+
+    fn self_signed(name: &str) -> CertificateDer<'static> {
+        CertificateDer::from(generate_simple_self_signed(vec![name.to_string()]).unwrap().serialize_der().unwrap())
+    }
+
+    #[test]
+    fn certificate_metadata_is_extracted() {
+        let host = "certs.unit.invalid";
+        let cert = self_signed(host);
+        let info = CertificateInfo::parse(&cert);
+        assert_eq!(info.sans, [host]);
+        assert_eq!(info.fingerprint, certificate_fingerprint(&cert));
+        assert!(!info.serial.is_empty());
+        let (not_before, not_after) = info.not_before.zip(info.not_after).unwrap();
+        assert!(not_before < not_after);
+    }
+
+    #[test]
+    fn repeated_leaf_is_deduped_and_new_leaf_is_a_change() {
+        let host = "dedupe.cert-chain.test";
+        let dedupe = Duration::from_secs(3600);
+        let notes = [
+            note_leaf(host, "a", dedupe),
+            note_leaf(host, "a", dedupe),
+            note_leaf(host, "b", dedupe),
+            // 이미 본 인증서끼리 번갈아 나오면 중복
+            note_leaf(host, "a", dedupe),
+            note_leaf(host, "a", Duration::ZERO),
+        ];
+        assert_eq!(notes, [
+            LeafNote::Record { replaced: None },
+            LeafNote::Duplicate,
+            LeafNote::Record { replaced: Some("a".to_string()) },
+            LeafNote::Duplicate,
+            LeafNote::Record { replaced: Some("b".to_string()) },
+        ]);
+    }
+
+    #[tokio::test]
+    async fn chains_are_logged_once_per_leaf() {
+        DbConfig::disable();
+        let host = "chain.cert-chain.test";
+        let (first, second) = (self_signed(host), self_signed(host));
+        let metrics = Metrics::new();
+        let (logged_before, changes_before) = (metrics.get("upstream_certs_logged"), metrics.get("upstream_cert_changes"));
+        let config = UpstreamCertLoggingConfig { enabled: true, target: UpstreamCertLogTarget::Log, dedupe_seconds: 3600 };
+        record_upstream_chain("unit", host, &[first.clone(), second.clone()], &config);
+        record_upstream_chain("unit", host, &[first], &config);
+        record_upstream_chain("unit", host, &[second], &config);
+        assert_eq!(metrics.get("upstream_certs_logged") - logged_before, 2);
+        assert_eq!(metrics.get("upstream_cert_changes") - changes_before, 1);
+    }
+
+    #[test]
+    fn forgotten_leaf_is_recorded_again() {
+        let host = "forget.cert-chain.test";
+        let dedupe = Duration::from_secs(3600);
+        assert_eq!(note_leaf(host, "aa", dedupe), LeafNote::Record { replaced: None });
+        assert_eq!(note_leaf(host, "aa", dedupe), LeafNote::Duplicate);
+        forget_leaf(host, "aa");
+        assert_eq!(note_leaf(host, "aa", dedupe), LeafNote::Record { replaced: None });
+    }
+}
@@ -0,0 +1,409 @@
+use std::time::Duration;
+
+use crate::proxy::connect_pipeline::PrefixedStream;
+
+/// TLS 레코드 헤더 길이 (타입 1, 버전 2, 길이 2)
+pub const TLS_RECORD_HEADER_LEN: usize = 5;
+
+/// TLS 핸드셰이크 레코드 타입 (ClientHello는 항상 이 레코드로 시작)
+pub const TLS_HANDSHAKE_RECORD: u8 = 0x16;
+
+/// TLS 레코드 본문 최대 길이
+const TLS_MAX_RECORD_LEN: usize = 16384;
+
+/// ClientHello 나머지를 기다릴 때 다시 확인하는 간격
+const PEEK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// ClientHello의 SNI 확인 결과
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientHelloSni {
+    /// 판단하려면 최소 이 길이(바이트)까지 받아야 함
+    Incomplete(usize),
+    /// 해석할 수 없음 (ClientHello가 아니거나 형식 오류)
+    Unknown,
+    /// server_name 확장이 없는 ClientHello
+    NoSni,
+    /// ClientHello의 호스트명
+    Sni(String),
+}
+
+/// CONNECT 터널 안에서 감지한 프로토콜
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TunnelProtocol {
+    /// 클라이언트가 TLS 핸드셰이크로 시작함
+    Tls,
+    /// 그 밖의 TCP 프로토콜 (그대로 전달)
+    Raw,
+}
+
+impl TunnelProtocol {
+    /// 로그 표시 이름
+    pub fn name(&self) -> &'static str {
+        match self {
+            TunnelProtocol::Tls => "tls",
+            TunnelProtocol::Raw => "raw",
+        }
+    }
+}
+
+/// ClientHello에서 업스트림 핸드셰이크에 옮길 수 있는 속성 (클라이언트가 보낸 순서, GREASE 값 제외)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientHelloFingerprint {
+    /// legacy_version (client_version)
+    pub version: u16,
+    pub cipher_suites: Vec<u16>,
+    /// 확장 유형 (보낸 순서)
+    pub extensions: Vec<u16>,
+    /// supported_groups (10)
+    pub supported_groups: Vec<u16>,
+    /// ec_point_formats (11)
+    pub ec_point_formats: Vec<u8>,
+    /// signature_algorithms (13)
+    pub signature_algorithms: Vec<u16>,
+    /// supported_versions (43, 없으면 legacy_version까지만 지원)
+    pub supported_versions: Vec<u16>,
+}
+
+impl ClientHelloFingerprint {
+    /// JA3 문자열 (버전,암호군,확장,그룹,점 형식 - 각 목록은 `-`로 연결)
+    pub fn ja3(&self) -> String {
+        let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join("-");
+        format!("{},{},{},{},{}",
+                self.version,
+                join(&mut self.cipher_suites.iter().map(u16::to_string)),
+                join(&mut self.extensions.iter().map(u16::to_string)),
+                join(&mut self.supported_groups.iter().map(u16::to_string)),
+                join(&mut self.ec_point_formats.iter().map(u8::to_string)))
+    }
+}
+
+/// GREASE(RFC 8701) 예약 값인지 (0x0a0a, 0x1a1a, ... 0xfafa)
+pub fn is_grease(value: u16) -> bool {
+    value & 0x0f0f == 0x0a0a && value >> 8 == value & 0xff
+}
+
+/// TLS 레코드에서 ClientHello의 server_name(RFC 6066) 확인
+///
+/// ClientHello가 여러 핸드셰이크 레코드에 나뉘어 있으면 이어지는 레코드의 조각을 모아 해석합니다.
+/// 더 받아야 하면 지금까지 알 수 있는 최소 길이를 `Incomplete`로 돌려줍니다.
+pub fn parse_client_hello_sni(data: &[u8]) -> ClientHelloSni {
+    match client_hello_message(data) {
+        Ok(handshake) => match client_hello_sni(&handshake) {
+            Some(Some(sni)) => ClientHelloSni::Sni(sni),
+            Some(None) => ClientHelloSni::NoSni,
+            None => ClientHelloSni::Unknown,
+        },
+        Err(result) => result,
+    }
+}
+
+/// TLS 레코드에서 ClientHello 지문 속성 추출 (ClientHello 전체가 있어야 함)
+pub fn parse_client_hello_fingerprint(data: &[u8]) -> Option<ClientHelloFingerprint> {
+    client_hello_fingerprint(&client_hello_message(data).ok()?)
+}
+
+/// 핸드셰이크 레코드의 조각을 모아 ClientHello 메시지 전체를 꺼냄 (못 꺼내면 `Incomplete` 또는 `Unknown`)
+fn client_hello_message(data: &[u8]) -> Result<Vec<u8>, ClientHelloSni> {
+    let mut handshake = Vec::new();
+    let mut offset = 0;
+    // 핸드셰이크 메시지를 마저 채우려면 더 받아야 하는 바이트 (길이를 아직 모르면 0)
+    let mut remaining = 0;
+
+    loop {
+        let record = &data[offset..];
+        if record.len() < TLS_RECORD_HEADER_LEN {
+            return Err(ClientHelloSni::Incomplete(offset + TLS_RECORD_HEADER_LEN + remaining));
+        }
+        if record[0] != TLS_HANDSHAKE_RECORD {
+            return Err(ClientHelloSni::Unknown);
+        }
+
+        let record_len = u16::from_be_bytes([record[3], record[4]]) as usize;
+        if record_len == 0 || record_len > TLS_MAX_RECORD_LEN {
+            return Err(ClientHelloSni::Unknown);
+        }
+        let record_end = offset + TLS_RECORD_HEADER_LEN + record_len;
+        if data.len() < record_end {
+            return Err(ClientHelloSni::Incomplete(record_end.max(offset + TLS_RECORD_HEADER_LEN + remaining)));
+        }
+        handshake.extend_from_slice(&data[offset + TLS_RECORD_HEADER_LEN..record_end]);
+        offset = record_end;
+
+        // 핸드셰이크 타입 ClientHello(1)와 3바이트 길이
+        if handshake[0] != 0x01 {
+            return Err(ClientHelloSni::Unknown);
+        }
+        if handshake.len() < 4 {
+            continue;
+        }
+        let message_len = 4 + u32::from_be_bytes([0, handshake[1], handshake[2], handshake[3]]) as usize;
+        if handshake.len() < message_len {
+            remaining = message_len - handshake.len();
+            continue;
+        }
+
+        handshake.truncate(message_len);
+        return Ok(handshake);
+    }
+}
+
+/// 연결에서 ClientHello를 기다린 결과
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientHelloPeek {
+    /// ClientHello를 모두 받아 해석함 (`Incomplete`는 나오지 않음)
+    Parsed(ClientHelloSni),
+    /// 제한 시간 안에 ClientHello를 모두 받지 못함 (지금까지 받은 바이트 수)
+    TimedOut(usize),
+    /// ClientHello가 크기 한도보다 큼 (필요한 최소 바이트 수)
+    TooLarge(usize),
+}
+
+/// 클라이언트 연결의 데이터를 소비하지 않고 읽어 ClientHello 확인
+///
+/// 느린 클라이언트가 ClientHello를 여러 조각으로 보내도 `timeout` 안에서 `max_bytes`까지 모일 때까지 기다립니다.
+pub async fn peek_client_hello(stream: &PrefixedStream, timeout: Duration, max_bytes: usize) -> ClientHelloPeek {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut data = vec![0u8; TLS_RECORD_HEADER_LEN];
+    let mut last_len = 0;
+
+    loop {
+        let n = match tokio::time::timeout_at(deadline, stream.peek(&mut data)).await {
+            Ok(Ok(n)) if n > 0 => n,
+            Ok(_) => return ClientHelloPeek::Parsed(ClientHelloSni::Unknown),
+            Err(_) => return ClientHelloPeek::TimedOut(last_len),
+        };
+
+        match parse_client_hello_sni(&data[..n]) {
+            ClientHelloSni::Incomplete(needed) => {
+                if needed > max_bytes {
+                    return ClientHelloPeek::TooLarge(needed);
+                }
+                if needed > data.len() {
+                    data.resize(needed, 0);
+                } else if n == last_len {
+                    // 나머지가 아직 도착하지 않음 (peek는 같은 데이터를 바로 반환하므로 잠시 대기)
+                    if tokio::time::Instant::now() >= deadline {
+                        return ClientHelloPeek::TimedOut(n);
+                    }
+                    tokio::time::sleep(PEEK_RETRY_INTERVAL).await;
+                }
+                last_len = n;
+            },
+            result => return ClientHelloPeek::Parsed(result),
+        }
+    }
+}
+
+/// 이미 모두 받은 ClientHello를 소비하지 않고 다시 읽어 지문 속성 추출 (peek_client_hello가 끝난 뒤 사용)
+pub async fn peek_client_hello_fingerprint(stream: &PrefixedStream, max_bytes: usize) -> Option<ClientHelloFingerprint> {
+    let mut data = vec![0u8; max_bytes];
+    let n = stream.peek(&mut data).await.ok()?;
+    parse_client_hello_fingerprint(&data[..n])
+}
+
+/// 핸드셰이크 메시지에서 지문 속성 추출 (해석 불가 시 None)
+fn client_hello_fingerprint(handshake: &[u8]) -> Option<ClientHelloFingerprint> {
+    let mut reader = Reader(handshake);
+    if reader.u8()? != 0x01 {
+        return None;
+    }
+    reader.take(3)?;
+
+    let mut fingerprint = ClientHelloFingerprint { version: reader.u16()?, ..Default::default() };
+    reader.take(32)?;
+    let session_id_len = reader.u8()? as usize;
+    reader.take(session_id_len)?;
+    let cipher_suites_len = reader.u16()? as usize;
+    fingerprint.cipher_suites = u16_list(reader.take(cipher_suites_len)?);
+    let compression_len = reader.u8()? as usize;
+    reader.take(compression_len)?;
+    if reader.0.is_empty() {
+        return Some(fingerprint);
+    }
+
+    let extensions_len = reader.u16()? as usize;
+    let mut extensions = Reader(reader.take(extensions_len)?);
+    while !extensions.0.is_empty() {
+        let extension_type = extensions.u16()?;
+        let extension_len = extensions.u16()? as usize;
+        let mut extension = Reader(extensions.take(extension_len)?);
+        if is_grease(extension_type) {
+            continue;
+        }
+        fingerprint.extensions.push(extension_type);
+        match extension_type {
+            10 => {
+                let len = extension.u16()? as usize;
+                fingerprint.supported_groups = u16_list(extension.take(len)?);
+            },
+            11 => {
+                let len = extension.u8()? as usize;
+                fingerprint.ec_point_formats = extension.take(len)?.to_vec();
+            },
+            13 => {
+                let len = extension.u16()? as usize;
+                fingerprint.signature_algorithms = u16_list(extension.take(len)?);
+            },
+            43 => {
+                let len = extension.u8()? as usize;
+                fingerprint.supported_versions = u16_list(extension.take(len)?);
+            },
+            _ => {},
+        }
+    }
+    Some(fingerprint)
+}
+
+/// 2바이트 값 목록 (GREASE 값 제외)
+fn u16_list(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .filter(|value| !is_grease(*value))
+        .collect()
+}
+
+/// 핸드셰이크 메시지에서 SNI 추출 (해석 불가 시 None, SNI가 없으면 Some(None))
+fn client_hello_sni(handshake: &[u8]) -> Option<Option<String>> {
+    let mut reader = Reader(handshake);
+
+    // 핸드셰이크 타입 ClientHello(1)와 3바이트 길이
+    if reader.u8()? != 0x01 {
+        return None;
+    }
+    reader.take(3)?;
+
+    // client_version, random, session_id, cipher_suites, compression_methods
+    reader.take(2 + 32)?;
+    let session_id_len = reader.u8()? as usize;
+    reader.take(session_id_len)?;
+    let cipher_suites_len = reader.u16()? as usize;
+    reader.take(cipher_suites_len)?;
+    let compression_len = reader.u8()? as usize;
+    reader.take(compression_len)?;
+
+    // 확장이 없는 ClientHello (SSLv3/TLS 1.0 클라이언트 일부)
+    if reader.0.is_empty() {
+        return Some(None);
+    }
+
+    let extensions_len = reader.u16()? as usize;
+    let mut extensions = Reader(reader.take(extensions_len)?);
+    while !extensions.0.is_empty() {
+        let extension_type = extensions.u16()?;
+        let extension_len = extensions.u16()? as usize;
+        let extension = extensions.take(extension_len)?;
+        if extension_type != 0x0000 {
+            continue;
+        }
+
+        // server_name_list: name_type(0 = host_name), 2바이트 길이, 호스트명
+        let mut names = Reader(extension);
+        let list_len = names.u16()? as usize;
+        let mut list = Reader(names.take(list_len)?);
+        while !list.0.is_empty() {
+            let name_type = list.u8()?;
+            let name_len = list.u16()? as usize;
+            let name = list.take(name_len)?;
+            if name_type == 0 && !name.is_empty() {
+                return Some(std::str::from_utf8(name).ok().map(|name| name.to_ascii_lowercase()));
+            }
+        }
+        return Some(None);
+    }
+
+    Some(None)
+}
+
+/// 길이 검사를 포함한 바이트 읽기
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use rustls::pki_types::ServerName;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use crate::tls::create_unverified_client_config;
+
+    const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn client_hello() -> Vec<u8> {
+        let server_name = ServerName::try_from("fragmented.example").unwrap();
+        let mut connection = rustls::ClientConnection::new(Arc::new(create_unverified_client_config(None).unwrap()), server_name).unwrap();
+        let mut hello = Vec::new();
+        connection.write_tls(&mut hello).unwrap();
+        hello
+    }
+
+    // 조각마다 잠시 쉬면서 보낸 데이터를 peek하고, 읽은 바이트가 소비되지 않았는지 확인
+    async fn peek(chunks: Vec<Vec<u8>>, timeout: Duration, max_bytes: usize) -> ClientHelloPeek {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let mut server = PrefixedStream::plain(listener.accept().await.unwrap().0);
+        let expected = chunks.concat();
+        let writer = tokio::spawn(async move {
+            for chunk in chunks {
+                client.write_all(&chunk).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            client
+        });
+        let result = peek_client_hello(&server, timeout, max_bytes).await;
+        let _client = writer.await.unwrap();
+        if matches!(result, ClientHelloPeek::Parsed(_)) {
+            let mut received = vec![0u8; expected.len()];
+            tokio::time::timeout(IO_TIMEOUT, server.read_exact(&mut received)).await.unwrap().unwrap();
+            assert_eq!(received, expected, "ClientHello peek consumed client bytes");
+        }
+        result
+    }
+
+    #[tokio::test]
+    async fn fragmented_client_hello_is_collected_before_parsing() {
+        let hello = client_hello();
+        let expected = ClientHelloPeek::Parsed(ClientHelloSni::Sni("fragmented.example".to_string()));
+
+        let split = hello.len() / 2;
+        assert_eq!(peek(vec![hello[..split].to_vec(), hello[split..].to_vec()], IO_TIMEOUT, 32 * 1024).await, expected);
+
+        // 같은 핸드셰이크 메시지를 두 레코드로 나누고, 두 번째 레코드 중간에서 나눠 보냄
+        let handshake = &hello[TLS_RECORD_HEADER_LEN..];
+        let (first, second) = handshake.split_at(handshake.len() / 3);
+        let mut records = Vec::new();
+        for fragment in [first, second] {
+            records.extend_from_slice(&hello[..3]);
+            records.extend_from_slice(&(fragment.len() as u16).to_be_bytes());
+            records.extend_from_slice(fragment);
+        }
+        let split = TLS_RECORD_HEADER_LEN + first.len() + 8;
+        assert_eq!(peek(vec![records[..split].to_vec(), records[split..].to_vec()], IO_TIMEOUT, 32 * 1024).await, expected);
+    }
+
+    #[tokio::test]
+    async fn incomplete_or_oversized_client_hello_is_reported() {
+        let hello = client_hello();
+        let split = hello.len() / 2;
+        assert_eq!(peek(vec![hello[..split].to_vec()], Duration::from_millis(200), 32 * 1024).await, ClientHelloPeek::TimedOut(split));
+        assert_eq!(peek(vec![hello.clone()], IO_TIMEOUT, 64).await, ClientHelloPeek::TooLarge(hello.len()));
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::constants::tls_exemptions;
+use crate::db;
+
+/// TLS 가로채기 제외 호스트 목록과 그 출처
+struct TlsExemptions {
+    hosts: HashSet<String>,
+    /// 목록 출처 (config 또는 database)
+    source: &'static str,
+    /// 관리 엔드포인트로 지정한 호스트별 가로채기 여부 (목록보다 우선, DB 갱신과 무관하게 유지)
+    overrides: HashMap<String, bool>,
+}
+
+// 가로채기 없이 그대로 전달할 호스트 (DB 목록을 한 번이라도 가져오기 전에는 설정 파일 목록)
+static TLS_EXEMPTIONS: Lazy<RwLock<TlsExemptions>> = Lazy::new(|| RwLock::new(TlsExemptions {
+    hosts: HashSet::new(),
+    source: "config",
+    overrides: HashMap::new(),
+}));
+
+/// 호스트의 현재 가로채기 상태 (관리 엔드포인트 응답)
+#[derive(Debug, Clone, Serialize)]
+pub struct InterceptState {
+    pub host: String,
+    /// 새 세션에서 가로채기(복호화) 여부
+    pub intercept: bool,
+    /// 상태를 결정한 항목 (정확한 호스트 또는 `*.상위도메인`, 없으면 None)
+    pub matched: Option<String>,
+    /// 상태를 결정한 출처 (runtime, config, database, default)
+    pub source: &'static str,
+}
+
+/// 가로채기 제외 목록 초기화 (DB 활성화 시 테이블 확인, 첫 로드 후 주기적 갱신 태스크 시작)
+///
+/// DB에서 목록을 가져오지 못하면 마지막으로 가져온 목록을, 한 번도 가져오지 못했다면 설정 파일의
+/// `tls_exempt_hosts`를 계속 사용합니다.
+pub async fn init_tls_exemptions(config: &Arc<Config>) {
+    let config_hosts: HashSet<String> = config.tls_exempt_hosts.iter().map(|host| normalize(host)).collect();
+    info!("TLS 가로채기 제외 호스트 (설정 파일): {} 개", config_hosts.len());
+    {
+        let mut exemptions = TLS_EXEMPTIONS.write().unwrap();
+        exemptions.hosts = config_hosts;
+        exemptions.source = "config";
+    }
+
+    if !db::config::DbConfig::is_enabled() {
+        return;
+    }
+
+    if let Err(e) = ensure_table().await {
+        warn!("tls_exemptions 테이블 초기화 실패: {}", e);
+    }
+    refresh().await;
+
+    let period = Duration::from_secs(config.tls_exemption_refresh_seconds);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            refresh().await;
+        }
+    });
+}
+
+/// 호스트(또는 SNI)가 가로채기 제외 대상인지 확인 (정확한 호스트 또는 `*.상위도메인`)
+pub fn is_tls_exempt(host: &str) -> bool {
+    !intercept_state(host).intercept
+}
+
+/// 호스트의 가로채기 상태 조회
+///
+/// 정확한 호스트부터 가까운 `*.상위도메인` 순으로 찾으며, 같은 항목에 관리 엔드포인트 지정 값이 있으면 목록보다 우선합니다.
+pub fn intercept_state(host: &str) -> InterceptState {
+    let host = normalize(host);
+    let exemptions = TLS_EXEMPTIONS.read().unwrap();
+    let mut state = InterceptState { host: host.clone(), intercept: true, matched: None, source: "default" };
+    if exemptions.hosts.is_empty() && exemptions.overrides.is_empty() {
+        return state;
+    }
+
+    let mut candidates = vec![host.clone()];
+    let mut parent = host.as_str();
+    while let Some((_, rest)) = parent.split_once('.') {
+        candidates.push(format!("*.{}", rest));
+        parent = rest;
+    }
+    for candidate in candidates {
+        if let Some(intercept) = exemptions.overrides.get(&candidate) {
+            state.intercept = *intercept;
+            state.source = "runtime";
+        } else if exemptions.hosts.contains(&candidate) {
+            state.intercept = false;
+            state.source = exemptions.source;
+        } else {
+            continue;
+        }
+        state.matched = Some(candidate);
+        break;
+    }
+    state
+}
+
+/// 호스트(또는 `*.상위도메인`)의 가로채기 여부를 즉시 지정 (새 세션부터 적용, 변경 후 상태 반환)
+pub fn set_intercept_override(host: &str, intercept: bool) -> InterceptState {
+    let host = normalize(host);
+    TLS_EXEMPTIONS.write().unwrap().overrides.insert(host.clone(), intercept);
+    intercept_state(&host)
+}
+
+/// 관리 엔드포인트로 지정한 값을 지워 목록 기준으로 되돌림 (변경 후 상태 반환)
+pub fn clear_intercept_override(host: &str) -> InterceptState {
+    let host = normalize(host);
+    TLS_EXEMPTIONS.write().unwrap().overrides.remove(&host);
+    intercept_state(&host)
+}
+
+/// 가로채기 여부를 지정할 수 있는 호스트 형식인지 (호스트 또는 `*.도메인`)
+pub fn is_valid_intercept_host(host: &str) -> bool {
+    let host = host.trim();
+    !host.is_empty()
+        && host.len() <= 253
+        && !host.trim_start_matches("*.").contains('*')
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '*' | ':'))
+}
+
+/// 현재 가로채기 제외 호스트 목록 (관리 엔드포인트 지정 값 반영, 정렬됨)
+pub fn tls_exempt_hosts() -> Vec<String> {
+    let exemptions = TLS_EXEMPTIONS.read().unwrap();
+    let mut hosts: HashSet<String> = exemptions.hosts.iter()
+        .filter(|host| exemptions.overrides.get(*host) != Some(&true))
+        .cloned()
+        .collect();
+    hosts.extend(exemptions.overrides.iter().filter(|(_, intercept)| !**intercept).map(|(host, _)| host.clone()));
+    let mut hosts: Vec<String> = hosts.into_iter().collect();
+    hosts.sort();
+    hosts
+}
+
+/// 가로채기 여부를 DB 목록에 반영하고 목록을 다시 읽음 (끄면 제외 항목 추가, 켜면 활성 항목 비활성화)
+pub async fn persist_intercept(host: &str, intercept: bool, created_by: &str, description: Option<&str>) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let host = normalize(host);
+    let client = db::pool::get_client().await?;
+    if intercept {
+        client.execute(tls_exemptions::DEACTIVATE_HOST, &[&host]).await?;
+    } else {
+        client.execute(tls_exemptions::INSERT_HOST, &[&host, &created_by, &description]).await?;
+    }
+    // 다음 주기를 기다리지 않고 DB 목록 다시 읽기
+    refresh().await;
+    Ok(())
+}
+
+/// DB 목록으로 교체 (실패 시 현재 목록 유지)
+async fn refresh() {
+    match load_from_db().await {
+        Ok(hosts) => {
+            let count = hosts.len();
+            let mut exemptions = TLS_EXEMPTIONS.write().unwrap();
+            exemptions.hosts = hosts;
+            exemptions.source = "database";
+            debug!("TLS 가로채기 제외 호스트 DB 갱신 완료: {} 개", count);
+        },
+        Err(e) => {
+            let exemptions = TLS_EXEMPTIONS.read().unwrap();
+            warn!("TLS 가로채기 제외 목록 DB 조회 실패, 기존 목록 유지 ({} {} 개): {}",
+                  exemptions.source, exemptions.hosts.len(), e);
+        }
+    }
+}
+
+async fn load_from_db() -> Result<HashSet<String>, Box<dyn Error + Send + Sync>> {
+    let client = db::pool::get_client().await?;
+    let rows = client.query(tls_exemptions::SELECT_ACTIVE_HOSTS, &[]).await?;
+    Ok(rows.iter().map(|row| normalize(&row.get::<_, String>(0))).collect())
+}
+
+async fn ensure_table() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let client = db::pool::get_client().await?;
+    client.execute(tls_exemptions::CREATE_TABLE, &[]).await?;
+    for index_query in tls_exemptions::CREATE_INDICES.iter() {
+        client.execute(*index_query, &[]).await?;
+    }
+    Ok(())
+}
+
+fn normalize(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
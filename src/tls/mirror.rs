@@ -0,0 +1,131 @@
+// ClientHello 지문 따라하기 (client_hello_mirroring)
+// 가로챈 클라이언트의 ClientHello에서 rustls로 바꿀 수 있는 속성만 골라 업스트림 핸드셰이크에 옮깁니다.
+// 암호군 순서, 키 교환 그룹 순서, TLS 버전은 클라이언트를 따르고, 확장 순서, GREASE, 서명 알고리즘, ALPN,
+// 압축/점 형식 목록은 rustls가 정하는 값을 그대로 씁니다. rustls가 구현하지 않은 암호군과 그룹은 빠집니다.
+
+use rustls::crypto::CryptoProvider;
+use rustls::version::{TLS12, TLS13};
+use rustls::SupportedProtocolVersion;
+
+use crate::tls::client_hello::ClientHelloFingerprint;
+
+/// TLS 1.2/1.3 버전 값
+const TLS12_VERSION: u16 = 0x0303;
+const TLS13_VERSION: u16 = 0x0304;
+
+/// 업스트림 핸드셰이크에 옮길 속성 (클라이언트 설정 캐시 키로도 사용)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MirrorProfile {
+    /// 클라이언트 순서대로, rustls가 지원하는 암호군
+    cipher_suites: Vec<u16>,
+    /// 클라이언트 순서대로, rustls가 지원하는 키 교환 그룹
+    kx_groups: Vec<u16>,
+    tls12: bool,
+    tls13: bool,
+}
+
+impl MirrorProfile {
+    /// 클라이언트 ClientHello와 기본 암호 공급자에서 따라 할 속성 결정
+    ///
+    /// 기본 공급자와 겹치는 암호군이나 키 교환 그룹이 없으면 None (기본 핸드셰이크 사용)
+    pub fn new(hello: &ClientHelloFingerprint, base: &CryptoProvider) -> Option<Self> {
+        // supported_versions가 없으면 legacy_version까지만 지원
+        let offers = |version: u16| if hello.supported_versions.is_empty() {
+            version == TLS12_VERSION && hello.version >= TLS12_VERSION
+        } else {
+            hello.supported_versions.contains(&version)
+        };
+
+        let cipher_suites: Vec<u16> = hello.cipher_suites.iter().copied()
+            .filter(|id| base.cipher_suites.iter().any(|suite| {
+                let version = if suite.tls13().is_some() { TLS13_VERSION } else { TLS12_VERSION };
+                u16::from(suite.suite()) == *id && offers(version)
+            }))
+            .collect();
+        let has_version = |tls13: bool| cipher_suites.iter().any(|id| base.cipher_suites.iter()
+            .any(|suite| u16::from(suite.suite()) == *id && suite.tls13().is_some() == tls13));
+        let (tls12, tls13) = (has_version(false), has_version(true));
+
+        let kx_groups: Vec<u16> = hello.supported_groups.iter().copied()
+            .filter(|id| base.kx_groups.iter().any(|group| u16::from(group.name()) == *id))
+            .collect();
+
+        if cipher_suites.is_empty() || kx_groups.is_empty() {
+            return None;
+        }
+        Some(Self { cipher_suites, kx_groups, tls12, tls13 })
+    }
+
+    /// 암호군과 키 교환 그룹을 클라이언트 순서로 바꾼 공급자
+    pub fn provider(&self, base: &CryptoProvider) -> CryptoProvider {
+        let mut provider = base.clone();
+        provider.cipher_suites = self.cipher_suites.iter()
+            .filter_map(|id| base.cipher_suites.iter().find(|suite| u16::from(suite.suite()) == *id).copied())
+            .collect();
+        provider.kx_groups = self.kx_groups.iter()
+            .filter_map(|id| base.kx_groups.iter().find(|group| u16::from(group.name()) == *id).copied())
+            .collect();
+        provider
+    }
+
+    /// 클라이언트가 제시한 TLS 버전 (높은 버전부터)
+    pub fn versions(&self) -> Vec<&'static SupportedProtocolVersion> {
+        let mut versions = Vec::with_capacity(2);
+        if self.tls13 {
+            versions.push(&TLS13);
+        }
+        if self.tls12 {
+            versions.push(&TLS12);
+        }
+        versions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use rustls::pki_types::ServerName;
+    use crate::tls::client_hello::parse_client_hello_fingerprint;
+    use crate::tls::create_unverified_client_config;
+
+    // 클라이언트 설정이 보내는 ClientHello 지문 (레코드를 메모리에 써서 해석)
+    fn client_hello_of(config: rustls::ClientConfig) -> ClientHelloFingerprint {
+        let server_name = ServerName::try_from("mirror.test").unwrap();
+        let mut connection = rustls::ClientConnection::new(Arc::new(config), server_name).unwrap();
+        let mut record = Vec::new();
+        connection.write_tls(&mut record).unwrap();
+        parse_client_hello_fingerprint(&record).unwrap()
+    }
+
+    // 재협상 신호용 SCSV(0x00ff)는 rustls가 덧붙이므로 비교에서 제외
+    fn suites(hello: &ClientHelloFingerprint) -> Vec<u16> {
+        hello.cipher_suites.iter().copied().filter(|id| *id != 0x00ff).collect()
+    }
+
+    #[test]
+    fn mirrored_config_follows_client_order_and_versions() {
+        let base = rustls::ClientConfig::builder().crypto_provider().clone();
+        // 암호군과 그룹을 기본과 반대 순서로 제시하는 클라이언트
+        let mut provider = (*base).clone();
+        provider.cipher_suites.reverse();
+        provider.kx_groups.reverse();
+        let client = rustls::ClientConfig::builder_with_provider(Arc::new(provider))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(rustls::RootCertStore::empty())
+            .with_no_client_auth();
+        let hello = client_hello_of(client);
+        assert_eq!(hello.ja3().split(',').count(), 5, "{}", hello.ja3());
+
+        let profile = MirrorProfile::new(&hello, &base).unwrap();
+        let mirrored = client_hello_of(create_unverified_client_config(Some(&profile)).unwrap());
+        assert_eq!(suites(&mirrored), suites(&hello));
+        assert_eq!(mirrored.supported_groups, hello.supported_groups);
+        assert_eq!(mirrored.supported_versions, hello.supported_versions);
+
+        // 기본 설정은 기본 공급자 순서 그대로
+        let default = client_hello_of(create_unverified_client_config(None).unwrap());
+        assert_ne!(suites(&default), suites(&hello));
+    }
+}
@@ -1,3 +1,11 @@
+pub mod cache_stats;
+pub mod cert_chain;
+pub mod client_hello;
+pub mod exemption;
+pub mod mirror;
+pub mod params;
+pub mod pkcs12;
+
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use std::fs;
@@ -8,7 +16,8 @@ use std::num::NonZeroUsize;
 
 use log::{debug, error, info, warn};
 use rcgen::{Certificate, CertificateParams, DistinguishedName, DnType, SanType, KeyPair};
-use rustls::{ServerConfig, ClientConfig};
+use rustls::{ServerConfig, ClientConfig, ConfigBuilder, WantsVerifier};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
 use tokio_rustls::{TlsAcceptor, TlsConnector, server::TlsStream as ServerTlsStream, client::TlsStream as ClientTlsStream};
 use rustls::pki_types::{CertificateDer, PrivateKeyDer};
@@ -16,152 +25,472 @@ use once_cell::sync::Lazy;
 use lru::LruCache;
 use nix::libc;
 use rustls_pemfile;
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, HandshakeKind, SignatureScheme};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, GetRandomFailed, WebPkiSupportedAlgorithms};
+use rustls::crypto::aws_lc_rs::Ticketer;
+use rustls::server::{ProducesTickets, ServerSessionMemoryCache, StoresServerSessions};
+use rustls::ticketer::TicketRotator;
 
 use crate::constants::*;
-use crate::config::Config;
+use crate::config::{CertCacheAlertConfig, Config, EgressPool, LeafCertTemplate, RootCaConfig, RootCaEntry, TlsResumptionConfig, UpstreamCertTier, WildcardCertConfig};
+use crate::metrics::Metrics;
+use crate::tls::cache_stats::MissRateWindow;
+use crate::tls::client_hello::ClientHelloFingerprint;
+use crate::tls::mirror::MirrorProfile;
+use crate::proxy::dialer::connect_upstream;
+use crate::proxy::timing::UpstreamTiming;
+use crate::proxy::target::parse_authority;
 use crate::error::{ProxyError, Result, tls_err, internal_err};
 
-// 루트 CA 인증서와 키를 저장하는 전역 변수
-static ROOT_CA: Lazy<Mutex<Option<Certificate>>> = Lazy::new(|| Mutex::new(None));
+// 리프 인증서 서명에 쓰는 루트 CA를 저장하는 전역 변수
+static ROOT_CA: Lazy<Mutex<Option<SigningCa>>> = Lazy::new(|| Mutex::new(None));
+
+/// 리프 인증서를 서명하는 루트 CA
+struct SigningCa {
+    cert: Certificate,
+    /// 리프와 함께 제시할 CA 인증서 (교차 서명 시 이전 CA가 서명한 인증서)
+    chain_der: CertificateDer<'static>,
+    /// 클라이언트가 신뢰 저장소에 설치할 자체 서명 CA 인증서 (GET /ca.crt)
+    root_der: CertificateDer<'static>,
+}
 
 // 도메인별 인증서 캐시 - LRU 캐시로 변경
 type CertKeyPair = (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>);
 static CERT_CACHE: Lazy<RwLock<LruCache<String, (CertKeyPair, Instant)>>> = 
     Lazy::new(|| RwLock::new(LruCache::new(NonZeroUsize::new(CERT_CACHE_SIZE).unwrap_or(NonZeroUsize::new(1000).unwrap()))));
 
+// 리프 인증서 캐시 최근 구간 미스율 (cert_cache_alert 설정 전에는 기본값)
+static CERT_CACHE_MISS_RATE: Lazy<Mutex<MissRateWindow>> =
+    Lazy::new(|| Mutex::new(MissRateWindow::new(&CertCacheAlertConfig::default())));
+
 // TLS 세션 캐시 추가
 static TLS_SESSION_CACHE: Lazy<RwLock<LruCache<String, Vec<u8>>>> =
     Lazy::new(|| RwLock::new(LruCache::new(NonZeroUsize::new(TLS_SESSION_CACHE_SIZE).unwrap_or(NonZeroUsize::new(5000).unwrap()))));
 
+// 리프 인증서 subject/SAN 템플릿
+static LEAF_CERT_TEMPLATE: Lazy<RwLock<LeafCertTemplate>> = Lazy::new(|| RwLock::new(LeafCertTemplate::default()));
+
+// 와일드카드 리프 인증서 상태 (비활성화 시 None)
+static WILDCARD_CERTS: Lazy<Mutex<Option<WildcardCerts>>> = Lazy::new(|| Mutex::new(None));
+
+/// 와일드카드 전환 기준과 상위 도메인별 하위 도메인 인증서 생성 수
+struct WildcardCerts {
+    min_subdomains: u32,
+    seen: LruCache<String, u32>,
+}
+
+// 클라이언트 측 TLS 세션 재개 상태 (비활성화 시 None)
+static TLS_RESUMPTION: Lazy<RwLock<Option<TlsResumption>>> = Lazy::new(|| RwLock::new(None));
+
+/// 연결마다 생성되는 서버 설정이 공유하는 세션 캐시와 티켓 발급기
+struct TlsResumption {
+    storage: Arc<dyn StoresServerSessions>,
+    ticketer: Option<Arc<dyn ProducesTickets>>,
+}
+
+/// 티켓 키 교체기가 생성하는 하위 티켓 발급기
+#[derive(Debug)]
+struct TicketKey(Arc<dyn ProducesTickets>);
+
+impl ProducesTickets for TicketKey {
+    fn enabled(&self) -> bool {
+        self.0.enabled()
+    }
+
+    fn lifetime(&self) -> u32 {
+        self.0.lifetime()
+    }
+
+    fn encrypt(&self, plain: &[u8]) -> Option<Vec<u8>> {
+        self.0.encrypt(plain)
+    }
+
+    fn decrypt(&self, cipher: &[u8]) -> Option<Vec<u8>> {
+        self.0.decrypt(cipher)
+    }
+}
+
+/// 새 티켓 키 생성
+fn generate_ticket_key() -> std::result::Result<Box<dyn ProducesTickets>, GetRandomFailed> {
+    Ticketer::new()
+        .map(|ticketer| Box::new(TicketKey(ticketer)) as Box<dyn ProducesTickets>)
+        .map_err(|_| GetRandomFailed)
+}
+
 // 클라이언트 TLS 설정 캐시 (재사용을 위함)
 type ConfigKey = (bool, Vec<String>);
 static CLIENT_TLS_CONFIGS: Lazy<RwLock<HashMap<ConfigKey, Arc<ClientConfig>>>> =
     Lazy::new(|| RwLock::new(HashMap::new()));
 
+// ClientHello 지문을 따라 한 클라이언트 TLS 설정 캐시 (검증 설정과 따라 한 속성별)
+type MirroredConfigKey = (ConfigKey, MirrorProfile);
+static MIRRORED_CLIENT_TLS_CONFIGS: Lazy<RwLock<HashMap<MirroredConfigKey, Arc<ClientConfig>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 지문을 따라 한 클라이언트 설정 캐시 최대 개수 (넘으면 비우고 다시 채움)
+const MAX_MIRRORED_CLIENT_TLS_CONFIGS: usize = 256;
+
+// warn 등급 호스트의 핸드셰이크 후 검증용 검증기 캐시 (신뢰할 인증서 목록별)
+static WARN_TIER_VERIFIERS: Lazy<RwLock<HashMap<Vec<String>, Arc<WebPkiServerVerifier>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+// 재로드로 교체된 신뢰할 인증서 목록 (None이면 시작 시 설정의 목록 사용)
+static TRUSTED_CERTIFICATES: Lazy<RwLock<Option<Vec<String>>>> = Lazy::new(|| RwLock::new(None));
+
+/// 재로드한 신뢰할 인증서 목록 적용 (캐시된 클라이언트 설정은 인증서 파일 내용이 바뀌었을 수 있으므로 비움)
+pub fn set_trusted_certificates(certificates: Vec<String>) {
+    *TRUSTED_CERTIFICATES.write().unwrap() = Some(certificates);
+    CLIENT_TLS_CONFIGS.write().unwrap().clear();
+    MIRRORED_CLIENT_TLS_CONFIGS.write().unwrap().clear();
+    WARN_TIER_VERIFIERS.write().unwrap().clear();
+}
+
+/// 현재 적용 중인 신뢰할 인증서 목록
+fn trusted_certificates(config: &Config) -> Vec<String> {
+    TRUSTED_CERTIFICATES.read().unwrap().clone()
+        .unwrap_or_else(|| config.trusted_certificates.clone())
+}
+
 /// 루트 CA 인증서를 초기화합니다
-pub fn init_root_ca() -> Result<()> {
-    let mut ca_guard = ROOT_CA.lock().unwrap();
-    
-    // 기존 CA 인증서가 있는지 확인
-    if Path::new(CA_CERT_FILE).exists() && Path::new(CA_KEY_FILE).exists() {
-        info!("Loading existing CA certificate");
-        let _cert_pem = fs::read_to_string(CA_CERT_FILE)?;
-        let key_pem = fs::read_to_string(CA_KEY_FILE)?;
-        
-        // PEM에서 인증서와 키 로드
-        let key_pair = KeyPair::from_pem(&key_pem)?;
-        let mut params = CertificateParams::new(vec![]);
-        params.key_pair = Some(key_pair);
-        let cert = Certificate::from_params(params)?;
-        
-        // .crt 파일이 없다면 생성
-        if !Path::new(CA_CERT_CRT_FILE).exists() {
-            info!("Creating .crt file from existing certificate");
-            let der_data = cert.serialize_der()?;
-            fs::write(CA_CERT_CRT_FILE, &der_data)?;
-        }
-        
-        *ca_guard = Some(cert);
+///
+/// `root_ca.cas`의 CA를 모두 로드하고(파일이 없으면 생성) `active` CA를 리프 서명에 사용합니다.
+/// `cross_sign_with`가 있으면 서명 CA를 그 CA로 교차 서명한 인증서를 리프와 함께 제시해
+/// 이전 CA만 신뢰하는 클라이언트도 새 CA가 서명한 리프를 검증할 수 있게 합니다.
+pub fn init_root_ca(settings: &RootCaConfig) -> Result<()> {
+    let entries = if settings.cas.is_empty() {
+        vec![RootCaEntry {
+            name: "default".to_string(),
+            cert_file: CA_CERT_FILE.to_string(),
+            key_file: CA_KEY_FILE.to_string(),
+        }]
     } else {
-        info!("Generating new CA certificate");
-        // 새 CA 인증서 생성
+        settings.cas.clone()
+    };
+    
+    let mut loaded = HashMap::new();
+    for entry in &entries {
+        loaded.insert(entry.name.clone(), load_or_generate_ca(entry)?);
+    }
+    
+    // 기본 CA는 클라이언트 배포용 DER(.crt) 파일도 유지
+    if settings.cas.is_empty() && !Path::new(CA_CERT_CRT_FILE).exists() {
+        info!("Creating .crt file from existing certificate");
+        fs::write(CA_CERT_CRT_FILE, loaded["default"].der.as_ref())?;
+    }
+    
+    let active_name = settings.active.clone().unwrap_or_else(|| entries[0].name.clone());
+    let active = loaded.remove(&active_name)
+        .ok_or_else(|| internal_err(format!("서명 CA를 찾을 수 없습니다: {}", active_name)))?;
+    
+    let chain_der = match &settings.cross_sign_with {
+        Some(previous_name) => {
+            let previous = loaded.get(previous_name)
+                .ok_or_else(|| internal_err(format!("교차 서명 CA를 찾을 수 없습니다: {}", previous_name)))?;
+            let mut params = CertificateParams::from_ca_cert_der(active.der.as_ref(), KeyPair::from_pem(&active.key_pem)?)?;
+            params.serial_number = None;
+            let cross_signed = Certificate::from_params(params)?.serialize_der_with_signer(&previous.cert)?;
+            info!("리프 서명 CA: {} ({}이(가) 교차 서명한 인증서를 체인에 포함)", active_name, previous_name);
+            CertificateDer::from(cross_signed)
+        },
+        None => {
+            info!("리프 서명 CA: {}", active_name);
+            active.der.clone()
+        }
+    };
+    
+    *ROOT_CA.lock().unwrap() = Some(SigningCa { cert: active.cert, chain_der, root_der: active.der });
+    // 이전 서명 CA로 만든 리프가 남지 않도록 캐시 초기화
+    CERT_CACHE.write().unwrap().clear();
+    Ok(())
+}
+
+/// 현재 리프 서명 CA의 자체 서명 인증서 DER (개인 키는 포함하지 않음, 초기화 전이면 None)
+pub fn root_ca_certificate() -> Option<CertificateDer<'static>> {
+    ROOT_CA.lock().unwrap().as_ref().map(|ca| ca.root_der.clone())
+}
+
+/// 파일에서 로드한 루트 CA (서명용 인증서, 원본 DER, 키 PEM)
+struct LoadedCa {
+    cert: Certificate,
+    der: CertificateDer<'static>,
+    key_pem: String,
+}
+
+/// CA 인증서/키 파일을 로드하고, 없으면 새 CA를 생성해 저장한 뒤 로드
+fn load_or_generate_ca(entry: &RootCaEntry) -> Result<LoadedCa> {
+    if entry.is_pkcs12() {
+        return load_ca_bundle(entry);
+    }
+    if !Path::new(&entry.cert_file).exists() || !Path::new(&entry.key_file).exists() {
+        info!("Generating new CA certificate: {}", entry.name);
         let mut params = CertificateParams::new(vec![]);
         params.not_before = time::OffsetDateTime::now_utc() - Duration::from_secs(60 * 60 * 24);
         params.not_after = time::OffsetDateTime::now_utc() + Duration::from_secs(60 * 60 * 24 * 365 * 10); // 10년
         
         let mut distinguished_name = DistinguishedName::new();
-        distinguished_name.push(DnType::CommonName, "UDSS Proxy Root CA");
+        if entry.cert_file == CA_CERT_FILE {
+            distinguished_name.push(DnType::CommonName, "UDSS Proxy Root CA");
+        } else {
+            distinguished_name.push(DnType::CommonName, format!("UDSS Proxy Root CA ({})", entry.name));
+        }
         distinguished_name.push(DnType::OrganizationName, "UDSS Proxy");
         params.distinguished_name = distinguished_name;
         
         // CA 인증서로 설정
         params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
         
-        // 인증서 생성
+        // 인증서와 키를 파일로 저장 (아래에서 저장한 파일을 다시 로드해 제시 인증서와 파일을 일치시킴)
         let cert = Certificate::from_params(params)?;
+        fs::write(&entry.cert_file, cert.serialize_pem()?)?;
+        fs::write(&entry.key_file, cert.serialize_private_key_pem())?;
         
-        // 인증서와 키를 파일로 저장
-        let pem_data = cert.serialize_pem()?;
-        fs::write(CA_CERT_FILE, &pem_data)?;
-        fs::write(CA_KEY_FILE, cert.serialize_private_key_pem())?;
-        
-        // DER 형식의 .crt 파일도 생성
-        let der_data = cert.serialize_der()?;
-        fs::write(CA_CERT_CRT_FILE, &der_data)?;
-        
-        info!("CA certificate generated and saved to {} and {}", CA_CERT_FILE, CA_CERT_CRT_FILE);
+        info!("CA certificate generated and saved to {}", entry.cert_file);
         info!("Please install the CA certificate in your browser's trusted root store");
-        
-        *ca_guard = Some(cert);
     }
     
+    info!("Loading existing CA certificate: {} ({})", entry.name, entry.cert_file);
+    let cert_pem = fs::read_to_string(&entry.cert_file)?;
+    let key_pem = fs::read_to_string(&entry.key_file)?;
+    
+    let der = rustls_pemfile::certs(&mut cert_pem.as_bytes())
+        .next()
+        .ok_or_else(|| internal_err(format!("CA 인증서가 없습니다: {}", entry.cert_file)))??;
+    
+    // 원본 인증서의 주체/키 식별자를 그대로 써야 리프의 발급자가 설치된 CA와 일치함
+    let params = CertificateParams::from_ca_cert_der(der.as_ref(), KeyPair::from_pem(&key_pem)?)?;
+    let cert = Certificate::from_params(params)?;
+    
+    Ok(LoadedCa { cert, der, key_pem })
+}
+
+/// PKCS#12 번들에서 CA 인증서(개인 키와 짝인 인증서)와 키를 로드 (번들은 생성하지 않음)
+fn load_ca_bundle(entry: &RootCaEntry) -> Result<LoadedCa> {
+    info!("Loading CA certificate from PKCS#12 bundle: {} ({})", entry.name, entry.cert_file);
+    let bundle = pkcs12::load_pkcs12(&entry.cert_file)
+        .and_then(|bundle| if bundle.key.is_some() { Ok(bundle) } else { Err(pkcs12::Pkcs12Error::MissingKey) })
+        .map_err(|e| tls_err(format!("CA 번들 로드 실패 ({}): {}", entry.cert_file, e)))?;
+    let (Some(key), Some(der)) = (bundle.key, bundle.certs.into_iter().next()) else {
+        return Err(tls_err(format!("CA 번들에 인증서나 키가 없습니다: {}", entry.cert_file)));
+    };
+    
+    let key_pair = KeyPair::from_der(key.secret_pkcs8_der())
+        .map_err(|e| tls_err(format!("CA 번들의 개인 키를 사용할 수 없습니다 ({}): {}", entry.cert_file, e)))?;
+    let key_pem = key_pair.serialize_pem();
+    let params = CertificateParams::from_ca_cert_der(der.as_ref(), key_pair)?;
+    let cert = Certificate::from_params(params)?;
+    
+    Ok(LoadedCa { cert, der, key_pem })
+}
+
+/// 리프 인증서 템플릿을 설정합니다 (기존에 생성된 인증서 캐시는 비움)
+pub fn set_leaf_cert_template(template: LeafCertTemplate) {
+    *LEAF_CERT_TEMPLATE.write().unwrap() = template;
+    CERT_CACHE.write().unwrap().clear();
+}
+
+/// 클라이언트 측 TLS 세션 재개(세션 ID 캐시, 세션 티켓)를 설정합니다
+pub fn set_tls_resumption(config: &TlsResumptionConfig) -> Result<()> {
+    if !config.enabled {
+        *TLS_RESUMPTION.write().unwrap() = None;
+        return Ok(());
+    }
+    
+    let ticketer: Option<Arc<dyn ProducesTickets>> = if config.tickets {
+        let lifetime = u32::try_from(config.ticket_rotation_seconds).map_err(tls_err)?;
+        Some(Arc::new(TicketRotator::new(lifetime, generate_ticket_key)?))
+    } else {
+        None
+    };
+    
+    info!("클라이언트 TLS 세션 재개 활성화: 세션 캐시 {}, 세션 티켓 {}",
+          config.session_cache_size,
+          if config.tickets { format!("사용 (키 교체 주기 {}초)", config.ticket_rotation_seconds) } else { "사용 안 함".to_string() });
+    
+    *TLS_RESUMPTION.write().unwrap() = Some(TlsResumption {
+        storage: ServerSessionMemoryCache::new(config.session_cache_size),
+        ticketer,
+    });
     Ok(())
 }
 
+/// 와일드카드 리프 인증서 사용 여부와 상위 도메인 집계를 설정합니다
+pub fn set_wildcard_certs(config: &WildcardCertConfig) {
+    if !config.enabled {
+        *WILDCARD_CERTS.lock().unwrap() = None;
+        return;
+    }
+    
+    info!("와일드카드 리프 인증서 활성화: 상위 도메인당 하위 도메인 {}개 초과 시 *.상위 도메인 인증서 공유",
+          config.min_subdomains);
+    *WILDCARD_CERTS.lock().unwrap() = Some(WildcardCerts {
+        min_subdomains: config.min_subdomains,
+        seen: LruCache::new(NonZeroUsize::new(WILDCARD_APEX_CACHE_SIZE).unwrap()),
+    });
+}
+
+/// 인증서 캐시 미스율 구간과 경고 임계값 설정
+pub fn set_cert_cache_alert(config: &CertCacheAlertConfig) {
+    if config.miss_rate_threshold_percent > 0 {
+        info!("인증서 캐시 미스율 경고: 최근 {}초 조회 {}건 이상에서 미스율 {}% 이상",
+              config.window_seconds, config.min_lookups, config.miss_rate_threshold_percent);
+    }
+    *CERT_CACHE_MISS_RATE.lock().unwrap() = MissRateWindow::new(config);
+}
+
+/// 인증서 캐시 조회 결과를 미스율 구간과 메트릭에 반영
+fn record_cert_lookup(hit: bool) {
+    let entries = CERT_CACHE.read().unwrap().len() as u64;
+    let rate = {
+        let mut window = CERT_CACHE_MISS_RATE.lock().unwrap();
+        let now = Instant::now();
+        window.record(hit, now);
+        let rate = window.snapshot(now);
+        window.check_threshold(rate);
+        rate
+    };
+    Metrics::new().cert_cache_lookup(hit, entries, rate.percent());
+}
+
+/// 와일드카드 인증서로 묶을 상위 도메인 (IP, 2단계 이하 도메인, 국가 코드 2단계 도메인은 제외)
+fn wildcard_parent(host: &str) -> Option<&str> {
+    if host.parse::<std::net::IpAddr>().is_ok() || host.starts_with("*.") {
+        return None;
+    }
+    
+    let (_, parent) = host.split_once('.')?;
+    let labels: Vec<&str> = parent.split('.').collect();
+    if labels.len() < 2 || labels.iter().any(|label| label.is_empty()) {
+        return None;
+    }
+    // *.co.kr 같은 인증서는 클라이언트가 거부하므로 국가 코드 2단계 도메인은 상위 도메인으로 쓰지 않음
+    if labels.len() == 2 && labels[1].len() == 2
+        && matches!(labels[0], "co" | "com" | "net" | "org" | "or" | "ne" | "go" | "ac" | "gov" | "edu") {
+        return None;
+    }
+    Some(parent)
+}
+
+/// 호스트의 상위 도메인이 와일드카드 전환 기준을 넘었으면 와일드카드 이름(`*.상위 도메인`) 반환
+///
+/// 호스트 인증서 캐시를 놓칠 때마다 호출되므로 상위 도메인별로 생성이 필요했던 하위 도메인 수를 집계합니다.
+fn wildcard_name(host: &str) -> Option<String> {
+    let mut wildcard = WILDCARD_CERTS.lock().unwrap();
+    let wildcard = wildcard.as_mut()?;
+    let parent = wildcard_parent(host)?;
+    
+    let seen = wildcard.seen.get_or_insert_mut(parent.to_string(), || 0);
+    *seen = seen.saturating_add(1);
+    (*seen > wildcard.min_subdomains).then(|| format!("*.{}", parent))
+}
+
+/// 캐시된 인증서 조회 (유효 기간의 80%가 지난 인증서는 제거)
+fn cached_cert(name: &str) -> Result<Option<CertKeyPair>> {
+    let mut cache = CERT_CACHE.write().unwrap();
+    if let Some((cert, created_time)) = cache.get(name) {
+        let age = created_time.elapsed();
+        
+        // 인증서가 만료되지 않았고 유효 기간의 80% 미만인 경우에만 재사용
+        if age < Duration::from_secs(60 * 60 * 24 * 365 * 4 / 5) {
+            debug!("Using cached certificate for host: {} (age: {}s)", name, age.as_secs());
+            
+            // 깊은 복사 수행 - 인증서 체인은 클론 가능
+            let cert_chain = cert.0.clone();
+            
+            // 개인 키는 직접 복제해야 함
+            let private_key = match &cert.1 {
+                PrivateKeyDer::Pkcs8(key) => {
+                    let key_data = key.secret_pkcs8_der().to_vec();
+                    PrivateKeyDer::Pkcs8(key_data.into())
+                },
+                PrivateKeyDer::Sec1(key) => {
+                    let key_data = key.secret_sec1_der().to_vec();
+                    PrivateKeyDer::Sec1(key_data.into())
+                },
+                PrivateKeyDer::Pkcs1(key) => {
+                    let key_data = key.secret_pkcs1_der().to_vec();
+                    PrivateKeyDer::Pkcs1(key_data.into())
+                },
+                _ => return Err(internal_err("Unsupported private key format")),
+            };
+            
+            return Ok(Some((cert_chain, private_key)));
+        } else {
+            debug!("Certificate for {} is too old ({}s), regenerating", name, age.as_secs());
+            // 오래된 인증서는 제거하고 새로 생성
+            cache.pop(name);
+        }
+    }
+    Ok(None)
+}
+
 /// 호스트명을 기반으로 가짜 인증서를 생성합니다
+///
+/// 와일드카드 인증서가 활성화되어 있으면 자주 보이는 상위 도메인의 하위 도메인들은
+/// `*.상위 도메인` 인증서 하나를 공유합니다 (처음 생성을 유발한 호스트도 SAN에 포함).
 pub async fn generate_fake_cert(host: &str) -> Result<CertKeyPair> {
     // 캐시에서 인증서 확인
-    {
-        let mut cache = CERT_CACHE.write().unwrap();
-        if let Some((cert, created_time)) = cache.get(host) {
-            let age = created_time.elapsed();
-            
-            // 인증서가 만료되지 않았고 유효 기간의 80% 미만인 경우에만 재사용
-            if age < Duration::from_secs(60 * 60 * 24 * 365 * 0.8 as u64) {
-                debug!("Using cached certificate for host: {} (age: {}s)", host, age.as_secs());
-                
-                // 깊은 복사 수행 - 인증서 체인은 클론 가능
-                let cert_chain = cert.0.clone();
-                
-                // 개인 키는 직접 복제해야 함
-                let private_key = match &cert.1 {
-                    PrivateKeyDer::Pkcs8(key) => {
-                        let key_data = key.secret_pkcs8_der().to_vec();
-                        PrivateKeyDer::Pkcs8(key_data.into())
-                    },
-                    PrivateKeyDer::Sec1(key) => {
-                        let key_data = key.secret_sec1_der().to_vec();
-                        PrivateKeyDer::Sec1(key_data.into())
-                    },
-                    PrivateKeyDer::Pkcs1(key) => {
-                        let key_data = key.secret_pkcs1_der().to_vec();
-                        PrivateKeyDer::Pkcs1(key_data.into())
-                    },
-                    _ => return Err(internal_err("Unsupported private key format")),
-                };
-                
-                return Ok((cert_chain, private_key));
-            } else {
-                debug!("Certificate for {} is too old ({}s), regenerating", host, age.as_secs());
-                // 오래된 인증서는 제거하고 새로 생성
-                cache.pop(host);
-            }
-        }
+    if let Some(cert_key_pair) = cached_cert(host)? {
+        record_cert_lookup(true);
+        return Ok(cert_key_pair);
+    }
+    
+    let wildcard = wildcard_name(host);
+    if let Some(name) = &wildcard
+        && let Some(cert_key_pair) = cached_cert(name)? {
+        record_cert_lookup(true);
+        return Ok(cert_key_pair);
     }
+    // 캐시 키와 CN (와일드카드면 *.상위 도메인)
+    let cert_name = wildcard.as_deref().unwrap_or(host);
     
-    debug!("Generating certificate for host: {}", host);
+    match &wildcard {
+        Some(name) => info!("와일드카드 인증서 생성: {} (요청 호스트 {})", name, host),
+        None => debug!("Generating certificate for host: {}", host),
+    }
     
     // 루트 CA 가져오기
     let ca_guard = ROOT_CA.lock().unwrap();
-    let ca_cert = ca_guard.as_ref().ok_or_else(|| {
+    let signing_ca = ca_guard.as_ref().ok_or_else(|| {
         let err = "Root CA not initialized";
         error!("{}", err);
         internal_err(err)
     })?;
     
     // 도메인 인증서 매개변수 설정
-    let mut params = CertificateParams::new(vec![host.to_string()]);
+    let mut params = CertificateParams::new(vec![cert_name.to_string()]);
     params.not_before = time::OffsetDateTime::now_utc() - Duration::from_secs(60 * 60 * 24);
     params.not_after = time::OffsetDateTime::now_utc() + Duration::from_secs(60 * 60 * 24 * 365); // 1년
     
+    let template = LEAF_CERT_TEMPLATE.read().unwrap().clone();
+    
     // 주체 이름 설정
     let mut distinguished_name = DistinguishedName::new();
-    distinguished_name.push(DnType::CommonName, host);
-    distinguished_name.push(DnType::OrganizationName, "UDSS Proxy Generated");
+    distinguished_name.push(DnType::CommonName, cert_name);
+    distinguished_name.push(DnType::OrganizationName, template.organization.as_str());
+    if let Some(unit) = &template.organizational_unit {
+        distinguished_name.push(DnType::OrganizationalUnitName, unit.as_str());
+    }
+    if let Some(country) = &template.country {
+        distinguished_name.push(DnType::CountryName, country.as_str());
+    }
+    if let Some(state) = &template.state {
+        distinguished_name.push(DnType::StateOrProvinceName, state.as_str());
+    }
+    if let Some(locality) = &template.locality {
+        distinguished_name.push(DnType::LocalityName, locality.as_str());
+    }
     params.distinguished_name = distinguished_name;
     
-    // SAN(Subject Alternative Name) 추가
-    let mut subject_alt_names = vec![SanType::DnsName(host.to_string())];
+    // SAN(Subject Alternative Name) 추가 - 방문 호스트(와일드카드면 와일드카드 이름)는 항상 첫 번째 SAN
+    let mut subject_alt_names = vec![SanType::DnsName(cert_name.to_string())];
+    
+    // 와일드카드 인증서에도 요청 호스트를 명시적으로 포함
+    if wildcard.is_some() {
+        subject_alt_names.push(SanType::DnsName(host.to_string()));
+    }
     
     // IP 주소인 경우 IP SAN 추가
     if let Ok(ip) = host.parse::<std::net::IpAddr>() {
@@ -170,6 +499,18 @@ pub async fn generate_fake_cert(host: &str) -> Result<CertKeyPair> {
         subject_alt_names.push(SanType::IpAddress(ip));
     }
     
+    // 템플릿의 추가 SAN
+    for extra in &template.extra_sans {
+        let extra = extra.replace("{host}", host);
+        let san = match extra.parse::<std::net::IpAddr>() {
+            Ok(ip) => SanType::IpAddress(ip),
+            Err(_) => SanType::DnsName(extra),
+        };
+        if !subject_alt_names.contains(&san) {
+            subject_alt_names.push(san);
+        }
+    }
+    
     params.subject_alt_names = subject_alt_names;
     
     // 인증서 생성
@@ -179,7 +520,7 @@ pub async fn generate_fake_cert(host: &str) -> Result<CertKeyPair> {
     })?;
     
     // CA로 서명
-    let cert_der = cert.serialize_der_with_signer(ca_cert).map_err(|e| {
+    let cert_der = cert.serialize_der_with_signer(&signing_ca.cert).map_err(|e| {
         error!("Failed to sign certificate with CA: {}", e);
         tls_err(e)
     })?;
@@ -187,14 +528,9 @@ pub async fn generate_fake_cert(host: &str) -> Result<CertKeyPair> {
     let key_der = cert.serialize_private_key_der();
     
     // 인증서 체인 구성 (도메인 인증서 + CA 인증서)
-    let ca_cert_der = ca_cert.serialize_der().map_err(|e| {
-        error!("Failed to serialize CA certificate: {}", e);
-        tls_err(e)
-    })?;
-    
     let cert_chain = vec![
         CertificateDer::from(cert_der),
-        CertificateDer::from(ca_cert_der),
+        signing_ca.chain_der.clone(),
     ];
     
     let private_key = PrivateKeyDer::Pkcs8(key_der.into());
@@ -219,18 +555,19 @@ pub async fn generate_fake_cert(host: &str) -> Result<CertKeyPair> {
     let cert_key_pair = (cert_chain.clone(), private_key_for_cache);
     {
         let mut cache = CERT_CACHE.write().unwrap();
-        cache.put(host.to_string(), (cert_key_pair, Instant::now()));
+        cache.put(cert_name.to_string(), (cert_key_pair, Instant::now()));
     }
+    record_cert_lookup(false);
     
     Ok((cert_chain, private_key))
 }
 
 /// 클라이언트와 TLS 연결을 수립합니다 - 세션 재사용 지원
-pub async fn accept_tls_with_cert(tcp_stream: TcpStream, cert_key_pair: CertKeyPair) -> Result<ServerTlsStream<TcpStream>> {
+pub async fn accept_tls_with_cert<S: AsyncRead + AsyncWrite + Unpin>(tcp_stream: S, cert_key_pair: CertKeyPair) -> Result<ServerTlsStream<S>> {
     let (certs, key) = cert_key_pair;
     
     // 서버 설정 구성 - 세션 재사용 지원
-    let server_config = ServerConfig::builder()
+    let mut server_config = ServerConfig::builder()
         .with_no_client_auth()
         .with_single_cert(certs, key)
         .map_err(|e| {
@@ -238,6 +575,19 @@ pub async fn accept_tls_with_cert(tcp_stream: TcpStream, cert_key_pair: CertKeyP
             e
         })?;
     
+    // 세션 재개: 모든 연결이 같은 세션 캐시와 티켓 발급기를 공유해야 재방문 클라이언트가 재개 가능
+    // (클라이언트 인증서를 요구하지 않으므로 재개된 세션에 별도 인증 정책을 적용할 필요 없음)
+    let resumption_enabled = match TLS_RESUMPTION.read().unwrap().as_ref() {
+        Some(resumption) => {
+            server_config.session_storage = Arc::clone(&resumption.storage);
+            if let Some(ticketer) = &resumption.ticketer {
+                server_config.ticketer = Arc::clone(ticketer);
+            }
+            true
+        },
+        None => false,
+    };
+    
     let server_config = Arc::new(server_config);
     let acceptor = TlsAcceptor::from(server_config);
     
@@ -247,29 +597,44 @@ pub async fn accept_tls_with_cert(tcp_stream: TcpStream, cert_key_pair: CertKeyP
         e
     })?;
     
+    if resumption_enabled {
+        let resumed = tls_stream.get_ref().1.handshake_kind() == Some(HandshakeKind::Resumed);
+        Metrics::new().tls_handshake_completed(resumed);
+    }
+    
     Ok(tls_stream)
 }
 
 /// 실제 서버와 TLS 연결을 수립합니다 - 세션 재사용 개선
 pub async fn connect_tls(host: &str, config: &Config) -> Result<ClientTlsStream<TcpStream>> {
-    // 포트 번호가 포함된 경우 분리
-    let (host_only, port) = if let Some(idx) = host.rfind(':') {
-        let (h, p) = host.split_at(idx);
-        (h, p[1..].parse::<u16>().unwrap_or(443))
-    } else {
-        (host, 443)
-    };
+    connect_tls_via(host, config, None, &mut UpstreamTiming::default(), None).await
+}
+
+/// 선택된 출구를 통해 실제 서버와 TLS 연결을 수립합니다 (None이면 기본 출구)
+///
+/// `upstream_timing_enabled`이면 이름 해석, 연결, TLS 핸드셰이크 시간을 `timing`에 기록합니다.
+/// `client_hello`가 있으면 그 암호군/키 교환 그룹 순서와 TLS 버전을 따라 핸드셰이크합니다 (client_hello_mirroring).
+pub async fn connect_tls_via(host: &str, config: &Config, egress: Option<&EgressPool>,
+                             timing: &mut UpstreamTiming, client_hello: Option<&ClientHelloFingerprint>) -> Result<ClientTlsStream<TcpStream>> {
+    // 포트 번호가 포함된 경우 분리 (IPv6 리터럴 포함)
+    let (host_only, port) = parse_authority(host, 443)
+        .ok_or_else(|| std::io::Error::other(format!("Invalid TLS target: {}", host)))?;
+    let host_only = host_only.as_str();
     
     // 추가 디버그 로그
     info!("TLS 연결 시도: {}:{}, 인증서 검증: {}", host_only, port, if config.tls_verify_certificate { "활성화" } else { "비활성화" });
     if !config.tls_verify_certificate {
         info!("인증서 검증이 비활성화되어 있으나 연결 실패할 경우 디버그를 위해 추가 설정을 확인하세요");
     } else {
-        info!("신뢰할 인증서 목록: {:?}", config.trusted_certificates);
+        info!("신뢰할 인증서 목록: {:?}", trusted_certificates(config));
     }
 
-    // 내부 IP 주소에 대한 인증서 검증 설정에 따라 처리
-    let should_verify = if config.disable_verify_internal_ip && is_internal_ip(host_only) {
+    // 호스트별 검증 등급이 있으면 우선 적용 (warn은 핸드셰이크 후 upstream_cert_warning에서 검증)
+    let should_verify = if let Some(tier) = config.upstream_cert_tier(host_only) {
+        debug!("업스트림 인증서 검증 등급: {} -> {:?}", host_only, tier);
+        tier == UpstreamCertTier::Strict
+    } else if config.disable_verify_internal_ip && is_internal_ip(host_only) {
+        // 내부 IP 주소에 대한 인증서 검증 설정에 따라 처리
         info!("내부 IP 주소 ({})에 대해 인증서 검증이 자동으로 비활성화되었습니다", host_only);
         false
     } else {
@@ -277,9 +642,20 @@ pub async fn connect_tls(host: &str, config: &Config) -> Result<ClientTlsStream<
     };
 
     // 캐시된 클라이언트 설정 사용
-    let client_config = {
+    let trusted = trusted_certificates(config);
+    let mirror = client_hello.and_then(|hello| {
+        let profile = MirrorProfile::new(hello, ClientConfig::builder().crypto_provider());
+        match &profile {
+            Some(profile) => debug!("ClientHello 지문 따라하기: {} (JA3 {}) -> {:?}", host_only, hello.ja3(), profile),
+            None => debug!("ClientHello 지문 따라하기 불가 (rustls와 겹치는 암호군/그룹 없음), 기본 핸드셰이크 사용: {}", host_only),
+        }
+        profile
+    });
+    let client_config = if let Some(profile) = mirror {
+        mirrored_client_config(should_verify, trusted, profile)?
+    } else {
         let configs = CLIENT_TLS_CONFIGS.read().unwrap();
-        if let Some(cached_config) = configs.get(&(should_verify, config.trusted_certificates.clone())) {
+        if let Some(cached_config) = configs.get(&(should_verify, trusted.clone())) {
             Arc::clone(cached_config)
         } else {
             drop(configs); // 읽기 락 해제
@@ -287,22 +663,28 @@ pub async fn connect_tls(host: &str, config: &Config) -> Result<ClientTlsStream<
             // 설정값에 따라 클라이언트 설정 생성
             let new_config = if should_verify {
                 info!("TLS certificate verification enabled for host: {}", host_only);
-                create_verified_client_config(config)?
+                create_verified_client_config(&trusted, None)?
             } else {
                 info!("TLS certificate verification disabled for host: {}", host_only);
-                create_unverified_client_config()?
+                create_unverified_client_config(None)?
             };
             
             // 캐시에 저장
             let mut configs = CLIENT_TLS_CONFIGS.write().unwrap();
             let config_arc = Arc::new(new_config);
-            configs.insert((should_verify, config.trusted_certificates.clone()), Arc::clone(&config_arc));
+            configs.insert((should_verify, trusted), Arc::clone(&config_arc));
             config_arc
         }
     };
     
-    // 호스트명으로 서버 이름 생성 - 문자열 복사하여 'static 라이프타임 문제 해결
-    let server_name = host_only.to_string().try_into()
+    // SNI는 원래 요청된 호스트명을 사용 (설정에 호스트별 대체 SNI가 있으면 그 값을 사용)
+    let sni = config.sni_for(host_only);
+    if sni != host_only {
+        info!("SNI 재지정: {} -> {}", host_only, sni);
+    }
+    
+    // 서버 이름 생성 - 문자열 복사하여 'static 라이프타임 문제 해결
+    let server_name = sni.split('%').next().unwrap_or(sni).to_string().try_into()
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Invalid DNS name: {}", e)))?;
     
     // TLS 커넥터 생성
@@ -315,13 +697,14 @@ pub async fn connect_tls(host: &str, config: &Config) -> Result<ClientTlsStream<
     };
     
     // 서버 연결 - 포트 번호 사용
-    let tcp_stream = TcpStream::connect(format!("{}:{}", host_only, port)).await
+    let tcp_stream = connect_upstream(host_only, port, config, egress, timing).await
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to connect to {}:{}: {}", host_only, port, e)))?;
     
     // TCP 소켓 최적화
     set_tcp_socket_options(&tcp_stream)?;
     
     // TLS 핸드셰이크
+    let started = UpstreamTiming::start(config.upstream_timing_enabled);
     let tls_stream = connector.connect(server_name, tcp_stream).await
         .map_err(|e| {
             if !config.tls_verify_certificate {
@@ -330,14 +713,53 @@ pub async fn connect_tls(host: &str, config: &Config) -> Result<ClientTlsStream<
                 error!("이 경우 서버 측에서 지원하는 TLS 버전이나 암호화 알고리즘이 호환되지 않을 수 있습니다.");
                 error!("서버 로그를 확인하고, 서버 TLS 설정을 점검하세요.");
             } else {
-                error!("TLS handshake failed with verification enabled: {}", e);
+                error!("TLS handshake failed with verification enabled (SNI: {}): {}", sni, e);
             }
-            std::io::Error::new(std::io::ErrorKind::Other, format!("TLS handshake with {}:{} failed: {}", host_only, port, e))
+            std::io::Error::other(format!("TLS handshake with {}:{} (SNI: {}) failed: {}", host_only, port, sni, e))
         })?;
+    timing.tls_handshake = started.map(|started| started.elapsed());
     
     Ok(tls_stream)
 }
 
+/// warn 등급 호스트의 업스트림 인증서 검증 (검증 없이 맺은 연결의 인증서를 핸드셰이크 후 확인)
+///
+/// 검증에 실패하면 경고 로그와 메트릭을 남기고 실패 사유를 반환합니다 (세션은 계속 진행).
+/// warn 등급이 아니거나 검증에 성공하면 None입니다.
+pub fn upstream_cert_warning(stream: &ClientTlsStream<TcpStream>, host: &str, config: &Config) -> Option<String> {
+    let (host_only, _) = parse_authority(host, 443)?;
+    if config.upstream_cert_tier(&host_only) != Some(UpstreamCertTier::Warn) {
+        return None;
+    }
+    
+    let trusted = trusted_certificates(config);
+    let cached = WARN_TIER_VERIFIERS.read().unwrap().get(&trusted).cloned();
+    let verifier = match cached {
+        Some(verifier) => verifier,
+        None => {
+            // 검증 설정을 만들면서 검증기도 캐시됨
+            if let Err(e) = create_verified_client_config(&trusted, None) {
+                warn!("[CERT-WARN] 인증서 검증기 생성 실패, 검증하지 않고 진행: {} ({})", host_only, e);
+                return Some(e.to_string());
+            }
+            WARN_TIER_VERIFIERS.read().unwrap().get(&trusted).cloned()?
+        }
+    };
+    
+    let certificates = stream.get_ref().1.peer_certificates().unwrap_or_default();
+    let Some((end_entity, intermediates)) = certificates.split_first() else {
+        return Some("no certificate presented".to_string());
+    };
+    let sni = config.sni_for(&host_only);
+    let server_name = ServerName::try_from(sni.split('%').next().unwrap_or(sni).to_string()).ok()?;
+    
+    let error = verifier.verify_server_cert(end_entity, intermediates, &server_name, &[], UnixTime::now()).err()?;
+    log_presented_certificate(&server_name, end_entity, &error);
+    warn!("[CERT-WARN] 업스트림 인증서 검증 실패, warn 등급으로 연결 허용: {} ({})", host_only, error);
+    Metrics::new().upstream_cert_warned();
+    Some(error.to_string())
+}
+
 /// TCP 소켓 최적화 설정을 적용합니다
 fn set_tcp_socket_options(stream: &TcpStream) -> std::result::Result<(), std::io::Error> {
     use std::os::unix::io::AsRawFd;
@@ -371,8 +793,42 @@ fn set_tcp_socket_options(stream: &TcpStream) -> std::result::Result<(), std::io
     Ok(())
 }
 
+/// ClientHello 지문을 따라 한 클라이언트 설정 (검증 설정과 따라 한 속성별로 캐시)
+fn mirrored_client_config(should_verify: bool, trusted: Vec<String>, profile: MirrorProfile) -> Result<Arc<ClientConfig>> {
+    let key = ((should_verify, trusted), profile);
+    if let Some(cached) = MIRRORED_CLIENT_TLS_CONFIGS.read().unwrap().get(&key) {
+        return Ok(Arc::clone(cached));
+    }
+    
+    let ((should_verify, trusted), profile) = &key;
+    let client_config = Arc::new(if *should_verify {
+        create_verified_client_config(trusted, Some(profile))?
+    } else {
+        create_unverified_client_config(Some(profile))?
+    });
+    
+    let mut configs = MIRRORED_CLIENT_TLS_CONFIGS.write().unwrap();
+    if configs.len() >= MAX_MIRRORED_CLIENT_TLS_CONFIGS {
+        configs.clear();
+    }
+    configs.insert(key, Arc::clone(&client_config));
+    Ok(client_config)
+}
+
+/// 클라이언트 설정 빌더 (`mirror`가 있으면 암호군/키 교환 그룹 순서와 TLS 버전을 바꾼 공급자 사용)
+fn client_config_builder(mirror: Option<&MirrorProfile>) -> Result<ConfigBuilder<ClientConfig, WantsVerifier>> {
+    let builder = ClientConfig::builder();
+    let Some(profile) = mirror else {
+        return Ok(builder);
+    };
+    let provider = profile.provider(builder.crypto_provider());
+    ClientConfig::builder_with_provider(Arc::new(provider))
+        .with_protocol_versions(&profile.versions())
+        .map_err(|e| tls_err(format!("ClientHello 지문 따라하기 설정 생성 실패: {}", e)))
+}
+
 // 인증서 검증이 활성화된 클라이언트 설정 생성
-fn create_verified_client_config(config: &Config) -> Result<ClientConfig> {
+fn create_verified_client_config(trusted: &[String], mirror: Option<&MirrorProfile>) -> Result<ClientConfig> {
     debug!("TLS certificate verification enabled - using system root certificates");
     
     // 시스템의 루트 인증서 로드
@@ -392,30 +848,18 @@ fn create_verified_client_config(config: &Config) -> Result<ClientConfig> {
     
     // 2. 사용자 지정 인증서 로드 (추가 신뢰 인증서)
     let mut custom_cert_count = 0;
-    for cert_path in &config.trusted_certificates {
+    for cert_path in trusted {
         debug!("Loading custom certificate from: {}", cert_path);
         
-        // PEM 또는 DER 형식 인증서 로드 시도
-        if let Ok(cert_data) = fs::read(cert_path) {
-            if cert_path.ends_with(".pem") || cert_path.ends_with(".crt") {
-                // PEM 형식 처리
-                let mut cert_data_slice = cert_data.as_slice();
-                let cert_iter = rustls_pemfile::certs(&mut cert_data_slice);
-                for cert_result in cert_iter {
-                    if let Ok(cert) = cert_result {
-                        if root_store.add(cert).is_ok() {
-                            custom_cert_count += 1;
-                        }
+        match read_certificate_file(cert_path) {
+            Ok(certs) => {
+                for cert in certs {
+                    if root_store.add(cert).is_ok() {
+                        custom_cert_count += 1;
                     }
                 }
-            } else {
-                // DER 형식으로 가정하고 처리 시도
-                if let Ok(_) = root_store.add(CertificateDer::from(cert_data)) {
-                    custom_cert_count += 1;
-                }
             }
-        } else {
-            warn!("Failed to read certificate file: {}", cert_path);
+            Err(e) => warn!("Failed to load trusted certificate {}: {}", cert_path, e),
         }
     }
     
@@ -423,90 +867,145 @@ fn create_verified_client_config(config: &Config) -> Result<ClientConfig> {
         info!("Loaded {} additional trusted certificates", custom_cert_count);
     }
 
-    let client_config = ClientConfig::builder()
-        .with_root_certificates(root_store)
+    // 검증 실패 시 서버가 제시한 인증서 정보를 남기도록 기본 검증기를 감쌈
+    let inner = WebPkiServerVerifier::builder(Arc::new(root_store))
+        .build()
+        .map_err(|e| std::io::Error::other(format!("Failed to build certificate verifier: {}", e)))?;
+    
+    if let Ok(mut verifiers) = WARN_TIER_VERIFIERS.write() {
+        verifiers.insert(trusted.to_vec(), Arc::clone(&inner));
+    }
+    
+    let client_config = client_config_builder(mirror)?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(DiagnosticVerifier { inner }))
         .with_no_client_auth();
     
     Ok(client_config)
 }
 
+/// 기본 검증기에 위임하고, 검증 실패 시 제시된 인증서의 subject/SAN을 로그로 남기는 검증기
+#[derive(Debug)]
+struct DiagnosticVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+}
+
+impl ServerCertVerifier for DiagnosticVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        self.inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+            .inspect_err(|e| log_presented_certificate(server_name, end_entity, e))
+    }
+    
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+    
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+    
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// 서버가 제시한 인증서의 subject, issuer, SAN 로깅
+fn log_presented_certificate(server_name: &ServerName<'_>, cert: &CertificateDer<'_>, err: &rustls::Error) {
+    let (_, parsed) = match x509_parser::parse_x509_certificate(cert.as_ref()) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            warn!("업스트림 인증서 검증 실패 (SNI: {:?}): {} - 제시된 인증서 파싱 실패: {}", server_name, err, e);
+            return;
+        }
+    };
+    
+    let sans = cert_chain::certificate_sans(&parsed);
+    
+    warn!("업스트림 인증서 검증 실패 (SNI: {:?}): {} - 제시된 인증서 subject: {}, issuer: {}, SAN: [{}]",
+          server_name, err, parsed.subject(), parsed.issuer(), sans.join(", "));
+}
+
+/// 인증서 체인과 이름은 검증하지 않는 검증기
+///
+/// 핸드셰이크 서명은 그대로 확인하므로 공개된 인증서 체인만 재전송하는 서버는 핸드셰이크에 실패합니다.
+#[derive(Debug)]
+struct NoCertificateVerification {
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        _ocsp: &[u8],
+        _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        // 체인과 이름은 확인하지 않음 (warn 등급은 핸드셰이크 뒤 upstream_cert_warning에서 확인)
+        info!("인증서 검증 비활성화: 서버 {:?} 인증서 검증 없이 통과 처리됨", server_name);
+        Ok(ServerCertVerified::assertion())
+    }
+    
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        // 서버가 인증서의 개인 키를 가졌는지는 기본 검증기와 같이 확인
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+    
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+    
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        // 암호 제공자가 검증할 수 있는 서명 방식
+        self.algorithms.supported_schemes()
+    }
+}
+
 // 인증서 검증이 비활성화된 클라이언트 설정 생성
-fn create_unverified_client_config() -> Result<ClientConfig> {
+pub(crate) fn create_unverified_client_config(mirror: Option<&MirrorProfile>) -> Result<ClientConfig> {
     // 인증서 검증 비활성화
     warn!("TLS certificate verification COMPLETELY DISABLED! All certificates will be trusted.");
     info!("인증서 검증 비활성화 모드로 TLS 설정 생성 중...");
     
-    // 모든 인증서를 항상 신뢰하는 검증기 구현
-    use rustls::client::danger::ServerCertVerifier;
-    use rustls::client::danger::{ServerCertVerified, HandshakeSignatureValid};
-    use rustls::pki_types::ServerName;
-    use rustls::pki_types::UnixTime;
-    use rustls::DigitallySignedStruct;
-    use rustls::SignatureScheme;
-    
-    // 모든 인증서를 항상 무조건 신뢰하는 검증기 구현
-    #[derive(Debug)]
-    struct NoCertificateVerification;
-    
-    impl ServerCertVerifier for NoCertificateVerification {
-        fn verify_server_cert(
-            &self,
-            _end_entity: &CertificateDer<'_>,
-            _intermediates: &[CertificateDer<'_>],
-            server_name: &ServerName<'_>,
-            _ocsp: &[u8],
-            _now: UnixTime,
-        ) -> std::result::Result<ServerCertVerified, rustls::Error> {
-            // 무조건 통과
-            info!("인증서 검증 비활성화: 서버 {:?} 인증서 검증 없이 통과 처리됨", server_name);
-            Ok(ServerCertVerified::assertion())
-        }
-        
-        fn verify_tls12_signature(
-            &self,
-            _message: &[u8],
-            _cert: &CertificateDer<'_>,
-            _dss: &DigitallySignedStruct,
-        ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
-            // 무조건 통과
-            Ok(HandshakeSignatureValid::assertion())
-        }
-        
-        fn verify_tls13_signature(
-            &self,
-            _message: &[u8],
-            _cert: &CertificateDer<'_>,
-            _dss: &DigitallySignedStruct,
-        ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
-            // 무조건 통과
-            Ok(HandshakeSignatureValid::assertion())
-        }
-        
-        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
-            // 모든 지원 가능한 서명 방식 반환
-            vec![
-                SignatureScheme::RSA_PKCS1_SHA256,
-                SignatureScheme::RSA_PKCS1_SHA384,
-                SignatureScheme::RSA_PKCS1_SHA512,
-                SignatureScheme::ECDSA_NISTP256_SHA256,
-                SignatureScheme::ECDSA_NISTP384_SHA384,
-                SignatureScheme::ECDSA_NISTP521_SHA512,
-                SignatureScheme::RSA_PSS_SHA256,
-                SignatureScheme::RSA_PSS_SHA384,
-                SignatureScheme::RSA_PSS_SHA512,
-                SignatureScheme::ED25519,
-            ]
-        }
-    }
-    
     // 모든 TLS 버전 지원 (TLS 1.2/1.3)
     info!("모든 TLS 버전 지원 활성화 (TLS 1.2/1.3)");
     
     // rustls 0.23 버전에 맞는 방식으로 TLS 버전 설정
     // ClientConfig 생성 - 간단한 방식으로 변경
-    let client_config = ClientConfig::builder()
+    let builder = client_config_builder(mirror)?;
+    let algorithms = builder.crypto_provider().signature_verification_algorithms;
+    let client_config = builder
         .dangerous()
-        .with_custom_certificate_verifier(Arc::new(NoCertificateVerification {}))
+        .with_custom_certificate_verifier(Arc::new(NoCertificateVerification { algorithms }))
         .with_no_client_auth();
     
     // 직접 versions 필드에 접근하지 않고, 대신 기본 설정 사용
@@ -517,7 +1016,9 @@ fn create_unverified_client_config() -> Result<ClientConfig> {
 }
 
 /// ssl/trusted_certs 폴더에서 인증서를 자동으로 로드합니다.
-pub fn load_trusted_certificates(config: &mut Config) -> Result<()> {
+///
+/// 인증서로 읽히지 않는 파일은 건너뛰며, 로드한 인증서 수를 반환합니다 (없어도 실패하지 않음).
+pub fn load_trusted_certificates(config: &mut Config) -> Result<usize> {
     let trusted_certs_dir = format!("{}/trusted_certs", config.ssl_dir);
     let trusted_certs_path = Path::new(&trusted_certs_dir);
     
@@ -531,42 +1032,84 @@ pub fn load_trusted_certificates(config: &mut Config) -> Result<()> {
         } else {
             info!("신뢰할 인증서 폴더 생성됨: {}", trusted_certs_dir);
         }
-        return Ok(());
+        return Ok(0);
     }
     
-    // 폴더 내의 모든 파일 검색
-    if let Ok(entries) = std::fs::read_dir(trusted_certs_path) {
-        let mut loaded_count = 0;
-        
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                
-                // 파일만 처리
-                if path.is_file() {
-                    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-                    
-                    // .pem, .crt, .cer 파일만 처리
-                    if extension == "pem" || extension == "crt" || extension == "cer" {
-                        let path_str = path.to_string_lossy().to_string();
-                        
-                        // 설정에 추가
-                        config.trusted_certificates.push(path_str.clone());
-                        loaded_count += 1;
-                        debug!("신뢰할 인증서 추가됨: {}", path_str);
-                    }
-                }
+    let entries = match std::fs::read_dir(trusted_certs_path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("신뢰할 인증서 폴더를 읽을 수 없습니다: {} ({})", trusted_certs_dir, e);
+            return Ok(0);
+        }
+    };
+    
+    // 인증서로 읽히는 파일만 추가하고 나머지(README, .DS_Store 등)는 건너뜀
+    let mut paths: Vec<String> = entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    paths.sort();
+    
+    let mut file_count = 0;
+    let mut cert_count = 0;
+    let mut skipped = 0;
+    for path in paths {
+        match read_certificate_file(&path) {
+            Ok(certs) => {
+                debug!("신뢰할 인증서 추가됨: {} ({} 개)", path, certs.len());
+                file_count += 1;
+                cert_count += certs.len();
+                config.trusted_certificates.push(path);
+            }
+            // PKCS#12 번들은 인증서를 두려고 넣은 파일이므로 암호 오류나 손상을 운영자가 알 수 있게 경고
+            Err(e) if pkcs12::is_pkcs12_path(&path) => {
+                warn!("PKCS#12 번들을 읽을 수 없어 건너뜀: {} ({})", path, e);
+                skipped += 1;
+            }
+            Err(e) => {
+                debug!("인증서가 아닌 파일 건너뜀: {} ({})", path, e);
+                skipped += 1;
             }
         }
-        
-        if loaded_count > 0 {
-            info!("ssl/trusted_certs에서 {} 개의 인증서가 자동으로 로드되었습니다", loaded_count);
-        } else {
-            debug!("ssl/trusted_certs 폴더에서 로드할 인증서가 없습니다");
+    }
+    
+    if cert_count > 0 {
+        info!("{}에서 {} 개 파일의 인증서 {} 개가 자동으로 로드되었습니다 (건너뛴 파일 {} 개)",
+              trusted_certs_dir, file_count, cert_count, skipped);
+    } else {
+        warn!("{}에 사용할 수 있는 인증서가 없습니다 (건너뛴 파일 {} 개)", trusted_certs_dir, skipped);
+    }
+    
+    Ok(cert_count)
+}
+
+/// 인증서 파일 읽기 (PEM, DER, PKCS#12 번들)
+///
+/// 인증서를 하나도 꺼낼 수 없으면 이유와 함께 실패합니다.
+pub fn read_certificate_file(path: &str) -> std::result::Result<Vec<CertificateDer<'static>>, String> {
+    // PKCS#12 번들은 번들 안의 인증서를 모두 사용 (키는 쓰지 않음)
+    if pkcs12::is_pkcs12_path(path) {
+        return pkcs12::load_pkcs12(path).map(|bundle| bundle.certs).map_err(|e| e.to_string());
+    }
+    
+    let data = fs::read(path).map_err(|e| format!("파일을 읽을 수 없습니다: {}", e))?;
+    if data.windows(PEM_BEGIN_MARKER.len()).any(|window| window == PEM_BEGIN_MARKER) {
+        let certs: Vec<CertificateDer<'static>> = rustls_pemfile::certs(&mut data.as_slice())
+            .filter_map(|cert| cert.ok())
+            .filter(|cert| x509_parser::parse_x509_certificate(cert.as_ref()).is_ok())
+            .collect();
+        if certs.is_empty() {
+            return Err("PEM 인증서 블록이 없습니다".to_string());
         }
+        return Ok(certs);
     }
     
-    Ok(())
+    // DER 인증서 하나 (남는 바이트 없이 X.509로 해석되어야 함)
+    match x509_parser::parse_x509_certificate(&data) {
+        Ok(([], _)) => Ok(vec![CertificateDer::from(data)]),
+        _ => Err("PEM/DER 인증서가 아닙니다".to_string()),
+    }
 }
 
 /// 주어진 호스트가 내부 IP 주소인지 확인합니다
@@ -637,3 +1180,132 @@ fn is_internal_ip(host: &str) -> bool {
     debug!("호스트 {}는 내부 IP 또는 내부 도메인으로 인식되지 않습니다", host);
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x509_parser::extensions::GeneralName;
+    use x509_parser::x509::AttributeTypeAndValue;
+
+    // 파일을 만들지 않고 메모리에서 만든 CA를 서명 CA로 설정
+    fn install_test_ca() {
+        let mut params = CertificateParams::new(vec![]);
+        params.distinguished_name.push(DnType::CommonName, "UDSS Proxy Test CA");
+        params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        let cert = Certificate::from_params(params).unwrap();
+        let der = CertificateDer::from(cert.serialize_der().unwrap());
+        *ROOT_CA.lock().unwrap() = Some(SigningCa { cert, chain_der: der.clone(), root_der: der });
+    }
+
+    // 주체 이름에서 해당 속성의 첫 값
+    fn first<'a>(mut attrs: impl Iterator<Item = &'a AttributeTypeAndValue<'a>>) -> Option<&'a str> {
+        attrs.next().and_then(|attr| attr.as_str().ok())
+    }
+
+    #[tokio::test]
+    async fn leaf_carries_template_subject_and_sans() {
+        crate::db::config::DbConfig::disable();
+        install_test_ca();
+        set_leaf_cert_template(LeafCertTemplate {
+            organization: "Example Corp".to_string(),
+            organizational_unit: Some("Security".to_string()),
+            country: Some("KR".to_string()),
+            state: Some("Seoul".to_string()),
+            locality: Some("Jung-gu".to_string()),
+            extra_sans: vec!["{host}.alt.test".to_string(), "192.0.2.7".to_string(), "leaf.example.test".to_string()],
+        });
+
+        let (chain, _) = generate_fake_cert("leaf.example.test").await.unwrap();
+        let (_, leaf) = x509_parser::parse_x509_certificate(chain[0].as_ref()).unwrap();
+        let subject = leaf.subject();
+        assert_eq!(first(subject.iter_common_name()), Some("leaf.example.test"));
+        assert_eq!(first(subject.iter_organization()), Some("Example Corp"));
+        assert_eq!(first(subject.iter_organizational_unit()), Some("Security"));
+        assert_eq!(first(subject.iter_country()), Some("KR"));
+        assert_eq!(first(subject.iter_state_or_province()), Some("Seoul"));
+        assert_eq!(first(subject.iter_locality()), Some("Jung-gu"));
+        assert_eq!(leaf.issuer().to_string(), "CN=UDSS Proxy Test CA");
+
+        // 방문 호스트가 첫 SAN, 템플릿 SAN은 {host} 치환 후 순서대로 (중복은 한 번만)
+        let sans = leaf.subject_alternative_name().unwrap().unwrap().value.general_names.clone();
+        assert_eq!(sans, vec![
+            GeneralName::DNSName("leaf.example.test"),
+            GeneralName::DNSName("leaf.example.test.alt.test"),
+            GeneralName::IPAddress(&[192, 0, 2, 7]),
+        ]);
+    }
+
+    // 인증서와 서명 키를 따로 정해 핸드셰이크 (키가 인증서와 다르면 공개 체인만 재전송한 서버)
+    async fn handshake_with(cert: &Certificate, signing_cert: &Certificate) -> std::io::Result<()> {
+        #[derive(Debug)]
+        struct Fixed(Arc<rustls::sign::CertifiedKey>);
+        impl rustls::server::ResolvesServerCert for Fixed {
+            fn resolve(&self, _hello: rustls::server::ClientHello<'_>) -> Option<Arc<rustls::sign::CertifiedKey>> {
+                Some(Arc::clone(&self.0))
+            }
+        }
+
+        let key = PrivateKeyDer::Pkcs8(signing_cert.serialize_private_key_der().into());
+        let signing_key = rustls::crypto::aws_lc_rs::sign::any_supported_type(&key).unwrap();
+        let certified = rustls::sign::CertifiedKey::new(vec![CertificateDer::from(cert.serialize_der().unwrap())], signing_key);
+        let server_config = ServerConfig::builder().with_no_client_auth().with_cert_resolver(Arc::new(Fixed(Arc::new(certified))));
+
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move { TlsAcceptor::from(Arc::new(server_config)).accept(server_io).await.map(|_| ()) });
+        let connector = TlsConnector::from(Arc::new(create_unverified_client_config(None).unwrap()));
+        let client = connector.connect(ServerName::try_from("replayed.test").unwrap(), client_io).await.map(|_| ());
+        let _ = server.await;
+        client
+    }
+
+    #[tokio::test]
+    async fn unverified_chain_still_checks_handshake_signature() {
+        let cert = rcgen::generate_simple_self_signed(vec!["replayed.test".to_string()]).unwrap();
+        let other = rcgen::generate_simple_self_signed(vec!["replayed.test".to_string()]).unwrap();
+
+        assert!(handshake_with(&cert, &cert).await.is_ok());
+        assert!(handshake_with(&cert, &other).await.is_err());
+    }
+
+    #[test]
+    fn trusted_certs_dir_skips_non_certificate_files() {
+        let root = std::env::temp_dir().join(format!("udss-unit-trust-{}", std::process::id()));
+        let dir = root.join("trusted_certs");
+        std::fs::create_dir_all(dir.join("subdir")).unwrap();
+        let self_signed = |name: &str| rcgen::generate_simple_self_signed(vec![name.to_string()]).unwrap();
+        std::fs::write(dir.join("single.pem"), self_signed("single.test").serialize_pem().unwrap()).unwrap();
+        std::fs::write(dir.join("chain.crt"), self_signed("chain-a.test").serialize_pem().unwrap() + &self_signed("chain-b.test").serialize_pem().unwrap()).unwrap();
+        std::fs::write(dir.join("raw.der"), self_signed("der.test").serialize_der().unwrap()).unwrap();
+        std::fs::write(dir.join("trust.p12"), include_bytes!("testdata/trust.p12")).unwrap();
+        pkcs12::set_pkcs12_passphrase("udss-test".to_string());
+
+        let junk: [(&str, &[u8]); 5] = [
+            ("README", b"drop trusted CA certificates here\n"),
+            (".DS_Store", b"\x00\x00\x00\x01Bud1\x00\x00\x10\x00"),
+            ("empty.pem", b""),
+            ("broken.pem", b"-----BEGIN CERTIFICATE-----\nnot base64\n-----END CERTIFICATE-----\n"),
+            ("garbage.cer", b"\x30\x82\x01\x00 truncated"),
+        ];
+        for (name, data) in junk {
+            std::fs::write(dir.join(name), data).unwrap();
+        }
+
+        let mut config = Config::new();
+        config.ssl_dir = root.to_string_lossy().to_string();
+        let loaded = load_trusted_certificates(&mut config).unwrap();
+        let mut files: Vec<String> = config.trusted_certificates.iter()
+            .filter_map(|path| Path::new(path).file_name().map(|name| name.to_string_lossy().to_string()))
+            .collect();
+        files.sort();
+        assert_eq!(loaded, 5);
+        assert_eq!(files, ["chain.crt", "raw.der", "single.pem", "trust.p12"]);
+
+        // 인증서가 하나도 없는 폴더는 경고만 남기고 빈 목록
+        for name in ["single.pem", "chain.crt", "raw.der", "trust.p12"] {
+            std::fs::remove_file(dir.join(name)).unwrap();
+        }
+        assert_eq!(load_trusted_certificates(&mut config).unwrap(), 0);
+        assert!(config.trusted_certificates.is_empty());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+use std::net::IpAddr;
+
+use rustls::CommonState;
+
+/// TLS 연결 한쪽에서 협상된 값 (핸드셰이크 전이거나 협상하지 않은 값은 None)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlsSideParams {
+    /// 프로토콜 버전 (예: TLSv1_3)
+    pub version: Option<String>,
+    /// 암호 스위트 (예: TLS13_AES_256_GCM_SHA384)
+    pub cipher_suite: Option<String>,
+    /// ALPN 프로토콜 (예: h2, http/1.1)
+    pub alpn: Option<String>,
+}
+
+impl TlsSideParams {
+    /// 핸드셰이크가 끝난 연결에서 협상 값 읽기
+    pub fn from_connection(connection: &CommonState) -> Self {
+        Self {
+            version: connection.protocol_version().map(|version| format!("{:?}", version)),
+            cipher_suite: connection.negotiated_cipher_suite().map(|suite| format!("{:?}", suite.suite())),
+            alpn: connection.alpn_protocol().map(|alpn| String::from_utf8_lossy(alpn).into_owned()),
+        }
+    }
+}
+
+/// 가로챈 세션의 클라이언트/업스트림 TLS 협상 값 (tls_params_logging 설정 시 기록)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlsSessionParams {
+    pub client: TlsSideParams,
+    pub upstream: TlsSideParams,
+    /// 업스트림 연결에 보낸 SNI (IP 주소로 연결해 SNI를 보내지 않았으면 None)
+    pub upstream_sni: Option<String>,
+}
+
+impl TlsSessionParams {
+    /// 업스트림에 보낸 SNI (sni_overrides 반영, IP 주소면 None)
+    pub fn sent_sni(sni: &str) -> Option<String> {
+        let sni = sni.split('%').next().unwrap_or(sni);
+        sni.trim_matches(['[', ']']).parse::<IpAddr>().is_err().then(|| sni.to_string())
+    }
+
+    /// 값 이름과 값 목록 (접근 로그 필드 순서)
+    pub fn fields(&self) -> [(&'static str, Option<&str>); 7] {
+        [
+            ("client_tls", self.client.version.as_deref()),
+            ("client_cipher", self.client.cipher_suite.as_deref()),
+            ("client_alpn", self.client.alpn.as_deref()),
+            ("upstream_tls", self.upstream.version.as_deref()),
+            ("upstream_cipher", self.upstream.cipher_suite.as_deref()),
+            ("upstream_alpn", self.upstream.alpn.as_deref()),
+            ("upstream_sni", self.upstream_sni.as_deref()),
+        ]
+    }
+}
+
+impl fmt::Display for TlsSessionParams {
+    /// 접근 로그 형식 (값이 있는 항목만): `client_tls=TLSv1_3 client_cipher=... upstream_sni=example.com`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (name, value) in self.fields() {
+            let Some(value) = value else { continue };
+            if !first {
+                write!(f, " ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
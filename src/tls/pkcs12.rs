@@ -0,0 +1,178 @@
+// PKCS#12(.p12/.pfx) 번들 로드
+// 루트 CA(`root_ca.cas`의 cert_file)와 신뢰할 인증서를 PEM으로 바꾸지 않고 번들에서 바로 읽습니다.
+// 암호는 시작 시 `pkcs12` 설정(또는 환경 변수)에서 한 번 정해 모든 번들에 같이 씁니다.
+
+use std::fmt;
+use std::fs;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use p12_keystore::Pkcs12Archive;
+use p12_keystore::error::Error as P12Error;
+use rcgen::KeyPair;
+use rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
+
+// 번들 암호 (설정 전에는 빈 암호)
+static PKCS12_PASSPHRASE: Lazy<RwLock<String>> = Lazy::new(|| RwLock::new(String::new()));
+
+/// PKCS#12 번들 경로인지 (확장자 .p12/.pfx, 대소문자 무시)
+pub fn is_pkcs12_path(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    lower.ends_with(".p12") || lower.ends_with(".pfx")
+}
+
+/// 번들 암호 설정 (`pkcs12` 설정에서 정한 값)
+pub fn set_pkcs12_passphrase(passphrase: String) {
+    *PKCS12_PASSPHRASE.write().unwrap() = passphrase;
+}
+
+/// 번들에서 꺼낸 인증서와 개인 키
+pub struct Pkcs12Bundle {
+    /// 인증서 목록 (개인 키와 짝인 인증서가 있으면 맨 앞)
+    pub certs: Vec<CertificateDer<'static>>,
+    /// PKCS#8 개인 키 (인증서만 담은 번들이면 None)
+    pub key: Option<PrivatePkcs8KeyDer<'static>>,
+}
+
+/// 번들 로드 실패 원인
+#[derive(Debug)]
+pub enum Pkcs12Error {
+    /// 파일을 읽을 수 없음
+    Io(std::io::Error),
+    /// 암호가 틀림 (무결성 MAC 또는 복호화 실패)
+    WrongPassword,
+    /// 파일이 손상되었거나 PKCS#12 형식이 아님
+    Corrupt(String),
+    /// 지원하지 않는 암호화/MAC 방식
+    Unsupported(String),
+    /// 개인 키가 필요한데 번들에 없음
+    MissingKey,
+}
+
+impl fmt::Display for Pkcs12Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pkcs12Error::Io(e) => write!(f, "파일을 읽을 수 없습니다: {}", e),
+            Pkcs12Error::WrongPassword => write!(f, "암호가 올바르지 않습니다 (pkcs12.passphrase 또는 pkcs12.passphrase_env 확인)"),
+            Pkcs12Error::Corrupt(detail) => write!(f, "파일이 손상되었거나 PKCS#12 형식이 아닙니다: {}", detail),
+            Pkcs12Error::Unsupported(detail) => write!(f, "지원하지 않는 PKCS#12 암호화 방식입니다: {}", detail),
+            Pkcs12Error::MissingKey => write!(f, "번들에 개인 키가 없습니다"),
+        }
+    }
+}
+
+impl std::error::Error for Pkcs12Error {}
+
+impl From<P12Error> for Pkcs12Error {
+    fn from(e: P12Error) -> Self {
+        match e {
+            // MAC은 암호로 만든 키로 검증하므로 불일치는 암호 오류, MAC 없는 번들은 복호화 패딩 오류로 드러남
+            P12Error::MacError(_) | P12Error::UnpadError | P12Error::Pkcs5Error(_) => Pkcs12Error::WrongPassword,
+            P12Error::UnsupportedEncryptionScheme | P12Error::UnsupportedMacAlgorithm
+            | P12Error::UnsupportedContentType | P12Error::UnsupportedCertificateType => Pkcs12Error::Unsupported(e.to_string()),
+            P12Error::IoError(e) => Pkcs12Error::Io(e),
+            e => Pkcs12Error::Corrupt(e.to_string()),
+        }
+    }
+}
+
+/// 설정된 암호로 번들 파일 로드
+pub fn load_pkcs12(path: &str) -> Result<Pkcs12Bundle, Pkcs12Error> {
+    let data = fs::read(path).map_err(Pkcs12Error::Io)?;
+    parse_pkcs12(&data, &PKCS12_PASSPHRASE.read().unwrap())
+}
+
+/// 번들 내용을 복호화해 인증서와 개인 키(첫 번째)를 꺼냄
+///
+/// 키와 짝인 인증서는 localKeyId로 찾고, 키에 localKeyId가 없으면 공개 키가 같은 인증서로 찾습니다.
+pub fn parse_pkcs12(data: &[u8], passphrase: &str) -> Result<Pkcs12Bundle, Pkcs12Error> {
+    let archive = Pkcs12Archive::from_pkcs12(data, passphrase)?;
+    let key_bag = archive.keys.into_iter().next();
+    let key_id = key_bag.as_ref().and_then(|key| key.local_key_id.as_ref());
+    // localKeyId가 없을 때만 키의 공개 키를 구함 (rcgen이 읽지 못하는 키 형식이면 짝을 찾지 않음)
+    let key_public = match (&key_bag, key_id) {
+        (Some(key), None) => KeyPair::from_der(key.key.as_der()).ok().map(|pair| pair.public_key_raw().to_vec()),
+        _ => None,
+    };
+
+    let mut certs: Vec<CertificateDer<'static>> = Vec::with_capacity(archive.certs.len());
+    let mut paired_found = false;
+    for bag in archive.certs {
+        let der = CertificateDer::from(bag.cert.as_der().to_vec());
+        let paired = match (key_id, &key_public) {
+            (Some(id), _) => bag.local_key_id.as_deref() == Some(id.as_ref()),
+            (None, Some(public)) => x509_parser::parse_x509_certificate(der.as_ref())
+                .is_ok_and(|(_, cert)| cert.public_key().subject_public_key.data.as_ref() == public.as_slice()),
+            (None, None) => false,
+        };
+        if paired && !paired_found {
+            paired_found = true;
+            certs.insert(0, der);
+        } else {
+            certs.push(der);
+        }
+    }
+    if certs.is_empty() {
+        return Err(Pkcs12Error::Corrupt("인증서가 없습니다".to_string()));
+    }
+
+    Ok(Pkcs12Bundle {
+        certs,
+        key: key_bag.map(|bag| PrivatePkcs8KeyDer::from(bag.key.as_der().to_vec())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::CertificateParams;
+
+    // OpenSSL로 만든 테스트 CA 번들 (암호 PASSPHRASE)
+    // ca.p12: 키+인증서, AES-256-CBC/HMAC-SHA256 / ca-legacy.pfx: 같은 내용, -legacy(RC2/3DES, SHA1 MAC) / trust.p12: 인증서만(-nokeys)
+    const PASSPHRASE: &str = "udss-test";
+    const CA: &[u8] = include_bytes!("testdata/ca.p12");
+    const CA_LEGACY: &[u8] = include_bytes!("testdata/ca-legacy.pfx");
+    const TRUST: &[u8] = include_bytes!("testdata/trust.p12");
+    // localKeyId 없이 다른 인증서 뒤에 CA 인증서를 담은 번들
+    const CA_NO_KEY_ID: &[u8] = include_bytes!("testdata/ca-no-key-id.p12");
+
+    fn assert_usable_ca(name: &str, bundle: Pkcs12Bundle) {
+        let (Some(key), Some(cert)) = (bundle.key, bundle.certs.first()) else {
+            panic!("{}: certificate or key missing", name);
+        };
+        let key_pair = KeyPair::from_der(key.secret_pkcs8_der()).expect(name);
+        CertificateParams::from_ca_cert_der(cert.as_ref(), key_pair).expect(name);
+    }
+
+    #[test]
+    fn key_bundles_yield_ca_certificate_and_key() {
+        for (name, data) in [("ca.p12", CA), ("ca-legacy.pfx", CA_LEGACY)] {
+            assert_usable_ca(name, parse_pkcs12(data, PASSPHRASE).expect(name));
+            assert!(matches!(parse_pkcs12(data, "wrong-passphrase"), Err(Pkcs12Error::WrongPassword)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn key_without_local_key_id_is_paired_by_public_key() {
+        let bundle = parse_pkcs12(CA_NO_KEY_ID, PASSPHRASE).unwrap();
+        assert_eq!(bundle.certs.len(), 2);
+        let (_, first) = x509_parser::parse_x509_certificate(bundle.certs[0].as_ref()).unwrap();
+        assert!(first.subject().to_string().contains("UDSS Proxy Test CA"));
+        assert_usable_ca("ca-no-key-id.p12", bundle);
+    }
+
+    #[test]
+    fn certificate_only_bundle_has_no_key() {
+        let trust = parse_pkcs12(TRUST, PASSPHRASE).unwrap();
+        assert!(trust.key.is_none());
+        assert_eq!(trust.certs.len(), 1);
+    }
+
+    #[test]
+    fn corrupt_bundles_are_reported_as_corrupt() {
+        let truncated = &CA[..CA.len() / 2];
+        for (name, data) in [("truncated", truncated), ("garbage", &b"not a pkcs12 bundle"[..])] {
+            assert!(matches!(parse_pkcs12(data, PASSPHRASE), Err(Pkcs12Error::Corrupt(_))), "{}", name);
+        }
+    }
+}